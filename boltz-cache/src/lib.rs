@@ -52,6 +52,21 @@ impl Cache {
         }
     }
 
+    /// Atomically adds to a counter and returns its new value; the TTL is only
+    /// set when the counter is created
+    pub async fn increment(
+        &self,
+        key: &str,
+        field: &str,
+        by: u64,
+        ttl: Option<u64>,
+    ) -> Result<u64> {
+        match self {
+            Cache::Redis(redis) => redis.increment(key, field, by, ttl).await,
+            Cache::Memory(memory) => memory.increment(key, field, by, ttl),
+        }
+    }
+
     pub async fn take<V: DeserializeOwned>(&self, key: &str, field: &str) -> Result<Option<V>> {
         match self {
             Cache::Redis(redis) => redis.take(key, field).await,
@@ -115,22 +115,40 @@ impl MemCache {
     ) -> anyhow::Result<()> {
         let key = Self::get_key(key, field);
 
-        let expires_at = match ttl {
-            Some(ttl) => {
-                let expiry_time = SystemTime::now()
-                    .checked_add(Duration::from_secs(ttl))
-                    .ok_or_else(|| anyhow::anyhow!("could not calculate expiration time"))?;
-                let seconds = expiry_time.duration_since(UNIX_EPOCH)?.as_secs();
-                Some(seconds)
-            }
-            None => None,
+        let cache_value = CacheValue {
+            value,
+            expires_at: Self::expires_at(ttl)?,
         };
-
-        let cache_value = CacheValue { value, expires_at };
         self.map.insert(key, serde_json::to_string(&cache_value)?);
         Ok(())
     }
 
+    /// Atomically adds to a counter and returns its new value; the TTL is only
+    /// set when the counter is created
+    pub fn increment(
+        &self,
+        key: &str,
+        field: &str,
+        by: u64,
+        ttl: Option<u64>,
+    ) -> anyhow::Result<u64> {
+        let mut entry = self.map.entry(Self::get_key(key, field)).or_default();
+
+        let cache_value = match serde_json::from_str::<CacheValue<u64>>(entry.value()) {
+            Ok(existing) if !Self::is_expired(existing.expires_at) => CacheValue {
+                value: existing.value + by,
+                expires_at: existing.expires_at,
+            },
+            _ => CacheValue {
+                value: by,
+                expires_at: Self::expires_at(ttl)?,
+            },
+        };
+        *entry.value_mut() = serde_json::to_string(&cache_value)?;
+
+        Ok(cache_value.value)
+    }
+
     pub fn take<V: DeserializeOwned>(&self, key: &str, field: &str) -> anyhow::Result<Option<V>> {
         let entry = self.map.remove(&Self::get_key(key, field));
         Ok(match entry {
@@ -154,6 +172,18 @@ impl MemCache {
     fn get_key(key: &str, field: &str) -> String {
         format!("{key}:{field}")
     }
+
+    fn expires_at(ttl: Option<u64>) -> anyhow::Result<Option<u64>> {
+        Ok(match ttl {
+            Some(ttl) => {
+                let expiry_time = SystemTime::now()
+                    .checked_add(Duration::from_secs(ttl))
+                    .ok_or_else(|| anyhow::anyhow!("could not calculate expiration time"))?;
+                Some(expiry_time.duration_since(UNIX_EPOCH)?.as_secs())
+            }
+            None => None,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -478,6 +508,32 @@ mod tests {
         assert_eq!(taken, None);
     }
 
+    #[tokio::test]
+    async fn test_increment() {
+        let cache = MemCache::new();
+        let key = "increment_key";
+        let field = "counter";
+
+        assert_eq!(cache.increment(key, field, 1, None).unwrap(), 1);
+        assert_eq!(cache.increment(key, field, 2, None).unwrap(), 3);
+        assert_eq!(cache.get::<u64>(key, field).unwrap(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_increment_keeps_ttl() {
+        let cache = MemCache::new();
+        let key = "increment_ttl_key";
+        let field = "counter";
+
+        assert_eq!(cache.increment(key, field, 1, Some(2)).unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(900)).await;
+        assert_eq!(cache.increment(key, field, 1, Some(2)).unwrap(), 2);
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+
+        assert_eq!(cache.get::<u64>(key, field).unwrap(), None);
+        assert_eq!(cache.increment(key, field, 1, Some(2)).unwrap(), 1);
+    }
+
     #[tokio::test]
     async fn test_delete_existing_key() {
         let cache = MemCache::new();
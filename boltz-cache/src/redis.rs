@@ -91,6 +91,33 @@ impl Redis {
         Ok(())
     }
 
+    pub async fn increment(
+        &self,
+        key: &str,
+        field: &str,
+        by: u64,
+        ttl: Option<u64>,
+    ) -> Result<u64> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+
+        pipe.cmd("HINCRBY").arg(key).arg(field).arg(by);
+
+        if let Some(ttl) = ttl {
+            pipe.cmd("HEXPIRE")
+                .arg(key)
+                .arg(ttl)
+                .arg("NX")
+                .arg("FIELDS")
+                .arg(1)
+                .arg(field)
+                .ignore();
+        }
+
+        let (value,): (u64,) = pipe.query_async(&mut self.connection.clone()).await?;
+        Ok(value)
+    }
+
     pub async fn take<V: DeserializeOwned>(&self, key: &str, field: &str) -> Result<Option<V>> {
         let res: Vec<Option<String>> = redis::cmd("HGETDEL")
             .arg(key)
@@ -385,6 +412,23 @@ mod test {
         assert!(second_take.is_none());
     }
 
+    #[tokio::test]
+    async fn test_increment() {
+        let cache = Redis::new(&CacheConfig {
+            redis_endpoint: REDIS_ENDPOINT.to_string(),
+        })
+        .await
+        .unwrap();
+
+        let key = "test_increment";
+        let field = "counter";
+        cache.delete(key, field).await.unwrap();
+
+        assert_eq!(cache.increment(key, field, 1, Some(60)).await.unwrap(), 1);
+        assert_eq!(cache.increment(key, field, 2, Some(60)).await.unwrap(), 3);
+        assert_eq!(cache.get::<u64>(key, field).await.unwrap(), Some(3));
+    }
+
     #[tokio::test]
     async fn test_delete_existing_key() {
         let cache = Redis::new(&CacheConfig {
@@ -0,0 +1,320 @@
+use crate::{
+    bitcoin::{Timelock, TreeError, UncooperativeDetails, decode_tree, reverse_tree},
+    descriptor::{self, RefundLock},
+    network::Network,
+    utils::{OutputType, SwapKind},
+};
+use bitcoin::{
+    Address, ScriptBuf, Sequence,
+    absolute::LockTime,
+    address::{NetworkUnchecked, ParseError},
+    key::TweakedPublicKey,
+    secp256k1::{Secp256k1, Verification},
+};
+use std::str::FromStr;
+
+/// Errors returned by [`output_descriptor`] and [`parse_descriptor`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum DescriptorError {
+    /// The descriptor checksum does not match or the descriptor contains
+    /// characters that descriptors do not allow.
+    #[error("invalid descriptor checksum")]
+    Checksum,
+    /// The descriptor is neither the `tr(...)` of a reverse swap tree nor an
+    /// `addr(...)`.
+    #[error("unsupported descriptor")]
+    Unsupported,
+    /// The Taproot builder could not finalize the swap tree.
+    #[error("could not finalize taproot builder")]
+    TaprootFinalize,
+    /// A swap [`Tree`](crate::bitcoin::Tree) operation failed.
+    #[error(transparent)]
+    Tree(#[from] TreeError),
+    /// The address in the descriptor could not be parsed or is for another network.
+    #[error(transparent)]
+    Address(#[from] ParseError),
+    /// The redeem script is too large for its output type.
+    #[error(transparent)]
+    Script(#[from] bitcoin::address::P2shError),
+}
+
+/// What a swap output descriptor describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Descriptor {
+    /// Taproot output of a reverse swap, with the full script tree.
+    Taproot(UncooperativeDetails),
+    /// Any other swap output, described only by its address.
+    Address(Address),
+}
+
+impl Descriptor {
+    /// The `scriptPubKey` of the described output.
+    pub fn script_pubkey<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+    ) -> Result<ScriptBuf, DescriptorError> {
+        Ok(match self {
+            Descriptor::Taproot(details) => ScriptBuf::new_p2tr_tweaked(output_key(secp, details)?),
+            Descriptor::Address(address) => address.script_pubkey(),
+        })
+    }
+}
+
+/// Export a swap output as BIP-380 output descriptor with checksum.
+///
+/// Reverse swap trees become `tr(KEY,{CLAIM,REFUND})` with both leaves in
+/// miniscript, which wallets can import to watch and sweep the output. The
+/// refund leaf is `after(N)` for absolute and `older(N)` for relative lock
+/// times. The
+/// claim leaf of submarine swaps and the legacy redeem scripts are not
+/// miniscript, so those outputs are exported as watch-only `addr(...)`.
+pub fn output_descriptor<C: Verification>(
+    secp: &Secp256k1<C>,
+    output_type: &OutputType<UncooperativeDetails, ScriptBuf>,
+    network: Network,
+) -> Result<String, DescriptorError> {
+    let network = network.bitcoin();
+
+    let address = match output_type {
+        OutputType::Taproot(details) => {
+            if let Ok(params) = decode_tree(&details.tree)
+                && params.kind == SwapKind::Reverse
+            {
+                return Ok(descriptor::taproot_descriptor(
+                    "tr",
+                    &details.internal_key,
+                    &params.preimage_hash,
+                    &params.claim_pubkey,
+                    &params.refund_pubkey,
+                    match params.lock_time {
+                        Timelock::Absolute(lock_time) => {
+                            RefundLock::After(lock_time.to_consensus_u32())
+                        }
+                        Timelock::Relative(sequence) => {
+                            RefundLock::Older(sequence.to_consensus_u32())
+                        }
+                    },
+                ));
+            }
+
+            Address::p2tr_tweaked(output_key(secp, details)?, network)
+        }
+        OutputType::SegwitV0(script) => Address::p2wsh(script, network),
+        OutputType::Compatibility(script) => Address::p2shwsh(script, network),
+        OutputType::Legacy(script) => Address::p2sh(script, network)?,
+    };
+
+    Ok(descriptor::with_checksum(format!("addr({address})")))
+}
+
+/// Parse a descriptor created by [`output_descriptor`].
+///
+/// The checksum is optional, but has to match when present.
+pub fn parse_descriptor(descriptor: &str, network: Network) -> Result<Descriptor, DescriptorError> {
+    let descriptor = descriptor::strip_checksum(descriptor).ok_or(DescriptorError::Checksum)?;
+
+    if let Some(address) = descriptor::function(descriptor, "addr") {
+        return Ok(Descriptor::Address(
+            Address::<NetworkUnchecked>::from_str(address)?.require_network(network.bitcoin())?,
+        ));
+    }
+
+    let parts = descriptor::parse_taproot(descriptor, "tr").ok_or(DescriptorError::Unsupported)?;
+    Ok(Descriptor::Taproot(UncooperativeDetails {
+        tree: reverse_tree(
+            parts.preimage_hash,
+            &parts.claim_pubkey,
+            &parts.refund_pubkey,
+            match parts.lock_time {
+                RefundLock::After(lock_time) => {
+                    Timelock::Absolute(LockTime::from_consensus(lock_time))
+                }
+                RefundLock::Older(sequence) => {
+                    Timelock::Relative(Sequence::from_consensus(sequence))
+                }
+            },
+        ),
+        internal_key: parts.internal_key,
+    }))
+}
+
+fn output_key<C: Verification>(
+    secp: &Secp256k1<C>,
+    details: &UncooperativeDetails,
+) -> Result<TweakedPublicKey, DescriptorError> {
+    Ok(details
+        .tree
+        .build()?
+        .finalize(secp, details.internal_key)
+        .map_err(|_| DescriptorError::TaprootFinalize)?
+        .output_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{swap_script, swap_tree};
+    use bitcoin::{
+        XOnlyPublicKey,
+        hashes::hash160,
+        secp256k1::{PublicKey, Secp256k1},
+    };
+    use rstest::rstest;
+
+    fn keys() -> (PublicKey, PublicKey) {
+        (
+            PublicKey::from_str(
+                "03f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539",
+            )
+            .unwrap(),
+            PublicKey::from_str(
+                "03ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn preimage_hash() -> hash160::Hash {
+        hash160::Hash::from_str("0be3e65567f55ff6ac791bd4f65f672bcaf5f211").unwrap()
+    }
+
+    fn internal_key() -> XOnlyPublicKey {
+        XOnlyPublicKey::from_str("a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd")
+            .unwrap()
+    }
+
+    fn details(reverse: bool, lock_time: Timelock) -> UncooperativeDetails {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let build = if reverse { reverse_tree } else { swap_tree };
+
+        UncooperativeDetails {
+            tree: build(
+                preimage_hash(),
+                &claim_pubkey.x_only_public_key().0,
+                &refund_pubkey.x_only_public_key().0,
+                lock_time,
+            ),
+            internal_key: internal_key(),
+        }
+    }
+
+    fn tweaked_script_pubkey(details: &UncooperativeDetails) -> ScriptBuf {
+        ScriptBuf::new_p2tr_tweaked(output_key(&Secp256k1::new(), details).unwrap())
+    }
+
+    #[rstest]
+    #[case::absolute(Timelock::Absolute(LockTime::from_height(515_924).unwrap()), "after(515924)")]
+    #[case::relative(Timelock::Relative(Sequence::from_height(144)), "older(144)")]
+    fn test_reverse_tree(#[case] lock_time: Timelock, #[case] refund_fragment: &str) {
+        let secp = Secp256k1::new();
+        let details = details(true, lock_time);
+
+        let descriptor = output_descriptor(
+            &secp,
+            &OutputType::Taproot(details.clone()),
+            Network::Regtest,
+        )
+        .unwrap();
+        assert!(descriptor.starts_with(&format!(
+            "tr({},{{and_v(v:hash160({}),pk(",
+            internal_key(),
+            preimage_hash()
+        )));
+        assert!(descriptor.contains(refund_fragment));
+
+        // Wallets parse the same output from it
+        let parsed = miniscript::Descriptor::<XOnlyPublicKey>::from_str(&descriptor).unwrap();
+        assert_eq!(parsed.script_pubkey(), tweaked_script_pubkey(&details));
+
+        let decoded = parse_descriptor(&descriptor, Network::Regtest).unwrap();
+        assert_eq!(decoded, Descriptor::Taproot(details.clone()));
+        assert_eq!(
+            decoded.script_pubkey(&secp).unwrap(),
+            tweaked_script_pubkey(&details)
+        );
+    }
+
+    #[test]
+    fn test_submarine_tree() {
+        let secp = Secp256k1::new();
+        let details = details(false, LockTime::from_height(515_924).unwrap().into());
+
+        let descriptor = output_descriptor(
+            &secp,
+            &OutputType::Taproot(details.clone()),
+            Network::Regtest,
+        )
+        .unwrap();
+        assert!(descriptor.starts_with("addr(bcrt1p"));
+
+        let decoded = parse_descriptor(&descriptor, Network::Regtest).unwrap();
+        assert_eq!(
+            decoded.script_pubkey(&secp).unwrap(),
+            tweaked_script_pubkey(&details)
+        );
+    }
+
+    #[rstest]
+    #[case::segwit_v0(OutputType::SegwitV0(()), "bcrt1q")]
+    #[case::compatibility(OutputType::Compatibility(()), "2")]
+    #[case::legacy(OutputType::Legacy(()), "2")]
+    fn test_legacy(#[case] output_type: OutputType<(), ()>, #[case] prefix: &str) {
+        let secp = Secp256k1::new();
+        let (claim_pubkey, refund_pubkey) = keys();
+        let script = swap_script(
+            preimage_hash(),
+            &claim_pubkey,
+            &refund_pubkey,
+            LockTime::from_height(515_924).unwrap(),
+        );
+
+        let (output_type, script_pubkey) = match output_type {
+            OutputType::SegwitV0(_) => (
+                OutputType::SegwitV0(script.clone()),
+                ScriptBuf::new_p2wsh(&script.wscript_hash()),
+            ),
+            OutputType::Compatibility(_) => (
+                OutputType::Compatibility(script.clone()),
+                ScriptBuf::new_p2sh(&ScriptBuf::new_p2wsh(&script.wscript_hash()).script_hash()),
+            ),
+            OutputType::Legacy(_) => (
+                OutputType::Legacy(script.clone()),
+                ScriptBuf::new_p2sh(&script.script_hash()),
+            ),
+            OutputType::Taproot(_) => unreachable!(),
+        };
+
+        let descriptor = output_descriptor(&secp, &output_type, Network::Regtest).unwrap();
+        assert!(descriptor.starts_with(&format!("addr({prefix}")));
+
+        let decoded = parse_descriptor(&descriptor, Network::Regtest).unwrap();
+        assert_eq!(decoded.script_pubkey(&secp).unwrap(), script_pubkey);
+    }
+
+    #[test]
+    fn test_parse_descriptor_errors() {
+        let descriptor = output_descriptor(
+            &Secp256k1::new(),
+            &OutputType::Taproot(details(
+                false,
+                LockTime::from_height(515_924).unwrap().into(),
+            )),
+            Network::Regtest,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            parse_descriptor(&descriptor, Network::Mainnet),
+            Err(DescriptorError::Address(_))
+        ));
+        assert!(matches!(
+            parse_descriptor(&descriptor.replace("addr(", "addr( "), Network::Regtest),
+            Err(DescriptorError::Checksum)
+        ));
+        assert!(matches!(
+            parse_descriptor("raw(deadbeef)#89f8spxm", Network::Regtest),
+            Err(DescriptorError::Unsupported)
+        ));
+    }
+}
@@ -4,6 +4,11 @@
 //! swaps ([`swap_script`], [`swap_tree`], [`reverse_script`],
 //! [`reverse_tree`]) plus the [`construct_tx`] entry point that signs and
 //! serializes a claim or refund transaction from a slice of [`InputDetail`].
+//! [`construct_psbt`] and [`finalize_psbt`] build the same transaction as an
+//! unsigned PSBT for external signers and extract it once it is signed.
+//! [`decode_tree`] and [`decode_script`] go the other way and recover the
+//! [`SwapParams`] a script was built from. [`output_descriptor`] exports a
+//! swap output as descriptor that wallets can import.
 
 use crate::utils::{InputType, OutputType, Transaction as TTransaction, TxIn as TTxIn};
 use bitcoin::{
@@ -13,10 +18,18 @@ use bitcoin::{
     transaction::{Transaction, TxIn},
 };
 
+mod descriptor;
+mod psbt;
 mod scripts;
 mod tx;
 
-pub use scripts::{Tapleaf, Tree, TreeError, reverse_script, reverse_tree, swap_script, swap_tree};
+pub use descriptor::{Descriptor, DescriptorError, output_descriptor, parse_descriptor};
+pub use psbt::{construct_psbt, finalize_psbt};
+pub use scripts::{
+    DecodeError, SwapParams, Tapleaf, Timelock, Tree, TreeError, decode_claim_leaf,
+    decode_refund_leaf, decode_script, decode_tree, reverse_script, reverse_tree, swap_script,
+    swap_tree,
+};
 pub use tx::{TxError, construct_tx};
 
 /// Information needed to spend a Taproot swap input via the script-path
@@ -30,9 +43,13 @@ pub struct UncooperativeDetails {
 }
 
 /// One UTXO to spend when constructing a swap claim or refund transaction.
+///
+/// Signed by a local [`Keypair`] unless another [`Signer`](crate::Signer) is
+/// chosen for `S`.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct InputDetail {
-    /// Whether this input is being claimed (preimage) or refunded (locktime).
+pub struct InputDetail<S = Keypair> {
+    /// Whether this input is being claimed (preimage), refunded (locktime) or
+    /// is an [`InputType::Wallet`] UTXO that adds funds.
     pub input_type: InputType,
     /// The output script type, with the Taproot variant carrying optional
     /// uncooperative-spend details. `None` selects the key-path spend; `Some`
@@ -42,8 +59,22 @@ pub struct InputDetail {
     pub outpoint: OutPoint,
     /// The previous output (`scriptPubKey` + value) being spent.
     pub tx_out: TxOut,
-    /// The keypair authorized to sign for this input.
-    pub keys: Keypair,
+    /// The signer authorized to sign for this input.
+    pub keys: S,
+}
+
+/// One UTXO to spend in a PSBT built by [`construct_psbt`], which is signed
+/// by an external wallet instead of a local [`Keypair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PsbtInputDetail {
+    /// Whether this input is being claimed (preimage) or refunded (locktime).
+    pub input_type: InputType,
+    /// The output script type; see [`InputDetail::output_type`].
+    pub output_type: OutputType<Option<UncooperativeDetails>, ScriptBuf>,
+    /// The outpoint of the UTXO being spent.
+    pub outpoint: OutPoint,
+    /// The previous output (`scriptPubKey` + value) being spent.
+    pub tx_out: TxOut,
 }
 
 impl TTransaction for Transaction {
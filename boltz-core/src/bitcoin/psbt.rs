@@ -0,0 +1,791 @@
+use crate::{
+    bitcoin::{
+        InputDetail, PsbtInputDetail,
+        scripts::TreeError,
+        tx::{
+            TxError, construct_raw, control_block, input_value, nested_redeem_script,
+            nested_script_sig, sort_inputs, spend_leaf, unsigned_tx,
+        },
+    },
+    consts::{PREIMAGE_DUMMY, STUB_SECRET_KEY},
+    target_fee::{FeeTarget, target_fee},
+    utils::{Destination, InputType, OutputType},
+};
+use bitcoin::{
+    Address, Amount, EcdsaSighashType, PublicKey, ScriptBuf, TapLeafHash, Witness,
+    bip32::KeySource,
+    hashes::{Hash, hash160},
+    key::Keypair,
+    psbt::{self, Psbt},
+    script::Instruction,
+    secp256k1::{Secp256k1, Signing, Verification},
+    taproot::LeafVersion,
+    transaction::Transaction,
+};
+use std::collections::BTreeMap;
+
+const PUBLIC_KEY_LENGTH: usize = 33;
+
+/// Build an unsigned PSBT (BIP174) spending `inputs` to `destination`,
+/// paying `fee`, for the inputs to be signed by an external wallet.
+///
+/// Every input carries what is needed to sign and finalize it: the previous
+/// output, witness and redeem scripts, the preimage of claims and, for
+/// script-path spends, the leaf script, control block and internal key. The
+/// public key expected to sign is added with an empty key origin, so that
+/// signers that look up keys by public key find it. Cooperative key-path
+/// spends only get their previous output, since their signature is created
+/// with MuSig2. Legacy inputs are rejected, because BIP174 requires their
+/// full previous transaction. Wallet inputs get their previous output and, for
+/// P2WPKH, the sighash type; adding the key origins is left to the wallet that
+/// owns them.
+///
+/// Returns the PSBT and the fee that will be charged, in satoshis; the
+/// signed PSBT can be turned into a transaction with [`finalize_psbt`].
+#[must_use = "ignoring the result discards the constructed PSBT"]
+pub fn construct_psbt<C: Signing + Verification>(
+    secp: &Secp256k1<C>,
+    inputs: Vec<PsbtInputDetail>,
+    destination: &Destination<&Address>,
+    fee: FeeTarget,
+) -> Result<(Psbt, u64), TxError> {
+    // Signing with a stub key yields a transaction of the same size as the
+    // externally signed one, which is all that is needed for fee estimation
+    let stub_keys = Keypair::from_seckey_slice(secp, &STUB_SECRET_KEY)?;
+    let mut inputs = inputs
+        .into_iter()
+        .map(|input| InputDetail {
+            input_type: input.input_type,
+            output_type: input.output_type,
+            outpoint: input.outpoint,
+            tx_out: input.tx_out,
+            keys: stub_keys,
+        })
+        .collect::<Vec<_>>();
+    sort_inputs(&mut inputs);
+
+    let (tx, fee) = target_fee(fee, input_value(&inputs), |fee, is_fee_estimation| {
+        if is_fee_estimation {
+            construct_raw(secp, &inputs, destination, Amount::from_sat(fee))
+        } else {
+            unsigned_tx(&inputs, destination, Amount::from_sat(fee))
+        }
+    })?;
+
+    let mut psbt_inputs = Vec::with_capacity(inputs.len());
+    for (i, input) in inputs.iter().enumerate() {
+        psbt_inputs.push(psbt_input(secp, i, input)?);
+    }
+
+    Ok((
+        Psbt {
+            outputs: vec![psbt::Output::default(); tx.output.len()],
+            unsigned_tx: tx,
+            version: 0,
+            xpub: BTreeMap::new(),
+            proprietary: BTreeMap::new(),
+            unknown: BTreeMap::new(),
+            inputs: psbt_inputs,
+        },
+        fee,
+    ))
+}
+
+/// Finalize the inputs of a PSBT created by [`construct_psbt`] once it has
+/// been signed and extract the transaction.
+///
+/// Inputs that are already finalized are left untouched, which allows the
+/// cooperative key-path inputs to be finalized by the caller.
+pub fn finalize_psbt(mut psbt: Psbt) -> Result<Transaction, TxError> {
+    for (i, input) in psbt.inputs.iter_mut().enumerate() {
+        if input.final_script_witness.is_some() || input.final_script_sig.is_some() {
+            continue;
+        }
+
+        let preimage = input.hash160_preimages.values().next().cloned();
+        let mut witness = Witness::new();
+
+        if let Some(sig) = input.tap_key_sig {
+            witness.push(sig.serialize());
+        } else if let Some((control_block, (script, version))) = input.tap_scripts.iter().next() {
+            let leaf_hash = TapLeafHash::from_script(script, *version);
+            let sig = input
+                .tap_script_sigs
+                .iter()
+                .find(|((_, hash), _)| *hash == leaf_hash)
+                .map(|(_, sig)| sig)
+                .ok_or(TxError::MissingSignature(i))?;
+
+            witness.push(sig.serialize());
+            if let Some(preimage) = preimage {
+                witness.push(preimage);
+            }
+            witness.push(script.as_bytes());
+            witness.push(control_block.serialize());
+        } else if let Some(witness_script) = &input.witness_script {
+            let sig = input
+                .partial_sigs
+                .values()
+                .next()
+                .ok_or(TxError::MissingSignature(i))?;
+
+            witness.push(sig.serialize());
+            match preimage {
+                Some(preimage) => witness.push(preimage),
+                None => witness.push(PREIMAGE_DUMMY),
+            };
+            witness.push(witness_script.as_bytes());
+
+            if input.redeem_script.is_some() {
+                input.final_script_sig = Some(nested_script_sig(witness_script)?);
+            }
+        } else if let Some((public_key, sig)) = input.partial_sigs.iter().next() {
+            // P2WPKH wallet inputs
+            witness = Witness::p2wpkh(sig, &public_key.inner);
+        } else {
+            return Err(TxError::MissingSpendInfo(i));
+        }
+
+        input.final_script_witness = Some(witness);
+
+        // BIP174: everything but the UTXO and the final scripts is cleared by the finalizer
+        *input = psbt::Input {
+            witness_utxo: input.witness_utxo.take(),
+            final_script_sig: input.final_script_sig.take(),
+            final_script_witness: input.final_script_witness.take(),
+            proprietary: std::mem::take(&mut input.proprietary),
+            unknown: std::mem::take(&mut input.unknown),
+            ..Default::default()
+        };
+    }
+
+    Ok(psbt.extract_tx_unchecked_fee_rate())
+}
+
+fn psbt_input<C: Verification>(
+    secp: &Secp256k1<C>,
+    index: usize,
+    input: &InputDetail,
+) -> Result<psbt::Input, TxError> {
+    let mut psbt_input = psbt::Input {
+        witness_utxo: Some(input.tx_out.clone()),
+        ..Default::default()
+    };
+
+    if input.input_type == InputType::Wallet {
+        if let OutputType::SegwitV0(_) = input.output_type {
+            psbt_input.sighash_type = Some(EcdsaSighashType::All.into());
+        }
+
+        return Ok(psbt_input);
+    }
+
+    if let InputType::Claim(preimage) = input.input_type {
+        psbt_input
+            .hash160_preimages
+            .insert(hash160::Hash::hash(&preimage), preimage.to_vec());
+    }
+
+    match &input.output_type {
+        OutputType::Legacy(_) => return Err(TxError::UnsupportedPsbtInput(index)),
+        OutputType::SegwitV0(witness_script) | OutputType::Compatibility(witness_script) => {
+            if let OutputType::Compatibility(_) = input.output_type {
+                psbt_input.redeem_script = Some(nested_redeem_script(witness_script));
+            }
+
+            psbt_input.witness_script = Some(witness_script.clone());
+            psbt_input.sighash_type = Some(EcdsaSighashType::All.into());
+
+            if let Some(public_key) = script_public_key(witness_script, input.input_type) {
+                psbt_input
+                    .bip32_derivation
+                    .insert(public_key.inner, KeySource::default());
+            }
+        }
+        OutputType::Taproot(None) => {}
+        OutputType::Taproot(Some(uncooperative)) => {
+            let leaf = spend_leaf(input.input_type, &uncooperative.tree);
+            let (control_block, spend_info) = control_block(secp, index, uncooperative, leaf)?;
+
+            psbt_input.tap_internal_key = Some(uncooperative.internal_key);
+            psbt_input.tap_merkle_root = spend_info.merkle_root();
+            psbt_input.tap_scripts.insert(
+                control_block,
+                (
+                    leaf.output.clone(),
+                    LeafVersion::from_consensus(leaf.version).map_err(TreeError::Taproot)?,
+                ),
+            );
+
+            let public_key = match input.input_type {
+                InputType::Claim(_) => uncooperative.tree.claim_pubkey()?,
+                InputType::Refund(_) | InputType::RelativeRefund(_) => {
+                    uncooperative.tree.refund_pubkey()?
+                }
+                InputType::Wallet => unreachable!("wallet inputs have no swap tree"),
+            };
+            psbt_input
+                .tap_key_origins
+                .insert(public_key, (vec![leaf.leaf_hash()?], KeySource::default()));
+        }
+    };
+
+    Ok(psbt_input)
+}
+
+/// The public key of the claim or refund branch of a swap script; the claim
+/// key is pushed before the refund key in all of them
+fn script_public_key(script: &ScriptBuf, input_type: InputType) -> Option<PublicKey> {
+    let mut keys = script
+        .instructions()
+        .flatten()
+        .filter_map(|inst| match inst {
+            Instruction::PushBytes(bytes) if bytes.len() == PUBLIC_KEY_LENGTH => {
+                PublicKey::from_slice(bytes.as_bytes()).ok()
+            }
+            _ => None,
+        });
+
+    match input_type {
+        InputType::Claim(_) => keys.next(),
+        InputType::Refund(_) | InputType::RelativeRefund(_) => keys.last(),
+        InputType::Wallet => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{
+        Tree, UncooperativeDetails, construct_tx, reverse_tree, swap_script, swap_tree,
+    };
+    use bitcoin::{
+        Network, OutPoint, PrivateKey, TxOut, Txid, XOnlyPublicKey,
+        absolute::LockTime,
+        ecdsa,
+        key::rand::{self, RngCore},
+        secp256k1::Message,
+        sighash::{Prevouts, SighashCache},
+        taproot,
+    };
+    use futures::executor::block_on;
+    use rstest::rstest;
+
+    const LOCK_TIME: u32 = 1_000;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Kind {
+        SegwitV0,
+        Compatibility,
+        Legacy,
+        Taproot,
+    }
+
+    fn input<C: Signing + Verification>(
+        secp: &Secp256k1<C>,
+        kind: Kind,
+        claim: bool,
+    ) -> (PsbtInputDetail, Keypair) {
+        let mut preimage = [0; 32];
+        rand::thread_rng().fill_bytes(&mut preimage);
+
+        let claim_keys = Keypair::new(secp, &mut rand::thread_rng());
+        let refund_keys = Keypair::new(secp, &mut rand::thread_rng());
+        let preimage_hash = hash160::Hash::hash(&preimage);
+        let lock_time = LockTime::from_height(LOCK_TIME).unwrap();
+
+        let (output_type, script_pubkey) = match kind {
+            Kind::Taproot => {
+                let tree = swap_tree(
+                    preimage_hash,
+                    &claim_keys.x_only_public_key().0,
+                    &refund_keys.x_only_public_key().0,
+                    lock_time,
+                );
+                let internal_key = Keypair::new(secp, &mut rand::thread_rng())
+                    .x_only_public_key()
+                    .0;
+                let spend_info = tree.build().unwrap().finalize(secp, internal_key).unwrap();
+
+                (
+                    OutputType::Taproot(Some(UncooperativeDetails { tree, internal_key })),
+                    ScriptBuf::new_p2tr_tweaked(spend_info.output_key()),
+                )
+            }
+            _ => {
+                let script = swap_script(
+                    preimage_hash,
+                    &claim_keys.public_key(),
+                    &refund_keys.public_key(),
+                    lock_time,
+                );
+
+                match kind {
+                    Kind::SegwitV0 => (
+                        OutputType::SegwitV0(script.clone()),
+                        ScriptBuf::new_p2wsh(&script.wscript_hash()),
+                    ),
+                    Kind::Compatibility => (
+                        OutputType::Compatibility(script.clone()),
+                        ScriptBuf::new_p2sh(&nested_redeem_script(&script).script_hash()),
+                    ),
+                    _ => (
+                        OutputType::Legacy(script.clone()),
+                        ScriptBuf::new_p2sh(&script.script_hash()),
+                    ),
+                }
+            }
+        };
+
+        let mut txid = [0; 32];
+        rand::thread_rng().fill_bytes(&mut txid);
+
+        (
+            PsbtInputDetail {
+                input_type: if claim {
+                    InputType::Claim(preimage)
+                } else {
+                    InputType::Refund(LOCK_TIME)
+                },
+                output_type,
+                outpoint: OutPoint::new(Txid::from_byte_array(txid), 1),
+                tx_out: TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey,
+                },
+            },
+            if claim { claim_keys } else { refund_keys },
+        )
+    }
+
+    fn sign(secp: &Secp256k1<bitcoin::secp256k1::All>, psbt: &mut Psbt, keys: &[Keypair]) {
+        let keys = keys
+            .iter()
+            .map(|keys| {
+                let key = PrivateKey::new(keys.secret_key(), Network::Regtest);
+                (key.public_key(secp), key)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        psbt.sign(&keys, secp).unwrap();
+    }
+
+    fn destination() -> Address {
+        let secp = Secp256k1::new();
+        Address::p2tr(
+            &secp,
+            Keypair::new(&secp, &mut rand::thread_rng())
+                .x_only_public_key()
+                .0,
+            None,
+            Network::Regtest,
+        )
+    }
+
+    fn verify_signatures(
+        secp: &Secp256k1<bitcoin::secp256k1::All>,
+        tx: &Transaction,
+        inputs: &[PsbtInputDetail],
+    ) {
+        let prevouts = inputs
+            .iter()
+            .map(|input| input.tx_out.clone())
+            .collect::<Vec<_>>();
+        let mut cache = SighashCache::new(tx);
+
+        for (i, (tx_in, input)) in tx.input.iter().zip(inputs.iter()).enumerate() {
+            let witness = tx_in.witness.to_vec();
+
+            match &input.output_type {
+                OutputType::Taproot(Some(uncooperative)) => {
+                    let leaf = spend_leaf(input.input_type, &uncooperative.tree);
+                    let sighash = cache
+                        .taproot_script_spend_signature_hash(
+                            i,
+                            &Prevouts::All(&prevouts),
+                            leaf.leaf_hash().unwrap(),
+                            bitcoin::TapSighashType::Default,
+                        )
+                        .unwrap();
+                    let sig = taproot::Signature::from_slice(&witness[0]).unwrap();
+                    let public_key = match input.input_type {
+                        InputType::Claim(_) => uncooperative.tree.claim_pubkey().unwrap(),
+                        InputType::Refund(_) | InputType::RelativeRefund(_) => {
+                            uncooperative.tree.refund_pubkey().unwrap()
+                        }
+                        InputType::Wallet => unreachable!(),
+                    };
+
+                    secp.verify_schnorr(
+                        &sig.signature,
+                        &Message::from_digest(sighash.to_byte_array()),
+                        &public_key,
+                    )
+                    .unwrap();
+                }
+                OutputType::SegwitV0(script) | OutputType::Compatibility(script) => {
+                    let sighash = cache
+                        .p2wsh_signature_hash(i, script, input.tx_out.value, EcdsaSighashType::All)
+                        .unwrap();
+                    let sig = ecdsa::Signature::from_slice(&witness[0]).unwrap();
+
+                    secp.verify_ecdsa(
+                        &Message::from_digest(sighash.to_byte_array()),
+                        &sig.signature,
+                        &script_public_key(script, input.input_type).unwrap().inner,
+                    )
+                    .unwrap();
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::segwit_claim(Kind::SegwitV0, true)]
+    #[case::segwit_refund(Kind::SegwitV0, false)]
+    #[case::compatibility_claim(Kind::Compatibility, true)]
+    #[case::compatibility_refund(Kind::Compatibility, false)]
+    #[case::taproot_claim(Kind::Taproot, true)]
+    #[case::taproot_refund(Kind::Taproot, false)]
+    fn test_construct_finalize_psbt(#[case] kind: Kind, #[case] claim: bool) {
+        let secp = Secp256k1::new();
+        let (input, keys) = input(&secp, kind, claim);
+        let destination = destination();
+
+        let (mut psbt, fee) = construct_psbt(
+            &secp,
+            vec![input.clone()],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        )
+        .unwrap();
+
+        let (expected, expected_fee) = block_on(construct_tx(
+            &secp,
+            vec![InputDetail {
+                input_type: input.input_type,
+                output_type: input.output_type.clone(),
+                outpoint: input.outpoint,
+                tx_out: input.tx_out.clone(),
+                keys,
+            }],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        ))
+        .unwrap();
+        assert_eq!(fee, expected_fee);
+
+        sign(&secp, &mut psbt, &[keys]);
+        let tx = finalize_psbt(psbt).unwrap();
+
+        assert_eq!(tx.compute_txid(), expected.compute_txid());
+        assert_eq!(tx.input[0].script_sig, expected.input[0].script_sig);
+        assert_eq!(
+            tx.input[0].witness.iter().skip(1).collect::<Vec<_>>(),
+            expected.input[0].witness.iter().skip(1).collect::<Vec<_>>()
+        );
+        verify_signatures(&secp, &tx, &[input]);
+    }
+
+    #[test]
+    fn test_construct_psbt_multiple_inputs() {
+        let secp = Secp256k1::new();
+        let (taproot, taproot_keys) = input(&secp, Kind::Taproot, true);
+        let (segwit, segwit_keys) = input(&secp, Kind::SegwitV0, false);
+        let destination = destination();
+
+        let (mut psbt, _) = construct_psbt(
+            &secp,
+            vec![taproot.clone(), segwit.clone()],
+            &Destination::Single(&destination),
+            FeeTarget::Absolute(1_000),
+        )
+        .unwrap();
+        assert_eq!(
+            psbt.unsigned_tx.lock_time,
+            LockTime::from_height(LOCK_TIME).unwrap()
+        );
+
+        sign(&secp, &mut psbt, &[taproot_keys, segwit_keys]);
+        let tx = finalize_psbt(psbt).unwrap();
+
+        let mut inputs = vec![taproot, segwit];
+        inputs.sort_by_key(|input| {
+            tx.input
+                .iter()
+                .position(|tx_in| tx_in.previous_output == input.outpoint)
+                .unwrap()
+        });
+        verify_signatures(&secp, &tx, &inputs);
+    }
+
+    #[test]
+    fn test_construct_psbt_metadata() {
+        let secp = Secp256k1::new();
+        let (input, keys) = input(&secp, Kind::Taproot, true);
+
+        let (psbt, _) = construct_psbt(
+            &secp,
+            vec![input.clone()],
+            &Destination::Single(&destination()),
+            FeeTarget::Absolute(1_000),
+        )
+        .unwrap();
+
+        let psbt_input = &psbt.inputs[0];
+        let uncooperative = match &input.output_type {
+            OutputType::Taproot(Some(uncooperative)) => uncooperative,
+            _ => unreachable!(),
+        };
+        let preimage = match input.input_type {
+            InputType::Claim(preimage) => preimage,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(psbt_input.witness_utxo, Some(input.tx_out.clone()));
+        assert_eq!(
+            psbt_input.tap_internal_key,
+            Some(uncooperative.internal_key)
+        );
+        assert_eq!(
+            psbt_input.tap_scripts.values().collect::<Vec<_>>(),
+            vec![&(
+                uncooperative.tree.claim_leaf.output.clone(),
+                LeafVersion::TapScript
+            )]
+        );
+        assert_eq!(
+            psbt_input
+                .hash160_preimages
+                .get(&hash160::Hash::hash(&preimage)),
+            Some(&preimage.to_vec())
+        );
+        assert_eq!(
+            psbt_input
+                .tap_key_origins
+                .keys()
+                .collect::<Vec<&XOnlyPublicKey>>(),
+            vec![&keys.x_only_public_key().0]
+        );
+
+        // The PSBT has to survive being passed to an external signer
+        assert_eq!(Psbt::deserialize(&psbt.serialize()).unwrap(), psbt);
+    }
+
+    #[test]
+    fn test_construct_psbt_cooperative() {
+        let secp = Secp256k1::new();
+        let (mut input, _) = input(&secp, Kind::Taproot, true);
+        input.output_type = OutputType::Taproot(None);
+
+        let (psbt, _) = construct_psbt(
+            &secp,
+            vec![input.clone()],
+            &Destination::Single(&destination()),
+            FeeTarget::Absolute(1_000),
+        )
+        .unwrap();
+
+        let mut psbt_input = psbt.inputs[0].clone();
+        assert_eq!(psbt_input.witness_utxo, Some(input.tx_out));
+        assert!(psbt_input.tap_scripts.is_empty());
+
+        psbt_input.tap_key_sig = Some(taproot::Signature::from_slice(&[1; 64]).unwrap());
+        let mut psbt = psbt;
+        psbt.inputs[0] = psbt_input;
+
+        let tx = finalize_psbt(psbt).unwrap();
+        assert_eq!(tx.input[0].witness.to_vec(), vec![vec![1; 64]]);
+    }
+
+    #[test]
+    fn test_construct_psbt_wallet_input() {
+        let secp = Secp256k1::new();
+        let (swap, swap_keys) = input(&secp, Kind::SegwitV0, true);
+        let wallet_keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let script_pubkey = ScriptBuf::new_p2wpkh(
+            &bitcoin::CompressedPublicKey(wallet_keys.public_key()).wpubkey_hash(),
+        );
+        let wallet = PsbtInputDetail {
+            input_type: InputType::Wallet,
+            output_type: OutputType::SegwitV0(script_pubkey.clone()),
+            outpoint: OutPoint::new(swap.outpoint.txid, 2),
+            tx_out: TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey,
+            },
+        };
+        let destination = destination();
+
+        let (mut psbt, fee) = construct_psbt(
+            &secp,
+            vec![swap.clone(), wallet.clone()],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        )
+        .unwrap();
+
+        let (expected, expected_fee) = block_on(construct_tx(
+            &secp,
+            [(swap, swap_keys), (wallet.clone(), wallet_keys)]
+                .into_iter()
+                .map(|(input, keys)| InputDetail {
+                    input_type: input.input_type,
+                    output_type: input.output_type,
+                    outpoint: input.outpoint,
+                    tx_out: input.tx_out,
+                    keys,
+                })
+                .collect(),
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        ))
+        .unwrap();
+        assert_eq!(fee, expected_fee);
+
+        // The wallet adds the origin of its key before signing
+        assert!(psbt.inputs[1].bip32_derivation.is_empty());
+        psbt.inputs[1]
+            .bip32_derivation
+            .insert(wallet_keys.public_key(), KeySource::default());
+
+        sign(&secp, &mut psbt, &[swap_keys, wallet_keys]);
+        let tx = finalize_psbt(psbt).unwrap();
+        let tx_out = wallet.tx_out;
+
+        assert_eq!(tx.compute_txid(), expected.compute_txid());
+        assert_eq!(tx.input[1].witness.nth(1), expected.input[1].witness.nth(1));
+
+        let sighash = SighashCache::new(&tx)
+            .p2wpkh_signature_hash(
+                1,
+                &tx_out.script_pubkey,
+                tx_out.value,
+                EcdsaSighashType::All,
+            )
+            .unwrap();
+        secp.verify_ecdsa(
+            &Message::from_digest(sighash.to_byte_array()),
+            &ecdsa::Signature::from_slice(&tx.input[1].witness[0])
+                .unwrap()
+                .signature,
+            &wallet_keys.public_key(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_construct_psbt_legacy() {
+        let secp = Secp256k1::new();
+        let (input, _) = input(&secp, Kind::Legacy, true);
+
+        assert_eq!(
+            construct_psbt(
+                &secp,
+                vec![input],
+                &Destination::Single(&destination()),
+                FeeTarget::Absolute(1_000),
+            )
+            .err()
+            .unwrap(),
+            TxError::UnsupportedPsbtInput(0)
+        );
+    }
+
+    #[rstest]
+    #[case::segwit(Kind::SegwitV0)]
+    #[case::taproot(Kind::Taproot)]
+    fn test_finalize_psbt_missing_signature(#[case] kind: Kind) {
+        let secp = Secp256k1::new();
+        let (input, _) = input(&secp, kind, false);
+
+        let (psbt, _) = construct_psbt(
+            &secp,
+            vec![input],
+            &Destination::Single(&destination()),
+            FeeTarget::Absolute(1_000),
+        )
+        .unwrap();
+
+        assert_eq!(
+            finalize_psbt(psbt).err().unwrap(),
+            TxError::MissingSignature(0)
+        );
+    }
+
+    #[test]
+    fn test_finalize_psbt_missing_spend_info() {
+        let secp = Secp256k1::new();
+        let (mut input, _) = input(&secp, Kind::Taproot, false);
+        input.output_type = OutputType::Taproot(None);
+
+        let (psbt, _) = construct_psbt(
+            &secp,
+            vec![input],
+            &Destination::Single(&destination()),
+            FeeTarget::Absolute(1_000),
+        )
+        .unwrap();
+
+        assert_eq!(
+            finalize_psbt(psbt).err().unwrap(),
+            TxError::MissingSpendInfo(0)
+        );
+    }
+
+    #[rstest]
+    #[case::claim(true)]
+    #[case::refund(false)]
+    fn test_script_public_key(#[case] claim: bool) {
+        let secp = Secp256k1::new();
+        let claim_keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let refund_keys = Keypair::new(&secp, &mut rand::thread_rng());
+
+        let script = swap_script(
+            hash160::Hash::hash(&[1; 32]),
+            &claim_keys.public_key(),
+            &refund_keys.public_key(),
+            LockTime::from_height(LOCK_TIME).unwrap(),
+        );
+
+        assert_eq!(
+            script_public_key(
+                &script,
+                if claim {
+                    InputType::Claim([1; 32])
+                } else {
+                    InputType::Refund(LOCK_TIME)
+                }
+            )
+            .unwrap()
+            .inner,
+            if claim {
+                claim_keys.public_key()
+            } else {
+                refund_keys.public_key()
+            }
+        );
+    }
+
+    #[test]
+    fn test_script_public_key_reverse() {
+        let secp = Secp256k1::new();
+        let tree: Tree = reverse_tree(
+            hash160::Hash::hash(&[1; 32]),
+            &Keypair::new(&secp, &mut rand::thread_rng())
+                .x_only_public_key()
+                .0,
+            &Keypair::new(&secp, &mut rand::thread_rng())
+                .x_only_public_key()
+                .0,
+            LockTime::from_height(LOCK_TIME).unwrap(),
+        );
+
+        // Tapscripts contain x-only keys, which are not picked up
+        assert_eq!(
+            script_public_key(&tree.claim_leaf.output, InputType::Claim([1; 32])),
+            None
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use crate::{
+    bitcoin::scripts::{Tapleaf, Timelock, Tree},
+    utils::SwapKind,
+};
+use bitcoin::{
+    Script, Sequence, XOnlyPublicKey,
+    absolute::LockTime,
+    hashes::{Hash, hash160},
+    opcodes::{
+        Opcode,
+        all::{
+            OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_CSV, OP_DROP, OP_ELSE, OP_ENDIF, OP_EQUAL,
+            OP_EQUALVERIFY, OP_HASH160, OP_IF, OP_SIZE,
+        },
+    },
+    script::Instruction,
+    secp256k1::PublicKey,
+    taproot::TAPROOT_LEAF_TAPSCRIPT,
+};
+
+/// Errors returned by [`decode_tree`] and [`decode_script`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The claim leaf does not match the submarine or reverse swap template.
+    #[error("claim leaf does not match a swap template")]
+    UnknownClaimLeaf,
+    /// The refund leaf does not match the `OP_CLTV` or `OP_CSV` refund template.
+    #[error("refund leaf does not match a swap template")]
+    UnknownRefundLeaf,
+    /// The redeem script does not match the submarine or reverse swap template.
+    #[error("script does not match a swap template")]
+    UnknownScript,
+    /// A leaf is not a tapscript leaf.
+    #[error("unexpected leaf version: {0:#x}")]
+    LeafVersion(u8),
+    /// A pushed key is not a valid public key.
+    #[error(transparent)]
+    Pubkey(#[from] bitcoin::secp256k1::Error),
+}
+
+/// Parameters of a swap, recovered from its script tree or redeem script.
+///
+/// `K` is [`XOnlyPublicKey`] for Taproot trees and [`PublicKey`] for legacy
+/// redeem scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapParams<K> {
+    /// Template the script matches.
+    pub kind: SwapKind,
+    /// HASH160 of the preimage that unlocks the claim path.
+    pub preimage_hash: hash160::Hash,
+    /// Key that signs the claim path.
+    pub claim_pubkey: K,
+    /// Key that signs the refund path.
+    pub refund_pubkey: K,
+    /// Lock time after which the refund path is spendable; always absolute
+    /// for legacy redeem scripts.
+    pub lock_time: Timelock,
+}
+
+/// Recover the swap parameters from a Taproot swap tree built by
+/// [`swap_tree`](crate::bitcoin::swap_tree) or [`reverse_tree`](crate::bitcoin::reverse_tree).
+pub fn decode_tree(tree: &Tree) -> Result<SwapParams<XOnlyPublicKey>, DecodeError> {
+    let (kind, preimage_hash, claim_pubkey) = decode_claim_leaf(&tree.claim_leaf)?;
+    let (refund_pubkey, lock_time) = decode_refund_leaf(&tree.refund_leaf)?;
+
+    Ok(SwapParams {
+        kind,
+        preimage_hash,
+        claim_pubkey,
+        refund_pubkey,
+        lock_time,
+    })
+}
+
+/// Recover the swap parameters from a legacy redeem script built by
+/// [`swap_script`](crate::bitcoin::swap_script) or [`reverse_script`](crate::bitcoin::reverse_script).
+pub fn decode_script(script: &Script) -> Result<SwapParams<PublicKey>, DecodeError> {
+    let params = match_script(script).ok_or(DecodeError::UnknownScript)?;
+
+    Ok(SwapParams {
+        kind: params.kind,
+        preimage_hash: params.preimage_hash,
+        claim_pubkey: PublicKey::from_slice(params.claim_pubkey)?,
+        refund_pubkey: PublicKey::from_slice(params.refund_pubkey)?,
+        lock_time: params.lock_time,
+    })
+}
+
+/// Decode the claim leaf into the swap kind, preimage hash and claim key.
+pub fn decode_claim_leaf(
+    leaf: &Tapleaf,
+) -> Result<(SwapKind, hash160::Hash, XOnlyPublicKey), DecodeError> {
+    check_leaf_version(leaf)?;
+
+    let (kind, preimage_hash, claim_pubkey) =
+        match_claim_leaf(&leaf.output).ok_or(DecodeError::UnknownClaimLeaf)?;
+
+    Ok((
+        kind,
+        hash160::Hash::from_slice(preimage_hash).expect("push has hash length"),
+        XOnlyPublicKey::from_slice(claim_pubkey)?,
+    ))
+}
+
+/// Decode the refund leaf into the refund key and lock time.
+pub fn decode_refund_leaf(leaf: &Tapleaf) -> Result<(XOnlyPublicKey, Timelock), DecodeError> {
+    check_leaf_version(leaf)?;
+
+    let (refund_pubkey, lock_time) =
+        match_refund_leaf(&leaf.output).ok_or(DecodeError::UnknownRefundLeaf)?;
+
+    Ok((XOnlyPublicKey::from_slice(refund_pubkey)?, lock_time))
+}
+
+fn check_leaf_version(leaf: &Tapleaf) -> Result<(), DecodeError> {
+    if leaf.version != TAPROOT_LEAF_TAPSCRIPT {
+        return Err(DecodeError::LeafVersion(leaf.version));
+    }
+
+    Ok(())
+}
+
+/// Match a claim leaf and return its kind, preimage hash and claim key
+fn match_claim_leaf(script: &Script) -> Option<(SwapKind, &[u8], &[u8])> {
+    let mut cursor = Cursor::new(script)?;
+    let kind = if cursor.op(OP_SIZE).is_some() {
+        cursor.num(32)?;
+        cursor.op(OP_EQUALVERIFY)?;
+        SwapKind::Reverse
+    } else {
+        SwapKind::Submarine
+    };
+
+    cursor.op(OP_HASH160)?;
+    let preimage_hash = cursor.push(20)?;
+    cursor.op(OP_EQUALVERIFY)?;
+    let claim_pubkey = cursor.push(32)?;
+    cursor.op(OP_CHECKSIG)?;
+    cursor.end()?;
+
+    Some((kind, preimage_hash, claim_pubkey))
+}
+
+/// Match an `OP_CLTV` or `OP_CSV` refund leaf and return its refund key and lock time
+fn match_refund_leaf(script: &Script) -> Option<(&[u8], Timelock)> {
+    let mut cursor = Cursor::new(script)?;
+    let refund_pubkey = cursor.push(32)?;
+    cursor.op(OP_CHECKSIGVERIFY)?;
+    let lock_time = cursor.uint()?;
+    let lock_time = if cursor.op(OP_CLTV).is_some() {
+        Timelock::Absolute(LockTime::from_consensus(lock_time))
+    } else {
+        cursor.op(OP_CSV)?;
+        Timelock::Relative(Sequence::from_consensus(lock_time))
+    };
+    cursor.end()?;
+
+    Some((refund_pubkey, lock_time))
+}
+
+/// Match a legacy redeem script; the keys are returned unparsed
+fn match_script(script: &Script) -> Option<SwapParams<&[u8]>> {
+    let mut cursor = Cursor::new(script)?;
+    let kind = if cursor.op(OP_SIZE).is_some() {
+        cursor.num(32)?;
+        cursor.op(OP_EQUAL)?;
+        cursor.op(OP_IF)?;
+        cursor.op(OP_HASH160)?;
+        SwapKind::Reverse
+    } else {
+        cursor.op(OP_HASH160)?;
+        SwapKind::Submarine
+    };
+
+    let preimage_hash = cursor.push(20)?;
+    match kind {
+        SwapKind::Submarine => {
+            cursor.op(OP_EQUAL)?;
+            cursor.op(OP_IF)?;
+        }
+        SwapKind::Reverse => {
+            cursor.op(OP_EQUALVERIFY)?;
+        }
+    }
+    let claim_pubkey = cursor.push(33)?;
+    cursor.op(OP_ELSE)?;
+    if kind == SwapKind::Reverse {
+        cursor.op(OP_DROP)?;
+    }
+    let lock_time = cursor.lock_time()?;
+    cursor.op(OP_CLTV)?;
+    cursor.op(OP_DROP)?;
+    let refund_pubkey = cursor.push(33)?;
+    cursor.op(OP_ENDIF)?;
+    cursor.op(OP_CHECKSIG)?;
+    cursor.end()?;
+
+    Some(SwapParams {
+        kind,
+        preimage_hash: hash160::Hash::from_slice(preimage_hash).expect("push has hash length"),
+        claim_pubkey,
+        refund_pubkey,
+        lock_time: Timelock::Absolute(lock_time),
+    })
+}
+
+/// Walks the instructions of a script, matching them one at a time
+struct Cursor<'a> {
+    instructions: std::vec::IntoIter<Instruction<'a>>,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(script: &'a Script) -> Option<Self> {
+        Some(Self {
+            instructions: script
+                .instructions()
+                .collect::<Result<Vec<_>, _>>()
+                .ok()?
+                .into_iter(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Instruction<'a>> {
+        self.instructions.as_slice().first()
+    }
+
+    fn op(&mut self, op: Opcode) -> Option<()> {
+        match self.peek()? {
+            Instruction::Op(next) if *next == op => {
+                self.instructions.next();
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, len: usize) -> Option<&'a [u8]> {
+        match self.peek()? {
+            Instruction::PushBytes(bytes) if bytes.len() == len => {
+                let bytes = bytes.as_bytes();
+                self.instructions.next();
+                Some(bytes)
+            }
+            _ => None,
+        }
+    }
+
+    fn num(&mut self, expected: i64) -> Option<()> {
+        (self.instructions.next()?.script_num()? == expected).then_some(())
+    }
+
+    fn uint(&mut self) -> Option<u32> {
+        u32::try_from(self.instructions.next()?.script_num()?).ok()
+    }
+
+    fn lock_time(&mut self) -> Option<LockTime> {
+        Some(LockTime::from_consensus(self.uint()?))
+    }
+
+    fn end(&mut self) -> Option<()> {
+        self.instructions.next().is_none().then_some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bitcoin::{reverse_script, reverse_tree, swap_script, swap_tree},
+        utils::InputType,
+    };
+    use bitcoin::{ScriptBuf, script::Builder};
+    use rstest::rstest;
+    use std::str::FromStr;
+
+    fn preimage_hash() -> hash160::Hash {
+        hash160::Hash::from_str("0be3e65567f55ff6ac791bd4f65f672bcaf5f211").unwrap()
+    }
+
+    fn keys() -> (PublicKey, PublicKey) {
+        (
+            PublicKey::from_str(
+                "03f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539",
+            )
+            .unwrap(),
+            PublicKey::from_str(
+                "03ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a",
+            )
+            .unwrap(),
+        )
+    }
+
+    #[rstest]
+    #[case::swap_low_lock_time(SwapKind::Submarine, 10)]
+    #[case::swap(SwapKind::Submarine, 515_924)]
+    #[case::reverse_low_lock_time(SwapKind::Reverse, 16)]
+    #[case::reverse(SwapKind::Reverse, 515_924)]
+    fn test_decode_tree(#[case] kind: SwapKind, #[case] lock_time: u32) {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let (claim_pubkey, refund_pubkey) = (
+            claim_pubkey.x_only_public_key().0,
+            refund_pubkey.x_only_public_key().0,
+        );
+        let lock_time = LockTime::from_height(lock_time).unwrap();
+
+        let build = match kind {
+            SwapKind::Submarine => swap_tree,
+            SwapKind::Reverse => reverse_tree,
+        };
+        let tree = build(preimage_hash(), &claim_pubkey, &refund_pubkey, lock_time);
+
+        assert_eq!(
+            decode_tree(&tree).unwrap(),
+            SwapParams {
+                kind,
+                preimage_hash: preimage_hash(),
+                claim_pubkey,
+                refund_pubkey,
+                lock_time: Timelock::Absolute(lock_time),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::swap_blocks(SwapKind::Submarine, Sequence::from_height(144))]
+    #[case::swap_low_blocks(SwapKind::Submarine, Sequence::from_height(10))]
+    #[case::reverse_blocks(SwapKind::Reverse, Sequence::from_height(4_032))]
+    #[case::reverse_time(SwapKind::Reverse, Sequence::from_512_second_intervals(42))]
+    fn test_decode_tree_relative(#[case] kind: SwapKind, #[case] sequence: Sequence) {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let (claim_pubkey, refund_pubkey) = (
+            claim_pubkey.x_only_public_key().0,
+            refund_pubkey.x_only_public_key().0,
+        );
+
+        let tree = match kind {
+            SwapKind::Submarine => {
+                swap_tree(preimage_hash(), &claim_pubkey, &refund_pubkey, sequence)
+            }
+            SwapKind::Reverse => {
+                reverse_tree(preimage_hash(), &claim_pubkey, &refund_pubkey, sequence)
+            }
+        };
+
+        let params = decode_tree(&tree).unwrap();
+        assert_eq!(params.kind, kind);
+        assert_eq!(params.lock_time, Timelock::Relative(sequence));
+        assert_eq!(
+            params.lock_time.refund_input_type(),
+            InputType::RelativeRefund(sequence.to_consensus_u32())
+        );
+    }
+
+    #[test]
+    fn test_decode_refund_leaf_relative_disabled() {
+        let (_, refund_pubkey) = keys();
+        let leaf = Tapleaf {
+            version: TAPROOT_LEAF_TAPSCRIPT,
+            output: Builder::new()
+                .push_x_only_key(&refund_pubkey.x_only_public_key().0)
+                .push_opcode(OP_CHECKSIGVERIFY)
+                .push_sequence(Sequence::MAX)
+                .push_opcode(OP_CSV)
+                .into_script(),
+        };
+
+        assert_eq!(
+            decode_refund_leaf(&leaf).unwrap_err(),
+            DecodeError::UnknownRefundLeaf
+        );
+    }
+
+    #[rstest]
+    #[case::swap(SwapKind::Submarine, 10)]
+    #[case::swap_high_lock_time(SwapKind::Submarine, 515_924)]
+    #[case::reverse(SwapKind::Reverse, 10)]
+    #[case::reverse_high_lock_time(SwapKind::Reverse, 515_924)]
+    fn test_decode_script(#[case] kind: SwapKind, #[case] lock_time: u32) {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let lock_time = LockTime::from_height(lock_time).unwrap();
+
+        let build = match kind {
+            SwapKind::Submarine => swap_script,
+            SwapKind::Reverse => reverse_script,
+        };
+        let script = build(preimage_hash(), &claim_pubkey, &refund_pubkey, lock_time);
+
+        assert_eq!(
+            decode_script(&script).unwrap(),
+            SwapParams {
+                kind,
+                preimage_hash: preimage_hash(),
+                claim_pubkey,
+                refund_pubkey,
+                lock_time: Timelock::Absolute(lock_time),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_tree_unknown_leaves() {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let tree = swap_tree(
+            preimage_hash(),
+            &claim_pubkey.x_only_public_key().0,
+            &refund_pubkey.x_only_public_key().0,
+            LockTime::from_height(123).unwrap(),
+        );
+
+        let mut swapped = tree.clone();
+        std::mem::swap(&mut swapped.claim_leaf, &mut swapped.refund_leaf);
+        assert_eq!(
+            decode_tree(&swapped).unwrap_err(),
+            DecodeError::UnknownClaimLeaf
+        );
+
+        let mut wrong_refund = tree.clone();
+        wrong_refund.refund_leaf = tree.claim_leaf.clone();
+        assert_eq!(
+            decode_tree(&wrong_refund).unwrap_err(),
+            DecodeError::UnknownRefundLeaf
+        );
+
+        let mut wrong_version = tree;
+        wrong_version.claim_leaf.version = 0xc4;
+        assert_eq!(
+            decode_tree(&wrong_version).unwrap_err(),
+            DecodeError::LeafVersion(0xc4)
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::p2wpkh("0014751e76e8199196d454941c45d1b3a323f1433bd6")]
+    #[case::trailing_opcode(
+        "a914e2ac8cb97af3d59b1c057db4b0c4f9aa12a9127387632103f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539670354df07b1752103ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a68acac"
+    )]
+    #[case::truncated(
+        "a914e2ac8cb97af3d59b1c057db4b0c4f9aa12a9127387632103f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539670354df07b1752103ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a68"
+    )]
+    fn test_decode_script_unknown(#[case] script: &str) {
+        assert_eq!(
+            decode_script(&ScriptBuf::from_hex(script).unwrap()).unwrap_err(),
+            DecodeError::UnknownScript
+        );
+    }
+}
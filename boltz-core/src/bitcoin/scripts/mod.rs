@@ -1,12 +1,18 @@
+mod decode;
 mod reverse_script;
 mod reverse_tree;
 mod swap_script;
 mod swap_tree;
+mod timelock;
 mod tree;
 mod utils;
 
+pub use decode::{
+    DecodeError, SwapParams, decode_claim_leaf, decode_refund_leaf, decode_script, decode_tree,
+};
 pub use reverse_script::reverse_script;
 pub use reverse_tree::reverse_tree;
 pub use swap_script::swap_script;
 pub use swap_tree::swap_tree;
+pub use timelock::Timelock;
 pub use tree::{Tapleaf, Tree, TreeError};
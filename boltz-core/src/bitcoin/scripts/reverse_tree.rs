@@ -1,7 +1,6 @@
-use crate::bitcoin::scripts::{Tapleaf, Tree, swap_tree::refund_leaf};
+use crate::bitcoin::scripts::{Tapleaf, Timelock, Tree, swap_tree::refund_leaf};
 use bitcoin::{
     XOnlyPublicKey,
-    absolute::LockTime,
     hashes::{Hash, hash160},
     opcodes::all::{OP_CHECKSIG, OP_EQUALVERIFY, OP_HASH160, OP_SIZE},
     script::Builder,
@@ -20,7 +19,7 @@ pub fn reverse_tree(
     preimage_hash: hash160::Hash,
     claim_pubkey: &XOnlyPublicKey,
     refund_pubkey: &XOnlyPublicKey,
-    lock_time: LockTime,
+    lock_time: impl Into<Timelock>,
 ) -> Tree {
     Tree {
         claim_leaf: Tapleaf {
@@ -36,13 +35,14 @@ pub fn reverse_tree(
                 .push_opcode(OP_CHECKSIG)
                 .into_script(),
         },
-        refund_leaf: refund_leaf(refund_pubkey, lock_time),
+        refund_leaf: refund_leaf(refund_pubkey, lock_time.into()),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::absolute::LockTime;
     use std::str::FromStr;
 
     #[test]
@@ -1,9 +1,8 @@
-use crate::bitcoin::scripts::{Tapleaf, Tree};
+use crate::bitcoin::scripts::{Tapleaf, Timelock, Tree};
 use bitcoin::{
     XOnlyPublicKey,
-    absolute::LockTime,
     hashes::{Hash, hash160},
-    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_EQUALVERIFY, OP_HASH160},
+    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_CSV, OP_EQUALVERIFY, OP_HASH160},
     script::Builder,
     taproot::TAPROOT_LEAF_TAPSCRIPT,
 };
@@ -13,13 +12,15 @@ use bitcoin::{
 /// The returned [`Tree`] has two leaves: a claim leaf gated on the
 /// preimage of `preimage_hash` plus `claim_pubkey`'s signature, and a
 /// refund leaf gated on `refund_pubkey`'s signature after `lock_time`.
-/// The cooperative key-path spend is left to the caller (typically
-/// MuSig2 over the two pubkeys).
+/// An absolute [`LockTime`](bitcoin::absolute::LockTime) builds an
+/// `OP_CLTV` refund leaf and a relative [`Sequence`](bitcoin::Sequence) an
+/// `OP_CSV` one. The cooperative key-path spend is left to the caller
+/// (typically MuSig2 over the two pubkeys).
 pub fn swap_tree(
     preimage_hash: hash160::Hash,
     claim_pubkey: &XOnlyPublicKey,
     refund_pubkey: &XOnlyPublicKey,
-    lock_time: LockTime,
+    lock_time: impl Into<Timelock>,
 ) -> Tree {
     Tree {
         claim_leaf: Tapleaf {
@@ -32,25 +33,29 @@ pub fn swap_tree(
                 .push_opcode(OP_CHECKSIG)
                 .into_script(),
         },
-        refund_leaf: refund_leaf(refund_pubkey, lock_time),
+        refund_leaf: refund_leaf(refund_pubkey, lock_time.into()),
     }
 }
 
-pub fn refund_leaf(refund_pubkey: &XOnlyPublicKey, lock_time: LockTime) -> Tapleaf {
+pub fn refund_leaf(refund_pubkey: &XOnlyPublicKey, lock_time: Timelock) -> Tapleaf {
+    let builder = Builder::new()
+        .push_x_only_key(refund_pubkey)
+        .push_opcode(OP_CHECKSIGVERIFY);
+
     Tapleaf {
         version: TAPROOT_LEAF_TAPSCRIPT,
-        output: Builder::new()
-            .push_x_only_key(refund_pubkey)
-            .push_opcode(OP_CHECKSIGVERIFY)
-            .push_lock_time(lock_time)
-            .push_opcode(OP_CLTV)
-            .into_script(),
+        output: match lock_time {
+            Timelock::Absolute(lock_time) => builder.push_lock_time(lock_time).push_opcode(OP_CLTV),
+            Timelock::Relative(sequence) => builder.push_sequence(sequence).push_opcode(OP_CSV),
+        }
+        .into_script(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::{Sequence, absolute::LockTime};
     use std::str::FromStr;
 
     #[test]
@@ -86,4 +91,25 @@ mod tests {
             "200095b23ac89f523cdbcd791d3325ada7ddb30fd6134bb7fff22ba1e00414cb7dad017bb1"
         );
     }
+
+    #[test]
+    fn test_swap_tree_relative_refund() {
+        let refund_pubkey = XOnlyPublicKey::from_str(
+            "0095b23ac89f523cdbcd791d3325ada7ddb30fd6134bb7fff22ba1e00414cb7d",
+        )
+        .unwrap();
+
+        let tree = swap_tree(
+            hash160::Hash::from_str("34e64e5b1373a872019ee2c7791cf4264d1079de").unwrap(),
+            &refund_pubkey,
+            &refund_pubkey,
+            Sequence::from_height(144),
+        );
+
+        assert_eq!(tree.refund_leaf.version, 192);
+        assert_eq!(
+            hex::encode(tree.refund_leaf.output.to_bytes()),
+            "200095b23ac89f523cdbcd791d3325ada7ddb30fd6134bb7fff22ba1e00414cb7dad029000b2"
+        );
+    }
 }
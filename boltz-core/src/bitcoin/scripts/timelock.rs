@@ -0,0 +1,38 @@
+use crate::utils::InputType;
+use bitcoin::{Sequence, absolute::LockTime};
+
+/// Timelock of the refund leaf of a swap tree.
+///
+/// Absolute lock times are enforced with `OP_CHECKLOCKTIMEVERIFY`. Relative
+/// ones are enforced with `OP_CHECKSEQUENCEVERIFY` and count from the
+/// confirmation of the swap output, which suits chained and long-running
+/// swaps whose lockup is not known when the tree is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timelock {
+    /// Block height or timestamp after which the refund leaf can be spent.
+    Absolute(LockTime),
+    /// BIP-68 relative lock time, like [`Sequence::from_height`].
+    Relative(Sequence),
+}
+
+impl Timelock {
+    /// The [`InputType`] that refunds via a leaf with this timelock.
+    pub fn refund_input_type(&self) -> InputType {
+        match self {
+            Timelock::Absolute(lock_time) => InputType::Refund(lock_time.to_consensus_u32()),
+            Timelock::Relative(sequence) => InputType::RelativeRefund(sequence.to_consensus_u32()),
+        }
+    }
+}
+
+impl From<LockTime> for Timelock {
+    fn from(lock_time: LockTime) -> Self {
+        Timelock::Absolute(lock_time)
+    }
+}
+
+impl From<Sequence> for Timelock {
+    fn from(sequence: Sequence) -> Self {
+        Timelock::Relative(sequence)
+    }
+}
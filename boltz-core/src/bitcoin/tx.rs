@@ -1,7 +1,8 @@
 use crate::{
-    bitcoin::{InputDetail, scripts::TreeError},
-    consts::{ECDSA_BYTES_TO_GRIND, PREIMAGE_DUMMY, STUB_SCHNORR_SIGNATURE_LENGTH},
-    target_fee::{FeeError, FeeTarget, target_fee},
+    bitcoin::{InputDetail, Tapleaf, Tree, UncooperativeDetails, scripts::TreeError},
+    consts::{PREIMAGE_DUMMY, STUB_SCHNORR_SIGNATURE_LENGTH, STUB_SECRET_KEY},
+    signer::{Signer, SignerError, resolve_local},
+    target_fee::{FeeBoundsError, FeeError, FeeTarget, target_fee},
     utils::{Destination, InputType, OutputType},
 };
 use bitcoin::{
@@ -14,14 +15,15 @@ use bitcoin::{
     script::{Builder, PushBytesBuf, PushBytesError},
     secp256k1::{Message, Secp256k1, Signing, Verification},
     sighash::{Prevouts, SighashCache},
-    taproot::{self, LeafVersion},
+    taproot::{self, ControlBlock, LeafVersion, TaprootSpendInfo},
     transaction::{InputsIndexError, Transaction, Version},
 };
 
 const SIGHASH_TYPE_LEGACY: EcdsaSighashType = EcdsaSighashType::All;
 const SIGHASH_TYPE_TAPROOT: TapSighashType = TapSighashType::Default;
 
-/// Errors returned by Bitcoin [`construct_tx`].
+/// Errors returned by Bitcoin [`construct_tx`], [`construct_psbt`](crate::bitcoin::construct_psbt)
+/// and [`finalize_psbt`](crate::bitcoin::finalize_psbt).
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 #[non_exhaustive]
 pub enum TxError {
@@ -34,12 +36,34 @@ pub enum TxError {
     /// The relative fee rate is non-finite or negative.
     #[error("invalid fee rate")]
     InvalidFeeRate,
+    /// The fee of a [`FeeTarget::Bounded`] is outside of its bounds.
+    #[error(transparent)]
+    FeeBounds(#[from] FeeBoundsError),
     /// The Taproot builder could not finalize the script tree for the named input.
     #[error("could not finalize taproot builder for input {0}")]
     TaprootFinalize(usize),
     /// No control block was found for the named input's spend leaf.
     #[error("could not create control block for input {0}")]
     ControlBlock(usize),
+    /// The named wallet input is neither P2WPKH nor a P2TR key-path spend.
+    #[error("input {0} is not a supported wallet input")]
+    InvalidWalletInput(usize),
+    /// The named relative refund input is not a Taproot output or its lock
+    /// time is not a BIP-68 relative lock time.
+    #[error("input {0} cannot be refunded with a relative lock time")]
+    InvalidRelativeRefund(usize),
+    /// The named input cannot be spent with a PSBT (legacy inputs need their full previous transaction).
+    #[error("input {0} is not supported in PSBTs")]
+    UnsupportedPsbtInput(usize),
+    /// The named PSBT input has no signature to finalize it with.
+    #[error("input {0} is missing a signature")]
+    MissingSignature(usize),
+    /// The named PSBT input has neither a tapscript nor a witness script to finalize it with.
+    #[error("input {0} has no script to spend it")]
+    MissingSpendInfo(usize),
+    /// The [`Signer`] of the named input failed to sign it.
+    #[error("could not sign input {0}")]
+    Signer(usize, #[source] SignerError),
     /// A swap [`Tree`](crate::bitcoin::Tree) operation failed.
     #[error(transparent)]
     Tree(#[from] TreeError),
@@ -49,6 +73,9 @@ pub enum TxError {
     /// Failed to compute a Taproot sighash.
     #[error(transparent)]
     Taproot(#[from] bitcoin::sighash::TaprootError),
+    /// Failed to compute the sighash of a P2WPKH wallet input.
+    #[error(transparent)]
+    P2wpkh(#[from] bitcoin::sighash::P2wpkhError),
     /// An input index was out of range while computing a sighash.
     #[error(transparent)]
     InputsIndex(#[from] InputsIndexError),
@@ -64,6 +91,7 @@ impl From<FeeError> for TxError {
     fn from(e: FeeError) -> Self {
         match e {
             FeeError::InvalidFeeRate => TxError::InvalidFeeRate,
+            FeeError::Bounds(err) => TxError::FeeBounds(err),
         }
     }
 }
@@ -73,15 +101,37 @@ impl From<FeeError> for TxError {
 ///
 /// For [`FeeTarget::Relative`] the transaction is constructed twice:
 /// once with a stub fee to measure its virtual size, then again with
-/// the resulting absolute fee. Returns the signed transaction and the
-/// fee that was actually charged, in satoshis.
+/// the resulting absolute fee. The size is measured with stub keys, so the
+/// [`Signer`] of every input is only asked to sign the final transaction.
+/// Returns the signed transaction and the fee that was actually charged,
+/// in satoshis.
 #[must_use = "ignoring the result discards the constructed transaction"]
-pub fn construct_tx<C: Signing + Verification>(
+pub async fn construct_tx<C: Signing + Verification, S: Signer>(
     secp: &Secp256k1<C>,
-    mut inputs: Vec<InputDetail>,
-    destination: &Destination<&Address>,
+    mut inputs: Vec<InputDetail<S>>,
+    destination: &Destination<'_, &Address>,
     fee: FeeTarget,
 ) -> Result<(Transaction, u64), TxError> {
+    sort_inputs(&mut inputs);
+    let stub_inputs = stub_inputs(secp, &inputs)?;
+
+    let (tx, fee) = target_fee(fee, input_value(&inputs), |fee, is_fee_estimation| {
+        if is_fee_estimation {
+            construct_raw(secp, &stub_inputs, destination, Amount::from_sat(fee))
+        } else {
+            unsigned_tx(&inputs, destination, Amount::from_sat(fee))
+        }
+    })?;
+
+    Ok((sign_inputs(secp, tx, &inputs).await?, fee))
+}
+
+/// Sum of the values of `inputs`, in satoshis
+pub(super) fn input_value<S>(inputs: &[InputDetail<S>]) -> u64 {
+    inputs.iter().map(|input| input.tx_out.value.to_sat()).sum()
+}
+
+pub(super) fn sort_inputs<S>(inputs: &mut [InputDetail<S>]) {
     // BIP69: sort inputs by (outpoint.txid asc, outpoint.vout asc).
     inputs.sort_by(|a, b| {
         bip69_txid_cmp(
@@ -90,10 +140,6 @@ pub fn construct_tx<C: Signing + Verification>(
         )
         .then(a.outpoint.vout.cmp(&b.outpoint.vout))
     });
-
-    target_fee(fee, |fee, _is_fee_estimation| {
-        construct_raw(secp, &inputs, destination, Amount::from_sat(fee))
-    })
 }
 
 fn bip69_txid_cmp(a: &[u8; 32], b: &[u8; 32]) -> std::cmp::Ordering {
@@ -101,94 +147,55 @@ fn bip69_txid_cmp(a: &[u8; 32], b: &[u8; 32]) -> std::cmp::Ordering {
     a.iter().rev().cmp(b.iter().rev())
 }
 
+/// The inputs with their signers replaced by a stub key. Signing with it
+/// yields a transaction of the same size, which is all the fee estimation needs
+fn stub_inputs<C: Signing, S>(
+    secp: &Secp256k1<C>,
+    inputs: &[InputDetail<S>],
+) -> Result<Vec<InputDetail>, TxError> {
+    let stub_keys = Keypair::from_seckey_slice(secp, &STUB_SECRET_KEY)?;
+
+    Ok(inputs
+        .iter()
+        .map(|input| InputDetail {
+            input_type: input.input_type,
+            output_type: input.output_type.clone(),
+            outpoint: input.outpoint,
+            tx_out: input.tx_out.clone(),
+            keys: stub_keys,
+        })
+        .collect())
+}
+
 /// Inputs are expected to be already BIP69-sorted.
 #[must_use = "ignoring the result discards the constructed transaction"]
-fn construct_raw<C: Signing + Verification>(
+pub(super) fn construct_raw<C: Verification>(
     secp: &Secp256k1<C>,
     inputs: &[InputDetail],
     destination: &Destination<&Address>,
     fee: Amount,
 ) -> Result<Transaction, TxError> {
-    let input_sum = inputs
-        .iter()
-        .map(|input| input.tx_out.value)
-        .sum::<Amount>();
-
-    let output = match destination {
-        Destination::Single(address) => vec![TxOut {
-            value: input_sum
-                .checked_sub(fee)
-                .ok_or(TxError::FeeExceedsInputs)?,
-            script_pubkey: address.script_pubkey(),
-        }],
-        Destination::Multiple(outputs) => {
-            let output_sum = outputs
-                .outputs
-                .iter()
-                .map(|output| Amount::from_sat(output.1))
-                .sum::<Amount>();
-
-            if output_sum + fee > input_sum {
-                return Err(TxError::OutputsExceedInputs);
-            }
-
-            let mut res = Vec::with_capacity(outputs.outputs.len() + 1);
-
-            for (address, amount) in outputs.outputs {
-                res.push(TxOut {
-                    value: Amount::from_sat(*amount),
-                    script_pubkey: address.script_pubkey(),
-                });
-            }
-
-            res.push(TxOut {
-                value: input_sum - fee - output_sum,
-                script_pubkey: outputs.change.script_pubkey(),
-            });
-
-            // BIP69: sort outputs by (value asc, scriptPubKey bytes asc).
-            res.sort_by(|a, b| {
-                a.value
-                    .cmp(&b.value)
-                    .then(a.script_pubkey.as_bytes().cmp(b.script_pubkey.as_bytes()))
-            });
-
-            res
-        }
-    };
-
-    let mut tx = Transaction {
-        version: Version::TWO,
-        lock_time: if let Some(lock_time) = inputs
-            .iter()
-            .filter_map(|input| match input.input_type {
-                InputType::Refund(lock_time) => Some(lock_time),
-                _ => None,
-            })
-            .max()
-        {
-            LockTime::from_height(lock_time)?
-        } else {
-            LockTime::ZERO
-        },
-        input: inputs
-            .iter()
-            .map(|input| TxIn {
-                previous_output: input.outpoint,
-                script_sig: ScriptBuf::new(),
-                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
-                witness: Witness::new(),
-            })
-            .collect(),
-        output,
-    };
+    let tx = unsigned_tx(inputs, destination, fee)?;
+    resolve_local(sign_inputs(secp, tx, inputs))
+}
 
+/// Adds the signatures and scripts that spend `inputs` to the unsigned `tx`
+async fn sign_inputs<C: Verification, S: Signer>(
+    secp: &Secp256k1<C>,
+    mut tx: Transaction,
+    inputs: &[InputDetail<S>],
+) -> Result<Transaction, TxError> {
     let prevouts: Vec<&TxOut> = inputs.iter().map(|input| &input.tx_out).collect();
     let prevouts = Prevouts::All(&prevouts);
 
     let mut sighash_cache = SighashCache::new(tx.clone());
 
     for ((i, input), tx_in) in inputs.iter().enumerate().zip(tx.input.iter_mut()) {
+        if input.input_type == InputType::Wallet {
+            tx_in.witness = wallet_witness(&mut sighash_cache, &prevouts, i, input).await?;
+            continue;
+        }
+
         match &input.output_type {
             OutputType::Legacy(witness_script) => {
                 let sighash = sighash_cache.legacy_signature_hash(
@@ -199,7 +206,9 @@ fn construct_raw<C: Signing + Verification>(
 
                 let mut script_sig = ScriptBuf::new();
                 script_sig.push_slice(
-                    legacy_signature(secp, &sighash.as_raw_hash(), &input.keys)?.serialize(),
+                    legacy_signature(i, &input.keys, sighash.as_raw_hash())
+                        .await?
+                        .serialize(),
                 );
 
                 match input.input_type {
@@ -209,6 +218,10 @@ fn construct_raw<C: Signing + Verification>(
                     InputType::Refund(_) => {
                         script_sig.push_slice(PREIMAGE_DUMMY);
                     }
+                    InputType::RelativeRefund(_) => {
+                        unreachable!("relative refunds are only possible via a swap tree")
+                    }
+                    InputType::Wallet => unreachable!("wallet inputs are signed separately"),
                 };
 
                 script_sig.push_slice(<&bitcoin::script::PushBytes>::try_from(
@@ -218,12 +231,7 @@ fn construct_raw<C: Signing + Verification>(
                 tx_in.script_sig = script_sig;
             }
             OutputType::Compatibility(witness_script) => {
-                let nested = Builder::new()
-                    .push_opcode(OP_0)
-                    .push_slice(sha256::Hash::hash(witness_script.as_bytes()).as_byte_array());
-                let nested = PushBytesBuf::try_from(nested.into_bytes())?;
-
-                tx_in.script_sig = Builder::new().push_slice(nested).into_script();
+                tx_in.script_sig = nested_script_sig(witness_script)?;
             }
             _ => {}
         };
@@ -233,12 +241,7 @@ fn construct_raw<C: Signing + Verification>(
                 tx_in.witness = stubbed_cooperative_witness();
             }
             OutputType::Taproot(Some(uncooperative)) => {
-                let leaf = if let InputType::Claim(_) = input.input_type {
-                    &uncooperative.tree.claim_leaf
-                } else {
-                    &uncooperative.tree.refund_leaf
-                };
-
+                let leaf = spend_leaf(input.input_type, &uncooperative.tree);
                 let leaf_hash = leaf.leaf_hash()?;
 
                 let sighash = sighash_cache.taproot_script_spend_signature_hash(
@@ -250,22 +253,14 @@ fn construct_raw<C: Signing + Verification>(
 
                 let sig = taproot::Signature {
                     sighash_type: SIGHASH_TYPE_TAPROOT,
-                    signature: secp.sign_schnorr(
-                        &Message::from_digest_slice(sighash.as_raw_hash().as_byte_array())?,
-                        &input.keys,
-                    ),
+                    signature: input
+                        .keys
+                        .sign_schnorr(&Message::from_digest(sighash.to_byte_array()))
+                        .await
+                        .map_err(|err| TxError::Signer(i, err))?,
                 };
 
-                let control_block = uncooperative
-                    .tree
-                    .build()?
-                    .finalize(secp, uncooperative.internal_key)
-                    .map_err(|_| TxError::TaprootFinalize(i))?
-                    .control_block(&(
-                        leaf.output.clone(),
-                        LeafVersion::from_consensus(leaf.version).map_err(TreeError::Taproot)?,
-                    ))
-                    .ok_or(TxError::ControlBlock(i))?;
+                let (control_block, _) = control_block(secp, i, uncooperative, leaf)?;
 
                 let mut witness = Witness::new();
 
@@ -289,8 +284,11 @@ fn construct_raw<C: Signing + Verification>(
                 )?;
 
                 let mut witness = Witness::new();
-                witness
-                    .push(legacy_signature(secp, &sighash.as_raw_hash(), &input.keys)?.serialize());
+                witness.push(
+                    legacy_signature(i, &input.keys, sighash.as_raw_hash())
+                        .await?
+                        .serialize(),
+                );
 
                 match input.input_type {
                     InputType::Claim(preimage) => {
@@ -299,6 +297,10 @@ fn construct_raw<C: Signing + Verification>(
                     InputType::Refund(_) => {
                         witness.push(PREIMAGE_DUMMY);
                     }
+                    InputType::RelativeRefund(_) => {
+                        unreachable!("relative refunds are only possible via a swap tree")
+                    }
+                    InputType::Wallet => unreachable!("wallet inputs are signed separately"),
                 };
 
                 witness.push(witness_script.as_bytes());
@@ -312,6 +314,222 @@ fn construct_raw<C: Signing + Verification>(
     Ok(tx)
 }
 
+/// Transaction with the inputs and outputs of a claim or refund, but without
+/// any signatures or scripts to spend the inputs
+pub(super) fn unsigned_tx<S>(
+    inputs: &[InputDetail<S>],
+    destination: &Destination<&Address>,
+    fee: Amount,
+) -> Result<Transaction, TxError> {
+    for (i, input) in inputs.iter().enumerate() {
+        check_wallet_input(i, input)?;
+        check_relative_refund(i, input)?;
+    }
+
+    let input_sum = inputs
+        .iter()
+        .map(|input| input.tx_out.value)
+        .sum::<Amount>();
+
+    let output = match destination {
+        Destination::Single(address) => vec![TxOut {
+            value: input_sum
+                .checked_sub(fee)
+                .ok_or(TxError::FeeExceedsInputs)?,
+            script_pubkey: address.script_pubkey(),
+        }],
+        Destination::Multiple(outputs) => {
+            let output_sum = outputs
+                .outputs
+                .iter()
+                .map(|output| Amount::from_sat(output.1))
+                .sum::<Amount>();
+
+            if output_sum + fee > input_sum {
+                return Err(TxError::OutputsExceedInputs);
+            }
+
+            let mut res = Vec::with_capacity(outputs.outputs.len() + 1);
+
+            for (address, amount) in outputs.outputs {
+                res.push(TxOut {
+                    value: Amount::from_sat(*amount),
+                    script_pubkey: address.script_pubkey(),
+                });
+            }
+
+            res.push(TxOut {
+                value: input_sum - fee - output_sum,
+                script_pubkey: outputs.change.script_pubkey(),
+            });
+
+            // BIP69: sort outputs by (value asc, scriptPubKey bytes asc).
+            res.sort_by(|a, b| {
+                a.value
+                    .cmp(&b.value)
+                    .then(a.script_pubkey.as_bytes().cmp(b.script_pubkey.as_bytes()))
+            });
+
+            res
+        }
+    };
+
+    Ok(Transaction {
+        version: Version::TWO,
+        lock_time: if let Some(lock_time) = inputs
+            .iter()
+            .filter_map(|input| match input.input_type {
+                InputType::Refund(lock_time) => Some(lock_time),
+                _ => None,
+            })
+            .max()
+        {
+            LockTime::from_height(lock_time)?
+        } else {
+            LockTime::ZERO
+        },
+        input: inputs
+            .iter()
+            .map(|input| TxIn {
+                previous_output: input.outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: match input.input_type {
+                    InputType::RelativeRefund(sequence) => Sequence::from_consensus(sequence),
+                    _ => Sequence::ENABLE_RBF_NO_LOCKTIME,
+                },
+                witness: Witness::new(),
+            })
+            .collect(),
+        output,
+    })
+}
+
+/// The leaf of the swap tree that is used to spend an input via the script-path
+pub(super) fn spend_leaf(input_type: InputType, tree: &Tree) -> &Tapleaf {
+    match input_type {
+        InputType::Claim(_) => &tree.claim_leaf,
+        InputType::Refund(_) | InputType::RelativeRefund(_) => &tree.refund_leaf,
+        InputType::Wallet => unreachable!("wallet inputs are not spent via a swap tree"),
+    }
+}
+
+/// Relative refunds have to spend a Taproot output with a BIP-68 relative lock time
+pub(super) fn check_relative_refund<S>(
+    index: usize,
+    input: &InputDetail<S>,
+) -> Result<(), TxError> {
+    let InputType::RelativeRefund(sequence) = input.input_type else {
+        return Ok(());
+    };
+
+    if matches!(input.output_type, OutputType::Taproot(_))
+        && Sequence::from_consensus(sequence).is_relative_lock_time()
+    {
+        Ok(())
+    } else {
+        Err(TxError::InvalidRelativeRefund(index))
+    }
+}
+
+/// Wallet inputs have to be P2WPKH with their `scriptPubKey` as payload or P2TR key-path spends
+pub(super) fn check_wallet_input<S>(index: usize, input: &InputDetail<S>) -> Result<(), TxError> {
+    if input.input_type != InputType::Wallet {
+        return Ok(());
+    }
+
+    let is_valid = match &input.output_type {
+        OutputType::SegwitV0(script_pubkey) => {
+            script_pubkey.is_p2wpkh() && *script_pubkey == input.tx_out.script_pubkey
+        }
+        OutputType::Taproot(None) => input.tx_out.script_pubkey.is_p2tr(),
+        _ => false,
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(TxError::InvalidWalletInput(index))
+    }
+}
+
+async fn wallet_witness<S: Signer>(
+    sighash_cache: &mut SighashCache<Transaction>,
+    prevouts: &Prevouts<'_, &TxOut>,
+    index: usize,
+    input: &InputDetail<S>,
+) -> Result<Witness, TxError> {
+    match &input.output_type {
+        OutputType::SegwitV0(script_pubkey) => {
+            let sighash = sighash_cache.p2wpkh_signature_hash(
+                index,
+                script_pubkey,
+                input.tx_out.value,
+                SIGHASH_TYPE_LEGACY,
+            )?;
+
+            Ok(Witness::p2wpkh(
+                &legacy_signature(index, &input.keys, sighash.as_raw_hash()).await?,
+                &input.keys.public_key(),
+            ))
+        }
+        OutputType::Taproot(None) => {
+            let sighash = sighash_cache.taproot_key_spend_signature_hash(
+                index,
+                prevouts,
+                SIGHASH_TYPE_TAPROOT,
+            )?;
+
+            Ok(Witness::p2tr_key_spend(&taproot::Signature {
+                sighash_type: SIGHASH_TYPE_TAPROOT,
+                signature: input
+                    .keys
+                    .sign_schnorr(&Message::from_digest(sighash.to_byte_array()))
+                    .await
+                    .map_err(|err| TxError::Signer(index, err))?,
+            }))
+        }
+        _ => Err(TxError::InvalidWalletInput(index)),
+    }
+}
+
+pub(super) fn control_block<C: Verification>(
+    secp: &Secp256k1<C>,
+    index: usize,
+    uncooperative: &UncooperativeDetails,
+    leaf: &Tapleaf,
+) -> Result<(ControlBlock, TaprootSpendInfo), TxError> {
+    let spend_info = uncooperative
+        .tree
+        .build()?
+        .finalize(secp, uncooperative.internal_key)
+        .map_err(|_| TxError::TaprootFinalize(index))?;
+
+    let control_block = spend_info
+        .control_block(&(
+            leaf.output.clone(),
+            LeafVersion::from_consensus(leaf.version).map_err(TreeError::Taproot)?,
+        ))
+        .ok_or(TxError::ControlBlock(index))?;
+
+    Ok((control_block, spend_info))
+}
+
+/// The P2SH script sig of a nested segwit input
+pub(super) fn nested_script_sig(witness_script: &ScriptBuf) -> Result<ScriptBuf, TxError> {
+    Ok(Builder::new()
+        .push_slice(PushBytesBuf::try_from(
+            nested_redeem_script(witness_script).into_bytes(),
+        )?)
+        .into_script())
+}
+
+pub(super) fn nested_redeem_script(witness_script: &ScriptBuf) -> ScriptBuf {
+    Builder::new()
+        .push_opcode(OP_0)
+        .push_slice(sha256::Hash::hash(witness_script.as_bytes()).as_byte_array())
+        .into_script()
+}
+
 fn stubbed_cooperative_witness() -> Witness {
     let mut witness = Witness::new();
     // Stub because we don't want to create cooperative signatures here
@@ -320,18 +538,17 @@ fn stubbed_cooperative_witness() -> Witness {
     witness
 }
 
-fn legacy_signature<C: Signing>(
-    secp: &Secp256k1<C>,
-    sighash: &&sha256d::Hash,
-    keys: &Keypair,
+async fn legacy_signature<S: Signer>(
+    index: usize,
+    signer: &S,
+    sighash: &sha256d::Hash,
 ) -> Result<ecdsa::Signature, TxError> {
     Ok(ecdsa::Signature {
         sighash_type: SIGHASH_TYPE_LEGACY,
-        signature: secp.sign_ecdsa_grind_r(
-            &Message::from_digest_slice(sighash.as_byte_array())?,
-            &keys.secret_key(),
-            ECDSA_BYTES_TO_GRIND,
-        ),
+        signature: signer
+            .sign_ecdsa(&Message::from_digest(sighash.to_byte_array()))
+            .await
+            .map_err(|err| TxError::Signer(index, err))?,
     })
 }
 
@@ -344,16 +561,18 @@ mod tests {
         },
         client::{RpcClient, RpcParam},
         detect_preimage,
+        target_fee::FeeBounds,
         utils::Outputs,
     };
     use bitcoin::{
-        OutPoint, Txid, XOnlyPublicKey,
+        CompressedPublicKey, OutPoint, Txid, XOnlyPublicKey,
         hashes::hash160,
-        key::{Keypair, rand::RngCore},
+        key::{Keypair, TapTweak, rand::RngCore},
         secp256k1::{PublicKey, Signing, Verification, rand},
         taproot::TaprootSpendInfo,
     };
     use elements::pset::serialize::Serialize;
+    use futures::executor::block_on;
     use rstest::rstest;
     use serial_test::serial;
     use std::str::FromStr;
@@ -509,6 +728,43 @@ mod tests {
         }
     }
 
+    fn fund_wallet<C: Signing + Verification>(
+        secp: &Secp256k1<C>,
+        node: &RpcClient,
+        taproot: bool,
+    ) -> InputDetail {
+        let keys = Keypair::new(secp, &mut rand::thread_rng());
+
+        let (keys, output_type, address) = if taproot {
+            let tweaked = keys.tap_tweak(secp, None).to_keypair();
+            (
+                tweaked,
+                OutputType::Taproot(None),
+                Address::p2tr(
+                    secp,
+                    keys.x_only_public_key().0,
+                    None,
+                    bitcoin::network::Network::Regtest,
+                ),
+            )
+        } else {
+            let address = Address::p2wpkh(
+                &CompressedPublicKey(keys.public_key()),
+                bitcoin::network::Network::Regtest,
+            );
+            (keys, OutputType::SegwitV0(address.script_pubkey()), address)
+        };
+        let (funding_tx, vout_index) = fund_address(node, &address);
+
+        InputDetail {
+            input_type: InputType::Wallet,
+            output_type,
+            outpoint: OutPoint::new(funding_tx.compute_txid(), vout_index as u32),
+            tx_out: funding_tx.tx_out(vout_index).unwrap().clone(),
+            keys,
+        }
+    }
+
     fn fund_compatibility<
         C: Signing + Verification,
         T: FnOnce(hash160::Hash, &PublicKey, &PublicKey, LockTime) -> ScriptBuf,
@@ -621,12 +877,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (mut tx, _) = construct_tx(
+        let (mut tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -688,12 +944,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -743,12 +999,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -761,6 +1017,67 @@ mod tests {
         assert_eq!(send_raw_transaction(&node, &tx), tx.compute_txid());
     }
 
+    #[rstest]
+    #[case::swap_tree(swap_tree)]
+    #[case::reverse_tree(reverse_tree)]
+    #[serial(Bitcoin)]
+    fn test_taproot_refund_relative(
+        #[case] create_tree: impl FnOnce(
+            hash160::Hash,
+            &XOnlyPublicKey,
+            &XOnlyPublicKey,
+            Sequence,
+        ) -> Tree,
+    ) {
+        let node = RpcClient::new_bitcoin_regtest();
+        let secp = Secp256k1::new();
+        let sequence = Sequence::from_height(2);
+        let (keys, tree, _, mut input) = fund_taproot(
+            &secp,
+            &node,
+            Some(0),
+            |preimage_hash, claim_pubkey, refund_pubkey, _| {
+                create_tree(preimage_hash, claim_pubkey, refund_pubkey, sequence)
+            },
+        );
+
+        input.input_type = InputType::RelativeRefund(sequence.to_consensus_u32());
+        input.output_type = OutputType::Taproot(Some(UncooperativeDetails {
+            tree,
+            internal_key: keys.x_only_public_key().0,
+        }));
+
+        let destination = get_destination(&node);
+
+        let (tx, _) = block_on(construct_tx(
+            &secp,
+            vec![input],
+            &Destination::Single(&destination),
+            FeeTarget::Absolute(1_000),
+        ))
+        .unwrap();
+
+        assert_eq!(tx.input[0].sequence, sequence);
+        assert_eq!(tx.lock_time, LockTime::ZERO);
+
+        // Not final until the lockup has enough confirmations
+        assert!(
+            node.request::<String>(
+                "sendrawtransaction",
+                Some(&[RpcParam::Str(&hex::encode(tx.serialize()))]),
+            )
+            .is_err()
+        );
+
+        node.request::<serde_json::Value>(
+            "generatetoaddress",
+            Some(&[RpcParam::Int(2), RpcParam::Str(&destination.to_string())]),
+        )
+        .unwrap();
+
+        assert_eq!(send_raw_transaction(&node, &tx), tx.compute_txid());
+    }
+
     #[rstest]
     #[case::swap_tree(swap_tree)]
     #[case::reverse_tree(reverse_tree)]
@@ -780,12 +1097,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee_rate = 3.0;
-        let (mut tx, fee) = construct_tx(
+        let (mut tx, fee) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Relative(fee_rate),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -848,12 +1165,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee_rate = 3.0;
-        let (tx, fee) = construct_tx(
+        let (tx, fee) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Relative(fee_rate),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -892,12 +1209,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -936,12 +1253,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -968,12 +1285,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -1012,12 +1329,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -1044,12 +1361,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -1088,12 +1405,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee = 1_000;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -1138,12 +1455,12 @@ mod tests {
         let destination = get_destination(&node);
 
         let fee_rate = 4.0;
-        let (tx, fee) = construct_tx(
+        let (tx, fee) = block_on(construct_tx(
             &secp,
             inputs.clone(),
             &Destination::Single(&destination),
             FeeTarget::Relative(fee_rate),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), 1);
@@ -1179,7 +1496,7 @@ mod tests {
         ];
 
         let fee = 500;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             inputs.clone(),
             &Destination::Multiple(Outputs {
@@ -1187,7 +1504,7 @@ mod tests {
                 outputs: &outputs,
             }),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), outputs.len() + 1);
@@ -1211,6 +1528,48 @@ mod tests {
         assert_eq!(send_raw_transaction(&node, &tx), tx.compute_txid());
     }
 
+    #[test]
+    #[serial(Bitcoin)]
+    fn test_wallet_inputs() {
+        let node = RpcClient::new_bitcoin_regtest();
+        let secp = Secp256k1::new();
+
+        let inputs = vec![
+            fund_segwit_v0(&secp, &node, None, reverse_script),
+            fund_wallet(&secp, &node, false),
+            fund_wallet(&secp, &node, true),
+        ];
+
+        let change = get_destination(&node);
+        let outputs = [(&get_destination(&node), 250_000)];
+
+        let (tx, fee) = block_on(construct_tx(
+            &secp,
+            inputs.clone(),
+            &Destination::Multiple(Outputs {
+                change: &change,
+                outputs: &outputs,
+            }),
+            FeeTarget::Relative(2.0),
+        ))
+        .unwrap();
+
+        assert_eq!(tx.input.len(), inputs.len());
+        assert_eq!(tx.output.len(), 2);
+
+        let change_output = tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == change.script_pubkey())
+            .unwrap();
+        assert_eq!(
+            change_output.value,
+            Amount::from_sat(FUNDING_AMOUNT * inputs.len() as u64 - outputs[0].1 - fee)
+        );
+
+        assert_eq!(send_raw_transaction(&node, &tx), tx.compute_txid());
+    }
+
     #[test]
     fn test_bip69_input_sort() {
         let secp = Secp256k1::new();
@@ -1320,12 +1679,12 @@ mod tests {
             .map(|(txid, vout)| make_input(Txid::from_str(txid).unwrap(), *vout))
             .collect();
 
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             inputs,
             &Destination::Single(&destination),
             FeeTarget::Absolute(1_000),
-        )
+        ))
         .unwrap();
 
         let actual_order: Vec<_> = tx.input.iter().map(|i| i.previous_output).collect();
@@ -1336,12 +1695,12 @@ mod tests {
             make_input(bip69_tie_breaker_txid, 0),
         ];
 
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             tie_breaker_inputs,
             &Destination::Single(&destination),
             FeeTarget::Absolute(1_000),
-        )
+        ))
         .unwrap();
 
         let expected_order = [
@@ -1379,7 +1738,7 @@ mod tests {
 
         let fee = 1_000u64;
         let outputs = [(&dest_a, 100_000u64), (&dest_a, 50_000u64)];
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             vec![input.clone()],
             &Destination::Multiple(crate::utils::Outputs {
@@ -1387,7 +1746,7 @@ mod tests {
                 outputs: &outputs,
             }),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let expected_values = [50_000u64, 100_000u64, 1_000_000 - 100_000 - 50_000 - fee];
@@ -1477,4 +1836,373 @@ mod tests {
 
         assert_eq!(result.unwrap_err(), TxError::OutputsExceedInputs);
     }
+
+    /// Signer that suspends before it signs, like a remote signer would
+    struct RemoteSigner {
+        keys: Keypair,
+        fail: bool,
+    }
+
+    impl RemoteSigner {
+        async fn roundtrip(&self) -> Result<(), SignerError> {
+            let mut suspended = false;
+            std::future::poll_fn(|cx| {
+                if suspended {
+                    return std::task::Poll::Ready(());
+                }
+
+                suspended = true;
+                cx.waker().wake_by_ref();
+                std::task::Poll::Pending
+            })
+            .await;
+
+            if self.fail {
+                return Err(SignerError("signer unavailable".to_string()));
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Signer for RemoteSigner {
+        fn public_key(&self) -> PublicKey {
+            self.keys.public_key()
+        }
+
+        async fn sign_schnorr(
+            &self,
+            msg: &Message,
+        ) -> Result<bitcoin::secp256k1::schnorr::Signature, SignerError> {
+            self.roundtrip().await?;
+            Signer::sign_schnorr(&self.keys, msg).await
+        }
+
+        async fn sign_ecdsa(
+            &self,
+            msg: &Message,
+        ) -> Result<bitcoin::secp256k1::ecdsa::Signature, SignerError> {
+            self.roundtrip().await?;
+            Signer::sign_ecdsa(&self.keys, msg).await
+        }
+    }
+
+    fn segwit_claim_input<S>(keys: S, claim_public_key: &PublicKey) -> InputDetail<S> {
+        let preimage = [1; 32];
+        let refund_keys = Keypair::from_seckey_slice(&Secp256k1::new(), &[2; 32]).unwrap();
+        let script = swap_script(
+            hash160::Hash::hash(&preimage),
+            claim_public_key,
+            &refund_keys.public_key(),
+            LockTime::from_height(1_000).unwrap(),
+        );
+
+        InputDetail {
+            input_type: InputType::Claim(preimage),
+            tx_out: TxOut {
+                value: Amount::from_sat(FUNDING_AMOUNT),
+                script_pubkey: ScriptBuf::new_p2wsh(&script.wscript_hash()),
+            },
+            output_type: OutputType::SegwitV0(script),
+            outpoint: OutPoint::new(Txid::all_zeros(), 1),
+            keys,
+        }
+    }
+
+    fn wallet_input(keys: Keypair, taproot: bool, vout: u32) -> InputDetail {
+        let secp = Secp256k1::new();
+        let (keys, output_type, script_pubkey) = if taproot {
+            (
+                keys.tap_tweak(&secp, None).to_keypair(),
+                OutputType::Taproot(None),
+                ScriptBuf::new_p2tr(&secp, keys.x_only_public_key().0, None),
+            )
+        } else {
+            let script_pubkey =
+                ScriptBuf::new_p2wpkh(&CompressedPublicKey(keys.public_key()).wpubkey_hash());
+            (
+                keys,
+                OutputType::SegwitV0(script_pubkey.clone()),
+                script_pubkey,
+            )
+        };
+
+        InputDetail {
+            input_type: InputType::Wallet,
+            output_type,
+            outpoint: OutPoint::new(Txid::all_zeros(), vout),
+            tx_out: TxOut {
+                value: Amount::from_sat(FUNDING_AMOUNT),
+                script_pubkey,
+            },
+            keys,
+        }
+    }
+
+    #[test]
+    fn test_construct_tx_wallet_inputs() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let destination =
+            Address::from_str("bcrt1p70dqezlrn37f043fmc6m45uzflaqe6678q9ludu8ryr9s5rsn0gs3qh0vd")
+                .unwrap()
+                .assume_checked();
+
+        let inputs = vec![
+            segwit_claim_input(keys, &keys.public_key()),
+            wallet_input(keys, false, 2),
+            wallet_input(keys, true, 3),
+        ];
+        let (tx, fee) = block_on(construct_tx(
+            &secp,
+            inputs.clone(),
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            fee,
+            ((tx.vsize() + tx.input.len()) as f64 * 2.0).ceil() as u64
+        );
+        assert_eq!(
+            tx.output[0].value,
+            Amount::from_sat(FUNDING_AMOUNT * inputs.len() as u64 - fee)
+        );
+
+        let prevouts = inputs
+            .iter()
+            .map(|input| input.tx_out.clone())
+            .collect::<Vec<_>>();
+        let mut sighash_cache = SighashCache::new(&tx);
+
+        let witness = tx.input[1].witness.to_vec();
+        assert_eq!(witness[1], keys.public_key().serialize());
+        let sighash = sighash_cache
+            .p2wpkh_signature_hash(
+                1,
+                &inputs[1].tx_out.script_pubkey,
+                inputs[1].tx_out.value,
+                SIGHASH_TYPE_LEGACY,
+            )
+            .unwrap();
+        secp.verify_ecdsa(
+            &Message::from_digest(sighash.to_byte_array()),
+            &ecdsa::Signature::from_slice(&witness[0]).unwrap().signature,
+            &keys.public_key(),
+        )
+        .unwrap();
+
+        let witness = tx.input[2].witness.to_vec();
+        assert_eq!(witness.len(), 1);
+        let sighash = sighash_cache
+            .taproot_key_spend_signature_hash(2, &Prevouts::All(&prevouts), SIGHASH_TYPE_TAPROOT)
+            .unwrap();
+        secp.verify_schnorr(
+            &taproot::Signature::from_slice(&witness[0])
+                .unwrap()
+                .signature,
+            &Message::from_digest(sighash.to_byte_array()),
+            &inputs[2].keys.x_only_public_key().0,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_construct_tx_bounded_fee() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let destination =
+            Address::from_str("bcrt1p70dqezlrn37f043fmc6m45uzflaqe6678q9ludu8ryr9s5rsn0gs3qh0vd")
+                .unwrap()
+                .assume_checked();
+        let bounds = FeeBounds {
+            fee_rate: 3.3,
+            ..Default::default()
+        };
+
+        // Schnorr signatures have a fixed size, so the fee matches the size exactly
+        let (tx, fee) = block_on(construct_tx(
+            &secp,
+            vec![wallet_input(keys, true, 0)],
+            &Destination::Single(&destination),
+            FeeTarget::Bounded(bounds),
+        ))
+        .unwrap();
+        assert_eq!(fee, (tx.vsize() as f64 * bounds.fee_rate).ceil() as u64);
+
+        let result = block_on(construct_tx(
+            &secp,
+            vec![wallet_input(keys, true, 0)],
+            &Destination::Single(&destination),
+            FeeTarget::Bounded(FeeBounds {
+                max_fee_percentage: Some(0.1),
+                ..bounds
+            }),
+        ));
+        assert_eq!(
+            result.unwrap_err(),
+            TxError::FeeBounds(FeeBoundsError::AbovePercentage {
+                fee,
+                max_fee: FUNDING_AMOUNT / 1_000,
+                input_value: FUNDING_AMOUNT,
+            })
+        );
+    }
+
+    #[test]
+    fn test_construct_tx_relative_refund() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let sequence = Sequence::from_height(144);
+
+        let uncooperative = UncooperativeDetails {
+            tree: swap_tree(
+                hash160::Hash::all_zeros(),
+                &keys.x_only_public_key().0,
+                &keys.x_only_public_key().0,
+                sequence,
+            ),
+            internal_key: keys.x_only_public_key().0,
+        };
+        let tweak = uncooperative
+            .tree
+            .build()
+            .unwrap()
+            .finalize(&secp, uncooperative.internal_key)
+            .unwrap();
+        let destination =
+            Address::p2tr_tweaked(tweak.output_key(), bitcoin::network::Network::Regtest);
+
+        let input = InputDetail {
+            input_type: InputType::RelativeRefund(sequence.to_consensus_u32()),
+            output_type: OutputType::Taproot(Some(uncooperative.clone())),
+            outpoint: OutPoint::default(),
+            tx_out: TxOut {
+                value: Amount::from_sat(FUNDING_AMOUNT),
+                script_pubkey: destination.script_pubkey(),
+            },
+            keys,
+        };
+
+        let construct = |input: InputDetail| {
+            block_on(construct_tx(
+                &secp,
+                vec![input],
+                &Destination::Single(&destination),
+                FeeTarget::Absolute(1_000),
+            ))
+        };
+
+        let (tx, _) = construct(input.clone()).unwrap();
+        assert_eq!(tx.input[0].sequence, sequence);
+        assert_eq!(tx.lock_time, LockTime::ZERO);
+        assert_eq!(
+            tx.input[0].witness.nth(1).unwrap(),
+            uncooperative.tree.refund_leaf.output.as_bytes()
+        );
+        assert_eq!(detect_preimage(&tx.input[0], &[0; 32]), None);
+
+        let disabled = InputDetail {
+            input_type: InputType::RelativeRefund(Sequence::MAX.to_consensus_u32()),
+            ..input.clone()
+        };
+        assert!(matches!(
+            construct(disabled).unwrap_err(),
+            TxError::InvalidRelativeRefund(0)
+        ));
+
+        let segwit = InputDetail {
+            output_type: OutputType::SegwitV0(ScriptBuf::new()),
+            ..input
+        };
+        assert!(matches!(
+            construct(segwit).unwrap_err(),
+            TxError::InvalidRelativeRefund(0)
+        ));
+    }
+
+    #[rstest]
+    #[case::p2wsh(OutputType::SegwitV0(ScriptBuf::new_p2wsh(&bitcoin::WScriptHash::all_zeros())))]
+    #[case::compatibility(OutputType::Compatibility(ScriptBuf::new()))]
+    #[case::legacy(OutputType::Legacy(ScriptBuf::new()))]
+    fn test_construct_tx_invalid_wallet_input(
+        #[case] output_type: OutputType<Option<UncooperativeDetails>, ScriptBuf>,
+    ) {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let destination =
+            Address::from_str("bcrt1p70dqezlrn37f043fmc6m45uzflaqe6678q9ludu8ryr9s5rsn0gs3qh0vd")
+                .unwrap()
+                .assume_checked();
+
+        let mut input = wallet_input(keys, false, 2);
+        input.output_type = output_type;
+
+        let result = block_on(construct_tx(
+            &secp,
+            vec![input],
+            &Destination::Single(&destination),
+            FeeTarget::Absolute(1_000),
+        ));
+        assert_eq!(result.unwrap_err(), TxError::InvalidWalletInput(0));
+    }
+
+    #[test]
+    fn test_construct_tx_remote_signer() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let destination =
+            Address::from_str("bcrt1p70dqezlrn37f043fmc6m45uzflaqe6678q9ludu8ryr9s5rsn0gs3qh0vd")
+                .unwrap()
+                .assume_checked();
+
+        let (expected, expected_fee) = block_on(construct_tx(
+            &secp,
+            vec![segwit_claim_input(keys, &keys.public_key())],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        ))
+        .unwrap();
+
+        let (tx, fee) = block_on(construct_tx(
+            &secp,
+            vec![segwit_claim_input(
+                RemoteSigner { keys, fail: false },
+                &keys.public_key(),
+            )],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        ))
+        .unwrap();
+
+        // ECDSA signatures are deterministic, so both signers create the same transaction
+        assert_eq!(tx, expected);
+        assert_eq!(fee, expected_fee);
+    }
+
+    #[test]
+    fn test_construct_tx_signer_error() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let destination =
+            Address::from_str("bcrt1p70dqezlrn37f043fmc6m45uzflaqe6678q9ludu8ryr9s5rsn0gs3qh0vd")
+                .unwrap()
+                .assume_checked();
+
+        let result = block_on(construct_tx(
+            &secp,
+            vec![segwit_claim_input(
+                RemoteSigner { keys, fail: true },
+                &keys.public_key(),
+            )],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(2.0),
+        ));
+
+        assert_eq!(
+            result.unwrap_err(),
+            TxError::Signer(0, SignerError("signer unavailable".to_string()))
+        );
+    }
 }
@@ -3,3 +3,6 @@ pub const ECDSA_BYTES_TO_GRIND: usize = 2;
 pub const PREIMAGE_DUMMY: [u8; 0] = [];
 
 pub const STUB_SCHNORR_SIGNATURE_LENGTH: usize = 64;
+
+/// Secret key to sign with when only the size of a signed transaction matters
+pub const STUB_SECRET_KEY: [u8; 32] = [1; 32];
@@ -0,0 +1,579 @@
+//! Cooperative spending of Taproot swap outputs via the MuSig2 key-path.
+//!
+//! Spending cooperatively is split in two steps, with the MuSig2 session of
+//! the [`musig`](crate::musig) module in between:
+//!
+//! 1. [`prepare_key_path_spend`] builds the transaction and returns, for every
+//!    Taproot input, the sighash to sign and the tweak to apply with
+//!    [`Setup::xonly_tweak_add`](crate::musig::Setup::xonly_tweak_add).
+//!    All other inputs are signed by their [`Signer`] as usual.
+//! 2. [`finalize_key_path_spend`] verifies the aggregated signatures of the
+//!    sessions and adds them to the transaction.
+
+use crate::{
+    Signer,
+    musig::{Musig, MusigError, XOnlyPublicKey},
+    utils::{InputType, OutputType},
+    wrapper::{Params, Transaction},
+};
+use bitcoin::{TapSighashType, Witness, hashes::Hash};
+use elements::SchnorrSighashType;
+use secp256k1::{Scalar, musig::AggregatedSignature};
+use std::collections::HashMap;
+
+/// Errors returned by [`prepare_key_path_spend`] and [`finalize_key_path_spend`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CooperativeError {
+    /// The named Taproot input has no swap tree to derive the key-path tweak from.
+    #[error("input {0} has no uncooperative details")]
+    MissingUncooperativeDetails(usize),
+    /// The number of aggregated signatures does not match the number of key-path inputs.
+    #[error("expected {expected} signatures but got {actual}")]
+    SignatureCount {
+        /// Number of key-path inputs of the transaction.
+        expected: usize,
+        /// Number of signatures that were supplied.
+        actual: usize,
+    },
+    /// The aggregated signature of the named input is not valid for its output key.
+    #[error("signature for input {0} is invalid")]
+    InvalidSignature(usize),
+    /// Constructing the Bitcoin transaction failed.
+    #[error(transparent)]
+    Bitcoin(#[from] crate::bitcoin::TxError),
+    /// Constructing the Elements transaction failed.
+    #[error(transparent)]
+    Elements(#[from] crate::elements::TxError),
+    /// Converting a tweak or key for the MuSig2 session failed.
+    #[error(transparent)]
+    Musig(#[from] MusigError),
+}
+
+/// A Taproot input that is spent via the key-path with an aggregated MuSig2 signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPathInput {
+    /// Index of the input in the transaction.
+    pub index: usize,
+    /// Sighash the MuSig2 session has to sign.
+    pub sighash: [u8; 32],
+    /// Taproot tweak of the aggregated key; apply it with
+    /// [`Setup::xonly_tweak_add`](crate::musig::Setup::xonly_tweak_add).
+    pub tweak: Scalar,
+    /// Tweaked output key the aggregated signature is verified against.
+    pub output_key: XOnlyPublicKey,
+}
+
+/// A transaction whose key-path inputs still await their aggregated signatures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyPathSpend {
+    /// The transaction with every input but the key-path ones signed.
+    pub transaction: Transaction,
+    /// Fee the transaction pays, in satoshis.
+    pub fee: u64,
+    /// The key-path inputs, ordered by their index in the transaction.
+    pub inputs: Vec<KeyPathInput>,
+}
+
+/// Build a transaction that spends all Taproot inputs of `params` via the
+/// key-path.
+///
+/// Taproot inputs need [`UncooperativeDetails`](crate::bitcoin::UncooperativeDetails),
+/// because the tweak is derived from their swap tree and internal key. The
+/// fee is estimated with a stub signature for each key-path input. Wallet
+/// inputs are signed by their [`Signer`] like in `construct_tx`.
+pub async fn prepare_key_path_spend<S: Signer>(
+    params: Params<'_, S>,
+) -> Result<KeyPathSpend, CooperativeError> {
+    match params {
+        Params::Bitcoin(mut params) => {
+            let secp = bitcoin::secp256k1::Secp256k1::new();
+
+            let mut tweaks = HashMap::new();
+            for (i, input) in params.inputs.iter_mut().enumerate() {
+                if let OutputType::Taproot(details) = &mut input.output_type
+                    && input.input_type != InputType::Wallet
+                {
+                    let uncooperative = details
+                        .take()
+                        .ok_or(CooperativeError::MissingUncooperativeDetails(i))?;
+                    let spend_info = uncooperative
+                        .tree
+                        .build()
+                        .map_err(crate::bitcoin::TxError::from)?
+                        .finalize(&secp, uncooperative.internal_key)
+                        .map_err(|_| crate::bitcoin::TxError::TaprootFinalize(i))?;
+
+                    tweaks.insert(
+                        input.outpoint,
+                        (
+                            spend_info.tap_tweak().to_scalar().to_be_bytes(),
+                            spend_info.output_key().to_x_only_public_key().serialize(),
+                        ),
+                    );
+                }
+            }
+
+            let prevouts = params
+                .inputs
+                .iter()
+                .map(|input| (input.outpoint, input.tx_out.clone()))
+                .collect::<HashMap<_, _>>();
+
+            let (tx, fee) =
+                crate::bitcoin::construct_tx(&secp, params.inputs, params.destination, params.fee)
+                    .await?;
+
+            let prevouts = tx
+                .input
+                .iter()
+                .map(|input| prevouts[&input.previous_output].clone())
+                .collect::<Vec<_>>();
+            let mut sighash_cache = bitcoin::sighash::SighashCache::new(&tx);
+
+            let mut inputs = Vec::with_capacity(tweaks.len());
+            for (index, tx_in) in tx.input.iter().enumerate() {
+                if let Some((tweak, output_key)) = tweaks.get(&tx_in.previous_output) {
+                    let sighash = sighash_cache
+                        .taproot_key_spend_signature_hash(
+                            index,
+                            &bitcoin::sighash::Prevouts::All(&prevouts),
+                            TapSighashType::Default,
+                        )
+                        .map_err(crate::bitcoin::TxError::from)?;
+
+                    inputs.push(key_path_input(
+                        index,
+                        sighash.to_byte_array(),
+                        tweak,
+                        output_key,
+                    )?);
+                }
+            }
+
+            Ok(KeyPathSpend {
+                transaction: Transaction::Bitcoin(tx),
+                fee,
+                inputs,
+            })
+        }
+        Params::Elements(mut params) => {
+            let secp = elements::secp256k1_zkp::Secp256k1::new();
+
+            let mut tweaks = HashMap::new();
+            for (i, input) in params.inputs.iter_mut().enumerate() {
+                if let OutputType::Taproot(details) = &mut input.output_type
+                    && input.input_type != InputType::Wallet
+                {
+                    let uncooperative = details
+                        .take()
+                        .ok_or(CooperativeError::MissingUncooperativeDetails(i))?;
+                    let spend_info = uncooperative
+                        .tree
+                        .build()
+                        .map_err(crate::elements::TxError::from)?
+                        .finalize(&secp, uncooperative.internal_key)
+                        .map_err(|_| crate::elements::TxError::TaprootFinalize(i))?;
+
+                    tweaks.insert(
+                        input.outpoint,
+                        (
+                            spend_info.tap_tweak().to_scalar().to_be_bytes(),
+                            spend_info.output_key().into_inner().serialize(),
+                        ),
+                    );
+                }
+            }
+
+            let prevouts = params
+                .inputs
+                .iter()
+                .map(|input| (input.outpoint, input.tx_out.clone()))
+                .collect::<HashMap<_, _>>();
+
+            let (tx, fee) = crate::elements::construct_tx(
+                &secp,
+                params.genesis_hash,
+                params.inputs,
+                params.destination,
+                params.fee,
+            )
+            .await?;
+
+            let prevouts = tx
+                .input
+                .iter()
+                .map(|input| prevouts[&input.previous_output].clone())
+                .collect::<Vec<_>>();
+            let mut sighash_cache = elements::sighash::SighashCache::new(&tx);
+
+            let mut inputs = Vec::with_capacity(tweaks.len());
+            for (index, tx_in) in tx.input.iter().enumerate() {
+                if let Some((tweak, output_key)) = tweaks.get(&tx_in.previous_output) {
+                    let sighash = sighash_cache
+                        .taproot_key_spend_signature_hash(
+                            index,
+                            &elements::sighash::Prevouts::All(&prevouts),
+                            SchnorrSighashType::Default,
+                            params.genesis_hash,
+                        )
+                        .map_err(crate::elements::TxError::from)?;
+
+                    inputs.push(key_path_input(
+                        index,
+                        sighash.to_byte_array(),
+                        tweak,
+                        output_key,
+                    )?);
+                }
+            }
+
+            Ok(KeyPathSpend {
+                transaction: Transaction::Elements(tx),
+                fee,
+                inputs,
+            })
+        }
+    }
+}
+
+/// Add the aggregated `signatures` of the key-path inputs of `spend`, in the
+/// order of [`KeyPathSpend::inputs`], and return the finalized transaction.
+///
+/// Every signature is verified against the output key of its input first.
+pub fn finalize_key_path_spend(
+    spend: KeyPathSpend,
+    signatures: &[AggregatedSignature],
+) -> Result<Transaction, CooperativeError> {
+    if signatures.len() != spend.inputs.len() {
+        return Err(CooperativeError::SignatureCount {
+            expected: spend.inputs.len(),
+            actual: signatures.len(),
+        });
+    }
+
+    let mut transaction = spend.transaction;
+
+    for (input, signature) in spend.inputs.iter().zip(signatures) {
+        // Signatures with the default sighash type are not suffixed with it
+        let signature = signature
+            .verify(&input.output_key, &input.sighash)
+            .map_err(|_| CooperativeError::InvalidSignature(input.index))?
+            .to_byte_array();
+
+        match &mut transaction {
+            Transaction::Bitcoin(tx) => {
+                tx.input[input.index].witness = Witness::from_slice(&[signature]);
+            }
+            Transaction::Elements(tx) => {
+                tx.input[input.index].witness.script_witness = vec![signature.to_vec()];
+            }
+        }
+    }
+
+    Ok(transaction)
+}
+
+fn key_path_input(
+    index: usize,
+    sighash: [u8; 32],
+    tweak: &[u8; 32],
+    output_key: &[u8; 32],
+) -> Result<KeyPathInput, CooperativeError> {
+    Ok(KeyPathInput {
+        index,
+        sighash,
+        tweak: Musig::convert_scalar_be(tweak)?,
+        output_key: XOnlyPublicKey::from_byte_array(*output_key).map_err(MusigError::from)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Destination, FeeTarget,
+        utils::InputType,
+        wrapper::{BitcoinInputDetail, BitcoinParams, ElementsInputDetail, ElementsParams},
+    };
+    use bitcoin::{
+        Amount, OutPoint, ScriptBuf, TxOut, Txid, absolute::LockTime, hashes::hash160,
+        key::Keypair, secp256k1::Secp256k1,
+    };
+    use elements::{
+        AddressParams, AssetId,
+        confidential::{Asset, Nonce, Value},
+    };
+    use futures::executor::block_on;
+    use rstest::rstest;
+    use std::str::FromStr;
+
+    const AMOUNT: u64 = 100_000;
+
+    /// Both parties of the MuSig2 sessions
+    fn musig_keys() -> [secp256k1::Keypair; 2] {
+        [
+            secp256k1::Keypair::new(&mut secp256k1::rand::rng()),
+            secp256k1::Keypair::new(&mut secp256k1::rand::rng()),
+        ]
+    }
+
+    fn internal_key(keys: &[secp256k1::Keypair; 2]) -> [u8; 32] {
+        Musig::setup(keys[0], keys.iter().map(|key| key.public_key()).collect())
+            .unwrap()
+            .agg_pk()
+            .serialize()
+    }
+
+    fn aggregate_signature(
+        keys: &[secp256k1::Keypair; 2],
+        input: &KeyPathInput,
+    ) -> AggregatedSignature {
+        let pub_keys = keys.iter().map(|key| key.public_key()).collect::<Vec<_>>();
+        let [ours, theirs] = keys.map(|key| {
+            Musig::setup(key, pub_keys.clone())
+                .unwrap()
+                .xonly_tweak_add(&input.tweak)
+                .unwrap()
+                .message(input.sighash)
+                .generate_nonce(&mut Musig::rng())
+        });
+
+        let ours_nonce = *ours.pub_nonce();
+        let theirs_nonce = *theirs.pub_nonce();
+
+        let ours = ours
+            .aggregate_nonces(vec![(keys[1].public_key(), theirs_nonce)])
+            .unwrap()
+            .initialize_session()
+            .unwrap()
+            .partial_sign()
+            .unwrap();
+        let theirs = theirs
+            .aggregate_nonces(vec![(keys[0].public_key(), ours_nonce)])
+            .unwrap()
+            .initialize_session()
+            .unwrap()
+            .partial_sign()
+            .unwrap();
+
+        ours.partial_add(keys[1].public_key(), theirs.our_partial_signature())
+            .unwrap()
+            .partial_aggregate()
+            .unwrap()
+    }
+
+    fn bitcoin_input(musig_keys: &[secp256k1::Keypair; 2], vout: u32) -> BitcoinInputDetail {
+        let secp = Secp256k1::new();
+        let internal_key = bitcoin::XOnlyPublicKey::from_slice(&internal_key(musig_keys)).unwrap();
+        let claim_keys = Keypair::new(&secp, &mut bitcoin::secp256k1::rand::thread_rng());
+
+        let tree = crate::bitcoin::swap_tree(
+            hash160::Hash::hash(&[1; 32]),
+            &claim_keys.x_only_public_key().0,
+            &claim_keys.x_only_public_key().0,
+            LockTime::from_height(1_000).unwrap(),
+        );
+        let spend_info = tree.build().unwrap().finalize(&secp, internal_key).unwrap();
+
+        BitcoinInputDetail {
+            input_type: InputType::Claim([1; 32]),
+            output_type: OutputType::Taproot(Some(crate::bitcoin::UncooperativeDetails {
+                tree,
+                internal_key,
+            })),
+            outpoint: OutPoint::new(Txid::all_zeros(), vout),
+            tx_out: TxOut {
+                value: Amount::from_sat(AMOUNT),
+                script_pubkey: ScriptBuf::new_p2tr_tweaked(spend_info.output_key()),
+            },
+            keys: claim_keys,
+        }
+    }
+
+    fn elements_input(musig_keys: &[secp256k1::Keypair; 2], vout: u32) -> ElementsInputDetail {
+        let secp = elements::secp256k1_zkp::Secp256k1::new();
+        let internal_key =
+            elements::secp256k1_zkp::XOnlyPublicKey::from_slice(&internal_key(musig_keys)).unwrap();
+        let claim_keys = Keypair::new(&secp, &mut bitcoin::secp256k1::rand::thread_rng());
+
+        let tree = crate::elements::swap_tree(
+            hash160::Hash::hash(&[1; 32]),
+            &claim_keys.x_only_public_key().0,
+            &claim_keys.x_only_public_key().0,
+            elements::LockTime::from_height(1_000).unwrap(),
+        );
+        let spend_info = tree.build().unwrap().finalize(&secp, internal_key).unwrap();
+
+        ElementsInputDetail {
+            input_type: InputType::Claim([1; 32]),
+            output_type: OutputType::Taproot(Some(crate::elements::UncooperativeDetails {
+                tree,
+                internal_key,
+            })),
+            outpoint: elements::OutPoint::new(elements::Txid::all_zeros(), vout),
+            tx_out: elements::TxOut {
+                asset: Asset::Explicit(
+                    AssetId::from_str(crate::Network::Regtest.liquid_asset_id().unwrap()).unwrap(),
+                ),
+                value: Value::Explicit(AMOUNT),
+                nonce: Nonce::Null,
+                script_pubkey: elements::Script::new_v1_p2tr_tweaked(spend_info.output_key()),
+                witness: Default::default(),
+            },
+            blinding_key: None,
+            keys: claim_keys,
+        }
+    }
+
+    fn bitcoin_destination() -> bitcoin::Address {
+        bitcoin::Address::from_str(
+            "bcrt1p70dqezlrn37f043fmc6m45uzflaqe6678q9ludu8ryr9s5rsn0gs3qh0vd",
+        )
+        .unwrap()
+        .assume_checked()
+    }
+
+    fn elements_destination() -> elements::Address {
+        elements::Address::p2wpkh(
+            &bitcoin::PublicKey::new(
+                Keypair::new(
+                    &Secp256k1::new(),
+                    &mut bitcoin::secp256k1::rand::thread_rng(),
+                )
+                .public_key(),
+            ),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+    }
+
+    #[rstest]
+    #[case::bitcoin(true)]
+    #[case::elements(false)]
+    fn test_key_path_spend(#[case] bitcoin: bool) {
+        let keys = musig_keys();
+        let bitcoin_destination = bitcoin_destination();
+        let elements_destination = elements_destination();
+
+        let params = if bitcoin {
+            Params::Bitcoin(BitcoinParams {
+                inputs: vec![bitcoin_input(&keys, 1), bitcoin_input(&keys, 0)],
+                destination: &Destination::Single(&bitcoin_destination),
+                fee: FeeTarget::Relative(2.0),
+            })
+        } else {
+            Params::Elements(ElementsParams {
+                genesis_hash: crate::Network::Regtest.liquid_genesis_hash().unwrap(),
+                inputs: vec![elements_input(&keys, 1), elements_input(&keys, 0)],
+                destination: &Destination::Single(&elements_destination),
+                fee: FeeTarget::Relative(0.1),
+            })
+        };
+
+        let spend = block_on(prepare_key_path_spend(params)).unwrap();
+        assert_eq!(
+            spend
+                .inputs
+                .iter()
+                .map(|input| input.index)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_ne!(spend.inputs[0].sighash, spend.inputs[1].sighash);
+
+        let signatures = spend
+            .inputs
+            .iter()
+            .map(|input| aggregate_signature(&keys, input))
+            .collect::<Vec<_>>();
+        let unsigned = spend.transaction.clone();
+        let fee = spend.fee;
+        let tx = finalize_key_path_spend(spend, &signatures).unwrap();
+
+        // Only the witnesses change, so the fee estimated with stubs still holds
+        assert_eq!(tx.txid(), unsigned.txid());
+        match tx {
+            Transaction::Bitcoin(tx) => {
+                assert_eq!(tx.output[0].value, Amount::from_sat(2 * AMOUNT - fee));
+                for (input, signature) in tx.input.iter().zip(&signatures) {
+                    assert_eq!(input.witness.len(), 1);
+                    assert_eq!(
+                        input.witness.nth(0).unwrap(),
+                        signature.assume_valid().as_byte_array()
+                    );
+                }
+            }
+            Transaction::Elements(tx) => {
+                for (input, signature) in tx.input.iter().zip(&signatures) {
+                    assert_eq!(
+                        input.witness.script_witness,
+                        vec![signature.assume_valid().as_byte_array().to_vec()]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prepare_key_path_spend_missing_uncooperative_details() {
+        let keys = musig_keys();
+        let destination = bitcoin_destination();
+
+        let mut input = bitcoin_input(&keys, 0);
+        input.output_type = OutputType::Taproot(None);
+
+        let result = block_on(prepare_key_path_spend(Params::Bitcoin(BitcoinParams {
+            inputs: vec![bitcoin_input(&keys, 1), input],
+            destination: &Destination::Single(&destination),
+            fee: FeeTarget::Absolute(1_000),
+        })));
+
+        assert!(matches!(
+            result.unwrap_err(),
+            CooperativeError::MissingUncooperativeDetails(1)
+        ));
+    }
+
+    #[test]
+    fn test_finalize_key_path_spend_signature_count() {
+        let keys = musig_keys();
+        let destination = bitcoin_destination();
+
+        let spend = block_on(prepare_key_path_spend(Params::Bitcoin(BitcoinParams {
+            inputs: vec![bitcoin_input(&keys, 0)],
+            destination: &Destination::Single(&destination),
+            fee: FeeTarget::Absolute(1_000),
+        })))
+        .unwrap();
+
+        assert!(matches!(
+            finalize_key_path_spend(spend, &[]).unwrap_err(),
+            CooperativeError::SignatureCount {
+                expected: 1,
+                actual: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn test_finalize_key_path_spend_invalid_signature() {
+        let keys = musig_keys();
+        let destination = bitcoin_destination();
+
+        let spend = block_on(prepare_key_path_spend(Params::Bitcoin(BitcoinParams {
+            inputs: vec![bitcoin_input(&keys, 0), bitcoin_input(&keys, 1)],
+            destination: &Destination::Single(&destination),
+            fee: FeeTarget::Absolute(1_000),
+        })))
+        .unwrap();
+
+        // The signature of the first input is not valid for the second one
+        let signature = aggregate_signature(&keys, &spend.inputs[0]);
+
+        assert!(matches!(
+            finalize_key_path_spend(spend, &[signature, signature]).unwrap_err(),
+            CooperativeError::InvalidSignature(1)
+        ));
+    }
+}
@@ -0,0 +1,294 @@
+//! Output descriptor helpers shared by the Bitcoin and Elements modules:
+//! the BIP-380 checksum and the miniscript fragments of the swap leaves.
+
+use bitcoin::{XOnlyPublicKey, hashes::hash160};
+use std::str::FromStr;
+
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_LENGTH: usize = 8;
+
+/// Timelock of a refund leaf, as miniscript `after(N)` or `older(N)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RefundLock {
+    /// Absolute lock time enforced with `OP_CLTV`
+    After(u32),
+    /// BIP-68 relative lock time enforced with `OP_CSV`
+    Older(u32),
+}
+
+/// Parameters of a reverse swap tree, read from a `tr(...)` descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReverseTreeParts {
+    pub(crate) internal_key: XOnlyPublicKey,
+    pub(crate) preimage_hash: hash160::Hash,
+    pub(crate) claim_pubkey: XOnlyPublicKey,
+    pub(crate) refund_pubkey: XOnlyPublicKey,
+    pub(crate) lock_time: RefundLock,
+}
+
+fn polymod(c: u64, value: u64) -> u64 {
+    const GENERATOR: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+
+    let c0 = c >> 35;
+    let mut c = ((c & 0x7ffffffff) << 5) ^ value;
+    for (i, generator) in GENERATOR.iter().enumerate() {
+        if (c0 >> i) & 1 == 1 {
+            c ^= generator;
+        }
+    }
+    c
+}
+
+/// BIP-380 checksum of `descriptor`; [`None`] when it contains a character
+/// descriptors do not allow
+pub(crate) fn checksum(descriptor: &str) -> Option<String> {
+    let mut c = 1;
+    let mut class = 0;
+    let mut class_count = 0;
+
+    for ch in descriptor.chars() {
+        let position = INPUT_CHARSET.find(ch)? as u64;
+        c = polymod(c, position & 31);
+        class = class * 3 + (position >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = polymod(c, class);
+    }
+    for _ in 0..CHECKSUM_LENGTH {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+
+    Some(
+        (0..CHECKSUM_LENGTH)
+            .map(|i| CHECKSUM_CHARSET[((c >> (5 * (7 - i))) & 31) as usize] as char)
+            .collect(),
+    )
+}
+
+pub(crate) fn with_checksum(descriptor: String) -> String {
+    let checksum = checksum(&descriptor).expect("descriptor has valid characters");
+    format!("{descriptor}#{checksum}")
+}
+
+/// Strips the checksum from `descriptor`, if it has one, and returns
+/// [`None`] when it does not match
+pub(crate) fn strip_checksum(descriptor: &str) -> Option<&str> {
+    match descriptor.split_once('#') {
+        Some((descriptor, expected)) => (checksum(descriptor)? == expected).then_some(descriptor),
+        None => checksum(descriptor).map(|_| descriptor),
+    }
+}
+
+/// Argument of the descriptor function `name`, like the address of `addr(...)`
+pub(crate) fn function<'a>(descriptor: &'a str, name: &str) -> Option<&'a str> {
+    descriptor
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Miniscript `and_v(v:hash160(H),pk(K))` of the reverse swap claim leaf
+pub(crate) fn claim_fragment(
+    preimage_hash: &hash160::Hash,
+    claim_pubkey: &XOnlyPublicKey,
+) -> String {
+    format!("and_v(v:hash160({preimage_hash}),pk({claim_pubkey}))")
+}
+
+/// Miniscript `and_v(v:pk(K),after(N))` or `and_v(v:pk(K),older(N))` of the refund leaf
+pub(crate) fn refund_fragment(refund_pubkey: &XOnlyPublicKey, lock_time: RefundLock) -> String {
+    match lock_time {
+        RefundLock::After(lock_time) => format!("and_v(v:pk({refund_pubkey}),after({lock_time}))"),
+        RefundLock::Older(sequence) => format!("and_v(v:pk({refund_pubkey}),older({sequence}))"),
+    }
+}
+
+pub(crate) fn taproot_descriptor(
+    name: &str,
+    internal_key: &XOnlyPublicKey,
+    preimage_hash: &hash160::Hash,
+    claim_pubkey: &XOnlyPublicKey,
+    refund_pubkey: &XOnlyPublicKey,
+    lock_time: RefundLock,
+) -> String {
+    with_checksum(format!(
+        "{name}({internal_key},{{{},{}}})",
+        claim_fragment(preimage_hash, claim_pubkey),
+        refund_fragment(refund_pubkey, lock_time),
+    ))
+}
+
+/// Parses the `name(KEY,{CLAIM,REFUND})` descriptor of a reverse swap tree;
+/// the leaves may be in either order
+pub(crate) fn parse_taproot(descriptor: &str, name: &str) -> Option<ReverseTreeParts> {
+    let (internal_key, leaves) = function(descriptor, name)?.split_once(',')?;
+    let leaves = leaves.strip_prefix('{')?.strip_suffix('}')?;
+    let (first, second) = split_top_level(leaves)?;
+
+    let ((preimage_hash, claim_pubkey), (refund_pubkey, lock_time)) =
+        match (parse_claim_fragment(first), parse_refund_fragment(second)) {
+            (Some(claim), Some(refund)) => (claim, refund),
+            _ => (parse_claim_fragment(second)?, parse_refund_fragment(first)?),
+        };
+
+    Some(ReverseTreeParts {
+        internal_key: XOnlyPublicKey::from_str(internal_key).ok()?,
+        preimage_hash,
+        claim_pubkey,
+        refund_pubkey,
+        lock_time,
+    })
+}
+
+fn parse_claim_fragment(fragment: &str) -> Option<(hash160::Hash, XOnlyPublicKey)> {
+    let (preimage_hash, claim_pubkey) = fragment
+        .strip_prefix("and_v(v:hash160(")?
+        .strip_suffix("))")?
+        .split_once("),pk(")?;
+
+    Some((
+        hash160::Hash::from_str(preimage_hash).ok()?,
+        XOnlyPublicKey::from_str(claim_pubkey).ok()?,
+    ))
+}
+
+fn parse_refund_fragment(fragment: &str) -> Option<(XOnlyPublicKey, RefundLock)> {
+    let fragment = fragment.strip_prefix("and_v(v:pk(")?.strip_suffix("))")?;
+    let (refund_pubkey, lock_time) = match fragment.split_once("),after(") {
+        Some((refund_pubkey, lock_time)) => {
+            (refund_pubkey, RefundLock::After(lock_time.parse().ok()?))
+        }
+        None => {
+            let (refund_pubkey, sequence) = fragment.split_once("),older(")?;
+            (refund_pubkey, RefundLock::Older(sequence.parse().ok()?))
+        }
+    };
+
+    Some((XOnlyPublicKey::from_str(refund_pubkey).ok()?, lock_time))
+}
+
+/// Splits `a,b` at the only comma that is not nested in parentheses
+fn split_top_level(expression: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    let mut split = None;
+
+    for (index, ch) in expression.char_indices() {
+        match ch {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                if split.is_some() {
+                    return None;
+                }
+                split = Some(index);
+            }
+            _ => {}
+        }
+    }
+
+    let index = split?;
+    Some((&expression[..index], &expression[index + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        // Test vector of BIP-380
+        assert_eq!(checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(checksum("raw(deadbeef)\u{e9}"), None);
+    }
+
+    #[test]
+    fn test_strip_checksum() {
+        assert_eq!(
+            strip_checksum("raw(deadbeef)#89f8spxm"),
+            Some("raw(deadbeef)")
+        );
+        assert_eq!(strip_checksum("raw(deadbeef)"), Some("raw(deadbeef)"));
+        assert_eq!(strip_checksum("raw(deadbeef)#89f8spxn"), None);
+        assert_eq!(strip_checksum("raw(deadbeee)#89f8spxm"), None);
+    }
+
+    #[test]
+    fn test_parse_taproot_leaf_order() {
+        let key = XOnlyPublicKey::from_str(
+            "f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539",
+        )
+        .unwrap();
+        let preimage_hash =
+            hash160::Hash::from_str("0be3e65567f55ff6ac791bd4f65f672bcaf5f211").unwrap();
+
+        let expected = ReverseTreeParts {
+            internal_key: key,
+            preimage_hash,
+            claim_pubkey: key,
+            refund_pubkey: key,
+            lock_time: RefundLock::After(123),
+        };
+
+        let descriptor = taproot_descriptor(
+            "tr",
+            &key,
+            &preimage_hash,
+            &key,
+            &key,
+            RefundLock::After(123),
+        );
+        assert_eq!(
+            parse_taproot(strip_checksum(&descriptor).unwrap(), "tr"),
+            Some(expected)
+        );
+
+        let swapped = format!(
+            "tr({key},{{{},{}}})",
+            refund_fragment(&key, RefundLock::After(123)),
+            claim_fragment(&preimage_hash, &key)
+        );
+        assert_eq!(parse_taproot(&swapped, "tr"), Some(expected));
+        assert_eq!(parse_taproot(&swapped, "eltr"), None);
+    }
+
+    #[test]
+    fn test_refund_fragment_older() {
+        let key = XOnlyPublicKey::from_str(
+            "f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539",
+        )
+        .unwrap();
+
+        let fragment = refund_fragment(&key, RefundLock::Older(144));
+        assert_eq!(fragment, format!("and_v(v:pk({key}),older(144))"));
+        assert_eq!(
+            parse_refund_fragment(&fragment),
+            Some((key, RefundLock::Older(144)))
+        );
+        assert_eq!(
+            parse_refund_fragment(&format!("and_v(v:pk({key}),newer(144))")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_split_top_level() {
+        assert_eq!(split_top_level("a(b,c),d"), Some(("a(b,c)", "d")));
+        assert_eq!(split_top_level("a,b,c"), None);
+        assert_eq!(split_top_level("a(b,c)"), None);
+        assert_eq!(split_top_level("a),(b"), None);
+    }
+}
@@ -2,15 +2,19 @@ use crate::{
     FeeTarget, Network,
     elements::tx::{ExplicitOutput, TxError, UnblindedOutput, create_output},
     network::NetworkError,
-    target_fee::{FeeError, target_fee},
+    signer::{Signer, SignerError},
+    target_fee::{FeeBoundsError, FeeError, target_fee},
 };
 use elements::{
-    Address, LockTime, OutPoint, Script, Sequence, Transaction, TxIn, TxInWitness, TxOut,
+    Address, EcdsaSighashType, LockTime, OutPoint, PubkeyHash, SchnorrSig, SchnorrSighashType,
+    Script, Sequence, Transaction, TxIn, TxInWitness, TxOut, WPubkeyHash,
     confidential::{AssetBlindingFactor, ValueBlindingFactor},
+    hashes::Hash,
     hex::ToHex,
     opcodes::all::OP_PUSHBYTES_0,
     script::Builder,
-    secp256k1_zkp::{Keypair, Secp256k1, Signing, Verification, rand},
+    secp256k1_zkp::{Keypair, Message, Secp256k1, Signing, Verification, rand},
+    sighash::{Prevouts, SighashCache},
 };
 
 const ECDSA_SIGNATURE_SIZE: usize = 70;
@@ -61,6 +65,9 @@ pub enum AssetRescueError {
     /// The relative fee rate is non-finite or negative.
     #[error("invalid fee rate")]
     InvalidFeeRate,
+    /// The fee of a [`FeeTarget::Bounded`] is outside of its bounds.
+    #[error(transparent)]
+    FeeBounds(#[from] FeeBoundsError),
     /// A [`Network`] operation failed.
     #[error(transparent)]
     Network(#[from] NetworkError),
@@ -70,12 +77,19 @@ pub enum AssetRescueError {
     /// Failed to unblind a confidential input.
     #[error(transparent)]
     Unblind(#[from] elements::UnblindError),
+    /// Failed to compute the sighash of an input.
+    #[error(transparent)]
+    Sighash(#[from] elements::sighash::Error),
+    /// The [`Signer`] of the named input failed to sign it.
+    #[error("could not sign input {0}")]
+    Signer(usize, #[source] SignerError),
 }
 
 impl From<FeeError> for AssetRescueError {
     fn from(e: FeeError) -> Self {
         match e {
             FeeError::InvalidFeeRate => AssetRescueError::InvalidFeeRate,
+            FeeError::Bounds(err) => AssetRescueError::FeeBounds(err),
         }
     }
 }
@@ -85,7 +99,7 @@ impl From<FeeError> for AssetRescueError {
 ///
 /// Used twice by [`construct_asset_rescue`] — once for the stuck non-L-BTC
 /// asset and once for the L-BTC funding input that pays the fee.
-pub struct AssetPair<'a> {
+pub struct AssetPair<'a, S = Keypair> {
     /// The previous output (`scriptPubKey` + commitments) being spent.
     pub tx_out: &'a TxOut,
     /// The outpoint of the UTXO being spent.
@@ -95,10 +109,16 @@ pub struct AssetPair<'a> {
 
     /// Address that receives the swept funds.
     pub destination: &'a Address,
+    /// Signer for the input, which is left unsigned when `None`.
+    ///
+    /// Taproot inputs are spent via the key-path, so the signer has to hold
+    /// the tweaked output key; all other inputs are single-key outputs of
+    /// [`Signer::public_key`].
+    pub signer: Option<S>,
 }
 
-struct UnblindedAssetPair<'a> {
-    asset_pair: &'a AssetPair<'a>,
+struct UnblindedAssetPair<'a, S> {
+    asset_pair: &'a AssetPair<'a, S>,
     unblinded: UnblindedOutput,
 }
 
@@ -109,12 +129,16 @@ struct UnblindedAssetPair<'a> {
 /// to pay the fee), sending the asset to its destination and the L-BTC
 /// remainder to its destination as change. The L-BTC destination must be
 /// a confidential address — see [`AssetRescueError::LbtcDestinationNotBlinded`].
+///
+/// Inputs of pairs with a [`AssetPair::signer`] are signed; the others are
+/// left unsigned for the caller to sign, e.g. cooperatively with MuSig2 or
+/// with the wallet that owns the L-BTC input.
 #[must_use = "ignoring the result discards the constructed rescue transaction"]
-pub fn construct_asset_rescue<C: Signing + Verification>(
+pub async fn construct_asset_rescue<C: Signing + Verification, S: Signer>(
     secp: &Secp256k1<C>,
     network: Network,
-    asset_pair: &AssetPair,
-    lbtc_pair: &AssetPair,
+    asset_pair: &AssetPair<'_, S>,
+    lbtc_pair: &AssetPair<'_, S>,
     fee: FeeTarget,
 ) -> Result<(Transaction, u64), AssetRescueError> {
     if !asset_pair.tx_out.script_pubkey.is_v1_p2tr() {
@@ -138,15 +162,20 @@ pub fn construct_asset_rescue<C: Signing + Verification>(
         return Err(AssetRescueError::LbtcInputWrongAsset);
     }
 
-    let (mut tx, fee) = target_fee(fee, |fee, is_fee_estimation| {
-        construct_raw(
-            secp,
-            &asset_unblinded,
-            &lbtc_unblinded,
-            fee,
-            is_fee_estimation,
-        )
-    })?;
+    // The fee is paid from the L-BTC input
+    let (mut tx, fee) = target_fee(
+        fee,
+        lbtc_unblinded.unblinded.amount(),
+        |fee, is_fee_estimation| {
+            construct_raw(
+                secp,
+                &asset_unblinded,
+                &lbtc_unblinded,
+                fee,
+                is_fee_estimation,
+            )
+        },
+    )?;
 
     // Clear the stubs we use for fee estimation
     for input in tx.input.iter_mut() {
@@ -154,13 +183,103 @@ pub fn construct_asset_rescue<C: Signing + Verification>(
         input.witness.script_witness = vec![];
     }
 
+    let prevouts = [asset_pair.tx_out.clone(), lbtc_pair.tx_out.clone()];
+    let unsigned_tx = tx.clone();
+    let mut sighash_cache = SighashCache::new(&unsigned_tx);
+
+    for (i, pair) in [asset_pair, lbtc_pair].into_iter().enumerate() {
+        if let Some(signer) = &pair.signer {
+            sign_input(
+                &mut sighash_cache,
+                &prevouts,
+                network,
+                &mut tx.input[i],
+                i,
+                pair.tx_out,
+                signer,
+            )
+            .await?;
+        }
+    }
+
     Ok((tx, fee))
 }
 
-fn construct_raw<C: Signing + Verification>(
+async fn sign_input<S: Signer>(
+    sighash_cache: &mut SighashCache<&Transaction>,
+    prevouts: &[TxOut],
+    network: Network,
+    tx_in: &mut TxIn,
+    index: usize,
+    tx_out: &TxOut,
+    signer: &S,
+) -> Result<(), AssetRescueError> {
+    let output_type = output_type(tx_out)?;
+    if let OutputType::SegwitV1 = output_type {
+        let sighash = sighash_cache.taproot_key_spend_signature_hash(
+            index,
+            &Prevouts::All(prevouts),
+            SchnorrSighashType::Default,
+            network.liquid_genesis_hash()?,
+        )?;
+        let sig = SchnorrSig {
+            sig: signer
+                .sign_schnorr(&Message::from_digest(sighash.to_byte_array()))
+                .await
+                .map_err(|err| AssetRescueError::Signer(index, err))?,
+            hash_ty: SchnorrSighashType::Default,
+        };
+        tx_in.witness.script_witness = vec![sig.to_vec()];
+
+        return Ok(());
+    }
+
+    let public_key = signer.public_key().serialize();
+    let sighash = match output_type {
+        OutputType::Legacy => {
+            sighash_cache.legacy_sighash(index, &tx_out.script_pubkey, EcdsaSighashType::All)
+        }
+        _ => sighash_cache.segwitv0_sighash(
+            index,
+            &Script::new_p2pkh(&PubkeyHash::hash(&public_key)),
+            tx_out.value,
+            EcdsaSighashType::All,
+        ),
+    };
+
+    let mut sig = signer
+        .sign_ecdsa(&Message::from_digest(sighash.to_byte_array()))
+        .await
+        .map_err(|err| AssetRescueError::Signer(index, err))?
+        .serialize_der()
+        .to_vec();
+    sig.push(EcdsaSighashType::All.as_u32() as u8);
+
+    match output_type {
+        OutputType::Legacy => {
+            tx_in.script_sig = Builder::new()
+                .push_slice(&sig)
+                .push_slice(&public_key)
+                .into_script();
+        }
+        OutputType::NestedSegwit => {
+            tx_in.script_sig = Builder::new()
+                .push_slice(Script::new_v0_wpkh(&WPubkeyHash::hash(&public_key)).as_bytes())
+                .into_script();
+            tx_in.witness.script_witness = vec![sig, public_key.to_vec()];
+        }
+        _ => {
+            tx_in.witness.script_witness = vec![sig, public_key.to_vec()];
+        }
+    }
+
+    Ok(())
+}
+
+fn construct_raw<C: Signing + Verification, S>(
     secp: &Secp256k1<C>,
-    asset_pair: &UnblindedAssetPair,
-    lbtc_pair: &UnblindedAssetPair,
+    asset_pair: &UnblindedAssetPair<S>,
+    lbtc_pair: &UnblindedAssetPair<S>,
     fee: u64,
     is_fee_estimation: bool,
 ) -> Result<Transaction, AssetRescueError> {
@@ -223,10 +342,10 @@ fn construct_raw<C: Signing + Verification>(
     })
 }
 
-fn blind_outputs<C: Signing>(
+fn blind_outputs<C: Signing, S>(
     secp: &Secp256k1<C>,
-    asset_pair: &UnblindedAssetPair,
-    lbtc_pair: &UnblindedAssetPair,
+    asset_pair: &UnblindedAssetPair<S>,
+    lbtc_pair: &UnblindedAssetPair<S>,
     fee: u64,
     is_fee_estimation: bool,
 ) -> Result<Vec<TxOut>, AssetRescueError> {
@@ -300,9 +419,9 @@ fn output_type(output: &TxOut) -> Result<OutputType, AssetRescueError> {
     }
 }
 
-fn unblind_outputs<C: Verification>(
+fn unblind_outputs<C: Verification, S>(
     secp: &Secp256k1<C>,
-    input: &AssetPair,
+    input: &AssetPair<S>,
 ) -> Result<UnblindedOutput, AssetRescueError> {
     Ok(if input.tx_out.value.is_confidential() {
         let sec = input.tx_out.unblind(
@@ -335,20 +454,21 @@ mod tests {
     use crate::{
         client::{RpcClient, RpcParam},
         elements::tx::tests::{
-            FUNDING_AMOUNT, address_blinding_key, fund_address, get_destination, get_genesis_hash,
-            mine_block, send_raw_transaction,
+            FUNDING_AMOUNT, address_blinding_key, fund_address, get_destination, mine_block,
+            send_raw_transaction,
         },
     };
     use bitcoin::Amount;
     use elements::{
-        AddressParams,
+        AddressParams, AssetId, ScriptHash,
+        confidential::{Asset, Nonce, Value},
         pset::serialize::Serialize,
         schnorr::TweakedPublicKey,
-        secp256k1_zkp::{Message, SecretKey},
-        sighash::{Prevouts, SighashCache},
+        secp256k1_zkp::{SecretKey, ecdsa},
     };
     type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+    use futures::executor::block_on;
     use rstest::rstest;
     use serde::Deserialize;
     use serial_test::serial;
@@ -455,7 +575,7 @@ mod tests {
         let (lbtc_tx, lbtc_vout) = fund_address(&client, &lbtc_address, None, Some(true));
 
         let fee_target = 0.1;
-        let (mut rescue_tx, fee) = construct_asset_rescue(
+        let (rescue_tx, fee) = block_on(construct_asset_rescue(
             &secp,
             Network::Regtest,
             &AssetPair {
@@ -463,6 +583,7 @@ mod tests {
                 outpoint: OutPoint::new(asset_tx.txid(), asset_vout as u32),
                 blinding_key: Some(keypair),
                 destination: &get_destination(&client, blinded_asset_output, None),
+                signer: Some(keypair),
             },
             &AssetPair {
                 tx_out: &lbtc_tx.output[lbtc_vout],
@@ -473,30 +594,13 @@ mod tests {
                         .unwrap(),
                 )),
                 destination: &get_destination(&client, true, None),
+                signer: None,
             },
             FeeTarget::Relative(fee_target),
-        )
+        ))
         .unwrap();
 
-        let sighash = SighashCache::new(&rescue_tx)
-            .taproot_key_spend_signature_hash(
-                0,
-                &Prevouts::All(&[
-                    asset_tx.output[asset_vout].clone(),
-                    lbtc_tx.output[lbtc_vout].clone(),
-                ]),
-                elements::SchnorrSighashType::Default,
-                get_genesis_hash(&client),
-            )
-            .unwrap();
-        rescue_tx.input[0].witness.script_witness = vec![
-            secp.sign_schnorr(
-                &Message::from_digest_slice(sighash.as_raw_hash().as_ref()).unwrap(),
-                &keypair,
-            )
-            .serialize()
-            .to_vec(),
-        ];
+        // The asset input was signed by its signer and the wallet signs the L-BTC one
         let signed_tx = sign_raw_transaction_with_wallet(&client, &rescue_tx).unwrap();
 
         mine_block(&client);
@@ -521,4 +625,152 @@ mod tests {
             (signed_tx.discount_vsize() as f64 * fee_target).ceil() as u64
         );
     }
+
+    fn explicit_tx_out(asset: AssetId, amount: u64, script_pubkey: Script) -> TxOut {
+        TxOut {
+            asset: Asset::Explicit(asset),
+            value: Value::Explicit(amount),
+            nonce: Nonce::Null,
+            script_pubkey,
+            witness: Default::default(),
+        }
+    }
+
+    #[rstest]
+    #[case::legacy("legacy")]
+    #[case::nested_segwit("p2sh-segwit")]
+    #[case::segwit_v0("bech32")]
+    #[case::segwit_v1("bech32m")]
+    fn test_construct_asset_rescue_signer(#[case] lbtc_type: &str) {
+        let secp = Secp256k1::new();
+        let network = Network::Regtest;
+
+        let asset_keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let lbtc_keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let lbtc_public_key = lbtc_keys.public_key().serialize();
+
+        let asset_tx_out = explicit_tx_out(
+            AssetId::from_slice(&[2; 32]).unwrap(),
+            1_000,
+            Address::p2tr_tweaked(
+                TweakedPublicKey::new(asset_keys.x_only_public_key().0),
+                None,
+                &AddressParams::ELEMENTS,
+            )
+            .script_pubkey(),
+        );
+        let lbtc_tx_out = explicit_tx_out(
+            AssetId::from_str(network.liquid_asset_id().unwrap()).unwrap(),
+            100_000,
+            match lbtc_type {
+                "legacy" => Script::new_p2pkh(&PubkeyHash::hash(&lbtc_public_key)),
+                "p2sh-segwit" => Script::new_p2sh(&ScriptHash::hash(
+                    Script::new_v0_wpkh(&WPubkeyHash::hash(&lbtc_public_key)).as_bytes(),
+                )),
+                "bech32" => Script::new_v0_wpkh(&WPubkeyHash::hash(&lbtc_public_key)),
+                _ => Address::p2tr_tweaked(
+                    TweakedPublicKey::new(lbtc_keys.x_only_public_key().0),
+                    None,
+                    &AddressParams::ELEMENTS,
+                )
+                .script_pubkey(),
+            },
+        );
+
+        let destination = Address::p2wpkh(
+            &bitcoin::PublicKey::new(asset_keys.public_key()),
+            Some(lbtc_keys.public_key()),
+            &AddressParams::ELEMENTS,
+        );
+
+        let (tx, _) = block_on(construct_asset_rescue(
+            &secp,
+            network,
+            &AssetPair {
+                tx_out: &asset_tx_out,
+                outpoint: OutPoint::new(elements::Txid::all_zeros(), 0),
+                blinding_key: None,
+                destination: &destination,
+                signer: Some(asset_keys),
+            },
+            &AssetPair {
+                tx_out: &lbtc_tx_out,
+                outpoint: OutPoint::new(elements::Txid::all_zeros(), 1),
+                blinding_key: None,
+                destination: &destination,
+                signer: Some(lbtc_keys),
+            },
+            FeeTarget::Relative(0.1),
+        ))
+        .unwrap();
+
+        let prevouts = [asset_tx_out.clone(), lbtc_tx_out.clone()];
+        let mut sighash_cache = SighashCache::new(&tx);
+        let genesis_hash = network.liquid_genesis_hash().unwrap();
+
+        let verify_taproot =
+            |sighash_cache: &mut SighashCache<&Transaction>, index: usize, keys: &Keypair| {
+                let sighash = sighash_cache
+                    .taproot_key_spend_signature_hash(
+                        index,
+                        &Prevouts::All(&prevouts),
+                        SchnorrSighashType::Default,
+                        genesis_hash,
+                    )
+                    .unwrap();
+                let witness = &tx.input[index].witness.script_witness;
+                assert_eq!(witness.len(), 1);
+                secp.verify_schnorr(
+                    &SchnorrSig::from_slice(&witness[0]).unwrap().sig,
+                    &Message::from_digest(sighash.to_byte_array()),
+                    &keys.x_only_public_key().0,
+                )
+                .unwrap();
+            };
+
+        verify_taproot(&mut sighash_cache, 0, &asset_keys);
+        if lbtc_type == "bech32m" {
+            verify_taproot(&mut sighash_cache, 1, &lbtc_keys);
+            return;
+        }
+
+        let lbtc_in = &tx.input[1];
+        let (sig, public_key) = if lbtc_type == "legacy" {
+            assert!(lbtc_in.witness.script_witness.is_empty());
+            let pushes = lbtc_in
+                .script_sig
+                .instructions()
+                .map(|inst| match inst.unwrap() {
+                    elements::script::Instruction::PushBytes(bytes) => bytes.to_vec(),
+                    _ => panic!("unexpected opcode"),
+                })
+                .collect::<Vec<_>>();
+            (pushes[0].clone(), pushes[1].clone())
+        } else {
+            let witness = &lbtc_in.witness.script_witness;
+            assert_eq!(witness.len(), 2);
+            (witness[0].clone(), witness[1].clone())
+        };
+        assert_eq!(public_key, lbtc_public_key.to_vec());
+        assert_eq!(lbtc_in.script_sig.is_empty(), lbtc_type == "bech32",);
+
+        let sighash = if lbtc_type == "legacy" {
+            sighash_cache.legacy_sighash(1, &lbtc_tx_out.script_pubkey, EcdsaSighashType::All)
+        } else {
+            sighash_cache.segwitv0_sighash(
+                1,
+                &Script::new_p2pkh(&PubkeyHash::hash(&lbtc_public_key)),
+                lbtc_tx_out.value,
+                EcdsaSighashType::All,
+            )
+        };
+
+        assert_eq!(*sig.last().unwrap(), EcdsaSighashType::All.as_u32() as u8);
+        secp.verify_ecdsa(
+            &Message::from_digest(sighash.to_byte_array()),
+            &ecdsa::Signature::from_der(&sig[..sig.len() - 1]).unwrap(),
+            &lbtc_keys.public_key(),
+        )
+        .unwrap();
+    }
 }
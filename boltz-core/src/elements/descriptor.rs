@@ -0,0 +1,384 @@
+use crate::{
+    NetworkError,
+    descriptor::{self, RefundLock},
+    elements::{Timelock, TreeError, UncooperativeDetails, decode_tree, reverse_tree},
+    network::Network,
+    utils::{OutputType, SwapKind},
+};
+use elements::{
+    Address, LockTime, Script, Sequence,
+    address::AddressError,
+    schnorr::TweakedPublicKey,
+    secp256k1_zkp::{Secp256k1, Verification},
+};
+use std::str::FromStr;
+
+/// Errors returned by [`output_descriptor`] and [`parse_descriptor`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum DescriptorError {
+    /// The descriptor checksum does not match or the descriptor contains
+    /// characters that descriptors do not allow.
+    #[error("invalid descriptor checksum")]
+    Checksum,
+    /// The descriptor is neither the `eltr(...)` of a reverse swap tree nor an
+    /// `addr(...)`.
+    #[error("unsupported descriptor")]
+    Unsupported,
+    /// The address in the descriptor is for another network.
+    #[error("address is for another network")]
+    WrongNetwork,
+    /// The Taproot builder could not finalize the swap tree.
+    #[error("could not finalize taproot builder")]
+    TaprootFinalize,
+    /// A swap [`Tree`](crate::elements::Tree) operation failed.
+    #[error(transparent)]
+    Tree(#[from] TreeError),
+    /// The address in the descriptor could not be parsed.
+    #[error(transparent)]
+    Address(#[from] AddressError),
+    /// The network has no Liquid equivalent.
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+}
+
+/// What a swap output descriptor describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Descriptor {
+    /// Taproot output of a reverse swap without covenant, with the full script tree.
+    Taproot(UncooperativeDetails),
+    /// Any other swap output, described only by its unconfidential address.
+    Address(Address),
+}
+
+impl Descriptor {
+    /// The `scriptPubKey` of the described output.
+    pub fn script_pubkey<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+    ) -> Result<Script, DescriptorError> {
+        Ok(match self {
+            Descriptor::Taproot(details) => Script::new_v1_p2tr_tweaked(output_key(secp, details)?),
+            Descriptor::Address(address) => address.script_pubkey(),
+        })
+    }
+}
+
+/// Export a swap output as output descriptor with BIP-380 checksum.
+///
+/// Reverse swap trees without covenant become `eltr(KEY,{CLAIM,REFUND})`
+/// with both leaves in miniscript. Covenant claim leaves, the claim leaf of
+/// submarine swaps and the legacy redeem scripts are not miniscript, so
+/// those outputs are exported as watch-only `addr(...)`. Descriptors do not
+/// carry blinding keys; the address is unconfidential.
+pub fn output_descriptor<C: Verification>(
+    secp: &Secp256k1<C>,
+    output_type: &OutputType<UncooperativeDetails, Script>,
+    network: Network,
+) -> Result<String, DescriptorError> {
+    let params = network.liquid()?;
+
+    let address = match output_type {
+        OutputType::Taproot(details) => {
+            if let Ok(swap) = decode_tree(&details.tree, params)
+                && swap.kind == SwapKind::Reverse
+                && swap.covenant.is_none()
+            {
+                return Ok(descriptor::taproot_descriptor(
+                    "eltr",
+                    &details.internal_key,
+                    &swap.preimage_hash,
+                    &swap.claim_pubkey,
+                    &swap.refund_pubkey,
+                    match swap.lock_time {
+                        Timelock::Absolute(lock_time) => {
+                            RefundLock::After(lock_time.to_consensus_u32())
+                        }
+                        Timelock::Relative(sequence) => {
+                            RefundLock::Older(sequence.to_consensus_u32())
+                        }
+                    },
+                ));
+            }
+
+            Address::p2tr_tweaked(output_key(secp, details)?, None, params)
+        }
+        OutputType::SegwitV0(script) => Address::p2wsh(script, None, params),
+        OutputType::Compatibility(script) => Address::p2shwsh(script, None, params),
+        OutputType::Legacy(script) => Address::p2sh(script, None, params),
+    };
+
+    Ok(descriptor::with_checksum(format!("addr({address})")))
+}
+
+/// Parse a descriptor created by [`output_descriptor`].
+///
+/// The checksum is optional, but has to match when present.
+pub fn parse_descriptor(descriptor: &str, network: Network) -> Result<Descriptor, DescriptorError> {
+    let descriptor = descriptor::strip_checksum(descriptor).ok_or(DescriptorError::Checksum)?;
+
+    if let Some(address) = descriptor::function(descriptor, "addr") {
+        let address = Address::from_str(address)?;
+        if address.params != network.liquid()? {
+            return Err(DescriptorError::WrongNetwork);
+        }
+
+        return Ok(Descriptor::Address(address));
+    }
+
+    let parts =
+        descriptor::parse_taproot(descriptor, "eltr").ok_or(DescriptorError::Unsupported)?;
+    Ok(Descriptor::Taproot(UncooperativeDetails {
+        tree: reverse_tree(
+            parts.preimage_hash,
+            &parts.claim_pubkey,
+            &parts.refund_pubkey,
+            match parts.lock_time {
+                RefundLock::After(lock_time) => {
+                    Timelock::Absolute(LockTime::from_consensus(lock_time))
+                }
+                RefundLock::Older(sequence) => {
+                    Timelock::Relative(Sequence::from_consensus(sequence))
+                }
+            },
+            None,
+        ),
+        internal_key: parts.internal_key,
+    }))
+}
+
+fn output_key<C: Verification>(
+    secp: &Secp256k1<C>,
+    details: &UncooperativeDetails,
+) -> Result<TweakedPublicKey, DescriptorError> {
+    Ok(details
+        .tree
+        .build()?
+        .finalize(secp, details.internal_key)
+        .map_err(|_| DescriptorError::TaprootFinalize)?
+        .output_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{ClaimCovenantParams, swap_script, swap_tree};
+    use elements::{
+        AddressParams, AssetId,
+        hashes::hash160,
+        secp256k1_zkp::{PublicKey, XOnlyPublicKey},
+    };
+    use rstest::rstest;
+
+    fn keys() -> (PublicKey, PublicKey) {
+        (
+            PublicKey::from_str(
+                "03f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539",
+            )
+            .unwrap(),
+            PublicKey::from_str(
+                "03ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn preimage_hash() -> hash160::Hash {
+        hash160::Hash::from_str("0be3e65567f55ff6ac791bd4f65f672bcaf5f211").unwrap()
+    }
+
+    fn internal_key() -> XOnlyPublicKey {
+        XOnlyPublicKey::from_str("a34b99f22c790c4e36b2b3c2c35a36db06226e41c692fc82b8b56ac1c540c5bd")
+            .unwrap()
+    }
+
+    fn details(reverse: bool, covenant: Option<&ClaimCovenantParams>) -> UncooperativeDetails {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let (claim_pubkey, refund_pubkey) = (
+            claim_pubkey.x_only_public_key().0,
+            refund_pubkey.x_only_public_key().0,
+        );
+        let lock_time = LockTime::from_height(515_924).unwrap();
+
+        UncooperativeDetails {
+            tree: if reverse {
+                reverse_tree(
+                    preimage_hash(),
+                    &claim_pubkey,
+                    &refund_pubkey,
+                    lock_time,
+                    covenant,
+                )
+            } else {
+                swap_tree(preimage_hash(), &claim_pubkey, &refund_pubkey, lock_time)
+            },
+            internal_key: internal_key(),
+        }
+    }
+
+    fn tweaked_script_pubkey(details: &UncooperativeDetails) -> Script {
+        Script::new_v1_p2tr_tweaked(output_key(&Secp256k1::new(), details).unwrap())
+    }
+
+    #[test]
+    fn test_reverse_tree() {
+        let secp = Secp256k1::new();
+        let details = details(true, None);
+
+        let descriptor = output_descriptor(
+            &secp,
+            &OutputType::Taproot(details.clone()),
+            Network::Regtest,
+        )
+        .unwrap();
+        assert!(descriptor.starts_with(&format!(
+            "eltr({},{{and_v(v:hash160({}),pk(",
+            internal_key(),
+            preimage_hash()
+        )));
+
+        // Wallets parse the same output from it
+        let parsed = elements_miniscript::Descriptor::<
+            elements_miniscript::bitcoin::XOnlyPublicKey,
+        >::from_str(&descriptor)
+        .unwrap();
+        assert_eq!(
+            parsed.script_pubkey().as_bytes(),
+            tweaked_script_pubkey(&details).as_bytes()
+        );
+
+        let decoded = parse_descriptor(&descriptor, Network::Regtest).unwrap();
+        assert_eq!(decoded, Descriptor::Taproot(details.clone()));
+        assert_eq!(
+            decoded.script_pubkey(&secp).unwrap(),
+            tweaked_script_pubkey(&details)
+        );
+    }
+
+    #[test]
+    fn test_reverse_tree_relative() {
+        let secp = Secp256k1::new();
+        let (claim_pubkey, refund_pubkey) = keys();
+        let details = UncooperativeDetails {
+            tree: reverse_tree(
+                preimage_hash(),
+                &claim_pubkey.x_only_public_key().0,
+                &refund_pubkey.x_only_public_key().0,
+                Sequence::from_height(144),
+                None,
+            ),
+            internal_key: internal_key(),
+        };
+
+        let descriptor = output_descriptor(
+            &secp,
+            &OutputType::Taproot(details.clone()),
+            Network::Regtest,
+        )
+        .unwrap();
+        assert!(descriptor.contains("older(144)"));
+
+        let parsed = elements_miniscript::Descriptor::<
+            elements_miniscript::bitcoin::XOnlyPublicKey,
+        >::from_str(&descriptor)
+        .unwrap();
+        assert_eq!(
+            parsed.script_pubkey().as_bytes(),
+            tweaked_script_pubkey(&details).as_bytes()
+        );
+
+        assert_eq!(
+            parse_descriptor(&descriptor, Network::Regtest).unwrap(),
+            Descriptor::Taproot(details)
+        );
+    }
+
+    #[rstest]
+    #[case::submarine(false, false)]
+    #[case::reverse_covenant(true, true)]
+    fn test_address_tree(#[case] reverse: bool, #[case] with_covenant: bool) {
+        let secp = Secp256k1::new();
+        let covenant = ClaimCovenantParams {
+            index: 0,
+            output: Address::p2wsh(&Script::new(), None, &AddressParams::ELEMENTS),
+            asset_id: AssetId::from_str(
+                "5ac9f65c0efcc4775e0baec4ec03abdde22473cd3cf33c0419ca290e0751b225",
+            )
+            .unwrap(),
+            expected_amount: 123_321,
+        };
+        let details = details(reverse, with_covenant.then_some(&covenant));
+
+        let descriptor = output_descriptor(
+            &secp,
+            &OutputType::Taproot(details.clone()),
+            Network::Regtest,
+        )
+        .unwrap();
+        assert!(descriptor.starts_with("addr(ert1p"));
+
+        let decoded = parse_descriptor(&descriptor, Network::Regtest).unwrap();
+        assert_eq!(
+            decoded.script_pubkey(&secp).unwrap(),
+            tweaked_script_pubkey(&details)
+        );
+    }
+
+    #[rstest]
+    #[case::segwit_v0(OutputType::SegwitV0(()), "ert1q")]
+    #[case::compatibility(OutputType::Compatibility(()), "X")]
+    #[case::legacy(OutputType::Legacy(()), "X")]
+    fn test_legacy(#[case] output_type: OutputType<(), ()>, #[case] prefix: &str) {
+        let secp = Secp256k1::new();
+        let (claim_pubkey, refund_pubkey) = keys();
+        let script = swap_script(
+            preimage_hash(),
+            &claim_pubkey,
+            &refund_pubkey,
+            LockTime::from_height(515_924).unwrap(),
+        );
+
+        let (output_type, script_pubkey) = match output_type {
+            OutputType::SegwitV0(_) => (OutputType::SegwitV0(script.clone()), script.to_v0_p2wsh()),
+            OutputType::Compatibility(_) => (
+                OutputType::Compatibility(script.clone()),
+                script.to_v0_p2wsh().to_p2sh(),
+            ),
+            OutputType::Legacy(_) => (OutputType::Legacy(script.clone()), script.to_p2sh()),
+            OutputType::Taproot(_) => unreachable!(),
+        };
+
+        let descriptor = output_descriptor(&secp, &output_type, Network::Regtest).unwrap();
+        assert!(descriptor.starts_with(&format!("addr({prefix}")));
+
+        let decoded = parse_descriptor(&descriptor, Network::Regtest).unwrap();
+        assert_eq!(decoded.script_pubkey(&secp).unwrap(), script_pubkey);
+    }
+
+    #[test]
+    fn test_parse_descriptor_errors() {
+        let descriptor = output_descriptor(
+            &Secp256k1::new(),
+            &OutputType::Taproot(details(false, None)),
+            Network::Regtest,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            parse_descriptor(&descriptor, Network::Mainnet),
+            Err(DescriptorError::WrongNetwork)
+        ));
+        assert!(matches!(
+            parse_descriptor(&descriptor, Network::Signet),
+            Err(DescriptorError::Network(_))
+        ));
+        assert!(matches!(
+            parse_descriptor(&descriptor.replace("addr(", "addr( "), Network::Regtest),
+            Err(DescriptorError::Checksum)
+        ));
+        assert!(matches!(
+            parse_descriptor("raw(deadbeef)#89f8spxm", Network::Regtest),
+            Err(DescriptorError::Unsupported)
+        ));
+    }
+}
@@ -3,8 +3,14 @@
 //! Mirrors the layout of the `bitcoin` module with Elements-specific
 //! support for confidential outputs and asset introspection.
 //! [`construct_tx`] handles unblinding inputs, building confidential
-//! outputs, and signing; [`construct_asset_rescue`] recovers a non-L-BTC
-//! asset accidentally sent to a swap address.
+//! outputs, and signing; [`construct_multi_asset_tx`] does the same for
+//! inputs of several assets, and [`construct_asset_rescue`] recovers a non-L-BTC
+//! asset accidentally sent to a swap address. [`construct_pset`] and
+//! [`finalize_pset`] build the same transaction as an unsigned PSET for
+//! external signers and extract it once it is signed. [`decode_tree`] and
+//! [`decode_script`] recover the [`SwapParams`], including the covenant, a
+//! script was built from. [`output_descriptor`] exports a swap output as
+//! descriptor that wallets can import.
 
 use crate::utils::{InputType, OutputType, Transaction as TTransaction, TxIn as TTxIn};
 use elements::{
@@ -14,15 +20,20 @@ use elements::{
 };
 
 mod asset_rescue;
+mod descriptor;
+mod pset;
 mod scripts;
 mod tx;
 
 pub use asset_rescue::{AssetPair, AssetRescueError, construct_asset_rescue};
+pub use descriptor::{Descriptor, DescriptorError, output_descriptor, parse_descriptor};
+pub use pset::{construct_pset, finalize_pset};
 pub use scripts::{
-    ClaimCovenantParams, Tapleaf, Tree, TreeError, create_covenant_claim_leaf, reverse_script,
-    reverse_tree, swap_script, swap_tree,
+    ClaimCovenantParams, DecodeError, SwapParams, Tapleaf, Timelock, Tree, TreeError,
+    create_covenant_claim_leaf, decode_claim_leaf, decode_covenant_claim_leaf, decode_refund_leaf,
+    decode_script, decode_tree, reverse_script, reverse_tree, swap_script, swap_tree,
 };
-pub use tx::{TxError, construct_tx};
+pub use tx::{AssetDestination, TxError, construct_multi_asset_tx, construct_tx};
 
 /// Information needed to spend an Elements Taproot swap input via the
 /// script-path (uncooperative) leg, when the cooperative MuSig2 key-path
@@ -37,9 +48,13 @@ pub struct UncooperativeDetails {
 
 /// One UTXO to spend when constructing an Elements swap claim or refund
 /// transaction.
+///
+/// Signed by a local [`Keypair`] unless another [`Signer`](crate::Signer) is
+/// chosen for `S`.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct InputDetail {
-    /// Whether this input is being claimed (preimage) or refunded (locktime).
+pub struct InputDetail<S = Keypair> {
+    /// Whether this input is being claimed (preimage), refunded (locktime) or
+    /// is an [`InputType::Wallet`] UTXO that adds funds.
     pub input_type: InputType,
     /// The output script type, with the Taproot variant carrying optional
     /// uncooperative-spend details. `None` selects the key-path spend; `Some`
@@ -51,8 +66,24 @@ pub struct InputDetail {
     pub tx_out: TxOut,
     /// Optional blinding key, required to unblind a confidential `tx_out`.
     pub blinding_key: Option<Keypair>,
-    /// The keypair authorized to sign for this input.
-    pub keys: Keypair,
+    /// The signer authorized to sign for this input.
+    pub keys: S,
+}
+
+/// One UTXO to spend in a PSET built by [`construct_pset`], which is signed
+/// by an external wallet instead of a local [`Keypair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PsetInputDetail {
+    /// Whether this input is being claimed (preimage) or refunded (locktime).
+    pub input_type: InputType,
+    /// The output script type; see [`InputDetail::output_type`].
+    pub output_type: OutputType<Option<UncooperativeDetails>, Script>,
+    /// The outpoint of the UTXO being spent.
+    pub outpoint: OutPoint,
+    /// The previous output (`scriptPubKey` + commitments) being spent.
+    pub tx_out: TxOut,
+    /// Optional blinding key, required to unblind a confidential `tx_out`.
+    pub blinding_key: Option<Keypair>,
 }
 
 impl TTransaction for Transaction {
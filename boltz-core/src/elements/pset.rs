@@ -0,0 +1,658 @@
+use crate::{
+    consts::{PREIMAGE_DUMMY, STUB_SECRET_KEY},
+    elements::{
+        InputDetail, PsetInputDetail,
+        scripts::TreeError,
+        tx::{
+            AssetDestination, TxError, construct_raw, control_block, input_value,
+            nested_redeem_script, nested_script_sig, sort_inputs, spend_leaf, unblind_inputs,
+            unsigned_tx,
+        },
+    },
+    target_fee::{FeeTarget, target_fee},
+    utils::{Destination, InputType, OutputType},
+};
+use bitcoin::{PublicKey, bip32::KeySource};
+use elements::{
+    Address, BlockHash, EcdsaSighashType, Script, Transaction,
+    hashes::{Hash, hash160},
+    pset::{self, PartiallySignedTransaction},
+    script::Instruction,
+    secp256k1_zkp::{Keypair, Secp256k1, Signing, Verification},
+    taproot::{LeafVersion, TapLeafHash},
+};
+
+const PUBLIC_KEY_LENGTH: usize = 33;
+
+/// Build an unsigned PSET spending `inputs` to `destination`, paying `fee`
+/// for chain `genesis_hash`, for the inputs to be signed by an external
+/// wallet.
+///
+/// The outputs are blinded like in [`construct_tx`](crate::elements::construct_tx),
+/// so the PSET only lacks the signatures. Every input carries what is needed
+/// to sign and finalize it: the previous output, witness and redeem scripts,
+/// the preimage of claims and, for script-path spends, the leaf script,
+/// control block and internal key. Cooperative key-path spends only get their
+/// previous output, since their signature is created with MuSig2. Legacy
+/// inputs are rejected, because PSETs require their full previous transaction.
+/// Wallet inputs get their previous output and, for P2WPKH, the sighash type;
+/// adding the key origins is left to the wallet that owns them.
+///
+/// Returns the PSET and the fee that will be charged, in satoshis; the
+/// signed PSET can be turned into a transaction with [`finalize_pset`].
+#[must_use = "ignoring the result discards the constructed PSET"]
+pub fn construct_pset<C: Signing + Verification>(
+    secp: &Secp256k1<C>,
+    genesis_hash: BlockHash,
+    inputs: Vec<PsetInputDetail>,
+    destination: &Destination<&Address>,
+    fee: FeeTarget,
+) -> Result<(PartiallySignedTransaction, u64), TxError> {
+    // Signing with a stub key yields a transaction of the same size as the
+    // externally signed one, which is all that is needed for fee estimation
+    let stub_keys = Keypair::from_seckey_slice(secp, &STUB_SECRET_KEY)?;
+    let mut inputs = inputs
+        .into_iter()
+        .map(|input| InputDetail {
+            input_type: input.input_type,
+            output_type: input.output_type,
+            outpoint: input.outpoint,
+            tx_out: input.tx_out,
+            blinding_key: input.blinding_key,
+            keys: stub_keys,
+        })
+        .collect::<Vec<_>>();
+    sort_inputs(&mut inputs);
+    let inputs = inputs.as_slice();
+
+    let (unblinded, asset_id) = unblind_inputs(secp, inputs)?;
+    let destinations = [AssetDestination {
+        asset_id,
+        destination: *destination,
+    }];
+
+    let (tx, fee) = target_fee(
+        fee,
+        input_value(&unblinded, asset_id),
+        |fee, is_fee_estimation| {
+            if is_fee_estimation {
+                construct_raw(
+                    secp,
+                    genesis_hash,
+                    inputs,
+                    &unblinded,
+                    &destinations,
+                    asset_id,
+                    fee,
+                    true,
+                )
+            } else {
+                unsigned_tx(
+                    secp,
+                    inputs,
+                    &unblinded,
+                    &destinations,
+                    asset_id,
+                    fee,
+                    false,
+                )
+            }
+        },
+    )?;
+
+    let mut pset = PartiallySignedTransaction::from_tx(tx);
+    for ((i, input), pset_input) in inputs.iter().enumerate().zip(pset.inputs_mut()) {
+        populate_input(secp, i, input, pset_input)?;
+    }
+
+    Ok((pset, fee))
+}
+
+/// Finalize the inputs of a PSET created by [`construct_pset`] once it has
+/// been signed and extract the transaction.
+///
+/// Inputs that are already finalized are left untouched, which allows the
+/// cooperative key-path inputs to be finalized by the caller.
+pub fn finalize_pset(mut pset: PartiallySignedTransaction) -> Result<Transaction, TxError> {
+    for (i, input) in pset.inputs_mut().iter_mut().enumerate() {
+        if input.final_script_witness.is_some() || input.final_script_sig.is_some() {
+            continue;
+        }
+
+        let preimage = input.hash160_preimages.values().next().cloned();
+        let mut witness = Vec::new();
+
+        if let Some(sig) = input.tap_key_sig {
+            witness.push(sig.to_vec());
+        } else if let Some((control_block, (script, version))) = input.tap_scripts.iter().next() {
+            let leaf_hash = TapLeafHash::from_script(script, *version);
+            let sig = input
+                .tap_script_sigs
+                .iter()
+                .find(|((_, hash), _)| *hash == leaf_hash)
+                .map(|(_, sig)| sig)
+                .ok_or(TxError::MissingSignature(i))?;
+
+            witness.push(sig.to_vec());
+            if let Some(preimage) = preimage {
+                witness.push(preimage);
+            }
+            witness.push(script.to_bytes());
+            witness.push(control_block.serialize());
+        } else if let Some(witness_script) = &input.witness_script {
+            let sig = input
+                .partial_sigs
+                .values()
+                .next()
+                .ok_or(TxError::MissingSignature(i))?;
+
+            witness.push(sig.clone());
+            witness.push(preimage.unwrap_or(PREIMAGE_DUMMY.to_vec()));
+            witness.push(witness_script.to_bytes());
+
+            if input.redeem_script.is_some() {
+                input.final_script_sig = Some(nested_script_sig(witness_script));
+            }
+        } else if let Some((public_key, sig)) = input.partial_sigs.iter().next() {
+            // P2WPKH wallet inputs
+            witness.push(sig.clone());
+            witness.push(public_key.to_bytes());
+        } else {
+            return Err(TxError::MissingSpendInfo(i));
+        }
+
+        input.final_script_witness = Some(witness);
+
+        // The finalizer clears everything that was only needed for signing
+        input.partial_sigs.clear();
+        input.sighash_type = None;
+        input.redeem_script = None;
+        input.witness_script = None;
+        input.bip32_derivation.clear();
+        input.hash160_preimages.clear();
+        input.tap_key_sig = None;
+        input.tap_script_sigs.clear();
+        input.tap_scripts.clear();
+        input.tap_key_origins.clear();
+        input.tap_internal_key = None;
+        input.tap_merkle_root = None;
+    }
+
+    Ok(pset.extract_tx()?)
+}
+
+fn populate_input<C: Verification>(
+    secp: &Secp256k1<C>,
+    index: usize,
+    input: &InputDetail,
+    pset_input: &mut pset::Input,
+) -> Result<(), TxError> {
+    // Created from a transaction without scripts; set when finalizing
+    pset_input.final_script_sig = None;
+    pset_input.final_script_witness = None;
+    pset_input.witness_utxo = Some(input.tx_out.clone());
+
+    if input.input_type == InputType::Wallet {
+        if let OutputType::SegwitV0(_) = input.output_type {
+            pset_input.sighash_type = Some(EcdsaSighashType::All.into());
+        }
+
+        return Ok(());
+    }
+
+    if let InputType::Claim(preimage) = input.input_type {
+        pset_input
+            .hash160_preimages
+            .insert(hash160::Hash::hash(&preimage), preimage.to_vec());
+    }
+
+    match &input.output_type {
+        OutputType::Legacy(_) => return Err(TxError::UnsupportedPsetInput(index)),
+        OutputType::SegwitV0(witness_script) | OutputType::Compatibility(witness_script) => {
+            if let OutputType::Compatibility(_) = input.output_type {
+                pset_input.redeem_script = Some(nested_redeem_script(witness_script));
+            }
+
+            pset_input.witness_script = Some(witness_script.clone());
+            pset_input.sighash_type = Some(EcdsaSighashType::All.into());
+
+            if let Some(public_key) = script_public_key(witness_script, input.input_type) {
+                pset_input
+                    .bip32_derivation
+                    .insert(public_key, KeySource::default());
+            }
+        }
+        OutputType::Taproot(None) => {}
+        OutputType::Taproot(Some(uncooperative)) => {
+            let leaf = spend_leaf(input.input_type, &uncooperative.tree);
+            let (control_block, spend_info) = control_block(secp, index, uncooperative, leaf)?;
+
+            pset_input.tap_internal_key = Some(uncooperative.internal_key);
+            pset_input.tap_merkle_root = spend_info.merkle_root();
+            pset_input.tap_scripts.insert(
+                control_block,
+                (
+                    leaf.output.clone(),
+                    LeafVersion::from_u8(leaf.version).map_err(TreeError::Taproot)?,
+                ),
+            );
+
+            let public_key = match input.input_type {
+                InputType::Claim(_) => uncooperative.tree.claim_pubkey()?,
+                InputType::Refund(_) | InputType::RelativeRefund(_) => {
+                    uncooperative.tree.refund_pubkey()?
+                }
+                InputType::Wallet => unreachable!("wallet inputs have no swap tree"),
+            };
+            pset_input
+                .tap_key_origins
+                .insert(public_key, (vec![leaf.leaf_hash()?], KeySource::default()));
+        }
+    };
+
+    Ok(())
+}
+
+/// The public key of the claim or refund branch of a swap script; the claim
+/// key is pushed before the refund key in all of them
+fn script_public_key(script: &Script, input_type: InputType) -> Option<PublicKey> {
+    let mut keys = script
+        .instructions()
+        .flatten()
+        .filter_map(|inst| match inst {
+            Instruction::PushBytes(bytes) if bytes.len() == PUBLIC_KEY_LENGTH => {
+                PublicKey::from_slice(bytes).ok()
+            }
+            _ => None,
+        });
+
+    match input_type {
+        InputType::Claim(_) => keys.next(),
+        InputType::Refund(_) | InputType::RelativeRefund(_) => keys.last(),
+        InputType::Wallet => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{UncooperativeDetails, construct_tx, swap_script, swap_tree};
+    use bitcoin::key::rand::{self, RngCore};
+    use elements::{
+        AddressParams, AssetId, LockTime, OutPoint, SchnorrSig, SchnorrSighashType, TxOut,
+        TxOutWitness, Txid,
+        confidential::{Asset, Nonce, Value},
+        secp256k1_zkp::{All, Message, SecretKey},
+        sighash::{Prevouts, SighashCache},
+    };
+    use futures::executor::block_on;
+    use rstest::rstest;
+
+    const LOCK_TIME: u32 = 1_000;
+    const AMOUNT: u64 = 100_000;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Kind {
+        SegwitV0,
+        Compatibility,
+        Legacy,
+        Taproot,
+    }
+
+    fn genesis_hash() -> BlockHash {
+        BlockHash::from_byte_array([2; 32])
+    }
+
+    fn asset() -> AssetId {
+        AssetId::from_slice(&[3; 32]).unwrap()
+    }
+
+    fn input(secp: &Secp256k1<All>, kind: Kind, claim: bool) -> (PsetInputDetail, Keypair) {
+        let mut preimage = [0; 32];
+        rand::thread_rng().fill_bytes(&mut preimage);
+
+        let claim_keys = Keypair::new(secp, &mut rand::thread_rng());
+        let refund_keys = Keypair::new(secp, &mut rand::thread_rng());
+        let preimage_hash = hash160::Hash::hash(&preimage);
+        let lock_time = LockTime::from_height(LOCK_TIME).unwrap();
+
+        let (output_type, script_pubkey) = match kind {
+            Kind::Taproot => {
+                let tree = swap_tree(
+                    preimage_hash,
+                    &claim_keys.x_only_public_key().0,
+                    &refund_keys.x_only_public_key().0,
+                    lock_time,
+                );
+                let internal_key = Keypair::new(secp, &mut rand::thread_rng())
+                    .x_only_public_key()
+                    .0;
+                let spend_info = tree.build().unwrap().finalize(secp, internal_key).unwrap();
+
+                (
+                    OutputType::Taproot(Some(UncooperativeDetails { tree, internal_key })),
+                    Script::new_v1_p2tr_tweaked(spend_info.output_key()),
+                )
+            }
+            _ => {
+                let script = swap_script(
+                    preimage_hash,
+                    &claim_keys.public_key(),
+                    &refund_keys.public_key(),
+                    lock_time,
+                );
+
+                match kind {
+                    Kind::SegwitV0 => (
+                        OutputType::SegwitV0(script.clone()),
+                        Script::new_v0_wsh(&script.wscript_hash()),
+                    ),
+                    Kind::Compatibility => (
+                        OutputType::Compatibility(script.clone()),
+                        Script::new_p2sh(&nested_redeem_script(&script).script_hash()),
+                    ),
+                    _ => (
+                        OutputType::Legacy(script.clone()),
+                        Script::new_p2sh(&script.script_hash()),
+                    ),
+                }
+            }
+        };
+
+        let mut txid = [0; 32];
+        rand::thread_rng().fill_bytes(&mut txid);
+
+        (
+            PsetInputDetail {
+                input_type: if claim {
+                    InputType::Claim(preimage)
+                } else {
+                    InputType::Refund(LOCK_TIME)
+                },
+                output_type,
+                outpoint: OutPoint::new(Txid::from_byte_array(txid), 1),
+                tx_out: TxOut {
+                    asset: Asset::Explicit(asset()),
+                    value: Value::Explicit(AMOUNT),
+                    nonce: Nonce::Null,
+                    script_pubkey,
+                    witness: TxOutWitness::default(),
+                },
+                blinding_key: None,
+            },
+            if claim { claim_keys } else { refund_keys },
+        )
+    }
+
+    fn destination(secp: &Secp256k1<All>, blinding_key: Option<&SecretKey>) -> Address {
+        Address::p2wpkh(
+            &PublicKey::new(SecretKey::new(&mut rand::thread_rng()).public_key(secp)),
+            blinding_key.map(|key| key.public_key(secp)),
+            &AddressParams::ELEMENTS,
+        )
+    }
+
+    /// Signs like an external wallet would, with nothing but the PSET and the keys
+    fn sign(secp: &Secp256k1<All>, pset: &mut PartiallySignedTransaction, keys: &[Keypair]) {
+        let tx = pset.extract_tx().unwrap();
+        let prevouts = pset
+            .inputs()
+            .iter()
+            .map(|input| input.witness_utxo.clone().unwrap())
+            .collect::<Vec<_>>();
+        let mut cache = SighashCache::new(&tx);
+
+        for (i, input) in pset.inputs_mut().iter_mut().enumerate() {
+            if let Some((_, (script, version))) = input.tap_scripts.iter().next() {
+                let leaf_hash = TapLeafHash::from_script(script, *version);
+                let (public_key, _) = input
+                    .tap_key_origins
+                    .iter()
+                    .find(|(_, (leaf_hashes, _))| leaf_hashes.contains(&leaf_hash))
+                    .unwrap();
+                let keys = keys
+                    .iter()
+                    .find(|keys| keys.x_only_public_key().0 == *public_key)
+                    .unwrap();
+
+                let sighash = cache
+                    .taproot_script_spend_signature_hash(
+                        i,
+                        &Prevouts::All(&prevouts),
+                        leaf_hash,
+                        SchnorrSighashType::Default,
+                        genesis_hash(),
+                    )
+                    .unwrap();
+                input.tap_script_sigs.insert(
+                    (*public_key, leaf_hash),
+                    SchnorrSig {
+                        sig: secp
+                            .sign_schnorr(&Message::from_digest(sighash.to_byte_array()), keys),
+                        hash_ty: SchnorrSighashType::Default,
+                    },
+                );
+            } else if let Some(witness_script) = &input.witness_script {
+                let (public_key, _) = input.bip32_derivation.iter().next().unwrap();
+                let keys = keys
+                    .iter()
+                    .find(|keys| keys.public_key() == public_key.inner)
+                    .unwrap();
+
+                let sighash = cache.segwitv0_sighash(
+                    i,
+                    witness_script,
+                    input.witness_utxo.as_ref().unwrap().value,
+                    EcdsaSighashType::All,
+                );
+                let mut sig = secp
+                    .sign_ecdsa(
+                        &Message::from_digest(sighash.to_byte_array()),
+                        &keys.secret_key(),
+                    )
+                    .serialize_der()
+                    .to_vec();
+                sig.push(EcdsaSighashType::All.as_u32() as u8);
+
+                input.partial_sigs.insert(*public_key, sig);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::segwit_claim(Kind::SegwitV0, true)]
+    #[case::segwit_refund(Kind::SegwitV0, false)]
+    #[case::compatibility_claim(Kind::Compatibility, true)]
+    #[case::compatibility_refund(Kind::Compatibility, false)]
+    #[case::taproot_claim(Kind::Taproot, true)]
+    #[case::taproot_refund(Kind::Taproot, false)]
+    fn test_construct_finalize_pset(#[case] kind: Kind, #[case] claim: bool) {
+        let secp = Secp256k1::new();
+        let (input, keys) = input(&secp, kind, claim);
+        let destination = destination(&secp, None);
+
+        let (mut pset, fee) = construct_pset(
+            &secp,
+            genesis_hash(),
+            vec![input.clone()],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(0.1),
+        )
+        .unwrap();
+
+        let (expected, expected_fee) = block_on(construct_tx(
+            &secp,
+            genesis_hash(),
+            vec![InputDetail {
+                input_type: input.input_type,
+                output_type: input.output_type.clone(),
+                outpoint: input.outpoint,
+                tx_out: input.tx_out.clone(),
+                blinding_key: None,
+                keys,
+            }],
+            &Destination::Single(&destination),
+            FeeTarget::Relative(0.1),
+        ))
+        .unwrap();
+        assert_eq!(fee, expected_fee);
+
+        sign(&secp, &mut pset, &[keys]);
+        let tx = finalize_pset(pset).unwrap();
+
+        // Nothing is blinded, so everything but the signatures has to match
+        assert_eq!(tx.txid(), expected.txid());
+        assert_eq!(tx.input[0].script_sig, expected.input[0].script_sig);
+        assert_eq!(
+            tx.input[0].witness.script_witness[1..],
+            expected.input[0].witness.script_witness[1..]
+        );
+    }
+
+    #[test]
+    fn test_construct_pset_blinded_destination() {
+        let secp = Secp256k1::new();
+        let (input, keys) = input(&secp, Kind::Taproot, true);
+        let blinding_key = SecretKey::new(&mut rand::thread_rng());
+        let destination = destination(&secp, Some(&blinding_key));
+
+        let (mut pset, fee) = construct_pset(
+            &secp,
+            genesis_hash(),
+            vec![input],
+            &Destination::Single(&destination),
+            FeeTarget::Absolute(100),
+        )
+        .unwrap();
+
+        sign(&secp, &mut pset, &[keys]);
+        let tx = finalize_pset(pset).unwrap();
+
+        let output = tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == destination.script_pubkey())
+            .unwrap();
+        let secrets = output.unblind(&secp, blinding_key).unwrap();
+        assert_eq!(secrets.asset, asset());
+        assert_eq!(secrets.value, AMOUNT - fee);
+    }
+
+    #[test]
+    fn test_construct_pset_metadata() {
+        let secp = Secp256k1::new();
+        let (input, keys) = input(&secp, Kind::Compatibility, true);
+
+        let (pset, _) = construct_pset(
+            &secp,
+            genesis_hash(),
+            vec![input.clone()],
+            &Destination::Single(&destination(&secp, None)),
+            FeeTarget::Absolute(100),
+        )
+        .unwrap();
+
+        let pset_input = &pset.inputs()[0];
+        let script = match &input.output_type {
+            OutputType::Compatibility(script) => script,
+            _ => unreachable!(),
+        };
+        let preimage = match input.input_type {
+            InputType::Claim(preimage) => preimage,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(pset_input.witness_utxo, Some(input.tx_out.clone()));
+        assert_eq!(pset_input.witness_script, Some(script.clone()));
+        assert_eq!(pset_input.redeem_script, Some(nested_redeem_script(script)));
+        assert_eq!(
+            pset_input
+                .hash160_preimages
+                .get(&hash160::Hash::hash(&preimage)),
+            Some(&preimage.to_vec())
+        );
+        assert_eq!(
+            pset_input
+                .bip32_derivation
+                .keys()
+                .map(|key| key.inner)
+                .collect::<Vec<_>>(),
+            vec![keys.public_key()]
+        );
+        assert_eq!(pset_input.final_script_sig, None);
+        assert_eq!(pset_input.final_script_witness, None);
+
+        // The PSET has to survive being passed to an external signer
+        assert_eq!(
+            elements::encode::deserialize::<PartiallySignedTransaction>(
+                &elements::encode::serialize(&pset)
+            )
+            .unwrap(),
+            pset
+        );
+    }
+
+    #[test]
+    fn test_construct_pset_legacy() {
+        let secp = Secp256k1::new();
+        let (input, _) = input(&secp, Kind::Legacy, true);
+
+        assert!(matches!(
+            construct_pset(
+                &secp,
+                genesis_hash(),
+                vec![input],
+                &Destination::Single(&destination(&secp, None)),
+                FeeTarget::Absolute(100),
+            ),
+            Err(TxError::UnsupportedPsetInput(0))
+        ));
+    }
+
+    #[rstest]
+    #[case::segwit(Kind::SegwitV0)]
+    #[case::taproot(Kind::Taproot)]
+    fn test_finalize_pset_missing_signature(#[case] kind: Kind) {
+        let secp = Secp256k1::new();
+        let (input, _) = input(&secp, kind, false);
+
+        let (pset, _) = construct_pset(
+            &secp,
+            genesis_hash(),
+            vec![input],
+            &Destination::Single(&destination(&secp, None)),
+            FeeTarget::Absolute(100),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            finalize_pset(pset),
+            Err(TxError::MissingSignature(0))
+        ));
+    }
+
+    #[test]
+    fn test_finalize_pset_cooperative() {
+        let secp = Secp256k1::new();
+        let (mut input, _) = input(&secp, Kind::Taproot, true);
+        input.output_type = OutputType::Taproot(None);
+
+        let (mut pset, _) = construct_pset(
+            &secp,
+            genesis_hash(),
+            vec![input],
+            &Destination::Single(&destination(&secp, None)),
+            FeeTarget::Absolute(100),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            finalize_pset(pset.clone()),
+            Err(TxError::MissingSpendInfo(0))
+        ));
+
+        pset.inputs_mut()[0].tap_key_sig = Some(SchnorrSig::from_slice(&[1; 64]).unwrap());
+        let tx = finalize_pset(pset).unwrap();
+        assert_eq!(tx.input[0].witness.script_witness, vec![vec![1; 64]]);
+    }
+}
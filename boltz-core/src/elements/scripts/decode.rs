@@ -0,0 +1,682 @@
+use crate::{
+    elements::scripts::{
+        ClaimCovenantParams, Tapleaf, Timelock, Tree, introspection::PREIMAGE_SIZE,
+    },
+    utils::SwapKind,
+};
+use elements::{
+    Address, AddressParams, AssetId, LockTime, Script, Sequence,
+    hashes::{Hash, hash160},
+    opcodes::{
+        All,
+        all::{
+            OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_CSV, OP_DROP, OP_ELSE, OP_ENDIF, OP_EQUAL,
+            OP_EQUALVERIFY, OP_HASH160, OP_IF, OP_INSPECTOUTPUTASSET, OP_INSPECTOUTPUTSCRIPTPUBKEY,
+            OP_INSPECTOUTPUTVALUE, OP_PUSHNUM_1, OP_SIZE,
+        },
+    },
+    script::{Builder, Instruction, read_scriptint},
+    secp256k1_zkp::{PublicKey, XOnlyPublicKey},
+    taproot::TAPROOT_LEAF_TAPSCRIPT,
+};
+
+/// Errors returned by [`decode_tree`] and [`decode_script`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The claim leaf does not match the submarine or reverse swap template.
+    #[error("claim leaf does not match a swap template")]
+    UnknownClaimLeaf,
+    /// The refund leaf does not match the `OP_CLTV` or `OP_CSV` refund template.
+    #[error("refund leaf does not match a swap template")]
+    UnknownRefundLeaf,
+    /// The covenant claim leaf does not match the covenant template.
+    #[error("covenant claim leaf does not match a swap template")]
+    UnknownCovenantClaimLeaf,
+    /// The redeem script does not match the submarine or reverse swap template.
+    #[error("script does not match a swap template")]
+    UnknownScript,
+    /// A leaf is not a tapscript leaf.
+    #[error("unexpected leaf version: {0:#x}")]
+    LeafVersion(u8),
+    /// The covenant claim leaf commits to another preimage hash than the claim leaf.
+    #[error("covenant claim leaf has a different preimage hash")]
+    CovenantPreimageHashMismatch,
+    /// The covenant claim leaf is only part of reverse swap trees.
+    #[error("covenant claim leaf in a submarine swap tree")]
+    CovenantInSubmarineSwap,
+    /// The covenant pins a non-segwit output, of which it only commits to a hash.
+    #[error("covenant output is not a witness program")]
+    CovenantOutputNotWitnessProgram,
+    /// A pushed key is not a valid public key.
+    #[error(transparent)]
+    Pubkey(#[from] elements::secp256k1_zkp::UpstreamError),
+}
+
+/// Parameters of a swap, recovered from its script tree or redeem script.
+///
+/// `K` is [`XOnlyPublicKey`] for Taproot trees and [`PublicKey`] for legacy
+/// redeem scripts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapParams<K> {
+    /// Template the script matches.
+    pub kind: SwapKind,
+    /// HASH160 of the preimage that unlocks the claim path.
+    pub preimage_hash: hash160::Hash,
+    /// Key that signs the claim path.
+    pub claim_pubkey: K,
+    /// Key that signs the refund path.
+    pub refund_pubkey: K,
+    /// Lock time after which the refund path is spendable; always absolute
+    /// for legacy redeem scripts.
+    pub lock_time: Timelock,
+    /// Outputs pinned by the covenant claim leaf of a reverse swap tree.
+    ///
+    /// The covenant only commits to the `scriptPubKey`, so the
+    /// [`output`](ClaimCovenantParams::output) is recovered as unconfidential
+    /// address.
+    pub covenant: Option<ClaimCovenantParams>,
+}
+
+/// Recover the swap parameters from a Taproot swap tree built by
+/// [`swap_tree`](crate::elements::swap_tree) or [`reverse_tree`](crate::elements::reverse_tree).
+///
+/// `params` are the address parameters of the network the covenant output is on.
+pub fn decode_tree(
+    tree: &Tree,
+    params: &'static AddressParams,
+) -> Result<SwapParams<XOnlyPublicKey>, DecodeError> {
+    let (kind, preimage_hash, claim_pubkey) = decode_claim_leaf(&tree.claim_leaf)?;
+    let (refund_pubkey, lock_time) = decode_refund_leaf(&tree.refund_leaf)?;
+
+    let covenant = match &tree.covenant_claim_leaf {
+        Some(leaf) => {
+            if kind != SwapKind::Reverse {
+                return Err(DecodeError::CovenantInSubmarineSwap);
+            }
+
+            let (covenant_preimage_hash, covenant) = decode_covenant_claim_leaf(leaf, params)?;
+            if covenant_preimage_hash != preimage_hash {
+                return Err(DecodeError::CovenantPreimageHashMismatch);
+            }
+
+            Some(covenant)
+        }
+        None => None,
+    };
+
+    Ok(SwapParams {
+        kind,
+        preimage_hash,
+        claim_pubkey,
+        refund_pubkey,
+        lock_time,
+        covenant,
+    })
+}
+
+/// Recover the swap parameters from a legacy redeem script built by
+/// [`swap_script`](crate::elements::swap_script) or [`reverse_script`](crate::elements::reverse_script).
+pub fn decode_script(script: &Script) -> Result<SwapParams<PublicKey>, DecodeError> {
+    let params = match_script(script).ok_or(DecodeError::UnknownScript)?;
+
+    Ok(SwapParams {
+        kind: params.kind,
+        preimage_hash: params.preimage_hash,
+        claim_pubkey: PublicKey::from_slice(params.claim_pubkey)?,
+        refund_pubkey: PublicKey::from_slice(params.refund_pubkey)?,
+        lock_time: params.lock_time,
+        covenant: None,
+    })
+}
+
+/// Decode the claim leaf into the swap kind, preimage hash and claim key.
+pub fn decode_claim_leaf(
+    leaf: &Tapleaf,
+) -> Result<(SwapKind, hash160::Hash, XOnlyPublicKey), DecodeError> {
+    check_leaf_version(leaf)?;
+
+    let (kind, preimage_hash, claim_pubkey) =
+        match_claim_leaf(&leaf.output).ok_or(DecodeError::UnknownClaimLeaf)?;
+
+    Ok((
+        kind,
+        hash160::Hash::from_slice(preimage_hash).expect("push has hash length"),
+        XOnlyPublicKey::from_slice(claim_pubkey)?,
+    ))
+}
+
+/// Decode the refund leaf into the refund key and lock time.
+pub fn decode_refund_leaf(leaf: &Tapleaf) -> Result<(XOnlyPublicKey, Timelock), DecodeError> {
+    check_leaf_version(leaf)?;
+
+    let (refund_pubkey, lock_time) =
+        match_refund_leaf(&leaf.output).ok_or(DecodeError::UnknownRefundLeaf)?;
+
+    Ok((XOnlyPublicKey::from_slice(refund_pubkey)?, lock_time))
+}
+
+/// Decode the covenant claim leaf into its preimage hash and the outputs it pins.
+pub fn decode_covenant_claim_leaf(
+    leaf: &Tapleaf,
+    params: &'static AddressParams,
+) -> Result<(hash160::Hash, ClaimCovenantParams), DecodeError> {
+    check_leaf_version(leaf)?;
+
+    let covenant =
+        match_covenant_claim_leaf(&leaf.output).ok_or(DecodeError::UnknownCovenantClaimLeaf)?;
+
+    let script_pubkey = match covenant.witness_version {
+        0 => Builder::new().push_int(0),
+        1 => Builder::new().push_opcode(OP_PUSHNUM_1),
+        _ => return Err(DecodeError::CovenantOutputNotWitnessProgram),
+    }
+    .push_slice(covenant.program)
+    .into_script();
+
+    Ok((
+        covenant.preimage_hash,
+        ClaimCovenantParams {
+            index: covenant.index,
+            output: Address::from_script(&script_pubkey, None, params)
+                .ok_or(DecodeError::UnknownCovenantClaimLeaf)?,
+            asset_id: covenant.asset_id,
+            expected_amount: covenant.expected_amount,
+        },
+    ))
+}
+
+fn check_leaf_version(leaf: &Tapleaf) -> Result<(), DecodeError> {
+    if leaf.version != TAPROOT_LEAF_TAPSCRIPT {
+        return Err(DecodeError::LeafVersion(leaf.version));
+    }
+
+    Ok(())
+}
+
+/// Match a claim leaf and return its kind, preimage hash and claim key
+fn match_claim_leaf(script: &Script) -> Option<(SwapKind, &[u8], &[u8])> {
+    let mut cursor = Cursor::new(script)?;
+    let kind = if cursor.op(OP_SIZE).is_some() {
+        cursor.num(PREIMAGE_SIZE)?;
+        cursor.op(OP_EQUALVERIFY)?;
+        SwapKind::Reverse
+    } else {
+        SwapKind::Submarine
+    };
+
+    cursor.op(OP_HASH160)?;
+    let preimage_hash = cursor.push(20)?;
+    cursor.op(OP_EQUALVERIFY)?;
+    let claim_pubkey = cursor.push(32)?;
+    cursor.op(OP_CHECKSIG)?;
+    cursor.end()?;
+
+    Some((kind, preimage_hash, claim_pubkey))
+}
+
+/// Match an `OP_CLTV` or `OP_CSV` refund leaf and return its refund key and lock time
+fn match_refund_leaf(script: &Script) -> Option<(&[u8], Timelock)> {
+    let mut cursor = Cursor::new(script)?;
+    let refund_pubkey = cursor.push(32)?;
+    cursor.op(OP_CHECKSIGVERIFY)?;
+    let lock_time = cursor.uint()?;
+    let lock_time = if cursor.op(OP_CLTV).is_some() {
+        Timelock::Absolute(LockTime::from_consensus(lock_time))
+    } else {
+        cursor.op(OP_CSV)?;
+        Timelock::Relative(Sequence::from_consensus(lock_time))
+    };
+    cursor.end()?;
+
+    Some((refund_pubkey, lock_time))
+}
+
+/// Fields of a covenant claim leaf, before the pinned output is turned into an address
+struct CovenantLeaf<'a> {
+    preimage_hash: hash160::Hash,
+    index: u32,
+    witness_version: i64,
+    program: &'a [u8],
+    asset_id: AssetId,
+    expected_amount: u64,
+}
+
+fn match_covenant_claim_leaf(script: &Script) -> Option<CovenantLeaf<'_>> {
+    let mut cursor = Cursor::new(script)?;
+    cursor.op(OP_SIZE)?;
+    cursor.num(PREIMAGE_SIZE)?;
+    cursor.op(OP_EQUALVERIFY)?;
+    cursor.op(OP_HASH160)?;
+    let preimage_hash = cursor.push(20)?;
+    cursor.op(OP_EQUALVERIFY)?;
+
+    let index = cursor.any_num()?;
+    cursor.op(OP_INSPECTOUTPUTSCRIPTPUBKEY)?;
+    let witness_version = cursor.any_num()?;
+    cursor.op(OP_EQUALVERIFY)?;
+    let program = cursor.any_push()?;
+    cursor.op(OP_EQUALVERIFY)?;
+
+    cursor.num(index)?;
+    cursor.op(OP_INSPECTOUTPUTASSET)?;
+    cursor.op(OP_PUSHNUM_1)?;
+    cursor.op(OP_EQUALVERIFY)?;
+    let asset_id = cursor.push(32)?;
+    cursor.op(OP_EQUALVERIFY)?;
+
+    cursor.num(index)?;
+    cursor.op(OP_INSPECTOUTPUTVALUE)?;
+    cursor.op(OP_DROP)?;
+    let expected_amount = cursor.push(8)?;
+    cursor.op(OP_EQUAL)?;
+    cursor.end()?;
+
+    Some(CovenantLeaf {
+        preimage_hash: hash160::Hash::from_slice(preimage_hash).expect("push has hash length"),
+        index: u32::try_from(index).ok()?,
+        witness_version,
+        program,
+        asset_id: elements::encode::deserialize(asset_id).ok()?,
+        expected_amount: u64::from_le_bytes(expected_amount.try_into().ok()?),
+    })
+}
+
+/// Match a legacy redeem script; the keys are returned unparsed
+fn match_script(script: &Script) -> Option<SwapParams<&[u8]>> {
+    let mut cursor = Cursor::new(script)?;
+    let kind = if cursor.op(OP_SIZE).is_some() {
+        cursor.num(PREIMAGE_SIZE)?;
+        cursor.op(OP_EQUAL)?;
+        cursor.op(OP_IF)?;
+        cursor.op(OP_HASH160)?;
+        SwapKind::Reverse
+    } else {
+        cursor.op(OP_HASH160)?;
+        SwapKind::Submarine
+    };
+
+    let preimage_hash = cursor.push(20)?;
+    match kind {
+        SwapKind::Submarine => {
+            cursor.op(OP_EQUAL)?;
+            cursor.op(OP_IF)?;
+        }
+        SwapKind::Reverse => {
+            cursor.op(OP_EQUALVERIFY)?;
+        }
+    }
+    let claim_pubkey = cursor.push(33)?;
+    cursor.op(OP_ELSE)?;
+    if kind == SwapKind::Reverse {
+        cursor.op(OP_DROP)?;
+    }
+    let lock_time = cursor.lock_time()?;
+    cursor.op(OP_CLTV)?;
+    cursor.op(OP_DROP)?;
+    let refund_pubkey = cursor.push(33)?;
+    cursor.op(OP_ENDIF)?;
+    cursor.op(OP_CHECKSIG)?;
+    cursor.end()?;
+
+    Some(SwapParams {
+        kind,
+        preimage_hash: hash160::Hash::from_slice(preimage_hash).expect("push has hash length"),
+        claim_pubkey,
+        refund_pubkey,
+        lock_time: Timelock::Absolute(lock_time),
+        covenant: None,
+    })
+}
+
+/// Walks the instructions of a script, matching them one at a time
+struct Cursor<'a> {
+    instructions: std::vec::IntoIter<Instruction<'a>>,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(script: &'a Script) -> Option<Self> {
+        Some(Self {
+            instructions: script
+                .instructions()
+                .collect::<Result<Vec<_>, _>>()
+                .ok()?
+                .into_iter(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Instruction<'a>> {
+        self.instructions.as_slice().first()
+    }
+
+    fn op(&mut self, op: All) -> Option<()> {
+        match self.peek()? {
+            Instruction::Op(next) if *next == op => {
+                self.instructions.next();
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, len: usize) -> Option<&'a [u8]> {
+        match self.peek()? {
+            Instruction::PushBytes(bytes) if bytes.len() == len => {
+                let bytes = *bytes;
+                self.instructions.next();
+                Some(bytes)
+            }
+            _ => None,
+        }
+    }
+
+    fn any_push(&mut self) -> Option<&'a [u8]> {
+        match self.instructions.next()? {
+            Instruction::PushBytes(bytes) => Some(bytes),
+            Instruction::Op(_) => None,
+        }
+    }
+
+    fn num(&mut self, expected: i64) -> Option<()> {
+        (self.any_num()? == expected).then_some(())
+    }
+
+    fn any_num(&mut self) -> Option<i64> {
+        match self.instructions.next()? {
+            Instruction::PushBytes(bytes) => read_scriptint(bytes).ok(),
+            Instruction::Op(op) => match op.into_u8() {
+                // OP_PUSHNUM_1 to OP_PUSHNUM_16
+                code @ 0x51..=0x60 => Some(i64::from(code) - 0x50),
+                // OP_PUSHNUM_NEG1
+                0x4f => Some(-1),
+                _ => None,
+            },
+        }
+    }
+
+    fn uint(&mut self) -> Option<u32> {
+        u32::try_from(self.any_num()?).ok()
+    }
+
+    fn lock_time(&mut self) -> Option<LockTime> {
+        Some(LockTime::from_consensus(self.uint()?))
+    }
+
+    fn end(&mut self) -> Option<()> {
+        self.instructions.next().is_none().then_some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elements::{reverse_script, reverse_tree, swap_script, swap_tree},
+        utils::InputType,
+    };
+    use rstest::rstest;
+    use std::str::FromStr;
+
+    fn preimage_hash() -> hash160::Hash {
+        hash160::Hash::from_str("0be3e65567f55ff6ac791bd4f65f672bcaf5f211").unwrap()
+    }
+
+    fn keys() -> (PublicKey, PublicKey) {
+        (
+            PublicKey::from_str(
+                "03f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539",
+            )
+            .unwrap(),
+            PublicKey::from_str(
+                "03ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn covenant(script_pubkey: &str) -> ClaimCovenantParams {
+        ClaimCovenantParams {
+            index: 0,
+            output: Address::from_script(
+                &Script::from(hex::decode(script_pubkey).unwrap()),
+                None,
+                &AddressParams::ELEMENTS,
+            )
+            .unwrap(),
+            asset_id: AssetId::from_str(
+                "5ac9f65c0efcc4775e0baec4ec03abdde22473cd3cf33c0419ca290e0751b225",
+            )
+            .unwrap(),
+            expected_amount: 123_321,
+        }
+    }
+
+    #[rstest]
+    #[case::swap(SwapKind::Submarine, 10, None)]
+    #[case::swap_high_lock_time(SwapKind::Submarine, 515_924, None)]
+    #[case::reverse(SwapKind::Reverse, 16, None)]
+    #[case::reverse_high_lock_time(SwapKind::Reverse, 515_924, None)]
+    #[case::reverse_covenant_segwit_v0(
+        SwapKind::Reverse,
+        515_924,
+        Some("0014751e76e8199196d454941c45d1b3a323f1433bd6")
+    )]
+    #[case::reverse_covenant_taproot(
+        SwapKind::Reverse,
+        515_924,
+        Some("5120f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539")
+    )]
+    fn test_decode_tree(
+        #[case] kind: SwapKind,
+        #[case] lock_time: u32,
+        #[case] covenant_output: Option<&str>,
+    ) {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let (claim_pubkey, refund_pubkey) = (
+            claim_pubkey.x_only_public_key().0,
+            refund_pubkey.x_only_public_key().0,
+        );
+        let lock_time = LockTime::from_height(lock_time).unwrap();
+        let covenant = covenant_output.map(covenant);
+
+        let tree = match kind {
+            SwapKind::Submarine => {
+                swap_tree(preimage_hash(), &claim_pubkey, &refund_pubkey, lock_time)
+            }
+            SwapKind::Reverse => reverse_tree(
+                preimage_hash(),
+                &claim_pubkey,
+                &refund_pubkey,
+                lock_time,
+                covenant.as_ref(),
+            ),
+        };
+
+        assert_eq!(
+            decode_tree(&tree, &AddressParams::ELEMENTS).unwrap(),
+            SwapParams {
+                kind,
+                preimage_hash: preimage_hash(),
+                claim_pubkey,
+                refund_pubkey,
+                lock_time: Timelock::Absolute(lock_time),
+                covenant,
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::swap(SwapKind::Submarine, Sequence::from_height(144))]
+    #[case::reverse(SwapKind::Reverse, Sequence::from_height(4_032))]
+    #[case::reverse_time(SwapKind::Reverse, Sequence::from_512_second_intervals(42))]
+    fn test_decode_tree_relative(#[case] kind: SwapKind, #[case] sequence: Sequence) {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let (claim_pubkey, refund_pubkey) = (
+            claim_pubkey.x_only_public_key().0,
+            refund_pubkey.x_only_public_key().0,
+        );
+
+        let tree = match kind {
+            SwapKind::Submarine => {
+                swap_tree(preimage_hash(), &claim_pubkey, &refund_pubkey, sequence)
+            }
+            SwapKind::Reverse => reverse_tree(
+                preimage_hash(),
+                &claim_pubkey,
+                &refund_pubkey,
+                sequence,
+                None,
+            ),
+        };
+
+        let params = decode_tree(&tree, &AddressParams::ELEMENTS).unwrap();
+        assert_eq!(params.kind, kind);
+        assert_eq!(params.lock_time, Timelock::Relative(sequence));
+        assert_eq!(
+            params.lock_time.refund_input_type(),
+            InputType::RelativeRefund(sequence.to_consensus_u32())
+        );
+    }
+
+    #[test]
+    fn test_decode_tree_covenant_not_witness_program() {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let tree = reverse_tree(
+            preimage_hash(),
+            &claim_pubkey.x_only_public_key().0,
+            &refund_pubkey.x_only_public_key().0,
+            LockTime::from_height(123).unwrap(),
+            Some(&covenant("a914751e76e8199196d454941c45d1b3a323f1433bd687")),
+        );
+
+        assert_eq!(
+            decode_tree(&tree, &AddressParams::ELEMENTS).unwrap_err(),
+            DecodeError::CovenantOutputNotWitnessProgram
+        );
+    }
+
+    #[test]
+    fn test_decode_tree_covenant_mismatch() {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let (claim_pubkey, refund_pubkey) = (
+            claim_pubkey.x_only_public_key().0,
+            refund_pubkey.x_only_public_key().0,
+        );
+        let lock_time = LockTime::from_height(123).unwrap();
+        let covenant = covenant("0014751e76e8199196d454941c45d1b3a323f1433bd6");
+
+        let mut tree = reverse_tree(
+            hash160::Hash::from_str("761de3d1f1f54cc8b3beec0a1ad03820a2e12b90").unwrap(),
+            &claim_pubkey,
+            &refund_pubkey,
+            lock_time,
+            Some(&covenant),
+        );
+        tree.claim_leaf = reverse_tree(
+            preimage_hash(),
+            &claim_pubkey,
+            &refund_pubkey,
+            lock_time,
+            None,
+        )
+        .claim_leaf;
+        assert_eq!(
+            decode_tree(&tree, &AddressParams::ELEMENTS).unwrap_err(),
+            DecodeError::CovenantPreimageHashMismatch
+        );
+
+        let mut tree = swap_tree(preimage_hash(), &claim_pubkey, &refund_pubkey, lock_time);
+        tree.covenant_claim_leaf = Some(crate::elements::create_covenant_claim_leaf(
+            preimage_hash(),
+            &covenant,
+        ));
+        assert_eq!(
+            decode_tree(&tree, &AddressParams::ELEMENTS).unwrap_err(),
+            DecodeError::CovenantInSubmarineSwap
+        );
+    }
+
+    #[test]
+    fn test_decode_tree_unknown_leaves() {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let tree = swap_tree(
+            preimage_hash(),
+            &claim_pubkey.x_only_public_key().0,
+            &refund_pubkey.x_only_public_key().0,
+            LockTime::from_height(123).unwrap(),
+        );
+
+        let mut swapped = tree.clone();
+        std::mem::swap(&mut swapped.claim_leaf, &mut swapped.refund_leaf);
+        assert_eq!(
+            decode_tree(&swapped, &AddressParams::ELEMENTS).unwrap_err(),
+            DecodeError::UnknownClaimLeaf
+        );
+
+        let mut wrong_covenant = tree.clone();
+        wrong_covenant.covenant_claim_leaf = Some(tree.claim_leaf.clone());
+        wrong_covenant.claim_leaf = reverse_tree(
+            preimage_hash(),
+            &claim_pubkey.x_only_public_key().0,
+            &refund_pubkey.x_only_public_key().0,
+            LockTime::from_height(123).unwrap(),
+            None,
+        )
+        .claim_leaf;
+        assert_eq!(
+            decode_tree(&wrong_covenant, &AddressParams::ELEMENTS).unwrap_err(),
+            DecodeError::UnknownCovenantClaimLeaf
+        );
+
+        let mut wrong_version = tree;
+        wrong_version.refund_leaf.version = 0xc0;
+        assert_eq!(
+            decode_tree(&wrong_version, &AddressParams::ELEMENTS).unwrap_err(),
+            DecodeError::LeafVersion(0xc0)
+        );
+    }
+
+    #[rstest]
+    #[case::swap(SwapKind::Submarine, 10)]
+    #[case::swap_high_lock_time(SwapKind::Submarine, 515_924)]
+    #[case::reverse(SwapKind::Reverse, 10)]
+    #[case::reverse_high_lock_time(SwapKind::Reverse, 515_924)]
+    fn test_decode_script(#[case] kind: SwapKind, #[case] lock_time: u32) {
+        let (claim_pubkey, refund_pubkey) = keys();
+        let lock_time = LockTime::from_height(lock_time).unwrap();
+
+        let build = match kind {
+            SwapKind::Submarine => swap_script,
+            SwapKind::Reverse => reverse_script,
+        };
+        let script = build(preimage_hash(), &claim_pubkey, &refund_pubkey, lock_time);
+
+        assert_eq!(
+            decode_script(&script).unwrap(),
+            SwapParams {
+                kind,
+                preimage_hash: preimage_hash(),
+                claim_pubkey,
+                refund_pubkey,
+                lock_time: Timelock::Absolute(lock_time),
+                covenant: None,
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::p2wpkh("0014751e76e8199196d454941c45d1b3a323f1433bd6")]
+    #[case::trailing_opcode(
+        "a914e2ac8cb97af3d59b1c057db4b0c4f9aa12a9127387632103f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539670354df07b1752103ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a68acac"
+    )]
+    #[case::truncated(
+        "a914e2ac8cb97af3d59b1c057db4b0c4f9aa12a9127387632103f8109578aae1e5cfc497e466cf6ae6625497cd31886e87b2f4f54f3f0f46b539670354df07b1752103ec0c1e45b709d708cd376a6f2daf19ac27be229647780d592e27d7fb7efb207a68"
+    )]
+    fn test_decode_script_unknown(#[case] script: &str) {
+        assert_eq!(
+            decode_script(&Script::from(hex::decode(script).unwrap())).unwrap_err(),
+            DecodeError::UnknownScript
+        );
+    }
+}
@@ -1,14 +1,21 @@
+mod decode;
 mod introspection;
 mod reverse_script;
 mod reverse_tree;
 mod swap_script;
 mod swap_tree;
+mod timelock;
 mod tree;
 mod utils;
 
+pub use decode::{
+    DecodeError, SwapParams, decode_claim_leaf, decode_covenant_claim_leaf, decode_refund_leaf,
+    decode_script, decode_tree,
+};
 pub use introspection::{ClaimCovenantParams, create_covenant_claim_leaf};
 pub use reverse_script::reverse_script;
 pub use reverse_tree::reverse_tree;
 pub use swap_script::swap_script;
 pub use swap_tree::swap_tree;
+pub use timelock::Timelock;
 pub use tree::{Tapleaf, Tree, TreeError};
@@ -1,12 +1,11 @@
 use crate::elements::{
-    Tapleaf, Tree,
+    Tapleaf, Timelock, Tree,
     scripts::{
         introspection::{ClaimCovenantParams, PREIMAGE_SIZE, create_covenant_claim_leaf},
         swap_tree::refund_leaf,
     },
 };
 use elements::{
-    LockTime,
     hashes::hash160,
     opcodes::all::{OP_CHECKSIG, OP_EQUALVERIFY, OP_HASH160, OP_SIZE},
     pset::serialize::Serialize,
@@ -32,7 +31,7 @@ pub fn reverse_tree(
     preimage_hash: hash160::Hash,
     claim_pubkey: &XOnlyPublicKey,
     refund_pubkey: &XOnlyPublicKey,
-    lock_time: LockTime,
+    lock_time: impl Into<Timelock>,
     claim_covenant_params: Option<&ClaimCovenantParams>,
 ) -> Tree {
     Tree {
@@ -49,7 +48,7 @@ pub fn reverse_tree(
                 .push_opcode(OP_CHECKSIG)
                 .into_script(),
         },
-        refund_leaf: refund_leaf(refund_pubkey, lock_time),
+        refund_leaf: refund_leaf(refund_pubkey, lock_time.into()),
         covenant_claim_leaf: claim_covenant_params
             .map(|params| create_covenant_claim_leaf(preimage_hash, params)),
     }
@@ -58,7 +57,7 @@ pub fn reverse_tree(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use elements::{Address, AssetId};
+    use elements::{Address, AssetId, LockTime};
     use std::str::FromStr;
 
     #[test]
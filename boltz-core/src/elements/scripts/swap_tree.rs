@@ -1,8 +1,7 @@
-use crate::elements::{Tapleaf, Tree};
+use crate::elements::{Tapleaf, Timelock, Tree};
 use elements::{
-    LockTime,
     hashes::hash160,
-    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_EQUALVERIFY, OP_HASH160},
+    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_CSV, OP_EQUALVERIFY, OP_HASH160},
     pset::serialize::Serialize,
     script::Builder,
     secp256k1_zkp::XOnlyPublicKey,
@@ -11,11 +10,14 @@ use elements::{
 
 /// Build the Elements Taproot script tree for a submarine swap (the
 /// Liquid analogue of `bitcoin::swap_tree`).
+///
+/// An absolute [`LockTime`](elements::LockTime) builds an `OP_CLTV` refund
+/// leaf and a relative [`Sequence`](elements::Sequence) an `OP_CSV` one.
 pub fn swap_tree(
     preimage_hash: hash160::Hash,
     claim_pubkey: &XOnlyPublicKey,
     refund_pubkey: &XOnlyPublicKey,
-    lock_time: LockTime,
+    lock_time: impl Into<Timelock>,
 ) -> Tree {
     Tree {
         claim_leaf: Tapleaf {
@@ -28,26 +30,34 @@ pub fn swap_tree(
                 .push_opcode(OP_CHECKSIG)
                 .into_script(),
         },
-        refund_leaf: refund_leaf(refund_pubkey, lock_time),
+        refund_leaf: refund_leaf(refund_pubkey, lock_time.into()),
         covenant_claim_leaf: None,
     }
 }
 
-pub fn refund_leaf(refund_pubkey: &XOnlyPublicKey, lock_time: LockTime) -> Tapleaf {
+pub fn refund_leaf(refund_pubkey: &XOnlyPublicKey, lock_time: Timelock) -> Tapleaf {
+    let builder = Builder::new()
+        .push_slice(&refund_pubkey.serialize())
+        .push_opcode(OP_CHECKSIGVERIFY);
+
     Tapleaf {
         version: TAPROOT_LEAF_TAPSCRIPT,
-        output: Builder::new()
-            .push_slice(&refund_pubkey.serialize())
-            .push_opcode(OP_CHECKSIGVERIFY)
-            .push_int(lock_time.to_consensus_u32().into())
-            .push_opcode(OP_CLTV)
-            .into_script(),
+        output: match lock_time {
+            Timelock::Absolute(lock_time) => builder
+                .push_int(lock_time.to_consensus_u32().into())
+                .push_opcode(OP_CLTV),
+            Timelock::Relative(sequence) => builder
+                .push_int(sequence.to_consensus_u32().into())
+                .push_opcode(OP_CSV),
+        }
+        .into_script(),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use elements::{LockTime, Sequence};
     use std::str::FromStr;
 
     #[test]
@@ -83,4 +93,25 @@ mod tests {
             "206ecd3e58ebe0a558badb9a083a365530e1de168dfa410233433f2b2e3c7f4438ad03b9e101b1"
         );
     }
+
+    #[test]
+    fn test_swap_tree_relative_refund() {
+        let refund_pubkey = XOnlyPublicKey::from_str(
+            "6ecd3e58ebe0a558badb9a083a365530e1de168dfa410233433f2b2e3c7f4438",
+        )
+        .unwrap();
+
+        let tree = swap_tree(
+            hash160::Hash::from_str("0be3e65567f55ff6ac791bd4f65f672bcaf5f211").unwrap(),
+            &refund_pubkey,
+            &refund_pubkey,
+            Sequence::from_height(144),
+        );
+
+        assert_eq!(tree.refund_leaf.version, 196);
+        assert_eq!(
+            hex::encode(tree.refund_leaf.output.as_bytes()),
+            "206ecd3e58ebe0a558badb9a083a365530e1de168dfa410233433f2b2e3c7f4438ad029000b2"
+        );
+    }
 }
@@ -1,14 +1,15 @@
 use crate::{
-    consts::{ECDSA_BYTES_TO_GRIND, PREIMAGE_DUMMY, STUB_SCHNORR_SIGNATURE_LENGTH},
-    elements::{InputDetail, scripts::TreeError},
-    target_fee::{FeeError, FeeTarget, target_fee},
+    consts::{PREIMAGE_DUMMY, STUB_SCHNORR_SIGNATURE_LENGTH, STUB_SECRET_KEY},
+    elements::{InputDetail, Tapleaf, Tree, UncooperativeDetails, scripts::TreeError},
+    signer::{Signer, SignerError, resolve_local},
+    target_fee::{FeeBoundsError, FeeError, FeeTarget, target_fee},
     utils::{Destination, InputType, OutputType},
 };
 use bitcoin::Witness;
 use elements::{
-    Address, AssetId, BlockHash, EcdsaSighashType, LockTime, RangeProofMessage, SchnorrSig,
-    SchnorrSighashType, Script, Sequence, Sighash, SurjectionInput, Transaction, TxIn, TxOut,
-    TxOutSecrets, TxOutWitness,
+    Address, AssetId, BlockHash, EcdsaSighashType, LockTime, OutPoint, PubkeyHash,
+    RangeProofMessage, SchnorrSig, SchnorrSighashType, Script, Sequence, Sighash, SurjectionInput,
+    Transaction, TxIn, TxOut, TxOutSecrets, TxOutWitness,
     confidential::{Asset, AssetBlindingFactor, Nonce, Value, ValueBlindingFactor},
     hashes::{Hash, sha256},
     opcodes::all::{OP_PUSHBYTES_0, OP_RETURN},
@@ -17,7 +18,7 @@ use elements::{
         Keypair, Message, PublicKey, Secp256k1, SecretKey, Signing, Verification, rand,
     },
     sighash::{Prevouts, SighashCache},
-    taproot::LeafVersion,
+    taproot::{ControlBlock, LeafVersion, TaprootSpendInfo},
 };
 
 const SIGHASH_TYPE_LEGACY: EcdsaSighashType = EcdsaSighashType::All;
@@ -25,7 +26,8 @@ const SIGHASH_TYPE_TAPROOT: SchnorrSighashType = SchnorrSighashType::Default;
 
 const DUMMY_BLINDED_OUTPUT: u64 = 1;
 
-/// Errors returned by Elements [`construct_tx`].
+/// Errors returned by Elements [`construct_tx`], [`construct_multi_asset_tx`], [`construct_pset`](crate::elements::construct_pset)
+/// and [`finalize_pset`](crate::elements::finalize_pset).
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum TxError {
@@ -38,15 +40,31 @@ pub enum TxError {
     /// The relative fee rate is non-finite or negative.
     #[error("invalid fee rate")]
     InvalidFeeRate,
+    /// The fee of a [`FeeTarget::Bounded`] is outside of its bounds.
+    #[error(transparent)]
+    FeeBounds(#[from] FeeBoundsError),
     /// Called with an empty `inputs` slice.
     #[error("inputs must not be empty")]
     EmptyInputs,
     /// An internal output index was out of range during blinding (a builder-state bug).
     #[error("output index {0} out of range")]
     OutputIndexOutOfBounds(usize),
-    /// Inputs span more than one asset (only single-asset spends are supported here).
+    /// Inputs span more than one asset; use [`construct_multi_asset_tx`] for those.
     #[error("all inputs must have the same asset")]
     MixedAssets,
+    /// The fee input of [`construct_multi_asset_tx`] is not one of its inputs.
+    #[error("fee input is not one of the inputs")]
+    MissingFeeInput,
+    /// The fee input of [`construct_multi_asset_tx`] is not of the policy asset.
+    #[error("fee input has asset {0} instead of the policy asset")]
+    FeeInputNotPolicyAsset(AssetId),
+    /// Inputs of the named asset have no [`AssetDestination`].
+    #[error("no destination for asset {0}")]
+    MissingAssetDestination(AssetId),
+    /// The [`AssetDestination`] of the named asset is duplicated or there are
+    /// no inputs of that asset.
+    #[error("destination for asset {0} is duplicated or has no inputs")]
+    InvalidAssetDestination(AssetId),
     /// The named confidential input did not carry a blinding key.
     #[error("input {0} has no blinding key")]
     MissingBlindingKey(usize),
@@ -62,6 +80,25 @@ pub enum TxError {
     /// No control block was found for the named input's spend leaf.
     #[error("could not create control block for input {0}")]
     ControlBlock(usize),
+    /// The named wallet input is neither P2WPKH nor a P2TR key-path spend.
+    #[error("input {0} is not a supported wallet input")]
+    InvalidWalletInput(usize),
+    /// The named relative refund input is not a Taproot output or its lock
+    /// time is not a BIP-68 relative lock time.
+    #[error("input {0} cannot be refunded with a relative lock time")]
+    InvalidRelativeRefund(usize),
+    /// The named input cannot be spent with a PSET (legacy inputs need their full previous transaction).
+    #[error("input {0} is not supported in PSETs")]
+    UnsupportedPsetInput(usize),
+    /// The named PSET input has no signature to finalize it with.
+    #[error("input {0} is missing a signature")]
+    MissingSignature(usize),
+    /// The named PSET input has neither a tapscript nor a witness script to finalize it with.
+    #[error("input {0} has no script to spend it")]
+    MissingSpendInfo(usize),
+    /// The [`Signer`] of the named input failed to sign it.
+    #[error("could not sign input {0}")]
+    Signer(usize, #[source] SignerError),
     /// A swap [`Tree`](crate::elements::Tree) operation failed.
     #[error(transparent)]
     Tree(#[from] TreeError),
@@ -80,6 +117,9 @@ pub enum TxError {
     /// Failed to construct a confidential output (range proof, surjection proof, ...).
     #[error(transparent)]
     Confidential(#[from] elements::ConfidentialTxOutError),
+    /// Failed to extract the transaction from a PSET.
+    #[error(transparent)]
+    Pset(#[from] elements::pset::Error),
     /// A secp256k1 (upstream) operation failed.
     #[error(transparent)]
     Secp(#[from] elements::secp256k1_zkp::UpstreamError),
@@ -92,6 +132,7 @@ impl From<FeeError> for TxError {
     fn from(e: FeeError) -> Self {
         match e {
             FeeError::InvalidFeeRate => TxError::InvalidFeeRate,
+            FeeError::Bounds(err) => TxError::FeeBounds(err),
         }
     }
 }
@@ -149,6 +190,15 @@ impl From<&UnblindedOutput> for (u64, AssetBlindingFactor, ValueBlindingFactor)
     }
 }
 
+/// Where [`construct_multi_asset_tx`] sends the inputs of one asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetDestination<'a> {
+    /// The asset of the inputs.
+    pub asset_id: AssetId,
+    /// Where the inputs of the asset are sent.
+    pub destination: Destination<'a, &'a Address>,
+}
+
 /// Build, sign, and finalize an Elements transaction spending `inputs`
 /// to `destination`, paying `fee` for chain `genesis_hash`.
 ///
@@ -158,18 +208,128 @@ impl From<&UnblindedOutput> for (u64, AssetBlindingFactor, ValueBlindingFactor)
 /// when paying to a confidential address. Returns the signed
 /// transaction and the fee that was actually charged, in satoshis.
 ///
-/// All inputs must share a single asset id; this function does not
-/// support multi-asset spends. See
+/// All inputs must share a single asset id; see [`construct_multi_asset_tx`]
+/// for multi-asset spends and
 /// [`construct_asset_rescue`](crate::elements::construct_asset_rescue)
 /// for the dual-asset (rescue + L-BTC) flow.
 #[must_use = "ignoring the result discards the constructed transaction"]
-pub fn construct_tx<C: Signing + Verification>(
+pub async fn construct_tx<C: Signing + Verification, S: Signer>(
+    secp: &Secp256k1<C>,
+    genesis_hash: BlockHash,
+    mut inputs: Vec<InputDetail<S>>,
+    destination: &Destination<'_, &Address>,
+    fee: FeeTarget,
+) -> Result<(Transaction, u64), TxError> {
+    sort_inputs(&mut inputs);
+    let inputs = inputs.as_slice();
+
+    let (unblinded, asset_id) = unblind_inputs(secp, inputs)?;
+    let destinations = [AssetDestination {
+        asset_id,
+        destination: *destination,
+    }];
+
+    build_tx(
+        secp,
+        genesis_hash,
+        inputs,
+        &unblinded,
+        &destinations,
+        asset_id,
+        fee,
+    )
+    .await
+}
+
+/// Build, sign, and finalize an Elements transaction spending `inputs` of
+/// several assets, like the swaps of a batch claim of L-BTC and USDT.
+///
+/// The inputs of every asset are sent to the [`AssetDestination`] of that
+/// asset, which is required for each asset of the inputs. The fee is paid
+/// in the asset of the input at `fee_input`, which has to be the
+/// `policy_asset` (L-BTC) of the chain; it is taken from what is sent to
+/// the destination of that asset. Every output to a confidential address is
+/// blinded, with the blinding factors balanced across all assets.
+#[must_use = "ignoring the result discards the constructed transaction"]
+pub async fn construct_multi_asset_tx<C: Signing + Verification, S: Signer>(
+    secp: &Secp256k1<C>,
+    genesis_hash: BlockHash,
+    mut inputs: Vec<InputDetail<S>>,
+    destinations: &[AssetDestination<'_>],
+    policy_asset: AssetId,
+    fee_input: OutPoint,
+    fee: FeeTarget,
+) -> Result<(Transaction, u64), TxError> {
+    sort_inputs(&mut inputs);
+    let inputs = inputs.as_slice();
+
+    let unblinded = unblind_outputs(secp, inputs)?;
+    let fee_asset = inputs
+        .iter()
+        .zip(&unblinded)
+        .find(|(input, _)| input.outpoint == fee_input)
+        .map(|(_, unblinded)| unblinded.asset())
+        .ok_or(TxError::MissingFeeInput)?;
+    if fee_asset != policy_asset {
+        return Err(TxError::FeeInputNotPolicyAsset(fee_asset));
+    }
+
+    build_tx(
+        secp,
+        genesis_hash,
+        inputs,
+        &unblinded,
+        destinations,
+        fee_asset,
+        fee,
+    )
+    .await
+}
+
+async fn build_tx<C: Signing + Verification, S: Signer>(
     secp: &Secp256k1<C>,
     genesis_hash: BlockHash,
-    mut inputs: Vec<InputDetail>,
-    destination: &Destination<&Address>,
+    inputs: &[InputDetail<S>],
+    unblinded: &[UnblindedOutput],
+    destinations: &[AssetDestination<'_>],
+    fee_asset: AssetId,
     fee: FeeTarget,
 ) -> Result<(Transaction, u64), TxError> {
+    let stub_inputs = stub_inputs(secp, inputs)?;
+
+    let (tx, fee) = target_fee(
+        fee,
+        input_value(unblinded, fee_asset),
+        |fee, is_fee_estimation| {
+            if is_fee_estimation {
+                construct_raw(
+                    secp,
+                    genesis_hash,
+                    &stub_inputs,
+                    unblinded,
+                    destinations,
+                    fee_asset,
+                    fee,
+                    is_fee_estimation,
+                )
+            } else {
+                unsigned_tx(
+                    secp,
+                    inputs,
+                    unblinded,
+                    destinations,
+                    fee_asset,
+                    fee,
+                    is_fee_estimation,
+                )
+            }
+        },
+    )?;
+
+    Ok((sign_inputs(secp, genesis_hash, tx, inputs).await?, fee))
+}
+
+pub(super) fn sort_inputs<S>(inputs: &mut [InputDetail<S>]) {
     // BIP69: sort inputs by (outpoint.txid asc, outpoint.vout asc).
     inputs.sort_by(|a, b| {
         bip69_txid_cmp(
@@ -178,26 +338,29 @@ pub fn construct_tx<C: Signing + Verification>(
         )
         .then(a.outpoint.vout.cmp(&b.outpoint.vout))
     });
-    let inputs = inputs.as_slice();
+}
 
+/// Unblinds the inputs and returns them with the asset id they all share
+pub(super) fn unblind_inputs<C: Verification, S>(
+    secp: &Secp256k1<C>,
+    inputs: &[InputDetail<S>],
+) -> Result<(Vec<UnblindedOutput>, AssetId), TxError> {
     let unblinded = unblind_outputs(secp, inputs)?;
     let asset_id = unblinded.first().ok_or(TxError::EmptyInputs)?.asset();
     if !unblinded.iter().all(|input| input.asset() == asset_id) {
         return Err(TxError::MixedAssets);
     }
 
-    target_fee(fee, |fee, is_fee_estimation| {
-        construct_raw(
-            secp,
-            genesis_hash,
-            inputs,
-            &unblinded,
-            asset_id,
-            destination,
-            fee,
-            is_fee_estimation,
-        )
-    })
+    Ok((unblinded, asset_id))
+}
+
+/// Sum of the unblinded values of the inputs of `asset_id`, in satoshis
+pub(super) fn input_value(unblinded: &[UnblindedOutput], asset_id: AssetId) -> u64 {
+    unblinded
+        .iter()
+        .filter(|input| input.asset() == asset_id)
+        .map(UnblindedOutput::amount)
+        .sum()
 }
 
 fn bip69_txid_cmp(a: &[u8; 32], b: &[u8; 32]) -> std::cmp::Ordering {
@@ -207,49 +370,57 @@ fn bip69_txid_cmp(a: &[u8; 32], b: &[u8; 32]) -> std::cmp::Ordering {
 
 // TODO: claim covenant support
 
+/// The inputs with their signers replaced by a stub key. Signing with it
+/// yields a transaction of the same size, which is all the fee estimation needs
+fn stub_inputs<C: Signing, S>(
+    secp: &Secp256k1<C>,
+    inputs: &[InputDetail<S>],
+) -> Result<Vec<InputDetail>, TxError> {
+    let stub_keys = Keypair::from_seckey_slice(secp, &STUB_SECRET_KEY)?;
+
+    Ok(inputs
+        .iter()
+        .map(|input| InputDetail {
+            input_type: input.input_type,
+            output_type: input.output_type.clone(),
+            outpoint: input.outpoint,
+            tx_out: input.tx_out.clone(),
+            blinding_key: input.blinding_key,
+            keys: stub_keys,
+        })
+        .collect())
+}
+
 #[allow(clippy::too_many_arguments)]
-fn construct_raw<C: Signing + Verification>(
+pub(super) fn construct_raw<C: Signing + Verification>(
     secp: &Secp256k1<C>,
     genesis_hash: BlockHash,
     inputs: &[InputDetail],
     unblinded: &[UnblindedOutput],
-    asset_id: AssetId,
-    destination: &Destination<&Address>,
+    destinations: &[AssetDestination],
+    fee_asset: AssetId,
     fee: u64,
     is_fee_estimation: bool,
 ) -> Result<Transaction, TxError> {
-    let mut tx = Transaction {
-        version: 2,
-        lock_time: if let Some(lock_time) = inputs
-            .iter()
-            .filter_map(|input| match input.input_type {
-                InputType::Refund(lock_time) => Some(lock_time),
-                _ => None,
-            })
-            .max()
-        {
-            LockTime::from_height(lock_time)?
-        } else {
-            LockTime::ZERO
-        },
-        input: inputs
-            .iter()
-            .map(|input| TxIn {
-                previous_output: input.outpoint,
-                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
-                ..Default::default()
-            })
-            .collect(),
-        output: blind_outputs(
-            secp,
-            unblinded,
-            asset_id,
-            destination,
-            fee,
-            is_fee_estimation,
-        )?,
-    };
+    let tx = unsigned_tx(
+        secp,
+        inputs,
+        unblinded,
+        destinations,
+        fee_asset,
+        fee,
+        is_fee_estimation,
+    )?;
+    resolve_local(sign_inputs(secp, genesis_hash, tx, inputs))
+}
 
+/// Adds the signatures and scripts that spend `inputs` to the unsigned `tx`
+async fn sign_inputs<C: Verification, S: Signer>(
+    secp: &Secp256k1<C>,
+    genesis_hash: BlockHash,
+    mut tx: Transaction,
+    inputs: &[InputDetail<S>],
+) -> Result<Transaction, TxError> {
     let prevouts: Vec<&TxOut> = inputs.iter().map(|input| &input.tx_out).collect();
     let prevouts = Prevouts::All(&prevouts);
 
@@ -257,12 +428,20 @@ fn construct_raw<C: Signing + Verification>(
     let mut sighash_cache = SighashCache::new(&sighash_cache);
 
     for ((i, input), tx_in) in inputs.iter().enumerate().zip(tx.input.iter_mut()) {
+        if input.input_type == InputType::Wallet {
+            tx_in.witness.script_witness =
+                wallet_witness(&mut sighash_cache, &prevouts, genesis_hash, i, input)
+                    .await?
+                    .to_vec();
+            continue;
+        }
+
         match &input.output_type {
             OutputType::Legacy(witness_script) => {
                 let sighash = sighash_cache.legacy_sighash(i, witness_script, SIGHASH_TYPE_LEGACY);
 
                 let mut script_sig =
-                    Builder::new().push_slice(&legacy_signature(secp, &sighash, &input.keys)?);
+                    Builder::new().push_slice(&legacy_signature(i, &input.keys, &sighash).await?);
 
                 match input.input_type {
                     InputType::Claim(preimage) => {
@@ -271,6 +450,10 @@ fn construct_raw<C: Signing + Verification>(
                     InputType::Refund(_) => {
                         script_sig = script_sig.push_slice(&PREIMAGE_DUMMY);
                     }
+                    InputType::RelativeRefund(_) => {
+                        unreachable!("relative refunds are only possible via a swap tree")
+                    }
+                    InputType::Wallet => unreachable!("wallet inputs are signed separately"),
                 };
 
                 tx_in.script_sig = script_sig
@@ -278,13 +461,7 @@ fn construct_raw<C: Signing + Verification>(
                     .into_script();
             }
             OutputType::Compatibility(witness_script) => {
-                let nested = Builder::new()
-                    .push_opcode(OP_PUSHBYTES_0)
-                    .push_slice(sha256::Hash::hash(witness_script.as_bytes()).as_ref());
-
-                tx_in.script_sig = Builder::new()
-                    .push_slice(nested.into_script().as_bytes())
-                    .into_script();
+                tx_in.script_sig = nested_script_sig(witness_script);
             }
             _ => {}
         };
@@ -294,12 +471,7 @@ fn construct_raw<C: Signing + Verification>(
                 tx_in.witness.script_witness = stubbed_cooperative_witness().to_vec();
             }
             OutputType::Taproot(Some(uncooperative)) => {
-                let leaf = if let InputType::Claim(_) = input.input_type {
-                    &uncooperative.tree.claim_leaf
-                } else {
-                    &uncooperative.tree.refund_leaf
-                };
-
+                let leaf = spend_leaf(input.input_type, &uncooperative.tree);
                 let leaf_hash = leaf.leaf_hash()?;
 
                 let sighash = sighash_cache.taproot_script_spend_signature_hash(
@@ -312,22 +484,14 @@ fn construct_raw<C: Signing + Verification>(
 
                 let sig = SchnorrSig {
                     hash_ty: SIGHASH_TYPE_TAPROOT,
-                    sig: secp.sign_schnorr(
-                        &Message::from_digest_slice(sighash.as_raw_hash().as_ref())?,
-                        &input.keys,
-                    ),
+                    sig: input
+                        .keys
+                        .sign_schnorr(&Message::from_digest(sighash.to_byte_array()))
+                        .await
+                        .map_err(|err| TxError::Signer(i, err))?,
                 };
 
-                let control_block = uncooperative
-                    .tree
-                    .build()?
-                    .finalize(secp, uncooperative.internal_key)
-                    .map_err(|_| TxError::TaprootFinalize(i))?
-                    .control_block(&(
-                        leaf.output.clone(),
-                        LeafVersion::from_u8(leaf.version).map_err(TreeError::Taproot)?,
-                    ))
-                    .ok_or(TxError::ControlBlock(i))?;
+                let (control_block, _) = control_block(secp, i, uncooperative, leaf)?;
 
                 let mut witness = Witness::new();
                 witness.push(sig.to_vec());
@@ -350,7 +514,7 @@ fn construct_raw<C: Signing + Verification>(
                 );
 
                 let mut witness = Witness::new();
-                witness.push(legacy_signature(secp, &sighash, &input.keys)?);
+                witness.push(legacy_signature(i, &input.keys, &sighash).await?);
 
                 if let InputType::Claim(preimage) = input.input_type {
                     witness.push(preimage);
@@ -369,9 +533,194 @@ fn construct_raw<C: Signing + Verification>(
     Ok(tx)
 }
 
-fn unblind_outputs<C: Verification>(
+/// Transaction with the inputs and blinded outputs of a claim or refund,
+/// but without any signatures or scripts to spend the inputs
+pub(super) fn unsigned_tx<C: Signing, S>(
     secp: &Secp256k1<C>,
-    inputs: &[InputDetail],
+    inputs: &[InputDetail<S>],
+    unblinded: &[UnblindedOutput],
+    destinations: &[AssetDestination],
+    fee_asset: AssetId,
+    fee: u64,
+    is_fee_estimation: bool,
+) -> Result<Transaction, TxError> {
+    for (i, input) in inputs.iter().enumerate() {
+        check_wallet_input(i, input)?;
+        check_relative_refund(i, input)?;
+    }
+
+    Ok(Transaction {
+        version: 2,
+        lock_time: if let Some(lock_time) = inputs
+            .iter()
+            .filter_map(|input| match input.input_type {
+                InputType::Refund(lock_time) => Some(lock_time),
+                _ => None,
+            })
+            .max()
+        {
+            LockTime::from_height(lock_time)?
+        } else {
+            LockTime::ZERO
+        },
+        input: inputs
+            .iter()
+            .map(|input| TxIn {
+                previous_output: input.outpoint,
+                sequence: match input.input_type {
+                    InputType::RelativeRefund(sequence) => Sequence::from_consensus(sequence),
+                    _ => Sequence::ENABLE_RBF_NO_LOCKTIME,
+                },
+                ..Default::default()
+            })
+            .collect(),
+        output: blind_outputs(
+            secp,
+            unblinded,
+            destinations,
+            fee_asset,
+            fee,
+            is_fee_estimation,
+        )?,
+    })
+}
+
+/// The leaf of the swap tree that is used to spend an input via the script-path
+pub(super) fn spend_leaf(input_type: InputType, tree: &Tree) -> &Tapleaf {
+    match input_type {
+        InputType::Claim(_) => &tree.claim_leaf,
+        InputType::Refund(_) | InputType::RelativeRefund(_) => &tree.refund_leaf,
+        InputType::Wallet => unreachable!("wallet inputs are not spent via a swap tree"),
+    }
+}
+
+/// Relative refunds have to spend a Taproot output with a BIP-68 relative lock time
+pub(super) fn check_relative_refund<S>(
+    index: usize,
+    input: &InputDetail<S>,
+) -> Result<(), TxError> {
+    let InputType::RelativeRefund(sequence) = input.input_type else {
+        return Ok(());
+    };
+
+    if matches!(input.output_type, OutputType::Taproot(_))
+        && Sequence::from_consensus(sequence).is_relative_lock_time()
+    {
+        Ok(())
+    } else {
+        Err(TxError::InvalidRelativeRefund(index))
+    }
+}
+
+/// Wallet inputs have to be P2WPKH with their `scriptPubKey` as payload or P2TR key-path spends
+pub(super) fn check_wallet_input<S>(index: usize, input: &InputDetail<S>) -> Result<(), TxError> {
+    if input.input_type != InputType::Wallet {
+        return Ok(());
+    }
+
+    let is_valid = match &input.output_type {
+        OutputType::SegwitV0(script_pubkey) => {
+            script_pubkey.is_v0_p2wpkh() && *script_pubkey == input.tx_out.script_pubkey
+        }
+        OutputType::Taproot(None) => input.tx_out.script_pubkey.is_v1_p2tr(),
+        _ => false,
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(TxError::InvalidWalletInput(index))
+    }
+}
+
+async fn wallet_witness<S: Signer>(
+    sighash_cache: &mut SighashCache<&Transaction>,
+    prevouts: &Prevouts<'_, &TxOut>,
+    genesis_hash: BlockHash,
+    index: usize,
+    input: &InputDetail<S>,
+) -> Result<Witness, TxError> {
+    match &input.output_type {
+        OutputType::SegwitV0(_) => {
+            let public_key = input.keys.public_key();
+            let sighash = sighash_cache.segwitv0_sighash(
+                index,
+                &Script::new_p2pkh(&PubkeyHash::hash(&public_key.serialize())),
+                input.tx_out.value,
+                SIGHASH_TYPE_LEGACY,
+            );
+
+            let mut witness = Witness::new();
+            witness.push(legacy_signature(index, &input.keys, &sighash).await?);
+            witness.push(public_key.serialize());
+
+            Ok(witness)
+        }
+        OutputType::Taproot(None) => {
+            let sighash = sighash_cache.taproot_key_spend_signature_hash(
+                index,
+                prevouts,
+                SIGHASH_TYPE_TAPROOT,
+                genesis_hash,
+            )?;
+
+            let sig = SchnorrSig {
+                hash_ty: SIGHASH_TYPE_TAPROOT,
+                sig: input
+                    .keys
+                    .sign_schnorr(&Message::from_digest(sighash.to_byte_array()))
+                    .await
+                    .map_err(|err| TxError::Signer(index, err))?,
+            };
+
+            let mut witness = Witness::new();
+            witness.push(sig.to_vec());
+
+            Ok(witness)
+        }
+        _ => Err(TxError::InvalidWalletInput(index)),
+    }
+}
+
+pub(super) fn control_block<C: Verification>(
+    secp: &Secp256k1<C>,
+    index: usize,
+    uncooperative: &UncooperativeDetails,
+    leaf: &Tapleaf,
+) -> Result<(ControlBlock, TaprootSpendInfo), TxError> {
+    let spend_info = uncooperative
+        .tree
+        .build()?
+        .finalize(secp, uncooperative.internal_key)
+        .map_err(|_| TxError::TaprootFinalize(index))?;
+
+    let control_block = spend_info
+        .control_block(&(
+            leaf.output.clone(),
+            LeafVersion::from_u8(leaf.version).map_err(TreeError::Taproot)?,
+        ))
+        .ok_or(TxError::ControlBlock(index))?;
+
+    Ok((control_block, spend_info))
+}
+
+/// The P2SH script sig of a nested segwit input
+pub(super) fn nested_script_sig(witness_script: &Script) -> Script {
+    Builder::new()
+        .push_slice(nested_redeem_script(witness_script).as_bytes())
+        .into_script()
+}
+
+pub(super) fn nested_redeem_script(witness_script: &Script) -> Script {
+    Builder::new()
+        .push_opcode(OP_PUSHBYTES_0)
+        .push_slice(sha256::Hash::hash(witness_script.as_bytes()).as_ref())
+        .into_script()
+}
+
+fn unblind_outputs<C: Verification, S>(
+    secp: &Secp256k1<C>,
+    inputs: &[InputDetail<S>],
 ) -> Result<Vec<UnblindedOutput>, TxError> {
     let mut unblinded = Vec::with_capacity(inputs.len());
 
@@ -405,94 +754,146 @@ fn unblind_outputs<C: Verification>(
 fn blind_outputs<C: Signing>(
     secp: &Secp256k1<C>,
     unblinded: &[UnblindedOutput],
-    asset_id: AssetId,
-    destination: &Destination<&Address>,
+    destinations: &[AssetDestination],
+    fee_asset: AssetId,
     fee: u64,
     is_fee_estimation: bool,
 ) -> Result<Vec<TxOut>, TxError> {
-    let mut input_sum = unblinded.iter().map(|input| input.amount()).sum::<u64>();
+    if let Some(input) = unblinded.iter().find(|input| {
+        !destinations
+            .iter()
+            .any(|destination| destination.asset_id == input.asset())
+    }) {
+        return Err(TxError::MissingAssetDestination(input.asset()));
+    }
 
     // We need a blinded dummy output if there is some blinded input and none of our outputs are blinded
     let needs_dummy_output = unblinded.iter().any(|i| match i {
         UnblindedOutput::Unblinded(_) => true,
         UnblindedOutput::Explicit(_) => false,
-    }) && match destination {
-        Destination::Single(address) => !address.is_blinded(),
-        Destination::Multiple(outputs) => {
-            !outputs.change.is_blinded()
-                && !outputs
-                    .outputs
-                    .iter()
-                    .any(|(address, _)| address.is_blinded())
+    }) && !destinations.iter().any(|destination| {
+        match destination.destination {
+            Destination::Single(address) => address.is_blinded(),
+            Destination::Multiple(outputs) => {
+                outputs.change.is_blinded()
+                    || outputs
+                        .outputs
+                        .iter()
+                        .any(|(address, _)| address.is_blinded())
+            }
         }
-    };
-
-    if needs_dummy_output {
-        input_sum -= DUMMY_BLINDED_OUTPUT;
-    }
+    });
 
     // Collect output intents up front so we can BIP69-sort them by
     // (amount asc, scriptPubKey bytes asc) before any blinding work. Once
     // built in sorted order, `last_to_blind` is just the last blinded
     // entry encountered by the build loop.
-    let mut intents: Vec<(u64, elements::Script, Option<PublicKey>)> = Vec::new();
+    let mut intents: Vec<(u64, elements::Script, Option<PublicKey>, AssetId)> = Vec::new();
 
     if needs_dummy_output {
         let stub_script = Builder::new().push_opcode(OP_RETURN).into_script();
         let blinding_pubkey = Some(SecretKey::new(&mut rand::thread_rng()).public_key(secp));
-        intents.push((DUMMY_BLINDED_OUTPUT, stub_script, blinding_pubkey));
+        intents.push((
+            DUMMY_BLINDED_OUTPUT,
+            stub_script,
+            blinding_pubkey,
+            fee_asset,
+        ));
     }
 
-    match destination {
-        Destination::Single(address) => {
-            let amount = input_sum
-                .checked_sub(fee)
-                .ok_or(TxError::FeeExceedsInputs)?;
-            intents.push((amount, address.script_pubkey(), address.blinding_pubkey));
+    for (
+        i,
+        AssetDestination {
+            asset_id,
+            destination,
+        },
+    ) in destinations.iter().enumerate()
+    {
+        let asset_id = *asset_id;
+        if destinations[..i]
+            .iter()
+            .any(|previous| previous.asset_id == asset_id)
+            || !unblinded.iter().any(|input| input.asset() == asset_id)
+        {
+            return Err(TxError::InvalidAssetDestination(asset_id));
         }
-        Destination::Multiple(destination) => {
-            let output_sum = destination
-                .outputs
-                .iter()
-                .map(|output| output.1)
-                .sum::<u64>();
 
-            if output_sum
-                + fee
-                + match needs_dummy_output {
+        let mut input_sum = input_value(unblinded, asset_id);
+
+        // The fee and the dummy output are paid in the fee asset
+        let (fee, dummy_output) = if asset_id == fee_asset {
+            if needs_dummy_output {
+                input_sum -= DUMMY_BLINDED_OUTPUT;
+            }
+
+            (
+                fee,
+                match needs_dummy_output {
                     true => DUMMY_BLINDED_OUTPUT,
                     false => 0,
-                }
-                > input_sum
-            {
-                return Err(TxError::OutputsExceedInputs);
-            }
+                },
+            )
+        } else {
+            (0, 0)
+        };
 
-            for (address, amount) in destination.outputs {
-                intents.push((*amount, address.script_pubkey(), address.blinding_pubkey));
+        match destination {
+            Destination::Single(address) => {
+                let amount = input_sum
+                    .checked_sub(fee)
+                    .ok_or(TxError::FeeExceedsInputs)?;
+                intents.push((
+                    amount,
+                    address.script_pubkey(),
+                    address.blinding_pubkey,
+                    asset_id,
+                ));
             }
+            Destination::Multiple(destination) => {
+                let output_sum = destination
+                    .outputs
+                    .iter()
+                    .map(|output| output.1)
+                    .sum::<u64>();
 
-            let sweep_amount = input_sum - fee - output_sum;
-            intents.push((
-                sweep_amount,
-                destination.change.script_pubkey(),
-                destination.change.blinding_pubkey,
-            ));
+                if output_sum + fee + dummy_output > input_sum {
+                    return Err(TxError::OutputsExceedInputs);
+                }
+
+                for (address, amount) in destination.outputs {
+                    intents.push((
+                        *amount,
+                        address.script_pubkey(),
+                        address.blinding_pubkey,
+                        asset_id,
+                    ));
+                }
+
+                let sweep_amount = input_sum - fee - output_sum;
+                intents.push((
+                    sweep_amount,
+                    destination.change.script_pubkey(),
+                    destination.change.blinding_pubkey,
+                    asset_id,
+                ));
+            }
         }
     }
 
-    intents.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.as_bytes().cmp(b.1.as_bytes())));
-
-    let asset_bf = AssetBlindingFactor::new(&mut rand::thread_rng());
+    intents.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(a.1.as_bytes().cmp(b.1.as_bytes()))
+            .then(a.3.cmp(&b.3))
+    });
 
     let mut last_to_blind = None;
     let mut outputs = Vec::with_capacity(intents.len() + 1);
 
-    for (amount, script, blinding_pubkey) in &intents {
+    for (amount, script, blinding_pubkey, asset_id) in &intents {
         let (blinded, asset_bf, value_bf, output) = create_output(
             secp,
             unblinded,
-            (asset_id, asset_bf),
+            (*asset_id, AssetBlindingFactor::new(&mut rand::thread_rng())),
             script,
             *blinding_pubkey,
             *amount,
@@ -501,7 +902,13 @@ fn blind_outputs<C: Signing>(
         )?;
 
         if blinded {
-            last_to_blind = Some((outputs.len(), *amount, script.clone(), *blinding_pubkey));
+            last_to_blind = Some((
+                outputs.len(),
+                *amount,
+                script.clone(),
+                *blinding_pubkey,
+                (*asset_id, asset_bf),
+            ));
         }
 
         outputs.push((*amount, asset_bf, value_bf, output));
@@ -511,14 +918,14 @@ fn blind_outputs<C: Signing>(
         fee,
         AssetBlindingFactor::zero(),
         ValueBlindingFactor::zero(),
-        TxOut::new_fee(fee, asset_id),
+        TxOut::new_fee(fee, fee_asset),
     ));
 
-    if let Some((index, amount, script, blinding_pubkey)) = last_to_blind {
+    if let Some((index, amount, script, blinding_pubkey, asset)) = last_to_blind {
         let (_, asset_bf, value_bf, output) = create_output(
             secp,
             unblinded,
-            (asset_id, asset_bf),
+            asset,
             &script,
             blinding_pubkey,
             amount,
@@ -614,17 +1021,15 @@ pub(crate) fn create_output<C: Signing>(
     }
 }
 
-fn legacy_signature<C: Signing>(
-    secp: &Secp256k1<C>,
+async fn legacy_signature<S: Signer>(
+    index: usize,
+    signer: &S,
     sighash: &Sighash,
-    keys: &Keypair,
 ) -> Result<Vec<u8>, TxError> {
-    let mut sig = secp
-        .sign_ecdsa_grind_r(
-            &Message::from_digest_slice(sighash.as_raw_hash().as_ref())?,
-            &keys.secret_key(),
-            ECDSA_BYTES_TO_GRIND,
-        )
+    let mut sig = signer
+        .sign_ecdsa(&Message::from_digest(sighash.to_byte_array()))
+        .await
+        .map_err(|err| TxError::Signer(index, err))?
         .serialize_der()
         .to_vec();
     sig.push(SIGHASH_TYPE_LEGACY.as_u32() as u8);
@@ -647,16 +1052,19 @@ pub mod tests {
         client::{RpcBlock, RpcClient, RpcParam},
         detect_preimage,
         elements::{
-            Tree, UncooperativeDetails, reverse_script, reverse_tree, swap_script, swap_tree,
+            Timelock, Tree, UncooperativeDetails, reverse_script, reverse_tree, swap_script,
+            swap_tree,
         },
+        target_fee::FeeBounds,
         utils::Outputs,
     };
     use bitcoin::{Amount, hashes::Hash, key::rand::RngCore};
     use elements::pset::serialize::Serialize;
     use elements::{
-        AddressParams, OutPoint, Script, Txid, hashes::hash160, secp256k1_zkp::PublicKey,
-        secp256k1_zkp::XOnlyPublicKey, taproot::TaprootSpendInfo,
+        AddressParams, OutPoint, Script, Txid, hashes::hash160, schnorr::TapTweak,
+        secp256k1_zkp::PublicKey, secp256k1_zkp::XOnlyPublicKey, taproot::TaprootSpendInfo,
     };
+    use futures::executor::block_on;
     use rstest::rstest;
     use serial_test::serial;
     use std::str::FromStr;
@@ -677,7 +1085,7 @@ pub mod tests {
         preimage_hash: hash160::Hash,
         claim_key: &XOnlyPublicKey,
         refund_key: &XOnlyPublicKey,
-        lock_time: LockTime,
+        lock_time: impl Into<Timelock>,
     ) -> Tree {
         reverse_tree(preimage_hash, claim_key, refund_key, lock_time, None)
     }
@@ -948,6 +1356,54 @@ pub mod tests {
         )
     }
 
+    fn fund_wallet<C: Signing + Verification>(
+        secp: &Secp256k1<C>,
+        client: &RpcClient,
+        taproot: bool,
+        blind: bool,
+    ) -> InputDetail {
+        let keys = Keypair::new(secp, &mut rand::thread_rng());
+        let blinding_keys = Keypair::new(secp, &mut rand::thread_rng());
+        let blinder = if blind {
+            Some(blinding_keys.public_key())
+        } else {
+            None
+        };
+
+        let (keys, output_type, address) = if taproot {
+            (
+                keys.tap_tweak(secp, None).to_inner(),
+                OutputType::Taproot(None),
+                Address::p2tr(
+                    secp,
+                    keys.x_only_public_key().0,
+                    None,
+                    blinder,
+                    &AddressParams::ELEMENTS,
+                ),
+            )
+        } else {
+            let address = Address::p2wpkh(
+                &bitcoin::PublicKey::new(keys.public_key()),
+                blinder,
+                &AddressParams::ELEMENTS,
+            );
+            (keys, OutputType::SegwitV0(address.script_pubkey()), address)
+        };
+
+        mine_block(client);
+        let (funding_tx, vout_index) = fund_address(client, &address, None, Some(blind));
+
+        InputDetail {
+            input_type: InputType::Wallet,
+            output_type,
+            outpoint: OutPoint::new(funding_tx.txid(), vout_index as u32),
+            tx_out: funding_tx.output[vout_index].clone(),
+            blinding_key: Some(blinding_keys),
+            keys,
+        }
+    }
+
     fn fund_compatibility<
         C: Signing + Verification,
         T: FnOnce(hash160::Hash, &PublicKey, &PublicKey, LockTime) -> Script,
@@ -1095,13 +1551,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (mut tx, _) = construct_tx(
+        let (mut tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let has_dummy_output = blind_input && !blind_output;
@@ -1178,13 +1634,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -1241,13 +1697,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let broadcast = send_raw_transaction(&client, &tx);
@@ -1255,19 +1711,83 @@ pub mod tests {
     }
 
     #[rstest]
-    #[case::swap_script_blinded(swap_script, true, true)]
-    #[case::swap_script_unblinded(swap_script, false, false)]
-    #[case::swap_script_unblinded_input(swap_script, false, true)]
-    #[case::swap_script_unblinded_output(swap_script, true, false)]
-    #[case::reverse_script_blinded(reverse_script, true, true)]
-    #[case::reverse_script_unblinded(reverse_script, false, false)]
-    #[case::reverse_script_unblinded_input(reverse_script, false, true)]
-    #[case::reverse_script_unblinded_output(reverse_script, true, false)]
+    #[case::swap_tree_blinded(swap_tree, true)]
+    #[case::swap_tree_unblinded(swap_tree, false)]
+    #[case::reverse_tree_blinded(reverse_tree_without_covenant, true)]
+    #[case::reverse_tree_unblinded(reverse_tree_without_covenant, false)]
     #[serial(Elements)]
-    fn test_segwit_v0_claim(
-        #[case] create_script: impl FnOnce(hash160::Hash, &PublicKey, &PublicKey, LockTime) -> Script,
-        #[case] blind_input: bool,
-        #[case] blind_output: bool,
+    fn test_taproot_refund_relative(
+        #[case] create_tree: impl FnOnce(
+            hash160::Hash,
+            &XOnlyPublicKey,
+            &XOnlyPublicKey,
+            Sequence,
+        ) -> Tree,
+        #[case] blind: bool,
+    ) {
+        let client = RpcClient::new_elements_regtest();
+        let secp = Secp256k1::new();
+        let sequence = Sequence::from_height(2);
+        let (keys, tree, _, mut input) = fund_taproot(
+            &secp,
+            &client,
+            Some(0),
+            |preimage_hash, claim_pubkey, refund_pubkey, _| {
+                create_tree(preimage_hash, claim_pubkey, refund_pubkey, sequence)
+            },
+            blind,
+        );
+
+        input.input_type = InputType::RelativeRefund(sequence.to_consensus_u32());
+        input.output_type = OutputType::Taproot(Some(UncooperativeDetails {
+            tree,
+            internal_key: keys.x_only_public_key().0,
+        }));
+
+        let destination = get_destination(&client, blind, None);
+
+        let (tx, _) = block_on(construct_tx(
+            &secp,
+            get_genesis_hash(&client),
+            vec![input],
+            &Destination::Single(&destination),
+            FeeTarget::Absolute(100),
+        ))
+        .unwrap();
+
+        assert_eq!(tx.input[0].sequence, sequence);
+        assert_eq!(tx.lock_time, LockTime::ZERO);
+
+        // Not final until the lockup has enough confirmations
+        assert!(
+            client
+                .request::<String>(
+                    "sendrawtransaction",
+                    Some(&[RpcParam::Str(&hex::encode(tx.serialize()))]),
+                )
+                .is_err()
+        );
+
+        mine_block(&client);
+        mine_block(&client);
+
+        assert_eq!(send_raw_transaction(&client, &tx), tx.txid());
+    }
+
+    #[rstest]
+    #[case::swap_script_blinded(swap_script, true, true)]
+    #[case::swap_script_unblinded(swap_script, false, false)]
+    #[case::swap_script_unblinded_input(swap_script, false, true)]
+    #[case::swap_script_unblinded_output(swap_script, true, false)]
+    #[case::reverse_script_blinded(reverse_script, true, true)]
+    #[case::reverse_script_unblinded(reverse_script, false, false)]
+    #[case::reverse_script_unblinded_input(reverse_script, false, true)]
+    #[case::reverse_script_unblinded_output(reverse_script, true, false)]
+    #[serial(Elements)]
+    fn test_segwit_v0_claim(
+        #[case] create_script: impl FnOnce(hash160::Hash, &PublicKey, &PublicKey, LockTime) -> Script,
+        #[case] blind_input: bool,
+        #[case] blind_output: bool,
     ) {
         let client = RpcClient::new_elements_regtest();
         let secp = Secp256k1::new();
@@ -1276,13 +1796,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -1329,13 +1849,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let broadcast = send_raw_transaction(&client, &tx);
@@ -1364,13 +1884,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -1417,13 +1937,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let broadcast = send_raw_transaction(&client, &tx);
@@ -1452,13 +1972,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let InputType::Claim(expected_preimage) = input.input_type else {
@@ -1505,13 +2025,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 100;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             vec![input.clone()],
             &Destination::Single(&destination),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let broadcast = send_raw_transaction(&client, &tx);
@@ -1560,13 +2080,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 4.0;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             inputs.clone(),
             &Destination::Single(&destination),
             FeeTarget::Relative(fee),
-        )
+        ))
         .unwrap();
 
         let has_dummy_output = blind_input && !blind_output;
@@ -1618,13 +2138,13 @@ pub mod tests {
         let destination = get_destination(&client, blind_output, None);
 
         let fee = 4.0;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             inputs.clone(),
             &Destination::Single(&destination),
             FeeTarget::Relative(fee),
-        )
+        ))
         .unwrap();
 
         assert_eq!(tx.output.len(), if blind_output { 2 } else { 3 });
@@ -1668,7 +2188,7 @@ pub mod tests {
         ];
 
         let fee = 500;
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             get_genesis_hash(&client),
             inputs.clone(),
@@ -1677,7 +2197,7 @@ pub mod tests {
                 outputs: &outputs,
             }),
             FeeTarget::Absolute(fee),
-        )
+        ))
         .unwrap();
 
         let dummy_output = blind_input && !blind_output;
@@ -1733,18 +2253,543 @@ pub mod tests {
         assert_eq!(send_raw_transaction(&client, &tx), tx.txid());
     }
 
+    #[rstest]
+    #[case::blinded(true)]
+    #[case::unblinded(false)]
+    #[serial(Elements)]
+    fn test_wallet_inputs(#[case] blind: bool) {
+        let client = RpcClient::new_elements_regtest();
+        let secp = Secp256k1::new();
+
+        let inputs = vec![
+            fund_segwit_v0(&secp, &client, None, reverse_script, blind).1,
+            fund_wallet(&secp, &client, false, blind),
+            fund_wallet(&secp, &client, true, blind),
+        ];
+
+        let change = get_destination(&client, blind, None);
+        let outputs = [(&get_destination(&client, blind, None), 250_000)];
+
+        let (tx, _) = block_on(construct_tx(
+            &secp,
+            get_genesis_hash(&client),
+            inputs,
+            &Destination::Multiple(Outputs {
+                change: &change,
+                outputs: &outputs,
+            }),
+            FeeTarget::Relative(0.1),
+        ))
+        .unwrap();
+
+        assert!(
+            tx.output
+                .iter()
+                .any(|output| output.script_pubkey == change.script_pubkey())
+        );
+
+        assert_eq!(send_raw_transaction(&client, &tx), tx.txid());
+    }
+
+    #[test]
+    fn test_construct_tx_wallet_inputs() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let tweaked = keys.tap_tweak(&secp, None).to_inner();
+        let destination = Address::from_script(
+            &Script::new_v0_wpkh(&elements::WPubkeyHash::all_zeros()),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+        .unwrap();
+
+        let wallet_input = |output_type, script_pubkey, vout, keys| {
+            let mut tx_out = TxOut::new_fee(FUNDING_AMOUNT, AssetId::default());
+            tx_out.script_pubkey = script_pubkey;
+
+            InputDetail {
+                input_type: InputType::Wallet,
+                output_type,
+                outpoint: OutPoint::new(Txid::all_zeros(), vout),
+                tx_out,
+                blinding_key: None,
+                keys,
+            }
+        };
+        let p2wpkh = Address::p2wpkh(
+            &bitcoin::PublicKey::new(keys.public_key()),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+        .script_pubkey();
+        let inputs = vec![
+            wallet_input(OutputType::SegwitV0(p2wpkh.clone()), p2wpkh, 0, keys),
+            wallet_input(
+                OutputType::Taproot(None),
+                Script::new_v1_p2tr(&secp, keys.x_only_public_key().0, None),
+                1,
+                tweaked,
+            ),
+        ];
+
+        let (tx, _) = block_on(construct_tx(
+            &secp,
+            BlockHash::all_zeros(),
+            inputs.clone(),
+            &Destination::Single(&destination),
+            FeeTarget::Relative(0.1),
+        ))
+        .unwrap();
+
+        let prevouts = inputs
+            .iter()
+            .map(|input| input.tx_out.clone())
+            .collect::<Vec<_>>();
+        let mut sighash_cache = SighashCache::new(&tx);
+
+        let witness = &tx.input[0].witness.script_witness;
+        assert_eq!(witness[1], keys.public_key().serialize());
+        let sighash = sighash_cache.segwitv0_sighash(
+            0,
+            &Script::new_p2pkh(&PubkeyHash::hash(&keys.public_key().serialize())),
+            inputs[0].tx_out.value,
+            SIGHASH_TYPE_LEGACY,
+        );
+        let signature = &witness[0];
+        secp.verify_ecdsa(
+            &Message::from_digest(sighash.to_byte_array()),
+            &elements::secp256k1_zkp::ecdsa::Signature::from_der(&signature[..signature.len() - 1])
+                .unwrap(),
+            &keys.public_key(),
+        )
+        .unwrap();
+
+        let witness = &tx.input[1].witness.script_witness;
+        assert_eq!(witness.len(), 1);
+        let sighash = sighash_cache
+            .taproot_key_spend_signature_hash(
+                1,
+                &Prevouts::All(&prevouts),
+                SIGHASH_TYPE_TAPROOT,
+                BlockHash::all_zeros(),
+            )
+            .unwrap();
+        secp.verify_schnorr(
+            &SchnorrSig::from_slice(&witness[0]).unwrap().sig,
+            &Message::from_digest(sighash.to_byte_array()),
+            &tweaked.x_only_public_key().0,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_construct_tx_bounded_fee() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let destination = Address::from_script(
+            &Script::new_v0_wpkh(&elements::WPubkeyHash::all_zeros()),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+        .unwrap();
+
+        let mut tx_out = TxOut::new_fee(FUNDING_AMOUNT, AssetId::default());
+        tx_out.script_pubkey = Script::new_v1_p2tr(&secp, keys.x_only_public_key().0, None);
+        let input = InputDetail {
+            input_type: InputType::Wallet,
+            output_type: OutputType::Taproot(None),
+            outpoint: OutPoint::default(),
+            tx_out,
+            blinding_key: None,
+            keys: keys.tap_tweak(&secp, None).to_inner(),
+        };
+        let bounds = FeeBounds {
+            fee_rate: 0.1,
+            ..Default::default()
+        };
+
+        let (tx, fee) = block_on(construct_tx(
+            &secp,
+            BlockHash::all_zeros(),
+            vec![input.clone()],
+            &Destination::Single(&destination),
+            FeeTarget::Bounded(bounds),
+        ))
+        .unwrap();
+        assert_eq!(
+            fee,
+            (tx.discount_vsize() as f64 * bounds.fee_rate).ceil() as u64
+        );
+
+        let result = block_on(construct_tx(
+            &secp,
+            BlockHash::all_zeros(),
+            vec![input],
+            &Destination::Single(&destination),
+            FeeTarget::Bounded(FeeBounds {
+                max_fee: Some(fee - 1),
+                ..bounds
+            }),
+        ));
+        assert!(matches!(
+            result.unwrap_err(),
+            TxError::FeeBounds(FeeBoundsError::AboveMax { max_fee, .. }) if max_fee == fee - 1
+        ));
+    }
+
+    #[test]
+    fn test_construct_tx_relative_refund() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let sequence = Sequence::from_height(144);
+
+        let uncooperative = UncooperativeDetails {
+            tree: swap_tree(
+                hash160::Hash::all_zeros(),
+                &keys.x_only_public_key().0,
+                &keys.x_only_public_key().0,
+                sequence,
+            ),
+            internal_key: keys.x_only_public_key().0,
+        };
+        let tweak = uncooperative
+            .tree
+            .build()
+            .unwrap()
+            .finalize(&secp, uncooperative.internal_key)
+            .unwrap();
+        let destination = Address::p2tr_tweaked(tweak.output_key(), None, &AddressParams::ELEMENTS);
+
+        let mut tx_out = TxOut::new_fee(FUNDING_AMOUNT, AssetId::default());
+        tx_out.script_pubkey = destination.script_pubkey();
+        let input = InputDetail {
+            input_type: InputType::RelativeRefund(sequence.to_consensus_u32()),
+            output_type: OutputType::Taproot(Some(uncooperative.clone())),
+            outpoint: OutPoint::default(),
+            tx_out,
+            blinding_key: None,
+            keys,
+        };
+
+        let construct = |input: InputDetail| {
+            block_on(construct_tx(
+                &secp,
+                BlockHash::all_zeros(),
+                vec![input],
+                &Destination::Single(&destination),
+                FeeTarget::Absolute(100),
+            ))
+        };
+
+        let (tx, _) = construct(input.clone()).unwrap();
+        assert_eq!(tx.input[0].sequence, sequence);
+        assert_eq!(tx.lock_time, LockTime::ZERO);
+        assert_eq!(
+            tx.input[0].witness.script_witness[1],
+            uncooperative.tree.refund_leaf.output.to_bytes()
+        );
+        assert_eq!(detect_preimage(&tx.input[0], &[0; 32]), None);
+
+        let disabled = InputDetail {
+            input_type: InputType::RelativeRefund(Sequence::MAX.to_consensus_u32()),
+            ..input.clone()
+        };
+        assert!(matches!(
+            construct(disabled).unwrap_err(),
+            TxError::InvalidRelativeRefund(0)
+        ));
+
+        let segwit = InputDetail {
+            output_type: OutputType::SegwitV0(Script::new()),
+            ..input
+        };
+        assert!(matches!(
+            construct(segwit).unwrap_err(),
+            TxError::InvalidRelativeRefund(0)
+        ));
+    }
+
+    #[rstest]
+    #[case::p2wsh(OutputType::SegwitV0(Script::new().to_v0_p2wsh()))]
+    #[case::compatibility(OutputType::Compatibility(Script::new()))]
+    #[case::legacy(OutputType::Legacy(Script::new()))]
+    fn test_construct_tx_invalid_wallet_input(
+        #[case] output_type: OutputType<Option<UncooperativeDetails>, Script>,
+    ) {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let address = Address::from_str("el1qqvhpw75zjc2hhvk9g0cgv3e75azcct4sduxdv9rwpzxauwmw46chnlxjjwhk0jw2fny2jpgp8etz8j6wsqd7qk89rmtyucc52").unwrap();
+
+        let mut tx_out = TxOut::new_fee(FUNDING_AMOUNT, AssetId::default());
+        tx_out.script_pubkey = Script::new_v0_wpkh(&elements::WPubkeyHash::all_zeros());
+
+        let result = block_on(construct_tx(
+            &secp,
+            BlockHash::all_zeros(),
+            vec![InputDetail {
+                input_type: InputType::Wallet,
+                output_type,
+                outpoint: OutPoint::default(),
+                tx_out,
+                blinding_key: None,
+                keys,
+            }],
+            &Destination::Single(&address),
+            FeeTarget::Absolute(1_000),
+        ));
+
+        assert!(matches!(
+            result.unwrap_err(),
+            TxError::InvalidWalletInput(0)
+        ));
+    }
+
+    fn multi_asset_inputs(
+        secp: &Secp256k1<elements::secp256k1_zkp::All>,
+        keys: &Keypair,
+        assets: &[AssetId],
+    ) -> Vec<InputDetail> {
+        assets
+            .iter()
+            .enumerate()
+            .map(|(vout, asset_id)| {
+                let mut tx_out = TxOut::new_fee(FUNDING_AMOUNT, *asset_id);
+                tx_out.script_pubkey = Script::new_v1_p2tr(secp, keys.x_only_public_key().0, None);
+
+                InputDetail {
+                    input_type: InputType::Wallet,
+                    output_type: OutputType::Taproot(None),
+                    outpoint: OutPoint::new(Txid::all_zeros(), vout as u32),
+                    tx_out,
+                    blinding_key: None,
+                    keys: keys.tap_tweak(secp, None).to_inner(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_construct_multi_asset_tx() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let lbtc = AssetId::from_slice(&[1u8; 32]).unwrap();
+        let usdt = AssetId::from_slice(&[2u8; 32]).unwrap();
+
+        let blinding_key = SecretKey::new(&mut rand::thread_rng());
+        let usdt_destination = Address::from_script(
+            &Script::new_v0_wpkh(&elements::WPubkeyHash::all_zeros()),
+            Some(blinding_key.public_key(&secp)),
+            &AddressParams::ELEMENTS,
+        )
+        .unwrap();
+        let lbtc_destination = Address::from_script(
+            &Script::new_v0_wpkh(&elements::WPubkeyHash::hash(&[1])),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+        .unwrap();
+
+        let inputs = multi_asset_inputs(&secp, &keys, &[usdt, lbtc, usdt]);
+        let fee = 1_000;
+
+        let (tx, tx_fee) = block_on(construct_multi_asset_tx(
+            &secp,
+            BlockHash::all_zeros(),
+            inputs.clone(),
+            &[
+                AssetDestination {
+                    asset_id: usdt,
+                    destination: Destination::Single(&usdt_destination),
+                },
+                AssetDestination {
+                    asset_id: lbtc,
+                    destination: Destination::Single(&lbtc_destination),
+                },
+            ],
+            lbtc,
+            inputs[1].outpoint,
+            FeeTarget::Absolute(fee),
+        ))
+        .unwrap();
+        assert_eq!(tx_fee, fee);
+        assert_eq!(tx.input.len(), 3);
+        assert_eq!(tx.output.len(), 3);
+
+        let fee_output = tx.output.last().unwrap();
+        assert!(fee_output.is_fee());
+        assert_eq!(fee_output.asset, Asset::Explicit(lbtc));
+        assert_eq!(fee_output.value, Value::Explicit(fee));
+
+        let lbtc_output = tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == lbtc_destination.script_pubkey())
+            .unwrap();
+        assert_eq!(lbtc_output.asset, Asset::Explicit(lbtc));
+        assert_eq!(lbtc_output.value, Value::Explicit(FUNDING_AMOUNT - fee));
+
+        let usdt_output = tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == usdt_destination.script_pubkey())
+            .unwrap();
+        let secrets = usdt_output.unblind(&secp, blinding_key).unwrap();
+        assert_eq!(secrets.asset, usdt);
+        assert_eq!(secrets.value, 2 * FUNDING_AMOUNT);
+
+        let spent_utxos = tx
+            .input
+            .iter()
+            .map(|tx_in| {
+                inputs
+                    .iter()
+                    .find(|input| input.outpoint == tx_in.previous_output)
+                    .unwrap()
+                    .tx_out
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+        tx.verify_tx_amt_proofs(&secp, &spent_utxos).unwrap();
+    }
+
+    #[test]
+    fn test_construct_multi_asset_tx_multiple_outputs() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let lbtc = AssetId::from_slice(&[1u8; 32]).unwrap();
+        let usdt = AssetId::from_slice(&[2u8; 32]).unwrap();
+        let destination = Address::from_script(
+            &Script::new_v0_wpkh(&elements::WPubkeyHash::all_zeros()),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+        .unwrap();
+        let change = Address::from_script(
+            &Script::new_v0_wpkh(&elements::WPubkeyHash::hash(&[1])),
+            None,
+            &AddressParams::ELEMENTS,
+        )
+        .unwrap();
+
+        let inputs = multi_asset_inputs(&secp, &keys, &[lbtc, usdt]);
+        let fee = 1_000;
+        let outputs = [(&destination, 10_000)];
+
+        let (tx, _) = block_on(construct_multi_asset_tx(
+            &secp,
+            BlockHash::all_zeros(),
+            inputs.clone(),
+            &[
+                AssetDestination {
+                    asset_id: lbtc,
+                    destination: Destination::Single(&destination),
+                },
+                AssetDestination {
+                    asset_id: usdt,
+                    destination: Destination::Multiple(Outputs {
+                        change: &change,
+                        outputs: &outputs,
+                    }),
+                },
+            ],
+            lbtc,
+            inputs[0].outpoint,
+            FeeTarget::Absolute(fee),
+        ))
+        .unwrap();
+
+        let outputs = tx
+            .output
+            .iter()
+            .map(|output| {
+                (
+                    output.asset.explicit().unwrap(),
+                    output.value.explicit().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [
+                (usdt, 10_000),
+                (usdt, FUNDING_AMOUNT - 10_000),
+                (lbtc, FUNDING_AMOUNT - fee),
+                (lbtc, fee),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_construct_multi_asset_tx_invalid_destinations() {
+        let secp = Secp256k1::new();
+        let keys = Keypair::new(&secp, &mut rand::thread_rng());
+        let lbtc = AssetId::from_slice(&[1u8; 32]).unwrap();
+        let usdt = AssetId::from_slice(&[2u8; 32]).unwrap();
+        let address = Address::from_str("el1qqvhpw75zjc2hhvk9g0cgv3e75azcct4sduxdv9rwpzxauwmw46chnlxjjwhk0jw2fny2jpgp8etz8j6wsqd7qk89rmtyucc52").unwrap();
+
+        let inputs = multi_asset_inputs(&secp, &keys, &[lbtc, usdt]);
+        let construct = |destinations: &[AssetDestination], fee_input| {
+            block_on(construct_multi_asset_tx(
+                &secp,
+                BlockHash::all_zeros(),
+                inputs.clone(),
+                destinations,
+                lbtc,
+                fee_input,
+                FeeTarget::Absolute(1_000),
+            ))
+            .unwrap_err()
+        };
+        let destination = |asset_id| AssetDestination {
+            asset_id,
+            destination: Destination::Single(&address),
+        };
+
+        assert!(matches!(
+            construct(
+                &[destination(lbtc), destination(usdt)],
+                OutPoint::new(Txid::all_zeros(), 2)
+            ),
+            TxError::MissingFeeInput
+        ));
+        assert!(matches!(
+            construct(&[destination(lbtc)], inputs[0].outpoint),
+            TxError::MissingAssetDestination(asset_id) if asset_id == usdt
+        ));
+        assert!(matches!(
+            construct(
+                &[destination(lbtc), destination(usdt), destination(lbtc)],
+                inputs[0].outpoint
+            ),
+            TxError::InvalidAssetDestination(asset_id) if asset_id == lbtc
+        ));
+
+        let unknown = AssetId::from_slice(&[3u8; 32]).unwrap();
+        assert!(matches!(
+            construct(
+                &[destination(lbtc), destination(usdt), destination(unknown)],
+                inputs[0].outpoint
+            ),
+            TxError::InvalidAssetDestination(asset_id) if asset_id == unknown
+        ));
+        assert!(matches!(
+            construct(&[destination(lbtc), destination(usdt)], inputs[1].outpoint),
+            TxError::FeeInputNotPolicyAsset(asset_id) if asset_id == usdt
+        ));
+    }
+
     #[test]
     fn test_construct_tx_empty_inputs() {
         let secp = Secp256k1::new();
         let address = Address::from_str("el1qqvhpw75zjc2hhvk9g0cgv3e75azcct4sduxdv9rwpzxauwmw46chnlxjjwhk0jw2fny2jpgp8etz8j6wsqd7qk89rmtyucc52").unwrap();
 
-        let result = construct_tx(
+        let result = block_on(construct_tx(
             &secp,
             BlockHash::all_zeros(),
-            Vec::new(),
+            Vec::<InputDetail>::new(),
             &Destination::Single(&address),
             FeeTarget::Absolute(0),
-        );
+        ));
 
         assert!(matches!(result.unwrap_err(), TxError::EmptyInputs));
     }
@@ -1845,8 +2890,11 @@ pub mod tests {
         let result = blind_outputs(
             &secp,
             &unblinded,
+            &[AssetDestination {
+                asset_id,
+                destination: Destination::Single(&address),
+            }],
             asset_id,
-            &Destination::Single(&address),
             fee,
             false,
         );
@@ -1873,11 +2921,14 @@ pub mod tests {
         let result = blind_outputs(
             &secp,
             &unblinded,
+            &[AssetDestination {
+                asset_id,
+                destination: Destination::Multiple(Outputs {
+                    change: &change,
+                    outputs: &outputs,
+                }),
+            }],
             asset_id,
-            &Destination::Multiple(Outputs {
-                change: &change,
-                outputs: &outputs,
-            }),
             fee,
             false,
         );
@@ -1915,11 +2966,14 @@ pub mod tests {
         let result = blind_outputs(
             &secp,
             &unblinded,
+            &[AssetDestination {
+                asset_id,
+                destination: Destination::Multiple(Outputs {
+                    change: &change,
+                    outputs: &outputs,
+                }),
+            }],
             asset_id,
-            &Destination::Multiple(Outputs {
-                change: &change,
-                outputs: &outputs,
-            }),
             fee,
             false,
         )
@@ -2088,13 +3142,13 @@ pub mod tests {
             .map(|(txid, vout)| make_input(Txid::from_str(txid).unwrap(), *vout))
             .collect();
 
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             BlockHash::all_zeros(),
             inputs,
             &Destination::Single(&destination),
             FeeTarget::Absolute(1_000),
-        )
+        ))
         .unwrap();
 
         let actual_order: Vec<_> = tx.input.iter().map(|i| i.previous_output).collect();
@@ -2105,13 +3159,13 @@ pub mod tests {
             make_input(bip69_tie_breaker_txid, 0),
         ];
 
-        let (tx, _) = construct_tx(
+        let (tx, _) = block_on(construct_tx(
             &secp,
             BlockHash::all_zeros(),
             tie_breaker_inputs,
             &Destination::Single(&destination),
             FeeTarget::Absolute(1_000),
-        )
+        ))
         .unwrap();
 
         let expected_order = [
@@ -12,13 +12,16 @@
 //!   for cooperative signing. The `network` module enumerates the supported
 //!   chains and maps to the upstream `bitcoin::Network` /
 //!   `elements::AddressParams`.
-//!   The `address` module parses an address as either chain.
+//!   The `address` module parses an address as either chain. The `signer`
+//!   module abstracts how inputs are signed, so that keys can live in a
+//!   remote signer or an HSM instead of in a local `Keypair`.
 //!
 //! Every chain and capability is gated behind a Cargo feature
 //! (`bitcoin`, `elements`, `musig`); all three are enabled by default.
 //!
 //! See the `musig` module for a runnable end-to-end cooperative signing
-//! example.
+//! example. The `cooperative` module builds the transactions such a session
+//! signs and adds the aggregated signatures to them.
 
 #![warn(missing_docs)]
 
@@ -26,12 +29,16 @@
 pub mod address;
 #[cfg(feature = "bitcoin")]
 pub mod bitcoin;
+#[cfg(all(feature = "bitcoin", feature = "elements", feature = "musig"))]
+pub mod cooperative;
 #[cfg(feature = "elements")]
 pub mod elements;
 #[cfg(feature = "musig")]
 pub mod musig;
 #[cfg(any(feature = "bitcoin", feature = "elements"))]
 pub mod network;
+#[cfg(any(feature = "bitcoin", feature = "elements"))]
+pub mod signer;
 pub mod utils;
 #[cfg(all(feature = "bitcoin", feature = "elements"))]
 pub mod wrapper;
@@ -39,6 +46,8 @@ pub mod wrapper;
 #[cfg(any(feature = "bitcoin", feature = "elements"))]
 mod consts;
 #[cfg(any(feature = "bitcoin", feature = "elements"))]
+mod descriptor;
+#[cfg(any(feature = "bitcoin", feature = "elements"))]
 mod preimage_detector;
 #[cfg(any(feature = "bitcoin", feature = "elements"))]
 mod target_fee;
@@ -50,6 +59,8 @@ mod client;
 pub use address::{Address, AddressError};
 #[cfg(feature = "bitcoin")]
 pub use bitcoin::TxError as BitcoinTxError;
+#[cfg(all(feature = "bitcoin", feature = "elements", feature = "musig"))]
+pub use cooperative::{CooperativeError, KeyPathSpend};
 #[cfg(feature = "elements")]
 pub use elements::{AssetRescueError, TxError as ElementsTxError};
 #[cfg(feature = "musig")]
@@ -59,7 +70,9 @@ pub use network::{Network, NetworkError};
 #[cfg(any(feature = "bitcoin", feature = "elements"))]
 pub use preimage_detector::detect_preimage;
 #[cfg(any(feature = "bitcoin", feature = "elements"))]
-pub use target_fee::FeeTarget;
+pub use signer::{Signer, SignerError};
+#[cfg(any(feature = "bitcoin", feature = "elements"))]
+pub use target_fee::{FeeBounds, FeeBoundsError, FeeTarget};
 pub use utils::Destination;
 #[cfg(all(feature = "bitcoin", feature = "elements"))]
 pub use wrapper::{Transaction, WrapperError};
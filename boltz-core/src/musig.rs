@@ -16,7 +16,9 @@
 //! For nonce-handling escape hatches see [`WithMessage::dangerous_set_nonce`]
 //! and [`WithNonce::dangerous_secnonce`]. Reusing a secret nonce leaks the
 //! private key — read those `# Safety` blocks before persisting nonce state
-//! across processes.
+//! across processes. To continue a signing round in another request or
+//! process, prefer [`MusigBuilder::snapshot`] and [`MusigSnapshot::restore`]:
+//! they guard the secret nonce against reuse with a [`NonceGuard`].
 //!
 //! # Example: 2-of-2 cooperative signing
 //!
@@ -71,7 +73,12 @@ use secp256k1::musig::{
 };
 use secp256k1::rand::rngs::ThreadRng;
 use secp256k1::{Keypair, PublicKey, Scalar, SecretKey, rand};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::future::{Future, ready};
 use std::marker::PhantomData;
+use std::sync::{Mutex, PoisonError};
 
 /// Re-export of `secp256k1::XOnlyPublicKey` used as the aggregated MuSig2 public key.
 pub use secp256k1::XOnlyPublicKey;
@@ -119,6 +126,21 @@ pub enum MusigError {
     /// A byte slice did not have the expected fixed length for the value being parsed.
     #[error(transparent)]
     Slice(#[from] std::array::TryFromSliceError),
+    /// A hex-encoded value of a [`MusigSnapshot`] could not be decoded.
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    /// The [`MusigSnapshot`] was written with an encoding version this crate cannot read.
+    #[error("unsupported snapshot version: {0}")]
+    UnsupportedSnapshotVersion(u8),
+    /// The fields of the [`MusigSnapshot`] contradict its state.
+    #[error("snapshot is invalid")]
+    InvalidSnapshot,
+    /// The secret nonce of the [`MusigSnapshot`] was restored before.
+    #[error("secret nonce of the snapshot was consumed already")]
+    NonceConsumed,
+    /// The [`NonceGuard`] could not arm or consume a marker.
+    #[error("nonce guard failed: {0}")]
+    NonceGuard(#[from] NonceGuardError),
 }
 
 /// Compile-time state markers for [`MusigBuilder`].
@@ -203,6 +225,7 @@ pub struct MusigBuilder<S: State, T: Signedness> {
     nonce: Option<(SecretNonce, PublicNonce)>,
 
     aggcache: KeyAggCache,
+    tweaks: Vec<Scalar>,
     pub_keys: Vec<PublicKey>,
     pub_nonces: Option<Vec<PublicNonce>>,
     aggnonce: Option<AggregatedNonce>,
@@ -253,6 +276,7 @@ impl Setup {
             msg: None,
             nonce: None,
             aggcache,
+            tweaks: Vec::new(),
             partial_sigs: vec![None; pub_keys.len()],
             pub_keys,
             pub_nonces: None,
@@ -266,6 +290,7 @@ impl Setup {
     /// Apply an x-only tweak to the aggregated public key (BIP-341 taproot tweak).
     pub fn xonly_tweak_add(mut self, tweak: &Scalar) -> Result<Self, MusigError> {
         self.aggcache.pubkey_xonly_tweak_add(tweak)?;
+        self.tweaks.push(*tweak);
         Ok(self)
     }
 
@@ -276,6 +301,7 @@ impl Setup {
             msg: Some(msg),
             nonce: self.nonce,
             aggcache: self.aggcache,
+            tweaks: self.tweaks,
             pub_keys: self.pub_keys,
             pub_nonces: self.pub_nonces,
             aggnonce: self.aggnonce,
@@ -303,6 +329,7 @@ impl WithMessage {
             msg: self.msg,
             nonce: Some((secret, public)),
             aggcache: self.aggcache,
+            tweaks: self.tweaks,
             pub_keys: self.pub_keys,
             pub_nonces: self.pub_nonces,
             aggnonce: self.aggnonce,
@@ -362,6 +389,7 @@ impl WithMessage {
                 Musig::convert_pub_nonce(pub_nonce)?,
             )),
             aggcache: self.aggcache,
+            tweaks: self.tweaks,
             pub_keys: self.pub_keys,
             pub_nonces: self.pub_nonces,
             aggnonce: self.aggnonce,
@@ -479,6 +507,7 @@ impl WithNonce {
             msg: self.msg,
             nonce: self.nonce,
             aggcache: self.aggcache,
+            tweaks: self.tweaks,
             pub_keys: self.pub_keys,
             aggnonce: Some(AggregatedNonce::new(&nonces.iter().collect::<Vec<_>>())),
             pub_nonces: Some(nonces),
@@ -506,6 +535,7 @@ impl WithAggregatedNonces {
             msg: self.msg,
             nonce: self.nonce,
             aggcache: self.aggcache,
+            tweaks: self.tweaks,
             pub_keys: self.pub_keys,
             pub_nonces: self.pub_nonces,
             aggnonce: self.aggnonce,
@@ -585,6 +615,7 @@ impl WithSession {
             msg: self.msg,
             nonce: None,
             aggcache: self.aggcache,
+            tweaks: self.tweaks,
             pub_keys: self.pub_keys,
             pub_nonces: self.pub_nonces,
             aggnonce: self.aggnonce,
@@ -626,6 +657,412 @@ impl SignedSession {
     }
 }
 
+/// Version of the [`MusigSnapshot`] encoding written by [`MusigBuilder::snapshot`].
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+/// Identifies the secret nonce held by a [`MusigSnapshot`]: the serialized
+/// public nonce it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NonceMarker([u8; 66]);
+
+impl NonceMarker {
+    /// The serialized public nonce.
+    pub fn as_bytes(&self) -> &[u8; 66] {
+        &self.0
+    }
+}
+
+impl fmt::Display for NonceMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl From<&PublicNonce> for NonceMarker {
+    fn from(nonce: &PublicNonce) -> Self {
+        NonceMarker(nonce.serialize())
+    }
+}
+
+/// Error returned by a [`NonceGuard`] whose backing store failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct NonceGuardError(pub String);
+
+/// One-shot consume markers for the secret nonces of [`MusigSnapshot`]s.
+///
+/// [`MusigBuilder::snapshot`] arms the marker of the secret nonce it writes
+/// and [`MusigSnapshot::restore`] consumes it, so every snapshot of a secret
+/// nonce can be restored at most once. Both operations have to be atomic in
+/// the backing store, and the store has to be shared by every process that
+/// may restore the snapshot.
+pub trait NonceGuard: Send + Sync {
+    /// Record that a snapshot holding the secret nonce of `marker` was written.
+    fn arm(&self, marker: &NonceMarker)
+    -> impl Future<Output = Result<(), NonceGuardError>> + Send;
+
+    /// Remove `marker` and return whether it was armed.
+    fn consume(
+        &self,
+        marker: &NonceMarker,
+    ) -> impl Future<Output = Result<bool, NonceGuardError>> + Send;
+}
+
+/// [`NonceGuard`] that keeps its markers in memory; only suitable when the
+/// snapshots are restored by the process that wrote them.
+#[derive(Debug, Default)]
+pub struct MemoryNonceGuard {
+    markers: Mutex<HashSet<NonceMarker>>,
+}
+
+impl MemoryNonceGuard {
+    /// Construct a guard without any armed markers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceGuard for MemoryNonceGuard {
+    fn arm(
+        &self,
+        marker: &NonceMarker,
+    ) -> impl Future<Output = Result<(), NonceGuardError>> + Send {
+        self.markers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(*marker);
+        ready(Ok(()))
+    }
+
+    fn consume(
+        &self,
+        marker: &NonceMarker,
+    ) -> impl Future<Output = Result<bool, NonceGuardError>> + Send {
+        ready(Ok(self
+            .markers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(marker)))
+    }
+}
+
+/// The protocol state a [`MusigSnapshot`] was taken in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotState {
+    /// Taken from a [`WithNonce`] builder.
+    NonceGenerated,
+    /// Taken from a [`WithAggregatedNonces`] builder.
+    NoncesAggregated,
+    /// Taken from a [`WithSession`] builder.
+    SessionInitialized,
+    /// Taken from a [`SignedSession`] builder; holds no secret nonce anymore.
+    Signed,
+}
+
+/// Serializable snapshot of a [`MusigBuilder`], taken at any state after
+/// nonce generation with [`MusigBuilder::snapshot`].
+///
+/// The snapshot lets a signing round span several requests or processes. It
+/// does not contain the signing key, which has to be passed again to
+/// [`MusigSnapshot::restore`]. Unless it was taken in the
+/// [`SnapshotState::Signed`] state, it contains the secret nonce, so the
+/// storage it is written to becomes part of the trusted signing boundary;
+/// restoring it consumes its [`NonceMarker`] from the [`NonceGuard`], which
+/// makes sure the secret nonce signs at most once.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MusigSnapshot {
+    version: u8,
+    state: SnapshotState,
+    pub_keys: Vec<String>,
+    tweaks: Vec<String>,
+    msg: String,
+    pub_nonce: String,
+    sec_nonce: Option<String>,
+    pub_nonces: Option<Vec<String>>,
+    partial_sigs: Vec<Option<String>>,
+}
+
+impl fmt::Debug for MusigSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MusigSnapshot")
+            .field("version", &self.version)
+            .field("state", &self.state)
+            .field("pub_keys", &self.pub_keys)
+            .field("tweaks", &self.tweaks)
+            .field("msg", &self.msg)
+            .field("pub_nonce", &self.pub_nonce)
+            .field("sec_nonce", &self.sec_nonce.as_ref().map(|_| "<redacted>"))
+            .field("pub_nonces", &self.pub_nonces)
+            .field("partial_sigs", &self.partial_sigs)
+            .finish()
+    }
+}
+
+/// A [`MusigBuilder`] restored from a [`MusigSnapshot`], in the state the
+/// snapshot was taken in.
+#[must_use = "the restored builder holds the signing progress of the snapshot"]
+pub enum RestoredSession {
+    /// Restored from [`SnapshotState::NonceGenerated`].
+    WithNonce(WithNonce),
+    /// Restored from [`SnapshotState::NoncesAggregated`].
+    WithAggregatedNonces(WithAggregatedNonces),
+    /// Restored from [`SnapshotState::SessionInitialized`].
+    WithSession(WithSession),
+    /// Restored from [`SnapshotState::Signed`].
+    SignedSession(SignedSession),
+}
+
+/// States a [`MusigSnapshot`] can be taken in.
+pub trait Snapshottable: State {
+    /// The state recorded in the snapshot.
+    const SNAPSHOT_STATE: SnapshotState;
+}
+
+impl Snapshottable for NonceGenerated {
+    const SNAPSHOT_STATE: SnapshotState = SnapshotState::NonceGenerated;
+}
+
+impl Snapshottable for NoncesAggregated {
+    const SNAPSHOT_STATE: SnapshotState = SnapshotState::NoncesAggregated;
+}
+
+impl Snapshottable for SessionInitialized {
+    const SNAPSHOT_STATE: SnapshotState = SnapshotState::SessionInitialized;
+}
+
+impl<S: Snapshottable, T: Signedness> MusigBuilder<S, T> {
+    /// Write a serializable snapshot of the builder, consuming it.
+    ///
+    /// If the builder still holds the secret nonce, its marker is armed in
+    /// `guard` first; the snapshot can then be restored once with
+    /// [`MusigSnapshot::restore`] against the same guard.
+    pub async fn snapshot<G: NonceGuard>(self, guard: &G) -> Result<MusigSnapshot, MusigError> {
+        let state = match self.nonce {
+            Some(_) => S::SNAPSHOT_STATE,
+            None => SnapshotState::Signed,
+        };
+
+        let pub_nonce = match (&self.nonce, &self.pub_nonces) {
+            (Some((_, pub_nonce)), _) => *pub_nonce,
+            (None, Some(pub_nonces)) => {
+                pub_nonces[self
+                    .our_index()
+                    .expect("our public key is in pub_keys (checked at builder construction)")]
+            }
+            (None, None) => unreachable!("snapshottable states have a nonce"),
+        };
+
+        if self.nonce.is_some() {
+            guard.arm(&NonceMarker::from(&pub_nonce)).await?;
+        }
+
+        Ok(MusigSnapshot {
+            version: SNAPSHOT_VERSION,
+            state,
+            pub_keys: self
+                .pub_keys
+                .iter()
+                .map(|key| hex::encode(key.serialize()))
+                .collect(),
+            tweaks: self
+                .tweaks
+                .iter()
+                .map(|tweak| hex::encode(tweak.to_be_bytes()))
+                .collect(),
+            msg: hex::encode(self.msg.expect("snapshottable states have a message")),
+            pub_nonce: hex::encode(pub_nonce.serialize()),
+            sec_nonce: self
+                .nonce
+                .map(|(sec_nonce, _)| hex::encode(sec_nonce.dangerous_into_bytes())),
+            pub_nonces: self.pub_nonces.map(|nonces| {
+                nonces
+                    .iter()
+                    .map(|nonce| hex::encode(nonce.serialize()))
+                    .collect()
+            }),
+            partial_sigs: self
+                .partial_sigs
+                .iter()
+                .map(|sig| sig.map(|sig| hex::encode(sig.serialize())))
+                .collect(),
+        })
+    }
+}
+
+impl MusigSnapshot {
+    /// The protocol state the snapshot was taken in.
+    pub fn state(&self) -> SnapshotState {
+        self.state
+    }
+
+    /// The marker of the secret nonce held by the snapshot, if it holds one.
+    pub fn nonce_marker(&self) -> Result<Option<NonceMarker>, MusigError> {
+        Ok(match self.sec_nonce {
+            Some(_) => Some(NonceMarker::from(&Musig::convert_pub_nonce(&hex::decode(
+                &self.pub_nonce,
+            )?)?)),
+            None => None,
+        })
+    }
+
+    /// Restore the builder with the signing `key`, in the state the snapshot
+    /// was taken in.
+    ///
+    /// Snapshots holding a secret nonce are only restored if their marker is
+    /// armed in `guard`, and the marker is consumed in the process; restoring
+    /// the same snapshot again fails with [`MusigError::NonceConsumed`].
+    pub async fn restore<G: NonceGuard>(
+        self,
+        key: Keypair,
+        guard: &G,
+    ) -> Result<RestoredSession, MusigError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(MusigError::UnsupportedSnapshotVersion(self.version));
+        }
+        if (self.state == SnapshotState::Signed) != self.sec_nonce.is_none()
+            || (self.state == SnapshotState::NonceGenerated) != self.pub_nonces.is_none()
+        {
+            return Err(MusigError::InvalidSnapshot);
+        }
+
+        // Parse everything before consuming the marker, so that a malformed
+        // snapshot does not burn it
+        let pub_keys = self
+            .pub_keys
+            .iter()
+            .map(|key| Musig::convert_pub_key(&hex::decode(key)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tweaks = self
+            .tweaks
+            .iter()
+            .map(|tweak| Musig::convert_scalar_be(&hex::decode(tweak)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let msg: [u8; 32] = hex::decode(&self.msg)?.as_slice().try_into()?;
+        let pub_nonce = Musig::convert_pub_nonce(&hex::decode(&self.pub_nonce)?)?;
+        let sec_nonce: Option<[u8; 132]> = self
+            .sec_nonce
+            .map(|nonce| Ok::<_, MusigError>(hex::decode(nonce)?.as_slice().try_into()?))
+            .transpose()?;
+        let pub_nonces = self
+            .pub_nonces
+            .map(|nonces| {
+                nonces
+                    .iter()
+                    .map(|nonce| Musig::convert_pub_nonce(&hex::decode(nonce)?))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let partial_sigs = self
+            .partial_sigs
+            .iter()
+            .map(|sig| {
+                sig.as_ref()
+                    .map(|sig| Musig::convert_partial_signature(&hex::decode(sig)?))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if partial_sigs.len() != pub_keys.len() {
+            return Err(MusigError::InvalidSnapshot);
+        }
+
+        let mut musig = Musig::setup(key, pub_keys)?;
+        for tweak in &tweaks {
+            musig = musig.xonly_tweak_add(tweak)?;
+        }
+        let musig = musig.message(msg);
+
+        let Some(sec_nonce) = sec_nonce else {
+            let pub_nonces = pub_nonces.expect("checked above");
+            let our_index = musig.our_index()?;
+            if pub_nonces.len() != musig.num_participants() {
+                return Err(MusigError::IncorrectNonceCount);
+            }
+            if pub_nonces.get(our_index) != Some(&pub_nonce) {
+                return Err(MusigError::OurNonceWrongIndex);
+            }
+            let our_sig = partial_sigs[our_index].ok_or(MusigError::InvalidSnapshot)?;
+
+            let aggnonce = AggregatedNonce::new(&pub_nonces.iter().collect::<Vec<_>>());
+            let mut our_partial_sigs = vec![None; pub_nonces.len()];
+            our_partial_sigs[our_index] = Some(our_sig);
+
+            let musig = MusigBuilder {
+                key: musig.key,
+                msg: musig.msg,
+                nonce: None,
+                session: Some(Session::new(&musig.aggcache, aggnonce, &msg)),
+                aggcache: musig.aggcache,
+                tweaks: musig.tweaks,
+                pub_keys: musig.pub_keys,
+                pub_nonces: Some(pub_nonces),
+                aggnonce: Some(aggnonce),
+                partial_sigs: our_partial_sigs,
+                _state: PhantomData::<SessionInitialized>,
+                _signed: PhantomData::<Signed>,
+            };
+            return Ok(RestoredSession::SignedSession(add_partial_signatures(
+                musig,
+                &partial_sigs,
+                our_index,
+            )?));
+        };
+
+        if !guard.consume(&NonceMarker::from(&pub_nonce)).await? {
+            return Err(MusigError::NonceConsumed);
+        }
+
+        let musig = MusigBuilder {
+            key: musig.key,
+            msg: musig.msg,
+            nonce: Some((SecretNonce::dangerous_from_bytes(sec_nonce), pub_nonce)),
+            aggcache: musig.aggcache,
+            tweaks: musig.tweaks,
+            pub_keys: musig.pub_keys,
+            pub_nonces: musig.pub_nonces,
+            aggnonce: musig.aggnonce,
+            session: musig.session,
+            partial_sigs: musig.partial_sigs,
+            _state: PhantomData::<NonceGenerated>,
+            _signed: PhantomData::<Unsigned>,
+        };
+        let Some(pub_nonces) = pub_nonces else {
+            return Ok(RestoredSession::WithNonce(musig));
+        };
+
+        let musig = musig.aggregate_nonces_ordered(pub_nonces)?;
+        if self.state == SnapshotState::NoncesAggregated {
+            return Ok(RestoredSession::WithAggregatedNonces(musig));
+        }
+
+        let our_index = musig.our_index()?;
+        Ok(RestoredSession::WithSession(add_partial_signatures(
+            musig.initialize_session()?,
+            &partial_sigs,
+            our_index,
+        )?))
+    }
+}
+
+/// Re-add the counterparty partial signatures of a snapshot, verifying each.
+fn add_partial_signatures<T: Signedness>(
+    mut musig: MusigBuilder<SessionInitialized, T>,
+    partial_sigs: &[Option<PartialSignature>],
+    our_index: usize,
+) -> Result<MusigBuilder<SessionInitialized, T>, MusigError> {
+    for (index, sig) in partial_sigs.iter().enumerate() {
+        if let Some(sig) = sig
+            && index != our_index
+        {
+            let public_key = musig.pub_keys[index];
+            musig = musig.partial_add(public_key, *sig)?;
+        }
+    }
+
+    Ok(musig)
+}
+
 /// Zero-sized facade for entry points and byte-payload conversion helpers.
 pub struct Musig;
 
@@ -669,6 +1106,7 @@ impl Musig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::executor::block_on;
     use rstest::rstest;
     use secp256k1::musig::new_nonce_pair;
     use secp256k1::rand::Rng;
@@ -684,6 +1122,9 @@ mod tests {
     assert_impl_all!(SignedSession: Send, Sync);
     assert_impl_all!(Musig: Send, Sync);
     assert_impl_all!(MusigError: Send, Sync);
+    assert_impl_all!(MusigSnapshot: Send, Sync);
+    assert_impl_all!(RestoredSession: Send, Sync);
+    assert_impl_all!(MemoryNonceGuard: Send, Sync);
 
     #[test]
     fn test_our_key_not_in_list() {
@@ -775,4 +1216,227 @@ mod tests {
         let sig = sig.verify(&agg_pk, &msg).unwrap();
         assert!(sig.verify(&msg, &agg_pk).is_ok());
     }
+
+    /// Serializes the snapshot to JSON and back, like a cache would
+    fn round_trip(snapshot: MusigSnapshot) -> MusigSnapshot {
+        serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap()
+    }
+
+    fn snapshot_setup() -> (Keypair, Keypair, [u8; 32], Scalar) {
+        let mut msg = [0u8; 32];
+        rand::rng().fill(&mut msg);
+
+        (
+            Keypair::new(&mut rand::rng()),
+            Keypair::new(&mut rand::rng()),
+            msg,
+            Scalar::random(),
+        )
+    }
+
+    fn with_nonce(
+        key: Keypair,
+        pub_keys: Vec<PublicKey>,
+        msg: [u8; 32],
+        tweak: &Scalar,
+    ) -> WithNonce {
+        Musig::setup(key, pub_keys)
+            .unwrap()
+            .xonly_tweak_add(tweak)
+            .unwrap()
+            .message(msg)
+            .generate_nonce(&mut rand::rng())
+    }
+
+    #[rstest]
+    #[case::nonce_generated(SnapshotState::NonceGenerated)]
+    #[case::nonces_aggregated(SnapshotState::NoncesAggregated)]
+    #[case::session_initialized(SnapshotState::SessionInitialized)]
+    #[case::signed(SnapshotState::Signed)]
+    fn test_snapshot_restore(#[case] state: SnapshotState) {
+        let guard = MemoryNonceGuard::new();
+        let (our_key, their_key, msg, tweak) = snapshot_setup();
+        let pub_keys = vec![our_key.public_key(), their_key.public_key()];
+
+        let our = with_nonce(our_key, pub_keys.clone(), msg, &tweak);
+        let their = with_nonce(their_key, pub_keys, msg, &tweak);
+        let our_nonce = *our.pub_nonce();
+        let their_nonces = vec![(their_key.public_key(), *their.pub_nonce())];
+
+        let their = their
+            .aggregate_nonces(vec![(our_key.public_key(), our_nonce)])
+            .unwrap()
+            .initialize_session()
+            .unwrap()
+            .partial_sign()
+            .unwrap();
+        let their_partial = their.our_partial_signature();
+
+        let snapshot = match state {
+            SnapshotState::NonceGenerated => block_on(our.snapshot(&guard)),
+            SnapshotState::NoncesAggregated => block_on(
+                our.aggregate_nonces(their_nonces.clone())
+                    .unwrap()
+                    .snapshot(&guard),
+            ),
+            SnapshotState::SessionInitialized => block_on(
+                our.aggregate_nonces(their_nonces.clone())
+                    .unwrap()
+                    .initialize_session()
+                    .unwrap()
+                    .partial_add(their_key.public_key(), their_partial)
+                    .unwrap()
+                    .snapshot(&guard),
+            ),
+            SnapshotState::Signed => block_on(
+                our.aggregate_nonces(their_nonces.clone())
+                    .unwrap()
+                    .initialize_session()
+                    .unwrap()
+                    .partial_sign()
+                    .unwrap()
+                    .snapshot(&guard),
+            ),
+        }
+        .unwrap();
+        assert_eq!(snapshot.state(), state);
+        assert_eq!(
+            snapshot.nonce_marker().unwrap(),
+            (state != SnapshotState::Signed).then(|| NonceMarker::from(&our_nonce))
+        );
+
+        let restored = block_on(round_trip(snapshot).restore(our_key, &guard)).unwrap();
+        let signed = match restored {
+            RestoredSession::WithNonce(our) => {
+                assert_eq!(state, SnapshotState::NonceGenerated);
+                our.aggregate_nonces(their_nonces)
+                    .unwrap()
+                    .initialize_session()
+                    .unwrap()
+                    .partial_sign()
+                    .unwrap()
+                    .partial_add(their_key.public_key(), their_partial)
+                    .unwrap()
+            }
+            RestoredSession::WithAggregatedNonces(our) => {
+                assert_eq!(state, SnapshotState::NoncesAggregated);
+                our.initialize_session()
+                    .unwrap()
+                    .partial_sign()
+                    .unwrap()
+                    .partial_add(their_key.public_key(), their_partial)
+                    .unwrap()
+            }
+            RestoredSession::WithSession(our) => {
+                assert_eq!(state, SnapshotState::SessionInitialized);
+                our.partial_sign().unwrap()
+            }
+            RestoredSession::SignedSession(our) => {
+                assert_eq!(state, SnapshotState::Signed);
+                our.partial_add(their_key.public_key(), their_partial)
+                    .unwrap()
+            }
+        };
+
+        let agg_pk = signed.agg_pk();
+        let sig = signed.partial_aggregate().unwrap();
+        assert!(sig.verify(&agg_pk, &msg).is_ok());
+    }
+
+    #[test]
+    fn test_snapshot_restore_twice() {
+        let guard = MemoryNonceGuard::new();
+        let (our_key, their_key, msg, tweak) = snapshot_setup();
+
+        let snapshot = block_on(
+            with_nonce(
+                our_key,
+                vec![our_key.public_key(), their_key.public_key()],
+                msg,
+                &tweak,
+            )
+            .snapshot(&guard),
+        )
+        .unwrap();
+        let serialized = serde_json::to_string(&snapshot).unwrap();
+
+        let first: MusigSnapshot = serde_json::from_str(&serialized).unwrap();
+        assert!(block_on(first.restore(our_key, &guard)).is_ok());
+
+        let second: MusigSnapshot = serde_json::from_str(&serialized).unwrap();
+        assert!(matches!(
+            block_on(second.restore(our_key, &guard)).err().unwrap(),
+            MusigError::NonceConsumed
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_restore_unarmed() {
+        let (our_key, their_key, msg, tweak) = snapshot_setup();
+
+        let snapshot = block_on(
+            with_nonce(
+                our_key,
+                vec![our_key.public_key(), their_key.public_key()],
+                msg,
+                &tweak,
+            )
+            .snapshot(&MemoryNonceGuard::new()),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            block_on(snapshot.restore(our_key, &MemoryNonceGuard::new()))
+                .err()
+                .unwrap(),
+            MusigError::NonceConsumed
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_restore_unsupported_version() {
+        let guard = MemoryNonceGuard::new();
+        let (our_key, their_key, msg, tweak) = snapshot_setup();
+
+        let snapshot = block_on(
+            with_nonce(
+                our_key,
+                vec![our_key.public_key(), their_key.public_key()],
+                msg,
+                &tweak,
+            )
+            .snapshot(&guard),
+        )
+        .unwrap();
+
+        let mut json = serde_json::to_value(&snapshot).unwrap();
+        json["version"] = serde_json::Value::from(SNAPSHOT_VERSION + 1);
+        let snapshot: MusigSnapshot = serde_json::from_value(json).unwrap();
+
+        assert!(matches!(
+            block_on(snapshot.restore(our_key, &guard)).err().unwrap(),
+            MusigError::UnsupportedSnapshotVersion(version) if version == SNAPSHOT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_debug_redacts_sec_nonce() {
+        let guard = MemoryNonceGuard::new();
+        let (our_key, their_key, msg, tweak) = snapshot_setup();
+
+        let snapshot = block_on(
+            with_nonce(
+                our_key,
+                vec![our_key.public_key(), their_key.public_key()],
+                msg,
+                &tweak,
+            )
+            .snapshot(&guard),
+        )
+        .unwrap();
+
+        let debug = format!("{snapshot:?}");
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(snapshot.sec_nonce.as_ref().unwrap()));
+    }
 }
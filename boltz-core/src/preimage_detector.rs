@@ -9,8 +9,9 @@ const PREIMAGE_SIZE: usize = 32;
 /// Inspects every witness stack item and then every data push from the
 /// `scriptSig` (legacy / nested segwit), returning the first 32-byte
 /// candidate that hashes to `preimage_hash`. Returns `None` when the
-/// input reveals no matching preimage — a refund, a cooperative
-/// (key-path) spend, or a claim of an unrelated swap.
+/// input reveals no matching preimage — a refund via the `OP_CLTV` or
+/// `OP_CSV` refund leaf, a cooperative (key-path) spend, or a claim of an
+/// unrelated swap.
 ///
 /// Matching against the expected hash (rather than returning the first
 /// 32-byte push) ensures an unrelated 32-byte item — an x-only public
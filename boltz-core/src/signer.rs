@@ -0,0 +1,121 @@
+//! Signing of swap inputs, abstracted behind the [`Signer`] trait so that the
+//! keys do not have to live in the process that constructs the transactions.
+//!
+//! A [`Keypair`] is the default implementation and signs locally; remote or
+//! HSM-backed signers implement [`Signer`] and are passed in its place as the
+//! `keys` of the input details.
+
+use crate::consts::ECDSA_BYTES_TO_GRIND;
+use bitcoin::secp256k1::{
+    All, Keypair, Message, PublicKey, Secp256k1, XOnlyPublicKey, ecdsa, schnorr,
+};
+use std::{
+    future::{Future, ready},
+    pin::pin,
+    sync::LazyLock,
+    task::{Context, Poll, Waker},
+};
+
+static SECP: LazyLock<Secp256k1<All>> = LazyLock::new(Secp256k1::new);
+
+/// Error returned by a [`Signer`] that could not create a signature.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct SignerError(pub String);
+
+/// Creates the signatures of one input of a claim or refund transaction.
+///
+/// The signing methods are async, so that implementations can wait for a
+/// remote signer or an HSM. Schnorr signatures are used for Taproot inputs
+/// and have to be made with the key the spending path commits to (for
+/// key-path spends that is the tweaked output key); ECDSA signatures are used
+/// for all other inputs.
+pub trait Signer: Send + Sync {
+    /// The public key whose signatures this signer creates.
+    fn public_key(&self) -> PublicKey;
+
+    /// The x-only variant of [`Signer::public_key`], as used in Taproot.
+    fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.public_key().x_only_public_key().0
+    }
+
+    /// Create a BIP-340 Schnorr signature of the sighash `msg`.
+    fn sign_schnorr(
+        &self,
+        msg: &Message,
+    ) -> impl Future<Output = Result<schnorr::Signature, SignerError>> + Send;
+
+    /// Create an ECDSA signature of the sighash `msg`.
+    fn sign_ecdsa(
+        &self,
+        msg: &Message,
+    ) -> impl Future<Output = Result<ecdsa::Signature, SignerError>> + Send;
+}
+
+impl Signer for Keypair {
+    fn public_key(&self) -> PublicKey {
+        Keypair::public_key(self)
+    }
+
+    fn sign_schnorr(
+        &self,
+        msg: &Message,
+    ) -> impl Future<Output = Result<schnorr::Signature, SignerError>> + Send {
+        ready(Ok(SECP.sign_schnorr(msg, self)))
+    }
+
+    fn sign_ecdsa(
+        &self,
+        msg: &Message,
+    ) -> impl Future<Output = Result<ecdsa::Signature, SignerError>> + Send {
+        // Grinding for a low R saves a byte in most signatures
+        ready(Ok(SECP.sign_ecdsa_grind_r(
+            msg,
+            &self.secret_key(),
+            ECDSA_BYTES_TO_GRIND,
+        )))
+    }
+}
+
+/// Resolves the future of a signer that never suspends, like a local [`Keypair`].
+pub(crate) fn resolve_local<F: Future>(future: F) -> F::Output {
+    match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("local signers do not suspend"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::secp256k1::rand;
+
+    #[test]
+    fn test_keypair_public_key() {
+        let keys = Keypair::new(&SECP, &mut rand::thread_rng());
+
+        assert_eq!(Signer::public_key(&keys), keys.public_key());
+        assert_eq!(Signer::x_only_public_key(&keys), keys.x_only_public_key().0);
+    }
+
+    #[test]
+    fn test_keypair_sign_schnorr() {
+        let keys = Keypair::new(&SECP, &mut rand::thread_rng());
+        let msg = Message::from_digest([1; 32]);
+
+        let sig = resolve_local(Signer::sign_schnorr(&keys, &msg)).unwrap();
+        SECP.verify_schnorr(&sig, &msg, &keys.x_only_public_key().0)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_keypair_sign_ecdsa() {
+        let keys = Keypair::new(&SECP, &mut rand::thread_rng());
+        let msg = Message::from_digest([1; 32]);
+
+        let sig = resolve_local(Signer::sign_ecdsa(&keys, &msg)).unwrap();
+        SECP.verify_ecdsa(&msg, &sig, &keys.public_key()).unwrap();
+        // Low R signatures are at most 70 bytes
+        assert!(sig.serialize_der().len() <= 70);
+    }
+}
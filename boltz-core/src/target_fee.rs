@@ -1,9 +1,14 @@
 use crate::utils::Transaction;
 
+/// Rounds of rebuilding after which [`FeeTarget::Bounded`] settles for the
+/// highest fee it has seen, when signature sizes keep the vsize flipping
+const MAX_CONVERGENCE_ROUNDS: usize = 10;
+
 /// How a transaction's fee should be selected.
 ///
-/// Implements `From<u64>` ([`FeeTarget::Absolute`]) and `From<f64>`
-/// ([`FeeTarget::Relative`]) for ergonomic call sites.
+/// Implements `From<u64>` ([`FeeTarget::Absolute`]), `From<f64>`
+/// ([`FeeTarget::Relative`]) and `From<FeeBounds>` ([`FeeTarget::Bounded`])
+/// for ergonomic call sites.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FeeTarget {
     /// Pay exactly this many satoshis as fee, regardless of transaction size.
@@ -11,6 +16,62 @@ pub enum FeeTarget {
     /// Pay this fee rate, in sat/vB. The transaction is built once to
     /// estimate its virtual size, then rebuilt with the resulting fee.
     Relative(f64),
+    /// Pay the fee rate of [`FeeBounds`] within its bounds. Instead of padding
+    /// the estimate, the transaction is rebuilt until its virtual size yields
+    /// the fee it was built with. Only the range proofs of blinded Elements
+    /// outputs can still change the size of the final transaction slightly.
+    Bounded(FeeBounds),
+}
+
+/// Fee rate and bounds of a [`FeeTarget::Bounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FeeBounds {
+    /// Fee rate to pay, in sat/vB.
+    pub fee_rate: f64,
+    /// Lowest fee in satoshis; smaller fees are raised to it.
+    pub min_fee: Option<u64>,
+    /// Highest fee in satoshis.
+    pub max_fee: Option<u64>,
+    /// Highest fee as percentage of the value of the inputs.
+    pub max_fee_percentage: Option<f64>,
+}
+
+/// Why the fee of a [`FeeTarget::Bounded`] could not be met.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum FeeBoundsError {
+    /// The minimum fee is larger than the maximum fee.
+    #[error("minimum fee of {min_fee} sat is greater than maximum fee of {max_fee} sat")]
+    MinAboveMax {
+        /// Configured minimum fee, in satoshis.
+        min_fee: u64,
+        /// Configured maximum fee, in satoshis.
+        max_fee: u64,
+    },
+    /// The maximum fee percentage is non-finite or negative.
+    #[error("invalid maximum fee percentage")]
+    InvalidPercentage,
+    /// The fee required by the fee rate is larger than the maximum fee.
+    #[error("fee of {fee} sat exceeds maximum fee of {max_fee} sat")]
+    AboveMax {
+        /// Fee the transaction would have to pay, in satoshis.
+        fee: u64,
+        /// Configured maximum fee, in satoshis.
+        max_fee: u64,
+    },
+    /// The fee required by the fee rate is a larger share of the input value
+    /// than allowed.
+    #[error(
+        "fee of {fee} sat exceeds maximum fee of {max_fee} sat for input value of {input_value} sat"
+    )]
+    AbovePercentage {
+        /// Fee the transaction would have to pay, in satoshis.
+        fee: u64,
+        /// Maximum fee the percentage allows for the input value, in satoshis.
+        max_fee: u64,
+        /// Value of the inputs the fee is paid from, in satoshis.
+        input_value: u64,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
@@ -18,6 +79,8 @@ pub enum FeeTarget {
 pub(crate) enum FeeError {
     #[error("invalid fee rate")]
     InvalidFeeRate,
+    #[error(transparent)]
+    Bounds(#[from] FeeBoundsError),
 }
 
 impl From<u64> for FeeTarget {
@@ -32,16 +95,91 @@ impl From<f64> for FeeTarget {
     }
 }
 
+impl From<FeeBounds> for FeeTarget {
+    fn from(value: FeeBounds) -> Self {
+        Self::Bounded(value)
+    }
+}
+
 impl std::fmt::Display for FeeTarget {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             FeeTarget::Absolute(fee) => write!(f, "{} sat", fee),
             FeeTarget::Relative(rate) => write!(f, "{} sat/vB", rate),
+            FeeTarget::Bounded(bounds) => {
+                write!(f, "{} sat/vB", bounds.fee_rate)?;
+                if let Some(min_fee) = bounds.min_fee {
+                    write!(f, ", min {} sat", min_fee)?;
+                }
+                if let Some(max_fee) = bounds.max_fee {
+                    write!(f, ", max {} sat", max_fee)?;
+                }
+                if let Some(max_fee_percentage) = bounds.max_fee_percentage {
+                    write!(f, ", max {}%", max_fee_percentage)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FeeBounds {
+    fn validate(&self) -> Result<(), FeeError> {
+        if !self.fee_rate.is_finite() || self.fee_rate < 0.0 {
+            return Err(FeeError::InvalidFeeRate);
+        }
+
+        if let (Some(min_fee), Some(max_fee)) = (self.min_fee, self.max_fee)
+            && min_fee > max_fee
+        {
+            return Err(FeeBoundsError::MinAboveMax { min_fee, max_fee }.into());
+        }
+
+        if let Some(max_fee_percentage) = self.max_fee_percentage
+            && (!max_fee_percentage.is_finite() || max_fee_percentage < 0.0)
+        {
+            return Err(FeeBoundsError::InvalidPercentage.into());
+        }
+
+        Ok(())
+    }
+
+    /// Fee for a transaction of `vsize`, raised to the minimum fee
+    fn fee(&self, vsize: usize) -> u64 {
+        let fee = (vsize as f64 * self.fee_rate).ceil() as u64;
+        self.min_fee.map_or(fee, |min_fee| fee.max(min_fee))
+    }
+
+    fn check(&self, fee: u64, input_value: u64) -> Result<(), FeeBoundsError> {
+        if let Some(max_fee) = self.max_fee
+            && fee > max_fee
+        {
+            return Err(FeeBoundsError::AboveMax { fee, max_fee });
+        }
+
+        if let Some(max_fee_percentage) = self.max_fee_percentage {
+            let max_fee = (input_value as f64 * max_fee_percentage / 100.0).floor() as u64;
+            if fee > max_fee {
+                return Err(FeeBoundsError::AbovePercentage {
+                    fee,
+                    max_fee,
+                    input_value,
+                });
+            }
         }
+
+        Ok(())
     }
 }
 
-pub(crate) fn target_fee<T, E, C>(fee_target: FeeTarget, construct_tx: C) -> Result<(T, u64), E>
+/// Builds the transaction with the fee `fee_target` asks for. `input_value`
+/// is the value the fee is paid from, in satoshis, which the
+/// maximum fee percentage of [`FeeTarget::Bounded`] refers to.
+pub(crate) fn target_fee<T, E, C>(
+    fee_target: FeeTarget,
+    input_value: u64,
+    construct_tx: C,
+) -> Result<(T, u64), E>
 where
     T: Transaction,
     E: From<FeeError>,
@@ -59,6 +197,29 @@ where
             let fee = ((tx.vsize() + tx.input_len()) as f64 * fee_rate).ceil() as u64;
             Ok((construct_tx(fee, false)?, fee))
         }
+        FeeTarget::Bounded(bounds) => {
+            bounds.validate()?;
+
+            // The fee changes the sighash and with it the size of the
+            // signatures, so the size is measured again with every new fee
+            let mut fee = bounds.fee(construct_tx(1, true)?.vsize());
+            let mut highest_fee = fee;
+            for _ in 0..MAX_CONVERGENCE_ROUNDS {
+                let required = bounds.fee(construct_tx(fee, true)?.vsize());
+                if required == fee {
+                    highest_fee = fee;
+                    break;
+                }
+
+                fee = required;
+                highest_fee = highest_fee.max(required);
+            }
+
+            bounds
+                .check(highest_fee, input_value)
+                .map_err(FeeError::from)?;
+            Ok((construct_tx(highest_fee, false)?, highest_fee))
+        }
     }
 }
 
@@ -67,6 +228,8 @@ mod tests {
     use super::*;
     use std::cell::Cell;
 
+    const INPUT_VALUE: u64 = 100_000;
+
     #[derive(Debug, Clone, PartialEq, thiserror::Error)]
     enum MockError {
         #[error("{0}")]
@@ -109,12 +272,28 @@ mod tests {
 
         let relative = 21.21;
         assert_eq!(FeeTarget::from(relative), FeeTarget::Relative(relative));
+
+        let bounds = FeeBounds {
+            fee_rate: 2.0,
+            ..Default::default()
+        };
+        assert_eq!(FeeTarget::from(bounds), FeeTarget::Bounded(bounds));
     }
 
     #[test]
     fn test_display() {
         assert_eq!(FeeTarget::Absolute(1000).to_string(), "1000 sat");
         assert_eq!(FeeTarget::Relative(2.5).to_string(), "2.5 sat/vB");
+        assert_eq!(
+            FeeTarget::Bounded(FeeBounds {
+                fee_rate: 2.5,
+                min_fee: Some(100),
+                max_fee: Some(5_000),
+                max_fee_percentage: Some(1.5),
+            })
+            .to_string(),
+            "2.5 sat/vB, min 100 sat, max 5000 sat, max 1.5%"
+        );
     }
 
     #[test]
@@ -125,6 +304,7 @@ mod tests {
 
         let result: Result<_, MockError> = target_fee(
             FeeTarget::Absolute(target_fee_value),
+            INPUT_VALUE,
             |fee, is_fee_estimation| {
                 assert!(!is_fee_estimation);
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
@@ -148,8 +328,10 @@ mod tests {
         let expected_fee = ((mock_vsize + mock_input_count) as f64 * fee_rate).ceil() as u64;
 
         let call_count = Cell::new(0);
-        let result: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(fee_rate), |fee, is_fee_estimation| {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(fee_rate),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
                 let count = call_count.get() + 1;
                 call_count.set(count);
                 if count == 1 {
@@ -160,7 +342,8 @@ mod tests {
                     assert!(!is_fee_estimation);
                 }
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
-            });
+            },
+        );
 
         assert!(result.is_ok());
         let (tx, fee) = result.unwrap();
@@ -181,8 +364,10 @@ mod tests {
         assert_eq!(expected_fee, 152);
 
         let call_count = Cell::new(0);
-        let result: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(fee_rate), |fee, is_fee_estimation| {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(fee_rate),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
                 let count = call_count.get() + 1;
                 call_count.set(count);
                 if count == 1 {
@@ -193,7 +378,8 @@ mod tests {
                     assert!(!is_fee_estimation);
                 }
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
-            });
+            },
+        );
 
         assert!(result.is_ok());
         let (tx, fee) = result.unwrap();
@@ -212,8 +398,10 @@ mod tests {
         assert_eq!(expected_fee, 13);
 
         let call_count = Cell::new(0);
-        let result: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(fee_rate), |fee, is_fee_estimation| {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(fee_rate),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
                 let count = call_count.get() + 1;
                 call_count.set(count);
                 if count == 1 {
@@ -224,7 +412,8 @@ mod tests {
                     assert!(!is_fee_estimation);
                 }
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
-            });
+            },
+        );
 
         assert!(result.is_ok());
         let (tx, fee) = result.unwrap();
@@ -240,8 +429,10 @@ mod tests {
         let mock_input_count = 2;
 
         let call_count = Cell::new(0);
-        let result: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(fee_rate), |fee, is_fee_estimation| {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(fee_rate),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
                 let count = call_count.get() + 1;
                 call_count.set(count);
                 if count == 1 {
@@ -252,7 +443,8 @@ mod tests {
                     assert!(!is_fee_estimation);
                 }
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
-            });
+            },
+        );
 
         assert!(result.is_ok());
         let (tx, fee) = result.unwrap();
@@ -265,6 +457,7 @@ mod tests {
     fn test_constructor_error_propagation() {
         let result = target_fee::<MockTransaction, MockError, _>(
             FeeTarget::Absolute(1000),
+            INPUT_VALUE,
             |_fee, _is_fee_estimation| Err(MockError::Construction("Construction failed")),
         );
 
@@ -284,8 +477,10 @@ mod tests {
         assert_eq!(expected_fee, 2);
 
         let call_count = Cell::new(0);
-        let result: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(fee_rate), |fee, is_fee_estimation| {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(fee_rate),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
                 let count = call_count.get() + 1;
                 call_count.set(count);
                 if count == 1 {
@@ -296,7 +491,8 @@ mod tests {
                     assert!(!is_fee_estimation);
                 }
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
-            });
+            },
+        );
 
         assert!(result.is_ok());
         let (tx, fee) = result.unwrap();
@@ -315,8 +511,10 @@ mod tests {
         assert_eq!(expected_fee, 550000);
 
         let call_count = Cell::new(0);
-        let result: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(fee_rate), |fee, is_fee_estimation| {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(fee_rate),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
                 let count = call_count.get() + 1;
                 call_count.set(count);
                 if count == 1 {
@@ -327,7 +525,8 @@ mod tests {
                     assert!(!is_fee_estimation);
                 }
                 Ok(MockTransaction::new(fee, mock_vsize, mock_input_count))
-            });
+            },
+        );
 
         assert!(result.is_ok());
         let (tx, fee) = result.unwrap();
@@ -340,6 +539,7 @@ mod tests {
     fn test_invalid_fee_rate() {
         let infinity: Result<_, MockError> = target_fee(
             FeeTarget::Relative(f64::INFINITY),
+            INPUT_VALUE,
             |_fee, _is_fee_estimation| Ok(MockTransaction::new(0, 0, 0)),
         );
         assert_eq!(
@@ -347,13 +547,164 @@ mod tests {
             MockError::Fee(FeeError::InvalidFeeRate)
         );
 
-        let negative: Result<_, MockError> =
-            target_fee(FeeTarget::Relative(-2.1), |_fee, _is_fee_estimation| {
-                Ok(MockTransaction::new(0, 0, 0))
-            });
+        let negative: Result<_, MockError> = target_fee(
+            FeeTarget::Relative(-2.1),
+            INPUT_VALUE,
+            |_fee, _is_fee_estimation| Ok(MockTransaction::new(0, 0, 0)),
+        );
         assert_eq!(
             negative.unwrap_err(),
             MockError::Fee(FeeError::InvalidFeeRate)
         );
     }
+
+    fn bounded(fee_rate: f64) -> FeeBounds {
+        FeeBounds {
+            fee_rate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bounded_fee_target_converges() {
+        // Signatures get one byte larger once the fee exceeds 200 sat
+        let vsize = |fee: u64| if fee > 200 { 101 } else { 100 };
+
+        let rounds = Cell::new(0);
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Bounded(bounded(2.5)),
+            INPUT_VALUE,
+            |fee, is_fee_estimation| {
+                if is_fee_estimation {
+                    rounds.set(rounds.get() + 1);
+                }
+                Ok(MockTransaction::new(fee, vsize(fee), 1))
+            },
+        );
+
+        let (tx, fee) = result.unwrap();
+        assert_eq!(fee, 253);
+        assert_eq!(tx.fee, fee);
+        assert_eq!(fee, (vsize(fee) as f64 * 2.5).ceil() as u64);
+        assert_eq!(rounds.get(), 3);
+    }
+
+    #[test]
+    fn test_bounded_fee_target_oscillation() {
+        // The size flips with every fee, so the highest fee is paid
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Bounded(bounded(1.0)),
+            INPUT_VALUE,
+            |fee, _is_fee_estimation| {
+                Ok(MockTransaction::new(
+                    fee,
+                    if fee % 2 == 0 { 101 } else { 100 },
+                    1,
+                ))
+            },
+        );
+
+        assert_eq!(result.unwrap().1, 101);
+    }
+
+    #[test]
+    fn test_bounded_fee_target_min_fee() {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Bounded(FeeBounds {
+                min_fee: Some(500),
+                ..bounded(1.0)
+            }),
+            INPUT_VALUE,
+            |fee, _is_fee_estimation| Ok(MockTransaction::new(fee, 150, 1)),
+        );
+
+        assert_eq!(result.unwrap().1, 500);
+    }
+
+    #[test]
+    fn test_bounded_fee_target_max_fee() {
+        let result: Result<_, MockError> = target_fee(
+            FeeTarget::Bounded(FeeBounds {
+                max_fee: Some(299),
+                ..bounded(2.0)
+            }),
+            INPUT_VALUE,
+            |fee, _is_fee_estimation| Ok(MockTransaction::new(fee, 150, 1)),
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            MockError::Fee(FeeError::Bounds(FeeBoundsError::AboveMax {
+                fee: 300,
+                max_fee: 299,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_bounded_fee_target_max_fee_percentage() {
+        let target = FeeTarget::Bounded(FeeBounds {
+            max_fee_percentage: Some(0.3),
+            ..bounded(2.0)
+        });
+        let construct = |fee, _is_fee_estimation| Ok(MockTransaction::new(fee, 150, 1));
+
+        let within: Result<_, MockError> = target_fee(target, INPUT_VALUE, construct);
+        assert_eq!(within.unwrap().1, 300);
+
+        let exceeded: Result<_, MockError> = target_fee(target, INPUT_VALUE - 1, construct);
+        assert_eq!(
+            exceeded.unwrap_err(),
+            MockError::Fee(FeeError::Bounds(FeeBoundsError::AbovePercentage {
+                fee: 300,
+                max_fee: 299,
+                input_value: INPUT_VALUE - 1,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_bounded_fee_target_invalid_bounds() {
+        let construct = |_fee, _is_fee_estimation| -> Result<_, MockError> {
+            Ok(MockTransaction::new(0, 0, 0))
+        };
+
+        assert_eq!(
+            target_fee(
+                FeeTarget::Bounded(bounded(f64::NAN)),
+                INPUT_VALUE,
+                construct
+            )
+            .unwrap_err(),
+            MockError::Fee(FeeError::InvalidFeeRate)
+        );
+        assert_eq!(
+            target_fee(
+                FeeTarget::Bounded(FeeBounds {
+                    min_fee: Some(2),
+                    max_fee: Some(1),
+                    ..bounded(1.0)
+                }),
+                INPUT_VALUE,
+                construct
+            )
+            .unwrap_err(),
+            MockError::Fee(FeeError::Bounds(FeeBoundsError::MinAboveMax {
+                min_fee: 2,
+                max_fee: 1,
+            }))
+        );
+        assert_eq!(
+            target_fee(
+                FeeTarget::Bounded(FeeBounds {
+                    max_fee_percentage: Some(-1.0),
+                    ..bounded(1.0)
+                }),
+                INPUT_VALUE,
+                construct
+            )
+            .unwrap_err(),
+            MockError::Fee(FeeError::Bounds(FeeBoundsError::InvalidPercentage))
+        );
+    }
 }
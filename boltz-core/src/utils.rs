@@ -31,6 +31,28 @@ pub enum InputType {
     Claim([u8; 32]),
     /// Refund leg, holding the absolute locktime that must be reached.
     Refund(u32),
+    /// Refund leg of an `OP_CSV` refund leaf, holding the consensus-encoded
+    /// BIP-68 relative lock time that is set as `nSequence` of the input.
+    ///
+    /// Only Taproot swap outputs have relative refund leaves.
+    RelativeRefund(u32),
+    /// Ordinary wallet UTXO that adds funds next to the swap inputs, e.g. to
+    /// pay the fee of a claim that is too small to cover it.
+    ///
+    /// Only single-key outputs are supported: P2WPKH with the `scriptPubKey`
+    /// as [`OutputType::SegwitV0`] payload, or a P2TR key-path spend with
+    /// `OutputType::Taproot(None)`, for which the signer has to hold the
+    /// tweaked key.
+    Wallet,
+}
+
+/// The swap template a script or script tree was built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapKind {
+    /// Submarine swap: the claim path only checks the preimage hash.
+    Submarine,
+    /// Reverse swap: the claim path also enforces a 32-byte preimage.
+    Reverse,
 }
 
 /// Where the funds of a constructed transaction should be sent.
@@ -3,10 +3,11 @@
 //!
 //! [`construct_tx`] dispatches on a [`Params`] enum and returns a unified
 //! [`Transaction`] that can be serialized or queried for its txid without
-//! caring which chain produced it.
+//! caring which chain produced it. Like the per-chain constructors, it is
+//! generic over the [`Signer`] of the inputs and defaults to local keypairs.
 
-use crate::{target_fee::FeeTarget, utils::Destination};
-use bitcoin::{Address as BitcoinAddress, Transaction as BitcoinTransaction};
+use crate::{Signer, target_fee::FeeTarget, utils::Destination};
+use bitcoin::{Address as BitcoinAddress, Transaction as BitcoinTransaction, key::Keypair};
 use elements::hex::ToHex;
 use elements::pset::serialize::Serialize;
 use elements::{Address as ElementsAddress, BlockHash, Transaction as ElementsTransaction};
@@ -36,17 +37,17 @@ pub enum WrapperError {
 /// enum small (`BitcoinInputDetail` and `ElementsInputDetail` differ
 /// significantly in size).
 #[derive(Debug, Clone, PartialEq)]
-pub enum InputDetail {
+pub enum InputDetail<S = Keypair> {
     /// A Bitcoin input.
-    Bitcoin(Box<BitcoinInputDetail>),
+    Bitcoin(Box<BitcoinInputDetail<S>>),
     /// An Elements input.
-    Elements(Box<ElementsInputDetail>),
+    Elements(Box<ElementsInputDetail<S>>),
 }
 
-impl TryInto<BitcoinInputDetail> for InputDetail {
+impl<S> TryInto<BitcoinInputDetail<S>> for InputDetail<S> {
     type Error = WrapperError;
 
-    fn try_into(self) -> Result<BitcoinInputDetail, Self::Error> {
+    fn try_into(self) -> Result<BitcoinInputDetail<S>, Self::Error> {
         match self {
             InputDetail::Bitcoin(input) => Ok(*input),
             InputDetail::Elements(_) => Err(WrapperError::NotBitcoin),
@@ -54,10 +55,10 @@ impl TryInto<BitcoinInputDetail> for InputDetail {
     }
 }
 
-impl TryInto<ElementsInputDetail> for InputDetail {
+impl<S> TryInto<ElementsInputDetail<S>> for InputDetail<S> {
     type Error = WrapperError;
 
-    fn try_into(self) -> Result<ElementsInputDetail, Self::Error> {
+    fn try_into(self) -> Result<ElementsInputDetail<S>, Self::Error> {
         match self {
             InputDetail::Bitcoin(_) => Err(WrapperError::NotElements),
             InputDetail::Elements(input) => Ok(*input),
@@ -67,9 +68,9 @@ impl TryInto<ElementsInputDetail> for InputDetail {
 
 /// Inputs for the Bitcoin branch of [`construct_tx`].
 #[derive(Debug, Clone, PartialEq)]
-pub struct BitcoinParams<'a> {
+pub struct BitcoinParams<'a, S = Keypair> {
     /// UTXOs to spend.
-    pub inputs: Vec<BitcoinInputDetail>,
+    pub inputs: Vec<BitcoinInputDetail<S>>,
     /// Where to send the funds.
     pub destination: &'a Destination<'a, &'a BitcoinAddress>,
     /// Fee selection.
@@ -78,11 +79,11 @@ pub struct BitcoinParams<'a> {
 
 /// Inputs for the Elements branch of [`construct_tx`].
 #[derive(Debug, Clone, PartialEq)]
-pub struct ElementsParams<'a> {
+pub struct ElementsParams<'a, S = Keypair> {
     /// Network-specific Liquid genesis block hash (used in sighash domain separation).
     pub genesis_hash: BlockHash,
     /// UTXOs to spend.
-    pub inputs: Vec<ElementsInputDetail>,
+    pub inputs: Vec<ElementsInputDetail<S>>,
     /// Where to send the funds.
     pub destination: &'a Destination<'a, &'a ElementsAddress>,
     /// Fee selection.
@@ -91,11 +92,11 @@ pub struct ElementsParams<'a> {
 
 /// Per-chain parameters dispatched on by [`construct_tx`].
 #[derive(Debug, Clone, PartialEq)]
-pub enum Params<'a> {
+pub enum Params<'a, S = Keypair> {
     /// Build a Bitcoin transaction.
-    Bitcoin(BitcoinParams<'a>),
+    Bitcoin(BitcoinParams<'a, S>),
     /// Build an Elements transaction.
-    Elements(ElementsParams<'a>),
+    Elements(ElementsParams<'a, S>),
 }
 
 /// A constructed transaction on either chain.
@@ -130,12 +131,15 @@ impl Transaction {
 /// and [`elements::construct_tx`](crate::elements::construct_tx) for the
 /// per-chain semantics.
 #[must_use = "ignoring the result discards the constructed transaction"]
-pub fn construct_tx(params: Params) -> Result<(Transaction, u64), WrapperError> {
+pub async fn construct_tx<S: Signer>(
+    params: Params<'_, S>,
+) -> Result<(Transaction, u64), WrapperError> {
     match params {
         Params::Bitcoin(params) => {
             let secp = bitcoin::secp256k1::Secp256k1::new();
             let (tx, fee) =
-                crate::bitcoin::construct_tx(&secp, params.inputs, params.destination, params.fee)?;
+                crate::bitcoin::construct_tx(&secp, params.inputs, params.destination, params.fee)
+                    .await?;
             Ok((Transaction::Bitcoin(tx), fee))
         }
         Params::Elements(params) => {
@@ -146,7 +150,8 @@ pub fn construct_tx(params: Params) -> Result<(Transaction, u64), WrapperError>
                 params.inputs,
                 params.destination,
                 params.fee,
-            )?;
+            )
+            .await?;
             Ok((Transaction::Elements(tx), fee))
         }
     }
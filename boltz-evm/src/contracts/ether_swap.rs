@@ -6,7 +6,7 @@ use alloy::primitives::{Address, B256, FixedBytes, U256};
 use alloy::providers::network::AnyNetwork;
 use alloy::providers::{CallItemBuilder, DynProvider, Provider};
 use alloy::rpc::types::Log;
-use alloy::sol_types::SolValue;
+use alloy::sol_types::{SolEvent, SolValue};
 use anyhow::anyhow;
 use tracing::{debug, info};
 
@@ -34,6 +34,9 @@ mod v6 {
 
 pub const NAME: &str = "EtherSwap";
 
+/// Topic of `Lockup` events; the indexed parameters changed between versions, the signature did not
+pub const LOCKUP_SIGNATURE: B256 = v6::EtherSwap::Lockup::SIGNATURE_HASH;
+
 macro_rules! with_ether_contract {
     ($this:expr, $contract:ident => $expr:expr) => {{
         if $this.version >= 6 {
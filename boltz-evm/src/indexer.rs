@@ -0,0 +1,786 @@
+use crate::contracts::erc20_swap::{self, ERC20SwapContract, ERC20SwapLockup};
+use crate::contracts::ether_swap::{self, EtherSwapContract, EtherSwapLockup};
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256, FixedBytes, U256};
+use alloy::providers::network::AnyNetwork;
+use alloy::providers::{DynProvider, Provider};
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol_types::SolEvent;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, instrument, warn};
+
+mod events {
+    use alloy::sol;
+
+    // Both swap contracts emit the same claim and refund events in all versions
+    sol! {
+        event Claim(bytes32 indexed preimageHash, bytes32 preimage);
+        event Refund(bytes32 indexed preimageHash);
+    }
+}
+
+const fn default_batch_size() -> u64 {
+    1_000
+}
+
+const fn default_reorg_depth() -> u64 {
+    128
+}
+
+const fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct Config {
+    /// Height from which indexing starts when nothing was indexed yet
+    #[serde(rename = "startHeight")]
+    pub start_height: u64,
+
+    /// Maximum number of blocks queried for logs at once
+    #[serde(rename = "batchSize", default = "default_batch_size")]
+    pub batch_size: u64,
+
+    /// Number of blocks below the tip that are kept to find the fork point of a reorg
+    #[serde(rename = "reorgDepth", default = "default_reorg_depth")]
+    pub reorg_depth: u64,
+
+    /// Seconds between polls for new blocks
+    #[serde(rename = "interval", default = "default_interval")]
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedBlock {
+    pub height: u64,
+    pub hash: B256,
+    pub parent_hash: B256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lockup {
+    pub preimage_hash: FixedBytes<32>,
+    pub amount: U256,
+    /// `None` for lockups of the EtherSwap contract
+    pub token_address: Option<Address>,
+    pub claim_address: Address,
+    pub refund_address: Address,
+    pub timelock: U256,
+}
+
+impl From<EtherSwapLockup> for Lockup {
+    fn from(lockup: EtherSwapLockup) -> Self {
+        Self {
+            preimage_hash: lockup.preimage_hash,
+            amount: lockup.amount,
+            token_address: None,
+            claim_address: lockup.claim_address,
+            refund_address: lockup.refund_address,
+            timelock: lockup.timelock,
+        }
+    }
+}
+
+impl From<ERC20SwapLockup> for Lockup {
+    fn from(lockup: ERC20SwapLockup) -> Self {
+        Self {
+            preimage_hash: lockup.preimage_hash,
+            amount: lockup.amount,
+            token_address: Some(lockup.token_address),
+            claim_address: lockup.claim_address,
+            refund_address: lockup.refund_address,
+            timelock: lockup.timelock,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapEvent {
+    Lockup(Lockup),
+    Claim {
+        preimage_hash: FixedBytes<32>,
+        preimage: FixedBytes<32>,
+    },
+    Refund {
+        preimage_hash: FixedBytes<32>,
+    },
+}
+
+impl SwapEvent {
+    pub fn preimage_hash(&self) -> FixedBytes<32> {
+        match self {
+            SwapEvent::Lockup(lockup) => lockup.preimage_hash,
+            SwapEvent::Claim { preimage_hash, .. } => *preimage_hash,
+            SwapEvent::Refund { preimage_hash } => *preimage_hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedEvent {
+    pub contract_address: Address,
+    pub block_height: u64,
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    pub log_index: u64,
+    pub event: SwapEvent,
+}
+
+impl IndexedEvent {
+    pub fn decode(log: &Log) -> Option<Self> {
+        let event = match *log.topic0()? {
+            ether_swap::LOCKUP_SIGNATURE => {
+                SwapEvent::Lockup(EtherSwapContract::decode_lockup_log(log)?.into())
+            }
+            erc20_swap::LOCKUP_SIGNATURE => {
+                SwapEvent::Lockup(ERC20SwapContract::decode_lockup_log(log)?.into())
+            }
+            events::Claim::SIGNATURE_HASH => {
+                let claim = log.log_decode::<events::Claim>().ok()?.inner.data;
+                SwapEvent::Claim {
+                    preimage_hash: claim.preimageHash,
+                    preimage: claim.preimage,
+                }
+            }
+            events::Refund::SIGNATURE_HASH => SwapEvent::Refund {
+                preimage_hash: log
+                    .log_decode::<events::Refund>()
+                    .ok()?
+                    .inner
+                    .data
+                    .preimageHash,
+            },
+            _ => return None,
+        };
+
+        Some(Self {
+            contract_address: log.address(),
+            block_height: log.block_number?,
+            block_hash: log.block_hash?,
+            transaction_hash: log.transaction_hash?,
+            log_index: log.log_index?,
+            event,
+        })
+    }
+}
+
+/// Persistence of the indexed blocks and events of a chain
+pub trait Store {
+    /// The most recently indexed block
+    fn tip(&self, symbol: &str) -> Result<Option<IndexedBlock>>;
+
+    /// All kept blocks, the most recent one first
+    fn blocks(&self, symbol: &str) -> Result<Vec<IndexedBlock>>;
+
+    /// Atomically stores the events and `block` as new tip; blocks below `prune_below` are dropped
+    fn insert(
+        &self,
+        symbol: &str,
+        block: &IndexedBlock,
+        events: &[IndexedEvent],
+        prune_below: u64,
+    ) -> Result<()>;
+
+    /// Deletes all blocks and events at or above `height`
+    fn rollback(&self, symbol: &str, height: u64) -> Result<usize>;
+}
+
+pub struct Indexer<S> {
+    symbol: String,
+    provider: DynProvider<AnyNetwork>,
+    contracts: Vec<Address>,
+    config: Config,
+    store: S,
+}
+
+impl<S> Indexer<S>
+where
+    S: Store + Send + Sync,
+{
+    pub fn new(
+        symbol: String,
+        provider: DynProvider<AnyNetwork>,
+        contracts: Vec<Address>,
+        config: Config,
+        store: S,
+    ) -> Result<Self> {
+        if contracts.is_empty() {
+            return Err(anyhow!("no contracts to index"));
+        }
+
+        if config.batch_size == 0 {
+            return Err(anyhow!("batchSize must be greater than 0"));
+        }
+
+        Ok(Self {
+            symbol,
+            provider,
+            contracts,
+            config,
+            store,
+        })
+    }
+
+    pub async fn start(&self, cancellation_token: CancellationToken) {
+        info!(
+            "Starting {} lockup indexer for contracts: {:?}",
+            self.symbol, self.contracts
+        );
+
+        loop {
+            match self.sync().await {
+                Ok(height) => debug!("Indexed {} up to height {}", self.symbol, height),
+                Err(err) => error!("Indexing {} failed: {}", self.symbol, err),
+            }
+
+            tokio::select! {
+                _ = cancellation_token.cancelled() => {
+                    debug!("Stopping {} lockup indexer", self.symbol);
+                    break;
+                }
+                _ = tokio::time::sleep(Duration::from_secs(self.config.interval)) => {}
+            }
+        }
+    }
+
+    /// Indexes all blocks up to the current chain tip and returns the indexed height
+    #[instrument(name = "Indexer::sync", skip(self), fields(symbol = %self.symbol))]
+    pub async fn sync(&self) -> Result<u64> {
+        let head = self.provider.get_block_number().await?;
+
+        loop {
+            let tip = self.store.tip(&self.symbol)?;
+            let from = match &tip {
+                Some(tip) => tip.height + 1,
+                None => self.config.start_height,
+            };
+            if from > head {
+                return Ok(from.saturating_sub(1));
+            }
+
+            let to = head.min(from + self.config.batch_size - 1);
+
+            let first = match &tip {
+                Some(tip) => {
+                    let first = self.get_block(from).await?;
+                    if first.parent_hash != tip.hash {
+                        self.handle_reorg().await?;
+                        continue;
+                    }
+
+                    Some(first)
+                }
+                None => None,
+            };
+
+            let block = match first {
+                Some(first) if first.height == to => first,
+                _ => self.get_block(to).await?,
+            };
+
+            let events = self.get_events(from, to).await?;
+            if events
+                .iter()
+                .any(|event| event.block_height == to && event.block_hash != block.hash)
+            {
+                return Err(anyhow!("chain changed while indexing block {}", to));
+            }
+
+            debug!(
+                "Indexed {} events of {} in blocks {} to {}",
+                events.len(),
+                self.symbol,
+                from,
+                to
+            );
+            self.store.insert(
+                &self.symbol,
+                &block,
+                &events,
+                to.saturating_sub(self.config.reorg_depth),
+            )?;
+        }
+    }
+
+    async fn handle_reorg(&self) -> Result<()> {
+        let mut fork_height = self.config.start_height;
+
+        for block in self.store.blocks(&self.symbol)? {
+            if self.get_block(block.height).await?.hash == block.hash {
+                fork_height = block.height + 1;
+                break;
+            }
+        }
+
+        let removed = self.store.rollback(&self.symbol, fork_height)?;
+        warn!(
+            "Reorg of {} detected; rolled back {} rows from height {}",
+            self.symbol, removed, fork_height
+        );
+
+        Ok(())
+    }
+
+    async fn get_block(&self, height: u64) -> Result<IndexedBlock> {
+        let block = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Number(height))
+            .await?
+            .ok_or_else(|| anyhow!("could not find block {}", height))?;
+
+        Ok(IndexedBlock {
+            height,
+            hash: block.header.hash,
+            parent_hash: block.header.parent_hash,
+        })
+    }
+
+    async fn get_events(&self, from: u64, to: u64) -> Result<Vec<IndexedEvent>> {
+        let logs = self
+            .provider
+            .get_logs(
+                &Filter::new()
+                    .address(self.contracts.clone())
+                    .event_signature(vec![
+                        ether_swap::LOCKUP_SIGNATURE,
+                        erc20_swap::LOCKUP_SIGNATURE,
+                        events::Claim::SIGNATURE_HASH,
+                        events::Refund::SIGNATURE_HASH,
+                    ])
+                    .from_block(from)
+                    .to_block(to),
+            )
+            .await?;
+
+        Ok(logs
+            .iter()
+            .filter(|log| !log.removed)
+            .filter_map(IndexedEvent::decode)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::primitives::{Bytes, LogData, b256};
+    use alloy::providers::ProviderBuilder;
+    use alloy::rpc::types::{Block, Header};
+    use alloy::sol_types::SolValue;
+    use alloy::transports::mock::Asserter;
+    use std::sync::Mutex;
+
+    const SYMBOL: &str = "RBTC";
+
+    const CONTRACT: Address = Address::repeat_byte(0xee);
+
+    #[derive(Default)]
+    struct MemoryStore {
+        blocks: Mutex<Vec<IndexedBlock>>,
+        events: Mutex<Vec<IndexedEvent>>,
+    }
+
+    impl Store for MemoryStore {
+        fn tip(&self, _symbol: &str) -> Result<Option<IndexedBlock>> {
+            Ok(self.blocks.lock().unwrap().last().copied())
+        }
+
+        fn blocks(&self, _symbol: &str) -> Result<Vec<IndexedBlock>> {
+            Ok(self.blocks.lock().unwrap().iter().rev().copied().collect())
+        }
+
+        fn insert(
+            &self,
+            _symbol: &str,
+            block: &IndexedBlock,
+            events: &[IndexedEvent],
+            prune_below: u64,
+        ) -> Result<()> {
+            let mut blocks = self.blocks.lock().unwrap();
+            blocks.push(*block);
+            blocks.retain(|block| block.height >= prune_below);
+
+            self.events.lock().unwrap().extend_from_slice(events);
+            Ok(())
+        }
+
+        fn rollback(&self, _symbol: &str, height: u64) -> Result<usize> {
+            let mut blocks = self.blocks.lock().unwrap();
+            let mut events = self.events.lock().unwrap();
+            let before = blocks.len() + events.len();
+
+            blocks.retain(|block| block.height < height);
+            events.retain(|event| event.block_height < height);
+
+            Ok(before - blocks.len() - events.len())
+        }
+    }
+
+    fn hash(height: u64, fork: u8) -> B256 {
+        let mut hash = B256::repeat_byte(fork);
+        hash[24..].copy_from_slice(&height.to_be_bytes());
+        hash
+    }
+
+    fn push_block(asserter: &Asserter, height: u64, fork: u8, parent_fork: u8) {
+        let header = Header {
+            hash: hash(height, fork),
+            inner: alloy::consensus::Header {
+                number: height,
+                parent_hash: hash(height - 1, parent_fork),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        asserter.push_success(&Block::<()>::empty(header));
+    }
+
+    fn refund_log(height: u64, fork: u8, preimage_hash: B256) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: CONTRACT,
+                data: LogData::new_unchecked(
+                    vec![events::Refund::SIGNATURE_HASH, preimage_hash],
+                    Bytes::new(),
+                ),
+            },
+            block_hash: Some(hash(height, fork)),
+            block_number: Some(height),
+            transaction_hash: Some(B256::repeat_byte(0x01)),
+            log_index: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn indexer(asserter: &Asserter, batch_size: u64) -> Indexer<MemoryStore> {
+        Indexer::new(
+            SYMBOL.to_string(),
+            DynProvider::new(
+                ProviderBuilder::new()
+                    .network::<AnyNetwork>()
+                    .connect_mocked_client(asserter.clone()),
+            ),
+            vec![CONTRACT],
+            Config {
+                start_height: 1,
+                batch_size,
+                reorg_depth: 10,
+                interval: 1,
+            },
+            MemoryStore::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_ether_lockup() {
+        let preimage_hash =
+            b256!("0x1111111111111111111111111111111111111111111111111111111111111111");
+        let claim_address = Address::repeat_byte(0x02);
+        let refund_address = Address::repeat_byte(0x03);
+
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: CONTRACT,
+                data: LogData::new_unchecked(
+                    vec![
+                        ether_swap::LOCKUP_SIGNATURE,
+                        preimage_hash,
+                        claim_address.into_word(),
+                        refund_address.into_word(),
+                    ],
+                    (U256::from(21), U256::from(123)).abi_encode().into(),
+                ),
+            },
+            block_hash: Some(hash(5, 0)),
+            block_number: Some(5),
+            transaction_hash: Some(B256::repeat_byte(0x04)),
+            log_index: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            IndexedEvent::decode(&log).unwrap(),
+            IndexedEvent {
+                contract_address: CONTRACT,
+                block_height: 5,
+                block_hash: hash(5, 0),
+                transaction_hash: B256::repeat_byte(0x04),
+                log_index: 2,
+                event: SwapEvent::Lockup(Lockup {
+                    preimage_hash,
+                    amount: U256::from(21),
+                    token_address: None,
+                    claim_address,
+                    refund_address,
+                    timelock: U256::from(123),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_erc20_lockup_v5() {
+        let token_address = Address::repeat_byte(0x05);
+        let claim_address = Address::repeat_byte(0x02);
+        let refund_address = Address::repeat_byte(0x03);
+
+        // Version 5 does not index the claim address yet
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: CONTRACT,
+                data: LogData::new_unchecked(
+                    vec![
+                        erc20_swap::LOCKUP_SIGNATURE,
+                        B256::repeat_byte(0x11),
+                        refund_address.into_word(),
+                    ],
+                    (
+                        U256::from(21),
+                        token_address,
+                        claim_address,
+                        U256::from(123),
+                    )
+                        .abi_encode()
+                        .into(),
+                ),
+            },
+            block_hash: Some(hash(5, 0)),
+            block_number: Some(5),
+            transaction_hash: Some(B256::repeat_byte(0x04)),
+            log_index: Some(0),
+            ..Default::default()
+        };
+
+        let SwapEvent::Lockup(lockup) = IndexedEvent::decode(&log).unwrap().event else {
+            panic!("expected lockup");
+        };
+        assert_eq!(lockup.token_address, Some(token_address));
+        assert_eq!(lockup.claim_address, claim_address);
+        assert_eq!(lockup.refund_address, refund_address);
+    }
+
+    #[test]
+    fn test_decode_claim() {
+        let log = Log {
+            inner: alloy::primitives::Log {
+                address: CONTRACT,
+                data: LogData::new_unchecked(
+                    vec![events::Claim::SIGNATURE_HASH, B256::repeat_byte(0x11)],
+                    B256::repeat_byte(0x22).abi_encode().into(),
+                ),
+            },
+            block_hash: Some(hash(5, 0)),
+            block_number: Some(5),
+            transaction_hash: Some(B256::repeat_byte(0x04)),
+            log_index: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            IndexedEvent::decode(&log).unwrap().event,
+            SwapEvent::Claim {
+                preimage_hash: B256::repeat_byte(0x11),
+                preimage: B256::repeat_byte(0x22),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_refund() {
+        let event = IndexedEvent::decode(&refund_log(5, 0, B256::repeat_byte(0x11))).unwrap();
+        assert_eq!(
+            event.event,
+            SwapEvent::Refund {
+                preimage_hash: B256::repeat_byte(0x11),
+            }
+        );
+        assert_eq!(event.event.preimage_hash(), B256::repeat_byte(0x11));
+    }
+
+    #[test]
+    fn test_decode_unknown_event() {
+        let mut log = refund_log(5, 0, B256::repeat_byte(0x11));
+        log.inner.data = LogData::new_unchecked(vec![B256::repeat_byte(0x99)], Bytes::new());
+
+        assert!(IndexedEvent::decode(&log).is_none());
+    }
+
+    #[test]
+    fn test_decode_pending_log() {
+        let mut log = refund_log(5, 0, B256::repeat_byte(0x11));
+        log.block_hash = None;
+
+        assert!(IndexedEvent::decode(&log).is_none());
+    }
+
+    #[test]
+    fn test_new_no_contracts() {
+        let asserter = Asserter::new();
+        let res = Indexer::new(
+            SYMBOL.to_string(),
+            DynProvider::new(
+                ProviderBuilder::new()
+                    .network::<AnyNetwork>()
+                    .connect_mocked_client(asserter),
+            ),
+            vec![],
+            Config {
+                start_height: 1,
+                batch_size: 1,
+                reorg_depth: 1,
+                interval: 1,
+            },
+            MemoryStore::default(),
+        );
+
+        assert_eq!(res.err().unwrap().to_string(), "no contracts to index");
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config: Config = serde_json::from_str(r#"{"startHeight": 21}"#).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                start_height: 21,
+                batch_size: default_batch_size(),
+                reorg_depth: default_reorg_depth(),
+                interval: default_interval(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_batches() {
+        let asserter = Asserter::new();
+        let indexer = indexer(&asserter, 2);
+
+        asserter.push_success(&3u64);
+        push_block(&asserter, 2, 0, 0);
+        asserter.push_success(&vec![refund_log(2, 0, B256::repeat_byte(0x11))]);
+        push_block(&asserter, 3, 0, 0);
+        asserter.push_success(&Vec::<Log>::new());
+
+        assert_eq!(indexer.sync().await.unwrap(), 3);
+
+        assert_eq!(
+            indexer.store.blocks(SYMBOL).unwrap(),
+            vec![
+                IndexedBlock {
+                    height: 3,
+                    hash: hash(3, 0),
+                    parent_hash: hash(2, 0),
+                },
+                IndexedBlock {
+                    height: 2,
+                    hash: hash(2, 0),
+                    parent_hash: hash(1, 0),
+                },
+            ]
+        );
+
+        let events = indexer.store.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].block_height, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sync_up_to_date() {
+        let asserter = Asserter::new();
+        let indexer = indexer(&asserter, 10);
+
+        asserter.push_success(&0u64);
+        assert_eq!(indexer.sync().await.unwrap(), 0);
+        assert!(indexer.store.tip(SYMBOL).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sync_reorg() {
+        let asserter = Asserter::new();
+        let indexer = indexer(&asserter, 1);
+
+        asserter.push_success(&2u64);
+        push_block(&asserter, 1, 0, 0);
+        asserter.push_success(&vec![refund_log(1, 0, B256::repeat_byte(0x11))]);
+        push_block(&asserter, 2, 0, 0);
+        asserter.push_success(&vec![refund_log(2, 0, B256::repeat_byte(0x22))]);
+
+        assert_eq!(indexer.sync().await.unwrap(), 2);
+        assert_eq!(indexer.store.events.lock().unwrap().len(), 2);
+
+        // Block 2 is replaced by a fork that does not include the second refund
+        asserter.push_success(&3u64);
+        push_block(&asserter, 3, 1, 1);
+        // Walking back to the fork point
+        push_block(&asserter, 2, 1, 0);
+        push_block(&asserter, 1, 0, 0);
+        // Indexing the new fork
+        push_block(&asserter, 2, 1, 0);
+        asserter.push_success(&Vec::<Log>::new());
+        push_block(&asserter, 3, 1, 1);
+        asserter.push_success(&Vec::<Log>::new());
+
+        assert_eq!(indexer.sync().await.unwrap(), 3);
+
+        let events = indexer.store.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event.preimage_hash(), B256::repeat_byte(0x11));
+
+        assert_eq!(
+            indexer
+                .store
+                .blocks(SYMBOL)
+                .unwrap()
+                .iter()
+                .map(|block| block.hash)
+                .collect::<Vec<_>>(),
+            vec![hash(3, 1), hash(2, 1), hash(1, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_reorg_below_kept_blocks() {
+        let asserter = Asserter::new();
+        let indexer = indexer(&asserter, 1);
+
+        asserter.push_success(&1u64);
+        push_block(&asserter, 1, 0, 0);
+        asserter.push_success(&vec![refund_log(1, 0, B256::repeat_byte(0x11))]);
+        assert_eq!(indexer.sync().await.unwrap(), 1);
+
+        // None of the kept blocks is part of the new chain anymore
+        asserter.push_success(&2u64);
+        push_block(&asserter, 2, 1, 1);
+        push_block(&asserter, 1, 1, 1);
+        push_block(&asserter, 1, 1, 1);
+        asserter.push_success(&Vec::<Log>::new());
+        push_block(&asserter, 2, 1, 1);
+        asserter.push_success(&Vec::<Log>::new());
+
+        assert_eq!(indexer.sync().await.unwrap(), 2);
+
+        assert!(indexer.store.events.lock().unwrap().is_empty());
+        assert_eq!(indexer.store.tip(SYMBOL).unwrap().unwrap().hash, hash(2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_sync_chain_changed() {
+        let asserter = Asserter::new();
+        let indexer = indexer(&asserter, 1);
+
+        asserter.push_success(&1u64);
+        push_block(&asserter, 1, 0, 0);
+        asserter.push_success(&vec![refund_log(1, 1, B256::repeat_byte(0x11))]);
+
+        assert_eq!(
+            indexer.sync().await.unwrap_err().to_string(),
+            "chain changed while indexing block 1"
+        );
+        assert!(indexer.store.tip(SYMBOL).unwrap().is_none());
+    }
+}
@@ -7,11 +7,14 @@ use serde::{Deserialize, Serialize};
 
 pub mod commitment;
 pub mod contracts;
+pub mod indexer;
 pub mod log_layer;
 pub mod manager;
+pub mod nonce_manager;
 pub mod quoter;
 pub mod refund;
 pub mod refund_signer;
+pub mod remote_signer;
 pub mod serde_utils;
 pub mod signature;
 pub mod utils;
@@ -130,6 +133,15 @@ pub struct Config {
 
     #[serde(rename = "quoters")]
     pub quoters: Option<quoter::Config>,
+
+    #[serde(rename = "indexer")]
+    pub indexer: Option<indexer::Config>,
+
+    #[serde(rename = "nonceManager")]
+    pub nonce_manager: Option<nonce_manager::Config>,
+
+    #[serde(rename = "remoteSigner")]
+    pub remote_signer: Option<remote_signer::Config>,
 }
 
 pub trait RefundSigner {
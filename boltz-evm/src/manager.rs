@@ -1,12 +1,16 @@
 use crate::RefundSigner;
+use crate::indexer::{Indexer, Store};
 use crate::log_layer::LoggingLayer;
+use crate::nonce_manager::{NonceManager, PendingTransaction};
 use crate::quoter::QuoteAggregator;
 use crate::refund_signer::LocalRefundSigner;
+use crate::remote_signer::RemoteSigner;
 use alloy::network::{AnyNetwork, EthereumWallet};
 use alloy::primitives::{Address, FixedBytes, Signature, U256};
 use alloy::providers::{DynProvider, Provider, ProviderBuilder};
 use alloy::pubsub::PubSubConnect;
 use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::TransactionRequest;
 use alloy::signers::local::coins_bip39::English;
 use alloy::signers::local::{MnemonicBuilder, PrivateKeySigner};
 use alloy::transports::{
@@ -18,19 +22,28 @@ use alloy::transports::{
 use anyhow::{Context, anyhow};
 use boltz_cache::Cache;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tower::ServiceBuilder;
 use tracing::{debug, info, instrument, warn};
 
 pub struct Manager {
     pub quote_aggregator: QuoteAggregator,
 
+    /// Assigns the nonces of the transactions sent with [`Manager::send_transaction`]
+    pub nonce_manager: Arc<NonceManager>,
+
     /// Map of token symbol to contract address
     pub tokens: HashMap<String, Address>,
 
+    symbol: String,
     signer: PrivateKeySigner,
+    provider: DynProvider<AnyNetwork>,
+    indexer_config: Option<crate::indexer::Config>,
 
     address_versions: HashMap<Address, u8>,
     refund_signers: HashMap<u8, LocalRefundSigner>,
+    /// Signs cooperative refunds instead of the local key when configured
+    remote_signer: Option<RemoteSigner>,
 }
 
 impl Manager {
@@ -65,6 +78,23 @@ impl Manager {
     ) -> anyhow::Result<Self> {
         info!("Using address: {}", signer.address());
 
+        // Swaps are locked with the address of the signer as claim address, so the
+        // contracts only accept refund signatures of that address
+        let remote_signer = config
+            .remote_signer
+            .as_ref()
+            .map(RemoteSigner::new)
+            .transpose()?;
+        if let Some(remote_signer) = &remote_signer
+            && remote_signer.address() != signer.address()
+        {
+            return Err(anyhow!(
+                "remote signer address {} does not match signer address {}",
+                remote_signer.address(),
+                signer.address()
+            ));
+        }
+
         let provider = Self::new_provider(symbol.clone(), config, signer.clone()).await?;
 
         let chain_id = provider.get_chain_id().await?;
@@ -110,15 +140,70 @@ impl Manager {
             .collect::<anyhow::Result<HashMap<_, _>>>()?;
 
         Ok(Self {
-            quote_aggregator: QuoteAggregator::new(symbol, cache, provider, config.quoters.clone())
-                .await?,
+            quote_aggregator: QuoteAggregator::new(
+                symbol.clone(),
+                cache,
+                provider.clone(),
+                config.quoters.clone(),
+            )
+            .await?,
+            nonce_manager: Arc::new(NonceManager::new(
+                symbol.clone(),
+                signer.clone(),
+                chain_id,
+                provider.clone(),
+                config.nonce_manager.clone().unwrap_or_default(),
+            )?),
             tokens,
+            symbol,
             signer,
+            provider,
+            indexer_config: config.indexer.clone(),
             address_versions,
             refund_signers,
+            remote_signer,
         })
     }
 
+    /// Sends a transaction from the signer with the next nonce of the nonce manager
+    #[instrument(name = "Manager::send_transaction", skip(self, data))]
+    pub async fn send_transaction(
+        &self,
+        to: Address,
+        value: U256,
+        data: Vec<u8>,
+        gas_limit: Option<u64>,
+    ) -> anyhow::Result<PendingTransaction> {
+        self.nonce_manager
+            .send(TransactionRequest {
+                to: Some(to.into()),
+                value: Some(value),
+                input: data.into(),
+                gas: gas_limit,
+                ..Default::default()
+            })
+            .await
+    }
+
+    /// Lockup indexer for all configured swap contracts; `None` when indexing is not configured
+    pub fn indexer<S>(&self, store: S) -> Option<anyhow::Result<Indexer<S>>>
+    where
+        S: Store + Send + Sync,
+    {
+        let config = self.indexer_config.clone()?;
+
+        let mut contracts = self.address_versions.keys().copied().collect::<Vec<_>>();
+        contracts.sort();
+
+        Some(Indexer::new(
+            self.symbol.clone(),
+            self.provider.clone(),
+            contracts,
+            config,
+            store,
+        ))
+    }
+
     async fn new_provider(
         symbol: String,
         config: &crate::Config,
@@ -195,11 +280,25 @@ impl RefundSigner for Manager {
         timeout: u64,
     ) -> anyhow::Result<Signature> {
         match self.refund_signers.get(&contract_version) {
-            Some(signer) => {
-                signer
-                    .sign(&self.signer, preimage_hash, amount, token_address, timeout)
-                    .await
-            }
+            Some(signer) => match &self.remote_signer {
+                Some(remote_signer) => {
+                    let (domain, values) = signer.refund_values(
+                        preimage_hash,
+                        amount,
+                        token_address,
+                        remote_signer.address(),
+                        timeout,
+                    );
+                    remote_signer
+                        .sign_refund(contract_version, domain, &values)
+                        .await
+                }
+                None => {
+                    signer
+                        .sign(&self.signer, preimage_hash, amount, token_address, timeout)
+                        .await
+                }
+            },
             None => Err(anyhow!(
                 "no refund signers for contracts version {}",
                 contract_version
@@ -355,6 +454,27 @@ pub mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_send_transaction() {
+        let manager = crate::test_utils::new_manager().await;
+
+        let sent = manager
+            .send_transaction(Address::repeat_byte(0x01), U256::from(1), Vec::new(), None)
+            .await
+            .unwrap();
+
+        let pending = manager.nonce_manager.pending().await;
+        assert_eq!(pending, vec![sent.clone()]);
+        assert_eq!(pending[0].request.value, Some(U256::from(1)));
+
+        let raw: alloy::primitives::Bytes = manager
+            .provider
+            .raw_request("eth_getRawTransactionByHash".into(), (sent.hash(),))
+            .await
+            .unwrap();
+        assert_eq!(raw, sent.raw);
+    }
+
     #[tokio::test]
     async fn test_tokens_parsing() {
         let token_address = "0x6c84a8f1c29108F47a79964b5Fe888D4f4D0de40";
@@ -382,6 +502,9 @@ pub mod test {
                     contract_address: Some(token_address.to_string()),
                 }]),
                 quoters: None,
+                indexer: None,
+                nonce_manager: None,
+                remote_signer: None,
             },
         )
         .await
@@ -419,6 +542,9 @@ pub mod test {
                     contract_address: None,
                 }]),
                 quoters: None,
+                indexer: None,
+                nonce_manager: None,
+                remote_signer: None,
             },
         )
         .await
@@ -453,6 +579,9 @@ pub mod test {
                     contract_address: Some("invalid".to_string()),
                 }]),
                 quoters: None,
+                indexer: None,
+                nonce_manager: None,
+                remote_signer: None,
             },
         )
         .await;
@@ -460,4 +589,48 @@ pub mod test {
         assert!(result.is_err());
         assert!(result.err().unwrap().to_string().contains("TBTC"));
     }
+
+    #[tokio::test]
+    async fn test_remote_signer_address_mismatch() {
+        let remote_address = Address::repeat_byte(0x01);
+        let result = Manager::new(
+            "RBTC".to_string(),
+            Cache::Memory(MemCache::new()),
+            MnemonicBuilder::<English>::default()
+                .phrase(MNEMONIC)
+                .index(0)
+                .unwrap()
+                .build()
+                .unwrap(),
+            &Config {
+                provider_endpoint: Some(PROVIDER.to_string()),
+                providers: None,
+                derivation_path: None,
+                contracts: vec![ContractAddresses {
+                    ether_swap: ETHER_SWAP_ADDRESS.to_string(),
+                    erc20_swap: ERC20_SWAP_ADDRESS.to_string(),
+                }],
+                tokens: None,
+                quoters: None,
+                indexer: None,
+                nonce_manager: None,
+                remote_signer: Some(crate::remote_signer::Config {
+                    endpoint: "http://127.0.0.1:9000".to_string(),
+                    address: remote_address.to_string(),
+                    timeout: 1,
+                    allowed_contracts: None,
+                    max_amounts: None,
+                }),
+            },
+        )
+        .await;
+
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            format!(
+                "remote signer address {} does not match signer address {}",
+                remote_address, EXPECTED_ADDRESS
+            )
+        );
+    }
 }
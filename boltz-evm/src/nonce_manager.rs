@@ -0,0 +1,760 @@
+use alloy::eips::eip1559::Eip1559Estimation;
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::{EthereumWallet, NetworkWallet};
+use alloy::primitives::{Address, B256, Bytes, U256};
+use alloy::providers::network::AnyNetwork;
+use alloy::providers::{DynProvider, Provider};
+use alloy::rpc::types::TransactionRequest;
+use alloy::serde::WithOtherFields;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, instrument, warn};
+
+/// Gas of a plain value transfer, which is what nonce gaps are filled with
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Nodes reject replacements that raise the fees by less than this percentage
+const MIN_BUMP_PERCENTAGE: u64 = 10;
+
+const fn default_bump_percentage() -> u64 {
+    MIN_BUMP_PERCENTAGE
+}
+
+const fn default_stuck_after() -> u64 {
+    180
+}
+
+const fn default_interval() -> u64 {
+    30
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct Config {
+    /// Upper bound for `maxFeePerGas` in wei; fees are not capped when unset
+    #[serde(rename = "maxFeePerGas")]
+    pub max_fee_per_gas: Option<u128>,
+
+    /// Percentage by which the fees of a stuck transaction are raised when it is replaced
+    #[serde(rename = "bumpPercentage", default = "default_bump_percentage")]
+    pub bump_percentage: u64,
+
+    /// Seconds after which an unconfirmed transaction is considered stuck
+    #[serde(rename = "stuckAfter", default = "default_stuck_after")]
+    pub stuck_after: u64,
+
+    /// Seconds between checks of the pending transactions
+    #[serde(rename = "interval", default = "default_interval")]
+    pub interval: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_fee_per_gas: None,
+            bump_percentage: default_bump_percentage(),
+            stuck_after: default_stuck_after(),
+            interval: default_interval(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTransaction {
+    pub nonce: u64,
+    /// Hashes of all broadcast versions of the transaction; the last one is the current
+    pub hashes: Vec<B256>,
+    pub request: TransactionRequest,
+    /// Signed current version
+    pub raw: Bytes,
+    broadcast_at: Instant,
+}
+
+impl PendingTransaction {
+    pub fn hash(&self) -> B256 {
+        *self
+            .hashes
+            .last()
+            .expect("pending transaction without hash")
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_nonce: Option<u64>,
+    pending: BTreeMap<u64, PendingTransaction>,
+}
+
+/// Assigns the nonces of all transactions sent from one address and keeps them moving:
+/// stuck transactions are replaced with higher fees, dropped ones are broadcast again
+/// and gaps in the nonce sequence are filled with transfers to ourselves
+pub struct NonceManager {
+    symbol: String,
+    address: Address,
+    chain_id: u64,
+    config: Config,
+    wallet: EthereumWallet,
+    provider: DynProvider<AnyNetwork>,
+
+    state: Mutex<State>,
+}
+
+impl NonceManager {
+    /// Transactions are signed with `signer` and broadcast raw through the provider
+    pub fn new(
+        symbol: String,
+        signer: PrivateKeySigner,
+        chain_id: u64,
+        provider: DynProvider<AnyNetwork>,
+        config: Config,
+    ) -> Result<Self> {
+        if config.bump_percentage < MIN_BUMP_PERCENTAGE {
+            return Err(anyhow!(
+                "bumpPercentage must be at least {}",
+                MIN_BUMP_PERCENTAGE
+            ));
+        }
+
+        Ok(Self {
+            symbol,
+            address: signer.address(),
+            chain_id,
+            config,
+            wallet: EthereumWallet::from(signer),
+            provider,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Transactions that were sent but are not confirmed yet, ordered by nonce
+    pub async fn pending(&self) -> Vec<PendingTransaction> {
+        self.state.lock().await.pending.values().cloned().collect()
+    }
+
+    pub async fn start(&self, cancellation_token: CancellationToken) {
+        info!(
+            "Starting {} nonce manager for address: {}",
+            self.symbol, self.address
+        );
+
+        loop {
+            tokio::select! {
+                _ = cancellation_token.cancelled() => {
+                    debug!("Stopping {} nonce manager", self.symbol);
+                    break;
+                }
+                _ = tokio::time::sleep(Duration::from_secs(self.config.interval)) => {}
+            }
+
+            if let Err(err) = self.check().await {
+                error!(
+                    "Checking pending {} transactions failed: {}",
+                    self.symbol, err
+                );
+            }
+        }
+    }
+
+    /// Sends a transaction with the next free nonce
+    #[instrument(name = "NonceManager::send", skip_all, fields(symbol = %self.symbol))]
+    pub async fn send(&self, mut tx: TransactionRequest) -> Result<PendingTransaction> {
+        let mut state = self.state.lock().await;
+
+        // Transactions sent from the same address by someone else have to be accounted for
+        let node_nonce = self
+            .provider
+            .get_transaction_count(self.address)
+            .pending()
+            .await?;
+        let nonce = state.next_nonce.unwrap_or_default().max(node_nonce);
+
+        let fees = self.estimate_fees().await?;
+
+        tx.from = Some(self.address);
+        tx.chain_id = Some(self.chain_id);
+        tx.nonce = Some(nonce);
+        tx.max_fee_per_gas = Some(fees.max_fee_per_gas);
+        tx.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+        tx.gas_price = None;
+
+        if tx.gas.is_none() {
+            tx.gas = Some(
+                self.provider
+                    .estimate_gas(WithOtherFields::new(tx.clone()))
+                    .await?,
+            );
+        }
+
+        let (hash, raw) = self.broadcast(&tx).await?;
+        debug!("Sent transaction {} with nonce {}", hash, nonce);
+
+        let pending = PendingTransaction {
+            nonce,
+            hashes: vec![hash],
+            request: tx,
+            raw,
+            broadcast_at: Instant::now(),
+        };
+
+        state.next_nonce = Some(nonce + 1);
+        state.pending.insert(nonce, pending.clone());
+
+        Ok(pending)
+    }
+
+    /// Forgets confirmed transactions, broadcasts dropped ones again,
+    /// replaces stuck ones and fills gaps in the nonce sequence
+    #[instrument(name = "NonceManager::check", skip_all, fields(symbol = %self.symbol))]
+    pub async fn check(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if state.pending.is_empty() {
+            return Ok(());
+        }
+
+        let confirmed_nonce = self
+            .provider
+            .get_transaction_count(self.address)
+            .latest()
+            .await?;
+        let node_nonce = self
+            .provider
+            .get_transaction_count(self.address)
+            .pending()
+            .await?;
+
+        state.pending.retain(|nonce, tx| {
+            let confirmed = *nonce < confirmed_nonce;
+            if confirmed {
+                debug!("Transaction {} with nonce {} confirmed", tx.hash(), nonce);
+            }
+
+            !confirmed
+        });
+
+        let highest_nonce = match state.pending.last_key_value() {
+            Some((nonce, _)) => *nonce,
+            None => return Ok(()),
+        };
+
+        let stuck_after = Duration::from_secs(self.config.stuck_after);
+        let mut fees = None;
+
+        for tx in state.pending.values_mut() {
+            // The mempool of the node does not know a transaction with that nonce anymore
+            if tx.nonce >= node_nonce {
+                warn!(
+                    "Transaction {} with nonce {} was dropped; broadcasting it again",
+                    tx.hash(),
+                    tx.nonce
+                );
+
+                if let Err(err) = self.broadcast(&tx.request).await {
+                    error!("Broadcasting transaction {} failed: {}", tx.hash(), err);
+                }
+                continue;
+            }
+
+            if tx.broadcast_at.elapsed() < stuck_after {
+                continue;
+            }
+
+            let estimation = match fees {
+                Some(fees) => fees,
+                None => *fees.insert(self.estimate_fees().await?),
+            };
+
+            if let Err(err) = self.replace(tx, &estimation).await {
+                error!(
+                    "Replacing transaction {} with nonce {} failed: {}",
+                    tx.hash(),
+                    tx.nonce,
+                    err
+                );
+            }
+        }
+
+        for nonce in confirmed_nonce..highest_nonce {
+            if state.pending.contains_key(&nonce) {
+                continue;
+            }
+
+            let estimation = match fees {
+                Some(fees) => fees,
+                None => *fees.insert(self.estimate_fees().await?),
+            };
+
+            match self.fill_gap(nonce, &estimation).await {
+                Ok(tx) => {
+                    state.pending.insert(nonce, tx);
+                }
+                Err(err) => error!("Filling nonce gap at {} failed: {}", nonce, err),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn replace(
+        &self,
+        tx: &mut PendingTransaction,
+        estimation: &Eip1559Estimation,
+    ) -> Result<()> {
+        let max_fee_per_gas = tx.request.max_fee_per_gas.unwrap_or_default();
+        let max_priority_fee_per_gas = tx.request.max_priority_fee_per_gas.unwrap_or_default();
+
+        if let Some(cap) = self.config.max_fee_per_gas
+            && max_fee_per_gas >= cap
+        {
+            warn!(
+                "Transaction {} with nonce {} is stuck but its fees are at the cap of {} already",
+                tx.hash(),
+                tx.nonce,
+                cap
+            );
+            return Ok(());
+        }
+
+        let fees = self.cap_fees(Eip1559Estimation {
+            max_fee_per_gas: self.bump(max_fee_per_gas).max(estimation.max_fee_per_gas),
+            max_priority_fee_per_gas: self
+                .bump(max_priority_fee_per_gas)
+                .max(estimation.max_priority_fee_per_gas),
+        });
+
+        let mut request = tx.request.clone();
+        request.max_fee_per_gas = Some(fees.max_fee_per_gas);
+        request.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+
+        let (hash, raw) = self.broadcast(&request).await?;
+        info!(
+            "Replaced stuck transaction {} with nonce {} by {} with maxFeePerGas {} and maxPriorityFeePerGas {}",
+            tx.hash(),
+            tx.nonce,
+            hash,
+            fees.max_fee_per_gas,
+            fees.max_priority_fee_per_gas
+        );
+
+        tx.hashes.push(hash);
+        tx.request = request;
+        tx.raw = raw;
+        tx.broadcast_at = Instant::now();
+
+        Ok(())
+    }
+
+    async fn fill_gap(&self, nonce: u64, fees: &Eip1559Estimation) -> Result<PendingTransaction> {
+        let request = TransactionRequest {
+            from: Some(self.address),
+            to: Some(self.address.into()),
+            value: Some(U256::ZERO),
+            chain_id: Some(self.chain_id),
+            nonce: Some(nonce),
+            gas: Some(TRANSFER_GAS),
+            max_fee_per_gas: Some(fees.max_fee_per_gas),
+            max_priority_fee_per_gas: Some(fees.max_priority_fee_per_gas),
+            ..Default::default()
+        };
+
+        let (hash, raw) = self.broadcast(&request).await?;
+        warn!("Filled nonce gap at {} with transaction {}", nonce, hash);
+
+        Ok(PendingTransaction {
+            nonce,
+            hashes: vec![hash],
+            request,
+            raw,
+            broadcast_at: Instant::now(),
+        })
+    }
+
+    /// Signs the transaction and broadcasts it; returns its hash and the signed transaction
+    async fn broadcast(&self, tx: &TransactionRequest) -> Result<(B256, Bytes)> {
+        let envelope = NetworkWallet::<AnyNetwork>::sign_request(
+            &self.wallet,
+            WithOtherFields::new(tx.clone()),
+        )
+        .await?;
+        let raw = envelope.encoded_2718();
+
+        let pending = self.provider.send_raw_transaction(&raw).await?;
+        Ok((*pending.tx_hash(), raw.into()))
+    }
+
+    async fn estimate_fees(&self) -> Result<Eip1559Estimation> {
+        Ok(self.cap_fees(self.provider.estimate_eip1559_fees().await?))
+    }
+
+    fn cap_fees(&self, fees: Eip1559Estimation) -> Eip1559Estimation {
+        match self.config.max_fee_per_gas {
+            Some(cap) => Eip1559Estimation {
+                max_fee_per_gas: fees.max_fee_per_gas.min(cap),
+                max_priority_fee_per_gas: fees.max_priority_fee_per_gas.min(cap),
+            },
+            None => fees,
+        }
+    }
+
+    fn bump(&self, fee: u128) -> u128 {
+        fee + (fee * self.config.bump_percentage as u128).div_ceil(100)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::consensus::{Transaction, TxEnvelope};
+    use alloy::eips::eip2718::Decodable2718;
+    use alloy::providers::ProviderBuilder;
+    use alloy::rpc::types::FeeHistory;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::transports::mock::Asserter;
+
+    const SYMBOL: &str = "RBTC";
+    const CHAIN_ID: u64 = 30;
+
+    fn nonce_manager(config: Config) -> (NonceManager, Asserter) {
+        let signer = PrivateKeySigner::random();
+
+        let asserter = Asserter::new();
+        // Nonces, fees and gas limits are set and transactions signed by the nonce manager itself
+        let provider =
+            ProviderBuilder::<_, _, AnyNetwork>::default().connect_mocked_client(asserter.clone());
+
+        (
+            NonceManager::new(
+                SYMBOL.to_string(),
+                signer,
+                CHAIN_ID,
+                DynProvider::new(provider),
+                config,
+            )
+            .unwrap(),
+            asserter,
+        )
+    }
+
+    fn push_nonce(asserter: &Asserter, nonce: u64) {
+        asserter.push_success(&format!("{:#x}", nonce));
+    }
+
+    fn push_fees(asserter: &Asserter, base_fee: u128, reward: u128) {
+        asserter.push_success(&FeeHistory {
+            oldest_block: 1,
+            base_fee_per_gas: vec![base_fee, base_fee],
+            gas_used_ratio: vec![0.5],
+            reward: Some(vec![vec![reward]]),
+            ..Default::default()
+        });
+    }
+
+    fn transfer() -> TransactionRequest {
+        TransactionRequest {
+            to: Some(Address::repeat_byte(0x01).into()),
+            value: Some(U256::from(1)),
+            gas: Some(TRANSFER_GAS),
+            ..Default::default()
+        }
+    }
+
+    async fn send(
+        manager: &NonceManager,
+        asserter: &Asserter,
+        node_nonce: u64,
+        hash: B256,
+    ) -> B256 {
+        push_nonce(asserter, node_nonce);
+        push_fees(asserter, 1_000, 100);
+        asserter.push_success(&hash);
+
+        manager.send(transfer()).await.unwrap().hash()
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.bump_percentage, 10);
+        assert_eq!(config.stuck_after, 180);
+        assert_eq!(config.interval, 30);
+        assert_eq!(config.max_fee_per_gas, None);
+    }
+
+    #[test]
+    fn test_new_bump_percentage_too_low() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new()
+            .network::<AnyNetwork>()
+            .connect_mocked_client(asserter);
+
+        let res = NonceManager::new(
+            SYMBOL.to_string(),
+            PrivateKeySigner::random(),
+            CHAIN_ID,
+            DynProvider::new(provider),
+            Config {
+                bump_percentage: 9,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "bumpPercentage must be at least 10"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        let hash = send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+        assert_eq!(hash, B256::repeat_byte(1));
+
+        // The node has not seen the first transaction in its mempool yet
+        let hash = send(&manager, &asserter, 5, B256::repeat_byte(2)).await;
+        assert_eq!(hash, B256::repeat_byte(2));
+
+        let pending = manager.pending().await;
+        assert_eq!(
+            pending.iter().map(|tx| tx.nonce).collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+        assert_eq!(pending[1].hashes, vec![B256::repeat_byte(2)]);
+        assert_eq!(pending[1].request.from, Some(manager.address()));
+
+        let request = &pending[0].request;
+        assert_eq!(request.max_priority_fee_per_gas, Some(100));
+        assert!(request.max_fee_per_gas.unwrap() > 1_000);
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_raw() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        push_nonce(&asserter, 5);
+        push_fees(&asserter, 1_000, 100);
+        asserter.push_success(&B256::repeat_byte(1));
+        let sent = manager.send(transfer()).await.unwrap();
+
+        let tx = TxEnvelope::decode_2718(&mut sent.raw.as_ref()).unwrap();
+        let signed = tx.as_eip1559().unwrap();
+        assert_eq!(
+            signed
+                .signature()
+                .recover_address_from_prehash(&signed.signature_hash())
+                .unwrap(),
+            manager.address()
+        );
+        assert_eq!(tx.chain_id(), Some(CHAIN_ID));
+        assert_eq!(tx.nonce(), 5);
+        assert_eq!(tx.to(), Some(Address::repeat_byte(0x01)));
+        assert_eq!(tx.value(), U256::from(1));
+        assert_eq!(tx.gas_limit(), TRANSFER_GAS);
+        assert_eq!(sent, manager.pending().await[0]);
+    }
+
+    #[tokio::test]
+    async fn test_send_node_nonce_ahead() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+        send(&manager, &asserter, 9, B256::repeat_byte(2)).await;
+
+        assert_eq!(
+            manager
+                .pending()
+                .await
+                .iter()
+                .map(|tx| tx.nonce)
+                .collect::<Vec<_>>(),
+            vec![5, 9]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_error() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        push_nonce(&asserter, 5);
+        push_fees(&asserter, 1_000, 100);
+        asserter.push_failure_msg("nonce too low");
+        assert!(manager.send(transfer()).await.is_err());
+        assert!(manager.pending().await.is_empty());
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+        assert_eq!(manager.pending().await[0].nonce, 5);
+    }
+
+    #[tokio::test]
+    async fn test_send_fee_cap() {
+        let (manager, asserter) = nonce_manager(Config {
+            max_fee_per_gas: Some(500),
+            ..Default::default()
+        });
+
+        send(&manager, &asserter, 0, B256::repeat_byte(1)).await;
+
+        let request = &manager.pending().await[0].request;
+        assert_eq!(request.max_fee_per_gas, Some(500));
+        assert_eq!(request.max_priority_fee_per_gas, Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_check_nothing_pending() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        manager.check().await.unwrap();
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_confirmed() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+        send(&manager, &asserter, 5, B256::repeat_byte(2)).await;
+
+        push_nonce(&asserter, 6);
+        push_nonce(&asserter, 7);
+        manager.check().await.unwrap();
+
+        let pending = manager.pending().await;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].nonce, 6);
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_dropped() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+
+        push_nonce(&asserter, 5);
+        push_nonce(&asserter, 5);
+        asserter.push_success(&B256::repeat_byte(1));
+        manager.check().await.unwrap();
+
+        let pending = manager.pending().await;
+        assert_eq!(pending[0].hashes, vec![B256::repeat_byte(1)]);
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_stuck() {
+        let (manager, asserter) = nonce_manager(Config {
+            stuck_after: 0,
+            ..Default::default()
+        });
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+        let before = manager.pending().await[0].request.clone();
+
+        push_nonce(&asserter, 5);
+        push_nonce(&asserter, 6);
+        push_fees(&asserter, 1_000, 100);
+        asserter.push_success(&B256::repeat_byte(2));
+        manager.check().await.unwrap();
+
+        let pending = manager.pending().await;
+        assert_eq!(
+            pending[0].hashes,
+            vec![B256::repeat_byte(1), B256::repeat_byte(2)]
+        );
+        assert_eq!(
+            pending[0].request.max_fee_per_gas,
+            Some(manager.bump(before.max_fee_per_gas.unwrap()))
+        );
+        assert_eq!(pending[0].request.max_priority_fee_per_gas, Some(110));
+        assert_eq!(pending[0].request.nonce, Some(5));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_stuck_fees_increased() {
+        let (manager, asserter) = nonce_manager(Config {
+            stuck_after: 0,
+            ..Default::default()
+        });
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+
+        push_nonce(&asserter, 5);
+        push_nonce(&asserter, 6);
+        push_fees(&asserter, 100_000, 1_000);
+        asserter.push_success(&B256::repeat_byte(2));
+        manager.check().await.unwrap();
+
+        let request = &manager.pending().await[0].request;
+        assert!(request.max_fee_per_gas.unwrap() > 100_000);
+        assert_eq!(request.max_priority_fee_per_gas, Some(1_000));
+    }
+
+    #[tokio::test]
+    async fn test_check_stuck_fee_cap() {
+        let (manager, asserter) = nonce_manager(Config {
+            stuck_after: 0,
+            max_fee_per_gas: Some(2_200),
+            ..Default::default()
+        });
+
+        send(&manager, &asserter, 5, B256::repeat_byte(1)).await;
+
+        push_nonce(&asserter, 5);
+        push_nonce(&asserter, 6);
+        push_fees(&asserter, 1_000, 100);
+        asserter.push_success(&B256::repeat_byte(2));
+        manager.check().await.unwrap();
+
+        let request = &manager.pending().await[0].request;
+        assert_eq!(request.max_fee_per_gas, Some(2_200));
+
+        // Fees at the cap cannot be raised anymore
+        push_nonce(&asserter, 5);
+        push_nonce(&asserter, 6);
+        push_fees(&asserter, 1_000, 100);
+        manager.check().await.unwrap();
+
+        assert_eq!(manager.pending().await[0].hashes.len(), 2);
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_check_gap() {
+        let (manager, asserter) = nonce_manager(Config::default());
+
+        send(&manager, &asserter, 7, B256::repeat_byte(1)).await;
+
+        // Transactions with nonces 5 and 6 were sent but got lost
+        push_nonce(&asserter, 5);
+        push_nonce(&asserter, 5);
+        asserter.push_success(&B256::repeat_byte(1));
+        push_fees(&asserter, 1_000, 100);
+        asserter.push_success(&B256::repeat_byte(2));
+        asserter.push_success(&B256::repeat_byte(3));
+        manager.check().await.unwrap();
+
+        let pending = manager.pending().await;
+        assert_eq!(
+            pending.iter().map(|tx| tx.nonce).collect::<Vec<_>>(),
+            vec![5, 6, 7]
+        );
+
+        for (tx, hash) in pending.iter().zip([2, 3]) {
+            assert_eq!(tx.hash(), B256::repeat_byte(hash));
+            assert_eq!(tx.request.to, Some(manager.address().into()));
+            assert_eq!(tx.request.value, Some(U256::ZERO));
+            assert_eq!(tx.request.gas, Some(TRANSFER_GAS));
+        }
+        assert!(asserter.read_q().is_empty());
+    }
+}
@@ -6,29 +6,58 @@ use anyhow::Result;
 use async_trait::async_trait;
 use boltz_cache::Cache;
 use serde::{Deserialize, Serialize};
+use split::{QuoteKind, SPLIT_STEPS};
 use std::fmt;
+use std::marker::PhantomData;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{debug, instrument, warn};
 
+mod split;
+mod uniswap_v2;
 mod uniswap_v3;
 
+pub use split::{Split, SplitPart};
+
+const ERROR_NO_RESULTS: &str = "no results";
+
+const fn default_max_splits() -> usize {
+    1
+}
+
+const fn default_split_gas() -> u64 {
+    100_000
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
 pub struct Config {
     pub weth: String,
 
+    /// Maximum number of routes a swap can be split across; 1 disables splitting
+    #[serde(rename = "maxSplits", default = "default_max_splits")]
+    pub max_splits: usize,
+
+    /// Gas every additional route of a split swap is expected to cost
+    #[serde(rename = "splitGas", default = "default_split_gas")]
+    pub split_gas: u64,
+
+    #[serde(rename = "uniswapV2")]
+    pub uniswap_v2: Option<uniswap_v2::Config>,
+
     #[serde(rename = "uniswapV3")]
     pub uniswap_v3: Option<uniswap_v3::Config>,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum QuoterType {
+    UniswapV2,
     UniswapV3,
 }
 
 impl fmt::Display for QuoterType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            QuoterType::UniswapV2 => write!(f, "UniswapV2"),
             QuoterType::UniswapV3 => write!(f, "UniswapV3"),
         }
     }
@@ -37,14 +66,67 @@ impl fmt::Display for QuoterType {
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum Data {
+    #[serde(rename = "uniswapV2")]
+    UniswapV2(uniswap_v2::Data),
     #[serde(rename = "uniswapV3")]
     UniswapV3(uniswap_v3::Data),
+    /// Swap divided between the routes of one or more quoters
+    #[serde(rename = "split")]
+    Split(Split),
 }
 
 impl Data {
-    pub fn quoter_type(&self) -> QuoterType {
+    /// Quoter that can encode the data; `None` for splits
+    pub fn quoter_type(&self) -> Option<QuoterType> {
+        match self {
+            Data::UniswapV2(_) => Some(QuoterType::UniswapV2),
+            Data::UniswapV3(_) => Some(QuoterType::UniswapV3),
+            Data::Split(_) => None,
+        }
+    }
+
+    /// Pools the route swaps through; empty for splits
+    fn pools(&self) -> Vec<Pool> {
         match self {
-            Data::UniswapV3(_) => QuoterType::UniswapV3,
+            Data::UniswapV2(data) => data
+                .path
+                .windows(2)
+                .map(|pair| Pool::new(QuoterType::UniswapV2, pair[0], pair[1], 0))
+                .collect(),
+            Data::UniswapV3(data) => std::iter::once(data.token_in)
+                .chain(data.hops.iter().map(|hop| hop.token))
+                .collect::<Vec<_>>()
+                .windows(2)
+                .zip(&data.hops)
+                .map(|(pair, hop)| Pool::new(QuoterType::UniswapV3, pair[0], pair[1], hop.fee))
+                .collect(),
+            Data::Split(_) => Vec::new(),
+        }
+    }
+}
+
+/// Pool of a quoter identified by its tokens and fee tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Pool {
+    quoter_type: QuoterType,
+    token0: Address,
+    token1: Address,
+    fee: u64,
+}
+
+impl Pool {
+    fn new(quoter_type: QuoterType, token_a: Address, token_b: Address, fee: u64) -> Self {
+        let (token0, token1) = if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+
+        Self {
+            quoter_type,
+            token0,
+            token1,
+            fee,
         }
     }
 }
@@ -58,23 +140,65 @@ pub struct Call {
     pub data: Vec<u8>,
 }
 
+/// Quotes of a single route for a list of amounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RouteQuotes {
+    data: Data,
+    /// `None` for amounts the route cannot handle
+    quotes: Vec<Option<U256>>,
+}
+
 #[async_trait]
-trait Quoter {
+trait Quoter: Send + Sync {
     fn quoter_type(&self) -> QuoterType;
 
+    /// Quotes the output of every route for each of the input amounts
+    async fn quote_input_routes(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amounts_in: &[U256],
+    ) -> Result<Vec<RouteQuotes>>;
+
+    /// Quotes the input every route needs for each of the output amounts
+    async fn quote_output_routes(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amounts_out: &[U256],
+    ) -> Result<Vec<RouteQuotes>>;
+
     async fn quote_input(
         &self,
         token_in: Address,
         token_out: Address,
         amount_in: U256,
-    ) -> Result<(U256, Data)>;
+    ) -> Result<(U256, Data)> {
+        QuoteKind::Input
+            .best(
+                &self
+                    .quote_input_routes(token_in, token_out, &[amount_in])
+                    .await?,
+                0,
+            )
+            .ok_or_else(|| anyhow::anyhow!(ERROR_NO_RESULTS))
+    }
 
     async fn quote_output(
         &self,
         token_in: Address,
         token_out: Address,
         amount_out: U256,
-    ) -> Result<(U256, Data)>;
+    ) -> Result<(U256, Data)> {
+        QuoteKind::Output
+            .best(
+                &self
+                    .quote_output_routes(token_in, token_out, &[amount_out])
+                    .await?,
+                0,
+            )
+            .ok_or_else(|| anyhow::anyhow!(ERROR_NO_RESULTS))
+    }
 
     fn encode(
         &self,
@@ -85,10 +209,35 @@ trait Quoter {
     ) -> Result<Vec<Call>>;
 }
 
+#[async_trait]
+trait GasPrice: Send + Sync {
+    async fn gas_price(&self) -> Result<u128>;
+}
+
+struct ProviderGasPrice<P, N> {
+    provider: P,
+    network: PhantomData<N>,
+}
+
+#[async_trait]
+impl<P, N> GasPrice for ProviderGasPrice<P, N>
+where
+    P: Provider<N> + Clone + 'static,
+    N: Network,
+{
+    async fn gas_price(&self) -> Result<u128> {
+        Ok(self.provider.get_gas_price().await?)
+    }
+}
+
 #[derive(Clone)]
 pub struct QuoteAggregator {
     symbol: String,
-    quoters: Vec<Arc<dyn Quoter + Send + Sync>>,
+    weth: Address,
+    max_splits: usize,
+    split_gas: u64,
+    gas_price: Arc<dyn GasPrice>,
+    quoters: Vec<Arc<dyn Quoter>>,
 }
 
 impl QuoteAggregator {
@@ -102,21 +251,58 @@ impl QuoteAggregator {
         P: Provider<N> + Clone + 'static,
         N: Network,
     {
-        let mut quoters: Vec<Arc<dyn Quoter + Send + Sync>> = Vec::new();
+        let mut quoters: Vec<Arc<dyn Quoter>> = Vec::new();
+        let gas_price = Arc::new(ProviderGasPrice {
+            provider: provider.clone(),
+            network: PhantomData,
+        });
 
-        if let Some(config) = config {
-            let weth = Address::from_str(&config.weth)?;
-            check_contract_exists(&provider, weth).await?;
+        let Some(config) = config else {
+            return Ok(Self {
+                symbol,
+                weth: Address::ZERO,
+                max_splits: default_max_splits(),
+                split_gas: default_split_gas(),
+                gas_price,
+                quoters,
+            });
+        };
 
-            if let Some(uniswap_v3) = config.uniswap_v3 {
-                quoters.push(Arc::new(
-                    uniswap_v3::UniswapV3::new(symbol.clone(), cache, provider, weth, uniswap_v3)
-                        .await?,
-                ));
-            }
+        let weth = Address::from_str(&config.weth)?;
+        check_contract_exists(&provider, weth).await?;
+
+        if config.max_splits == 0 {
+            return Err(anyhow::anyhow!("maxSplits must be at least 1"));
+        }
+
+        if let Some(uniswap_v2) = config.uniswap_v2 {
+            quoters.push(Arc::new(
+                uniswap_v2::UniswapV2::new(
+                    symbol.clone(),
+                    cache.clone(),
+                    provider.clone(),
+                    weth,
+                    uniswap_v2,
+                )
+                .await?,
+            ));
+        }
+
+        if let Some(uniswap_v3) = config.uniswap_v3 {
+            quoters.push(Arc::new(
+                uniswap_v3::UniswapV3::new(symbol.clone(), cache, provider, weth, uniswap_v3)
+                    .await?,
+            ));
         }
 
-        Ok(Self { symbol, quoters })
+        Ok(Self {
+            symbol,
+            weth,
+            max_splits: config.max_splits.min(SPLIT_STEPS),
+            split_gas: config.split_gas,
+            gas_price,
+            quoters,
+        })
     }
 
     #[instrument(name = "QuoteAggregator::quote_input", skip(self, amount_in))]
@@ -132,6 +318,12 @@ impl QuoteAggregator {
 
         debug!("Quoting {}", self.symbol);
 
+        if self.max_splits > 1 {
+            return self
+                .quote_split(QuoteKind::Input, token_in, token_out, amount_in)
+                .await;
+        }
+
         Ok(Self::filter_quotes(
             futures::future::join_all(self.quoters.iter().map(async |quoter| {
                 (
@@ -156,6 +348,12 @@ impl QuoteAggregator {
 
         debug!("Quoting {}", self.symbol);
 
+        if self.max_splits > 1 {
+            return self
+                .quote_split(QuoteKind::Output, token_in, token_out, amount_out)
+                .await;
+        }
+
         Ok(Self::filter_quotes(
             futures::future::join_all(self.quoters.iter().map(async |quoter| {
                 (
@@ -174,22 +372,132 @@ impl QuoteAggregator {
         amount_in: U256,
         amount_out_min: U256,
     ) -> Result<Vec<Call>> {
+        let Data::Split(split) = data else {
+            return self.encode_route(data, recipient, amount_in, amount_out_min);
+        };
+
+        let mut calls = Vec::new();
+        for (data, amount_in, amount_out_min) in split.allocate(amount_in, amount_out_min)? {
+            calls.extend(self.encode_route(data, recipient, amount_in, amount_out_min)?);
+        }
+
+        Ok(calls)
+    }
+
+    fn encode_route(
+        &self,
+        data: Data,
+        recipient: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+    ) -> Result<Vec<Call>> {
+        let quoter_type = data
+            .quoter_type()
+            .ok_or_else(|| anyhow::anyhow!("splits cannot be nested"))?;
         let quoter = self
             .quoters
             .iter()
-            .find(|quoter| quoter.quoter_type() == data.quoter_type());
+            .find(|quoter| quoter.quoter_type() == quoter_type);
 
         if let Some(quoter) = quoter {
             quoter.encode(data, recipient, amount_in, amount_out_min)
         } else {
             Err(anyhow::anyhow!(
                 "no quoter found for type: {:?}",
-                data.quoter_type()
+                quoter_type
             ))
         }
     }
 
-    fn filter_quotes(result: Vec<(QuoterType, Result<(U256, Data)>)>) -> Vec<(U256, Data)> {
+    /// Quotes the best route of every quoter and, when it beats all of them,
+    /// a split of the amount across several routes
+    async fn quote_split(
+        &self,
+        kind: QuoteKind,
+        token_in: Address,
+        token_out: Address,
+        amount: U256,
+    ) -> Result<Vec<(U256, Data)>> {
+        let amounts = split::split_amounts(amount);
+
+        let routes = Self::filter_quotes(
+            futures::future::join_all(self.quoters.iter().map(async |quoter| {
+                (
+                    quoter.quoter_type(),
+                    match kind {
+                        QuoteKind::Input => {
+                            quoter
+                                .quote_input_routes(token_in, token_out, &amounts)
+                                .await
+                        }
+                        QuoteKind::Output => {
+                            quoter
+                                .quote_output_routes(token_in, token_out, &amounts)
+                                .await
+                        }
+                    },
+                )
+            }))
+            .await,
+        );
+
+        let mut quotes = routes
+            .iter()
+            .filter_map(|routes| kind.best(routes, SPLIT_STEPS - 1))
+            .collect::<Vec<_>>();
+
+        let routes = routes.into_iter().flatten().collect::<Vec<_>>();
+        if routes.len() < 2 {
+            return Ok(quotes);
+        }
+
+        // The penalty is denominated in the token that is quoted
+        let penalty = match self
+            .split_penalty(match kind {
+                QuoteKind::Input => token_out,
+                QuoteKind::Output => token_in,
+            })
+            .await
+        {
+            Ok(penalty) => penalty,
+            Err(err) => {
+                warn!("Could not price gas of split routes: {:#}", err);
+                return Ok(quotes);
+            }
+        };
+
+        if let Some(split) = split::best_split(kind, &amounts, &routes, self.max_splits, penalty) {
+            quotes.push(split);
+        }
+
+        Ok(quotes)
+    }
+
+    /// Gas cost of one additional route in the given token
+    async fn split_penalty(&self, token: Address) -> Result<U256> {
+        let cost = U256::from(self.split_gas)
+            .saturating_mul(U256::from(self.gas_price.gas_price().await?));
+
+        if cost.is_zero() || token == Address::ZERO || token == self.weth {
+            return Ok(cost);
+        }
+
+        Self::filter_quotes(
+            futures::future::join_all(self.quoters.iter().map(async |quoter| {
+                (
+                    quoter.quoter_type(),
+                    quoter.quote_input(self.weth, token, cost).await,
+                )
+            }))
+            .await,
+        )
+        .into_iter()
+        .map(|(quote, _)| quote)
+        .max()
+        .ok_or_else(|| anyhow::anyhow!(ERROR_NO_RESULTS))
+    }
+
+    fn filter_quotes<T>(result: Vec<(QuoterType, Result<T>)>) -> Vec<T> {
         result
             .into_iter()
             .filter_map(|(quoter_type, res)| {
@@ -202,3 +510,352 @@ impl QuoteAggregator {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WETH: Address = Address::repeat_byte(0xee);
+    const TOKEN: Address = Address::repeat_byte(0x01);
+
+    struct MockGasPrice(u128);
+
+    #[async_trait]
+    impl GasPrice for MockGasPrice {
+        async fn gas_price(&self) -> Result<u128> {
+            Ok(self.0)
+        }
+    }
+
+    /// Constant product pools without fees; one route per pool
+    struct MockQuoter {
+        quoter_type: QuoterType,
+        reserves: Vec<u64>,
+    }
+
+    impl MockQuoter {
+        fn data(&self, pool: usize) -> Data {
+            match self.quoter_type {
+                QuoterType::UniswapV2 => Data::UniswapV2(uniswap_v2::Data {
+                    path: vec![Address::ZERO, Address::with_last_byte(pool as u8)],
+                }),
+                QuoterType::UniswapV3 => Data::UniswapV3(uniswap_v3::Data {
+                    token_in: Address::ZERO,
+                    hops: vec![uniswap_v3::Hop {
+                        fee: pool as u64,
+                        token: TOKEN,
+                    }],
+                }),
+            }
+        }
+
+        fn routes(
+            &self,
+            quote: impl Fn(U256, U256) -> Option<U256>,
+            amounts: &[U256],
+        ) -> Vec<RouteQuotes> {
+            self.reserves
+                .iter()
+                .enumerate()
+                .map(|(pool, reserve)| RouteQuotes {
+                    data: self.data(pool),
+                    quotes: amounts
+                        .iter()
+                        .map(|amount| quote(*amount, U256::from(*reserve)))
+                        .collect(),
+                })
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl Quoter for MockQuoter {
+        fn quoter_type(&self) -> QuoterType {
+            self.quoter_type
+        }
+
+        async fn quote_input_routes(
+            &self,
+            _token_in: Address,
+            _token_out: Address,
+            amounts_in: &[U256],
+        ) -> Result<Vec<RouteQuotes>> {
+            Ok(self.routes(
+                |amount, reserve| Some(amount * reserve / (reserve + amount)),
+                amounts_in,
+            ))
+        }
+
+        async fn quote_output_routes(
+            &self,
+            _token_in: Address,
+            _token_out: Address,
+            amounts_out: &[U256],
+        ) -> Result<Vec<RouteQuotes>> {
+            Ok(self.routes(
+                |amount, reserve| {
+                    (amount < reserve)
+                        .then(|| reserve * amount / (reserve - amount) + U256::from(1))
+                },
+                amounts_out,
+            ))
+        }
+
+        fn encode(
+            &self,
+            data: Data,
+            recipient: Address,
+            amount_in: U256,
+            amount_out_min: U256,
+        ) -> Result<Vec<Call>> {
+            assert_eq!(data.quoter_type(), Some(self.quoter_type));
+
+            Ok(vec![Call {
+                to: recipient,
+                value: amount_in,
+                data: amount_out_min.to_be_bytes_vec(),
+            }])
+        }
+    }
+
+    fn aggregator(max_splits: usize, gas_price: u128, quoters: Vec<MockQuoter>) -> QuoteAggregator {
+        QuoteAggregator {
+            symbol: "ETH".to_string(),
+            weth: WETH,
+            max_splits,
+            split_gas: 1,
+            gas_price: Arc::new(MockGasPrice(gas_price)),
+            quoters: quoters
+                .into_iter()
+                .map(|quoter| Arc::new(quoter) as Arc<dyn Quoter>)
+                .collect(),
+        }
+    }
+
+    fn v2(reserves: Vec<u64>) -> MockQuoter {
+        MockQuoter {
+            quoter_type: QuoterType::UniswapV2,
+            reserves,
+        }
+    }
+
+    fn v3(reserves: Vec<u64>) -> MockQuoter {
+        MockQuoter {
+            quoter_type: QuoterType::UniswapV3,
+            reserves,
+        }
+    }
+
+    fn split(quotes: &[(U256, Data)]) -> Option<&Split> {
+        quotes.iter().find_map(|(_, data)| match data {
+            Data::Split(split) => Some(split),
+            _ => None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_quote_input_disabled_splits() {
+        let quotes = aggregator(1, 0, vec![v2(vec![1_000, 1_000])])
+            .quote_input(Address::ZERO, WETH, U256::from(1_000))
+            .await
+            .unwrap();
+
+        assert_eq!(quotes, vec![(U256::from(500), v2(vec![]).data(0))]);
+    }
+
+    #[tokio::test]
+    async fn test_quote_input_split() {
+        let quotes = aggregator(3, 0, vec![v2(vec![1_000, 1_000]), v3(vec![1_000])])
+            .quote_input(Address::ZERO, WETH, U256::from(1_000))
+            .await
+            .unwrap();
+
+        // The best single route of every quoter and the split
+        assert_eq!(quotes.len(), 3);
+        assert_eq!(quotes[0], (U256::from(500), v2(vec![]).data(0)));
+        assert_eq!(quotes[1], (U256::from(500), v3(vec![]).data(0)));
+
+        let (quote, _) = quotes[2];
+        assert!(quote > U256::from(500));
+
+        let split = split(&quotes).unwrap();
+        assert_eq!(split.parts.len(), 3);
+        assert_eq!(
+            split
+                .parts
+                .iter()
+                .filter(|part| part.data.quoter_type() == Some(QuoterType::UniswapV3))
+                .count(),
+            1
+        );
+        assert_eq!(
+            split
+                .parts
+                .iter()
+                .fold(U256::ZERO, |sum, part| sum + part.amount_out),
+            quote
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quote_output_split() {
+        let quotes = aggregator(2, 0, vec![v2(vec![1_000]), v3(vec![1_000])])
+            .quote_output(Address::ZERO, WETH, U256::from(500))
+            .await
+            .unwrap();
+
+        assert_eq!(quotes.len(), 3);
+        let (quote, _) = quotes[2];
+        assert_eq!(quote, U256::from(668));
+
+        let split = split(&quotes).unwrap();
+        assert_eq!(
+            split
+                .parts
+                .iter()
+                .map(|part| part.amount_out)
+                .collect::<Vec<_>>(),
+            vec![U256::from(250), U256::from(250)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quote_split_gas_penalty() {
+        // Splitting gains 166, which does not cover the gas of another route
+        let quotes = aggregator(2, 167, vec![v2(vec![1_000, 1_000])])
+            .quote_input(Address::ZERO, WETH, U256::from(1_000))
+            .await
+            .unwrap();
+        assert_eq!(split(&quotes), None);
+
+        let quotes = aggregator(2, 165, vec![v2(vec![1_000, 1_000])])
+            .quote_input(Address::ZERO, WETH, U256::from(1_000))
+            .await
+            .unwrap();
+        assert!(split(&quotes).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_split_penalty_priced_in_token() {
+        let aggregator = aggregator(2, 100, vec![v2(vec![1_000, 2_000])]);
+
+        assert_eq!(
+            aggregator.split_penalty(WETH).await.unwrap(),
+            U256::from(100)
+        );
+        assert_eq!(
+            aggregator.split_penalty(Address::ZERO).await.unwrap(),
+            U256::from(100)
+        );
+        // Best quote for 100 wei of WETH: 100 * 2000 / 2100
+        assert_eq!(
+            aggregator.split_penalty(TOKEN).await.unwrap(),
+            U256::from(95)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encode_split() {
+        let aggregator = aggregator(2, 0, vec![v2(vec![1_000]), v3(vec![1_000])]);
+        let quotes = aggregator
+            .quote_input(Address::ZERO, WETH, U256::from(1_000))
+            .await
+            .unwrap();
+        let (_, data) = quotes
+            .into_iter()
+            .find(|(_, data)| matches!(data, Data::Split(_)))
+            .unwrap();
+
+        let recipient = Address::repeat_byte(0x42);
+        let calls = aggregator
+            .encode(data, recipient, U256::from(1_001), U256::from(600))
+            .unwrap();
+
+        assert_eq!(
+            calls,
+            vec![
+                Call {
+                    to: recipient,
+                    value: U256::from(500),
+                    data: U256::from(300).to_be_bytes_vec(),
+                },
+                Call {
+                    to: recipient,
+                    value: U256::from(501),
+                    data: U256::from(300).to_be_bytes_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_unknown_quoter() {
+        let err = aggregator(1, 0, vec![v3(vec![])])
+            .encode(
+                v2(vec![]).data(0),
+                Address::ZERO,
+                U256::from(1),
+                U256::from(1),
+            )
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "no quoter found for type: UniswapV2");
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config: Config =
+            serde_json::from_str(r#"{"weth":"0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee"}"#)
+                .unwrap();
+
+        assert_eq!(config.max_splits, 1);
+        assert_eq!(config.split_gas, 100_000);
+    }
+
+    #[test]
+    fn test_data_pools() {
+        let v2 = Data::UniswapV2(uniswap_v2::Data {
+            path: vec![TOKEN, WETH, Address::ZERO],
+        });
+        assert_eq!(
+            v2.pools(),
+            vec![
+                Pool::new(QuoterType::UniswapV2, WETH, TOKEN, 0),
+                Pool::new(QuoterType::UniswapV2, Address::ZERO, WETH, 0),
+            ]
+        );
+
+        let v3 = Data::UniswapV3(uniswap_v3::Data {
+            token_in: TOKEN,
+            hops: vec![
+                uniswap_v3::Hop {
+                    fee: 500,
+                    token: WETH,
+                },
+                uniswap_v3::Hop {
+                    fee: 3_000,
+                    token: Address::ZERO,
+                },
+            ],
+        });
+        assert_eq!(
+            v3.pools(),
+            vec![
+                Pool::new(QuoterType::UniswapV3, WETH, TOKEN, 500),
+                Pool::new(QuoterType::UniswapV3, WETH, Address::ZERO, 3_000),
+            ]
+        );
+
+        // The same tokens in the other direction are the same pool
+        assert_eq!(
+            Pool::new(QuoterType::UniswapV3, TOKEN, WETH, 500),
+            Pool::new(QuoterType::UniswapV3, WETH, TOKEN, 500)
+        );
+        assert_ne!(
+            Pool::new(QuoterType::UniswapV2, TOKEN, WETH, 0),
+            Pool::new(QuoterType::UniswapV3, TOKEN, WETH, 0)
+        );
+
+        assert!(Data::Split(Split { parts: vec![] }).pools().is_empty());
+    }
+}
@@ -0,0 +1,604 @@
+use crate::quoter::{Data, RouteQuotes};
+use alloy::primitives::U256;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Total of a split and how many steps are allocated to which routes
+type Plan = (U256, Vec<(usize, usize)>);
+
+/// Granularity in which an amount is divided between the routes of a split
+pub const SPLIT_STEPS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteKind {
+    /// Exact input; more output is better
+    Input,
+    /// Exact output; less input is better
+    Output,
+}
+
+impl QuoteKind {
+    pub fn is_better(self, quote: U256, other: U256) -> bool {
+        match self {
+            QuoteKind::Input => quote > other,
+            QuoteKind::Output => quote < other,
+        }
+    }
+
+    /// Best quote among the routes for the amount at `index`
+    pub fn best(self, routes: &[RouteQuotes], index: usize) -> Option<(U256, Data)> {
+        routes
+            .iter()
+            .filter_map(|route| {
+                route
+                    .quotes
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .map(|quote| (quote, &route.data))
+            })
+            .reduce(|best, candidate| {
+                if self.is_better(candidate.0, best.0) {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .map(|(quote, data)| (quote, data.clone()))
+    }
+
+    /// Quotes with more routes have to make up for the gas their extra routes cost
+    fn penalize(self, quote: U256, penalty: U256) -> U256 {
+        match self {
+            QuoteKind::Input => quote.saturating_sub(penalty),
+            QuoteKind::Output => quote.saturating_add(penalty),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+    pub parts: Vec<SplitPart>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SplitPart {
+    #[serde(rename = "amountIn")]
+    #[serde(serialize_with = "crate::serde_utils::u256::serialize")]
+    pub amount_in: U256,
+    #[serde(rename = "amountOut")]
+    #[serde(serialize_with = "crate::serde_utils::u256::serialize")]
+    pub amount_out: U256,
+    pub data: Data,
+}
+
+impl Split {
+    /// Divides the amounts of a swap between the parts in the same ratios as
+    /// they were quoted
+    pub fn allocate(
+        self,
+        amount_in: U256,
+        amount_out_min: U256,
+    ) -> Result<Vec<(Data, U256, U256)>> {
+        if self.parts.is_empty() {
+            return Err(anyhow::anyhow!("split has no parts"));
+        }
+
+        if self
+            .parts
+            .iter()
+            .any(|part| matches!(part.data, Data::Split(_)))
+        {
+            return Err(anyhow::anyhow!("splits cannot be nested"));
+        }
+
+        let amounts_in = allocate(
+            amount_in,
+            &self
+                .parts
+                .iter()
+                .map(|part| part.amount_in)
+                .collect::<Vec<_>>(),
+        )?;
+        let amounts_out_min = allocate(
+            amount_out_min,
+            &self
+                .parts
+                .iter()
+                .map(|part| part.amount_out)
+                .collect::<Vec<_>>(),
+        )?;
+
+        Ok(self
+            .parts
+            .into_iter()
+            .zip(amounts_in.into_iter().zip(amounts_out_min))
+            .map(|(part, (amount_in, amount_out_min))| (part.data, amount_in, amount_out_min))
+            .collect())
+    }
+}
+
+/// Amounts for every step of [`SPLIT_STEPS`]; the last one is the full amount
+pub fn split_amounts(amount: U256) -> Vec<U256> {
+    (1..=SPLIT_STEPS)
+        .map(|step| amount * U256::from(step) / U256::from(SPLIT_STEPS))
+        .collect()
+}
+
+/// Finds the combination of at most `max_splits` routes that gives the best
+/// quote after the `penalty` for every route beyond the first. Routes are
+/// quoted independently of each other, so only routes that do not share a pool
+/// are combined. Returns `None` when a single route is better
+pub fn best_split(
+    kind: QuoteKind,
+    amounts: &[U256],
+    routes: &[RouteQuotes],
+    max_splits: usize,
+    penalty: U256,
+) -> Option<(U256, Data)> {
+    let steps = amounts.len();
+    let pools = routes
+        .iter()
+        .map(|route| route.data.pools().into_iter().collect::<HashSet<_>>())
+        .collect::<Vec<_>>();
+
+    // The best total and how the steps are allocated to routes, indexed by
+    // the number of routes used and the number of steps allocated
+    let mut plans: Vec<Vec<Option<Plan>>> = vec![vec![None; steps + 1]; max_splits + 1];
+    plans[0][0] = Some((U256::ZERO, Vec::new()));
+
+    for (route_index, route) in routes.iter().enumerate() {
+        // Going from the most parts down uses every route only once
+        for parts in (1..=max_splits).rev() {
+            let (previous, current) = plans.split_at_mut(parts);
+            let (previous, current) = (&previous[parts - 1], &mut current[0]);
+
+            for allocated in 1..=steps {
+                for route_steps in 1..=allocated {
+                    let (Some((total, allocation)), Some(Some(quote))) = (
+                        &previous[allocated - route_steps],
+                        route.quotes.get(route_steps - 1),
+                    ) else {
+                        continue;
+                    };
+
+                    if allocation
+                        .iter()
+                        .any(|(other, _)| !pools[*other].is_disjoint(&pools[route_index]))
+                    {
+                        continue;
+                    }
+
+                    let candidate = total.saturating_add(*quote);
+                    if current[allocated]
+                        .as_ref()
+                        .is_none_or(|(best, _)| kind.is_better(candidate, *best))
+                    {
+                        let mut allocation = allocation.clone();
+                        allocation.push((route_index, route_steps));
+                        current[allocated] = Some((candidate, allocation));
+                    }
+                }
+            }
+        }
+    }
+
+    let (_, total, allocation) = plans
+        .into_iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(parts, mut plans)| plans[steps].take().map(|plan| (parts, plan)))
+        .map(|(parts, (total, allocation))| {
+            let penalty = penalty.saturating_mul(U256::from(parts - 1));
+            (kind.penalize(total, penalty), total, allocation)
+        })
+        .reduce(|best, candidate| {
+            if kind.is_better(candidate.0, best.0) {
+                candidate
+            } else {
+                best
+            }
+        })?;
+
+    if allocation.len() < 2 {
+        return None;
+    }
+
+    let mut allocation = allocation;
+    allocation.sort();
+
+    Some((
+        total,
+        Data::Split(Split {
+            parts: allocation
+                .into_iter()
+                .map(|(route_index, route_steps)| {
+                    let route = &routes[route_index];
+                    let amount = amounts[route_steps - 1];
+                    let quote = route.quotes[route_steps - 1].unwrap_or_default();
+
+                    let (amount_in, amount_out) = match kind {
+                        QuoteKind::Input => (amount, quote),
+                        QuoteKind::Output => (quote, amount),
+                    };
+
+                    SplitPart {
+                        amount_in,
+                        amount_out,
+                        data: route.data.clone(),
+                    }
+                })
+                .collect(),
+        }),
+    ))
+}
+
+/// Divides `amount` proportionally to the weights, giving the rounding
+/// remainder to the last one
+fn allocate(amount: U256, weights: &[U256]) -> Result<Vec<U256>> {
+    let total = weights
+        .iter()
+        .try_fold(U256::ZERO, |total, weight| total.checked_add(*weight))
+        .ok_or_else(|| anyhow::anyhow!("split amounts overflow"))?;
+    if total.is_zero() {
+        return Err(anyhow::anyhow!("split has no amounts"));
+    }
+
+    let mut amounts = weights[..weights.len() - 1]
+        .iter()
+        .map(|weight| {
+            amount
+                .checked_mul(*weight)
+                .map(|amount| amount / total)
+                .ok_or_else(|| anyhow::anyhow!("split amounts overflow"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let allocated = amounts.iter().fold(U256::ZERO, |sum, amount| sum + *amount);
+    amounts.push(amount - allocated);
+
+    Ok(amounts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::quoter::{uniswap_v2, uniswap_v3};
+    use alloy::primitives::Address;
+
+    fn data(token: u8) -> Data {
+        Data::UniswapV2(uniswap_v2::Data {
+            path: vec![Address::ZERO, Address::repeat_byte(token)],
+        })
+    }
+
+    /// Quotes of a constant product pool without fees
+    fn pool(kind: QuoteKind, token: u8, reserve: u64, amounts: &[U256]) -> RouteQuotes {
+        let reserve = U256::from(reserve);
+
+        RouteQuotes {
+            data: data(token),
+            quotes: amounts
+                .iter()
+                .map(|amount| match kind {
+                    QuoteKind::Input => Some(amount * reserve / (reserve + amount)),
+                    QuoteKind::Output => (*amount < reserve)
+                        .then(|| reserve * amount / (reserve - amount) + U256::from(1)),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_split_amounts() {
+        let amounts = split_amounts(U256::from(1_001));
+
+        assert_eq!(amounts.len(), SPLIT_STEPS);
+        assert_eq!(amounts[0], U256::from(50));
+        assert_eq!(amounts[9], U256::from(500));
+        assert_eq!(amounts[SPLIT_STEPS - 1], U256::from(1_001));
+    }
+
+    #[test]
+    fn test_best() {
+        let amounts = split_amounts(U256::from(1_000));
+        let routes = [
+            pool(QuoteKind::Input, 1, 1_000, &amounts),
+            pool(QuoteKind::Input, 2, 10_000, &amounts),
+        ];
+
+        let (quote, data) = QuoteKind::Input.best(&routes, SPLIT_STEPS - 1).unwrap();
+        assert_eq!(quote, U256::from(909));
+        assert_eq!(data, self::data(2));
+
+        let routes = [
+            pool(QuoteKind::Output, 1, 1_000, &amounts),
+            pool(QuoteKind::Output, 2, 10_000, &amounts),
+        ];
+        let (quote, data) = QuoteKind::Output.best(&routes, SPLIT_STEPS - 1).unwrap();
+        assert_eq!(quote, U256::from(1_112));
+        assert_eq!(data, self::data(2));
+
+        assert_eq!(QuoteKind::Input.best(&[], 0), None);
+    }
+
+    #[test]
+    fn test_best_split_input() {
+        let amounts = split_amounts(U256::from(1_000));
+        let routes = [
+            pool(QuoteKind::Input, 1, 1_000, &amounts),
+            pool(QuoteKind::Input, 2, 1_000, &amounts),
+        ];
+
+        let (quote, data) = best_split(QuoteKind::Input, &amounts, &routes, 2, U256::ZERO).unwrap();
+
+        // Half into each pool beats everything into one: 2 * 333 > 500
+        assert_eq!(quote, U256::from(666));
+        assert_eq!(
+            data,
+            Data::Split(Split {
+                parts: vec![
+                    SplitPart {
+                        amount_in: U256::from(500),
+                        amount_out: U256::from(333),
+                        data: self::data(1),
+                    },
+                    SplitPart {
+                        amount_in: U256::from(500),
+                        amount_out: U256::from(333),
+                        data: self::data(2),
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_best_split_output() {
+        let amounts = split_amounts(U256::from(500));
+        let routes = [
+            pool(QuoteKind::Output, 1, 1_000, &amounts),
+            pool(QuoteKind::Output, 2, 1_000, &amounts),
+        ];
+
+        let (quote, data) =
+            best_split(QuoteKind::Output, &amounts, &routes, 2, U256::ZERO).unwrap();
+
+        // 2 * 334 is less than the 1001 a single pool needs
+        assert_eq!(quote, U256::from(668));
+        let Data::Split(split) = data else {
+            panic!("expected split");
+        };
+        assert_eq!(split.parts.len(), 2);
+        assert!(split.parts.iter().all(|part| {
+            part.amount_out == U256::from(250) && part.amount_in == U256::from(334)
+        }));
+    }
+
+    #[test]
+    fn test_best_split_max_splits() {
+        let amounts = split_amounts(U256::from(1_000));
+        let routes = (1..=4)
+            .map(|token| pool(QuoteKind::Input, token, 1_000, &amounts))
+            .collect::<Vec<_>>();
+
+        for max_splits in [2, 3, 4] {
+            let (_, data) =
+                best_split(QuoteKind::Input, &amounts, &routes, max_splits, U256::ZERO).unwrap();
+            let Data::Split(split) = data else {
+                panic!("expected split");
+            };
+            assert_eq!(split.parts.len(), max_splits);
+        }
+
+        assert_eq!(
+            best_split(QuoteKind::Input, &amounts, &routes, 1, U256::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn test_best_split_penalty() {
+        let amounts = split_amounts(U256::from(1_000));
+        let routes = [
+            pool(QuoteKind::Input, 1, 1_000, &amounts),
+            pool(QuoteKind::Input, 2, 1_000, &amounts),
+        ];
+
+        // Splitting gains 166, so a higher penalty makes it not worth it
+        assert!(best_split(QuoteKind::Input, &amounts, &routes, 2, U256::from(165)).is_some());
+        assert_eq!(
+            best_split(QuoteKind::Input, &amounts, &routes, 2, U256::from(167)),
+            None
+        );
+
+        let routes = [
+            pool(QuoteKind::Output, 1, 1_000, &amounts[..10]),
+            pool(QuoteKind::Output, 2, 1_000, &amounts[..10]),
+        ];
+        assert_eq!(
+            best_split(
+                QuoteKind::Output,
+                &amounts[..10],
+                &routes,
+                2,
+                U256::from(1_000)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_best_split_single_route() {
+        let amounts = split_amounts(U256::from(1_000));
+        let routes = [
+            pool(QuoteKind::Input, 1, 1_000_000, &amounts),
+            pool(QuoteKind::Input, 2, 10, &amounts),
+        ];
+
+        assert_eq!(
+            best_split(QuoteKind::Input, &amounts, &routes, 2, U256::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn test_best_split_skips_failed_quotes() {
+        let amounts = split_amounts(U256::from(1_000));
+        let mut limited = pool(QuoteKind::Input, 2, 1_000, &amounts);
+        // Only handles up to a quarter of the amount
+        for quote in limited.quotes.iter_mut().skip(5) {
+            *quote = None;
+        }
+        let routes = [pool(QuoteKind::Input, 1, 1_000, &amounts), limited];
+
+        let (_, data) = best_split(QuoteKind::Input, &amounts, &routes, 2, U256::ZERO).unwrap();
+        let Data::Split(split) = data else {
+            panic!("expected split");
+        };
+        assert_eq!(split.parts[0].amount_in, U256::from(750));
+        assert_eq!(split.parts[1].amount_in, U256::from(250));
+    }
+
+    #[test]
+    fn test_best_split_shared_pool() {
+        let amounts = split_amounts(U256::from(1_000));
+        let weth = Address::repeat_byte(0xee);
+        let through_weth = |fee: u64| {
+            Data::UniswapV3(uniswap_v3::Data {
+                token_in: Address::ZERO,
+                hops: vec![
+                    uniswap_v3::Hop {
+                        fee: 500,
+                        token: weth,
+                    },
+                    uniswap_v3::Hop {
+                        fee,
+                        token: Address::repeat_byte(1),
+                    },
+                ],
+            })
+        };
+
+        // Both routes swap through the same pool into WETH, so quoting them
+        // independently overestimates what a split of them gets
+        let mut low_fee = pool(QuoteKind::Input, 1, 1_000, &amounts);
+        low_fee.data = through_weth(500);
+        let mut high_fee = pool(QuoteKind::Input, 2, 1_000, &amounts);
+        high_fee.data = through_weth(3_000);
+
+        assert_eq!(
+            best_split(
+                QuoteKind::Input,
+                &amounts,
+                &[low_fee.clone(), high_fee.clone()],
+                2,
+                U256::ZERO
+            ),
+            None
+        );
+
+        // A route with pools of its own can still be combined with either
+        let routes = [
+            low_fee,
+            high_fee,
+            pool(QuoteKind::Input, 3, 1_000, &amounts),
+        ];
+        let (quote, data) = best_split(QuoteKind::Input, &amounts, &routes, 3, U256::ZERO).unwrap();
+        assert_eq!(quote, U256::from(666));
+        let Data::Split(split) = data else {
+            panic!("expected split");
+        };
+        assert_eq!(split.parts.len(), 2);
+        assert_eq!(split.parts[0].data, through_weth(500));
+        assert_eq!(split.parts[1].data, self::data(3));
+    }
+
+    #[test]
+    fn test_allocate() {
+        let split = Split {
+            parts: vec![
+                SplitPart {
+                    amount_in: U256::from(1),
+                    amount_out: U256::from(10),
+                    data: data(1),
+                },
+                SplitPart {
+                    amount_in: U256::from(2),
+                    amount_out: U256::from(30),
+                    data: data(2),
+                },
+            ],
+        };
+
+        let allocated = split.allocate(U256::from(100), U256::from(41)).unwrap();
+        assert_eq!(
+            allocated,
+            vec![
+                (data(1), U256::from(33), U256::from(10)),
+                (data(2), U256::from(67), U256::from(31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_allocate_invalid() {
+        assert_eq!(
+            Split { parts: vec![] }
+                .allocate(U256::from(1), U256::from(1))
+                .unwrap_err()
+                .to_string(),
+            "split has no parts"
+        );
+
+        let nested = Split {
+            parts: vec![SplitPart {
+                amount_in: U256::from(1),
+                amount_out: U256::from(1),
+                data: Data::Split(Split { parts: vec![] }),
+            }],
+        };
+        assert_eq!(
+            nested
+                .allocate(U256::from(1), U256::from(1))
+                .unwrap_err()
+                .to_string(),
+            "splits cannot be nested"
+        );
+
+        let empty = Split {
+            parts: vec![SplitPart {
+                amount_in: U256::ZERO,
+                amount_out: U256::ZERO,
+                data: data(1),
+            }],
+        };
+        assert_eq!(
+            empty
+                .allocate(U256::from(1), U256::from(1))
+                .unwrap_err()
+                .to_string(),
+            "split has no amounts"
+        );
+    }
+
+    #[test]
+    fn test_split_serde() {
+        let data = Data::Split(Split {
+            parts: vec![SplitPart {
+                amount_in: U256::from(21),
+                amount_out: U256::from(42),
+                data: data(1),
+            }],
+        });
+
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["type"], "split");
+        assert_eq!(json["parts"][0]["amountIn"], "21");
+        assert_eq!(json["parts"][0]["amountOut"], "42");
+        assert_eq!(json["parts"][0]["data"]["type"], "uniswapV2");
+
+        assert_eq!(serde_json::from_value::<Data>(json).unwrap(), data);
+    }
+}
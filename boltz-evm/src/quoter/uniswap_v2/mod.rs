@@ -0,0 +1,1243 @@
+use crate::quoter::uniswap_v2::IUniswapV2Factory::IUniswapV2FactoryInstance;
+use crate::quoter::uniswap_v2::IUniswapV2Pair::IUniswapV2PairInstance;
+use crate::quoter::{Call, Data as QuoterData, Quoter, QuoterType, RouteQuotes};
+use crate::utils::check_contract_exists;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::providers::network::Network;
+use alloy::sol;
+use anyhow::Result;
+use async_trait::async_trait;
+use boltz_cache::Cache;
+use router::Router;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::info;
+use tracing::instrument;
+
+mod router;
+
+/// Reserves of a pair, keyed by its tokens in sorted order
+type PairReserves = HashMap<(Address, Address), (U256, U256)>;
+
+/// Fees of pairs are configured in basis points
+const FEE_DENOMINATOR: u64 = 10_000;
+
+const CACHE_KEY_PAIRS: &str = "uniswap_v2_pairs";
+const CACHE_TTL_SECS: u64 = Duration::from_mins(60).as_secs();
+
+const fn default_fee() -> u64 {
+    30
+}
+
+sol!(
+    #[sol(rpc)]
+    "./src/quoter/uniswap_v2/abis/IUniswapV2Factory.sol"
+);
+
+sol!(
+    #[sol(rpc)]
+    "./src/quoter/uniswap_v2/abis/IUniswapV2Pair.sol"
+);
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct Config {
+    pub factory: Address,
+    pub router: Address,
+
+    /// Swap fee of the pairs in basis points. Forks like PancakeSwap charge
+    /// less than the 30 of Uniswap
+    #[serde(default = "default_fee")]
+    pub fee: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Data {
+    /// Tokens to swap through, starting with the input and ending with the
+    /// output token
+    pub path: Vec<Address>,
+}
+
+impl Data {
+    pub fn normalize(mut self, token_in: Address, token_out: Address) -> Self {
+        if let Some(first) = self.path.first_mut() {
+            *first = token_in;
+        }
+
+        if let Some(last) = self.path.last_mut() {
+            *last = token_out;
+        }
+
+        self
+    }
+}
+
+/// A path through existing pairs with their reserves in the direction of the swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Route {
+    path: Vec<Address>,
+    reserves: Vec<(U256, U256)>,
+}
+
+impl Route {
+    fn amount_out(&self, amount_in: U256, fee: u64) -> Option<U256> {
+        self.reserves
+            .iter()
+            .try_fold(amount_in, |amount, (reserve_in, reserve_out)| {
+                get_amount_out(amount, *reserve_in, *reserve_out, fee)
+            })
+    }
+
+    fn amount_in(&self, amount_out: U256, fee: u64) -> Option<U256> {
+        self.reserves
+            .iter()
+            .rev()
+            .try_fold(amount_out, |amount, (reserve_in, reserve_out)| {
+                get_amount_in(amount, *reserve_in, *reserve_out, fee)
+            })
+    }
+}
+
+/// Same maths as `UniswapV2Library.getAmountOut`, but with a configurable fee.
+/// Returns `None` when the pair cannot give out anything for the input
+fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256, fee: u64) -> Option<U256> {
+    if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+        return None;
+    }
+
+    let amount_in_with_fee = amount_in.checked_mul(U256::from(FEE_DENOMINATOR - fee))?;
+    let numerator = amount_in_with_fee.checked_mul(reserve_out)?;
+    let denominator = reserve_in
+        .checked_mul(U256::from(FEE_DENOMINATOR))?
+        .checked_add(amount_in_with_fee)?;
+
+    Some(numerator / denominator).filter(|amount_out| !amount_out.is_zero())
+}
+
+/// Same maths as `UniswapV2Library.getAmountIn`, but with a configurable fee.
+/// Returns `None` when the pair does not have enough liquidity for the output
+fn get_amount_in(amount_out: U256, reserve_in: U256, reserve_out: U256, fee: u64) -> Option<U256> {
+    if amount_out.is_zero() || reserve_in.is_zero() || amount_out >= reserve_out {
+        return None;
+    }
+
+    let numerator = reserve_in
+        .checked_mul(amount_out)?
+        .checked_mul(U256::from(FEE_DENOMINATOR))?;
+    let denominator = (reserve_out - amount_out).checked_mul(U256::from(FEE_DENOMINATOR - fee))?;
+
+    Some(numerator / denominator + U256::from(1))
+}
+
+fn sort_tokens(token_a: Address, token_b: Address) -> (Address, Address) {
+    if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UniswapV2<P, N> {
+    provider: P,
+    cache: Cache,
+    symbol: String,
+    weth: Address,
+    fee: u64,
+    factory: IUniswapV2FactoryInstance<P, N>,
+    router: Router,
+}
+
+impl<P, N> UniswapV2<P, N>
+where
+    P: Provider<N> + Clone + 'static,
+    N: Network,
+{
+    pub async fn new(
+        symbol: String,
+        cache: Cache,
+        provider: P,
+        weth: Address,
+        config: Config,
+    ) -> Result<Self> {
+        if config.fee >= FEE_DENOMINATOR {
+            return Err(anyhow::anyhow!(
+                "fee must be less than {} basis points",
+                FEE_DENOMINATOR
+            ));
+        }
+
+        info!(
+            "Using {} Uniswap V2 factory {} and router {} with a fee of {} basis points",
+            symbol,
+            config.factory.to_string(),
+            config.router.to_string(),
+            config.fee,
+        );
+
+        tokio::try_join!(
+            check_contract_exists(&provider, weth),
+            check_contract_exists(&provider, config.factory),
+            check_contract_exists(&provider, config.router),
+        )?;
+
+        Ok(Self {
+            provider: provider.clone(),
+            cache,
+            symbol,
+            weth,
+            fee: config.fee,
+            factory: IUniswapV2FactoryInstance::new(config.factory, provider),
+            router: Router::new(weth, config.router),
+        })
+    }
+
+    fn candidate_paths(&self, token_in: Address, token_out: Address) -> Vec<Vec<Address>> {
+        let mut paths = vec![vec![token_in, token_out]];
+
+        // Most of the liquidity is paired with the wrapped native token
+        if token_in != self.weth && token_out != self.weth {
+            paths.push(vec![token_in, self.weth, token_out]);
+        }
+
+        paths
+    }
+
+    async fn lookup_pair(&self, token_a: Address, token_b: Address) -> Result<Option<Address>> {
+        let (cache_key, cache_field) = self.cache_key_pairs(token_a, token_b);
+
+        let pair = match self.cache.get::<Address>(&cache_key, &cache_field).await? {
+            Some(pair) => pair,
+            None => {
+                let pair = self.factory.getPair(token_a, token_b).call().await?;
+                self.cache
+                    .set(&cache_key, &cache_field, &pair, Some(CACHE_TTL_SECS))
+                    .await?;
+                pair
+            }
+        };
+
+        Ok(if pair == Address::ZERO {
+            None
+        } else {
+            Some(pair)
+        })
+    }
+
+    async fn lookup_reserves(
+        &self,
+        token_a: Address,
+        token_b: Address,
+    ) -> Result<Option<(U256, U256)>> {
+        let pair = match self.lookup_pair(token_a, token_b).await? {
+            Some(pair) => pair,
+            None => return Ok(None),
+        };
+
+        let reserves = IUniswapV2PairInstance::new(pair, self.provider.clone())
+            .getReserves()
+            .call()
+            .await?;
+
+        Ok(Some((
+            U256::from(reserves.reserve0),
+            U256::from(reserves.reserve1),
+        )))
+    }
+
+    async fn lookup_and_route(&self, token_in: Address, token_out: Address) -> Result<Vec<Route>> {
+        let paths = self.candidate_paths(token_in, token_out);
+
+        let pairs = paths
+            .iter()
+            .flat_map(|path| path.windows(2).map(|hop| sort_tokens(hop[0], hop[1])))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let reserves = futures::future::try_join_all(
+            pairs
+                .iter()
+                .map(|(token_a, token_b)| self.lookup_reserves(*token_a, *token_b)),
+        )
+        .await?;
+
+        let reserves: PairReserves = pairs
+            .into_iter()
+            .zip(reserves)
+            .filter_map(|(pair, reserves)| reserves.map(|reserves| (pair, reserves)))
+            .collect();
+
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| Self::route(path, &reserves))
+            .collect())
+    }
+
+    fn route(path: Vec<Address>, reserves: &PairReserves) -> Option<Route> {
+        let hops = path
+            .windows(2)
+            .map(|hop| {
+                let pair = sort_tokens(hop[0], hop[1]);
+                let (reserve0, reserve1) = reserves.get(&pair)?;
+
+                Some(if hop[0] == pair.0 {
+                    (*reserve0, *reserve1)
+                } else {
+                    (*reserve1, *reserve0)
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Route {
+            path,
+            reserves: hops,
+        })
+    }
+
+    fn cache_key_pairs(&self, token_a: Address, token_b: Address) -> (String, String) {
+        let (token_a, token_b) = sort_tokens(token_a, token_b);
+
+        (
+            format!("{CACHE_KEY_PAIRS}:{}", self.symbol),
+            format!("{token_a}-{token_b}"),
+        )
+    }
+}
+
+#[async_trait]
+impl<P, N> Quoter for UniswapV2<P, N>
+where
+    P: Provider<N> + Clone + 'static,
+    N: Network,
+{
+    fn quoter_type(&self) -> QuoterType {
+        QuoterType::UniswapV2
+    }
+
+    #[instrument(name = "UniswapV2::quote_input_routes", skip_all)]
+    async fn quote_input_routes(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amounts_in: &[U256],
+    ) -> Result<Vec<RouteQuotes>> {
+        Ok(self
+            .lookup_and_route(
+                self.router.handle_eth(token_in),
+                self.router.handle_eth(token_out),
+            )
+            .await?
+            .into_iter()
+            .map(|route| RouteQuotes {
+                quotes: amounts_in
+                    .iter()
+                    .map(|amount_in| route.amount_out(*amount_in, self.fee))
+                    .collect(),
+                data: QuoterData::UniswapV2(
+                    Data { path: route.path }.normalize(token_in, token_out),
+                ),
+            })
+            .collect())
+    }
+
+    #[instrument(name = "UniswapV2::quote_output_routes", skip_all)]
+    async fn quote_output_routes(
+        &self,
+        token_in: Address,
+        token_out: Address,
+        amounts_out: &[U256],
+    ) -> Result<Vec<RouteQuotes>> {
+        Ok(self
+            .lookup_and_route(
+                self.router.handle_eth(token_in),
+                self.router.handle_eth(token_out),
+            )
+            .await?
+            .into_iter()
+            .map(|route| RouteQuotes {
+                quotes: amounts_out
+                    .iter()
+                    .map(|amount_out| route.amount_in(*amount_out, self.fee))
+                    .collect(),
+                data: QuoterData::UniswapV2(
+                    Data { path: route.path }.normalize(token_in, token_out),
+                ),
+            })
+            .collect())
+    }
+
+    fn encode(
+        &self,
+        data: QuoterData,
+        recipient: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+    ) -> Result<Vec<Call>> {
+        let QuoterData::UniswapV2(data) = data else {
+            return Err(anyhow::anyhow!("unsupported data"));
+        };
+
+        self.router
+            .encode(data, recipient, amount_in, amount_out_min)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::router::IUniswapV2Router02;
+    use super::*;
+    use crate::test_utils::{PROVIDER, signer, signer_address};
+    use alloy::network::{Ethereum, EthereumWallet, TransactionBuilder};
+    use alloy::primitives::{Bytes, address};
+    use alloy::providers::{DynProvider, ProviderBuilder};
+    use alloy::rpc::types::TransactionRequest;
+    use alloy::sol_types::SolCall;
+    use boltz_cache::MemCache;
+    use serial_test::serial;
+
+    /// Deploys WETH9, the factory and the router of Uniswap V2 with its first
+    /// nonces in the regtest setup (`tools/deploy-uniswap-v2.sh`)
+    const DEPLOYER: Address = address!("0xa0Ee7A142d267C1f36714E4a8F75612F20a79720");
+
+    const FEE: u64 = 30;
+
+    sol!(
+        #[sol(rpc)]
+        interface IWETH9 {
+            function deposit() external payable;
+            function transfer(address to, uint256 value) external returns (bool);
+            function balanceOf(address account) external view returns (uint256);
+        }
+    );
+
+    struct Setup {
+        provider: DynProvider,
+        weth: Address,
+        factory: Address,
+        router: Address,
+        token_code: Bytes,
+    }
+
+    impl Setup {
+        async fn new() -> Self {
+            let provider = DynProvider::new(
+                ProviderBuilder::new()
+                    .wallet(EthereumWallet::from(signer()))
+                    .connect_http(PROVIDER.parse().unwrap()),
+            );
+            let weth = DEPLOYER.create(0);
+            let token_code = provider.get_code_at(weth).await.unwrap();
+
+            Self {
+                provider,
+                weth,
+                factory: DEPLOYER.create(1),
+                router: DEPLOYER.create(2),
+                token_code,
+            }
+        }
+
+        async fn quoter(&self, cache: Cache) -> UniswapV2<DynProvider, Ethereum> {
+            UniswapV2::new(
+                "ETH".to_string(),
+                cache,
+                self.provider.clone(),
+                self.weth,
+                Config {
+                    factory: self.factory,
+                    router: self.router,
+                    fee: FEE,
+                },
+            )
+            .await
+            .unwrap()
+        }
+
+        /// New ERC20 token that mints for Ether deposited into it
+        async fn token(&self) -> Address {
+            let token = random_token();
+            set_code(&self.provider, token, &self.token_code).await;
+            token
+        }
+
+        /// Mints `amount` of `token` and sends it to `recipient`
+        async fn fund(&self, token: Address, recipient: Address, amount: u128) {
+            let balance = self.provider.get_balance(signer_address()).await.unwrap();
+            self.provider
+                .raw_request::<_, serde_json::Value>(
+                    "anvil_setBalance".into(),
+                    (signer_address(), balance + U256::from(amount)),
+                )
+                .await
+                .unwrap();
+
+            let token = IWETH9::new(token, self.provider.clone());
+            token
+                .deposit()
+                .value(U256::from(amount))
+                .send()
+                .await
+                .unwrap()
+                .get_receipt()
+                .await
+                .unwrap();
+
+            if recipient != signer_address() {
+                token
+                    .transfer(recipient, U256::from(amount))
+                    .send()
+                    .await
+                    .unwrap()
+                    .get_receipt()
+                    .await
+                    .unwrap();
+            }
+        }
+
+        /// Creates the pair with the factory and adds liquidity to it
+        async fn add_pair(
+            &self,
+            token_a: Address,
+            token_b: Address,
+            reserve_a: u128,
+            reserve_b: u128,
+        ) -> Address {
+            let factory = IUniswapV2Factory::new(self.factory, self.provider.clone());
+            factory
+                .createPair(token_a, token_b)
+                .send()
+                .await
+                .unwrap()
+                .get_receipt()
+                .await
+                .unwrap();
+            let pair = factory.getPair(token_a, token_b).call().await.unwrap();
+
+            self.fund(token_a, pair, reserve_a).await;
+            self.fund(token_b, pair, reserve_b).await;
+
+            IUniswapV2Pair::new(pair, self.provider.clone())
+                .mint(signer_address())
+                .send()
+                .await
+                .unwrap()
+                .get_receipt()
+                .await
+                .unwrap();
+
+            pair
+        }
+
+        async fn execute(&self, calls: Vec<Call>) {
+            for call in calls {
+                let receipt = self
+                    .provider
+                    .send_transaction(
+                        TransactionRequest::default()
+                            .with_to(call.to)
+                            .with_value(call.value)
+                            .with_input(call.data),
+                    )
+                    .await
+                    .unwrap()
+                    .get_receipt()
+                    .await
+                    .unwrap();
+                assert!(receipt.status());
+            }
+        }
+
+        async fn token_balance(&self, token: Address, owner: Address) -> U256 {
+            IWETH9::new(token, self.provider.clone())
+                .balanceOf(owner)
+                .call()
+                .await
+                .unwrap()
+        }
+    }
+
+    async fn set_code(provider: &DynProvider, address: Address, code: &Bytes) {
+        provider
+            .raw_request::<_, serde_json::Value>("anvil_setCode".into(), (address, code))
+            .await
+            .unwrap();
+    }
+
+    fn random_token() -> Address {
+        Address::from(rand::random::<[u8; 20]>())
+    }
+
+    fn ether(amount: u128) -> u128 {
+        amount * 10u128.pow(18)
+    }
+
+    fn route(path: Vec<Address>, reserves: Vec<(u128, u128)>) -> Route {
+        Route {
+            path,
+            reserves: reserves
+                .into_iter()
+                .map(|(reserve_in, reserve_out)| (U256::from(reserve_in), U256::from(reserve_out)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_get_amount_out() {
+        assert_eq!(
+            get_amount_out(
+                U256::from(ether(1)),
+                U256::from(ether(100)),
+                U256::from(ether(100)),
+                FEE
+            ),
+            Some(U256::from(987158034397061298u128))
+        );
+    }
+
+    #[test]
+    fn test_get_amount_in() {
+        assert_eq!(
+            get_amount_in(
+                U256::from(ether(1)),
+                U256::from(ether(100)),
+                U256::from(ether(100)),
+                FEE
+            ),
+            Some(U256::from(1013140431395195689u128))
+        );
+    }
+
+    #[test]
+    fn test_amounts_match_router() {
+        // The router hardcodes its 0.3% fee as 997 / 1000
+        for (amount, reserve_in, reserve_out) in [
+            (1u128, 1_000u128, 1_000u128),
+            (12_345, 987_654_321, 123_456_789),
+            (ether(3), ether(1_234), 5_678_000_000),
+            (999_999_999, 1_000_000_000_000, ether(42)),
+        ] {
+            let (amount, reserve_in, reserve_out) = (
+                U256::from(amount),
+                U256::from(reserve_in),
+                U256::from(reserve_out),
+            );
+
+            let expected_out = amount * U256::from(997) * reserve_out
+                / (reserve_in * U256::from(1000) + amount * U256::from(997));
+            assert_eq!(
+                get_amount_out(amount, reserve_in, reserve_out, FEE).unwrap_or_default(),
+                expected_out
+            );
+
+            if amount < reserve_out {
+                let expected_in = reserve_in * amount * U256::from(1000)
+                    / ((reserve_out - amount) * U256::from(997))
+                    + U256::from(1);
+                assert_eq!(
+                    get_amount_in(amount, reserve_in, reserve_out, FEE),
+                    Some(expected_in)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_amount_out_lower_fee() {
+        let (amount, reserve_in, reserve_out) = (
+            U256::from(ether(1)),
+            U256::from(ether(100)),
+            U256::from(ether(100)),
+        );
+
+        assert!(
+            get_amount_out(amount, reserve_in, reserve_out, 25).unwrap()
+                > get_amount_out(amount, reserve_in, reserve_out, FEE).unwrap()
+        );
+        assert!(
+            get_amount_in(amount, reserve_in, reserve_out, 25).unwrap()
+                < get_amount_in(amount, reserve_in, reserve_out, FEE).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_amount_no_liquidity() {
+        let one = U256::from(1);
+        let hundred = U256::from(100);
+
+        assert_eq!(get_amount_out(U256::ZERO, hundred, hundred, FEE), None);
+        assert_eq!(get_amount_out(one, U256::ZERO, hundred, FEE), None);
+        assert_eq!(get_amount_out(one, hundred, U256::ZERO, FEE), None);
+        // Rounds down to nothing
+        assert_eq!(get_amount_out(one, hundred, hundred, FEE), None);
+        assert_eq!(get_amount_out(U256::MAX, hundred, hundred, FEE), None);
+
+        assert_eq!(get_amount_in(U256::ZERO, hundred, hundred, FEE), None);
+        assert_eq!(get_amount_in(one, U256::ZERO, hundred, FEE), None);
+        assert_eq!(get_amount_in(hundred, hundred, hundred, FEE), None);
+    }
+
+    #[test]
+    fn test_route_amounts() {
+        let route = route(
+            vec![random_token(), random_token(), random_token()],
+            vec![(ether(100), ether(200)), (ether(50), ether(10))],
+        );
+
+        let intermediate = get_amount_out(
+            U256::from(ether(1)),
+            U256::from(ether(100)),
+            U256::from(ether(200)),
+            FEE,
+        )
+        .unwrap();
+        let amount_out = route.amount_out(U256::from(ether(1)), FEE).unwrap();
+        assert_eq!(
+            amount_out,
+            get_amount_out(
+                intermediate,
+                U256::from(ether(50)),
+                U256::from(ether(10)),
+                FEE
+            )
+            .unwrap()
+        );
+
+        // Rounding up when going backwards needs at least the original input
+        let amount_in = route.amount_in(amount_out, FEE).unwrap();
+        assert!(amount_in <= U256::from(ether(1)));
+        assert!(route.amount_out(amount_in, FEE).unwrap() >= amount_out);
+
+        assert_eq!(route.amount_in(U256::from(ether(10)), FEE), None);
+    }
+
+    #[test]
+    fn test_data_normalize() {
+        let weth = random_token();
+        let token = random_token();
+
+        let data = Data {
+            path: vec![weth, random_token(), token],
+        }
+        .normalize(Address::ZERO, token);
+
+        assert_eq!(data.path[0], Address::ZERO);
+        assert_eq!(data.path[2], token);
+    }
+
+    #[test]
+    fn test_config_default_fee() {
+        let config: Config = serde_json::from_str(
+            r#"{"factory":"0x0000000000000000000000000000000000000001","router":"0x0000000000000000000000000000000000000002"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.fee, FEE);
+    }
+
+    #[test]
+    fn test_encode_tokens() {
+        let weth = random_token();
+        let router_address = random_token();
+        let (token_in, token_out, recipient) = (random_token(), random_token(), random_token());
+
+        let calls = Router::new(weth, router_address)
+            .encode(
+                Data {
+                    path: vec![token_in, weth, token_out],
+                },
+                recipient,
+                U256::from(100),
+                U256::from(90),
+            )
+            .unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, token_in);
+        assert_eq!(calls[0].value, U256::ZERO);
+        let approve = router::IERC20::approveCall::abi_decode(&calls[0].data).unwrap();
+        assert_eq!(approve.spender, router_address);
+        assert_eq!(approve.value, U256::from(100));
+
+        assert_eq!(calls[1].to, router_address);
+        assert_eq!(calls[1].value, U256::ZERO);
+        let swap =
+            IUniswapV2Router02::swapExactTokensForTokensCall::abi_decode(&calls[1].data).unwrap();
+        assert_eq!(swap.amountIn, U256::from(100));
+        assert_eq!(swap.amountOutMin, U256::from(90));
+        assert_eq!(swap.path, vec![token_in, weth, token_out]);
+        assert_eq!(swap.to, recipient);
+    }
+
+    #[test]
+    fn test_encode_ether_in() {
+        let weth = random_token();
+        let router_address = random_token();
+        let (token_out, recipient) = (random_token(), random_token());
+
+        let calls = Router::new(weth, router_address)
+            .encode(
+                Data {
+                    path: vec![Address::ZERO, token_out],
+                },
+                recipient,
+                U256::from(100),
+                U256::from(90),
+            )
+            .unwrap();
+
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].to, router_address);
+        assert_eq!(calls[0].value, U256::from(100));
+        let swap =
+            IUniswapV2Router02::swapExactETHForTokensCall::abi_decode(&calls[0].data).unwrap();
+        assert_eq!(swap.amountOutMin, U256::from(90));
+        assert_eq!(swap.path, vec![weth, token_out]);
+        assert_eq!(swap.to, recipient);
+    }
+
+    #[test]
+    fn test_encode_ether_out() {
+        let weth = random_token();
+        let router_address = random_token();
+        let (token_in, recipient) = (random_token(), random_token());
+
+        let calls = Router::new(weth, router_address)
+            .encode(
+                Data {
+                    path: vec![token_in, Address::ZERO],
+                },
+                recipient,
+                U256::from(100),
+                U256::from(90),
+            )
+            .unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, token_in);
+        let swap =
+            IUniswapV2Router02::swapExactTokensForETHCall::abi_decode(&calls[1].data).unwrap();
+        assert_eq!(swap.amountIn, U256::from(100));
+        assert_eq!(swap.path, vec![token_in, weth]);
+        assert_eq!(swap.to, recipient);
+    }
+
+    #[test]
+    fn test_encode_invalid_path() {
+        let err = Router::new(random_token(), random_token())
+            .encode(
+                Data {
+                    path: vec![random_token()],
+                },
+                random_token(),
+                U256::from(100),
+                U256::from(90),
+            )
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "path needs at least two tokens");
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_new_invalid_fee() {
+        let setup = Setup::new().await;
+
+        let err = UniswapV2::new(
+            "ETH".to_string(),
+            Cache::Memory(MemCache::new()),
+            setup.provider.clone(),
+            setup.weth,
+            Config {
+                factory: setup.factory,
+                router: setup.router,
+                fee: FEE_DENOMINATOR,
+            },
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(err.to_string(), "fee must be less than 10000 basis points");
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_quote_input() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+        setup
+            .add_pair(token_in, token_out, ether(100), ether(300))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let amount_in = U256::from(ether(1));
+
+        let (quote, data) = quoter
+            .quote_input(token_in, token_out, amount_in)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            quote,
+            get_amount_out(
+                amount_in,
+                U256::from(ether(100)),
+                U256::from(ether(300)),
+                FEE
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: vec![token_in, token_out],
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_quote_output() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+        setup
+            .add_pair(token_in, token_out, ether(100), ether(300))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let amount_out = U256::from(ether(3));
+
+        let (quote, data) = quoter
+            .quote_output(token_in, token_out, amount_out)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            quote,
+            get_amount_in(
+                amount_out,
+                U256::from(ether(100)),
+                U256::from(ether(300)),
+                FEE
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: vec![token_in, token_out],
+            })
+        );
+
+        let (roundtrip, _) = quoter
+            .quote_input(token_in, token_out, quote)
+            .await
+            .unwrap();
+        assert!(roundtrip >= amount_out);
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_quote_through_weth() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+        setup
+            .add_pair(token_in, setup.weth, ether(1_000), ether(10))
+            .await;
+        setup
+            .add_pair(setup.weth, token_out, ether(10), ether(30_000))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let expected_route = route(
+            vec![token_in, setup.weth, token_out],
+            vec![(ether(1_000), ether(10)), (ether(10), ether(30_000))],
+        );
+
+        let amount_in = U256::from(ether(5));
+        let (quote, data) = quoter
+            .quote_input(token_in, token_out, amount_in)
+            .await
+            .unwrap();
+        assert_eq!(quote, expected_route.amount_out(amount_in, FEE).unwrap());
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: expected_route.path.clone(),
+            })
+        );
+
+        let amount_out = U256::from(ether(100));
+        let (quote, data) = quoter
+            .quote_output(token_in, token_out, amount_out)
+            .await
+            .unwrap();
+        assert_eq!(quote, expected_route.amount_in(amount_out, FEE).unwrap());
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: expected_route.path,
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_quote_select_best() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+
+        // The direct pair is shallow, so routing through WETH is cheaper
+        setup
+            .add_pair(token_in, token_out, ether(10), ether(10))
+            .await;
+        setup
+            .add_pair(token_in, setup.weth, ether(10_000), ether(5_000))
+            .await;
+        setup
+            .add_pair(setup.weth, token_out, ether(5_000), ether(10_000))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let through_weth = vec![token_in, setup.weth, token_out];
+
+        let (_, data) = quoter
+            .quote_input(token_in, token_out, U256::from(ether(5)))
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: through_weth.clone(),
+            })
+        );
+
+        let (_, data) = quoter
+            .quote_output(token_in, token_out, U256::from(ether(5)))
+            .await
+            .unwrap();
+        assert_eq!(data, QuoterData::UniswapV2(Data { path: through_weth }));
+
+        // Tiny amounts lose less to the extra hop fee on the direct pair
+        let (_, data) = quoter
+            .quote_input(token_in, token_out, U256::from(1_000_000))
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: vec![token_in, token_out],
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_quote_ether() {
+        let setup = Setup::new().await;
+        let token = setup.token().await;
+        setup
+            .add_pair(setup.weth, token, ether(10), ether(20))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+
+        let (quote, data) = quoter
+            .quote_input(Address::ZERO, token, U256::from(ether(1)))
+            .await
+            .unwrap();
+        assert!(!quote.is_zero());
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: vec![Address::ZERO, token],
+            })
+        );
+
+        let (quote, data) = quoter
+            .quote_input(token, Address::ZERO, U256::from(ether(1)))
+            .await
+            .unwrap();
+        assert!(!quote.is_zero());
+        assert_eq!(
+            data,
+            QuoterData::UniswapV2(Data {
+                path: vec![token, Address::ZERO],
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_quote_fail() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+        setup
+            .add_pair(token_in, token_out, ether(100), ether(300))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+
+        let err = quoter
+            .quote_input(random_token(), random_token(), U256::from(ether(1)))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "no results");
+
+        // More than the pair holds
+        let err = quoter
+            .quote_output(token_in, token_out, U256::from(ether(300)))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "no results");
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_execute_tokens() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+        setup
+            .add_pair(token_in, setup.weth, ether(1_000), ether(10))
+            .await;
+        setup
+            .add_pair(setup.weth, token_out, ether(10), ether(30_000))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let amount_in = U256::from(ether(5));
+        let (quote, data) = quoter
+            .quote_input(token_in, token_out, amount_in)
+            .await
+            .unwrap();
+
+        setup.fund(token_in, signer_address(), ether(5)).await;
+
+        let recipient = random_token();
+        setup
+            .execute(quoter.encode(data, recipient, amount_in, quote).unwrap())
+            .await;
+
+        assert_eq!(setup.token_balance(token_out, recipient).await, quote);
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_execute_quote_output() {
+        let setup = Setup::new().await;
+        let (token_in, token_out) = (setup.token().await, setup.token().await);
+        setup
+            .add_pair(token_in, token_out, ether(100), ether(300))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let amount_out = U256::from(ether(3));
+        let (quote, data) = quoter
+            .quote_output(token_in, token_out, amount_out)
+            .await
+            .unwrap();
+
+        setup
+            .fund(token_in, signer_address(), quote.to::<u128>())
+            .await;
+
+        let recipient = random_token();
+        setup
+            .execute(quoter.encode(data, recipient, quote, amount_out).unwrap())
+            .await;
+
+        assert!(setup.token_balance(token_out, recipient).await >= amount_out);
+        assert_eq!(
+            setup.token_balance(token_in, signer_address()).await,
+            U256::ZERO
+        );
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_execute_ether_in() {
+        let setup = Setup::new().await;
+        let token = setup.token().await;
+        setup
+            .add_pair(setup.weth, token, ether(10), ether(20))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let amount_in = U256::from(ether(1));
+        let (quote, data) = quoter
+            .quote_input(Address::ZERO, token, amount_in)
+            .await
+            .unwrap();
+
+        let recipient = random_token();
+        setup
+            .execute(quoter.encode(data, recipient, amount_in, quote).unwrap())
+            .await;
+
+        assert_eq!(setup.token_balance(token, recipient).await, quote);
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_execute_ether_out() {
+        let setup = Setup::new().await;
+        let token = setup.token().await;
+        setup
+            .add_pair(token, setup.weth, ether(20), ether(10))
+            .await;
+
+        let quoter = setup.quoter(Cache::Memory(MemCache::new())).await;
+        let amount_in = U256::from(ether(1));
+        let (quote, data) = quoter
+            .quote_input(token, Address::ZERO, amount_in)
+            .await
+            .unwrap();
+
+        setup.fund(token, signer_address(), ether(1)).await;
+
+        let recipient = random_token();
+        setup
+            .execute(quoter.encode(data, recipient, amount_in, quote).unwrap())
+            .await;
+
+        assert_eq!(setup.provider.get_balance(recipient).await.unwrap(), quote);
+    }
+
+    #[tokio::test]
+    #[serial(evm)]
+    async fn test_lookup_pair_cached() {
+        let setup = Setup::new().await;
+        let (token_a, token_b) = (setup.token().await, setup.token().await);
+        let pair = setup.add_pair(token_a, token_b, ether(1), ether(1)).await;
+
+        let cache = Cache::Memory(MemCache::new());
+        let quoter = setup.quoter(cache.clone()).await;
+
+        assert_eq!(
+            quoter.lookup_pair(token_a, token_b).await.unwrap(),
+            Some(pair)
+        );
+        assert_eq!(quoter.lookup_pair(token_a, setup.weth).await.unwrap(), None);
+
+        let (cache_key, cache_field) = quoter.cache_key_pairs(token_b, token_a);
+        assert_eq!(
+            cache
+                .get::<Address>(&cache_key, &cache_field)
+                .await
+                .unwrap(),
+            Some(pair)
+        );
+        let (cache_key, cache_field) = quoter.cache_key_pairs(token_a, setup.weth);
+        assert_eq!(
+            cache
+                .get::<Address>(&cache_key, &cache_field)
+                .await
+                .unwrap(),
+            Some(Address::ZERO)
+        );
+    }
+}
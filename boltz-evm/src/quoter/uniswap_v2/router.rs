@@ -0,0 +1,107 @@
+use crate::quoter::Call;
+use crate::quoter::uniswap_v2::Data;
+use alloy::primitives::{Address, U256};
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use anyhow::Result;
+
+sol!("./src/quoter/uniswap_v2/abis/IUniswapV2Router02.sol");
+
+sol!("./src/quoter/uniswap_v3/abis/IERC20.sol");
+
+#[derive(Debug, Clone)]
+pub struct Router {
+    weth: Address,
+    router: Address,
+}
+
+impl Router {
+    pub fn new(weth: Address, router: Address) -> Self {
+        Self { weth, router }
+    }
+
+    pub fn encode(
+        &self,
+        data: Data,
+        recipient: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+    ) -> Result<Vec<Call>> {
+        if data.path.len() < 2 {
+            return Err(anyhow::anyhow!("path needs at least two tokens"));
+        }
+
+        let token_in = data.path[0];
+        let is_ether_out = data.path[data.path.len() - 1] == Address::ZERO;
+        let path = data
+            .path
+            .iter()
+            .map(|token| self.handle_eth(*token))
+            .collect::<Vec<_>>();
+
+        // The amount out minimum already protects the swap, so the router's
+        // deadline is not needed
+        let deadline = U256::MAX;
+
+        if token_in == Address::ZERO {
+            return Ok(vec![Call {
+                to: self.router,
+                value: amount_in,
+                data: IUniswapV2Router02::swapExactETHForTokensCall {
+                    amountOutMin: amount_out_min,
+                    path,
+                    to: recipient,
+                    deadline,
+                }
+                .abi_encode(),
+            }]);
+        }
+
+        let swap = if is_ether_out {
+            IUniswapV2Router02::swapExactTokensForETHCall {
+                amountIn: amount_in,
+                amountOutMin: amount_out_min,
+                path,
+                to: recipient,
+                deadline,
+            }
+            .abi_encode()
+        } else {
+            IUniswapV2Router02::swapExactTokensForTokensCall {
+                amountIn: amount_in,
+                amountOutMin: amount_out_min,
+                path,
+                to: recipient,
+                deadline,
+            }
+            .abi_encode()
+        };
+
+        Ok(vec![
+            // The router pulls the input tokens with transferFrom
+            Call {
+                to: token_in,
+                value: U256::ZERO,
+                data: IERC20::approveCall {
+                    spender: self.router,
+                    value: amount_in,
+                }
+                .abi_encode(),
+            },
+            Call {
+                to: self.router,
+                value: U256::ZERO,
+                data: swap,
+            },
+        ])
+    }
+
+    pub fn handle_eth(&self, token: Address) -> Address {
+        // Uniswap V2 pairs only hold wrapped native tokens
+        if token == Address::ZERO {
+            return self.weth;
+        }
+
+        token
+    }
+}
@@ -1,7 +1,7 @@
 use crate::quoter::uniswap_v3::IQuoterV2::IQuoterV2Instance;
 use crate::quoter::uniswap_v3::IUniswapV3Factory::IUniswapV3FactoryInstance;
 use crate::quoter::uniswap_v3::router::PathEncoder;
-use crate::quoter::{Call, Data as QuoterData, Quoter, QuoterType};
+use crate::quoter::{Call, Data as QuoterData, ERROR_NO_RESULTS, Quoter, QuoterType, RouteQuotes};
 use crate::utils::check_contract_exists;
 use alloy::primitives::aliases::U24;
 use alloy::primitives::{Address, U256, address};
@@ -80,8 +80,6 @@ impl<'de> Deserialize<'de> for TokenPair {
 
 type RelevantPools = HashMap<TokenPair, Vec<u64>>;
 
-const ERROR_NO_RESULTS: &str = "no results";
-
 const FEE_OPTIONS: [u64; 4] = [100, 500, 3_000, 10_000];
 const DEFAULT_MULTICALL: Address = address!("0xcA11bde05977b3631167028862bE2a173976CA11");
 
@@ -371,13 +369,13 @@ where
         QuoterType::UniswapV3
     }
 
-    #[instrument(name = "UniswapV3::quote_input", skip_all)]
-    async fn quote_input(
+    #[instrument(name = "UniswapV3::quote_input_routes", skip_all)]
+    async fn quote_input_routes(
         &self,
         token_in: Address,
         token_out: Address,
-        amount_in: U256,
-    ) -> Result<(U256, QuoterData)> {
+        amounts_in: &[U256],
+    ) -> Result<Vec<RouteQuotes>> {
         let routes = self
             .lookup_and_route(
                 self.router.handle_eth(token_in),
@@ -395,32 +393,38 @@ where
                 .add_token(route.token_in)
                 .add_hops(&route.hops)?
                 .build();
-            multicall = multicall.add_call_dynamic(
-                CallItemBuilder::new(self.quoter.quoteExactInput(path.into(), amount_in))
+            for amount_in in amounts_in {
+                multicall = multicall.add_call_dynamic(
+                    CallItemBuilder::new(
+                        self.quoter.quoteExactInput(path.clone().into(), *amount_in),
+                    )
                     .allow_failure(true),
-            );
+                );
+            }
         }
 
-        let (data, amount_out) = routes
+        let mut results = multicall.aggregate3().await?.into_iter();
+
+        Ok(routes
             .into_iter()
-            .zip(multicall.aggregate3().await?.into_iter())
-            .filter_map(|(data, result)| result.ok().map(|quote| (data, quote.amountOut)))
-            .max_by_key(|(_, amount_out)| *amount_out)
-            .ok_or_else(|| anyhow::anyhow!(ERROR_NO_RESULTS))?;
-
-        Ok((
-            amount_out,
-            QuoterData::UniswapV3(data.normalize(token_in, token_out)),
-        ))
+            .map(|data| RouteQuotes {
+                quotes: results
+                    .by_ref()
+                    .take(amounts_in.len())
+                    .map(|result| result.ok().map(|quote| quote.amountOut))
+                    .collect(),
+                data: QuoterData::UniswapV3(data.normalize(token_in, token_out)),
+            })
+            .collect())
     }
 
-    #[instrument(name = "UniswapV3::quote_output", skip_all)]
-    async fn quote_output(
+    #[instrument(name = "UniswapV3::quote_output_routes", skip_all)]
+    async fn quote_output_routes(
         &self,
         token_in: Address,
         token_out: Address,
-        amount_out: U256,
-    ) -> Result<(U256, QuoterData)> {
+        amounts_out: &[U256],
+    ) -> Result<Vec<RouteQuotes>> {
         let routes = self
             .lookup_and_route(
                 self.router.handle_eth(token_in),
@@ -440,23 +444,30 @@ where
                 .add_token(reverse.token_in)
                 .add_hops(&reverse.hops)?
                 .build();
-            multicall = multicall.add_call_dynamic(
-                CallItemBuilder::new(self.quoter.quoteExactOutput(path.into(), amount_out))
+            for amount_out in amounts_out {
+                multicall = multicall.add_call_dynamic(
+                    CallItemBuilder::new(
+                        self.quoter
+                            .quoteExactOutput(path.clone().into(), *amount_out),
+                    )
                     .allow_failure(true),
-            );
+                );
+            }
         }
 
-        let (data, amount_in) = routes
+        let mut results = multicall.aggregate3().await?.into_iter();
+
+        Ok(routes
             .into_iter()
-            .zip(multicall.aggregate3().await?.into_iter())
-            .filter_map(|(data, result)| result.ok().map(|quote| (data, quote.amountIn)))
-            .min_by_key(|(_, amount_in)| *amount_in)
-            .ok_or_else(|| anyhow::anyhow!(ERROR_NO_RESULTS))?;
-
-        Ok((
-            amount_in,
-            QuoterData::UniswapV3(data.normalize(token_in, token_out)),
-        ))
+            .map(|data| RouteQuotes {
+                quotes: results
+                    .by_ref()
+                    .take(amounts_out.len())
+                    .map(|result| result.ok().map(|quote| quote.amountIn))
+                    .collect(),
+                data: QuoterData::UniswapV3(data.normalize(token_in, token_out)),
+            })
+            .collect())
     }
 
     fn encode(
@@ -466,7 +477,6 @@ where
         amount_in: U256,
         amount_out_min: U256,
     ) -> Result<Vec<Call>> {
-        #[allow(irrefutable_let_patterns)]
         let QuoterData::UniswapV3(data) = data else {
             return Err(anyhow::anyhow!("unsupported data"));
         };
@@ -677,14 +687,13 @@ mod test {
             .unwrap();
 
         assert!(!quote.is_zero());
-        match data {
-            QuoterData::UniswapV3(Data { token_in, hops }) => {
-                assert_eq!(token_in, Address::from_str(WETH).unwrap());
-                assert_eq!(hops.len(), 1);
-                assert_eq!(hops[0].token, Address::from_str(USDT).unwrap());
-                assert!(hops[0].fee > 0);
-            }
+        let QuoterData::UniswapV3(Data { token_in, hops }) = data else {
+            panic!("unexpected quoter data");
         };
+        assert_eq!(token_in, Address::from_str(WETH).unwrap());
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].token, Address::from_str(USDT).unwrap());
+        assert!(hops[0].fee > 0);
     }
 
     #[tokio::test]
@@ -809,14 +818,13 @@ mod test {
             .unwrap();
 
         assert!(!quote.is_zero());
-        match data {
-            QuoterData::UniswapV3(Data { token_in, hops }) => {
-                assert_eq!(token_in, Address::from_str(WETH).unwrap());
-                assert_eq!(hops.len(), 1);
-                assert_eq!(hops[0].token, Address::from_str(USDT).unwrap());
-                assert!(hops[0].fee > 0);
-            }
+        let QuoterData::UniswapV3(Data { token_in, hops }) = data else {
+            panic!("unexpected quoter data");
         };
+        assert_eq!(token_in, Address::from_str(WETH).unwrap());
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].token, Address::from_str(USDT).unwrap());
+        assert!(hops[0].fee > 0);
     }
 
     #[tokio::test]
@@ -1,7 +1,6 @@
-use alloy::dyn_abi::Eip712Domain;
+use alloy::dyn_abi::{Eip712Domain, TypedData};
 use alloy::primitives::FixedBytes;
 use alloy::signers::{Signature, Signer};
-use alloy::sol_types::SolStruct;
 use anyhow::Result;
 
 use crate::{SwapType, SwapValues, ensure_supported_version};
@@ -10,6 +9,7 @@ mod ether_v4 {
     use alloy::sol;
 
     sol!(
+        #[derive(serde::Serialize)]
         struct Refund {
             bytes32 preimageHash;
             uint256 amount;
@@ -23,6 +23,7 @@ mod ether_current {
     use alloy::sol;
 
     sol!(
+        #[derive(serde::Serialize)]
         struct Refund {
             bytes32 preimageHash;
             uint256 amount;
@@ -36,6 +37,7 @@ mod erc20_v4 {
     use alloy::sol;
 
     sol!(
+        #[derive(serde::Serialize)]
         struct Refund {
             bytes32 preimageHash;
             uint256 amount;
@@ -50,6 +52,7 @@ mod erc20_current {
     use alloy::sol;
 
     sol!(
+        #[derive(serde::Serialize)]
         struct Refund {
             bytes32 preimageHash;
             uint256 amount;
@@ -60,31 +63,37 @@ mod erc20_current {
     );
 }
 
-pub fn hash(
+/// EIP-712 typed data of a refund, as it is signed with `eth_signTypedData`
+pub fn typed_data(
     contract_version: u8,
     domain: &Eip712Domain,
     values: &SwapValues,
-) -> Result<FixedBytes<32>> {
+) -> Result<TypedData> {
     ensure_supported_version(contract_version)?;
 
-    let digest = match values.swap_type {
+    let domain = Some(domain.clone());
+    let typed_data = match values.swap_type {
         SwapType::Ether => {
             if contract_version <= 4 {
-                ether_v4::Refund {
-                    preimageHash: values.preimage_hash,
-                    amount: values.amount,
-                    claimAddress: values.claim_address,
-                    timeout: values.timelock,
-                }
-                .eip712_signing_hash(domain)
+                TypedData::from_struct(
+                    &ether_v4::Refund {
+                        preimageHash: values.preimage_hash,
+                        amount: values.amount,
+                        claimAddress: values.claim_address,
+                        timeout: values.timelock,
+                    },
+                    domain,
+                )
             } else {
-                ether_current::Refund {
-                    preimageHash: values.preimage_hash,
-                    amount: values.amount,
-                    claimAddress: values.claim_address,
-                    timelock: values.timelock,
-                }
-                .eip712_signing_hash(domain)
+                TypedData::from_struct(
+                    &ether_current::Refund {
+                        preimageHash: values.preimage_hash,
+                        amount: values.amount,
+                        claimAddress: values.claim_address,
+                        timelock: values.timelock,
+                    },
+                    domain,
+                )
             }
         }
         SwapType::ERC20 => {
@@ -93,28 +102,40 @@ pub fn hash(
                 .ok_or_else(|| anyhow::anyhow!("token address is required for ERC20 refunds"))?;
 
             if contract_version <= 4 {
-                erc20_v4::Refund {
-                    preimageHash: values.preimage_hash,
-                    amount: values.amount,
-                    tokenAddress: token_address,
-                    claimAddress: values.claim_address,
-                    timeout: values.timelock,
-                }
-                .eip712_signing_hash(domain)
+                TypedData::from_struct(
+                    &erc20_v4::Refund {
+                        preimageHash: values.preimage_hash,
+                        amount: values.amount,
+                        tokenAddress: token_address,
+                        claimAddress: values.claim_address,
+                        timeout: values.timelock,
+                    },
+                    domain,
+                )
             } else {
-                erc20_current::Refund {
-                    preimageHash: values.preimage_hash,
-                    amount: values.amount,
-                    tokenAddress: token_address,
-                    claimAddress: values.claim_address,
-                    timelock: values.timelock,
-                }
-                .eip712_signing_hash(domain)
+                TypedData::from_struct(
+                    &erc20_current::Refund {
+                        preimageHash: values.preimage_hash,
+                        amount: values.amount,
+                        tokenAddress: token_address,
+                        claimAddress: values.claim_address,
+                        timelock: values.timelock,
+                    },
+                    domain,
+                )
             }
         }
     };
 
-    Ok(digest)
+    Ok(typed_data)
+}
+
+pub fn hash(
+    contract_version: u8,
+    domain: &Eip712Domain,
+    values: &SwapValues,
+) -> Result<FixedBytes<32>> {
+    Ok(typed_data(contract_version, domain, values)?.eip712_signing_hash()?)
 }
 
 pub async fn sign(
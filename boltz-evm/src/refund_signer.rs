@@ -1,7 +1,8 @@
 use crate::contracts::erc20_swap::ERC20SwapContract;
 use crate::contracts::ether_swap::EtherSwapContract;
 use crate::contracts::{SwapContract, erc20_swap, ether_swap};
-use crate::{MAX_CONTRACT_VERSION, MIN_CONTRACT_VERSION};
+use crate::{MAX_CONTRACT_VERSION, MIN_CONTRACT_VERSION, SwapType, SwapValues};
+use alloy::dyn_abi::Eip712Domain;
 use alloy::primitives::{Address, FixedBytes, Signature, U256};
 use alloy::providers::DynProvider;
 use alloy::providers::network::AnyNetwork;
@@ -60,6 +61,33 @@ impl LocalRefundSigner {
         self.version
     }
 
+    /// EIP-712 domain and values of a cooperative refund to `claim_address`
+    pub fn refund_values(
+        &self,
+        preimage_hash: FixedBytes<32>,
+        amount: U256,
+        token_address: Option<Address>,
+        claim_address: Address,
+        timeout: u64,
+    ) -> (&Eip712Domain, SwapValues) {
+        let (domain, swap_type) = match token_address {
+            Some(_) => (self.erc20_swap.eip712_domain(), SwapType::ERC20),
+            None => (self.ether_swap.eip712_domain(), SwapType::Ether),
+        };
+
+        (
+            domain,
+            SwapValues {
+                swap_type,
+                preimage_hash,
+                amount,
+                token_address,
+                claim_address,
+                timelock: U256::from(timeout),
+            },
+        )
+    }
+
     pub async fn sign(
         &self,
         signer: &PrivateKeySigner,
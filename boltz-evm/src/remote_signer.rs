@@ -0,0 +1,589 @@
+use crate::{SwapType, SwapValues};
+use alloy::dyn_abi::{Eip712Domain, TypedData};
+use alloy::primitives::{Address, Signature, U256};
+use anyhow::{Result, anyhow};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, info, instrument};
+
+const fn default_timeout() -> u64 {
+    5
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct Config {
+    /// JSON-RPC endpoint of a signing service that supports `eth_signTypedData` like Web3Signer
+    pub endpoint: String,
+
+    /// Address of the key of the signing service; signatures by any other key are rejected
+    pub address: String,
+
+    /// Seconds after which requests to the signing service are aborted
+    #[serde(rename = "timeout", default = "default_timeout")]
+    pub timeout: u64,
+
+    /// Contracts for which signatures may be requested; all contracts when unset
+    #[serde(rename = "allowedContracts")]
+    pub allowed_contracts: Option<Vec<String>>,
+
+    /// Maximal amount per token address that may be signed for, with the zero address
+    /// for the native currency; tokens without an entry are rejected.
+    /// No limits are enforced when unset
+    #[serde(rename = "maxAmounts")]
+    pub max_amounts: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Policy {
+    contracts: Option<HashSet<Address>>,
+    max_amounts: Option<HashMap<Address, U256>>,
+}
+
+impl Policy {
+    fn new(config: &Config) -> Result<Self> {
+        let contracts = config
+            .allowed_contracts
+            .as_ref()
+            .map(|contracts| {
+                contracts
+                    .iter()
+                    .map(|address| {
+                        Address::from_str(address)
+                            .map_err(|err| anyhow!("invalid allowed contract {}: {}", address, err))
+                    })
+                    .collect::<Result<HashSet<_>>>()
+            })
+            .transpose()?;
+
+        let max_amounts = config
+            .max_amounts
+            .as_ref()
+            .map(|amounts| {
+                amounts
+                    .iter()
+                    .map(|(token, amount)| {
+                        let token = Address::from_str(token)
+                            .map_err(|err| anyhow!("invalid token address {}: {}", token, err))?;
+                        let amount = U256::from_str(amount)
+                            .map_err(|err| anyhow!("invalid max amount for {}: {}", token, err))?;
+                        Ok((token, amount))
+                    })
+                    .collect::<Result<HashMap<_, _>>>()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            contracts,
+            max_amounts,
+        })
+    }
+
+    fn check(&self, domain: &Eip712Domain, values: &SwapValues) -> Result<()> {
+        let contract = domain
+            .verifying_contract
+            .ok_or_else(|| anyhow!("EIP-712 domain has no verifying contract"))?;
+
+        if let Some(contracts) = &self.contracts
+            && !contracts.contains(&contract)
+        {
+            return Err(anyhow!("contract {} is not allowed", contract));
+        }
+
+        if let Some(max_amounts) = &self.max_amounts {
+            let token = match values.swap_type {
+                SwapType::Ether => Address::ZERO,
+                SwapType::ERC20 => values
+                    .token_address
+                    .ok_or_else(|| anyhow!("token address is required for ERC20 swaps"))?,
+            };
+
+            match max_amounts.get(&token) {
+                Some(max) if values.amount > *max => {
+                    return Err(anyhow!(
+                        "amount {} of {} exceeds maximum of {}",
+                        values.amount,
+                        token,
+                        max
+                    ));
+                }
+                Some(_) => {}
+                None => return Err(anyhow!("token {} is not allowed", token)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SignRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: (Address, &'a TypedData),
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    result: Option<String>,
+    error: Option<SignError>,
+}
+
+#[derive(Deserialize)]
+struct SignError {
+    message: String,
+}
+
+/// Signs the EIP-712 typed data of cooperative refunds with a key held by an external service
+pub struct RemoteSigner {
+    url: Url,
+    address: Address,
+    policy: Policy,
+    client: reqwest::Client,
+}
+
+impl RemoteSigner {
+    pub fn new(config: &Config) -> Result<Self> {
+        let address = Address::from_str(&config.address)
+            .map_err(|err| anyhow!("invalid remote signer address: {}", err))?;
+        let url = Url::parse(&config.endpoint)?;
+
+        let timeout = Duration::from_secs(config.timeout);
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .build()?;
+
+        info!("Using remote signer {} with address: {}", url, address);
+
+        Ok(Self {
+            url,
+            address,
+            policy: Policy::new(config)?,
+            client,
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    #[instrument(name = "RemoteSigner::sign_refund", skip_all)]
+    pub async fn sign_refund(
+        &self,
+        contract_version: u8,
+        domain: &Eip712Domain,
+        values: &SwapValues,
+    ) -> Result<Signature> {
+        self.policy.check(domain, values)?;
+        self.sign_typed_data(&crate::refund::typed_data(
+            contract_version,
+            domain,
+            values,
+        )?)
+        .await
+    }
+
+    async fn sign_typed_data(&self, typed_data: &TypedData) -> Result<Signature> {
+        let digest = typed_data.eip712_signing_hash()?;
+        debug!("Requesting signature for typed data with hash: {}", digest);
+
+        let res = self
+            .client
+            .post(self.url.clone())
+            .json(&SignRequest {
+                jsonrpc: "2.0",
+                id: 1,
+                method: "eth_signTypedData",
+                params: (self.address, typed_data),
+            })
+            .send()
+            .await
+            .map_err(|err| anyhow!("remote signer request failed: {}", err))?;
+
+        let status = res.status();
+        let body = res.text().await?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "remote signer returned {}: {}",
+                status,
+                body.trim()
+            ));
+        }
+
+        let signature = match serde_json::from_str::<SignResponse>(&body)
+            .map_err(|err| anyhow!("invalid response from remote signer: {}", err))?
+        {
+            SignResponse {
+                error: Some(error), ..
+            } => return Err(anyhow!("remote signer returned error: {}", error.message)),
+            SignResponse {
+                result: Some(signature),
+                ..
+            } => Signature::from_str(&signature)
+                .map_err(|err| anyhow!("invalid signature from remote signer: {}", err))?,
+            _ => return Err(anyhow!("remote signer returned no signature")),
+        };
+
+        let signer = signature.recover_address_from_prehash(&digest)?;
+        if signer != self.address {
+            return Err(anyhow!(
+                "remote signer signed with {} instead of {}",
+                signer,
+                self.address
+            ));
+        }
+
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::eip712_domain;
+    use alloy::primitives::FixedBytes;
+    use alloy::signers::SignerSync;
+    use alloy::signers::local::PrivateKeySigner;
+    use axum::Json;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const CONTRACT: Address = Address::repeat_byte(0xee);
+    const TOKEN: Address = Address::repeat_byte(0x44);
+
+    #[derive(Deserialize)]
+    struct ServerRequest {
+        method: String,
+        params: (Address, TypedData),
+    }
+
+    struct Server {
+        /// Address for which the server accepts requests
+        address: Address,
+        signer: PrivateKeySigner,
+        delay: Duration,
+        status: StatusCode,
+        requests: AtomicUsize,
+    }
+
+    async fn sign(
+        State(server): State<Arc<Server>>,
+        Json(request): Json<ServerRequest>,
+    ) -> (StatusCode, Json<serde_json::Value>) {
+        server.requests.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(server.delay).await;
+
+        if server.status != StatusCode::OK {
+            return (server.status, Json(serde_json::json!("signing failed")));
+        }
+
+        let (address, typed_data) = request.params;
+        if request.method != "eth_signTypedData" || address != server.address {
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": { "code": -32000, "message": "signer not found" },
+                })),
+            );
+        }
+
+        // Like Web3Signer, the signing service hashes the typed data itself
+        let signature = server
+            .signer
+            .sign_hash_sync(&typed_data.eip712_signing_hash().unwrap())
+            .unwrap();
+        (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": format!("0x{}", hex::encode(signature.as_bytes())),
+            })),
+        )
+    }
+
+    async fn start_server(
+        signer: PrivateKeySigner,
+        delay: Duration,
+        status: StatusCode,
+    ) -> (String, Arc<Server>) {
+        start_server_for(signer.address(), signer, delay, status).await
+    }
+
+    async fn start_server_for(
+        address: Address,
+        signer: PrivateKeySigner,
+        delay: Duration,
+        status: StatusCode,
+    ) -> (String, Arc<Server>) {
+        let server = Arc::new(Server {
+            address,
+            signer,
+            delay,
+            status,
+            requests: AtomicUsize::new(0),
+        });
+        let router = axum::Router::new()
+            .route("/", post(sign))
+            .with_state(server.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        (endpoint, server)
+    }
+
+    fn config(endpoint: String, address: Address) -> Config {
+        Config {
+            endpoint,
+            address: address.to_string(),
+            timeout: default_timeout(),
+            allowed_contracts: None,
+            max_amounts: None,
+        }
+    }
+
+    fn values(swap_type: SwapType, amount: u64) -> (Eip712Domain, SwapValues) {
+        (
+            eip712_domain(swap_type, 5, 31_337, CONTRACT).unwrap(),
+            SwapValues {
+                swap_type,
+                preimage_hash: FixedBytes::<32>::from([0x33; 32]),
+                amount: U256::from(amount),
+                token_address: if swap_type == SwapType::ERC20 {
+                    Some(TOKEN)
+                } else {
+                    None
+                },
+                claim_address: Address::repeat_byte(0x22),
+                timelock: U256::from(1234u64),
+            },
+        )
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config: Config =
+            serde_json::from_str(r#"{"endpoint": "http://127.0.0.1:9000", "address": "0x00"}"#)
+                .unwrap();
+        assert_eq!(config.timeout, 5);
+        assert_eq!(config.allowed_contracts, None);
+        assert_eq!(config.max_amounts, None);
+    }
+
+    #[test]
+    fn test_new_invalid_address() {
+        let res = RemoteSigner::new(&Config {
+            address: "invalid".to_string(),
+            ..config("http://127.0.0.1:9000".to_string(), Address::ZERO)
+        });
+        assert!(
+            res.err()
+                .unwrap()
+                .to_string()
+                .starts_with("invalid remote signer address")
+        );
+    }
+
+    #[test]
+    fn test_new_invalid_policy() {
+        let res = RemoteSigner::new(&Config {
+            max_amounts: Some(HashMap::from([(
+                Address::ZERO.to_string(),
+                "not a number".to_string(),
+            )])),
+            ..config("http://127.0.0.1:9000".to_string(), Address::ZERO)
+        });
+        assert!(
+            res.err()
+                .unwrap()
+                .to_string()
+                .starts_with("invalid max amount")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_refund() {
+        let key = PrivateKeySigner::random();
+        let (endpoint, _) = start_server(key.clone(), Duration::ZERO, StatusCode::OK).await;
+        let signer = RemoteSigner::new(&config(endpoint, key.address())).unwrap();
+
+        for swap_type in [SwapType::Ether, SwapType::ERC20] {
+            let (domain, values) = values(swap_type, 42);
+            let signature = signer.sign_refund(5, &domain, &values).await.unwrap();
+
+            assert_eq!(
+                signature,
+                crate::refund::sign(&key, 5, &domain, &values)
+                    .await
+                    .unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_pinned_address_mismatch() {
+        let key = PrivateKeySigner::random();
+        let pinned = PrivateKeySigner::random().address();
+
+        // The service signs with a different key than the one of the pinned address
+        let (endpoint, _) =
+            start_server_for(pinned, key.clone(), Duration::ZERO, StatusCode::OK).await;
+        let signer = RemoteSigner::new(&config(endpoint, pinned)).unwrap();
+
+        let (domain, values) = values(SwapType::Ether, 42);
+        assert_eq!(
+            signer
+                .sign_refund(5, &domain, &values)
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            format!(
+                "remote signer signed with {} instead of {}",
+                key.address(),
+                pinned
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_error_status() {
+        let key = PrivateKeySigner::random();
+        let (endpoint, _) = start_server(
+            key.clone(),
+            Duration::ZERO,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .await;
+        let signer = RemoteSigner::new(&config(endpoint, key.address())).unwrap();
+
+        let (domain, values) = values(SwapType::Ether, 42);
+        assert_eq!(
+            signer
+                .sign_refund(5, &domain, &values)
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            "remote signer returned 500 Internal Server Error: \"signing failed\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_unknown_address() {
+        let key = PrivateKeySigner::random();
+        let (endpoint, _) = start_server(key.clone(), Duration::ZERO, StatusCode::OK).await;
+        let signer = RemoteSigner::new(&config(endpoint, Address::repeat_byte(0x01))).unwrap();
+
+        let (domain, values) = values(SwapType::Ether, 42);
+        assert_eq!(
+            signer
+                .sign_refund(5, &domain, &values)
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            "remote signer returned error: signer not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_timeout() {
+        let key = PrivateKeySigner::random();
+        let (endpoint, _) = start_server(key.clone(), Duration::from_secs(3), StatusCode::OK).await;
+        let signer = RemoteSigner::new(&Config {
+            timeout: 1,
+            ..config(endpoint, key.address())
+        })
+        .unwrap();
+
+        let (domain, values) = values(SwapType::Ether, 42);
+        assert!(
+            signer
+                .sign_refund(5, &domain, &values)
+                .await
+                .err()
+                .unwrap()
+                .to_string()
+                .starts_with("remote signer request failed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_contract_not_allowed() {
+        let key = PrivateKeySigner::random();
+        let (endpoint, server) = start_server(key.clone(), Duration::ZERO, StatusCode::OK).await;
+        let signer = RemoteSigner::new(&Config {
+            allowed_contracts: Some(vec![Address::repeat_byte(0x01).to_string()]),
+            ..config(endpoint, key.address())
+        })
+        .unwrap();
+
+        let (domain, values) = values(SwapType::Ether, 42);
+        assert_eq!(
+            signer
+                .sign_refund(5, &domain, &values)
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            format!("contract {} is not allowed", CONTRACT)
+        );
+        assert_eq!(server.requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sign_max_amounts() {
+        let key = PrivateKeySigner::random();
+        let (endpoint, server) = start_server(key.clone(), Duration::ZERO, StatusCode::OK).await;
+        let signer = RemoteSigner::new(&Config {
+            allowed_contracts: Some(vec![CONTRACT.to_string()]),
+            max_amounts: Some(HashMap::from([(
+                Address::ZERO.to_string(),
+                "100".to_string(),
+            )])),
+            ..config(endpoint, key.address())
+        })
+        .unwrap();
+
+        let (domain, ether) = values(SwapType::Ether, 100);
+        signer.sign_refund(5, &domain, &ether).await.unwrap();
+
+        let (domain, ether) = values(SwapType::Ether, 101);
+        assert_eq!(
+            signer
+                .sign_refund(5, &domain, &ether)
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            format!("amount 101 of {} exceeds maximum of 100", Address::ZERO)
+        );
+
+        let (domain, token) = values(SwapType::ERC20, 1);
+        assert_eq!(
+            signer
+                .sign_refund(5, &domain, &token)
+                .await
+                .err()
+                .unwrap()
+                .to_string(),
+            format!("token {} is not allowed", TOKEN)
+        );
+
+        assert_eq!(server.requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -47,6 +47,9 @@ pub async fn new_manager() -> Manager {
             }],
             tokens: None,
             quoters: None,
+            indexer: None,
+            nonce_manager: None,
+            remote_signer: None,
         },
     )
     .await
@@ -1,5 +1,6 @@
 mod drop_guard;
 mod rustls_provider;
+pub mod webhook;
 pub mod ws;
 
 pub use drop_guard::{DropGuard, defer};
@@ -0,0 +1,183 @@
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+use std::fmt;
+
+pub const SIGNATURE_HEADER: &str = "Boltz-Signature";
+
+/// Maximal difference in seconds between the timestamp of a signature and
+/// the time it is verified at, before it is considered a replay
+pub const DEFAULT_REPLAY_WINDOW: u64 = 300;
+
+const TIMESTAMP_KEY: &str = "t";
+const SIGNATURE_KEY: &str = "v1";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SignatureError {
+    InvalidHeader,
+    InvalidSecret,
+    OutsideReplayWindow,
+    Mismatch,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SignatureError::InvalidHeader => f.write_str("invalid signature header"),
+            SignatureError::InvalidSecret => f.write_str("invalid secret"),
+            SignatureError::OutsideReplayWindow => {
+                f.write_str("signature timestamp is outside of the replay window")
+            }
+            SignatureError::Mismatch => f.write_str("signature does not match"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Signs a WebHook body and returns the value for the `Boltz-Signature` header
+///
+/// The signature is an HMAC-SHA256 of `{timestamp}.{body}` keyed with the
+/// secret of the hook and encoded as `t={timestamp},v1={hex signature}`
+pub fn sign(secret: &str, timestamp: u64, body: &[u8]) -> Result<String, SignatureError> {
+    let mac = compute_mac(secret, timestamp, body)?;
+    Ok(format!(
+        "{TIMESTAMP_KEY}={timestamp},{SIGNATURE_KEY}={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+/// Verifies the `Boltz-Signature` header of a WebHook call
+///
+/// `now` is the current UNIX timestamp in seconds; signatures with a timestamp
+/// more than `replay_window` seconds away from it are rejected
+pub fn verify(
+    secret: &str,
+    header: &str,
+    body: &[u8],
+    now: u64,
+    replay_window: u64,
+) -> Result<(), SignatureError> {
+    let mut timestamp = None;
+    let mut signature = None;
+
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some((TIMESTAMP_KEY, value)) => {
+                timestamp = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| SignatureError::InvalidHeader)?,
+                );
+            }
+            Some((SIGNATURE_KEY, value)) => {
+                signature = Some(hex::decode(value).map_err(|_| SignatureError::InvalidHeader)?);
+            }
+            _ => {}
+        }
+    }
+
+    let (timestamp, signature) = match (timestamp, signature) {
+        (Some(timestamp), Some(signature)) => (timestamp, signature),
+        _ => return Err(SignatureError::InvalidHeader),
+    };
+
+    if now.abs_diff(timestamp) > replay_window {
+        return Err(SignatureError::OutsideReplayWindow);
+    }
+
+    compute_mac(secret, timestamp, body)?
+        .verify_slice(&signature)
+        .map_err(|_| SignatureError::Mismatch)
+}
+
+fn compute_mac(secret: &str, timestamp: u64, body: &[u8]) -> Result<Hmac<Sha256>, SignatureError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| SignatureError::InvalidSecret)?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    Ok(mac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &[u8] = br#"{"event":"swap.update","data":{"id":"gm","status":"swap.created"}}"#;
+    const TIMESTAMP: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_sign() {
+        let header = sign(SECRET, TIMESTAMP, BODY).unwrap();
+        assert!(header.starts_with(&format!("t={TIMESTAMP},v1=")));
+        assert_eq!(header.len(), "t=1700000000,v1=".len() + 64);
+    }
+
+    #[test]
+    fn test_verify() {
+        let header = sign(SECRET, TIMESTAMP, BODY).unwrap();
+        assert_eq!(
+            verify(SECRET, &header, BODY, TIMESTAMP + 10, DEFAULT_REPLAY_WINDOW),
+            Ok(())
+        );
+        assert_eq!(
+            verify(SECRET, &header, BODY, TIMESTAMP - 10, DEFAULT_REPLAY_WINDOW),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_wrong_secret() {
+        let header = sign(SECRET, TIMESTAMP, BODY).unwrap();
+        assert_eq!(
+            verify("other", &header, BODY, TIMESTAMP, DEFAULT_REPLAY_WINDOW),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_tampered_body() {
+        let header = sign(SECRET, TIMESTAMP, BODY).unwrap();
+        assert_eq!(
+            verify(SECRET, &header, b"{}", TIMESTAMP, DEFAULT_REPLAY_WINDOW),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_tampered_timestamp() {
+        let header = sign(SECRET, TIMESTAMP, BODY)
+            .unwrap()
+            .replace(&TIMESTAMP.to_string(), &(TIMESTAMP + 1).to_string());
+        assert_eq!(
+            verify(SECRET, &header, BODY, TIMESTAMP, DEFAULT_REPLAY_WINDOW),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_outside_replay_window() {
+        let header = sign(SECRET, TIMESTAMP, BODY).unwrap();
+        assert_eq!(
+            verify(
+                SECRET,
+                &header,
+                BODY,
+                TIMESTAMP + DEFAULT_REPLAY_WINDOW + 1,
+                DEFAULT_REPLAY_WINDOW
+            ),
+            Err(SignatureError::OutsideReplayWindow)
+        );
+    }
+
+    #[test]
+    fn test_verify_invalid_header() {
+        for header in ["", "t=1", "v1=00", "t=abc,v1=00", "t=1,v1=zz"] {
+            assert_eq!(
+                verify(SECRET, header, BODY, TIMESTAMP, DEFAULT_REPLAY_WINDOW),
+                Err(SignatureError::InvalidHeader)
+            );
+        }
+    }
+}
@@ -16,6 +16,9 @@ pub enum SubscriptionChannel {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct SwapUpdateSubscriptionRequest {
     pub args: Vec<String>,
+    /// Event id of the last update the client received; missed updates after it are replayed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -81,6 +84,8 @@ pub struct SwapStatus {
     pub id: String,
     #[serde(flatten)]
     pub base: SwapStatusNoId,
+    #[serde(rename = "eventId", default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<u64>,
 }
 
 impl SwapStatus {
@@ -91,6 +96,7 @@ impl SwapStatus {
                 status,
                 ..Default::default()
             },
+            event_id: None,
         }
     }
 }
@@ -134,6 +140,7 @@ mod tests {
         let request = SwapUpdateWsRequest::Subscribe(SwapUpdateSubscribeRequest::SwapUpdate(
             SwapUpdateSubscriptionRequest {
                 args: vec!["swap1".to_string(), "swap2".to_string()],
+                since: None,
             },
         ));
 
@@ -147,6 +154,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserializes_swap_update_subscription_request_since() {
+        let request = serde_json::from_value::<SwapUpdateWsRequest>(serde_json::json!({
+            "op": "subscribe",
+            "channel": "swap.update",
+            "args": ["swap1"],
+            "since": 21,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            request,
+            SwapUpdateWsRequest::Subscribe(SwapUpdateSubscribeRequest::SwapUpdate(
+                SwapUpdateSubscriptionRequest {
+                    args: vec!["swap1".to_string()],
+                    since: Some(21),
+                },
+            ))
+        );
+    }
+
+    #[test]
+    fn serializes_swap_status_event_id() {
+        let mut status = SwapStatus::new("swap1".to_string(), "invoice.set".to_string());
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            serde_json::json!({
+                "id": "swap1",
+                "status": "invoice.set",
+            }),
+        );
+
+        status.event_id = Some(21);
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            serde_json::json!({
+                "id": "swap1",
+                "status": "invoice.set",
+                "eventId": 21,
+            }),
+        );
+    }
+
     #[test]
     fn deserializes_swap_update_response() {
         let response = serde_json::from_value::<SwapUpdateWsResponse>(serde_json::json!({
@@ -184,6 +234,7 @@ mod tests {
                         failure_reason: None,
                         failure_details: None,
                     },
+                    event_id: None,
                 }],
                 timestamp: "123".to_string(),
             }),
@@ -519,4 +519,52 @@ impl BoltzClient {
             .await?;
         Ok(response.into_inner())
     }
+
+    pub async fn list_web_hook_dead_letters(
+        &mut self,
+        hook_id: Option<String>,
+    ) -> Result<boltzr::ListWebHookDeadLettersResponse> {
+        let response = self
+            .client
+            .list_web_hook_dead_letters(self.req(boltzr::ListWebHookDeadLettersRequest { hook_id }))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn get_web_hook_dead_letter(
+        &mut self,
+        id: u64,
+    ) -> Result<boltzr::GetWebHookDeadLetterResponse> {
+        let response = self
+            .client
+            .get_web_hook_dead_letter(self.req(boltzr::GetWebHookDeadLetterRequest { id }))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn replay_web_hook_dead_letter(
+        &mut self,
+        id: u64,
+    ) -> Result<boltzr::ReplayWebHookDeadLetterResponse> {
+        let response = self
+            .client
+            .replay_web_hook_dead_letter(self.req(boltzr::ReplayWebHookDeadLetterRequest { id }))
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn purge_web_hook_dead_letters(
+        &mut self,
+        hook_id: Option<String>,
+        older_than: Option<u64>,
+    ) -> Result<boltzr::PurgeWebHookDeadLettersResponse> {
+        let response = self
+            .client
+            .purge_web_hook_dead_letters(self.req(boltzr::PurgeWebHookDeadLettersRequest {
+                hook_id,
+                older_than,
+            }))
+            .await?;
+        Ok(response.into_inner())
+    }
 }
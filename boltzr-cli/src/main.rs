@@ -182,6 +182,33 @@ enum Commands {
         #[command(subcommand)]
         command: JwtCommands,
     },
+    #[command(about = "WebHook dead letter commands")]
+    DeadLetter {
+        #[command(subcommand)]
+        command: DeadLetterCommands,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum DeadLetterCommands {
+    #[command(about = "Lists abandoned WebHook calls; optionally filtered by swap id")]
+    List { hook_id: Option<String> },
+    #[command(about = "Gets an abandoned WebHook call including all its attempts")]
+    Get { id: u64 },
+    #[command(about = "Sends an abandoned WebHook call again")]
+    Replay { id: u64 },
+    #[command(about = "Deletes abandoned WebHook calls")]
+    Purge {
+        #[arg(long, help = "Only delete dead letters of this swap id")]
+        hook_id: Option<String>,
+        #[arg(
+            long,
+            help = "Only delete dead letters older than this. Plain integer = seconds; suffixes s/m/h/d/w/y accepted (e.g. '30d')"
+        )]
+        older_than: Option<parsers::HumanDuration>,
+        #[arg(short, long, help = "Skip the confirmation prompt")]
+        yes: bool,
+    },
 }
 
 #[derive(Clone, Subcommand)]
@@ -310,7 +337,6 @@ enum TxCommands {
     #[command(about = "Refunds a HTLC UTXO")]
     Refund {
         network: parsers::Network,
-        timeout_block_height: u32,
         #[arg(value_parser = parsers::parse_hex_fixed_bytes)]
         private_key: alloy::primitives::FixedBytes<32>,
         swap_tree_or_redeem_script: String,
@@ -320,6 +346,14 @@ enum TxCommands {
         fee_per_vbyte: f64,
         #[arg(short, long, value_parser = parsers::parse_hex_fixed_bytes)]
         blinding_key: Option<alloy::primitives::FixedBytes<32>>,
+        /// Defaults to the timeout of the swap tree or redeem script
+        #[arg(short, long)]
+        timeout_block_height: Option<u32>,
+    },
+    #[command(about = "Decodes the parameters of a swap tree or redeem script")]
+    Decode {
+        network: parsers::Network,
+        swap_tree_or_redeem_script: String,
     },
 }
 
@@ -572,7 +606,8 @@ async fn run_command(cli: Cli) -> Result<()> {
                     destination_address,
                     *fee_per_vbyte,
                     blinding_key.as_ref().map(|key| key.0),
-                )?;
+                )
+                .await?;
                 print_pretty(&Transaction {
                     transaction: alloy::hex::encode(claim_tx.serialize()),
                 })?;
@@ -596,11 +631,21 @@ async fn run_command(cli: Cli) -> Result<()> {
                     destination_address,
                     *fee_per_vbyte,
                     blinding_key.as_ref().map(|key| key.0),
-                )?;
+                )
+                .await?;
                 print_pretty(&Transaction {
                     transaction: alloy::hex::encode(refund_tx.serialize()),
                 })?;
             }
+            TxCommands::Decode {
+                network,
+                swap_tree_or_redeem_script,
+            } => {
+                print_pretty(&tx::decode_swap(
+                    network.into(),
+                    swap_tree_or_redeem_script,
+                )?)?;
+            }
         },
         Commands::Evm {
             contract,
@@ -1232,6 +1277,61 @@ async fn run_command(cli: Cli) -> Result<()> {
                 print_pretty(&response)?;
             }
         },
+        Commands::DeadLetter { ref command } => match command {
+            DeadLetterCommands::List { hook_id } => {
+                let response = get_grpc_client(&cli)
+                    .await?
+                    .list_web_hook_dead_letters(hook_id.clone())
+                    .await?;
+                print_pretty(&response)?;
+            }
+            DeadLetterCommands::Get { id } => {
+                let response = get_grpc_client(&cli)
+                    .await?
+                    .get_web_hook_dead_letter(*id)
+                    .await?;
+                print_pretty(&response)?;
+            }
+            DeadLetterCommands::Replay { id } => {
+                let response = get_grpc_client(&cli)
+                    .await?
+                    .replay_web_hook_dead_letter(*id)
+                    .await?;
+                print_pretty(&response)?;
+            }
+            DeadLetterCommands::Purge {
+                hook_id,
+                older_than,
+                yes,
+            } => {
+                if !*yes {
+                    let confirmed = inquire::Confirm::new(match hook_id {
+                        Some(_) => "Delete the dead letters of this swap?",
+                        None => "Delete the dead letters of all swaps?",
+                    })
+                    .with_default(false)
+                    .prompt()?;
+                    if !confirmed {
+                        println!("Aborted");
+                        return Ok(());
+                    }
+                }
+
+                let older_than = older_than.map(|duration| {
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs()
+                        .saturating_sub(duration.0)
+                });
+
+                let response = get_grpc_client(&cli)
+                    .await?
+                    .purge_web_hook_dead_letters(hook_id.clone(), older_than)
+                    .await?;
+                print_pretty(&response)?;
+            }
+        },
     }
 
     Ok(())
@@ -22,6 +22,19 @@ pub mod u256 {
     }
 }
 
+pub mod display {
+    use serde::Serializer;
+    use std::fmt::Display;
+
+    pub fn serialize<T, S>(data: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        serializer.collect_str(data)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +53,12 @@ mod tests {
         amount: U256,
     }
 
+    #[derive(Serialize)]
+    struct DisplayWrapper {
+        #[serde(serialize_with = "display::serialize")]
+        value: std::net::Ipv4Addr,
+    }
+
     #[test]
     fn test_serialize_non_empty() {
         let wrapper = Wrapper {
@@ -64,4 +83,13 @@ mod tests {
         let json = serde_json::to_string(&wrapper).unwrap();
         assert_eq!(json, "{\"amount\":\"123456789\"}");
     }
+
+    #[test]
+    fn test_serialize_display() {
+        let wrapper = DisplayWrapper {
+            value: std::net::Ipv4Addr::LOCALHOST,
+        };
+        let json = serde_json::to_string(&wrapper).unwrap();
+        assert_eq!(json, "{\"value\":\"127.0.0.1\"}");
+    }
 }
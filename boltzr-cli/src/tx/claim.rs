@@ -1,9 +1,10 @@
-use crate::tx::utils::construct_transaction;
+use crate::tx::{decode::decode_swap, utils::construct_transaction};
 use anyhow::Result;
+use bitcoin::hashes::{Hash, hash160};
 use boltz_core::{Network, Transaction, utils::InputType};
 
 #[allow(clippy::too_many_arguments)]
-pub fn claim_utxo(
+pub async fn claim_utxo(
     network: Network,
     preimage: [u8; 32],
     private_key: [u8; 32],
@@ -13,6 +14,11 @@ pub fn claim_utxo(
     fee_per_vbyte: f64,
     blinding_key: Option<[u8; 32]>,
 ) -> Result<Transaction> {
+    let swap = decode_swap(network, swap_tree_or_redeem_script)?;
+    if swap.preimage_hash != hash160::Hash::hash(&preimage) {
+        return Err(anyhow::anyhow!("preimage does not match swap"));
+    }
+
     construct_transaction(
         network,
         InputType::Claim(preimage),
@@ -23,4 +29,5 @@ pub fn claim_utxo(
         fee_per_vbyte,
         blinding_key,
     )
+    .await
 }
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use bitcoin::{ScriptBuf, hashes::hash160};
+use boltz_core::{
+    Network,
+    bitcoin::{
+        DecodeError as BitcoinDecodeError, Timelock as BitcoinTimelock, Tree as BitcoinTree,
+    },
+    elements::{ClaimCovenantParams, Timelock as ElementsTimelock, Tree as ElementsTree},
+    utils::{InputType, SwapKind},
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct DecodedSwap {
+    kind: &'static str,
+    #[serde(serialize_with = "crate::serde::display::serialize")]
+    pub preimage_hash: hash160::Hash,
+    claim_public_key: String,
+    refund_public_key: String,
+    #[serde(flatten)]
+    pub timeout: DecodedTimeout,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    covenant: Option<DecodedCovenant>,
+}
+
+/// Refund timeout of a swap; swap trees may use a relative (`OP_CSV`) one
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodedTimeout {
+    TimeoutBlockHeight(u32),
+    RelativeTimeout(u32),
+}
+
+impl DecodedTimeout {
+    pub fn input_type(&self) -> InputType {
+        match self {
+            DecodedTimeout::TimeoutBlockHeight(lock_time) => InputType::Refund(*lock_time),
+            DecodedTimeout::RelativeTimeout(sequence) => InputType::RelativeRefund(*sequence),
+        }
+    }
+}
+
+impl From<BitcoinTimelock> for DecodedTimeout {
+    fn from(timelock: BitcoinTimelock) -> Self {
+        match timelock {
+            BitcoinTimelock::Absolute(lock_time) => {
+                DecodedTimeout::TimeoutBlockHeight(lock_time.to_consensus_u32())
+            }
+            BitcoinTimelock::Relative(sequence) => {
+                DecodedTimeout::RelativeTimeout(sequence.to_consensus_u32())
+            }
+        }
+    }
+}
+
+impl From<ElementsTimelock> for DecodedTimeout {
+    fn from(timelock: ElementsTimelock) -> Self {
+        match timelock {
+            ElementsTimelock::Absolute(lock_time) => {
+                DecodedTimeout::TimeoutBlockHeight(lock_time.to_consensus_u32())
+            }
+            ElementsTimelock::Relative(sequence) => {
+                DecodedTimeout::RelativeTimeout(sequence.to_consensus_u32())
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DecodedCovenant {
+    index: u32,
+    output: String,
+    asset_id: String,
+    expected_amount: u64,
+}
+
+impl From<ClaimCovenantParams> for DecodedCovenant {
+    fn from(params: ClaimCovenantParams) -> Self {
+        Self {
+            index: params.index,
+            output: params.output.to_string(),
+            asset_id: params.asset_id.to_string(),
+            expected_amount: params.expected_amount,
+        }
+    }
+}
+
+/// Recovers the parameters of a swap from its swap tree or redeem script and
+/// fails when it does not match a Boltz swap template
+pub fn decode_swap(network: Network, swap_tree_or_redeem_script: &str) -> Result<DecodedSwap> {
+    let swap_tree_or_redeem_script = swap_tree_or_redeem_script.replace("\\\"", "\"");
+
+    if let Ok(tree) = serde_json::from_str::<BitcoinTree>(&swap_tree_or_redeem_script) {
+        match boltz_core::bitcoin::decode_tree(&tree) {
+            Ok(params) => {
+                return Ok(DecodedSwap {
+                    kind: kind_name(params.kind),
+                    preimage_hash: params.preimage_hash,
+                    claim_public_key: params.claim_pubkey.to_string(),
+                    refund_public_key: params.refund_pubkey.to_string(),
+                    timeout: params.lock_time.into(),
+                    covenant: None,
+                });
+            }
+            // Liquid trees have a different leaf version
+            Err(BitcoinDecodeError::LeafVersion(_)) => {}
+            Err(err) => return Err(anyhow::anyhow!("invalid swap tree: {}", err)),
+        }
+
+        let tree = serde_json::from_str::<ElementsTree>(&swap_tree_or_redeem_script)?;
+        let params = boltz_core::elements::decode_tree(&tree, network.liquid()?)
+            .map_err(|err| anyhow::anyhow!("invalid swap tree: {}", err))?;
+
+        return Ok(DecodedSwap {
+            kind: kind_name(params.kind),
+            preimage_hash: params.preimage_hash,
+            claim_public_key: params.claim_pubkey.to_string(),
+            refund_public_key: params.refund_pubkey.to_string(),
+            timeout: params.lock_time.into(),
+            covenant: params.covenant.map(DecodedCovenant::from),
+        });
+    }
+
+    // Legacy redeem scripts are the same on Bitcoin and Liquid
+    let script = ScriptBuf::from_hex(&swap_tree_or_redeem_script)
+        .map_err(|e| anyhow::anyhow!("failed to parse redeem script: {}", e))?;
+    let params = boltz_core::bitcoin::decode_script(&script)
+        .map_err(|err| anyhow::anyhow!("invalid redeem script: {}", err))?;
+
+    Ok(DecodedSwap {
+        kind: kind_name(params.kind),
+        preimage_hash: params.preimage_hash,
+        claim_public_key: params.claim_pubkey.to_string(),
+        refund_public_key: params.refund_pubkey.to_string(),
+        timeout: params.lock_time.into(),
+        covenant: None,
+    })
+}
+
+fn kind_name(kind: SwapKind) -> &'static str {
+    match kind {
+        SwapKind::Submarine => "submarine",
+        SwapKind::Reverse => "reverse",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{
+        Sequence,
+        absolute::LockTime,
+        hashes::Hash,
+        secp256k1::{PublicKey, Secp256k1, SecretKey},
+    };
+
+    fn keys() -> (PublicKey, PublicKey) {
+        let secp = Secp256k1::new();
+        (
+            SecretKey::from_slice(&[1; 32]).unwrap().public_key(&secp),
+            SecretKey::from_slice(&[2; 32]).unwrap().public_key(&secp),
+        )
+    }
+
+    #[test]
+    fn test_decode_swap_tree() {
+        let (claim, refund) = keys();
+        let preimage_hash = hash160::Hash::hash(&[3; 32]);
+        let tree = boltz_core::bitcoin::reverse_tree(
+            preimage_hash,
+            &claim.x_only_public_key().0,
+            &refund.x_only_public_key().0,
+            LockTime::from_height(123).unwrap(),
+        );
+
+        let decoded =
+            decode_swap(Network::Regtest, &serde_json::to_string(&tree).unwrap()).unwrap();
+        assert_eq!(decoded.kind, "reverse");
+        assert_eq!(decoded.preimage_hash, preimage_hash);
+        assert_eq!(decoded.timeout, DecodedTimeout::TimeoutBlockHeight(123));
+    }
+
+    #[test]
+    fn test_decode_swap_elements_tree() {
+        let (claim, refund) = keys();
+        let preimage_hash = hash160::Hash::hash(&[3; 32]);
+        let tree = boltz_core::elements::swap_tree(
+            preimage_hash,
+            &claim.x_only_public_key().0,
+            &refund.x_only_public_key().0,
+            elements::LockTime::from_height(321).unwrap(),
+        );
+
+        let decoded =
+            decode_swap(Network::Regtest, &serde_json::to_string(&tree).unwrap()).unwrap();
+        assert_eq!(decoded.kind, "submarine");
+        assert_eq!(decoded.preimage_hash, preimage_hash);
+        assert_eq!(decoded.timeout, DecodedTimeout::TimeoutBlockHeight(321));
+    }
+
+    #[test]
+    fn test_decode_swap_redeem_script() {
+        let (claim, refund) = keys();
+        let preimage_hash = hash160::Hash::hash(&[3; 32]);
+        let script = boltz_core::bitcoin::swap_script(
+            preimage_hash,
+            &claim,
+            &refund,
+            LockTime::from_height(42).unwrap(),
+        );
+
+        let decoded = decode_swap(Network::Regtest, &script.to_hex_string()).unwrap();
+        assert_eq!(decoded.kind, "submarine");
+        assert_eq!(decoded.preimage_hash, preimage_hash);
+        assert_eq!(decoded.timeout, DecodedTimeout::TimeoutBlockHeight(42));
+    }
+
+    #[test]
+    fn test_decode_swap_tree_relative() {
+        let (claim, refund) = keys();
+        let preimage_hash = hash160::Hash::hash(&[3; 32]);
+        let tree = boltz_core::bitcoin::swap_tree(
+            preimage_hash,
+            &claim.x_only_public_key().0,
+            &refund.x_only_public_key().0,
+            Sequence::from_height(144),
+        );
+
+        let decoded =
+            decode_swap(Network::Regtest, &serde_json::to_string(&tree).unwrap()).unwrap();
+        assert_eq!(decoded.timeout, DecodedTimeout::RelativeTimeout(144));
+        assert_eq!(decoded.timeout.input_type(), InputType::RelativeRefund(144));
+
+        let json = serde_json::to_value(&decoded).unwrap();
+        assert_eq!(json["relative_timeout"], 144);
+        assert!(json.get("timeout_block_height").is_none());
+    }
+
+    #[test]
+    fn test_decode_swap_unknown_script() {
+        assert!(
+            decode_swap(
+                Network::Regtest,
+                "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+            )
+            .is_err()
+        );
+    }
+}
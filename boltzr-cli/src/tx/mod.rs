@@ -1,6 +1,8 @@
 mod claim;
+mod decode;
 mod refund;
 mod utils;
 
 pub use claim::claim_utxo;
+pub use decode::decode_swap;
 pub use refund::refund_utxo;
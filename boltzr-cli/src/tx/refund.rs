@@ -1,11 +1,11 @@
-use crate::tx::utils::construct_transaction;
+use crate::tx::{decode::decode_swap, utils::construct_transaction};
 use anyhow::Result;
 use boltz_core::{Network, Transaction, utils::InputType};
 
 #[allow(clippy::too_many_arguments)]
-pub fn refund_utxo(
+pub async fn refund_utxo(
     network: Network,
-    timeout_block_height: u32,
+    timeout_block_height: Option<u32>,
     private_key: [u8; 32],
     swap_tree_or_redeem_script: &str,
     raw_transaction: Vec<u8>,
@@ -13,9 +13,16 @@ pub fn refund_utxo(
     fee_per_vbyte: f64,
     blinding_key: Option<[u8; 32]>,
 ) -> Result<Transaction> {
+    let input_type = match timeout_block_height {
+        Some(timeout_block_height) => InputType::Refund(timeout_block_height),
+        None => decode_swap(network, swap_tree_or_redeem_script)?
+            .timeout
+            .input_type(),
+    };
+
     construct_transaction(
         network,
-        InputType::Refund(timeout_block_height),
+        input_type,
         private_key,
         swap_tree_or_redeem_script,
         raw_transaction,
@@ -23,4 +30,5 @@ pub fn refund_utxo(
         fee_per_vbyte,
         blinding_key,
     )
+    .await
 }
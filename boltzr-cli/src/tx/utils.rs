@@ -59,7 +59,7 @@ macro_rules! find_matching_output_taproot {
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn construct_transaction(
+pub async fn construct_transaction(
     network: Network,
     input_type: InputType,
     private_key: [u8; 32],
@@ -116,7 +116,7 @@ pub fn construct_transaction(
         }
     };
 
-    Ok(construct_tx(params)?.0)
+    Ok(construct_tx(params).await?.0)
 }
 
 pub fn parse_transaction(transaction: Vec<u8>) -> Result<Transaction> {
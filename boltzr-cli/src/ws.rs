@@ -17,7 +17,10 @@ pub async fn subscribe_swap_updates(endpoint: &str, ids: Vec<String>) -> Result<
     let (mut sender, mut receiver) = stream.split();
 
     let request = serde_json::to_string(&SwapUpdateWsRequest::Subscribe(
-        SwapUpdateSubscribeRequest::SwapUpdate(SwapUpdateSubscriptionRequest { args: ids }),
+        SwapUpdateSubscribeRequest::SwapUpdate(SwapUpdateSubscriptionRequest {
+            args: ids,
+            since: None,
+        }),
     ))?;
     sender
         .send(Message::text(request))
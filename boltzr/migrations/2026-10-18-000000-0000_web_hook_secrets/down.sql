@@ -0,0 +1,5 @@
+ALTER TABLE web_hooks
+DROP COLUMN secret;
+
+ALTER TABLE offers
+DROP COLUMN secret;
@@ -0,0 +1,5 @@
+ALTER TABLE web_hooks
+ADD COLUMN secret TEXT;
+
+ALTER TABLE offers
+ADD COLUMN secret TEXT;
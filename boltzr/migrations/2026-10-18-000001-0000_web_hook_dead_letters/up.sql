@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS web_hook_dead_letters (
+  id BIGSERIAL PRIMARY KEY,
+  hook_id TEXT NOT NULL,
+  url TEXT NOT NULL,
+  payload JSONB NOT NULL,
+  last_status INTEGER,
+  last_error TEXT,
+  attempts JSONB NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS web_hook_dead_letters_hook_id_idx ON web_hook_dead_letters (hook_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS claim_replacements;
+DROP TABLE IF EXISTS pending_claims;
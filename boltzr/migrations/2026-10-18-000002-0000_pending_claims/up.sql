@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS pending_claims (
+  swap_id TEXT PRIMARY KEY,
+  symbol TEXT NOT NULL,
+  transaction_id TEXT NOT NULL,
+  status TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS pending_claims_symbol_status_idx ON pending_claims (symbol, status);
+CREATE INDEX IF NOT EXISTS pending_claims_transaction_id_idx ON pending_claims (transaction_id);
+
+CREATE TABLE IF NOT EXISTS claim_replacements (
+  id BIGSERIAL PRIMARY KEY,
+  swap_id TEXT NOT NULL REFERENCES pending_claims (swap_id) ON DELETE CASCADE,
+  replaced_transaction_id TEXT NOT NULL,
+  transaction_id TEXT NOT NULL,
+  fee_rate DOUBLE PRECISION NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS claim_replacements_swap_id_idx ON claim_replacements (swap_id);
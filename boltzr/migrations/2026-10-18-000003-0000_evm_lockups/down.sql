@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS evm_refunds;
+DROP TABLE IF EXISTS evm_claims;
+DROP TABLE IF EXISTS evm_lockups;
+DROP TABLE IF EXISTS evm_indexed_blocks;
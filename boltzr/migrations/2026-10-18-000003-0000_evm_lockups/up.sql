@@ -0,0 +1,55 @@
+CREATE TABLE IF NOT EXISTS evm_indexed_blocks (
+  symbol TEXT NOT NULL,
+  height BIGINT NOT NULL,
+  hash BYTEA NOT NULL,
+  parent_hash BYTEA NOT NULL,
+  PRIMARY KEY (symbol, height)
+);
+
+CREATE TABLE IF NOT EXISTS evm_lockups (
+  symbol TEXT NOT NULL,
+  transaction_hash BYTEA NOT NULL,
+  log_index BIGINT NOT NULL,
+  contract_address BYTEA NOT NULL,
+  block_height BIGINT NOT NULL,
+  block_hash BYTEA NOT NULL,
+  preimage_hash BYTEA NOT NULL,
+  amount TEXT NOT NULL,
+  token_address BYTEA,
+  claim_address BYTEA NOT NULL,
+  refund_address BYTEA NOT NULL,
+  timelock BIGINT NOT NULL,
+  PRIMARY KEY (symbol, transaction_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS evm_lockups_symbol_block_height_idx ON evm_lockups (symbol, block_height);
+CREATE INDEX IF NOT EXISTS evm_lockups_preimage_hash_idx ON evm_lockups (preimage_hash);
+
+CREATE TABLE IF NOT EXISTS evm_claims (
+  symbol TEXT NOT NULL,
+  transaction_hash BYTEA NOT NULL,
+  log_index BIGINT NOT NULL,
+  contract_address BYTEA NOT NULL,
+  block_height BIGINT NOT NULL,
+  block_hash BYTEA NOT NULL,
+  preimage_hash BYTEA NOT NULL,
+  preimage BYTEA NOT NULL,
+  PRIMARY KEY (symbol, transaction_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS evm_claims_symbol_block_height_idx ON evm_claims (symbol, block_height);
+CREATE INDEX IF NOT EXISTS evm_claims_preimage_hash_idx ON evm_claims (preimage_hash);
+
+CREATE TABLE IF NOT EXISTS evm_refunds (
+  symbol TEXT NOT NULL,
+  transaction_hash BYTEA NOT NULL,
+  log_index BIGINT NOT NULL,
+  contract_address BYTEA NOT NULL,
+  block_height BIGINT NOT NULL,
+  block_hash BYTEA NOT NULL,
+  preimage_hash BYTEA NOT NULL,
+  PRIMARY KEY (symbol, transaction_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS evm_refunds_symbol_block_height_idx ON evm_refunds (symbol, block_height);
+CREATE INDEX IF NOT EXISTS evm_refunds_preimage_hash_idx ON evm_refunds (preimage_hash);
@@ -22,6 +22,7 @@ use std::sync::Arc;
 pub struct CreateRequest {
     offer: String,
     url: Option<String>,
+    secret: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -98,7 +99,7 @@ where
         None => return Ok(no_cln_error()),
     };
 
-    cln.hold.add_offer(body.offer, body.url)?;
+    cln.hold.add_offer(body.offer, body.url, body.secret)?;
     Ok((StatusCode::CREATED, Json(CreateResponse {})).into_response())
 }
 
@@ -343,6 +344,7 @@ mod test {
                 service: Arc::new(Service::new_mocked_prometheus(false)),
                 swap_status_update_tx: status_tx.clone(),
                 swap_infos: Fetcher { status_tx },
+                rate_limiter: None,
             },
         )))
     }
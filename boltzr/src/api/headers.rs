@@ -1,5 +1,11 @@
+use crate::service::ReferralAuth;
+use axum::http::HeaderMap;
 use axum_extra::headers::{Error, Header, HeaderName, HeaderValue};
 
+pub const HEADER_TS: &str = "TS";
+pub const HEADER_API_KEY: &str = "API-KEY";
+pub const HEADER_API_HMAC: &str = "API-HMAC";
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Referral(String);
 
@@ -34,6 +40,27 @@ impl Header for Referral {
     }
 }
 
+/// Parses the headers with which referrals authenticate; `None` when no API key is set
+pub fn referral_auth(headers: &HeaderMap) -> anyhow::Result<Option<ReferralAuth>> {
+    if !headers.contains_key(HEADER_API_KEY) {
+        return Ok(None);
+    }
+
+    let get_header = |name: &str| -> anyhow::Result<String> {
+        Ok(headers
+            .get(name)
+            .ok_or(anyhow::anyhow!("{} header not set", name))?
+            .to_str()?
+            .to_string())
+    };
+
+    Ok(Some(ReferralAuth {
+        ts: get_header(HEADER_TS)?,
+        api_key: get_header(HEADER_API_KEY)?,
+        hmac: get_header(HEADER_API_HMAC)?,
+    }))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,4 +1,5 @@
 use crate::api::errors::{error_middleware, logging_middleware};
+use crate::api::rate_limit::{RateLimiter, rate_limit_middleware};
 use crate::api::rescue::{swap_rescue, swap_restore, swap_restore_index};
 use crate::api::sse::sse_handler;
 use crate::api::stats::get_stats;
@@ -6,8 +7,10 @@ use crate::service::Service;
 use crate::swap::manager::SwapManager;
 use axum::routing::{delete, get, patch, post};
 use axum::{Extension, Router};
+use boltz_cache::Cache;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
@@ -23,6 +26,7 @@ mod errors;
 mod headers;
 mod lightning;
 mod quoter;
+mod rate_limit;
 mod rescue;
 mod sse;
 mod stats;
@@ -30,11 +34,18 @@ mod types;
 pub mod ws;
 
 pub use bolt12::MagicRoutingHint;
+pub use rate_limit::{Limit, RateLimitConfig};
 
 #[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
 pub struct Config {
     pub host: String,
     pub port: u16,
+    #[serde(rename = "rateLimits")]
+    pub rate_limits: Option<Vec<RateLimitConfig>>,
+    /// Addresses of reverse proxies whose X-Forwarded-For header is used to
+    /// get the address of clients for rate limiting
+    #[serde(rename = "trustedProxies")]
+    pub trusted_proxies: Option<Vec<IpAddr>>,
 }
 
 pub struct Server<S, M> {
@@ -46,6 +57,7 @@ pub struct Server<S, M> {
 
     swap_infos: S,
     swap_status_update_tx: tokio::sync::broadcast::Sender<(Option<u64>, Vec<SwapStatus>)>,
+    rate_limiter: Option<RateLimiter>,
 }
 
 struct ServerState<S, M> {
@@ -54,6 +66,7 @@ struct ServerState<S, M> {
 
     swap_infos: S,
     swap_status_update_tx: tokio::sync::broadcast::Sender<(Option<u64>, Vec<SwapStatus>)>,
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl<S, M> Server<S, M>
@@ -66,10 +79,18 @@ where
         cancellation_token: CancellationToken,
         manager: Arc<M>,
         service: Arc<Service>,
+        cache: Cache,
         swap_infos: S,
         swap_status_update_tx: tokio::sync::broadcast::Sender<(Option<u64>, Vec<SwapStatus>)>,
     ) -> Self {
         Server {
+            rate_limiter: config.rate_limits.clone().map(|limits| {
+                RateLimiter::new(
+                    cache,
+                    limits,
+                    config.trusted_proxies.clone().unwrap_or_default(),
+                )
+            }),
             config,
             manager,
             service,
@@ -106,12 +127,16 @@ where
             Ok(listener) => {
                 axum::serve(
                     listener,
-                    router.layer(Extension(Arc::new(ServerState {
-                        manager: self.manager.clone(),
-                        service: self.service.clone(),
-                        swap_infos: self.swap_infos.clone(),
-                        swap_status_update_tx: self.swap_status_update_tx.clone(),
-                    }))),
+                    router
+                        .layer(Extension(Arc::new(ServerState {
+                            manager: self.manager.clone(),
+                            service: self.service.clone(),
+                            swap_infos: self.swap_infos.clone(),
+                            swap_status_update_tx: self.swap_status_update_tx.clone(),
+                            rate_limiter: self.rate_limiter.clone(),
+                        })))
+                        // Needed to rate limit clients that are not behind a proxy
+                        .into_make_service_with_connect_info::<SocketAddr>(),
                 )
                 .with_graceful_shutdown(async move {
                     cancellation_token.cancelled().await;
@@ -195,6 +220,7 @@ where
             )
             .route("/v2/quote/{currency}/encode", post(quoter::encode::<S, M>))
             // Middlewares
+            .route_layer(axum::middleware::from_fn(rate_limit_middleware::<S, M>))
             .layer(axum::middleware::from_fn(error_middleware))
             .layer(axum::middleware::from_fn(logging_middleware))
     }
@@ -208,6 +234,7 @@ pub mod test {
     use crate::service::Service;
     use crate::swap::manager::test::MockManager;
     use async_trait::async_trait;
+    use boltz_cache::{Cache, MemCache};
     use reqwest::StatusCode;
     use std::sync::Arc;
     use std::time::Duration;
@@ -257,10 +284,13 @@ pub mod test {
             Config {
                 port,
                 host: "127.0.0.1".to_string(),
+                rate_limits: None,
+                trusted_proxies: None,
             },
             cancel.clone(),
             Arc::new(MockManager::new()),
             Arc::new(Service::new_mocked_prometheus(false)),
+            Cache::Memory(MemCache::new()),
             Fetcher {
                 status_tx: status_tx.clone(),
             },
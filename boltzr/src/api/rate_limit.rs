@@ -0,0 +1,714 @@
+use crate::api::ServerState;
+use crate::api::errors::ApiError;
+use crate::api::headers::referral_auth;
+use anyhow::Result;
+use axum::Json;
+use axum::body::Body;
+use axum::extract::{ConnectInfo, MatchedPath, OriginalUri, Request};
+use axum::http::header::{CONTENT_LENGTH, RETRY_AFTER};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use boltz_cache::Cache;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+const CACHE_KEY_RATE_LIMIT: &str = "api:ratelimit";
+const HEADER_FORWARDED_FOR: &str = "X-Forwarded-For";
+
+/// Maximal size of bodies that are read to authenticate the referral of a request
+const MAX_AUTHENTICATED_BODY_SIZE: usize = 1024 * 1024;
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct Limit {
+    #[serde(rename = "requestsPerMinute")]
+    pub requests_per_minute: u32,
+    /// Requests that can be sent at once; defaults to `requestsPerMinute`
+    pub burst: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+pub struct RateLimitConfig {
+    /// Route as it is registered in the router, like `/v2/lightning/{currency}/search`
+    pub route: String,
+    #[serde(rename = "perIp")]
+    pub per_ip: Option<Limit>,
+    /// Only applies to requests authenticated with the API key of a referral
+    #[serde(rename = "perReferral")]
+    pub per_referral: Option<Limit>,
+}
+
+impl Limit {
+    fn capacity(&self) -> u64 {
+        self.burst.unwrap_or(self.requests_per_minute) as u64
+    }
+
+    /// Length of the windows in which `capacity` requests are allowed, so that
+    /// `requestsPerMinute` are allowed on average
+    fn window_ms(&self) -> u64 {
+        (self.capacity() * 60_000)
+            .div_ceil(self.requests_per_minute as u64)
+            .max(1)
+    }
+
+    /// Seconds until the window of `now` is over
+    fn retry_after(&self, now: u64) -> u64 {
+        let window_ms = self.window_ms();
+        (window_ms - now % window_ms).div_ceil(1_000).max(1)
+    }
+}
+
+/// Fixed window rate limiter for routes of the API. The request counters are
+/// incremented atomically in the cache, so that all instances sharing it also
+/// share the limits.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    cache: Cache,
+    limits: Arc<HashMap<String, RateLimitConfig>>,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+}
+
+impl RateLimiter {
+    pub fn new(cache: Cache, configs: Vec<RateLimitConfig>, trusted_proxies: Vec<IpAddr>) -> Self {
+        let mut limits = HashMap::new();
+        for mut config in configs {
+            for (kind, limit) in [
+                ("IP", &mut config.per_ip),
+                ("referral", &mut config.per_referral),
+            ] {
+                if limit
+                    .as_ref()
+                    .is_some_and(|limit| limit.requests_per_minute == 0)
+                {
+                    warn!(
+                        "Ignoring per {} rate limit of {} with 0 requests per minute",
+                        kind, config.route
+                    );
+                    *limit = None;
+                }
+            }
+
+            limits.insert(config.route.clone(), config);
+        }
+
+        Self {
+            cache,
+            limits: Arc::new(limits),
+            trusted_proxies: Arc::new(
+                trusted_proxies
+                    .into_iter()
+                    .map(|proxy| proxy.to_canonical())
+                    .collect(),
+            ),
+        }
+    }
+
+    fn limits_referrals(&self, route: &str) -> bool {
+        self.limits
+            .get(route)
+            .is_some_and(|config| config.per_referral.is_some())
+    }
+
+    /// Returns the seconds after which the client may retry when it is rate limited
+    pub async fn check(
+        &self,
+        route: &str,
+        ip: Option<&str>,
+        referral: Option<&str>,
+        now: u64,
+    ) -> Result<Option<u64>> {
+        let config = match self.limits.get(route) {
+            Some(config) => config,
+            None => return Ok(None),
+        };
+
+        if let (Some(limit), Some(ip)) = (&config.per_ip, ip)
+            && let Some(retry_after) = self.take(limit, &format!("{route}:ip:{ip}"), now).await?
+        {
+            return Ok(Some(retry_after));
+        }
+
+        if let (Some(limit), Some(referral)) = (&config.per_referral, referral)
+            && let Some(retry_after) = self
+                .take(limit, &format!("{route}:referral:{referral}"), now)
+                .await?
+        {
+            return Ok(Some(retry_after));
+        }
+
+        Ok(None)
+    }
+
+    async fn take(&self, limit: &Limit, field: &str, now: u64) -> Result<Option<u64>> {
+        let window_ms = limit.window_ms();
+        let count = self
+            .cache
+            .increment(
+                CACHE_KEY_RATE_LIMIT,
+                &format!("{field}:{}", now / window_ms),
+                1,
+                Some(window_ms.div_ceil(1_000)),
+            )
+            .await?;
+
+        Ok((count > limit.capacity()).then(|| limit.retry_after(now)))
+    }
+}
+
+pub async fn rate_limit_middleware<S, M>(request: Request<Body>, next: Next) -> Response<Body>
+where
+    S: Send + Sync + 'static,
+    M: Send + Sync + 'static,
+{
+    let state = match request.extensions().get::<Arc<ServerState<S, M>>>() {
+        Some(state) => state.clone(),
+        None => return next.run(request).await,
+    };
+    let rate_limiter = match &state.rate_limiter {
+        Some(rate_limiter) => rate_limiter,
+        None => return next.run(request).await,
+    };
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(route) => route.as_str().to_string(),
+        None => return next.run(request).await,
+    };
+
+    let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(now) => now.as_millis() as u64,
+        Err(err) => {
+            warn!("Could not get UNIX time: {}", err);
+            return next.run(request).await;
+        }
+    };
+
+    let ip = client_ip(&request, &rate_limiter.trusted_proxies);
+    let (request, referral) = if rate_limiter.limits_referrals(&route) {
+        match authenticated_referral::<S, M>(&state, request, now / 1_000).await {
+            Ok(res) => res,
+            Err(response) => return response,
+        }
+    } else {
+        (request, None)
+    };
+
+    match rate_limiter
+        .check(&route, ip.as_deref(), referral.as_deref(), now)
+        .await
+    {
+        Ok(None) => next.run(request).await,
+        Ok(Some(retry_after)) => {
+            debug!(
+                "Rate limited request to {} from {}",
+                route,
+                ip.as_deref().unwrap_or("unknown address")
+            );
+
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, retry_after.to_string())],
+                Json(ApiError {
+                    error: "rate limit exceeded".to_string(),
+                }),
+            )
+                .into_response()
+        }
+        // The API should stay available when the cache is not
+        Err(err) => {
+            warn!("Could not check rate limit of {}: {}", route, err);
+            next.run(request).await
+        }
+    }
+}
+
+/// Referral that authenticated the request with its API key. The `Referral`
+/// header can be set by anyone, so it cannot be used to key limits. Because the
+/// body of POST requests is part of the HMAC, it is buffered and put back.
+async fn authenticated_referral<S, M>(
+    state: &ServerState<S, M>,
+    request: Request<Body>,
+    now: u64,
+) -> Result<(Request<Body>, Option<String>), Response<Body>> {
+    let auth = match referral_auth(request.headers()) {
+        Ok(Some(auth)) => auth,
+        _ => return Ok((request, None)),
+    };
+
+    let is_post = request.method() == Method::POST;
+    if is_post
+        && !request
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok())
+            .is_some_and(|length| length <= MAX_AUTHENTICATED_BODY_SIZE)
+    {
+        return Ok((request, None));
+    }
+
+    let uri = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.0.clone())
+        .unwrap_or_else(|| request.uri().clone());
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or(uri.path())
+        .to_string();
+
+    let (parts, body) = request.into_parts();
+    let body = if is_post {
+        axum::body::to_bytes(body, MAX_AUTHENTICATED_BODY_SIZE)
+            .await
+            .map_err(|err| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiError {
+                        error: format!("could not read body: {err}"),
+                    }),
+                )
+                    .into_response()
+            })?
+    } else {
+        Default::default()
+    };
+
+    let referral = match state.service.referral_swaps.authenticate(
+        &auth,
+        parts.method.as_str(),
+        &path,
+        &body,
+        now,
+    ) {
+        Ok(referral) => Some(referral),
+        Err(err) => {
+            debug!(
+                "Could not authenticate referral of request to {}: {}",
+                path, err
+            );
+            None
+        }
+    };
+
+    let body = if is_post {
+        Body::from(body)
+    } else {
+        Body::empty()
+    };
+    Ok((Request::from_parts(parts, body), referral))
+}
+
+/// The X-Forwarded-For header can be set by anyone, so it is only used when the
+/// connection comes from a trusted proxy. The rightmost address in it that is
+/// not a trusted proxy is the client that connected to the outermost of them.
+fn client_ip(request: &Request<Body>, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip().to_canonical())?;
+
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer.to_string());
+    }
+
+    Some(
+        forwarded_for(request.headers(), trusted_proxies)
+            .unwrap_or(peer)
+            .to_string(),
+    )
+}
+
+fn forwarded_for(headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let hops = headers
+        .get_all(HEADER_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|hop| hop.trim())
+        .collect::<Vec<_>>();
+
+    for hop in hops.into_iter().rev() {
+        let ip = hop.parse::<IpAddr>().ok()?.to_canonical();
+        if !trusted_proxies.contains(&ip) {
+            return Some(ip);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::headers::{HEADER_API_HMAC, HEADER_API_KEY, HEADER_TS, Referral};
+    use crate::api::test::Fetcher;
+    use crate::api::ws::types::SwapStatus;
+    use crate::db::helpers::chain_swap::test::MockChainSwapHelper;
+    use crate::db::helpers::referral::test::MockReferralHelper;
+    use crate::db::helpers::reverse_swap::test::MockReverseSwapHelper;
+    use crate::db::helpers::swap::test::MockSwapHelper;
+    use crate::db::models::ReferralCredentials;
+    use crate::service::{ReferralSwaps, Service};
+    use crate::swap::manager::test::MockManager;
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use axum_extra::headers::Header;
+    use boltz_cache::MemCache;
+    use hmac::{Hmac, KeyInit, Mac};
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use sha2::Sha256;
+    use tower::ServiceExt;
+
+    const ROUTE: &str = "/v2/limited/{id}";
+    const PATH: &str = "/v2/limited/1";
+    const PROXY: &str = "10.0.0.1";
+
+    const API_KEY: &str = "key";
+    const API_SECRET: &str = "secret";
+
+    fn limit(requests_per_minute: u32, burst: Option<u32>) -> Limit {
+        Limit {
+            requests_per_minute,
+            burst,
+        }
+    }
+
+    fn rate_limiter(per_ip: Option<Limit>, per_referral: Option<Limit>) -> RateLimiter {
+        RateLimiter::new(
+            Cache::Memory(MemCache::new()),
+            vec![RateLimitConfig {
+                route: ROUTE.to_string(),
+                per_ip,
+                per_referral,
+            }],
+            vec![PROXY.parse().unwrap()],
+        )
+    }
+
+    #[rstest]
+    #[case::default_burst(limit(10, None), 60_000)]
+    #[case::burst(limit(10, Some(5)), 30_000)]
+    #[case::rounded_up(limit(7, Some(2)), 17_143)]
+    fn test_limit_window_ms(#[case] limit: Limit, #[case] expected: u64) {
+        assert_eq!(limit.window_ms(), expected);
+    }
+
+    #[rstest]
+    #[case::window_start(0, 30)]
+    #[case::window_middle(14_500, 16)]
+    #[case::window_end(29_999, 1)]
+    #[case::next_window(30_000, 30)]
+    fn test_limit_retry_after(#[case] now: u64, #[case] expected: u64) {
+        assert_eq!(limit(10, Some(5)).retry_after(now), expected);
+    }
+
+    #[tokio::test]
+    async fn test_check_per_ip() {
+        let limiter = rate_limiter(Some(limit(60, Some(2))), None);
+
+        for _ in 0..2 {
+            assert_eq!(
+                limiter
+                    .check(ROUTE, Some("1.1.1.1"), None, 0)
+                    .await
+                    .unwrap(),
+                None
+            );
+        }
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("1.1.1.1"), None, 0)
+                .await
+                .unwrap(),
+            Some(2)
+        );
+
+        // Other addresses and routes have their own counters
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("2.2.2.2"), None, 0)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            limiter
+                .check("/other", Some("1.1.1.1"), None, 0)
+                .await
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("1.1.1.1"), None, 1_000)
+                .await
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("1.1.1.1"), None, 2_000)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_per_referral() {
+        let limiter = rate_limiter(Some(limit(60, None)), Some(limit(1, None)));
+
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("1.1.1.1"), Some("pro"), 0)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("2.2.2.2"), Some("pro"), 0)
+                .await
+                .unwrap(),
+            Some(60)
+        );
+        assert_eq!(
+            limiter
+                .check(ROUTE, Some("2.2.2.2"), None, 0)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_new_ignores_zero_limits() {
+        let limiter = rate_limiter(Some(limit(0, None)), Some(limit(1, None)));
+
+        let config = limiter.limits.get(ROUTE).unwrap();
+        assert_eq!(config.per_ip, None);
+        assert_eq!(config.per_referral, Some(limit(1, None)));
+    }
+
+    #[rstest]
+    #[case::none(None, None)]
+    #[case::single(Some("1.1.1.1"), Some("1.1.1.1"))]
+    #[case::rightmost_untrusted(Some("1.1.1.1, 2.2.2.2"), Some("2.2.2.2"))]
+    #[case::skips_trusted(Some(" 1.1.1.1 , 10.0.0.1"), Some("1.1.1.1"))]
+    #[case::only_trusted(Some("10.0.0.1"), None)]
+    #[case::invalid(Some("1.1.1.1, invalid, 10.0.0.1"), None)]
+    #[case::empty(Some(""), None)]
+    fn test_forwarded_for(#[case] header: Option<&str>, #[case] expected: Option<&str>) {
+        let mut headers = HeaderMap::new();
+        if let Some(header) = header {
+            headers.insert("x-forwarded-for", header.parse().unwrap());
+        }
+
+        assert_eq!(
+            forwarded_for(&headers, &[PROXY.parse().unwrap()]),
+            expected.map(|ip| ip.parse().unwrap())
+        );
+    }
+
+    #[rstest]
+    #[case::no_proxy_configured(&[], PROXY, Some("1.1.1.1"), Some(PROXY))]
+    #[case::untrusted_peer(&[PROXY], "2.2.2.2", Some("1.1.1.1"), Some("2.2.2.2"))]
+    #[case::trusted_peer(&[PROXY], PROXY, Some("1.1.1.1"), Some("1.1.1.1"))]
+    #[case::trusted_peer_no_header(&[PROXY], PROXY, None, Some(PROXY))]
+    #[case::mapped_peer(&[PROXY], "::ffff:10.0.0.1", Some("1.1.1.1"), Some("1.1.1.1"))]
+    fn test_client_ip(
+        #[case] trusted_proxies: &[&str],
+        #[case] peer: &str,
+        #[case] header: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        let mut request = Request::builder().uri("/");
+        if let Some(header) = header {
+            request = request.header(HEADER_FORWARDED_FOR, header);
+        }
+        let mut request = request.body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 1234)));
+
+        let trusted_proxies = trusted_proxies
+            .iter()
+            .map(|proxy| proxy.parse().unwrap())
+            .collect::<Vec<IpAddr>>();
+        assert_eq!(
+            client_ip(&request, &trusted_proxies),
+            expected.map(|ip| ip.to_string())
+        );
+    }
+
+    #[test]
+    fn test_client_ip_no_connect_info() {
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        assert_eq!(client_ip(&request, &[]), None);
+    }
+
+    fn referral_service() -> Service {
+        let mut referral_helper = MockReferralHelper::new();
+        referral_helper
+            .expect_get_credentials()
+            .returning(|api_key| {
+                Ok((api_key == API_KEY).then(|| ReferralCredentials {
+                    id: "pro".to_string(),
+                    apiKey: API_KEY.to_string(),
+                    apiSecret: API_SECRET.to_string(),
+                }))
+            });
+
+        let mut service = Service::new_mocked_prometheus(false);
+        service.referral_swaps = ReferralSwaps::new(
+            Arc::new(referral_helper),
+            Arc::new(MockSwapHelper::new()),
+            Arc::new(MockChainSwapHelper::new()),
+            Arc::new(MockReverseSwapHelper::new()),
+        );
+        service
+    }
+
+    fn setup_router(rate_limiter: Option<RateLimiter>) -> Router {
+        let (status_tx, _) = tokio::sync::broadcast::channel::<(Option<u64>, Vec<SwapStatus>)>(1);
+
+        Router::new()
+            .route(
+                ROUTE,
+                get(|| async { "ok" }).post(|body: String| async move { body }),
+            )
+            .route_layer(axum::middleware::from_fn(
+                rate_limit_middleware::<Fetcher, MockManager>,
+            ))
+            .layer(Extension(Arc::new(ServerState {
+                manager: Arc::new(MockManager::new()),
+                service: Arc::new(referral_service()),
+                swap_status_update_tx: status_tx.clone(),
+                swap_infos: Fetcher { status_tx },
+                rate_limiter,
+            })))
+    }
+
+    fn request_builder(method: Method, ip: &str) -> axum::http::request::Builder {
+        Request::builder()
+            .method(method)
+            .uri(PATH)
+            .header(HEADER_FORWARDED_FOR, ip)
+            .extension(ConnectInfo(SocketAddr::new(PROXY.parse().unwrap(), 1234)))
+    }
+
+    async fn request(router: &Router, ip: &str) -> Response {
+        router
+            .clone()
+            .oneshot(
+                request_builder(Method::GET, ip)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn referral_request(router: &Router, method: Method, body: &str) -> Response {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(API_SECRET.as_bytes()).unwrap();
+        mac.update(format!("{ts}{method}{PATH}").as_bytes());
+        mac.update(body.as_bytes());
+
+        router
+            .clone()
+            .oneshot(
+                request_builder(method, "1.1.1.1")
+                    .header(HEADER_TS, ts.to_string())
+                    .header(HEADER_API_KEY, API_KEY)
+                    .header(HEADER_API_HMAC, hex::encode(mac.finalize().into_bytes()))
+                    .header(CONTENT_LENGTH, body.len())
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware() {
+        let router = setup_router(Some(rate_limiter(Some(limit(1, None)), None)));
+
+        assert_eq!(request(&router, "1.1.1.1").await.status(), StatusCode::OK);
+
+        let res = request(&router, "1.1.1.1").await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after = res
+            .headers()
+            .get(RETRY_AFTER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse::<u64>()
+            .unwrap();
+        assert!((1..=60).contains(&retry_after));
+
+        assert_eq!(request(&router, "2.2.2.2").await.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware_unauthenticated_referral() {
+        let router = setup_router(Some(rate_limiter(None, Some(limit(1, None)))));
+
+        for _ in 0..3 {
+            let res = router
+                .clone()
+                .oneshot(
+                    request_builder(Method::GET, "1.1.1.1")
+                        .header(Referral::name(), "pro")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    #[rstest]
+    #[case::get(Method::GET, "")]
+    #[case::post(Method::POST, r#"{"some":"data"}"#)]
+    #[tokio::test]
+    async fn test_rate_limit_middleware_authenticated_referral(
+        #[case] method: Method,
+        #[case] body: &str,
+    ) {
+        let router = setup_router(Some(rate_limiter(None, Some(limit(1, None)))));
+
+        let res = referral_request(&router, method.clone(), body).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        if method == Method::POST {
+            let received = res.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(std::str::from_utf8(&received).unwrap(), body);
+        }
+
+        assert_eq!(
+            referral_request(&router, method, body).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware_not_configured() {
+        let router = setup_router(None);
+
+        for _ in 0..3 {
+            assert_eq!(request(&router, "1.1.1.1").await.status(), StatusCode::OK);
+        }
+    }
+}
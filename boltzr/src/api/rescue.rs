@@ -229,6 +229,7 @@ mod test {
                 service: Arc::new(Service::new_mocked_prometheus(false)),
                 swap_status_update_tx: status_tx.clone(),
                 swap_infos: Fetcher { status_tx },
+                rate_limiter: None,
             },
         )))
     }
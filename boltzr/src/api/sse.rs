@@ -1,17 +1,33 @@
 use crate::api::ServerState;
-use crate::api::ws::status::SwapInfos;
+use crate::api::errors::{ApiError, AxumError};
+use crate::api::headers::referral_auth;
+use crate::api::ws::status::{DEFAULT_MAX_SWAP_UPDATE_IDS_PER_MESSAGE, SwapInfos};
 use crate::api::ws::types::SwapStatus;
+use crate::service::{AuthenticationError, Replay};
+use crate::swap::SwapUpdate;
 use crate::swap::manager::SwapManager;
 use async_stream::try_stream;
+use axum::extract::OriginalUri;
+use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::sse::{Event, Sse};
-use axum::{Extension, extract::Query};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json, extract::Query};
 use futures_util::stream::Stream;
 use serde::Deserialize;
+use std::collections::HashSet;
 use std::convert::Infallible;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::Receiver;
 use tracing::{error, trace};
 
+const HEADER_LAST_EVENT_ID: &str = "Last-Event-ID";
+
+/// Maximal number of swaps a stream can follow after the pending swaps of a
+/// referral were added to the requested ones
+const MAX_REFERRAL_SWAP_IDS: usize = 1_000;
+
 struct SseGuard;
 
 impl Drop for SseGuard {
@@ -23,43 +39,164 @@ impl Drop for SseGuard {
 
 #[derive(Deserialize, Debug)]
 pub struct IdParams {
-    pub id: String,
+    /// Comma separated list of swap ids
+    pub id: Option<String>,
 }
 
 pub async fn sse_handler<S, M>(
     Extension(state): Extension<Arc<ServerState<S, M>>>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
     Query(params): Query<IdParams>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>>
+) -> Result<Response, AxumError>
 where
     S: SwapInfos + Send + Sync + Clone + 'static,
     M: SwapManager + Send + Sync + 'static,
 {
-    trace!("New SSE status stream for swap: {}", params.id);
+    let mut ids = parse_ids(params.id.as_deref());
+    if ids.len() > DEFAULT_MAX_SWAP_UPDATE_IDS_PER_MESSAGE {
+        return Ok(bad_request(format!(
+            "too many swap ids: max {DEFAULT_MAX_SWAP_UPDATE_IDS_PER_MESSAGE}"
+        )));
+    }
+
+    let referral = match referral_auth(&headers) {
+        Ok(Some(auth)) => {
+            let path = uri
+                .path_and_query()
+                .map(|path| path.as_str())
+                .unwrap_or(uri.path());
+
+            match state.service.referral_swaps.authenticate(
+                &auth,
+                Method::GET.as_str(),
+                path,
+                &[],
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            ) {
+                Ok(referral) => Some(referral),
+                Err(err) if err.is::<AuthenticationError>() => {
+                    return Err(AxumError::new(StatusCode::UNAUTHORIZED, err));
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(None) => None,
+        Err(err) => return Err(AxumError::new(StatusCode::UNAUTHORIZED, err)),
+    };
+
+    if ids.is_empty() && referral.is_none() {
+        return Ok(bad_request("no swap id provided".to_string()));
+    }
+
+    let connection_id = sse_id(&match &referral {
+        Some(referral) => format!("{}|referral:{}", ids.join(","), referral),
+        None => ids.join(","),
+    });
+
+    if let Some(referral) = &referral {
+        for id in state.service.referral_swaps.pending_swap_ids(referral)? {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+
+        if ids.len() > MAX_REFERRAL_SWAP_IDS {
+            return Ok(bad_request(format!(
+                "too many pending swaps: max {MAX_REFERRAL_SWAP_IDS}"
+            )));
+        }
+    }
+
+    trace!(
+        "New SSE status stream for {} swaps{}",
+        ids.len(),
+        referral
+            .as_ref()
+            .map(|referral| format!(" of referral {referral}"))
+            .unwrap_or_default()
+    );
 
     #[cfg(feature = "metrics")]
     metrics::gauge!(crate::metrics::SSE_OPEN_COUNT).increment(1);
 
-    let connection_id = sse_id(&params.id);
-    let mut rx = state.swap_status_update_tx.subscribe();
-    let initial_update = match state
-        .swap_infos
-        .fetch_status_info(connection_id, vec![params.id.clone()])
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            error!("Error fetching initial swap update for SSE: {}", err);
-            None
+    // Subscribe before reading the replay buffer, so that no update falls in between
+    let rx = state.swap_status_update_tx.subscribe();
+
+    let replay = match last_event_id(&headers) {
+        Some(cursor) => match state.service.status_replay.since(cursor).await {
+            Ok(replay) => replay,
+            Err(err) => {
+                error!("Could not replay swap updates for SSE: {}", err);
+                None
+            }
+        },
+        None => None,
+    };
+
+    // When the missed updates cannot be replayed, the latest status is the next best thing
+    let initial_update = if replay.is_none() {
+        match state
+            .swap_infos
+            .fetch_status_info(connection_id, ids.clone())
+            .await
+        {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Error fetching initial swap update for SSE: {}", err);
+                None
+            }
         }
+    } else {
+        None
     };
 
-    Sse::new(try_stream! {
+    Ok(Sse::new(status_stream(
+        state,
+        rx,
+        connection_id,
+        ids.into_iter().collect(),
+        initial_update,
+        replay,
+        referral,
+    ))
+    .into_response())
+}
+
+fn status_stream<S, M>(
+    state: Arc<ServerState<S, M>>,
+    mut rx: Receiver<(Option<u64>, Vec<SwapStatus>)>,
+    connection_id: u64,
+    mut subscribed: HashSet<String>,
+    initial_update: Option<Vec<SwapStatus>>,
+    replay: Option<Replay>,
+    referral: Option<String>,
+) -> impl Stream<Item = Result<Event, Infallible>>
+where
+    S: Send + Sync + 'static,
+    M: Send + Sync + 'static,
+{
+    try_stream! {
         let _guard = SseGuard;
 
         if let Some(updates) = initial_update {
             for update in updates {
-                if let Some(data) = serialize_update(&update) {
-                    yield Event::default().data(data);
+                if let Some(event) = update_event(&update) {
+                    yield event;
+                }
+            }
+        }
+
+        // Everything up to the head of the replay was already received from the buffer
+        let replayed_head = replay.as_ref().map(|replay| replay.head);
+        if let Some(replay) = replay {
+            for update in replay.updates {
+                if !should_forward(&state, &mut subscribed, referral.as_deref(), &update).await {
+                    continue;
+                }
+
+                if let Some(event) = update_event(&update) {
+                    yield event;
                 }
             }
         }
@@ -72,12 +209,19 @@ where
                     }
 
                     for e in events {
-                        if e.id != params.id {
+                        if e.event_id
+                            .zip(replayed_head)
+                            .is_some_and(|(event_id, head)| event_id <= head)
+                        {
+                            continue;
+                        }
+
+                        if !should_forward(&state, &mut subscribed, referral.as_deref(), &e).await {
                             continue;
                         }
 
-                        if let Some(data) = serialize_update(&e) {
-                            yield Event::default().data(data);
+                        if let Some(event) = update_event(&e) {
+                            yield event;
                         }
                     }
                 },
@@ -87,12 +231,86 @@ where
                 }
             }
         }
-    })
+    }
+}
+
+async fn should_forward<S, M>(
+    state: &ServerState<S, M>,
+    subscribed: &mut HashSet<String>,
+    referral: Option<&str>,
+    update: &SwapStatus,
+) -> bool {
+    if subscribed.contains(&update.id) {
+        return true;
+    }
+
+    let Some(referral) = referral else {
+        return false;
+    };
+    if !is_new_referral_swap(state, referral, update).await {
+        return false;
+    }
+
+    subscribed.insert(update.id.clone());
+    true
+}
+
+/// Only the first update of a swap is checked, so that the database is not
+/// queried for every update of swaps of other referrals
+async fn is_new_referral_swap<S, M>(
+    state: &ServerState<S, M>,
+    referral: &str,
+    update: &SwapStatus,
+) -> bool {
+    if !matches!(
+        SwapUpdate::parse(&update.base.status),
+        SwapUpdate::SwapCreated | SwapUpdate::InvoiceSet
+    ) {
+        return false;
+    }
+
+    match state
+        .service
+        .referral_swaps
+        .is_referral_swap(referral, &update.id)
+        .await
+    {
+        Ok(res) => res,
+        Err(err) => {
+            error!(
+                "Could not check if swap {} belongs to referral {}: {}",
+                update.id, referral, err
+            );
+            false
+        }
+    }
+}
+
+fn parse_ids(ids: Option<&str>) -> Vec<String> {
+    let mut parsed: Vec<String> = Vec::new();
+    for id in ids.unwrap_or_default().split(',') {
+        let id = id.trim();
+        if !id.is_empty() && !parsed.iter().any(|existing| existing == id) {
+            parsed.push(id.to_string());
+        }
+    }
+
+    parsed
 }
 
-fn serialize_update(update: &SwapStatus) -> Option<String> {
+fn bad_request(error: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(ApiError { error })).into_response()
+}
+
+fn update_event(update: &SwapStatus) -> Option<Event> {
     match serde_json::to_string(update) {
-        Ok(data) => Some(data),
+        Ok(data) => {
+            let event = Event::default().data(data);
+            Some(match update.event_id {
+                Some(event_id) => event.id(event_id.to_string()),
+                None => event,
+            })
+        }
         Err(err) => {
             error!("Could not serialize swap update: {}", err);
             None
@@ -100,6 +318,13 @@ fn serialize_update(update: &SwapStatus) -> Option<String> {
     }
 }
 
+fn last_event_id(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(HEADER_LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
 fn sse_id(swap_id: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     format!("sse-{swap_id}").hash(&mut hasher);
@@ -109,11 +334,29 @@ fn sse_id(swap_id: &str) -> u64 {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::api::test::start;
+    use crate::api::headers::{HEADER_API_HMAC, HEADER_API_KEY, HEADER_TS};
+    use crate::api::test::{Fetcher, start};
     use crate::api::ws::types::{SwapStatus, SwapStatusNoId};
+    use crate::db::helpers::chain_swap::test::MockChainSwapHelper;
+    use crate::db::helpers::referral::test::MockReferralHelper;
+    use crate::db::helpers::reverse_swap::test::MockReverseSwapHelper;
+    use crate::db::helpers::swap::test::MockSwapHelper;
+    use crate::db::models::{ReferralCredentials, Swap};
+    use crate::db::schema::swaps;
+    use crate::service::ReferralSwaps;
+    use diesel::QueryDsl;
+    use diesel::debug_query;
+    use diesel::pg::Pg;
+    use hmac::{Hmac, KeyInit, Mac};
     use reqwest::Response;
+    use rstest::rstest;
+    use sha2::Sha256;
+    use tokio::sync::broadcast::Sender;
     use tokio_util::sync::CancellationToken;
 
+    const API_KEY: &str = "key";
+    const API_SECRET: &str = "secret";
+
     fn parse_sse_data(frame: &str) -> Option<String> {
         let data: Vec<String> = frame
             .lines()
@@ -130,14 +373,27 @@ mod test {
         }
     }
 
+    fn parse_sse_id(frame: &str) -> Option<String> {
+        frame
+            .lines()
+            .find_map(|line| line.strip_prefix("id:"))
+            .map(|value| value.trim_start().to_string())
+    }
+
     async fn next_sse_data(response: &mut Response, buffer: &mut String) -> Option<String> {
+        next_sse_frame(response, buffer)
+            .await
+            .and_then(|frame| parse_sse_data(&frame))
+    }
+
+    async fn next_sse_frame(response: &mut Response, buffer: &mut String) -> Option<String> {
         loop {
             if let Some(pos) = buffer.find("\n\n") {
                 let frame = buffer[..pos].to_string();
                 buffer.drain(..pos + 2);
 
-                if let Some(data) = parse_sse_data(&frame) {
-                    return Some(data);
+                if parse_sse_data(&frame).is_some() {
+                    return Some(frame);
                 }
                 continue;
             }
@@ -176,6 +432,7 @@ mod test {
                     status: "swap.created".to_string(),
                     ..Default::default()
                 },
+                event_id: None,
             }
         );
 
@@ -189,6 +446,7 @@ mod test {
                             status: "ignored".to_string(),
                             ..Default::default()
                         },
+                        event_id: None,
                     },
                     SwapStatus {
                         id: id.to_string(),
@@ -196,6 +454,7 @@ mod test {
                             status: "new.status".to_string(),
                             ..Default::default()
                         },
+                        event_id: None,
                     },
                 ],
             ))
@@ -212,6 +471,7 @@ mod test {
                     status: "new.status".to_string(),
                     ..Default::default()
                 },
+                event_id: None,
             }
         );
 
@@ -243,6 +503,7 @@ mod test {
                     status: "swap.created".to_string(),
                     ..Default::default()
                 },
+                event_id: None,
             }
         );
 
@@ -255,6 +516,7 @@ mod test {
                         status: "ignored".to_string(),
                         ..Default::default()
                     },
+                    event_id: None,
                 }],
             ))
             .unwrap();
@@ -268,6 +530,7 @@ mod test {
                         status: "new.status".to_string(),
                         ..Default::default()
                     },
+                    event_id: None,
                 }],
             ))
             .unwrap();
@@ -283,12 +546,389 @@ mod test {
                     status: "new.status".to_string(),
                     ..Default::default()
                 },
+                event_id: None,
             }
         );
 
         cancel.cancel();
     }
 
+    #[tokio::test]
+    async fn test_sse_handler_multiple_ids() {
+        let port = 13_101;
+        let (cancel, status_tx) = start(port).await;
+
+        let mut response = reqwest::get(format!(
+            "http://127.0.0.1:{port}/streamswapstatus?id=swap1,swap2,swap1"
+        ))
+        .await
+        .unwrap();
+        assert!(response.status().is_success());
+
+        let mut buffer = String::new();
+
+        for id in ["swap1", "swap2"] {
+            let data = next_sse_data(&mut response, &mut buffer)
+                .await
+                .expect("did not receive initial SSE event");
+            assert_eq!(
+                serde_json::from_str::<SwapStatus>(&data).unwrap(),
+                SwapStatus::new(id.to_string(), "swap.created".to_string())
+            );
+        }
+
+        status_tx
+            .send((
+                None,
+                vec![
+                    SwapStatus::new("swap2".to_string(), "invoice.set".to_string()),
+                    SwapStatus::new("ignored".to_string(), "invoice.set".to_string()),
+                    SwapStatus::new("swap1".to_string(), "invoice.set".to_string()),
+                ],
+            ))
+            .unwrap();
+
+        for id in ["swap2", "swap1"] {
+            let data = next_sse_data(&mut response, &mut buffer)
+                .await
+                .expect("did not receive SSE event");
+            assert_eq!(
+                serde_json::from_str::<SwapStatus>(&data).unwrap(),
+                SwapStatus::new(id.to_string(), "invoice.set".to_string())
+            );
+        }
+
+        cancel.cancel();
+    }
+
+    #[rstest]
+    #[case::no_id(13_102, String::new(), "no swap id provided")]
+    #[case::empty_ids(13_103, "?id=,".to_string(), "no swap id provided")]
+    #[case::too_many_ids(
+        13_104,
+        format!(
+            "?id={}",
+            (0..=DEFAULT_MAX_SWAP_UPDATE_IDS_PER_MESSAGE)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        "too many swap ids: max 100"
+    )]
+    #[tokio::test]
+    async fn test_sse_handler_invalid_ids(
+        #[case] port: u16,
+        #[case] query: String,
+        #[case] error: &str,
+    ) {
+        let (cancel, _) = start(port).await;
+
+        let response = reqwest::get(format!("http://127.0.0.1:{port}/streamswapstatus{query}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.json::<ApiError>().await.unwrap().error, error);
+
+        cancel.cancel();
+    }
+
+    fn referral_service(pending: usize) -> crate::service::Service {
+        let mut referral_helper = MockReferralHelper::new();
+        referral_helper
+            .expect_get_credentials()
+            .returning(|api_key| {
+                Ok(if api_key == API_KEY {
+                    Some(ReferralCredentials {
+                        id: "pro".to_string(),
+                        apiKey: API_KEY.to_string(),
+                        apiSecret: API_SECRET.to_string(),
+                    })
+                } else {
+                    None
+                })
+            });
+
+        // The pending swap of the referral is found when querying by status,
+        // "new" is the only other swap of the referral
+        let mut swap_helper = MockSwapHelper::new();
+        swap_helper.expect_get_all().returning(|condition| {
+            let sql = debug_query::<Pg, _>(&swaps::table.filter(condition)).to_string();
+            let (_, filter) = sql.split_once(" WHERE ").unwrap();
+            Ok(if filter.contains(r#""swaps"."status""#) {
+                (0..pending)
+                    .map(|i| Swap {
+                        id: match i {
+                            0 => "pending".to_string(),
+                            i => format!("pending{i}"),
+                        },
+                        ..Default::default()
+                    })
+                    .collect()
+            } else if filter.contains(r#""new""#) {
+                vec![Swap {
+                    id: "new".to_string(),
+                    ..Default::default()
+                }]
+            } else {
+                vec![]
+            })
+        });
+
+        let mut reverse_swap_helper = MockReverseSwapHelper::new();
+        reverse_swap_helper
+            .expect_get_all()
+            .returning(|_| Ok(vec![]));
+
+        let mut chain_swap_helper = MockChainSwapHelper::new();
+        chain_swap_helper.expect_get_all().returning(|_| Ok(vec![]));
+
+        let mut service = crate::service::Service::new_mocked_prometheus(false);
+        service.referral_swaps = ReferralSwaps::new(
+            Arc::new(referral_helper),
+            Arc::new(swap_helper),
+            Arc::new(chain_swap_helper),
+            Arc::new(reverse_swap_helper),
+        );
+        service
+    }
+
+    async fn start_referral(
+        port: u16,
+        pending: usize,
+    ) -> (CancellationToken, Sender<(Option<u64>, Vec<SwapStatus>)>) {
+        let cancel = CancellationToken::new();
+        let (status_tx, _) = tokio::sync::broadcast::channel::<(Option<u64>, Vec<SwapStatus>)>(16);
+
+        let server = crate::api::Server::new(
+            crate::api::Config {
+                port,
+                host: "127.0.0.1".to_string(),
+                rate_limits: None,
+                trusted_proxies: None,
+            },
+            cancel.clone(),
+            Arc::new(crate::swap::manager::test::MockManager::new()),
+            Arc::new(referral_service(pending)),
+            boltz_cache::Cache::Memory(boltz_cache::MemCache::new()),
+            Fetcher {
+                status_tx: status_tx.clone(),
+            },
+            status_tx.clone(),
+        );
+
+        tokio::spawn(async move {
+            #[cfg(feature = "metrics")]
+            server.start(None).await.unwrap();
+
+            #[cfg(not(feature = "metrics"))]
+            server.start().await.unwrap();
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        (cancel, status_tx)
+    }
+
+    fn referral_request(port: u16, path: &str, secret: &str) -> reqwest::RequestBuilder {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{ts}GET{path}").as_bytes());
+
+        reqwest::Client::new()
+            .get(format!("http://127.0.0.1:{port}{path}"))
+            .header(HEADER_TS, ts.to_string())
+            .header(HEADER_API_KEY, API_KEY)
+            .header(HEADER_API_HMAC, hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[tokio::test]
+    async fn test_sse_handler_referral() {
+        let port = 13_105;
+        let (cancel, status_tx) = start_referral(port, 1).await;
+
+        let mut response = referral_request(port, "/streamswapstatus?id=swap1", API_SECRET)
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        let mut buffer = String::new();
+
+        for id in ["swap1", "pending"] {
+            let data = next_sse_data(&mut response, &mut buffer)
+                .await
+                .expect("did not receive initial SSE event");
+            assert_eq!(
+                serde_json::from_str::<SwapStatus>(&data).unwrap(),
+                SwapStatus::new(id.to_string(), "swap.created".to_string())
+            );
+        }
+
+        status_tx
+            .send((
+                None,
+                vec![
+                    SwapStatus::new("other".to_string(), "swap.created".to_string()),
+                    SwapStatus::new("new".to_string(), "swap.created".to_string()),
+                    SwapStatus::new("new".to_string(), "transaction.mempool".to_string()),
+                ],
+            ))
+            .unwrap();
+
+        for status in ["swap.created", "transaction.mempool"] {
+            let data = next_sse_data(&mut response, &mut buffer)
+                .await
+                .expect("did not receive SSE event");
+            assert_eq!(
+                serde_json::from_str::<SwapStatus>(&data).unwrap(),
+                SwapStatus::new("new".to_string(), status.to_string())
+            );
+        }
+
+        cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_sse_handler_referral_unauthorized() {
+        let port = 13_106;
+        let (cancel, _) = start_referral(port, 1).await;
+
+        let response = referral_request(port, "/streamswapstatus", "wrong secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.json::<ApiError>().await.unwrap().error,
+            "unauthorized"
+        );
+
+        cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_sse_handler_referral_too_many_pending() {
+        let port = 13_108;
+        let (cancel, _) = start_referral(port, MAX_REFERRAL_SWAP_IDS + 1).await;
+
+        let response = referral_request(port, "/streamswapstatus", API_SECRET)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.json::<ApiError>().await.unwrap().error,
+            format!("too many pending swaps: max {MAX_REFERRAL_SWAP_IDS}")
+        );
+
+        cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_sse_handler_last_event_id() {
+        let port = 13_107;
+        let cancel = CancellationToken::new();
+        let (status_tx, _) = tokio::sync::broadcast::channel::<(Option<u64>, Vec<SwapStatus>)>(16);
+
+        let service = crate::service::Service::new_mocked_prometheus(false);
+        let recorded = service
+            .status_replay
+            .record(vec![
+                SwapStatus::new("swap1".to_string(), "swap.created".to_string()),
+                SwapStatus::new("other".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap1".to_string(), "transaction.mempool".to_string()),
+            ])
+            .await
+            .unwrap();
+
+        let server = crate::api::Server::new(
+            crate::api::Config {
+                port,
+                host: "127.0.0.1".to_string(),
+                rate_limits: None,
+                trusted_proxies: None,
+            },
+            cancel.clone(),
+            Arc::new(crate::swap::manager::test::MockManager::new()),
+            Arc::new(service),
+            boltz_cache::Cache::Memory(boltz_cache::MemCache::new()),
+            Fetcher {
+                status_tx: status_tx.clone(),
+            },
+            status_tx.clone(),
+        );
+
+        tokio::spawn(async move {
+            #[cfg(feature = "metrics")]
+            server.start(None).await.unwrap();
+
+            #[cfg(not(feature = "metrics"))]
+            server.start().await.unwrap();
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let mut response = reqwest::Client::new()
+            .get(format!("http://127.0.0.1:{port}/streamswapstatus?id=swap1"))
+            .header(HEADER_LAST_EVENT_ID, "1")
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        let mut buffer = String::new();
+
+        // The replayed update is sent instead of the latest status
+        let frame = next_sse_frame(&mut response, &mut buffer)
+            .await
+            .expect("did not receive replayed SSE event");
+        assert_eq!(parse_sse_id(&frame), Some("3".to_string()));
+        assert_eq!(
+            serde_json::from_str::<SwapStatus>(&parse_sse_data(&frame).unwrap()).unwrap(),
+            recorded[2]
+        );
+
+        let mut live = SwapStatus::new("swap1".to_string(), "transaction.confirmed".to_string());
+        live.event_id = Some(4);
+        status_tx
+            .send((None, vec![recorded[2].clone(), live.clone()]))
+            .unwrap();
+
+        let frame = next_sse_frame(&mut response, &mut buffer)
+            .await
+            .expect("did not receive live SSE event");
+        assert_eq!(parse_sse_id(&frame), Some("4".to_string()));
+        assert_eq!(
+            serde_json::from_str::<SwapStatus>(&parse_sse_data(&frame).unwrap()).unwrap(),
+            live
+        );
+
+        cancel.cancel();
+    }
+
+    #[test]
+    fn test_last_event_id() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(last_event_id(&headers), None);
+
+        headers.insert("last-event-id", "21".parse().unwrap());
+        assert_eq!(last_event_id(&headers), Some(21));
+
+        headers.insert("last-event-id", "invalid".parse().unwrap());
+        assert_eq!(last_event_id(&headers), None);
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(parse_ids(None), Vec::<String>::new());
+        assert_eq!(
+            parse_ids(Some(" a,b ,,a")),
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+
     #[test]
     fn test_sse_id() {
         assert_eq!(sse_id("asdf"), sse_id("asdf"));
@@ -323,6 +963,7 @@ mod test {
                     status: "swap.created".to_string(),
                     ..Default::default()
                 },
+                event_id: None,
             },
             SwapStatus {
                 id: "swap1".to_string(),
@@ -336,6 +977,7 @@ mod test {
                     }),
                     ..Default::default()
                 },
+                event_id: None,
             },
         ];
 
@@ -344,10 +986,13 @@ mod test {
             crate::api::Config {
                 port,
                 host: "127.0.0.1".to_string(),
+                rate_limits: None,
+                trusted_proxies: None,
             },
             cancel.clone(),
             Arc::new(crate::swap::manager::test::MockManager::new()),
             Arc::new(crate::service::Service::new_mocked_prometheus(false)),
+            boltz_cache::Cache::Memory(boltz_cache::MemCache::new()),
             InitialUpdateFetcher {
                 updates: initial_updates.clone(),
             },
@@ -406,10 +1051,13 @@ mod test {
             crate::api::Config {
                 port,
                 host: "127.0.0.1".to_string(),
+                rate_limits: None,
+                trusted_proxies: None,
             },
             cancel.clone(),
             Arc::new(crate::swap::manager::test::MockManager::new()),
             Arc::new(crate::service::Service::new_mocked_prometheus(false)),
+            boltz_cache::Cache::Memory(boltz_cache::MemCache::new()),
             ErrorFetcher,
             status_tx.clone(),
         );
@@ -436,6 +1084,7 @@ mod test {
                 status: "transaction.mempool".to_string(),
                 ..Default::default()
             },
+            event_id: None,
         };
 
         tokio::spawn(async move {
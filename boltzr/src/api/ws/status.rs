@@ -6,6 +6,7 @@ use crate::api::ws::types::{
     ErrorResponse, SubscribeResponse, SubscriptionChannel, SwapStatus,
     SwapUpdateSubscriptionRequest, UnsubscribeRequest, UnsubscribeResponse, UpdateResponse,
 };
+use crate::service::StatusReplay;
 use crate::webhook::InvoiceRequestCallData;
 use async_trait::async_trait;
 use async_tungstenite::tokio::accept_async;
@@ -14,6 +15,7 @@ use async_tungstenite::tungstenite::protocol::{CloseFrame, frame::coding::CloseC
 use futures::StreamExt;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH};
@@ -28,7 +30,7 @@ const PING_INTERVAL_SECS: u64 = 15;
 const ACTIVITY_CHECK_INTERVAL_SECS: u64 = 60;
 
 const ACTIVITY_TIMEOUT_SECS: u64 = 60 * 10;
-const DEFAULT_MAX_SWAP_UPDATE_IDS_PER_MESSAGE: usize = 100;
+pub const DEFAULT_MAX_SWAP_UPDATE_IDS_PER_MESSAGE: usize = 100;
 
 #[async_trait]
 pub trait SwapInfos {
@@ -129,6 +131,7 @@ pub struct Status<S> {
     message_limit: Option<MessageLimitConfig>,
     max_swap_update_ids_per_message: usize,
 
+    status_replay: StatusReplay,
     status_subscriptions: Arc<StatusSubscriptions>,
     offer_subscriptions: Arc<OfferSubscriptions>,
 }
@@ -142,6 +145,7 @@ where
         config: Config,
         swap_infos: S,
         swap_status_update_tx: tokio::sync::broadcast::Sender<(Option<u64>, Vec<SwapStatus>)>,
+        status_replay: StatusReplay,
         offer_subscriptions: OfferSubscriptions,
     ) -> Self {
         let Config {
@@ -160,6 +164,7 @@ where
             swap_infos,
             message_limit,
             max_swap_update_ids_per_message,
+            status_replay,
             status_subscriptions: Arc::new(StatusSubscriptions::new(
                 cancellation_token,
                 swap_status_update_tx,
@@ -237,6 +242,9 @@ where
             .clone()
             .map(|config| MessageRateLimiter::new(config, Instant::now()));
 
+        // Highest event id per swap that was sent to the connection from the replay buffer
+        let mut replayed: HashMap<String, u64> = HashMap::new();
+
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
 
         loop {
@@ -276,24 +284,39 @@ where
                                 }
 
                                 let res = match self.handle_message(connection_id, msg.as_ref()).await {
-                                    Ok(res) => res.map(|res| serde_json::to_string(&res)),
-                                    Err(res) => Some(serde_json::to_string(&res)),
+                                    Ok(res) => {
+                                        for res in &res {
+                                            if let WsResponse::Update(update) = res {
+                                                Self::track_replayed(&mut replayed, &update.args);
+                                            }
+                                        }
+
+                                        res.iter().map(serde_json::to_string).collect()
+                                    }
+                                    Err(res) => vec![serde_json::to_string(&res)],
                                 };
 
-                                if let Some(res) = res {
+                                let mut failed = false;
+                                for res in res {
                                     match res {
                                         Ok(res) => {
                                             if let Err(err) = ws_sender.send(Message::text(res)).await {
                                                 trace!("Could not send message: {}", err);
+                                                failed = true;
                                                 break;
                                             }
                                         },
                                         Err(err) => {
                                             trace!("Could not serialize message: {}", err);
+                                            failed = true;
                                             break;
                                         }
                                     }
                                 }
+
+                                if failed {
+                                    break;
+                                }
                             },
                             Message::Ping(payload) => {
                                 last_activity = Instant::now();
@@ -315,9 +338,20 @@ where
                 },
                 update = swap_status_update_rx.recv() => {
                     match update {
-                        Some(updates) => {
+                        Some(mut updates) => {
                             last_activity = Instant::now();
 
+                            // Updates that were replayed already might still be queued
+                            updates.retain(|update| {
+                                !update
+                                    .event_id
+                                    .zip(replayed.get(&update.id))
+                                    .is_some_and(|(event_id, replayed)| event_id <= *replayed)
+                            });
+                            if updates.is_empty() {
+                                continue;
+                            }
+
                             let timestamp = match Self::get_timestamp() {
                                 Ok(res) => res,
                                 Err(err) => {
@@ -409,7 +443,7 @@ where
         &self,
         connection_id: ConnectionId,
         msg: &[u8],
-    ) -> Result<Option<WsResponse>, ErrorResponse> {
+    ) -> Result<Vec<WsResponse>, ErrorResponse> {
         let msg = match serde_json::from_slice::<WsRequest>(msg) {
             Ok(msg) => msg,
             Err(err) => {
@@ -434,55 +468,78 @@ where
                 SubscribeRequest::SwapUpdate(sub) => {
                     let args = match self.validate_swap_update_ids(sub.args) {
                         Ok(args) => args,
-                        Err(err) => return Ok(Some(WsResponse::Error(err))),
+                        Err(err) => return Ok(vec![WsResponse::Error(err)]),
                     };
                     self.status_subscriptions
                         .subscription_added(connection_id, args.clone());
 
+                    let replay = match sub.since {
+                        Some(since) => self.replay_updates(since, &args).await,
+                        None => None,
+                    };
+
                     // Cache hits we directly inject back into the connection
-                    match self
-                        .swap_infos
-                        .fetch_status_info(connection_id, args.clone())
-                        .await
-                    {
-                        Ok(Some(updates)) => {
-                            self.status_subscriptions
-                                .inject_updates(connection_id, updates)
-                                .await;
-                        }
-                        Ok(None) => {}
-                        Err(err) => {
-                            tracing::warn!("Error fetching status info: {}", err);
+                    if replay.is_none() {
+                        match self
+                            .swap_infos
+                            .fetch_status_info(connection_id, args.clone())
+                            .await
+                        {
+                            Ok(Some(updates)) => {
+                                self.status_subscriptions
+                                    .inject_updates(connection_id, updates)
+                                    .await;
+                            }
+                            Ok(None) => {}
+                            Err(err) => {
+                                tracing::warn!("Error fetching status info: {}", err);
+                            }
                         }
                     }
 
-                    Ok(Some(WsResponse::Subscribe(SubscribeResponse {
-                        timestamp: match get_timestamp() {
-                            Some(time) => time,
-                            None => return Ok(None),
-                        },
+                    let timestamp = match get_timestamp() {
+                        Some(time) => time,
+                        None => return Ok(Vec::new()),
+                    };
+
+                    let mut responses = vec![WsResponse::Subscribe(SubscribeResponse {
+                        timestamp: timestamp.clone(),
                         args,
                         channel: SubscriptionChannel::SwapUpdate,
-                    })))
+                    })];
+
+                    // Replayed updates are sent right after the subscription is
+                    // confirmed and before any update that is queued for the connection
+                    if let Some(updates) = replay
+                        && !updates.is_empty()
+                    {
+                        responses.push(WsResponse::Update(UpdateResponse {
+                            timestamp,
+                            channel: SubscriptionChannel::SwapUpdate,
+                            args: updates,
+                        }));
+                    }
+
+                    Ok(responses)
                 }
                 SubscribeRequest::InvoiceRequest { args } => {
                     if let Err(err) = self
                         .offer_subscriptions
                         .offers_subscribe(connection_id, &args)
                     {
-                        return Ok(Some(WsResponse::Error(ErrorResponse {
+                        return Ok(vec![WsResponse::Error(ErrorResponse {
                             error: format!("could not subscribe to offers: {err}"),
-                        })));
+                        })]);
                     }
 
-                    Ok(Some(WsResponse::Subscribe(SubscribeResponse {
+                    Ok(vec![WsResponse::Subscribe(SubscribeResponse {
                         timestamp: match get_timestamp() {
                             Some(time) => time,
-                            None => return Ok(None),
+                            None => return Ok(Vec::new()),
                         },
                         channel: SubscriptionChannel::InvoiceRequest,
                         args: args.into_iter().map(|arg| arg.offer).collect(),
-                    })))
+                    })])
                 }
             },
             WsRequest::Invoice(invoice) => {
@@ -491,12 +548,12 @@ where
                         .offer_subscriptions
                         .received_invoice_response(id, Ok(invoice.invoice)),
                     Err(err) => {
-                        return Ok(Some(WsResponse::Error(ErrorResponse {
+                        return Ok(vec![WsResponse::Error(ErrorResponse {
                             error: format!("invalid invoice id: {err}"),
-                        })));
+                        })]);
                     }
                 };
-                Ok(None)
+                Ok(Vec::new())
             }
             WsRequest::InvoiceError(invoice_error) => {
                 match invoice_error.id.parse::<u64>() {
@@ -504,12 +561,12 @@ where
                         .offer_subscriptions
                         .received_invoice_response(id, Err(invoice_error.error)),
                     Err(err) => {
-                        return Ok(Some(WsResponse::Error(ErrorResponse {
+                        return Ok(vec![WsResponse::Error(ErrorResponse {
                             error: format!("invalid invoice id: {err}"),
-                        })));
+                        })]);
                     }
                 };
-                Ok(None)
+                Ok(Vec::new())
             }
             WsRequest::Unsubscribe(unsub) => {
                 let leftover_subscriptions = match unsub.channel {
@@ -523,24 +580,40 @@ where
                         {
                             Ok(res) => res,
                             Err(err) => {
-                                return Ok(Some(WsResponse::Error(ErrorResponse {
+                                return Ok(vec![WsResponse::Error(ErrorResponse {
                                     error: format!("could not unsubscribe from offers: {err}"),
-                                })));
+                                })]);
                             }
                         }
                     }
                 };
 
-                Ok(Some(WsResponse::Unsubscribe(UnsubscribeResponse {
+                Ok(vec![WsResponse::Unsubscribe(UnsubscribeResponse {
                     timestamp: match get_timestamp() {
                         Some(time) => time,
-                        None => return Ok(None),
+                        None => return Ok(Vec::new()),
                     },
                     channel: unsub.channel,
                     args: leftover_subscriptions,
-                })))
+                })])
+            }
+            WsRequest::Ping => Ok(vec![WsResponse::Pong]),
+        }
+    }
+
+    async fn replay_updates(&self, since: u64, swap_ids: &[String]) -> Option<Vec<SwapStatus>> {
+        match self.status_replay.since(since).await {
+            Ok(replay) => replay.map(|replay| {
+                replay
+                    .updates
+                    .into_iter()
+                    .filter(|update| swap_ids.contains(&update.id))
+                    .collect()
+            }),
+            Err(err) => {
+                warn!("Could not replay swap updates: {}", err);
+                None
             }
-            WsRequest::Ping => Ok(Some(WsResponse::Pong)),
         }
     }
 
@@ -577,6 +650,17 @@ where
         Ok(ids)
     }
 
+    fn track_replayed(replayed: &mut HashMap<String, u64>, updates: &[SwapStatus]) {
+        for update in updates {
+            if let Some(event_id) = update.event_id {
+                replayed
+                    .entry(update.id.clone())
+                    .and_modify(|replayed| *replayed = (*replayed).max(event_id))
+                    .or_insert(event_id);
+            }
+        }
+    }
+
     fn check_message_limit(
         message_limiter: &mut Option<MessageRateLimiter>,
         now: Instant,
@@ -603,9 +687,11 @@ mod status_test {
     };
     use crate::api::ws::types::{ErrorResponse, SubscriptionChannel, SwapStatus};
     use crate::api::ws::{Config, MessageLimitConfig, OfferSubscriptions};
+    use crate::service::StatusReplay;
     use async_trait::async_trait;
     use async_tungstenite::tungstenite::Message;
     use async_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use boltz_cache::{Cache, MemCache};
     use futures::StreamExt;
     use serde_json::json;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -1127,6 +1213,7 @@ mod status_test {
                 updates: cached_updates.clone(),
             },
             status_tx.clone(),
+            StatusReplay::new(Cache::Memory(MemCache::new())),
             OfferSubscriptions::new(crate::wallet::Network::Regtest),
         );
         tokio::spawn(async move {
@@ -1218,6 +1305,7 @@ mod status_test {
             ws_config(port),
             EmptyCacheFetcher,
             status_tx.clone(),
+            StatusReplay::new(Cache::Memory(MemCache::new())),
             OfferSubscriptions::new(crate::wallet::Network::Regtest),
         );
         tokio::spawn(async move {
@@ -1301,6 +1389,7 @@ mod status_test {
             ws_config(port),
             ErrorFetcher,
             status_tx.clone(),
+            StatusReplay::new(Cache::Memory(MemCache::new())),
             OfferSubscriptions::new(crate::wallet::Network::Regtest),
         );
         tokio::spawn(async move {
@@ -1534,6 +1623,97 @@ mod status_test {
         cancel.cancel();
     }
 
+    #[tokio::test]
+    async fn test_subscribe_since() {
+        let port = 12_015;
+        let cancel = CancellationToken::new();
+        let (status_tx, _status_rx) =
+            tokio::sync::broadcast::channel::<(Option<u64>, Vec<SwapStatus>)>(16);
+
+        let status_replay = StatusReplay::new(Cache::Memory(MemCache::new()));
+        let recorded = status_replay
+            .record(vec![
+                SwapStatus::new("ids".into(), "swap.created".into()),
+                SwapStatus::new("other".into(), "swap.created".into()),
+                SwapStatus::new("ids".into(), "invoice.set".into()),
+            ])
+            .await
+            .unwrap();
+
+        let status = Status::new(
+            cancel.clone(),
+            ws_config(port),
+            Fetcher {
+                status_tx: status_tx.clone(),
+            },
+            status_tx.clone(),
+            status_replay,
+            OfferSubscriptions::new(crate::wallet::Network::Regtest),
+        );
+        tokio::spawn(async move {
+            status.start().await.unwrap();
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let (client, _) = async_tungstenite::tokio::connect_async(format!("ws://127.0.0.1:{port}"))
+            .await
+            .unwrap();
+
+        let (mut tx, mut rx) = client.split();
+
+        tx.send(Message::text(
+            json!({
+                "op": "subscribe",
+                "channel": "swap.update",
+                "args": vec!["ids"],
+                "since": 1,
+            })
+            .to_string(),
+        ))
+        .await
+        .unwrap();
+
+        match next_response(&mut rx).await {
+            WsResponse::Subscribe(res) => assert_eq!(res.args, vec!["ids".to_string()]),
+            _ => panic!("expected subscribe response"),
+        }
+
+        // Only the missed update is replayed instead of the latest status
+        match next_response(&mut rx).await {
+            WsResponse::Update(res) => assert_eq!(res.args, vec![recorded[2].clone()]),
+            _ => panic!("expected replayed update"),
+        }
+
+        let mut live = SwapStatus::new("ids".into(), "transaction.mempool".into());
+        live.event_id = Some(4);
+        status_tx
+            .send((None, vec![recorded[2].clone(), live.clone()]))
+            .unwrap();
+
+        match next_response(&mut rx).await {
+            WsResponse::Update(res) => assert_eq!(res.args, vec![live]),
+            _ => panic!("expected live update"),
+        }
+
+        cancel.cancel();
+    }
+
+    async fn next_response<S>(rx: &mut S) -> WsResponse
+    where
+        S: futures::Stream<Item = Result<Message, async_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        loop {
+            let msg = tokio::time::timeout(Duration::from_secs(1), rx.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            if msg.is_text() {
+                return serde_json::from_str::<WsResponse>(msg.to_text().unwrap()).unwrap();
+            }
+        }
+    }
+
     async fn create_server(
         port: u16,
     ) -> (CancellationToken, Sender<(Option<u64>, Vec<SwapStatus>)>) {
@@ -1554,6 +1734,7 @@ mod status_test {
                 status_tx: status_tx.clone(),
             },
             status_tx.clone(),
+            StatusReplay::new(Cache::Memory(MemCache::new())),
             OfferSubscriptions::new(crate::wallet::Network::Regtest),
         );
         tokio::spawn(async move {
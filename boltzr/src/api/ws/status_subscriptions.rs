@@ -200,6 +200,7 @@ mod tests {
                 status: status.to_string(),
                 ..Default::default()
             },
+            event_id: None,
         }
     }
 
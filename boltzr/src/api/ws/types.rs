@@ -36,6 +36,7 @@ impl From<&SwapUpdate> for SwapStatus {
                 failure_reason: value.failure_reason.clone(),
                 failure_details: value.failure_details.map(|details| details.into()),
             },
+            event_id: None,
         }
     }
 }
@@ -0,0 +1,261 @@
+use crate::chain::Client;
+use crate::chain::types::{MempoolEntry, UnspentOutput};
+use anyhow::{Result, anyhow};
+use bitcoin::{
+    Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+    absolute::LockTime, consensus, transaction::Version,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::debug;
+
+// Conservative estimations that are used before the child is signed
+const TX_OVERHEAD_VSIZE: u64 = 11;
+const INPUT_VSIZE: u64 = 68;
+const OUTPUT_VSIZE: u64 = 43;
+
+const MIN_RELAY_FEE: f64 = 1.0;
+const INCREMENTAL_RELAY_FEE: f64 = 1.0;
+
+const DUST_LIMIT: u64 = 546;
+
+// After signing, the child is rebuilt with its actual size in case the estimation was off
+const MAX_SIGNING_ROUNDS: usize = 2;
+
+/// Fee rate in sat/vbyte at which miners value a transaction
+///
+/// When children are attached, the package of the transaction and its descendants
+/// is what competes for block space if that pays a higher fee rate than the transaction alone
+pub fn package_fee_rate(entry: &MempoolEntry) -> Result<f64> {
+    let own = Amount::from_btc(entry.fees.base)?.to_sat() as f64 / entry.vsize.max(1) as f64;
+    let package = Amount::from_btc(entry.fees.descendant)?.to_sat() as f64
+        / entry.descendant_size.max(1) as f64;
+
+    Ok(own.max(package))
+}
+
+/// Fee a child of `child_vsize` has to pay for the package with its parent to reach `fee_target`
+///
+/// `replaced_fee` are the fees paid by the current descendants of the parent which the
+/// child replaces; the child has to pay more than those to be accepted into the mempool
+pub fn child_fee(
+    fee_target: f64,
+    parent_fee: u64,
+    parent_vsize: u64,
+    child_vsize: u64,
+    replaced_fee: u64,
+) -> u64 {
+    let package_fee = (fee_target * (parent_vsize + child_vsize) as f64).ceil() as u64;
+    let mut fee = package_fee
+        .saturating_sub(parent_fee)
+        .max((MIN_RELAY_FEE * child_vsize as f64).ceil() as u64);
+
+    if replaced_fee > 0 {
+        fee = fee.max(replaced_fee + (INCREMENTAL_RELAY_FEE * child_vsize as f64).ceil() as u64);
+    }
+
+    fee
+}
+
+/// Bumps the fee of a transaction by spending one of its outputs with a high fee child
+///
+/// The child is funded and signed by the wallet of the node; when the output is too
+/// small to pay for the package, confirmed wallet UTXOs are added as inputs
+pub struct Cpfp {
+    chain_client: Arc<dyn Client + Send + Sync>,
+}
+
+impl Cpfp {
+    pub fn new(chain_client: Arc<dyn Client + Send + Sync>) -> Self {
+        Self { chain_client }
+    }
+
+    /// Returns the transaction id of the broadcast child
+    pub async fn bump(
+        &self,
+        parent: &Transaction,
+        vout: u32,
+        entry: &MempoolEntry,
+        fee_target: f64,
+    ) -> Result<String> {
+        let parent_id = parent.compute_txid();
+        let output = parent.output.get(vout as usize).ok_or(anyhow!(
+            "output {} of {} does not exist",
+            vout,
+            parent_id
+        ))?;
+
+        let parent_fee = Amount::from_btc(entry.fees.base)?.to_sat();
+        let replaced_fee = Amount::from_btc(entry.fees.descendant)?
+            .to_sat()
+            .saturating_sub(parent_fee);
+
+        let destination = bitcoin::Address::from_str(
+            &self
+                .chain_client
+                .get_new_address(None, &format!("CPFP of {}", parent_id), None)
+                .await?,
+        )?
+        .assume_checked()
+        .script_pubkey();
+
+        let mut inputs = vec![(OutPoint::new(parent_id, vout), output.value.to_sat())];
+        let mut wallet_utxos: Option<Vec<UnspentOutput>> = None;
+
+        let mut child_vsize = TX_OVERHEAD_VSIZE + INPUT_VSIZE + OUTPUT_VSIZE;
+        for _ in 0..MAX_SIGNING_ROUNDS {
+            let fee = loop {
+                let fee = child_fee(
+                    fee_target,
+                    parent_fee,
+                    entry.vsize,
+                    child_vsize,
+                    replaced_fee,
+                );
+                if Self::input_sum(&inputs) >= fee + DUST_LIMIT {
+                    break fee;
+                }
+
+                if wallet_utxos.is_none() {
+                    let mut utxos = self.chain_client.list_unspent(None).await?;
+                    utxos.sort_by(|a, b| a.amount.total_cmp(&b.amount));
+                    wallet_utxos = Some(utxos);
+                }
+
+                let utxo = wallet_utxos
+                    .as_mut()
+                    .and_then(|utxos| utxos.pop())
+                    .ok_or(anyhow!(
+                        "not enough wallet funds for a child paying {} sat",
+                        fee
+                    ))?;
+                inputs.push((
+                    OutPoint::new(Txid::from_str(&utxo.txid)?, utxo.vout),
+                    Amount::from_btc(utxo.amount)?.to_sat(),
+                ));
+                child_vsize += INPUT_VSIZE;
+            };
+
+            let child = self.sign(&inputs, &destination, fee).await?;
+            let actual_vsize = child.vsize() as u64;
+            if child_fee(
+                fee_target,
+                parent_fee,
+                entry.vsize,
+                actual_vsize,
+                replaced_fee,
+            ) <= fee
+            {
+                return self
+                    .chain_client
+                    .send_raw_transaction(&consensus::encode::serialize_hex(&child))
+                    .await;
+            }
+
+            debug!(
+                "CPFP child of {} is larger than estimated ({} > {} vbytes); rebuilding",
+                parent_id, actual_vsize, child_vsize
+            );
+            child_vsize = actual_vsize;
+        }
+
+        Err(anyhow!("could not construct CPFP child of {}", parent_id))
+    }
+
+    async fn sign(
+        &self,
+        inputs: &[(OutPoint, u64)],
+        destination: &ScriptBuf,
+        fee: u64,
+    ) -> Result<Transaction> {
+        let unsigned = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs
+                .iter()
+                .map(|(outpoint, _)| TxIn {
+                    previous_output: *outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: vec![TxOut {
+                script_pubkey: destination.clone(),
+                value: Amount::from_sat(Self::input_sum(inputs) - fee),
+            }],
+        };
+
+        let signed = self
+            .chain_client
+            .sign_raw_transaction_with_wallet(None, &consensus::encode::serialize_hex(&unsigned))
+            .await?;
+        if !signed.complete {
+            return Err(anyhow!("wallet could not sign CPFP child"));
+        }
+
+        Ok(consensus::deserialize(&hex::decode(signed.hex)?)?)
+    }
+
+    fn input_sum(inputs: &[(OutPoint, u64)]) -> u64 {
+        inputs.iter().map(|(_, amount)| amount).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain::types::MempoolEntryFees;
+    use rstest::rstest;
+
+    fn entry(base: f64, vsize: u64, descendant: f64, descendant_size: u64) -> MempoolEntry {
+        MempoolEntry {
+            vsize,
+            time: 0,
+            ancestor_size: vsize,
+            descendant_count: 1,
+            descendant_size,
+            fees: MempoolEntryFees {
+                base,
+                ancestor: base,
+                descendant,
+            },
+            bip125_replaceable: true,
+        }
+    }
+
+    #[rstest]
+    #[case::no_children(entry(0.000_01, 200, 0.000_01, 200), 5.0)]
+    #[case::child_raises(entry(0.000_01, 200, 0.000_05, 300), 5_000.0 / 300.0)]
+    #[case::child_lowers(entry(0.000_01, 200, 0.000_011, 400), 5.0)]
+    fn test_package_fee_rate(#[case] entry: MempoolEntry, #[case] expected: f64) {
+        assert_eq!(package_fee_rate(&entry).unwrap(), expected);
+    }
+
+    #[rstest]
+    // (10 * (200 + 100)) - 1_000
+    #[case::package(10.0, 1_000, 200, 100, 0, 2_000)]
+    #[case::rounds_up(10.5, 1_000, 200, 101, 0, 2_161)]
+    #[case::min_relay_fee(2.0, 1_000, 200, 100, 0, 100)]
+    #[case::replaces_child(10.0, 1_000, 200, 100, 2_000, 2_100)]
+    #[case::replaced_child_cheaper(10.0, 1_000, 200, 100, 500, 2_000)]
+    fn test_child_fee(
+        #[case] fee_target: f64,
+        #[case] parent_fee: u64,
+        #[case] parent_vsize: u64,
+        #[case] child_vsize: u64,
+        #[case] replaced_fee: u64,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(
+            child_fee(
+                fee_target,
+                parent_fee,
+                parent_vsize,
+                child_vsize,
+                replaced_fee
+            ),
+            expected
+        );
+    }
+}
@@ -1,11 +1,16 @@
-use crate::chain::types::Type;
+use crate::chain::types::{MempoolEntry, Type};
 use crate::chain::utils::Transaction;
 use crate::chain::{
     Client,
-    bumper::handlers::{PendingTransaction, TransactionHandler},
+    bumper::cpfp::{self, Cpfp},
+    bumper::escalation::Escalation,
+    bumper::handlers::{HandlerType, PendingTransaction, TransactionHandler},
 };
-use anyhow::Result;
+use crate::notifications::NotificationClient;
+use crate::swap::TimeoutDeltaProvider;
+use anyhow::{Result, anyhow};
 use boltz_core::Address;
+use dashmap::DashSet;
 use std::sync::Arc;
 use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
@@ -21,6 +26,13 @@ pub struct Bumper {
     cancellation_token: CancellationToken,
     chain_client: Arc<dyn Client + Send + Sync>,
     handlers: Vec<Arc<dyn TransactionHandler + Send + Sync>>,
+    cpfp: Arc<Cpfp>,
+
+    escalation: Option<Escalation>,
+    timeout_delta_provider: Option<Arc<TimeoutDeltaProvider>>,
+    notification_client: Option<Arc<dyn NotificationClient + Send + Sync>>,
+    /// Swaps for which an alert about their capped fee was sent already
+    alerted: Arc<DashSet<String>>,
 }
 
 impl Bumper {
@@ -32,10 +44,28 @@ impl Bumper {
         Self {
             cancellation_token,
             handlers,
+            cpfp: Arc::new(Cpfp::new(chain_client.clone())),
             chain_client,
+            escalation: None,
+            timeout_delta_provider: None,
+            notification_client: None,
+            alerted: Arc::new(DashSet::new()),
         }
     }
 
+    /// Escalate the fee target of transactions the closer they get to the timeout of their swap
+    pub fn with_escalation(
+        mut self,
+        escalation: Escalation,
+        timeout_delta_provider: Arc<TimeoutDeltaProvider>,
+        notification_client: Option<Arc<dyn NotificationClient + Send + Sync>>,
+    ) -> Self {
+        self.escalation = Some(escalation);
+        self.timeout_delta_provider = Some(timeout_delta_provider);
+        self.notification_client = notification_client;
+        self
+    }
+
     pub fn symbol(&self) -> String {
         self.chain_client.symbol()
     }
@@ -82,9 +112,17 @@ impl Bumper {
         #[cfg(feature = "metrics")]
         metrics::gauge!(crate::metrics::FEE_TARGET, "symbol" => self.symbol()).set(fee_target);
 
+        let block_height = match self.escalation {
+            Some(_) => Some(self.chain_client.blockchain_info().await?.blocks),
+            None => None,
+        };
+
         for handler in self.handlers.iter() {
             for tx in handler.fetch_pending()? {
-                if let Err(e) = self.check_transaction(handler, &tx, fee_target).await {
+                if let Err(e) = self
+                    .check_transaction(handler, &tx, fee_target, block_height)
+                    .await
+                {
                     warn!(
                         "{} RBF bumper errored for transaction {} ({}): {}",
                         self.chain_client.symbol(),
@@ -105,6 +143,7 @@ impl Bumper {
         handler: &Arc<dyn TransactionHandler + Send + Sync>,
         pending_tx: &PendingTransaction,
         fee_target: f64,
+        block_height: Option<u64>,
     ) -> Result<()> {
         trace!(
             "{} RBF bumper checking {} transaction {} ({})",
@@ -116,10 +155,38 @@ impl Bumper {
 
         let tx = self
             .chain_client
-            .raw_transaction(&pending_tx.transaction_id)
+            .raw_transaction_verbose(&pending_tx.transaction_id)
             .await?;
-        let tx = Transaction::parse_hex(&self.chain_client.chain_type(), &tx)?;
-        let fee_sat_vbyte = tx.calculate_fee(&self.chain_client).await? as f64 / tx.vsize() as f64;
+        if tx.is_confirmed() {
+            debug!(
+                "{} {} transaction {} ({}) confirmed",
+                self.chain_client.symbol(),
+                handler.handler_type(),
+                pending_tx.swap_id,
+                pending_tx.transaction_id
+            );
+            self.alerted.remove(&pending_tx.swap_id);
+            return handler.confirmed(pending_tx).await;
+        }
+
+        let fee_target = self
+            .escalate(handler.handler_type(), pending_tx, fee_target, block_height)
+            .await;
+
+        let tx = Transaction::parse_hex(&self.chain_client.chain_type(), &tx.hex)?;
+        let entry = self
+            .chain_client
+            .mempool_entry(&pending_tx.transaction_id)
+            .await?;
+        let has_descendants = entry
+            .as_ref()
+            .is_some_and(|entry| entry.descendant_count > 1);
+
+        let fee_sat_vbyte = match &entry {
+            // When we attached a child already, the package is what counts
+            Some(entry) if has_descendants => cpfp::package_fee_rate(entry)?,
+            _ => tx.calculate_fee(&self.chain_client).await? as f64 / tx.vsize() as f64,
+        };
 
         if !Self::should_bump(fee_sat_vbyte, fee_target) {
             debug!(
@@ -133,30 +200,186 @@ impl Bumper {
             return Ok(());
         }
 
-        debug!(
-            "{} RBF bumping {} transaction {} ({}) from {:.2} sat/vbyte to {:.2} sat/vbyte",
-            self.chain_client.symbol(),
+        // Replacing the parent would evict the children we attached
+        if !has_descendants {
+            debug!(
+                "{} RBF bumping {} transaction {} ({}) from {:.2} sat/vbyte to {:.2} sat/vbyte",
+                self.chain_client.symbol(),
+                handler.handler_type(),
+                pending_tx.swap_id,
+                pending_tx.transaction_id,
+                fee_sat_vbyte,
+                fee_target
+            );
+
+            match handler
+                .bump_fee(pending_tx, fee_target, self.get_sweep_address(tx.clone()))
+                .await
+            {
+                Ok(tx_id) => {
+                    info!(
+                        "RBF bumped {} transaction for swap {} to {:.2} sat/vbyte: {}",
+                        handler.handler_type(),
+                        pending_tx.swap_id,
+                        fee_target,
+                        tx_id
+                    );
+                    return Ok(());
+                }
+                Err(err) => {
+                    if handler.cpfp_output(&tx).await.is_none() {
+                        return Err(err);
+                    }
+
+                    warn!(
+                        "{} RBF of {} transaction {} ({}) failed; falling back to CPFP: {}",
+                        self.chain_client.symbol(),
+                        handler.handler_type(),
+                        pending_tx.swap_id,
+                        pending_tx.transaction_id,
+                        err
+                    );
+                }
+            }
+        }
+
+        self.bump_with_child(handler, pending_tx, &tx, entry, fee_target)
+            .await
+    }
+
+    async fn bump_with_child(
+        &self,
+        handler: &Arc<dyn TransactionHandler + Send + Sync>,
+        pending_tx: &PendingTransaction,
+        tx: &Transaction,
+        entry: Option<MempoolEntry>,
+        fee_target: f64,
+    ) -> Result<()> {
+        let vout = handler.cpfp_output(tx).await.ok_or(anyhow!(
+            "{} transaction {} has no output we can spend with a child",
             handler.handler_type(),
-            pending_tx.swap_id,
-            pending_tx.transaction_id,
-            fee_sat_vbyte,
-            fee_target
-        );
+            pending_tx.transaction_id
+        ))?;
+        let entry = entry.ok_or(anyhow!(
+            "transaction {} is not in the mempool",
+            pending_tx.transaction_id
+        ))?;
+        let parent = match tx {
+            Transaction::Bitcoin(tx) => tx,
+            Transaction::Elements(_) => {
+                return Err(anyhow!("CPFP is not supported for Elements transactions"));
+            }
+        };
 
-        let tx_id = handler
-            .bump_fee(pending_tx, fee_target, self.get_sweep_address(tx))
-            .await?;
+        let child_id = self.cpfp.bump(parent, vout, &entry, fee_target).await?;
         info!(
-            "RBF bumped {} transaction for swap {} to {:.2} sat/vbyte: {}",
+            "CPFP bumped {} transaction {} for swap {} to {:.2} sat/vbyte with child: {}",
             handler.handler_type(),
+            pending_tx.transaction_id,
             pending_tx.swap_id,
             fee_target,
-            tx_id
+            child_id
         );
 
         Ok(())
     }
 
+    async fn escalate(
+        &self,
+        handler_type: HandlerType,
+        pending_tx: &PendingTransaction,
+        fee_target: f64,
+        block_height: Option<u64>,
+    ) -> f64 {
+        let (escalation, timeout_delta_provider, block_height, timeout) = match (
+            &self.escalation,
+            &self.timeout_delta_provider,
+            block_height,
+            &pending_tx.timeout,
+        ) {
+            (Some(escalation), Some(provider), Some(block_height), Some(timeout)) => {
+                (escalation, provider, block_height, timeout)
+            }
+            _ => return fee_target,
+        };
+
+        let window = match timeout_delta_provider.get_block_delta(
+            &timeout.pair,
+            &self.symbol(),
+            timeout.swap_type,
+        ) {
+            Ok(window) => window,
+            Err(err) => {
+                debug!(
+                    "Not escalating fee of {} transaction {}: {}",
+                    handler_type, pending_tx.transaction_id, err
+                );
+                return fee_target;
+            }
+        };
+
+        let deadline = match &timeout.counterparty_symbol {
+            Some(symbol) => match timeout_delta_provider.get_chain_timeout_offset(
+                &timeout.pair,
+                &self.symbol(),
+                symbol,
+            ) {
+                Ok(offset) => timeout.block_height + offset,
+                Err(err) => {
+                    debug!(
+                        "Not escalating fee of {} transaction {}: {}",
+                        handler_type, pending_tx.transaction_id, err
+                    );
+                    return fee_target;
+                }
+            },
+            None => timeout.block_height,
+        };
+        let blocks_left = deadline.saturating_sub(block_height);
+
+        let target = escalation.target(fee_target, blocks_left, window);
+        trace!(
+            "Escalated fee target of {} transaction {} with {} blocks left: {:?}",
+            handler_type, pending_tx.transaction_id, blocks_left, target
+        );
+
+        if target.capped {
+            self.alert_capped(handler_type, pending_tx, blocks_left, target.fee_rate)
+                .await;
+        }
+
+        target.fee_rate
+    }
+
+    async fn alert_capped(
+        &self,
+        handler_type: HandlerType,
+        pending_tx: &PendingTransaction,
+        blocks_left: u64,
+        fee_rate: f64,
+    ) {
+        if !self.alerted.insert(pending_tx.swap_id.clone()) {
+            return;
+        }
+
+        let message = format!(
+            "{} {} transaction {} of swap {} is unconfirmed with {} blocks left at the fee cap of {:.2} sat/vbyte",
+            self.symbol(),
+            handler_type,
+            pending_tx.transaction_id,
+            pending_tx.swap_id,
+            blocks_left,
+            fee_rate
+        );
+        warn!("{}", message);
+
+        if let Some(client) = &self.notification_client
+            && let Err(err) = client.send_message(&message, true, true).await
+        {
+            warn!("Could not send fee cap alert: {}", err);
+        }
+    }
+
     fn get_sweep_address(&self, tx: Transaction) -> Option<Address> {
         let scripts = tx.output_script_pubkeys();
         if scripts.is_empty() || scripts.len() > 1 {
@@ -196,7 +419,10 @@ impl Bumper {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::chain::{bumper::handlers::HandlerType, chain_client::test as bitcoin_test};
+    use crate::chain::bumper::EscalationConfig;
+    use crate::chain::{bumper::handlers::Timeout, chain_client::test as bitcoin_test};
+    use crate::db::models::SwapType;
+    use crate::swap::{PairConfig, PairTimeoutBlockDelta};
     use async_trait::async_trait;
     use mockall::mock;
     use rstest::rstest;
@@ -210,6 +436,179 @@ mod test {
             fn handler_type(&self) -> HandlerType;
             fn fetch_pending(&self) -> anyhow::Result<Vec<PendingTransaction>>;
             async fn bump_fee(&self, tx: &PendingTransaction, fee_target: f64, sweep_address: Option<Address>) -> Result<String>;
+            async fn cpfp_output(&self, tx: &Transaction) -> Option<u32>;
+            async fn confirmed(&self, tx: &PendingTransaction) -> Result<()>;
+        }
+    }
+
+    mock! {
+        NotificationClient {}
+
+        #[async_trait]
+        impl NotificationClient for NotificationClient {
+            fn listen_to_messages(&self) -> tokio::sync::broadcast::Receiver<String>;
+            async fn send_message(&self, message: &str, is_important: bool, send_alert: bool) -> anyhow::Result<()>;
+        }
+    }
+
+    fn timeout_delta_provider() -> Arc<TimeoutDeltaProvider> {
+        Arc::new(
+            TimeoutDeltaProvider::new(
+                &Arc::new(std::collections::HashMap::new()),
+                &["BTC", "L-BTC"].map(|base| PairConfig {
+                    base: base.to_string(),
+                    quote: "BTC".to_string(),
+                    timeout_delta: PairTimeoutBlockDelta {
+                        chain: 1_000,
+                        reverse: 1_000,
+                        swap_minimal: 1_000,
+                        swap_maximal: 1_000,
+                        swap_taproot: 1_000,
+                    },
+                }),
+            )
+            .unwrap(),
+        )
+    }
+
+    async fn escalating_bumper(notification_client: Option<MockNotificationClient>) -> Bumper {
+        let client = bitcoin_test::get_client().await;
+        Bumper::new(CancellationToken::new(), Arc::new(client), vec![]).with_escalation(
+            Escalation::new(&EscalationConfig {
+                max_multiplier: Some(3.0),
+                max_fee_rate: Some(50.0),
+            }),
+            timeout_delta_provider(),
+            notification_client
+                .map(|client| Arc::new(client) as Arc<dyn NotificationClient + Send + Sync>),
+        )
+    }
+
+    fn pending_with_timeout(swap_type: SwapType, block_height: u64) -> PendingTransaction {
+        PendingTransaction {
+            swap_id: "swap".to_string(),
+            transaction_id: "tx".to_string(),
+            timeout: Some(Timeout {
+                pair: "BTC/BTC".to_string(),
+                swap_type,
+                block_height,
+                counterparty_symbol: None,
+            }),
+        }
+    }
+
+    #[rstest]
+    // BTC/BTC has a timeout delta of 100 blocks
+    #[case::claim_start(HandlerType::Claim, SwapType::Submarine, 1_100, 10.0)]
+    #[case::claim_halfway(HandlerType::Claim, SwapType::Submarine, 1_050, 15.0)]
+    #[case::claim_deadline(HandlerType::Claim, SwapType::Chain, 1_000, 30.0)]
+    #[case::refund_start(HandlerType::Refund, SwapType::Reverse, 1_100, 10.0)]
+    #[case::refund_halfway(HandlerType::Refund, SwapType::Chain, 1_050, 15.0)]
+    #[case::refund_deadline(HandlerType::Refund, SwapType::Reverse, 1_000, 30.0)]
+    #[tokio::test]
+    async fn test_escalate(
+        #[case] handler_type: HandlerType,
+        #[case] swap_type: SwapType,
+        #[case] timeout: u64,
+        #[case] expected: f64,
+    ) {
+        let bumper = escalating_bumper(None).await;
+        assert_eq!(
+            bumper
+                .escalate(
+                    handler_type,
+                    &pending_with_timeout(swap_type, timeout),
+                    10.0,
+                    Some(1_000),
+                )
+                .await,
+            expected
+        );
+    }
+
+    #[rstest]
+    // The lockup of the user on L-BTC times out 50 BTC blocks after ours
+    #[case::start(1_050, 10.0)]
+    #[case::halfway(1_000, 15.0)]
+    #[case::deadline(950, 30.0)]
+    #[tokio::test]
+    async fn test_escalate_chain_swap_cross_chain(#[case] timeout: u64, #[case] expected: f64) {
+        let bumper = escalating_bumper(None).await;
+        let pending = PendingTransaction {
+            swap_id: "swap".to_string(),
+            transaction_id: "tx".to_string(),
+            timeout: Some(Timeout {
+                pair: "L-BTC/BTC".to_string(),
+                swap_type: SwapType::Chain,
+                block_height: timeout,
+                counterparty_symbol: Some("L-BTC".to_string()),
+            }),
+        };
+
+        assert_eq!(
+            bumper
+                .escalate(HandlerType::Refund, &pending, 10.0, Some(1_000))
+                .await,
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escalate_without_timeout() {
+        let bumper = escalating_bumper(None).await;
+        let mut pending = pending_with_timeout(SwapType::Submarine, 1_000);
+        pending.timeout = None;
+
+        assert_eq!(
+            bumper
+                .escalate(HandlerType::Claim, &pending, 10.0, Some(1_000))
+                .await,
+            10.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escalate_not_configured() {
+        let client = bitcoin_test::get_client().await;
+        let bumper = Bumper::new(CancellationToken::new(), Arc::new(client), vec![]);
+
+        assert_eq!(
+            bumper
+                .escalate(
+                    HandlerType::Claim,
+                    &pending_with_timeout(SwapType::Submarine, 1_000),
+                    10.0,
+                    Some(1_000),
+                )
+                .await,
+            10.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escalate_capped_alerts_once() {
+        let mut notification_client = MockNotificationClient::new();
+        notification_client
+            .expect_send_message()
+            .withf(|message, is_important, send_alert| {
+                message.contains("claim transaction tx of swap swap")
+                    && message.contains("fee cap of 50.00 sat/vbyte")
+                    && *is_important
+                    && *send_alert
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let bumper = escalating_bumper(Some(notification_client)).await;
+        let pending = pending_with_timeout(SwapType::Submarine, 1_000);
+
+        for _ in 0..2 {
+            assert_eq!(
+                bumper
+                    .escalate(HandlerType::Claim, &pending, 20.0, Some(1_000))
+                    .await,
+                50.0
+            );
         }
     }
 
@@ -226,10 +625,14 @@ mod test {
         let pending_tx = PendingTransaction {
             swap_id: "swap".to_string(),
             transaction_id: tx.txid_hex().to_string(),
+            timeout: None,
         };
         let fee_target = 21.0;
 
         let mut handler = MockTransactionHandler::new();
+        handler
+            .expect_handler_type()
+            .returning(|| HandlerType::Claim);
         let pending_cloned = pending_tx.clone();
         handler.expect_bump_fee().returning(move |tx, fee, _| {
             assert_eq!(tx, &pending_cloned);
@@ -242,6 +645,7 @@ mod test {
                 &(Arc::new(handler) as Arc<dyn TransactionHandler + Send + Sync>),
                 &pending_tx,
                 fee_target,
+                None,
             )
             .await
             .unwrap();
@@ -249,6 +653,71 @@ mod test {
         cancel_token.cancel();
     }
 
+    #[tokio::test]
+    #[serial(BTC)]
+    async fn test_check_transaction_cpfp_fallback() {
+        let client = bitcoin_test::get_client().await;
+
+        let cancel_token = CancellationToken::new();
+        let bumper = Bumper::new(cancel_token.clone(), Arc::new(client.clone()), vec![]);
+
+        let tx = bitcoin_test::send_transaction(&client).await;
+        let vout = match &tx {
+            Transaction::Bitcoin(tx) => tx
+                .output
+                .iter()
+                .position(|out| out.value.to_sat() == 21_000_000)
+                .unwrap() as u32,
+            Transaction::Elements(_) => unreachable!(),
+        };
+
+        let pending_tx = PendingTransaction {
+            swap_id: "swap".to_string(),
+            transaction_id: tx.txid_hex().to_string(),
+            timeout: None,
+        };
+
+        let mut handler = MockTransactionHandler::new();
+        handler
+            .expect_handler_type()
+            .returning(|| HandlerType::Claim);
+        handler
+            .expect_bump_fee()
+            .times(1)
+            .returning(|_, _, _| Err(anyhow!("insufficient fee")));
+        handler.expect_cpfp_output().returning(move |_| Some(vout));
+        let handler = Arc::new(handler) as Arc<dyn TransactionHandler + Send + Sync>;
+
+        bumper
+            .check_transaction(&handler, &pending_tx, 21.0, None)
+            .await
+            .unwrap();
+
+        let entry = client
+            .mempool_entry(&pending_tx.transaction_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.descendant_count, 2);
+        assert!(cpfp::package_fee_rate(&entry).unwrap() >= 21.0);
+
+        // With a child attached, the parent is not replaced anymore but the child is
+        bumper
+            .check_transaction(&handler, &pending_tx, 42.0, None)
+            .await
+            .unwrap();
+
+        let entry = client
+            .mempool_entry(&pending_tx.transaction_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.descendant_count, 2);
+        assert!(cpfp::package_fee_rate(&entry).unwrap() >= 42.0);
+
+        cancel_token.cancel();
+    }
+
     #[tokio::test]
     #[serial(BTC)]
     async fn test_check_transaction_skip() {
@@ -259,15 +728,21 @@ mod test {
 
         let tx = bitcoin_test::send_transaction(&client).await;
 
+        let mut handler = MockTransactionHandler::new();
+        handler
+            .expect_handler_type()
+            .returning(|| HandlerType::Claim);
+
         bumper
             .check_transaction(
-                &(Arc::new(MockTransactionHandler::new())
-                    as Arc<dyn TransactionHandler + Send + Sync>),
+                &(Arc::new(handler) as Arc<dyn TransactionHandler + Send + Sync>),
                 &PendingTransaction {
                     swap_id: "swap".to_string(),
                     transaction_id: tx.txid_hex().to_string(),
+                    timeout: None,
                 },
                 1.0,
+                None,
             )
             .await
             .unwrap();
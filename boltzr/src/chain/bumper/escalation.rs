@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_MULTIPLIER: f64 = 3.0;
+const DEFAULT_MAX_FEE_RATE: f64 = 250.0;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+pub struct Config {
+    /// Multiplier of the fee estimation that is reached at the deadline of a transaction
+    #[serde(rename = "maxMultiplier")]
+    pub max_multiplier: Option<f64>,
+
+    /// Fee rate in sat/vbyte escalated transactions are capped at
+    #[serde(rename = "maxFeeRate")]
+    pub max_fee_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target {
+    pub fee_rate: f64,
+    /// Whether the escalation inside the window wanted to go above the fee rate cap;
+    /// an estimation above the cap outside of the window does not count
+    pub capped: bool,
+}
+
+/// Raises the fee target of a transaction the closer it gets to its deadline
+///
+/// The escalation starts `window` blocks before the deadline and follows a
+/// quadratic curve, so that the fee stays close to the estimation for most
+/// of the window and only rises steeply right before the deadline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Escalation {
+    max_multiplier: f64,
+    max_fee_rate: f64,
+}
+
+impl Escalation {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            max_multiplier: config
+                .max_multiplier
+                .unwrap_or(DEFAULT_MAX_MULTIPLIER)
+                .max(1.0),
+            max_fee_rate: config.max_fee_rate.unwrap_or(DEFAULT_MAX_FEE_RATE),
+        }
+    }
+
+    pub fn target(&self, estimation: f64, blocks_left: u64, window: u64) -> Target {
+        let progress = if window == 0 {
+            1.0
+        } else {
+            1.0 - (blocks_left.min(window) as f64 / window as f64)
+        };
+
+        let escalated = estimation * (1.0 + (self.max_multiplier - 1.0) * progress.powi(2));
+        if escalated <= self.max_fee_rate {
+            return Target {
+                fee_rate: escalated,
+                capped: false,
+            };
+        }
+
+        Target {
+            // The cap limits how far we escalate, but never goes below the estimation
+            fee_rate: self.max_fee_rate.max(estimation),
+            capped: progress > 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn escalation() -> Escalation {
+        Escalation::new(&Config {
+            max_multiplier: Some(3.0),
+            max_fee_rate: Some(50.0),
+        })
+    }
+
+    #[rstest]
+    #[case(100, 100, 10.0)]
+    #[case(200, 100, 10.0)]
+    #[case(50, 100, 15.0)]
+    #[case(0, 100, 30.0)]
+    #[case(0, 0, 30.0)]
+    fn test_target(#[case] blocks_left: u64, #[case] window: u64, #[case] expected: f64) {
+        assert_eq!(
+            escalation().target(10.0, blocks_left, window),
+            Target {
+                fee_rate: expected,
+                capped: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_target_capped() {
+        assert_eq!(
+            escalation().target(20.0, 0, 100),
+            Target {
+                fee_rate: 50.0,
+                capped: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_target_cap_below_estimation() {
+        assert_eq!(
+            escalation().target(60.0, 100, 100),
+            Target {
+                fee_rate: 60.0,
+                capped: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_target_cap_below_estimation_in_window() {
+        assert_eq!(
+            escalation().target(60.0, 50, 100),
+            Target {
+                fee_rate: 60.0,
+                capped: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_defaults() {
+        let escalation = Escalation::new(&Config::default());
+        assert_eq!(escalation.max_multiplier, DEFAULT_MAX_MULTIPLIER);
+        assert_eq!(escalation.max_fee_rate, DEFAULT_MAX_FEE_RATE);
+    }
+}
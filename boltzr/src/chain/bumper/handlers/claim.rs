@@ -0,0 +1,391 @@
+use crate::{
+    api::ws::types::{SwapStatus, SwapStatusNoId, TransactionInfo},
+    chain::{
+        Client,
+        bumper::handlers::{
+            HandlerType, PendingTransaction, Timeout, TransactionHandler, sweep_output,
+        },
+        utils::Transaction,
+    },
+    db::{
+        helpers::{
+            chain_swap::ChainSwapHelper, claim_transaction::ClaimTransactionHelper,
+            swap::SwapHelper,
+        },
+        models::SomeSwap,
+    },
+    swap::SwapUpdate,
+    wallet::Wallet,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use boltz_core::{
+    Address, Destination,
+    wrapper::{BitcoinParams, Params, construct_tx},
+};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+const SWAP_ID_SEPARATOR: &str = ",";
+
+/// Bumps the fees of (batch) claim transactions of Submarine and Chain Swaps
+#[derive(Clone)]
+pub struct ClaimTransactionHandler<H, S, C>
+where
+    H: ClaimTransactionHelper + Send + Sync,
+    S: SwapHelper + Send + Sync,
+    C: ChainSwapHelper + Send + Sync,
+{
+    tx_helper: H,
+    swap_helper: S,
+    chain_swap_helper: C,
+
+    wallet: Arc<dyn Wallet + Send + Sync>,
+    chain_client: Arc<dyn Client + Send + Sync>,
+    update_tx: broadcast::Sender<SwapStatus>,
+}
+
+impl<H, S, C> ClaimTransactionHandler<H, S, C>
+where
+    H: ClaimTransactionHelper + Send + Sync,
+    S: SwapHelper + Send + Sync,
+    C: ChainSwapHelper + Send + Sync,
+{
+    pub fn new(
+        tx_helper: H,
+        swap_helper: S,
+        chain_swap_helper: C,
+        wallet: Arc<dyn Wallet + Send + Sync>,
+        chain_client: Arc<dyn Client + Send + Sync>,
+        update_tx: broadcast::Sender<SwapStatus>,
+    ) -> Self {
+        Self {
+            tx_helper,
+            swap_helper,
+            chain_swap_helper,
+            wallet,
+            chain_client,
+            update_tx,
+        }
+    }
+
+    fn get_swap(&self, id: &str) -> Result<Box<dyn SomeSwap + Send + Sync>> {
+        Ok(match self.swap_helper.get_by_id(id) {
+            Ok(swap) => Box::new(swap),
+            Err(_) => match self.chain_swap_helper.get_by_id(id) {
+                Ok(swap) => Box::new(swap),
+                Err(_) => return Err(anyhow::anyhow!("swap {} not found", id)),
+            },
+        })
+    }
+
+    fn get_timeout(&self, id: &str) -> Option<Timeout> {
+        let swap = self.get_swap(id).ok()?;
+        Some(Timeout {
+            pair: swap.pair(),
+            swap_type: swap.kind(),
+            block_height: swap.claim_timeout_block_height().ok()?,
+            counterparty_symbol: None,
+        })
+    }
+
+    fn send_update(&self, swap_id: &str, tx_id: &str, tx_hex: &str) {
+        let update = SwapStatus {
+            id: swap_id.to_string(),
+            base: SwapStatusNoId {
+                status: SwapUpdate::TransactionClaimed.to_string(),
+                transaction: Some(TransactionInfo {
+                    id: tx_id.to_string(),
+                    hex: Some(tx_hex.to_string()),
+                    eta: None,
+                    confirmed: None,
+                }),
+                ..Default::default()
+            },
+            event_id: None,
+        };
+
+        if let Err(e) = self.update_tx.send(update) {
+            warn!("Failed to send claim replacement update: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl<H, S, C> TransactionHandler for ClaimTransactionHandler<H, S, C>
+where
+    H: ClaimTransactionHelper + Send + Sync,
+    S: SwapHelper + Send + Sync,
+    C: ChainSwapHelper + Send + Sync,
+{
+    fn handler_type(&self) -> HandlerType {
+        HandlerType::Claim
+    }
+
+    fn fetch_pending(&self) -> Result<Vec<PendingTransaction>> {
+        let claims = self.tx_helper.get_pending(&self.chain_client.symbol())?;
+
+        // Batch claims have one entry per swap, but we bump the transaction only once
+        let mut pending: Vec<PendingTransaction> = Vec::new();
+        for claim in claims {
+            let timeout = self.get_timeout(&claim.swap_id);
+
+            match pending
+                .iter_mut()
+                .find(|tx| tx.transaction_id == claim.transaction_id)
+            {
+                Some(tx) => {
+                    tx.swap_id.push_str(SWAP_ID_SEPARATOR);
+                    tx.swap_id.push_str(&claim.swap_id);
+
+                    // The swap that times out first decides the deadline of the batch
+                    if let Some(timeout) = timeout
+                        && tx
+                            .timeout
+                            .as_ref()
+                            .is_none_or(|existing| timeout.block_height < existing.block_height)
+                    {
+                        tx.timeout = Some(timeout);
+                    }
+                }
+                None => pending.push(PendingTransaction {
+                    swap_id: claim.swap_id,
+                    transaction_id: claim.transaction_id,
+                    timeout,
+                }),
+            }
+        }
+
+        Ok(pending)
+    }
+
+    async fn cpfp_output(&self, tx: &Transaction) -> Option<u32> {
+        sweep_output(&self.chain_client, tx).await
+    }
+
+    #[tracing::instrument(name = "ClaimTransactionHandler::bump_fee", skip(self))]
+    async fn bump_fee(
+        &self,
+        pending: &PendingTransaction,
+        fee_target: f64,
+        sweep_address: Option<Address>,
+    ) -> Result<String> {
+        let claims = self
+            .tx_helper
+            .get_by_transaction_id(&pending.transaction_id)?;
+        if claims.is_empty() {
+            return Err(anyhow::anyhow!(
+                "no claims for transaction {}",
+                pending.transaction_id
+            ));
+        }
+
+        let mut inputs = Vec::with_capacity(claims.len());
+        for claim in claims.iter() {
+            let swap = self.get_swap(&claim.swap_id)?;
+            inputs.push(
+                swap.claim_details(&self.wallet, &self.chain_client)
+                    .await?
+                    .try_into()?,
+            );
+        }
+
+        let destination = match sweep_address {
+            Some(address) => address,
+            None => Address::try_from(
+                self.wallet
+                    .get_address(
+                        None,
+                        &self.wallet.label_batch_claim(
+                            &claims
+                                .iter()
+                                .map(|claim| claim.swap_id.as_str())
+                                .collect::<Vec<_>>(),
+                        ),
+                    )
+                    .await?
+                    .as_str(),
+            )?,
+        };
+
+        let (tx, _) = construct_tx(Params::Bitcoin(BitcoinParams {
+            inputs,
+            fee: fee_target.into(),
+            destination: &Destination::Single(&destination.try_into()?),
+        }))
+        .await?;
+
+        let tx_hex = hex::encode(tx.serialize());
+        self.chain_client.send_raw_transaction(&tx_hex).await?;
+
+        let tx_id = tx.txid();
+        self.tx_helper
+            .replace(&pending.transaction_id, &tx_id, fee_target)
+            .map_err(|err| {
+                anyhow::anyhow!("failed to record claim replacement ({}): {}", tx_id, err)
+            })?;
+
+        for claim in claims.iter() {
+            self.send_update(&claim.swap_id, &tx_id, &tx_hex);
+        }
+
+        Ok(tx_id)
+    }
+
+    async fn confirmed(&self, tx: &PendingTransaction) -> Result<()> {
+        self.tx_helper.set_confirmed(&tx.transaction_id)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain::chain_client::test as bitcoin_test;
+    use crate::db::helpers::chain_swap::test::MockChainSwapHelper;
+    use crate::db::helpers::claim_transaction::{ClaimStatus, test::MockClaimTransactionHelper};
+    use crate::db::helpers::swap::test::MockSwapHelper;
+    use crate::db::models::{PendingClaim, Swap, SwapType};
+    use crate::wallet::{Bitcoin, Network};
+    use mockall::predicate::eq;
+
+    fn pending_claim(swap_id: &str, transaction_id: &str) -> PendingClaim {
+        PendingClaim {
+            swap_id: swap_id.to_string(),
+            symbol: "BTC".to_string(),
+            transaction_id: transaction_id.to_string(),
+            status: ClaimStatus::Pending.into(),
+        }
+    }
+
+    async fn get_handler(
+        tx_helper: MockClaimTransactionHelper,
+        swap_helper: MockSwapHelper,
+        chain_swap_helper: MockChainSwapHelper,
+    ) -> ClaimTransactionHandler<MockClaimTransactionHelper, MockSwapHelper, MockChainSwapHelper>
+    {
+        let client = Arc::new(bitcoin_test::get_client().await);
+        let wallet = Bitcoin::new(
+            Network::Regtest,
+            &[0; 64],
+            "m/0/0".to_string(),
+            client.clone(),
+        )
+        .unwrap();
+
+        ClaimTransactionHandler::new(
+            tx_helper,
+            swap_helper,
+            chain_swap_helper,
+            Arc::new(wallet),
+            client,
+            broadcast::channel(16).0,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pending_groups_batches() {
+        let mut tx_helper = MockClaimTransactionHelper::new();
+        tx_helper
+            .expect_get_pending()
+            .with(eq("BTC"))
+            .returning(|_| {
+                Ok(vec![
+                    pending_claim("first", "batch"),
+                    pending_claim("single", "tx"),
+                    pending_claim("second", "batch"),
+                ])
+            });
+
+        let mut swap_helper = MockSwapHelper::new();
+        swap_helper.expect_get_by_id().returning(|id| {
+            if id == "single" {
+                return Err(anyhow::anyhow!("not found"));
+            }
+
+            Ok(Swap {
+                id: id.to_string(),
+                pair: "BTC/BTC".to_string(),
+                timeoutBlockHeight: if id == "first" { 110 } else { 100 },
+                ..Default::default()
+            })
+        });
+
+        let mut chain_swap_helper = MockChainSwapHelper::new();
+        chain_swap_helper
+            .expect_get_by_id()
+            .returning(|_| Err(anyhow::anyhow!("not found")));
+
+        let handler = get_handler(tx_helper, swap_helper, chain_swap_helper).await;
+        assert_eq!(
+            handler.fetch_pending().unwrap(),
+            vec![
+                PendingTransaction {
+                    swap_id: "first,second".to_string(),
+                    transaction_id: "batch".to_string(),
+                    timeout: Some(Timeout {
+                        pair: "BTC/BTC".to_string(),
+                        swap_type: SwapType::Submarine,
+                        block_height: 100,
+                        counterparty_symbol: None,
+                    }),
+                },
+                PendingTransaction {
+                    swap_id: "single".to_string(),
+                    transaction_id: "tx".to_string(),
+                    timeout: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bump_fee_no_claims() {
+        let mut tx_helper = MockClaimTransactionHelper::new();
+        tx_helper
+            .expect_get_by_transaction_id()
+            .with(eq("tx"))
+            .returning(|_| Ok(vec![]));
+
+        let handler =
+            get_handler(tx_helper, MockSwapHelper::new(), MockChainSwapHelper::new()).await;
+        assert_eq!(
+            handler
+                .bump_fee(
+                    &PendingTransaction {
+                        swap_id: "swap".to_string(),
+                        transaction_id: "tx".to_string(),
+                        timeout: None,
+                    },
+                    21.0,
+                    None,
+                )
+                .await
+                .unwrap_err()
+                .to_string(),
+            "no claims for transaction tx"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirmed() {
+        let mut tx_helper = MockClaimTransactionHelper::new();
+        tx_helper
+            .expect_set_confirmed()
+            .with(eq("tx"))
+            .times(1)
+            .returning(|_| Ok(2));
+
+        let handler =
+            get_handler(tx_helper, MockSwapHelper::new(), MockChainSwapHelper::new()).await;
+        handler
+            .confirmed(&PendingTransaction {
+                swap_id: "swap".to_string(),
+                transaction_id: "tx".to_string(),
+                timeout: None,
+            })
+            .await
+            .unwrap();
+    }
+}
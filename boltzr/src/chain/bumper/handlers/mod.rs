@@ -1,26 +1,47 @@
+use crate::chain::Client;
+use crate::chain::utils::Transaction;
+use crate::db::models::SwapType;
 use anyhow::Result;
 use async_trait::async_trait;
 use boltz_core::Address;
+use std::sync::Arc;
+use tracing::{debug, warn};
 
+pub mod claim;
 pub mod refund;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HandlerType {
     Refund,
+    Claim,
 }
 
 impl std::fmt::Display for HandlerType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             HandlerType::Refund => write!(f, "refund"),
+            HandlerType::Claim => write!(f, "claim"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Deadline of a pending transaction derived from the timelocks of its swap
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeout {
+    pub pair: String,
+    pub swap_type: SwapType,
+    /// Block height by which the transaction has to confirm
+    pub block_height: u64,
+    /// Receiving chain of a Chain Swap, the lockup of the user on which times out after
+    /// `block_height` and thereby extends the deadline
+    pub counterparty_symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PendingTransaction {
     pub swap_id: String,
     pub transaction_id: String,
+    pub timeout: Option<Timeout>,
 }
 
 #[async_trait]
@@ -33,4 +54,115 @@ pub trait TransactionHandler {
         fee_target: f64,
         sweep_address: Option<Address>,
     ) -> Result<String>;
+
+    /// Output of a pending transaction that pays to our wallet and can be spent
+    /// by a child to bump its fee when it cannot be replaced
+    async fn cpfp_output(&self, _tx: &Transaction) -> Option<u32> {
+        None
+    }
+
+    /// Called once a pending transaction is confirmed
+    async fn confirmed(&self, _tx: &PendingTransaction) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Claims and refunds sweep everything to a single output; the child that spends it
+/// is signed by the wallet of the node, so that output has to belong to it
+async fn sweep_output(
+    chain_client: &Arc<dyn Client + Send + Sync>,
+    tx: &Transaction,
+) -> Option<u32> {
+    if let Transaction::Elements(_) = tx {
+        return None;
+    }
+
+    let scripts = tx.output_script_pubkeys();
+    if scripts.len() != 1 {
+        return None;
+    }
+
+    let address = Address::from_bitcoin_script(chain_client.network(), &scripts[0]).ok()?;
+    match chain_client.is_mine(None, &address.to_string()).await {
+        Ok(true) => Some(0),
+        Ok(false) => {
+            debug!(
+                "Output of transaction {} does not pay to our wallet: {}",
+                tx.txid_hex(),
+                address
+            );
+            None
+        }
+        Err(err) => {
+            warn!(
+                "Could not check whether output of transaction {} pays to our wallet: {}",
+                tx.txid_hex(),
+                err
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chain::chain_client::test as bitcoin_test;
+    use bitcoin::{Amount, ScriptBuf, TxOut, absolute::LockTime, transaction::Version};
+    use std::str::FromStr;
+
+    fn sweep_transaction(outputs: Vec<ScriptBuf>) -> Transaction {
+        Transaction::Bitcoin(bitcoin::Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: outputs
+                .into_iter()
+                .map(|script_pubkey| TxOut {
+                    value: Amount::from_sat(10_000),
+                    script_pubkey,
+                })
+                .collect(),
+        })
+    }
+
+    async fn wallet_script(client: &Arc<dyn Client + Send + Sync>) -> ScriptBuf {
+        bitcoin::Address::from_str(&client.get_new_address(None, "", None).await.unwrap())
+            .unwrap()
+            .assume_checked()
+            .script_pubkey()
+    }
+
+    #[tokio::test]
+    async fn test_sweep_output() {
+        let client: Arc<dyn Client + Send + Sync> = Arc::new(bitcoin_test::get_client().await);
+        let script = wallet_script(&client).await;
+
+        assert_eq!(
+            sweep_output(&client, &sweep_transaction(vec![script])).await,
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweep_output_not_ours() {
+        let client: Arc<dyn Client + Send + Sync> = Arc::new(bitcoin_test::get_client().await);
+        let script = ScriptBuf::from_bytes([vec![0x00, 0x14], vec![1; 20]].concat());
+
+        assert_eq!(
+            sweep_output(&client, &sweep_transaction(vec![script])).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweep_output_multiple_outputs() {
+        let client: Arc<dyn Client + Send + Sync> = Arc::new(bitcoin_test::get_client().await);
+        let script = wallet_script(&client).await;
+
+        assert_eq!(
+            sweep_output(&client, &sweep_transaction(vec![script.clone(), script])).await,
+            None
+        );
+    }
 }
@@ -1,8 +1,11 @@
 use crate::{
     chain::{
         Client,
-        bumper::handlers::{HandlerType, PendingTransaction, TransactionHandler},
+        bumper::handlers::{
+            HandlerType, PendingTransaction, Timeout, TransactionHandler, sweep_output,
+        },
         elements_client::SYMBOL as ELEMENTS_SYMBOL,
+        utils::Transaction,
     },
     db::{
         helpers::{
@@ -10,7 +13,7 @@ use crate::{
             refund_transaction::{RefundStatus, RefundTransactionHelper},
             reverse_swap::ReverseSwapHelper,
         },
-        models::SomeSwap,
+        models::{SomeSwap, SwapType},
     },
     wallet::Wallet,
 };
@@ -69,6 +72,28 @@ where
             },
         })
     }
+
+    fn get_timeout(&self, id: &str) -> Option<Timeout> {
+        let swap = self.get_swap(id).ok()?;
+        Self::timeout(swap.as_ref()).ok()
+    }
+
+    /// A late claim of the counterparty only costs us once it can also get its own coins back:
+    /// when the lockup of a Chain Swap it sent us can be refunded. That lockup is on the other
+    /// chain, so its timeout is passed along as counterparty to be converted into blocks of the
+    /// chain we refund on. The invoice of a Reverse Swap is cancelled before we refund, so its
+    /// refund is due as soon as it is possible
+    fn timeout(swap: &(dyn SomeSwap + Send + Sync)) -> Result<Timeout> {
+        Ok(Timeout {
+            pair: swap.pair(),
+            swap_type: swap.kind(),
+            block_height: swap.refund_timeout_block_height()?,
+            counterparty_symbol: match swap.kind() {
+                SwapType::Chain => Some(swap.claim_symbol()?),
+                _ => None,
+            },
+        })
+    }
 }
 
 #[async_trait]
@@ -95,12 +120,17 @@ where
         Ok(transactions
             .into_iter()
             .map(|t| PendingTransaction {
+                timeout: self.get_timeout(&t.swapId),
                 swap_id: t.swapId,
                 transaction_id: t.id,
             })
             .collect())
     }
 
+    async fn cpfp_output(&self, tx: &Transaction) -> Option<u32> {
+        sweep_output(&self.chain_client, tx).await
+    }
+
     #[tracing::instrument(name = "RefundTransactionHandler::bump_fee", skip(self))]
     async fn bump_fee(
         &self,
@@ -137,7 +167,8 @@ where
             inputs: vec![refund_details.try_into()?],
             fee: fee_target.into(),
             destination: &Destination::Single(&destination.try_into()?),
-        }))?;
+        }))
+        .await?;
 
         self.chain_client
             .send_raw_transaction(&hex::encode(tx.serialize()))
@@ -161,7 +192,7 @@ mod test {
     use crate::db::helpers::chain_swap::test::MockChainSwapHelper;
     use crate::db::helpers::refund_transaction::test::MockRefundTransactionHelper;
     use crate::db::helpers::reverse_swap::test::MockReverseSwapHelper;
-    use crate::db::models::ReverseSwap;
+    use crate::db::models::{ChainSwap, ChainSwapData, ChainSwapInfo, ReverseSwap};
     use crate::swap::SwapUpdate;
     use crate::wallet::{Bitcoin, Network};
     use mockall::predicate::eq;
@@ -170,6 +201,68 @@ mod test {
         Bitcoin::new(Network::Regtest, &[0; 64], "m/0/0".to_string(), client).unwrap()
     }
 
+    #[test]
+    fn test_timeout() {
+        let reverse = ReverseSwap {
+            id: "reverse".to_string(),
+            pair: "BTC/BTC".to_string(),
+            timeoutBlockHeight: 100,
+            ..Default::default()
+        };
+        assert_eq!(
+            RefundTransactionHandler::<
+                MockRefundTransactionHelper,
+                MockReverseSwapHelper,
+                MockChainSwapHelper,
+            >::timeout(&reverse)
+            .unwrap(),
+            Timeout {
+                pair: "BTC/BTC".to_string(),
+                swap_type: SwapType::Reverse,
+                block_height: 100,
+                counterparty_symbol: None,
+            }
+        );
+
+        let chain = ChainSwapInfo::new(
+            ChainSwap {
+                id: "chain".to_string(),
+                pair: "L-BTC/BTC".to_string(),
+                orderSide: 1,
+                ..Default::default()
+            },
+            vec![
+                ChainSwapData {
+                    swapId: "chain".to_string(),
+                    symbol: "BTC".to_string(),
+                    timeoutBlockHeight: 100,
+                    ..Default::default()
+                },
+                ChainSwapData {
+                    swapId: "chain".to_string(),
+                    symbol: "L-BTC".to_string(),
+                    timeoutBlockHeight: 200,
+                    ..Default::default()
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            RefundTransactionHandler::<
+                MockRefundTransactionHelper,
+                MockReverseSwapHelper,
+                MockChainSwapHelper,
+            >::timeout(&chain)
+            .unwrap(),
+            Timeout {
+                pair: "L-BTC/BTC".to_string(),
+                swap_type: SwapType::Chain,
+                block_height: 100,
+                counterparty_symbol: Some("L-BTC".to_string()),
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_bump_fee_elements() {
         let client = bitcoin_test::get_client().await;
@@ -178,6 +271,7 @@ mod test {
         let pending = PendingTransaction {
             swap_id: "swap".to_string(),
             transaction_id: "tx".to_string(),
+            timeout: None,
         };
 
         let mut reverse_helper = MockReverseSwapHelper::new();
@@ -1,5 +1,9 @@
+mod cpfp;
 mod engine;
+mod escalation;
 mod handlers;
 
 pub use engine::Bumper;
+pub use escalation::{Config as EscalationConfig, Escalation};
+pub use handlers::claim::ClaimTransactionHandler;
 pub use handlers::refund::RefundTransactionHandler;
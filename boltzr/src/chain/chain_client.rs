@@ -1,10 +1,14 @@
 use crate::chain::mempool_client::{MempoolSpace, Thresholds};
 use crate::chain::rpc_client::RpcClient;
 use crate::chain::types::{
-    BlockInfo, BlockchainInfo, NetworkInfo, RawMempool, RawTransactionVerbose, RpcParam,
-    SignRawTransactionResponse, SmartFeeEstimate, Type, UnspentOutput, ZmqNotification,
+    AddressInfo, BlockInfo, BlockchainInfo, MempoolEntry, NetworkInfo, RawMempool,
+    RawTransactionVerbose, RpcError, RpcParam, SignRawTransactionResponse, SmartFeeEstimate, Type,
+    UnspentOutput, ZmqNotification,
 };
 use crate::chain::utils::{Block, Outpoint, Transaction};
+use crate::chain::zero_conf_policy::{
+    LimitsPolicy, ZeroConfCandidate, ZeroConfDecision, ZeroConfPolicy, ZeroConfRejection,
+};
 use crate::chain::zmq_client::{ZMQ_BLOCK_CHANNEL_SIZE, ZMQ_TX_CHANNEL_SIZE, ZmqClient};
 use crate::chain::{BaseClient, Client, Config, Transactions};
 use crate::db::helpers::chain_tip::ChainTipHelper;
@@ -14,7 +18,7 @@ use async_trait::async_trait;
 use boltz_cache::Cache;
 use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::broadcast::{Receiver, Sender, channel, error::RecvError};
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
@@ -25,6 +29,9 @@ const BATCH_REQUEST_SIZE: usize = 64;
 const CHANNEL_BUFFER_SIZE: usize = 1_024;
 
 const BTC_KVB_SAT_VBYTE_FACTOR: u64 = 100_000;
+const BTC_SAT_FACTOR: f64 = 100_000_000.0;
+
+const MAX_ZERO_CONF_EVALUATIONS: usize = 3;
 
 const CACHE_TTL_SECS: u64 = 60 * 60 * 24;
 
@@ -42,6 +49,7 @@ pub struct ChainClient {
     mempool_thresholds: Thresholds,
     zmq_client: ZmqClient,
     mempool_space: Option<MempoolSpace>,
+    zero_conf_policy: Option<Arc<dyn ZeroConfPolicy + Send + Sync>>,
     tx_sender: Sender<(Transactions, bool)>,
     block_sender: Sender<(u64, Block)>,
 }
@@ -111,8 +119,23 @@ impl ChainClient {
             ))
         };
 
+        let zero_conf_policy: Option<Arc<dyn ZeroConfPolicy + Send + Sync>> =
+            match (&config.zero_conf, client_type) {
+                (Some(zero_conf), Type::Bitcoin) => {
+                    let policy = LimitsPolicy::new(zero_conf);
+                    info!("Using {symbol} 0-conf policy: {policy:?}");
+                    Some(Arc::new(policy))
+                }
+                (Some(_), Type::Elements) => {
+                    warn!("Ignoring 0-conf policy of {symbol}; use the 0-conf tool instead");
+                    None
+                }
+                (None, _) => None,
+            };
+
         let client = Self {
             cache,
+            zero_conf_policy,
             network,
             fee_floor,
             mempool_thresholds,
@@ -287,6 +310,166 @@ impl ChainClient {
         Ok(fee * BTC_KVB_SAT_VBYTE_FACTOR as f64)
     }
 
+    /// Replaces the 0-conf policy built from the config
+    pub fn with_zero_conf_policy(
+        mut self,
+        zero_conf_policy: Arc<dyn ZeroConfPolicy + Send + Sync>,
+    ) -> Self {
+        info!(
+            "Using {} 0-conf policy: {:?}",
+            self.symbol(),
+            zero_conf_policy
+        );
+        self.zero_conf_policy = Some(zero_conf_policy);
+        self
+    }
+
+    fn swap_output_amount(tx: &bitcoin::Transaction, swap_outputs: &[Vec<u8>]) -> u64 {
+        tx.output
+            .iter()
+            .filter(|out| {
+                swap_outputs
+                    .iter()
+                    .any(|script| script.as_slice() == out.script_pubkey.as_bytes())
+            })
+            .map(|out| out.value.to_sat())
+            .sum()
+    }
+
+    async fn zero_conf_candidate(
+        &self,
+        transaction: &Transaction,
+        swap_outputs: &[Vec<u8>],
+    ) -> anyhow::Result<Option<ZeroConfCandidate>> {
+        let tx = match transaction {
+            Transaction::Bitcoin(tx) => tx,
+            Transaction::Elements(_) => {
+                return Err(anyhow!("0-conf policy only supports Bitcoin transactions"));
+            }
+        };
+
+        let entry = match self.mempool_entry(&transaction.txid_hex()).await? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let (estimated_fee, mempool_space_fee) = tokio::join!(self.estimate_fee(), async {
+            match &self.mempool_space {
+                Some(mempool_space) => mempool_space
+                    .get_fees_and_height()
+                    .await
+                    .map(|(_, fee)| fee),
+                None => None,
+            }
+        });
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        Ok(Some(ZeroConfCandidate {
+            amount: Self::swap_output_amount(tx, swap_outputs),
+            signals_rbf: tx.is_explicitly_rbf(),
+            replaceable: entry.bip125_replaceable,
+            fee_rate: entry.fees.base * BTC_SAT_FACTOR / entry.vsize.max(1) as f64,
+            ancestor_fee_rate: entry.fees.ancestor * BTC_SAT_FACTOR
+                / entry.ancestor_size.max(1) as f64,
+            estimated_fee: estimated_fee?,
+            mempool_space_fee,
+            seen_for: Duration::from_secs(now.saturating_sub(entry.time)),
+        }))
+    }
+
+    async fn check_zero_conf(
+        &self,
+        policy: &(dyn ZeroConfPolicy + Send + Sync),
+        transaction: &Transaction,
+        swap_outputs: &[Vec<u8>],
+    ) -> bool {
+        let tx_id = transaction.txid_hex();
+
+        let mut evaluations = 0;
+        let result = loop {
+            evaluations += 1;
+
+            let candidate = match self.zero_conf_candidate(transaction, swap_outputs).await {
+                Ok(Some(candidate)) => candidate,
+                Ok(None) => break Err(ZeroConfRejection::NotInMempool),
+                Err(err) => {
+                    warn!(
+                        "Could not gather 0-conf information of {} transaction {}: {}",
+                        self.symbol(),
+                        tx_id,
+                        err
+                    );
+                    break Err(ZeroConfRejection::Error);
+                }
+            };
+            trace!(
+                "0-conf candidate {} transaction {}: {:?}",
+                self.symbol(),
+                tx_id,
+                candidate
+            );
+
+            match policy.evaluate(&candidate) {
+                ZeroConfDecision::Accept => break Ok(()),
+                ZeroConfDecision::Reject(reason) => break Err(reason),
+                ZeroConfDecision::RetryAfter(wait) => {
+                    if evaluations >= MAX_ZERO_CONF_EVALUATIONS {
+                        break Err(ZeroConfRejection::NotSeenLongEnough);
+                    }
+
+                    trace!(
+                        "Evaluating 0-conf of {} transaction {} again in {:?}",
+                        self.symbol(),
+                        tx_id,
+                        wait
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        };
+
+        match result {
+            Ok(_) => {
+                info!(
+                    "Accepting {} transaction {} with 0-conf",
+                    self.symbol(),
+                    tx_id
+                );
+
+                #[cfg(feature = "metrics")]
+                metrics::counter!(
+                    crate::metrics::ZEROCONF_POLICY_DECISIONS,
+                    "symbol" => self.symbol(),
+                    "result" => "accepted",
+                    "reason" => "",
+                )
+                .increment(1);
+
+                true
+            }
+            Err(reason) => {
+                debug!(
+                    "Not accepting {} transaction {} with 0-conf: {}",
+                    self.symbol(),
+                    tx_id,
+                    reason
+                );
+
+                #[cfg(feature = "metrics")]
+                metrics::counter!(
+                    crate::metrics::ZEROCONF_POLICY_DECISIONS,
+                    "symbol" => self.symbol(),
+                    "result" => "rejected",
+                    "reason" => reason.as_str(),
+                )
+                .increment(1);
+
+                false
+            }
+        }
+    }
+
     fn cache_key_raw_tx<'a>(&self, tx_id: &'a str) -> (String, &'a str) {
         (format!("{CACHE_KEY_RAW_TX}:{}", self.symbol()), tx_id)
     }
@@ -674,6 +857,24 @@ impl Client for ChainClient {
             .await
     }
 
+    async fn mempool_entry(&self, tx_id: &str) -> anyhow::Result<Option<MempoolEntry>> {
+        match self
+            .client
+            .request::<MempoolEntry>("getmempoolentry", Some(&[RpcParam::Str(tx_id)]))
+            .await
+        {
+            Ok(entry) => Ok(Some(entry)),
+            Err(err)
+                if err
+                    .downcast_ref::<RpcError>()
+                    .is_some_and(|err| err.code == RpcError::INVALID_ADDRESS_OR_KEY) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     async fn send_raw_transaction(&self, tx: &str) -> anyhow::Result<String> {
         self.client
             .request::<String>("sendrawtransaction", Some(&[RpcParam::Str(tx)]))
@@ -686,6 +887,18 @@ impl Client for ChainClient {
             .await
     }
 
+    async fn is_mine(&self, wallet: Option<&str>, address: &str) -> anyhow::Result<bool> {
+        Ok(self
+            .client
+            .request_wallet::<AddressInfo>(
+                wallet,
+                "getaddressinfo",
+                Some(&[RpcParam::Str(address)]),
+            )
+            .await?
+            .is_mine)
+    }
+
     async fn get_new_address(
         &self,
         wallet: Option<&str>,
@@ -745,9 +958,37 @@ impl Client for ChainClient {
         self.client.request_wallet(wallet, method, params).await
     }
 
-    fn zero_conf_safe(&self, _transaction: &Transaction) -> oneshot::Receiver<bool> {
+    fn zero_conf_safe(
+        &self,
+        transaction: &Transaction,
+        swap_outputs: &[Vec<u8>],
+    ) -> oneshot::Receiver<bool> {
         let (tx, rx) = oneshot::channel();
-        tx.send(false).unwrap();
+
+        let policy = match &self.zero_conf_policy {
+            Some(policy) => policy.clone(),
+            None => {
+                tx.send(false).unwrap();
+                return rx;
+            }
+        };
+
+        let client = self.clone();
+        let transaction = transaction.clone();
+        let swap_outputs = swap_outputs.to_vec();
+        tokio::spawn(async move {
+            let safe = client
+                .check_zero_conf(policy.as_ref(), &transaction, &swap_outputs)
+                .await;
+            if tx.send(safe).is_err() {
+                trace!(
+                    "0-conf result receiver of {} transaction {} dropped",
+                    client.symbol(),
+                    transaction.txid_hex()
+                );
+            }
+        });
+
         rx
     }
 
@@ -776,6 +1017,8 @@ pub mod test {
 
     const PORT: u16 = 18_443;
 
+    const BITCOIN_TX_HEX: &str = include_str!("../../fixtures/bitcoin-tx.txt");
+
     mock! {
         ChainTipHelper {}
 
@@ -1130,7 +1373,7 @@ pub mod test {
         let client = get_client().await;
 
         let tx = send_transaction(&client).await;
-        let zero_conf_safe = client.zero_conf_safe(&tx);
+        let zero_conf_safe = client.zero_conf_safe(&tx, &[]);
 
         let received = zero_conf_safe.await.unwrap();
         assert!(!received);
@@ -1138,6 +1381,69 @@ pub mod test {
         generate_block(&client).await;
     }
 
+    #[derive(Debug)]
+    struct AcceptPolicy;
+
+    impl ZeroConfPolicy for AcceptPolicy {
+        fn evaluate(&self, _candidate: &ZeroConfCandidate) -> ZeroConfDecision {
+            ZeroConfDecision::Accept
+        }
+    }
+
+    #[tokio::test]
+    #[serial(BTC)]
+    async fn test_zero_conf_safe_injected_policy() {
+        let client = get_client()
+            .await
+            .with_zero_conf_policy(Arc::new(AcceptPolicy));
+
+        let tx = send_transaction(&client).await;
+        let zero_conf_safe = client.zero_conf_safe(&tx, &tx.output_script_pubkeys());
+
+        let received = zero_conf_safe.await.unwrap();
+        assert!(received);
+
+        generate_block(&client).await;
+    }
+
+    #[tokio::test]
+    #[serial(BTC)]
+    async fn test_mempool_entry_not_found() {
+        let client = get_client().await;
+
+        let entry = client
+            .mempool_entry("0000000000000000000000000000000000000000000000000000000000000000")
+            .await
+            .unwrap();
+        assert!(entry.is_none());
+    }
+
+    #[test]
+    fn test_swap_output_amount() {
+        let tx = match Transaction::parse_hex(&Type::Bitcoin, BITCOIN_TX_HEX).unwrap() {
+            Transaction::Bitcoin(tx) => tx,
+            _ => unreachable!(),
+        };
+        assert!(tx.output.len() > 1);
+
+        let first = tx.output[0].script_pubkey.to_bytes();
+        assert_eq!(
+            ChainClient::swap_output_amount(&tx, std::slice::from_ref(&first)),
+            tx.output[0].value.to_sat()
+        );
+        assert_eq!(ChainClient::swap_output_amount(&tx, &[]), 0);
+        assert_eq!(
+            ChainClient::swap_output_amount(
+                &tx,
+                &tx.output
+                    .iter()
+                    .map(|out| out.script_pubkey.to_bytes())
+                    .collect::<Vec<_>>()
+            ),
+            tx.output.iter().map(|out| out.value.to_sat()).sum::<u64>()
+        );
+    }
+
     #[tokio::test]
     #[serial(BTC)]
     async fn rescan_empty() {
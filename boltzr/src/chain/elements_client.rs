@@ -1,8 +1,8 @@
 use crate::chain::chain_client::ChainClient;
 use crate::chain::elements::{ZeroConfCheck, zero_conf_tool};
 use crate::chain::types::{
-    BlockchainInfo, NetworkInfo, RawTransactionVerbose, SignRawTransactionResponse, Type,
-    UnspentOutput,
+    BlockchainInfo, MempoolEntry, NetworkInfo, RawTransactionVerbose, SignRawTransactionResponse,
+    Type, UnspentOutput,
 };
 use crate::chain::utils::{Block, Outpoint, Transaction};
 use crate::chain::{BaseClient, Client, LiquidConfig, Transactions};
@@ -146,10 +146,18 @@ impl Client for ElementsClient {
         self.client.send_raw_transaction(tx).await
     }
 
+    async fn mempool_entry(&self, tx_id: &str) -> anyhow::Result<Option<MempoolEntry>> {
+        self.client.mempool_entry(tx_id).await
+    }
+
     async fn list_unspent(&self, wallet: Option<&str>) -> anyhow::Result<Vec<UnspentOutput>> {
         self.client.list_unspent(wallet).await
     }
 
+    async fn is_mine(&self, wallet: Option<&str>, address: &str) -> anyhow::Result<bool> {
+        self.client.is_mine(wallet, address).await
+    }
+
     async fn get_new_address(
         &self,
         wallet: Option<&str>,
@@ -193,7 +201,11 @@ impl Client for ElementsClient {
         self.client.request_wallet(wallet, method, params).await
     }
 
-    fn zero_conf_safe(&self, transaction: &Transaction) -> oneshot::Receiver<bool> {
+    fn zero_conf_safe(
+        &self,
+        transaction: &Transaction,
+        swap_outputs: &[Vec<u8>],
+    ) -> oneshot::Receiver<bool> {
         if self.network == Network::Regtest {
             let (tx, rx) = oneshot::channel();
             let _ = tx.send(true);
@@ -202,7 +214,7 @@ impl Client for ElementsClient {
 
         match &self.zero_conf_tool {
             Some(tool) => tool.check_transaction(transaction),
-            None => self.client.zero_conf_safe(transaction),
+            None => self.client.zero_conf_safe(transaction, swap_outputs),
         }
     }
 
@@ -345,7 +357,7 @@ pub mod test {
         assert_eq!(client.network, Network::Regtest);
 
         let tx = send_transaction(&client).await;
-        let zero_conf_safe = client.zero_conf_safe(&tx);
+        let zero_conf_safe = client.zero_conf_safe(&tx, &[]);
 
         let received = zero_conf_safe.await.unwrap();
         assert!(received);
@@ -360,7 +372,7 @@ pub mod test {
         client.network = Network::Testnet;
 
         let tx = send_transaction(&client).await;
-        let zero_conf_safe = client.zero_conf_safe(&tx);
+        let zero_conf_safe = client.zero_conf_safe(&tx, &[]);
 
         let received = zero_conf_safe.await.unwrap();
         assert!(!received);
@@ -389,7 +401,7 @@ pub mod test {
         client.zero_conf_tool = Some(Arc::new(tool));
 
         let tx = Transaction::parse_hex(&Type::Elements, ELEMENTS_TX_HEX).unwrap();
-        let zero_conf_safe = client.zero_conf_safe(&tx);
+        let zero_conf_safe = client.zero_conf_safe(&tx, &[]);
 
         let received = zero_conf_safe.await.unwrap();
         assert_eq!(received, expected);
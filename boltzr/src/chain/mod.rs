@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::{broadcast, oneshot};
+use zero_conf_policy::ZeroConfPolicyConfig;
 
 pub mod bumper;
 pub mod chain_client;
@@ -19,6 +20,7 @@ pub mod mrh_watcher;
 mod rpc_client;
 pub mod types;
 pub mod utils;
+pub mod zero_conf_policy;
 pub mod zmq_client;
 
 #[derive(PartialEq, Debug, Clone)]
@@ -78,6 +80,14 @@ pub struct Config {
     /// Minimum fee rate in sat/vbyte used as floor for fee estimation
     #[serde(rename = "feeFloor")]
     pub fee_floor: Option<f64>,
+
+    /// Policy for accepting Bitcoin transactions with 0-conf; they are never accepted when not set
+    #[serde(rename = "zeroConf")]
+    pub zero_conf: Option<ZeroConfPolicyConfig>,
+
+    /// Escalation of the fee of refund and claim transactions as they approach their deadline
+    #[serde(rename = "feeEscalation")]
+    pub fee_escalation: Option<bumper::EscalationConfig>,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
@@ -128,7 +138,12 @@ pub trait Client: BaseClient {
 
     async fn send_raw_transaction(&self, tx: &str) -> Result<String>;
 
+    /// Returns `None` when the transaction is not in the mempool
+    async fn mempool_entry(&self, tx_id: &str) -> Result<Option<types::MempoolEntry>>;
+
     async fn list_unspent(&self, wallet: Option<&str>) -> Result<Vec<types::UnspentOutput>>;
+    /// Whether the wallet of the node can spend from the address
+    async fn is_mine(&self, wallet: Option<&str>, address: &str) -> Result<bool>;
     async fn get_new_address(
         &self,
         wallet: Option<&str>,
@@ -150,7 +165,13 @@ pub trait Client: BaseClient {
         params: Option<&[types::RpcParam<'_>]>,
     ) -> Result<serde_json::Value>;
 
-    fn zero_conf_safe(&self, transaction: &Transaction) -> oneshot::Receiver<bool>;
+    /// `swap_outputs` are the script pubkeys of the outputs that pay swaps; only their value
+    /// counts towards the amount limit of the 0-conf policy
+    fn zero_conf_safe(
+        &self,
+        transaction: &Transaction,
+        swap_outputs: &[Vec<u8>],
+    ) -> oneshot::Receiver<bool>;
 
     fn tx_receiver(&self) -> broadcast::Receiver<(Transactions, bool)>;
     fn block_receiver(&self) -> broadcast::Receiver<(u64, Block)>;
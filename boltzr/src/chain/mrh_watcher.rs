@@ -83,7 +83,10 @@ impl MrhWatcher {
             let swap_status_update_tx = self.swap_status_update_tx.clone();
 
             tokio::spawn(async move {
-                match chain_client.zero_conf_safe(&tx).await {
+                match chain_client
+                    .zero_conf_safe(&tx, std::slice::from_ref(&routing_hint.scriptPubkey))
+                    .await
+                {
                     Ok(true) => {}
                     _ => return,
                 }
@@ -100,6 +103,7 @@ impl MrhWatcher {
                         }),
                         ..Default::default()
                     },
+                    event_id: None,
                 };
 
                 if let Err(e) = swap_status_update_tx.send(update) {
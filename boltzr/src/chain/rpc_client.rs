@@ -114,7 +114,7 @@ impl RpcClient {
             .into_iter()
             .map(|res| {
                 if let Some(err) = res.error {
-                    Err(anyhow!(err.message))
+                    Err(anyhow::Error::new(err))
                 } else if let Some(res) = res.result {
                     Ok(res)
                 } else {
@@ -140,7 +140,7 @@ impl RpcClient {
 
         let data = self.parse_response::<RpcResponse<T>>(response).await?;
         if let Some(err) = data.error {
-            return Err(anyhow!(err.message));
+            return Err(anyhow::Error::new(err));
         }
 
         match data.result {
@@ -209,6 +209,7 @@ impl RpcClient {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::chain::types::RpcError;
     use rstest::rstest;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -386,6 +387,35 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_request_error_code() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(serde_json::json!({
+                "result": null,
+                "error": {
+                    "code": -5,
+                    "message": "Transaction not in mempool",
+                },
+                "id": null,
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = RpcClient::new("BTC".to_string(), get_mock_config(&mock_server)).unwrap();
+        let res = client
+            .request::<serde_json::Value>("getmempoolentry", Some(&[RpcParam::Str("00")]))
+            .await
+            .unwrap_err();
+
+        assert_eq!(res.to_string(), "Transaction not in mempool");
+        assert_eq!(
+            res.downcast_ref::<RpcError>().unwrap().code,
+            RpcError::INVALID_ADDRESS_OR_KEY
+        );
+    }
+
     #[tokio::test]
     async fn test_request_batch_non_json_error() {
         let mock_server = MockServer::start().await;
@@ -56,9 +56,24 @@ pub struct RpcRequest<'a> {
 
 #[derive(Deserialize, Debug)]
 pub struct RpcError {
+    #[serde(default)]
+    pub code: i64,
     pub message: String,
 }
 
+impl RpcError {
+    /// Returned by bitcoind and elementsd when a transaction or key can't be found
+    pub const INVALID_ADDRESS_OR_KEY: i64 = -5;
+}
+
+impl Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
 #[derive(Deserialize, Debug)]
 pub struct RpcResponse<T>
 where
@@ -123,6 +138,38 @@ pub struct UnspentOutput {
     pub asset: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolEntryFees {
+    /// In BTC
+    pub base: f64,
+    /// In BTC
+    pub ancestor: f64,
+    /// In BTC
+    pub descendant: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MempoolEntry {
+    pub vsize: u64,
+    /// UNIX timestamp of when the transaction entered the mempool
+    pub time: u64,
+    #[serde(rename = "ancestorsize")]
+    pub ancestor_size: u64,
+    #[serde(rename = "descendantcount")]
+    pub descendant_count: u64,
+    #[serde(rename = "descendantsize")]
+    pub descendant_size: u64,
+    pub fees: MempoolEntryFees,
+    #[serde(rename = "bip125-replaceable")]
+    pub bip125_replaceable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddressInfo {
+    #[serde(rename = "ismine")]
+    pub is_mine: bool,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct SignRawTransactionResponse {
     pub hex: String,
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+const DEFAULT_MIN_FEE_RATIO: f64 = 1.0;
+const DEFAULT_MIN_SEEN_SECS: u64 = 5;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+pub struct ZeroConfPolicyConfig {
+    /// Maximal sum of the outputs of a transaction paying swaps in satoshis that is accepted with 0-conf
+    #[serde(rename = "maxAmount")]
+    pub max_amount: u64,
+
+    /// Minimal ratio between the fee rate of a transaction and the current fee estimation
+    #[serde(rename = "minFeeRatio")]
+    pub min_fee_ratio: Option<f64>,
+
+    /// Minimal number of seconds a transaction has to be in our mempool before it is accepted
+    #[serde(rename = "minSeenSecs")]
+    pub min_seen_secs: Option<u64>,
+}
+
+/// Everything a policy gets to know about an unconfirmed transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeroConfCandidate {
+    /// Sum of the outputs paying swaps in satoshis; change and other outputs are not included
+    pub amount: u64,
+    /// Whether the transaction itself signals BIP-125 replaceability
+    pub signals_rbf: bool,
+    /// Whether the transaction or any of its unconfirmed ancestors signals replaceability
+    pub replaceable: bool,
+    /// Fee rate of the transaction in sat/vbyte
+    pub fee_rate: f64,
+    /// Fee rate of the transaction including its unconfirmed ancestors in sat/vbyte
+    pub ancestor_fee_rate: f64,
+    /// Our fee estimation in sat/vbyte
+    pub estimated_fee: f64,
+    /// Fee estimation of mempool.space in sat/vbyte
+    pub mempool_space_fee: Option<f64>,
+    /// How long the transaction has been in our mempool
+    pub seen_for: Duration,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ZeroConfRejection {
+    AmountTooHigh,
+    SignalsRbf,
+    AncestorSignalsRbf,
+    FeeTooLow,
+    NotSeenLongEnough,
+    NotInMempool,
+    Error,
+}
+
+impl ZeroConfRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZeroConfRejection::AmountTooHigh => "amount",
+            ZeroConfRejection::SignalsRbf => "rbf",
+            ZeroConfRejection::AncestorSignalsRbf => "ancestor_rbf",
+            ZeroConfRejection::FeeTooLow => "fee",
+            ZeroConfRejection::NotSeenLongEnough => "seen_time",
+            ZeroConfRejection::NotInMempool => "not_in_mempool",
+            ZeroConfRejection::Error => "error",
+        }
+    }
+}
+
+impl Display for ZeroConfRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZeroConfRejection::AmountTooHigh => f.write_str("amount is above the 0-conf limit"),
+            ZeroConfRejection::SignalsRbf => f.write_str("transaction signals RBF"),
+            ZeroConfRejection::AncestorSignalsRbf => {
+                f.write_str("unconfirmed ancestor signals RBF")
+            }
+            ZeroConfRejection::FeeTooLow => f.write_str("fee rate is too low"),
+            ZeroConfRejection::NotSeenLongEnough => {
+                f.write_str("transaction was not in the mempool for long enough")
+            }
+            ZeroConfRejection::NotInMempool => f.write_str("transaction is not in the mempool"),
+            ZeroConfRejection::Error => f.write_str("could not gather transaction information"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZeroConfDecision {
+    Accept,
+    Reject(ZeroConfRejection),
+    /// The candidate should be evaluated again after waiting for the duration
+    RetryAfter(Duration),
+}
+
+pub trait ZeroConfPolicy: std::fmt::Debug {
+    fn evaluate(&self, candidate: &ZeroConfCandidate) -> ZeroConfDecision;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitsPolicy {
+    max_amount: u64,
+    min_fee_ratio: f64,
+    min_seen: Duration,
+}
+
+impl LimitsPolicy {
+    pub fn new(config: &ZeroConfPolicyConfig) -> Self {
+        Self {
+            max_amount: config.max_amount,
+            min_fee_ratio: config.min_fee_ratio.unwrap_or(DEFAULT_MIN_FEE_RATIO),
+            min_seen: Duration::from_secs(config.min_seen_secs.unwrap_or(DEFAULT_MIN_SEEN_SECS)),
+        }
+    }
+}
+
+impl ZeroConfPolicy for LimitsPolicy {
+    fn evaluate(&self, candidate: &ZeroConfCandidate) -> ZeroConfDecision {
+        if candidate.amount > self.max_amount {
+            return ZeroConfDecision::Reject(ZeroConfRejection::AmountTooHigh);
+        }
+
+        if candidate.signals_rbf {
+            return ZeroConfDecision::Reject(ZeroConfRejection::SignalsRbf);
+        }
+
+        if candidate.replaceable {
+            return ZeroConfDecision::Reject(ZeroConfRejection::AncestorSignalsRbf);
+        }
+
+        // Low fee ancestors drag down the chance of the transaction to be included in a block
+        let effective_fee_rate = candidate.fee_rate.min(candidate.ancestor_fee_rate);
+        let required_fee_rate = candidate
+            .estimated_fee
+            .max(candidate.mempool_space_fee.unwrap_or_default())
+            * self.min_fee_ratio;
+        if effective_fee_rate < required_fee_rate {
+            return ZeroConfDecision::Reject(ZeroConfRejection::FeeTooLow);
+        }
+
+        if candidate.seen_for < self.min_seen {
+            return ZeroConfDecision::RetryAfter(self.min_seen - candidate.seen_for);
+        }
+
+        ZeroConfDecision::Accept
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn policy() -> LimitsPolicy {
+        LimitsPolicy::new(&ZeroConfPolicyConfig {
+            max_amount: 1_000_000,
+            min_fee_ratio: Some(0.9),
+            min_seen_secs: Some(10),
+        })
+    }
+
+    fn candidate() -> ZeroConfCandidate {
+        ZeroConfCandidate {
+            amount: 500_000,
+            signals_rbf: false,
+            replaceable: false,
+            fee_rate: 10.0,
+            ancestor_fee_rate: 10.0,
+            estimated_fee: 10.0,
+            mempool_space_fee: Some(10.5),
+            seen_for: Duration::from_secs(15),
+        }
+    }
+
+    #[test]
+    fn test_accept() {
+        assert_eq!(policy().evaluate(&candidate()), ZeroConfDecision::Accept);
+    }
+
+    #[test]
+    fn test_defaults() {
+        let policy = LimitsPolicy::new(&ZeroConfPolicyConfig {
+            max_amount: 21,
+            ..Default::default()
+        });
+        assert_eq!(policy.min_fee_ratio, DEFAULT_MIN_FEE_RATIO);
+        assert_eq!(policy.min_seen, Duration::from_secs(DEFAULT_MIN_SEEN_SECS));
+    }
+
+    #[test]
+    fn test_reject_amount() {
+        let mut candidate = candidate();
+        candidate.amount = 1_000_001;
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::Reject(ZeroConfRejection::AmountTooHigh)
+        );
+    }
+
+    #[test]
+    fn test_reject_rbf() {
+        let mut candidate = candidate();
+        candidate.signals_rbf = true;
+        candidate.replaceable = true;
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::Reject(ZeroConfRejection::SignalsRbf)
+        );
+    }
+
+    #[test]
+    fn test_reject_ancestor_rbf() {
+        let mut candidate = candidate();
+        candidate.replaceable = true;
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::Reject(ZeroConfRejection::AncestorSignalsRbf)
+        );
+    }
+
+    #[test]
+    fn test_reject_fee_below_estimation() {
+        let mut candidate = candidate();
+        candidate.fee_rate = 8.9;
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::Reject(ZeroConfRejection::FeeTooLow)
+        );
+    }
+
+    #[test]
+    fn test_reject_fee_below_mempool_space() {
+        let mut candidate = candidate();
+        candidate.mempool_space_fee = Some(20.0);
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::Reject(ZeroConfRejection::FeeTooLow)
+        );
+    }
+
+    #[test]
+    fn test_reject_low_fee_ancestor() {
+        let mut candidate = candidate();
+        candidate.fee_rate = 50.0;
+        candidate.ancestor_fee_rate = 2.0;
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::Reject(ZeroConfRejection::FeeTooLow)
+        );
+    }
+
+    #[test]
+    fn test_retry_when_seen_too_recently() {
+        let mut candidate = candidate();
+        candidate.seen_for = Duration::from_secs(3);
+        assert_eq!(
+            policy().evaluate(&candidate),
+            ZeroConfDecision::RetryAfter(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn test_rejection_labels() {
+        assert_eq!(
+            ZeroConfRejection::AncestorSignalsRbf.as_str(),
+            "ancestor_rbf"
+        );
+        assert_eq!(
+            ZeroConfRejection::FeeTooLow.to_string(),
+            "fee rate is too low"
+        );
+    }
+}
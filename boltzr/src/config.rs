@@ -335,6 +335,9 @@ macaroonpath = "/tmp/fulmine/admin.macaroon"
                 }],
                 tokens: None,
                 quoters: None,
+                indexer: None,
+                nonce_manager: None,
+                remote_signer: None,
             }
         );
 
@@ -369,10 +372,13 @@ macaroonpath = "/tmp/fulmine/admin.macaroon"
                     request_timeout: None,
                     max_retries: None,
                     block_list: None,
+                    ..Default::default()
                 }),
                 api: crate::api::Config {
                     host: "127.0.0.1".to_string(),
                     port: 9005,
+                    rate_limits: None,
+                    trusted_proxies: None,
                 },
                 ws: crate::ws::Config {
                     host: "0.0.0.0".to_string(),
@@ -1,18 +1,11 @@
 use crate::api::ws::OfferSubscriptions;
 use crate::ark::Config as ArkConfig;
-use crate::chain::{
-    BaseClient,
-    bumper::{Bumper, RefundTransactionHandler},
-    chain_client::ChainClient,
-    elements_client::ElementsClient,
-};
+use crate::chain::{BaseClient, chain_client::ChainClient, elements_client::ElementsClient};
 use crate::config::{CurrencyConfig, LiquidConfig};
 use crate::db::{
     Pool,
     helpers::{
-        chain_swap::ChainSwapHelperDatabase, keys::KeysHelper, offer::OfferHelperDatabase,
-        refund_transaction::RefundTransactionHelperDatabase,
-        reverse_swap::ReverseSwapHelperDatabase,
+        keys::KeysHelper, offer::OfferHelperDatabase, reverse_swap::ReverseSwapHelperDatabase,
     },
 };
 use crate::lightning::{cln::Cln, lnd::Lnd};
@@ -130,17 +123,6 @@ pub async fn connect_nodes<K: KeysHelper>(
                 }
                 .map(|wallet| Arc::new(wallet) as Arc<dyn Wallet + Send + Sync>);
 
-                if let Some(chain) = chain.as_ref()
-                    && let Some(wallet) = wallet.as_ref()
-                {
-                    create_bumper(
-                        cancellation_token.clone(),
-                        db.clone(),
-                        chain.clone(),
-                        wallet.clone(),
-                    );
-                }
-
                 curs.insert(
                     symbol.clone(),
                     Currency {
@@ -205,17 +187,6 @@ pub async fn connect_nodes<K: KeysHelper>(
         }
         .map(|wallet| Arc::new(wallet) as Arc<dyn Wallet + Send + Sync>);
 
-        if let Some(chain) = chain.as_ref()
-            && let Some(wallet) = wallet.as_ref()
-        {
-            create_bumper(
-                cancellation_token.clone(),
-                db.clone(),
-                chain.clone(),
-                wallet.clone(),
-            );
-        }
-
         curs.insert(
             crate::chain::elements_client::SYMBOL.to_string(),
             Currency {
@@ -336,29 +307,6 @@ async fn connect_lnds(
     lnds
 }
 
-fn create_bumper(
-    cancellation_token: CancellationToken,
-    db: Pool,
-    chain: Arc<dyn crate::chain::Client + Send + Sync>,
-    wallet: Arc<dyn Wallet + Send + Sync>,
-) {
-    let bumper = Bumper::new(
-        cancellation_token.clone(),
-        chain.clone(),
-        vec![Arc::new(RefundTransactionHandler::new(
-            RefundTransactionHelperDatabase::new(db.clone()),
-            ReverseSwapHelperDatabase::new(db.clone()),
-            ChainSwapHelperDatabase::new(db),
-            wallet,
-            chain,
-        ))],
-    );
-
-    tokio::spawn(async move {
-        bumper.start().await;
-    });
-}
-
 async fn connect_client<T: BaseClient>(client: anyhow::Result<T>) -> Option<T> {
     match client {
         Ok(mut client) => match timeout(NODE_CONNECTION_TIMEOUT, client.connect()).await {
@@ -0,0 +1,169 @@
+use crate::db::Pool;
+use crate::db::helpers::QueryResponse;
+use crate::db::models::{NewClaimReplacement, PendingClaim};
+use crate::db::schema::{claim_replacements, pending_claims};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::{insert_into, update};
+use strum_macros::{AsRefStr, EnumString};
+use tracing::instrument;
+
+#[derive(EnumString, AsRefStr, Debug, PartialEq, Clone, Copy)]
+pub enum ClaimStatus {
+    #[strum(serialize = "pending")]
+    Pending,
+    #[strum(serialize = "confirmed")]
+    Confirmed,
+}
+
+impl From<ClaimStatus> for String {
+    fn from(value: ClaimStatus) -> Self {
+        String::from(value.as_ref())
+    }
+}
+
+pub trait ClaimTransactionHelper {
+    fn insert_pending(&self, claims: &[PendingClaim]) -> QueryResponse<usize>;
+    fn get_pending(&self, symbol: &str) -> QueryResponse<Vec<PendingClaim>>;
+    fn get_by_transaction_id(&self, transaction_id: &str) -> QueryResponse<Vec<PendingClaim>>;
+    fn set_confirmed(&self, transaction_id: &str) -> QueryResponse<usize>;
+
+    /// Points all claims of `replaced_transaction_id` to `transaction_id` and records the replacement
+    fn replace(
+        &self,
+        replaced_transaction_id: &str,
+        transaction_id: &str,
+        fee_rate: f64,
+    ) -> QueryResponse<usize>;
+}
+
+#[derive(Clone, Debug)]
+pub struct ClaimTransactionHelperDatabase {
+    pool: Pool,
+}
+
+impl ClaimTransactionHelperDatabase {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+impl ClaimTransactionHelper for ClaimTransactionHelperDatabase {
+    #[instrument(name = "db::ClaimTransactionHelperDatabase::insert_pending", skip_all)]
+    fn insert_pending(&self, claims: &[PendingClaim]) -> QueryResponse<usize> {
+        Ok(insert_into(pending_claims::dsl::pending_claims)
+            .values(claims)
+            .on_conflict(pending_claims::dsl::swap_id)
+            .do_update()
+            .set((
+                pending_claims::dsl::transaction_id
+                    .eq(excluded(pending_claims::dsl::transaction_id)),
+                pending_claims::dsl::status.eq(excluded(pending_claims::dsl::status)),
+            ))
+            .execute(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(name = "db::ClaimTransactionHelperDatabase::get_pending", skip(self))]
+    fn get_pending(&self, symbol: &str) -> QueryResponse<Vec<PendingClaim>> {
+        Ok(pending_claims::dsl::pending_claims
+            .select(PendingClaim::as_select())
+            .filter(pending_claims::dsl::symbol.eq(symbol))
+            .filter(pending_claims::dsl::status.eq(String::from(ClaimStatus::Pending)))
+            .order(pending_claims::dsl::created_at.asc())
+            .load(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(
+        name = "db::ClaimTransactionHelperDatabase::get_by_transaction_id",
+        skip(self)
+    )]
+    fn get_by_transaction_id(&self, transaction_id: &str) -> QueryResponse<Vec<PendingClaim>> {
+        Ok(pending_claims::dsl::pending_claims
+            .select(PendingClaim::as_select())
+            .filter(pending_claims::dsl::transaction_id.eq(transaction_id))
+            .order(pending_claims::dsl::swap_id.asc())
+            .load(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(name = "db::ClaimTransactionHelperDatabase::set_confirmed", skip(self))]
+    fn set_confirmed(&self, transaction_id: &str) -> QueryResponse<usize> {
+        Ok(update(pending_claims::dsl::pending_claims)
+            .filter(pending_claims::dsl::transaction_id.eq(transaction_id))
+            .set(pending_claims::dsl::status.eq(String::from(ClaimStatus::Confirmed)))
+            .execute(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(name = "db::ClaimTransactionHelperDatabase::replace", skip(self))]
+    fn replace(
+        &self,
+        replaced_transaction_id: &str,
+        transaction_id: &str,
+        fee_rate: f64,
+    ) -> QueryResponse<usize> {
+        Ok(self.pool.get()?.transaction(|con| {
+            let swap_ids = pending_claims::dsl::pending_claims
+                .select(pending_claims::dsl::swap_id)
+                .filter(pending_claims::dsl::transaction_id.eq(replaced_transaction_id))
+                .load::<String>(con)?;
+
+            insert_into(claim_replacements::dsl::claim_replacements)
+                .values(
+                    swap_ids
+                        .into_iter()
+                        .map(|swap_id| NewClaimReplacement {
+                            swap_id,
+                            replaced_transaction_id: replaced_transaction_id.to_string(),
+                            transaction_id: transaction_id.to_string(),
+                            fee_rate,
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .execute(con)?;
+
+            update(pending_claims::dsl::pending_claims)
+                .filter(pending_claims::dsl::transaction_id.eq(replaced_transaction_id))
+                .set(pending_claims::dsl::transaction_id.eq(transaction_id))
+                .execute(con)
+        })?)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use mockall::mock;
+    use std::str::FromStr;
+
+    mock! {
+        pub ClaimTransactionHelper {}
+
+        impl Clone for ClaimTransactionHelper {
+            fn clone(&self) -> Self;
+        }
+
+        impl ClaimTransactionHelper for ClaimTransactionHelper {
+            fn insert_pending(&self, claims: &[PendingClaim]) -> QueryResponse<usize>;
+            fn get_pending(&self, symbol: &str) -> QueryResponse<Vec<PendingClaim>>;
+            fn get_by_transaction_id(&self, transaction_id: &str) -> QueryResponse<Vec<PendingClaim>>;
+            fn set_confirmed(&self, transaction_id: &str) -> QueryResponse<usize>;
+            fn replace(
+                &self,
+                replaced_transaction_id: &str,
+                transaction_id: &str,
+                fee_rate: f64,
+            ) -> QueryResponse<usize>;
+        }
+    }
+
+    #[test]
+    fn test_claim_status_serialization() {
+        assert_eq!(String::from(ClaimStatus::Pending), "pending");
+        assert_eq!(String::from(ClaimStatus::Confirmed), "confirmed");
+
+        assert_eq!(
+            ClaimStatus::from_str("confirmed").unwrap(),
+            ClaimStatus::Confirmed
+        );
+        assert!(ClaimStatus::from_str("unknown").is_err());
+    }
+}
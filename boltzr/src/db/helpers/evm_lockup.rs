@@ -0,0 +1,645 @@
+use crate::db::Pool;
+use crate::db::helpers::QueryResponse;
+use crate::db::models::{EvmClaim, EvmIndexedBlock, EvmLockup, EvmRefund};
+use crate::db::schema::{evm_claims, evm_indexed_blocks, evm_lockups, evm_refunds};
+use boltz_evm::FixedBytes;
+use boltz_evm::indexer::{IndexedBlock, IndexedEvent, Store, SwapEvent};
+use diesel::prelude::*;
+use diesel::{delete, insert_into};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tracing::instrument;
+
+pub trait EvmLockupHelper {
+    /// Height up to which the events of `symbol` were indexed
+    fn get_indexed_height(&self, symbol: &str) -> QueryResponse<Option<u64>>;
+
+    /// Lockups that have been neither claimed nor refunded, ordered by their position in the chain
+    fn get_active_lockups(&self, symbol: &str) -> QueryResponse<Vec<EvmLockup>>;
+}
+
+#[derive(Clone, Debug)]
+pub struct EvmLockupHelperDatabase {
+    pool: Pool,
+}
+
+impl EvmLockupHelperDatabase {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    fn get_tip(&self, symbol: &str) -> QueryResponse<Option<EvmIndexedBlock>> {
+        Ok(evm_indexed_blocks::dsl::evm_indexed_blocks
+            .select(EvmIndexedBlock::as_select())
+            .filter(evm_indexed_blocks::dsl::symbol.eq(symbol))
+            .order(evm_indexed_blocks::dsl::height.desc())
+            .first(&mut self.pool.get()?)
+            .optional()?)
+    }
+}
+
+impl EvmLockupHelper for EvmLockupHelperDatabase {
+    #[instrument(name = "db::EvmLockupHelperDatabase::get_indexed_height", skip(self))]
+    fn get_indexed_height(&self, symbol: &str) -> QueryResponse<Option<u64>> {
+        Ok(self
+            .get_tip(symbol)?
+            .map(|block| u64::try_from(block.height))
+            .transpose()?)
+    }
+
+    #[instrument(name = "db::EvmLockupHelperDatabase::get_active_lockups", skip(self))]
+    fn get_active_lockups(&self, symbol: &str) -> QueryResponse<Vec<EvmLockup>> {
+        let mut con = self.pool.get()?;
+
+        // Only claims and refunds after the lockup can settle it
+        let claims = evm_claims::table.on(evm_claims::symbol
+            .eq(evm_lockups::symbol)
+            .and(evm_claims::contract_address.eq(evm_lockups::contract_address))
+            .and(evm_claims::preimage_hash.eq(evm_lockups::preimage_hash))
+            .and(
+                evm_claims::block_height
+                    .gt(evm_lockups::block_height)
+                    .or(evm_claims::block_height
+                        .eq(evm_lockups::block_height)
+                        .and(evm_claims::log_index.gt(evm_lockups::log_index))),
+            ));
+        let refunds = evm_refunds::table.on(evm_refunds::symbol
+            .eq(evm_lockups::symbol)
+            .and(evm_refunds::contract_address.eq(evm_lockups::contract_address))
+            .and(evm_refunds::preimage_hash.eq(evm_lockups::preimage_hash))
+            .and(
+                evm_refunds::block_height.gt(evm_lockups::block_height).or(
+                    evm_refunds::block_height
+                        .eq(evm_lockups::block_height)
+                        .and(evm_refunds::log_index.gt(evm_lockups::log_index)),
+                ),
+            ));
+
+        // When a preimage hash was used for multiple lockups, a single settlement would
+        // match all lockups before it. Those groups are matched one-to-one below instead
+        let reused = evm_lockups::dsl::evm_lockups
+            .filter(evm_lockups::dsl::symbol.eq(symbol))
+            .group_by((
+                evm_lockups::dsl::contract_address,
+                evm_lockups::dsl::preimage_hash,
+            ))
+            .having(diesel::dsl::count_star().gt(1))
+            .select((
+                evm_lockups::dsl::contract_address,
+                evm_lockups::dsl::preimage_hash,
+            ))
+            .load::<(Vec<u8>, Vec<u8>)>(&mut con)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut active = evm_lockups::dsl::evm_lockups
+            .left_join(claims)
+            .left_join(refunds)
+            .filter(evm_lockups::dsl::symbol.eq(symbol))
+            .filter(evm_claims::dsl::symbol.is_null())
+            .filter(evm_refunds::dsl::symbol.is_null())
+            .select(EvmLockup::as_select())
+            .load(&mut con)?
+            .into_iter()
+            .filter(|lockup| {
+                !reused.contains(&(
+                    lockup.contract_address.clone(),
+                    lockup.preimage_hash.clone(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        if !reused.is_empty() {
+            let preimage_hashes = reused
+                .iter()
+                .map(|(_, preimage_hash)| preimage_hash.clone())
+                .collect::<Vec<_>>();
+            let in_reused = |contract_address: &Vec<u8>, preimage_hash: &Vec<u8>| {
+                reused.contains(&(contract_address.clone(), preimage_hash.clone()))
+            };
+
+            let lockups = evm_lockups::dsl::evm_lockups
+                .select(EvmLockup::as_select())
+                .filter(evm_lockups::dsl::symbol.eq(symbol))
+                .filter(evm_lockups::dsl::preimage_hash.eq_any(preimage_hashes.clone()))
+                .load(&mut con)?
+                .into_iter()
+                .filter(|lockup| in_reused(&lockup.contract_address, &lockup.preimage_hash));
+            let claims = evm_claims::dsl::evm_claims
+                .select(EvmClaim::as_select())
+                .filter(evm_claims::dsl::symbol.eq(symbol))
+                .filter(evm_claims::dsl::preimage_hash.eq_any(preimage_hashes.clone()))
+                .load(&mut con)?
+                .into_iter()
+                .filter(|claim| in_reused(&claim.contract_address, &claim.preimage_hash))
+                .map(|claim| Settlement {
+                    contract_address: claim.contract_address,
+                    preimage_hash: claim.preimage_hash,
+                    block_height: claim.block_height,
+                    log_index: claim.log_index,
+                });
+            let refunds = evm_refunds::dsl::evm_refunds
+                .select(EvmRefund::as_select())
+                .filter(evm_refunds::dsl::symbol.eq(symbol))
+                .filter(evm_refunds::dsl::preimage_hash.eq_any(preimage_hashes.clone()))
+                .load(&mut con)?
+                .into_iter()
+                .filter(|refund| in_reused(&refund.contract_address, &refund.preimage_hash))
+                .map(|refund| Settlement {
+                    contract_address: refund.contract_address,
+                    preimage_hash: refund.preimage_hash,
+                    block_height: refund.block_height,
+                    log_index: refund.log_index,
+                });
+
+            active.extend(match_settlements(lockups, claims.chain(refunds)));
+        }
+
+        active.sort_by_key(|lockup| (lockup.block_height, lockup.log_index));
+        Ok(active)
+    }
+}
+
+/// Claim or refund of a lockup
+struct Settlement {
+    contract_address: Vec<u8>,
+    preimage_hash: Vec<u8>,
+    block_height: i64,
+    log_index: i64,
+}
+
+/// Returns the lockups that are left open after matching every settlement
+/// with a single lockup of the same contract and preimage hash
+///
+/// The events of claims and refunds only contain the preimage hash, so which of
+/// the lockups sharing it was settled cannot be told from them. Every settlement
+/// is assumed to settle the oldest lockup that was still open at that point
+fn match_settlements(
+    lockups: impl IntoIterator<Item = EvmLockup>,
+    settlements: impl IntoIterator<Item = Settlement>,
+) -> Vec<EvmLockup> {
+    // Lockups sort before settlements at the same position, which cannot happen
+    // on chain anyway because a log index is unique in its block
+    let mut events = lockups
+        .into_iter()
+        .map(|lockup| {
+            (
+                (lockup.block_height, lockup.log_index, 0),
+                (
+                    lockup.contract_address.clone(),
+                    lockup.preimage_hash.clone(),
+                ),
+                Some(lockup),
+            )
+        })
+        .chain(settlements.into_iter().map(|settlement| {
+            (
+                (settlement.block_height, settlement.log_index, 1),
+                (settlement.contract_address, settlement.preimage_hash),
+                None,
+            )
+        }))
+        .collect::<Vec<_>>();
+    events.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut open = HashMap::<_, VecDeque<EvmLockup>>::new();
+    for (_, key, lockup) in events {
+        let lockups = open.entry(key).or_default();
+        match lockup {
+            Some(lockup) => lockups.push_back(lockup),
+            None => {
+                lockups.pop_front();
+            }
+        }
+    }
+
+    open.into_values().flatten().collect()
+}
+
+impl TryFrom<EvmIndexedBlock> for IndexedBlock {
+    type Error = anyhow::Error;
+
+    fn try_from(block: EvmIndexedBlock) -> Result<Self, Self::Error> {
+        Ok(IndexedBlock {
+            height: u64::try_from(block.height)?,
+            hash: FixedBytes::try_from(block.hash.as_slice())?,
+            parent_hash: FixedBytes::try_from(block.parent_hash.as_slice())?,
+        })
+    }
+}
+
+impl Store for EvmLockupHelperDatabase {
+    fn tip(&self, symbol: &str) -> anyhow::Result<Option<IndexedBlock>> {
+        self.get_tip(symbol)?
+            .map(IndexedBlock::try_from)
+            .transpose()
+    }
+
+    #[instrument(name = "db::EvmLockupHelperDatabase::blocks", skip(self))]
+    fn blocks(&self, symbol: &str) -> anyhow::Result<Vec<IndexedBlock>> {
+        evm_indexed_blocks::dsl::evm_indexed_blocks
+            .select(EvmIndexedBlock::as_select())
+            .filter(evm_indexed_blocks::dsl::symbol.eq(symbol))
+            .order(evm_indexed_blocks::dsl::height.desc())
+            .load(&mut self.pool.get()?)?
+            .into_iter()
+            .map(IndexedBlock::try_from)
+            .collect()
+    }
+
+    #[instrument(
+        name = "db::EvmLockupHelperDatabase::insert",
+        skip_all,
+        fields(symbol = %symbol, height = block.height, events = events.len())
+    )]
+    fn insert(
+        &self,
+        symbol: &str,
+        block: &IndexedBlock,
+        events: &[IndexedEvent],
+        prune_below: u64,
+    ) -> anyhow::Result<()> {
+        let mut lockups = Vec::new();
+        let mut claims = Vec::new();
+        let mut refunds = Vec::new();
+
+        for event in events {
+            let transaction_hash = event.transaction_hash.to_vec();
+            let log_index = i64::try_from(event.log_index)?;
+            let contract_address = event.contract_address.to_vec();
+            let block_height = i64::try_from(event.block_height)?;
+            let block_hash = event.block_hash.to_vec();
+
+            match event.event {
+                SwapEvent::Lockup(lockup) => lockups.push(EvmLockup {
+                    symbol: symbol.to_string(),
+                    transaction_hash,
+                    log_index,
+                    contract_address,
+                    block_height,
+                    block_hash,
+                    preimage_hash: lockup.preimage_hash.to_vec(),
+                    amount: lockup.amount.to_string(),
+                    token_address: lockup.token_address.map(|address| address.to_vec()),
+                    claim_address: lockup.claim_address.to_vec(),
+                    refund_address: lockup.refund_address.to_vec(),
+                    timelock: i64::try_from(u64::try_from(lockup.timelock)?)?,
+                }),
+                SwapEvent::Claim {
+                    preimage_hash,
+                    preimage,
+                } => claims.push(EvmClaim {
+                    symbol: symbol.to_string(),
+                    transaction_hash,
+                    log_index,
+                    contract_address,
+                    block_height,
+                    block_hash,
+                    preimage_hash: preimage_hash.to_vec(),
+                    preimage: preimage.to_vec(),
+                }),
+                SwapEvent::Refund { preimage_hash } => refunds.push(EvmRefund {
+                    symbol: symbol.to_string(),
+                    transaction_hash,
+                    log_index,
+                    contract_address,
+                    block_height,
+                    block_hash,
+                    preimage_hash: preimage_hash.to_vec(),
+                }),
+            }
+        }
+
+        let block = EvmIndexedBlock {
+            symbol: symbol.to_string(),
+            height: i64::try_from(block.height)?,
+            hash: block.hash.to_vec(),
+            parent_hash: block.parent_hash.to_vec(),
+        };
+        let prune_below = i64::try_from(prune_below)?;
+
+        self.pool
+            .get()?
+            .transaction::<_, diesel::result::Error, _>(|con| {
+                if !lockups.is_empty() {
+                    insert_into(evm_lockups::dsl::evm_lockups)
+                        .values(&lockups)
+                        .on_conflict_do_nothing()
+                        .execute(con)?;
+                }
+                if !claims.is_empty() {
+                    insert_into(evm_claims::dsl::evm_claims)
+                        .values(&claims)
+                        .on_conflict_do_nothing()
+                        .execute(con)?;
+                }
+                if !refunds.is_empty() {
+                    insert_into(evm_refunds::dsl::evm_refunds)
+                        .values(&refunds)
+                        .on_conflict_do_nothing()
+                        .execute(con)?;
+                }
+
+                insert_into(evm_indexed_blocks::dsl::evm_indexed_blocks)
+                    .values(&block)
+                    .on_conflict((
+                        evm_indexed_blocks::dsl::symbol,
+                        evm_indexed_blocks::dsl::height,
+                    ))
+                    .do_update()
+                    .set((
+                        evm_indexed_blocks::dsl::hash.eq(&block.hash),
+                        evm_indexed_blocks::dsl::parent_hash.eq(&block.parent_hash),
+                    ))
+                    .execute(con)?;
+
+                delete(evm_indexed_blocks::dsl::evm_indexed_blocks)
+                    .filter(evm_indexed_blocks::dsl::symbol.eq(symbol))
+                    .filter(evm_indexed_blocks::dsl::height.lt(prune_below))
+                    .execute(con)?;
+
+                Ok(())
+            })?;
+
+        Ok(())
+    }
+
+    #[instrument(name = "db::EvmLockupHelperDatabase::rollback", skip(self))]
+    fn rollback(&self, symbol: &str, height: u64) -> anyhow::Result<usize> {
+        let height = i64::try_from(height)?;
+
+        Ok(self
+            .pool
+            .get()?
+            .transaction::<_, diesel::result::Error, _>(|con| {
+                let lockups = delete(evm_lockups::dsl::evm_lockups)
+                    .filter(evm_lockups::dsl::symbol.eq(symbol))
+                    .filter(evm_lockups::dsl::block_height.ge(height))
+                    .execute(con)?;
+                let claims = delete(evm_claims::dsl::evm_claims)
+                    .filter(evm_claims::dsl::symbol.eq(symbol))
+                    .filter(evm_claims::dsl::block_height.ge(height))
+                    .execute(con)?;
+                let refunds = delete(evm_refunds::dsl::evm_refunds)
+                    .filter(evm_refunds::dsl::symbol.eq(symbol))
+                    .filter(evm_refunds::dsl::block_height.ge(height))
+                    .execute(con)?;
+                let blocks = delete(evm_indexed_blocks::dsl::evm_indexed_blocks)
+                    .filter(evm_indexed_blocks::dsl::symbol.eq(symbol))
+                    .filter(evm_indexed_blocks::dsl::height.ge(height))
+                    .execute(con)?;
+
+                Ok(lockups + claims + refunds + blocks)
+            })?)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::db::helpers::web_hook::test::get_pool;
+    use boltz_evm::indexer::Lockup;
+    use boltz_evm::{Address, U256};
+    use mockall::mock;
+    use rand::distributions::{Alphanumeric, DistString};
+
+    mock! {
+        pub EvmLockupHelper {}
+
+        impl Clone for EvmLockupHelper {
+            fn clone(&self) -> Self;
+        }
+
+        impl EvmLockupHelper for EvmLockupHelper {
+            fn get_indexed_height(&self, symbol: &str) -> QueryResponse<Option<u64>>;
+            fn get_active_lockups(&self, symbol: &str) -> QueryResponse<Vec<EvmLockup>>;
+        }
+    }
+
+    const CONTRACT: Address = Address::repeat_byte(0xee);
+
+    fn random_symbol() -> String {
+        Alphanumeric.sample_string(&mut rand::thread_rng(), 8)
+    }
+
+    fn block(height: u64) -> IndexedBlock {
+        IndexedBlock {
+            height,
+            hash: FixedBytes::repeat_byte(height as u8),
+            parent_hash: FixedBytes::repeat_byte(height as u8 - 1),
+        }
+    }
+
+    fn event(height: u64, event: SwapEvent) -> IndexedEvent {
+        event_at(height, 0, event)
+    }
+
+    fn event_at(height: u64, log_index: u64, event: SwapEvent) -> IndexedEvent {
+        IndexedEvent {
+            contract_address: CONTRACT,
+            block_height: height,
+            block_hash: block(height).hash,
+            transaction_hash: FixedBytes::from(rand::random::<[u8; 32]>()),
+            log_index,
+            event,
+        }
+    }
+
+    fn lockup(height: u64, preimage_hash: FixedBytes<32>) -> IndexedEvent {
+        lockup_at(height, 0, preimage_hash)
+    }
+
+    fn lockup_at(height: u64, log_index: u64, preimage_hash: FixedBytes<32>) -> IndexedEvent {
+        event_at(
+            height,
+            log_index,
+            SwapEvent::Lockup(Lockup {
+                preimage_hash,
+                amount: U256::from(21),
+                token_address: None,
+                claim_address: Address::repeat_byte(0x01),
+                refund_address: Address::repeat_byte(0x02),
+                timelock: U256::from(100),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_insert_and_tip() {
+        let helper = EvmLockupHelperDatabase::new(get_pool());
+        let symbol = random_symbol();
+
+        assert!(helper.tip(&symbol).unwrap().is_none());
+        assert!(helper.get_indexed_height(&symbol).unwrap().is_none());
+
+        helper.insert(&symbol, &block(1), &[], 0).unwrap();
+        helper.insert(&symbol, &block(2), &[], 0).unwrap();
+
+        assert_eq!(helper.tip(&symbol).unwrap(), Some(block(2)));
+        assert_eq!(helper.get_indexed_height(&symbol).unwrap(), Some(2));
+        assert_eq!(helper.blocks(&symbol).unwrap(), vec![block(2), block(1)]);
+    }
+
+    #[test]
+    fn test_insert_prunes_blocks() {
+        let helper = EvmLockupHelperDatabase::new(get_pool());
+        let symbol = random_symbol();
+
+        helper.insert(&symbol, &block(1), &[], 0).unwrap();
+        helper.insert(&symbol, &block(2), &[], 0).unwrap();
+        helper.insert(&symbol, &block(3), &[], 2).unwrap();
+
+        assert_eq!(helper.blocks(&symbol).unwrap(), vec![block(3), block(2)]);
+    }
+
+    #[test]
+    fn test_get_active_lockups() {
+        let helper = EvmLockupHelperDatabase::new(get_pool());
+        let symbol = random_symbol();
+
+        let claimed = FixedBytes::repeat_byte(0x11);
+        let refunded = FixedBytes::repeat_byte(0x22);
+        let active = FixedBytes::repeat_byte(0x33);
+
+        helper
+            .insert(
+                &symbol,
+                &block(1),
+                &[lockup(1, claimed), lockup(1, refunded), lockup(1, active)],
+                0,
+            )
+            .unwrap();
+        helper
+            .insert(
+                &symbol,
+                &block(2),
+                &[
+                    event(
+                        2,
+                        SwapEvent::Claim {
+                            preimage_hash: claimed,
+                            preimage: FixedBytes::repeat_byte(0x01),
+                        },
+                    ),
+                    event(
+                        2,
+                        SwapEvent::Refund {
+                            preimage_hash: refunded,
+                        },
+                    ),
+                ],
+                0,
+            )
+            .unwrap();
+
+        let lockups = helper.get_active_lockups(&symbol).unwrap();
+        assert_eq!(lockups.len(), 1);
+        assert_eq!(lockups[0].preimage_hash, active.to_vec());
+        assert_eq!(lockups[0].amount, "21");
+        assert_eq!(lockups[0].timelock, 100);
+        assert!(lockups[0].token_address.is_none());
+
+        // A new lockup with a reused preimage hash is active again
+        helper
+            .insert(&symbol, &block(3), &[lockup(3, claimed)], 0)
+            .unwrap();
+        assert_eq!(helper.get_active_lockups(&symbol).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_get_active_lockups_same_block() {
+        let helper = EvmLockupHelperDatabase::new(get_pool());
+        let symbol = random_symbol();
+
+        let preimage_hash = FixedBytes::repeat_byte(0x11);
+
+        // The refund is before the lockup in the block, so it cannot settle it
+        helper
+            .insert(
+                &symbol,
+                &block(1),
+                &[
+                    event_at(1, 0, SwapEvent::Refund { preimage_hash }),
+                    lockup_at(1, 1, preimage_hash),
+                ],
+                0,
+            )
+            .unwrap();
+        assert_eq!(helper.get_active_lockups(&symbol).unwrap().len(), 1);
+
+        helper
+            .insert(
+                &symbol,
+                &block(2),
+                &[
+                    lockup_at(2, 3, FixedBytes::repeat_byte(0x22)),
+                    event_at(2, 4, SwapEvent::Refund { preimage_hash }),
+                ],
+                0,
+            )
+            .unwrap();
+
+        let lockups = helper.get_active_lockups(&symbol).unwrap();
+        assert_eq!(lockups.len(), 1);
+        assert_eq!(lockups[0].preimage_hash, vec![0x22; 32]);
+    }
+
+    #[test]
+    fn test_get_active_lockups_reused_preimage_hash() {
+        let helper = EvmLockupHelperDatabase::new(get_pool());
+        let symbol = random_symbol();
+
+        let preimage_hash = FixedBytes::repeat_byte(0x11);
+        let claim = |height| {
+            event(
+                height,
+                SwapEvent::Claim {
+                    preimage_hash,
+                    preimage: FixedBytes::repeat_byte(0x01),
+                },
+            )
+        };
+
+        helper
+            .insert(&symbol, &block(1), &[lockup(1, preimage_hash)], 0)
+            .unwrap();
+        helper
+            .insert(&symbol, &block(2), &[lockup(2, preimage_hash)], 0)
+            .unwrap();
+        helper.insert(&symbol, &block(3), &[claim(3)], 0).unwrap();
+
+        // A claim settles only one of the lockups
+        let lockups = helper.get_active_lockups(&symbol).unwrap();
+        assert_eq!(lockups.len(), 1);
+        assert_eq!(lockups[0].block_height, 2);
+
+        helper.insert(&symbol, &block(4), &[claim(4)], 0).unwrap();
+        assert!(helper.get_active_lockups(&symbol).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rollback() {
+        let helper = EvmLockupHelperDatabase::new(get_pool());
+        let symbol = random_symbol();
+
+        let preimage_hash = FixedBytes::repeat_byte(0x11);
+
+        helper
+            .insert(&symbol, &block(1), &[lockup(1, preimage_hash)], 0)
+            .unwrap();
+        helper
+            .insert(
+                &symbol,
+                &block(2),
+                &[event(2, SwapEvent::Refund { preimage_hash })],
+                0,
+            )
+            .unwrap();
+        assert!(helper.get_active_lockups(&symbol).unwrap().is_empty());
+
+        // Removes the refund and block 2
+        assert_eq!(helper.rollback(&symbol, 2).unwrap(), 2);
+
+        assert_eq!(helper.tip(&symbol).unwrap(), Some(block(1)));
+        assert_eq!(helper.get_active_lockups(&symbol).unwrap().len(), 1);
+    }
+}
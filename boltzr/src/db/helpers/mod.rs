@@ -4,6 +4,8 @@ use diesel::sql_types::{Bool, Nullable};
 
 pub mod chain_swap;
 pub mod chain_tip;
+pub mod claim_transaction;
+pub mod evm_lockup;
 pub mod keys;
 pub mod offer;
 pub mod preimage_hash_triggers;
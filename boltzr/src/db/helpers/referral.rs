@@ -1,14 +1,15 @@
 use crate::db::Pool;
 use crate::db::helpers::{BoxedCondition, QueryResponse};
-use crate::db::models::Referral;
+use crate::db::models::{Referral, ReferralCredentials};
 use crate::db::schema::referrals;
-use diesel::{QueryDsl, RunQueryDsl, SelectableHelper};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper};
 use tracing::instrument;
 
 pub type ReferralCondition = BoxedCondition<referrals::table>;
 
 pub trait ReferralHelper {
     fn get_all(&self, condition: ReferralCondition) -> QueryResponse<Vec<Referral>>;
+    fn get_credentials(&self, api_key: &str) -> QueryResponse<Option<ReferralCredentials>>;
 }
 
 #[derive(Clone, Debug)]
@@ -30,4 +31,32 @@ impl ReferralHelper for ReferralHelperDatabase {
             .filter(condition)
             .load(&mut self.pool.get()?)?)
     }
+
+    #[instrument(name = "db::ReferralHelperDatabase::get_credentials", skip_all)]
+    fn get_credentials(&self, api_key: &str) -> QueryResponse<Option<ReferralCredentials>> {
+        Ok(referrals::dsl::referrals
+            .select(ReferralCredentials::as_select())
+            .filter(referrals::dsl::apiKey.eq(api_key))
+            .first(&mut self.pool.get()?)
+            .optional()?)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use mockall::mock;
+
+    mock! {
+        pub ReferralHelper {}
+
+        impl Clone for ReferralHelper {
+            fn clone(&self) -> Self;
+        }
+
+        impl ReferralHelper for ReferralHelper {
+            fn get_all(&self, condition: ReferralCondition) -> QueryResponse<Vec<Referral>>;
+            fn get_credentials(&self, api_key: &str) -> QueryResponse<Option<ReferralCredentials>>;
+        }
+    }
 }
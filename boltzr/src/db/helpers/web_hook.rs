@@ -1,8 +1,8 @@
 use crate::db::Pool;
 use crate::db::helpers::QueryResponse;
-use crate::db::models::{WebHook, WebHookState};
-use crate::db::schema::{chainSwaps, reverseSwaps, swaps, web_hooks};
-use crate::webhook::caller::HookState;
+use crate::db::models::{NewWebHookDeadLetter, WebHook, WebHookDeadLetter, WebHookState};
+use crate::db::schema::{chainSwaps, reverseSwaps, swaps, web_hook_dead_letters, web_hooks};
+use crate::webhook::caller::{CallAttempt, HookState};
 use crate::webhook::{SwapUpdateCallData, WebHookCallData};
 use diesel::prelude::*;
 use diesel::{delete, insert_into, update};
@@ -15,6 +15,23 @@ pub trait WebHookHelper {
     fn get_by_id(&self, id: &str) -> QueryResponse<Option<WebHook>>;
     fn get_by_state(&self, state: WebHookState) -> QueryResponse<Vec<WebHook>>;
     fn get_swap_status(&self, id: &str) -> QueryResponse<Option<String>>;
+
+    fn insert_dead_letter(&self, dead_letter: &NewWebHookDeadLetter) -> QueryResponse<usize>;
+    fn get_dead_letters(&self, hook_id: Option<String>) -> QueryResponse<Vec<WebHookDeadLetter>>;
+    fn get_dead_letter(&self, id: i64) -> QueryResponse<Option<WebHookDeadLetter>>;
+    fn set_dead_letter_attempts(
+        &self,
+        id: i64,
+        last_status: Option<i32>,
+        last_error: Option<String>,
+        attempts: serde_json::Value,
+    ) -> QueryResponse<usize>;
+    fn delete_dead_letter(&self, id: i64) -> QueryResponse<usize>;
+    fn delete_dead_letters(
+        &self,
+        hook_id: Option<String>,
+        older_than: Option<chrono::NaiveDateTime>,
+    ) -> QueryResponse<usize>;
 }
 
 #[derive(Clone, Debug)]
@@ -119,6 +136,88 @@ impl WebHookHelper for WebHookHelperDatabase {
             .find(|elem| !elem.is_empty())
             .map(|res| res[0].clone()))
     }
+
+    #[instrument(skip_all, fields(swap_id = dead_letter.hook_id))]
+    fn insert_dead_letter(&self, dead_letter: &NewWebHookDeadLetter) -> QueryResponse<usize> {
+        trace!("Inserting WebHook dead letter: {:#?}", dead_letter);
+        Ok(
+            insert_into(web_hook_dead_letters::dsl::web_hook_dead_letters)
+                .values(dead_letter)
+                .execute(&mut self.pool.get()?)?,
+        )
+    }
+
+    #[instrument(skip_all)]
+    fn get_dead_letters(&self, hook_id: Option<String>) -> QueryResponse<Vec<WebHookDeadLetter>> {
+        trace!("Fetching WebHook dead letters");
+        let mut query = web_hook_dead_letters::dsl::web_hook_dead_letters
+            .select(WebHookDeadLetter::as_select())
+            .order(web_hook_dead_letters::dsl::id.asc())
+            .into_boxed();
+
+        if let Some(hook_id) = hook_id {
+            query = query.filter(web_hook_dead_letters::dsl::hook_id.eq(hook_id));
+        }
+
+        Ok(query.load(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(skip_all, fields(id = id))]
+    fn get_dead_letter(&self, id: i64) -> QueryResponse<Option<WebHookDeadLetter>> {
+        trace!("Fetching WebHook dead letter: {}", id);
+        Ok(web_hook_dead_letters::dsl::web_hook_dead_letters
+            .select(WebHookDeadLetter::as_select())
+            .filter(web_hook_dead_letters::dsl::id.eq(id))
+            .first(&mut self.pool.get()?)
+            .optional()?)
+    }
+
+    #[instrument(skip_all, fields(id = id))]
+    fn set_dead_letter_attempts(
+        &self,
+        id: i64,
+        last_status: Option<i32>,
+        last_error: Option<String>,
+        attempts: serde_json::Value,
+    ) -> QueryResponse<usize> {
+        trace!("Updating attempts of WebHook dead letter: {}", id);
+        Ok(update(web_hook_dead_letters::dsl::web_hook_dead_letters)
+            .filter(web_hook_dead_letters::dsl::id.eq(id))
+            .set((
+                web_hook_dead_letters::dsl::last_status.eq(last_status),
+                web_hook_dead_letters::dsl::last_error.eq(last_error),
+                web_hook_dead_letters::dsl::attempts.eq(attempts),
+            ))
+            .execute(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(skip_all, fields(id = id))]
+    fn delete_dead_letter(&self, id: i64) -> QueryResponse<usize> {
+        trace!("Deleting WebHook dead letter: {}", id);
+        Ok(delete(web_hook_dead_letters::dsl::web_hook_dead_letters)
+            .filter(web_hook_dead_letters::dsl::id.eq(id))
+            .execute(&mut self.pool.get()?)?)
+    }
+
+    #[instrument(skip_all)]
+    fn delete_dead_letters(
+        &self,
+        hook_id: Option<String>,
+        older_than: Option<chrono::NaiveDateTime>,
+    ) -> QueryResponse<usize> {
+        trace!("Purging WebHook dead letters");
+        let mut query = delete(web_hook_dead_letters::dsl::web_hook_dead_letters).into_boxed();
+
+        if let Some(hook_id) = hook_id {
+            query = query.filter(web_hook_dead_letters::dsl::hook_id.eq(hook_id));
+        }
+
+        if let Some(older_than) = older_than {
+            query = query.filter(web_hook_dead_letters::dsl::created_at.lt(older_than));
+        }
+
+        Ok(query.execute(&mut self.pool.get()?)?)
+    }
 }
 
 impl HookState<WebHook> for WebHookHelperDatabase {
@@ -151,6 +250,24 @@ impl HookState<WebHook> for WebHookHelperDatabase {
         WebHookHelper::set_state(self, &hook.id, state)?;
         Ok(())
     }
+
+    fn add_dead_letter(
+        &self,
+        hook: &WebHook,
+        params: &WebHookCallData,
+        attempts: &[CallAttempt],
+    ) -> anyhow::Result<()> {
+        let last = attempts.last();
+        self.insert_dead_letter(&NewWebHookDeadLetter {
+            hook_id: hook.id.clone(),
+            url: hook.url.clone(),
+            payload: serde_json::to_value(params)?,
+            last_status: last.and_then(|attempt| attempt.status.map(i32::from)),
+            last_error: last.map(|attempt| attempt.error.clone()),
+            attempts: serde_json::to_value(attempts)?,
+        })?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -219,6 +336,7 @@ pub mod test {
             url: "https://some.thing".to_string(),
             hash_swap_id: true,
             status: None,
+            secret: None,
         };
         assert_eq!(helper.insert_web_hook(&hook).unwrap(), 1);
         assert_eq!(helper.get_by_id(&hook.id).unwrap().unwrap(), hook);
@@ -234,6 +352,7 @@ pub mod test {
             url: "https://some.thing".to_string(),
             hash_swap_id: true,
             status: None,
+            secret: None,
         };
         assert_eq!(helper.insert_web_hook(&hook).unwrap(), 1);
         assert!(helper.insert_web_hook(&hook).err().is_some());
@@ -249,6 +368,7 @@ pub mod test {
             url: "https://some.thing".to_string(),
             hash_swap_id: true,
             status: None,
+            secret: None,
         };
         assert_eq!(helper.insert_web_hook(&hook).unwrap(), 1);
 
@@ -277,6 +397,7 @@ pub mod test {
             url: "https://some.thing".to_string(),
             hash_swap_id: true,
             status: None,
+            secret: None,
         };
         assert_eq!(helper.insert_web_hook(&hook).unwrap(), 1);
         assert_eq!(
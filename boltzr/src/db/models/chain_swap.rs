@@ -126,6 +126,10 @@ impl SomeSwap for ChainSwapInfo {
         self.swap.id.clone()
     }
 
+    fn pair(&self) -> String {
+        self.swap.pair.clone()
+    }
+
     fn status(&self) -> SwapUpdate {
         SwapUpdate::parse(self.swap.status.as_str())
     }
@@ -150,6 +154,10 @@ impl SomeSwap for ChainSwapInfo {
         Ok(self.receiving().symbol.clone())
     }
 
+    fn claim_timeout_block_height(&self) -> Result<u64> {
+        Ok(self.receiving().timeoutBlockHeight as u64)
+    }
+
     async fn claim_details(
         &self,
         wallet: &Arc<dyn Wallet + Send + Sync>,
@@ -169,6 +177,10 @@ impl SomeSwap for ChainSwapInfo {
         Ok(self.sending().symbol.clone())
     }
 
+    fn refund_timeout_block_height(&self) -> Result<u64> {
+        Ok(self.sending().timeoutBlockHeight as u64)
+    }
+
     async fn refund_details(
         &self,
         wallet: &Arc<dyn Wallet + Send + Sync>,
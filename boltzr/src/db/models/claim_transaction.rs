@@ -0,0 +1,19 @@
+use diesel::{Insertable, Queryable, Selectable};
+
+#[derive(Queryable, Selectable, Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::pending_claims)]
+pub struct PendingClaim {
+    pub swap_id: String,
+    pub symbol: String,
+    pub transaction_id: String,
+    pub status: String,
+}
+
+#[derive(Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::claim_replacements)]
+pub struct NewClaimReplacement {
+    pub swap_id: String,
+    pub replaced_transaction_id: String,
+    pub transaction_id: String,
+    pub fee_rate: f64,
+}
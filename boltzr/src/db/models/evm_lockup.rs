@@ -0,0 +1,53 @@
+use diesel::{Insertable, Queryable, Selectable};
+
+#[derive(Queryable, Selectable, Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::evm_indexed_blocks)]
+pub struct EvmIndexedBlock {
+    pub symbol: String,
+    pub height: i64,
+    pub hash: Vec<u8>,
+    pub parent_hash: Vec<u8>,
+}
+
+#[derive(Queryable, Selectable, Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::evm_lockups)]
+pub struct EvmLockup {
+    pub symbol: String,
+    pub transaction_hash: Vec<u8>,
+    pub log_index: i64,
+    pub contract_address: Vec<u8>,
+    pub block_height: i64,
+    pub block_hash: Vec<u8>,
+    pub preimage_hash: Vec<u8>,
+    /// Decimal string, because the amounts are 256-bit integers
+    pub amount: String,
+    pub token_address: Option<Vec<u8>>,
+    pub claim_address: Vec<u8>,
+    pub refund_address: Vec<u8>,
+    pub timelock: i64,
+}
+
+#[derive(Queryable, Selectable, Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::evm_claims)]
+pub struct EvmClaim {
+    pub symbol: String,
+    pub transaction_hash: Vec<u8>,
+    pub log_index: i64,
+    pub contract_address: Vec<u8>,
+    pub block_height: i64,
+    pub block_hash: Vec<u8>,
+    pub preimage_hash: Vec<u8>,
+    pub preimage: Vec<u8>,
+}
+
+#[derive(Queryable, Selectable, Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::evm_refunds)]
+pub struct EvmRefund {
+    pub symbol: String,
+    pub transaction_hash: Vec<u8>,
+    pub log_index: i64,
+    pub contract_address: Vec<u8>,
+    pub block_height: i64,
+    pub block_hash: Vec<u8>,
+    pub preimage_hash: Vec<u8>,
+}
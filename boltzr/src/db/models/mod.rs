@@ -13,6 +13,8 @@ use strum_macros::{Display, EnumString};
 
 mod chain_swap;
 mod chain_tip;
+mod claim_transaction;
+mod evm_lockup;
 mod keys;
 mod offer;
 mod referral;
@@ -24,6 +26,8 @@ mod web_hook;
 
 pub use chain_swap::*;
 pub use chain_tip::*;
+pub use claim_transaction::*;
+pub use evm_lockup::*;
 pub use keys::*;
 pub use offer::*;
 pub use referral::*;
@@ -149,11 +153,13 @@ pub trait SomeSwap {
     fn kind(&self) -> SwapType;
 
     fn id(&self) -> String;
+    fn pair(&self) -> String;
     fn status(&self) -> SwapUpdate;
 
     fn sending_outpoint(&self) -> Result<Option<Outpoint>>;
 
     fn claim_symbol(&self) -> Result<String>;
+    fn claim_timeout_block_height(&self) -> Result<u64>;
     async fn claim_details(
         &self,
         wallet: &Arc<dyn Wallet + Send + Sync>,
@@ -161,6 +167,7 @@ pub trait SomeSwap {
     ) -> Result<InputDetail>;
 
     fn refund_symbol(&self) -> Result<String>;
+    fn refund_timeout_block_height(&self) -> Result<u64>;
     async fn refund_details(
         &self,
         wallet: &Arc<dyn Wallet + Send + Sync>,
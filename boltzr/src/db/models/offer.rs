@@ -11,6 +11,8 @@ pub struct Offer {
     pub signer: Vec<u8>,
     pub offer: String,
     pub url: Option<String>,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
 }
 
 #[cfg(test)]
@@ -23,6 +25,7 @@ mod tests {
             signer: vec![0x01, 0x02, 0x0A, 0xFF],
             offer: "dummy offer".to_string(),
             url: Some("http://example.com".to_string()),
+            secret: None,
         };
         let json = serde_json::to_string(&offer).expect("serialization should work");
         assert!(json.contains("\"signer\":\"01020aff\""));
@@ -45,6 +45,16 @@ pub struct Referral {
     pub config: Option<serde_json::Value>,
 }
 
+/// API credentials with which a referral authenticates its requests
+#[derive(Queryable, Selectable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::referrals)]
+#[allow(non_snake_case)]
+pub struct ReferralCredentials {
+    pub id: String,
+    pub apiKey: String,
+    pub apiSecret: String,
+}
+
 impl Referral {
     pub fn custom_expiration_secs(
         &self,
@@ -61,6 +61,10 @@ impl SomeSwap for ReverseSwap {
         self.id.clone()
     }
 
+    fn pair(&self) -> String {
+        self.pair.clone()
+    }
+
     fn status(&self) -> SwapUpdate {
         SwapUpdate::parse(self.status.as_str())
     }
@@ -85,6 +89,10 @@ impl SomeSwap for ReverseSwap {
         self.lightning_symbol()
     }
 
+    fn claim_timeout_block_height(&self) -> Result<u64> {
+        Err(anyhow::anyhow!("reverse swaps cannot be claimed onchain"))
+    }
+
     async fn claim_details(
         &self,
         _: &Arc<dyn Wallet + Send + Sync>,
@@ -97,6 +105,10 @@ impl SomeSwap for ReverseSwap {
         self.chain_symbol()
     }
 
+    fn refund_timeout_block_height(&self) -> Result<u64> {
+        Ok(self.timeoutBlockHeight as u64)
+    }
+
     async fn refund_details(
         &self,
         wallet: &Arc<dyn Wallet + Send + Sync>,
@@ -48,6 +48,10 @@ impl SomeSwap for Swap {
         self.id.clone()
     }
 
+    fn pair(&self) -> String {
+        self.pair.clone()
+    }
+
     fn status(&self) -> SwapUpdate {
         SwapUpdate::parse(self.status.as_str())
     }
@@ -60,6 +64,10 @@ impl SomeSwap for Swap {
         self.chain_symbol()
     }
 
+    fn claim_timeout_block_height(&self) -> Result<u64> {
+        Ok(self.timeoutBlockHeight as u64)
+    }
+
     async fn claim_details(
         &self,
         wallet: &Arc<dyn Wallet + Send + Sync>,
@@ -185,6 +193,12 @@ impl SomeSwap for Swap {
         self.lightning_symbol()
     }
 
+    fn refund_timeout_block_height(&self) -> Result<u64> {
+        Err(anyhow::anyhow!(
+            "submarine swaps cannot be refunded onchain"
+        ))
+    }
+
     async fn refund_details(
         &self,
         _: &Arc<dyn Wallet + Send + Sync>,
@@ -31,6 +31,32 @@ pub struct WebHook {
     pub url: String,
     pub hash_swap_id: bool,
     pub status: Option<Vec<String>>,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+}
+
+#[derive(Queryable, Selectable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::web_hook_dead_letters)]
+pub struct WebHookDeadLetter {
+    pub id: i64,
+    pub hook_id: String,
+    pub url: String,
+    pub payload: serde_json::Value,
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub attempts: serde_json::Value,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, PartialEq, Clone, Debug)]
+#[diesel(table_name = crate::db::schema::web_hook_dead_letters)]
+pub struct NewWebHookDeadLetter {
+    pub hook_id: String,
+    pub url: String,
+    pub payload: serde_json::Value,
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub attempts: serde_json::Value,
 }
 
 impl Hook for WebHook {
@@ -43,6 +69,10 @@ impl Hook for WebHook {
     fn url(&self) -> String {
         self.url.clone()
     }
+
+    fn secret(&self) -> Option<String> {
+        self.secret.clone()
+    }
 }
 
 #[cfg(test)]
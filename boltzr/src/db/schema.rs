@@ -7,6 +7,20 @@ diesel::table! {
         url -> Text,
         hash_swap_id -> Bool,
         status -> Nullable<Array<Text>>,
+        secret -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    web_hook_dead_letters (id) {
+        id -> BigInt,
+        hook_id -> Text,
+        url -> Text,
+        payload -> Jsonb,
+        last_status -> Nullable<Integer>,
+        last_error -> Nullable<Text>,
+        attempts -> Jsonb,
+        created_at -> Timestamptz,
     }
 }
 
@@ -15,12 +29,16 @@ diesel::table! {
         signer -> Binary,
         offer -> Text,
         url -> Nullable<Text>,
+        secret -> Nullable<Text>,
     }
 }
 
 diesel::table! {
+    #[allow(non_snake_case)]
     referrals (id) {
         id -> Text,
+        apiKey -> Text,
+        apiSecret -> Text,
         config -> Nullable<Json>,
     }
 }
@@ -64,6 +82,7 @@ diesel::table! {
     reverseSwaps (id) {
         id -> Text,
         version -> Integer,
+        referral -> Nullable<Text>,
         pair -> Text,
         orderSide -> Integer,
         status -> Text,
@@ -101,6 +120,7 @@ diesel::table! {
     #[allow(non_snake_case)]
     chainSwaps (id) {
         id -> Text,
+        referral -> Nullable<Text>,
         pair -> Text,
         orderSide -> Integer,
         status -> Text,
@@ -147,6 +167,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    pending_claims (swap_id) {
+        swap_id -> Text,
+        symbol -> Text,
+        transaction_id -> Text,
+        status -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    claim_replacements (id) {
+        id -> BigInt,
+        swap_id -> Text,
+        replaced_transaction_id -> Text,
+        transaction_id -> Text,
+        fee_rate -> Double,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     #[allow(non_snake_case)]
     script_pubkeys(symbol, script_pubkey) {
@@ -164,5 +205,58 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    evm_indexed_blocks (symbol, height) {
+        symbol -> Text,
+        height -> BigInt,
+        hash -> Binary,
+        parent_hash -> Binary,
+    }
+}
+
+diesel::table! {
+    evm_lockups (symbol, transaction_hash, log_index) {
+        symbol -> Text,
+        transaction_hash -> Binary,
+        log_index -> BigInt,
+        contract_address -> Binary,
+        block_height -> BigInt,
+        block_hash -> Binary,
+        preimage_hash -> Binary,
+        amount -> Text,
+        token_address -> Nullable<Binary>,
+        claim_address -> Binary,
+        refund_address -> Binary,
+        timelock -> BigInt,
+    }
+}
+
+diesel::table! {
+    evm_claims (symbol, transaction_hash, log_index) {
+        symbol -> Text,
+        transaction_hash -> Binary,
+        log_index -> BigInt,
+        contract_address -> Binary,
+        block_height -> BigInt,
+        block_hash -> Binary,
+        preimage_hash -> Binary,
+        preimage -> Binary,
+    }
+}
+
+diesel::table! {
+    evm_refunds (symbol, transaction_hash, log_index) {
+        symbol -> Text,
+        transaction_hash -> Binary,
+        log_index -> BigInt,
+        contract_address -> Binary,
+        block_height -> BigInt,
+        block_hash -> Binary,
+        preimage_hash -> Binary,
+    }
+}
+
+allow_tables_to_appear_in_same_query!(evm_lockups, evm_claims, evm_refunds);
+
 joinable!(chainSwapData -> chainSwaps (swapId));
 allow_tables_to_appear_in_same_query!(chainSwaps, chainSwapData);
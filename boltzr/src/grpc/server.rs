@@ -190,7 +190,7 @@ mod server_test {
     use crate::currencies::{Currencies, Currency};
     use crate::db::helpers::QueryResponse;
     use crate::db::helpers::web_hook::WebHookHelper;
-    use crate::db::models::{WebHook, WebHookState};
+    use crate::db::models::{NewWebHookDeadLetter, WebHook, WebHookDeadLetter, WebHookState};
     use crate::grpc::server::{Config, Server};
     use crate::grpc::service::boltzr::GetInfoRequest;
     use crate::grpc::service::boltzr::boltz_r_client::BoltzRClient;
@@ -225,6 +225,23 @@ mod server_test {
             fn get_by_id(&self, id: &str) -> QueryResponse<Option<WebHook>>;
             fn get_by_state(&self, state: WebHookState) -> QueryResponse<Vec<WebHook>>;
             fn get_swap_status(&self, id: &str) -> QueryResponse<Option<String>>;
+
+            fn insert_dead_letter(&self, dead_letter: &NewWebHookDeadLetter) -> QueryResponse<usize>;
+            fn get_dead_letters(&self, hook_id: Option<String>) -> QueryResponse<Vec<WebHookDeadLetter>>;
+            fn get_dead_letter(&self, id: i64) -> QueryResponse<Option<WebHookDeadLetter>>;
+            fn set_dead_letter_attempts(
+                &self,
+                id: i64,
+                last_status: Option<i32>,
+                last_error: Option<String>,
+                attempts: serde_json::Value,
+            ) -> QueryResponse<usize>;
+            fn delete_dead_letter(&self, id: i64) -> QueryResponse<usize>;
+            fn delete_dead_letters(
+                &self,
+                hook_id: Option<String>,
+                older_than: Option<chrono::NaiveDateTime>,
+            ) -> QueryResponse<usize>;
         }
     }
 
@@ -265,6 +282,12 @@ mod server_test {
             ) -> anyhow::Result<(u64, u64)>;
             fn get_asset_rescue(&self) -> Arc<AssetRescue>;
             async fn claim_batch(&self, swap_ids: Vec<String>) -> anyhow::Result<(boltz_core::wrapper::Transaction, u64)>;
+            fn claim_batch_broadcasted(
+                &self,
+                symbol: &str,
+                swap_ids: &[String],
+                transaction_id: &str,
+            ) -> anyhow::Result<()>;
             fn listen_to_updates(&self) -> tokio::sync::broadcast::Receiver<SwapStatus>;
             async fn rescan_chains(
                 &self,
@@ -1,30 +1,37 @@
 use crate::api::ws::types::SwapStatus;
 use crate::db::helpers::web_hook::WebHookHelper;
-use crate::db::models::{WebHook, WebHookState};
+use crate::db::models::{EvmLockup, WebHook, WebHookDeadLetter, WebHookState};
 use crate::grpc::service::boltzr::boltz_r_server::BoltzR;
 use crate::grpc::service::boltzr::sign_evm_refund_request::Contract;
 use crate::grpc::service::boltzr::swap_update::{FailureDetails, TransactionInfo};
 use crate::grpc::service::boltzr::{
     Block, BlockAddedRequest, Bolt11Invoice, Bolt12Invoice, Bolt12Offer, CheckTransactionRequest,
-    CheckTransactionResponse, ClaimBatchRequest, ClaimBatchResponse, CreateWebHookRequest,
-    CreateWebHookResponse, DecodeInvoiceOrOfferRequest, DecodeInvoiceOrOfferResponse,
-    DeleteWebHookRequest, DeleteWebHookResponse, EstimateFeeRequest, EstimateFeeResponse, Feature,
-    GetInfoRequest, GetInfoResponse, GetMessagesRequest, GetMessagesResponse, IsMarkedRequest,
-    IsMarkedResponse, LogLevel, RelevantTransaction, RelevantTransactionRequest,
-    RescanChainsRequest, RescanChainsResponse, SendMessageRequest, SendMessageResponse,
-    SendSwapUpdateRequest, SendSwapUpdateResponse, SendWebHookRequest, SendWebHookResponse,
-    SetLogLevelRequest, SetLogLevelResponse, SignEvmRefundRequest, SignEvmRefundResponse,
-    StartWebHookRetriesRequest, StartWebHookRetriesResponse, SwapUpdate, SwapUpdateRequest,
-    SwapUpdateResponse, TransactionStatus, bolt11_invoice, bolt12_invoice,
-    decode_invoice_or_offer_response,
+    CheckTransactionResponse, ClaimBatchBroadcastedRequest, ClaimBatchBroadcastedResponse,
+    ClaimBatchRequest, ClaimBatchResponse, CreateWebHookRequest, CreateWebHookResponse,
+    DecodeInvoiceOrOfferRequest, DecodeInvoiceOrOfferResponse, DeleteWebHookRequest,
+    DeleteWebHookResponse, EstimateFeeRequest, EstimateFeeResponse, EvmLockupState, Feature,
+    GetEvmLockupsRequest, GetEvmLockupsResponse, GetInfoRequest, GetInfoResponse,
+    GetMessagesRequest, GetMessagesResponse, GetWebHookDeadLetterRequest,
+    GetWebHookDeadLetterResponse, IsMarkedRequest, IsMarkedResponse, ListWebHookDeadLettersRequest,
+    ListWebHookDeadLettersResponse, LogLevel, PurgeWebHookDeadLettersRequest,
+    PurgeWebHookDeadLettersResponse, RelevantTransaction, RelevantTransactionRequest,
+    ReplayWebHookDeadLetterRequest, ReplayWebHookDeadLetterResponse, RescanChainsRequest,
+    RescanChainsResponse, SendEvmTransactionRequest, SendEvmTransactionResponse,
+    SendMessageRequest, SendMessageResponse, SendSwapUpdateRequest, SendSwapUpdateResponse,
+    SendWebHookRequest, SendWebHookResponse, SetLogLevelRequest, SetLogLevelResponse,
+    SignEvmRefundRequest, SignEvmRefundResponse, StartWebHookRetriesRequest,
+    StartWebHookRetriesResponse, SwapUpdate, SwapUpdateRequest, SwapUpdateResponse,
+    TransactionStatus, bolt11_invoice, bolt12_invoice, decode_invoice_or_offer_response,
 };
 use crate::grpc::status_fetcher::StatusFetcher;
 use crate::lightning::invoice::Invoice;
 use crate::notifications::NotificationClient;
-use crate::service::Service;
+use crate::service::{LockupFilter, LockupState, Service};
 use crate::swap::TxStatus;
 use crate::swap::manager::{RescanChainOptions, SwapManager};
 use crate::tracing_setup::ReloadHandler;
+use crate::webhook::WebHookCallData;
+use crate::webhook::caller::CallAttempt;
 use crate::webhook::status_caller::StatusCaller;
 use boltz_evm::{Address, FixedBytes, RefundSigner};
 use futures::StreamExt;
@@ -89,6 +96,17 @@ impl<M, T> BoltzService<M, T> {
             web_hook_retry_handle: Arc::new(Default::default()),
         }
     }
+
+    fn get_dead_letter(&self, id: u64) -> Result<WebHookDeadLetter, Status> {
+        match self.web_hook_helper.get_dead_letter(id as i64) {
+            Ok(Some(dead_letter)) => Ok(dead_letter),
+            Ok(None) => Err(Status::new(
+                Code::NotFound,
+                format!("could not find dead letter {id}"),
+            )),
+            Err(err) => Err(Status::new(Code::Internal, err.to_string())),
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -209,6 +227,7 @@ where
 
         self.status_fetcher.set_sender(tx.clone()).await;
         let swap_status_update_tx = self.swap_status_update_tx.clone();
+        let status_replay = self.service.status_replay.clone();
 
         tokio::spawn(async move {
             while let Some(res) = in_stream.next().await {
@@ -225,9 +244,22 @@ where
                             None => None,
                         };
 
-                        if let Err(err) = swap_status_update_tx
-                            .send((id, res.status.iter().map(|entry| entry.into()).collect()))
-                        {
+                        let mut updates: Vec<SwapStatus> =
+                            res.status.iter().map(|entry| entry.into()).collect();
+
+                        // Responses to a single connection are snapshots and not part of the replay
+                        if id.is_none() {
+                            match status_replay.record(updates.clone()).await {
+                                Ok(recorded) => updates = recorded,
+                                Err(err) => {
+                                    error!(
+                                        "Could not record swap status updates for replay: {err}"
+                                    );
+                                }
+                            }
+                        }
+
+                        if let Err(err) = swap_status_update_tx.send((id, updates)) {
                             error!("Could not propagate swap status update: {}", err);
                             break;
                         }
@@ -325,6 +357,17 @@ where
             return Err(Status::new(Code::InvalidArgument, err.to_string()));
         }
 
+        if params
+            .secret
+            .as_ref()
+            .is_some_and(|secret| secret.is_empty())
+        {
+            return Err(Status::new(
+                Code::InvalidArgument,
+                "WebHook secret cannot be empty",
+            ));
+        }
+
         match self.web_hook_helper.insert_web_hook(&WebHook {
             id: params.id,
             state: WebHookState::None.into(),
@@ -335,6 +378,7 @@ where
             } else {
                 None
             },
+            secret: params.secret,
         }) {
             Ok(_) => Ok(Response::new(CreateWebHookResponse {})),
             Err(err) => Err(Status::new(Code::InvalidArgument, err.to_string())),
@@ -382,12 +426,142 @@ where
             .await
         {
             Ok(res) => Ok(Response::new(SendWebHookResponse {
-                ok: res != crate::webhook::caller::CallResult::Failed,
+                ok: !matches!(
+                    res,
+                    crate::webhook::caller::CallResult::Failed
+                        | crate::webhook::caller::CallResult::Paused
+                ),
             })),
             Err(err) => Err(Status::new(Code::Internal, err.to_string())),
         }
     }
 
+    #[instrument(name = "grpc::list_web_hook_dead_letters", skip_all)]
+    async fn list_web_hook_dead_letters(
+        &self,
+        request: Request<ListWebHookDeadLettersRequest>,
+    ) -> Result<Response<ListWebHookDeadLettersResponse>, Status> {
+        let params = request.into_inner();
+
+        let dead_letters = self
+            .web_hook_helper
+            .get_dead_letters(params.hook_id)
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+
+        Ok(Response::new(ListWebHookDeadLettersResponse {
+            dead_letters: dead_letters
+                .into_iter()
+                .map(|dead_letter| dead_letter.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err: anyhow::Error| Status::new(Code::Internal, err.to_string()))?,
+        }))
+    }
+
+    #[instrument(name = "grpc::get_web_hook_dead_letter", skip_all)]
+    async fn get_web_hook_dead_letter(
+        &self,
+        request: Request<GetWebHookDeadLetterRequest>,
+    ) -> Result<Response<GetWebHookDeadLetterResponse>, Status> {
+        let dead_letter = self.get_dead_letter(request.into_inner().id)?;
+
+        Ok(Response::new(GetWebHookDeadLetterResponse {
+            dead_letter: Some(
+                dead_letter
+                    .try_into()
+                    .map_err(|err: anyhow::Error| Status::new(Code::Internal, err.to_string()))?,
+            ),
+        }))
+    }
+
+    #[instrument(name = "grpc::replay_web_hook_dead_letter", skip_all)]
+    async fn replay_web_hook_dead_letter(
+        &self,
+        request: Request<ReplayWebHookDeadLetterRequest>,
+    ) -> Result<Response<ReplayWebHookDeadLetterResponse>, Status> {
+        let dead_letter = self.get_dead_letter(request.into_inner().id)?;
+
+        let hook = match self.web_hook_helper.get_by_id(&dead_letter.hook_id) {
+            Ok(Some(hook)) => hook,
+            Ok(None) => {
+                return Err(Status::new(
+                    Code::NotFound,
+                    format!("could not find hook for swap {}", dead_letter.hook_id),
+                ));
+            }
+            Err(err) => return Err(Status::new(Code::Internal, err.to_string())),
+        };
+
+        let data: WebHookCallData = serde_json::from_value(dead_letter.payload.clone())
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+
+        debug!(
+            "Replaying WebHook dead letter {} of swap {}",
+            dead_letter.id, dead_letter.hook_id
+        );
+
+        match self.web_hook_status_caller.replay(hook, data).await {
+            Ok(_) => {
+                self.web_hook_helper
+                    .delete_dead_letter(dead_letter.id)
+                    .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+
+                Ok(Response::new(ReplayWebHookDeadLetterResponse {
+                    ok: true,
+                    error: None,
+                }))
+            }
+            Err(attempt) => {
+                let mut attempts: Vec<CallAttempt> =
+                    serde_json::from_value(dead_letter.attempts).unwrap_or_default();
+                let error = attempt.error.clone();
+                let last_status = attempt.status.map(i32::from);
+                attempts.push(attempt);
+
+                self.web_hook_helper
+                    .set_dead_letter_attempts(
+                        dead_letter.id,
+                        last_status,
+                        Some(error.clone()),
+                        serde_json::to_value(attempts)
+                            .map_err(|err| Status::new(Code::Internal, err.to_string()))?,
+                    )
+                    .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+
+                Ok(Response::new(ReplayWebHookDeadLetterResponse {
+                    ok: false,
+                    error: Some(error),
+                }))
+            }
+        }
+    }
+
+    #[instrument(name = "grpc::purge_web_hook_dead_letters", skip_all)]
+    async fn purge_web_hook_dead_letters(
+        &self,
+        request: Request<PurgeWebHookDeadLettersRequest>,
+    ) -> Result<Response<PurgeWebHookDeadLettersResponse>, Status> {
+        let params = request.into_inner();
+
+        let older_than = match params.older_than {
+            Some(older_than) => Some(
+                chrono::DateTime::from_timestamp(older_than as i64, 0)
+                    .ok_or_else(|| Status::new(Code::InvalidArgument, "invalid timestamp"))?
+                    .naive_utc(),
+            ),
+            None => None,
+        };
+
+        let deleted = self
+            .web_hook_helper
+            .delete_dead_letters(params.hook_id, older_than)
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+        debug!("Purged {} WebHook dead letters", deleted);
+
+        Ok(Response::new(PurgeWebHookDeadLettersResponse {
+            deleted: deleted as u64,
+        }))
+    }
+
     #[instrument(name = "grpc::claim_batch", skip_all)]
     async fn claim_batch(
         &self,
@@ -408,6 +582,26 @@ where
         }
     }
 
+    #[instrument(name = "grpc::claim_batch_broadcasted", skip_all)]
+    async fn claim_batch_broadcasted(
+        &self,
+        request: Request<ClaimBatchBroadcastedRequest>,
+    ) -> Result<Response<ClaimBatchBroadcastedResponse>, Status> {
+        let params = request.into_inner();
+        if params.swap_ids.is_empty() {
+            return Err(Status::new(Code::InvalidArgument, "no swaps were claimed"));
+        }
+
+        match self.manager.claim_batch_broadcasted(
+            &params.symbol,
+            &params.swap_ids,
+            &params.transaction_id,
+        ) {
+            Ok(_) => Ok(Response::new(ClaimBatchBroadcastedResponse {})),
+            Err(err) => Err(Status::new(Code::Internal, err.to_string())),
+        }
+    }
+
     #[instrument(name = "grpc::sign_evm_refund", skip_all)]
     async fn sign_evm_refund(
         &self,
@@ -510,6 +704,123 @@ where
         }))
     }
 
+    #[instrument(name = "grpc::send_evm_transaction", skip_all)]
+    async fn send_evm_transaction(
+        &self,
+        request: Request<SendEvmTransactionRequest>,
+    ) -> Result<Response<SendEvmTransactionResponse>, Status> {
+        let params = request.into_inner();
+
+        let evm_manager = match self
+            .manager
+            .get_currency(&params.chain)
+            .and_then(|currency| currency.evm_manager)
+        {
+            Some(manager) => manager,
+            None => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("{} signer not found", params.chain),
+                ));
+            }
+        };
+
+        let to = match params.to.parse::<Address>() {
+            Ok(res) => res,
+            Err(err) => {
+                return Err(Status::new(
+                    Code::InvalidArgument,
+                    format!("could not parse address: {err}"),
+                ));
+            }
+        };
+        let value = match boltz_evm::utils::parse_wei(&params.value) {
+            Ok(res) => res,
+            Err(err) => {
+                return Err(Status::new(
+                    Code::InvalidArgument,
+                    format!("could not parse value: {err}"),
+                ));
+            }
+        };
+
+        match evm_manager
+            .send_transaction(to, value, params.data, params.gas_limit)
+            .await
+        {
+            Ok(tx) => Ok(Response::new(SendEvmTransactionResponse {
+                transaction_hash: tx.hash().to_vec(),
+                nonce: tx.nonce,
+                raw_transaction: tx.raw.to_vec(),
+            })),
+            Err(err) => Err(Status::new(
+                Code::Internal,
+                format!("sending transaction failed: {err}"),
+            )),
+        }
+    }
+
+    #[instrument(name = "grpc::get_evm_lockups", skip_all)]
+    async fn get_evm_lockups(
+        &self,
+        request: Request<GetEvmLockupsRequest>,
+    ) -> Result<Response<GetEvmLockupsResponse>, Status> {
+        let params = request.into_inner();
+
+        let state = match EvmLockupState::try_from(params.state) {
+            Ok(EvmLockupState::Active) => LockupState::Active,
+            Ok(EvmLockupState::Claimable) => LockupState::Claimable,
+            Ok(EvmLockupState::Refundable) => LockupState::Refundable,
+            Err(_) => return Err(Status::new(Code::InvalidArgument, "invalid lockup state")),
+        };
+        let parse_address = |address: Option<String>, name: &str| {
+            address
+                .map(|address| address.parse::<Address>())
+                .transpose()
+                .map_err(|err| {
+                    Status::new(
+                        Code::InvalidArgument,
+                        format!("could not parse {name} address: {err}"),
+                    )
+                })
+        };
+
+        let filter = LockupFilter {
+            state,
+            claim_address: parse_address(params.claim_address, "claim")?,
+            refund_address: parse_address(params.refund_address, "refund")?,
+        };
+
+        let indexed = match self.service.evm_lockups.get(&params.chain, &filter) {
+            Ok(Some(indexed)) => indexed,
+            Ok(None) => {
+                return Err(Status::new(
+                    Code::NotFound,
+                    format!("no lockups of {} have been indexed", params.chain),
+                ));
+            }
+            Err(err) => return Err(Status::new(Code::Internal, err.to_string())),
+        };
+
+        let locked = indexed
+            .locked()
+            .map_err(|err| Status::new(Code::Internal, err.to_string()))?;
+
+        Ok(Response::new(GetEvmLockupsResponse {
+            height: indexed.height,
+            lockups: indexed
+                .lockups
+                .into_iter()
+                .map(|lockup| lockup.try_into())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err: anyhow::Error| Status::new(Code::Internal, err.to_string()))?,
+            locked: locked
+                .into_iter()
+                .map(|(token, amount)| (token.to_string(), amount.to_string()))
+                .collect(),
+        }))
+    }
+
     #[instrument(name = "grpc::decode_invoice_or_offer", skip_all)]
     async fn decode_invoice_or_offer(
         &self,
@@ -819,6 +1130,54 @@ where
     }
 }
 
+impl TryFrom<WebHookDeadLetter> for boltzr::WebHookDeadLetter {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WebHookDeadLetter) -> Result<Self, Self::Error> {
+        let attempts: Vec<CallAttempt> = serde_json::from_value(value.attempts)?;
+
+        Ok(boltzr::WebHookDeadLetter {
+            id: value.id as u64,
+            hook_id: value.hook_id,
+            url: value.url,
+            payload: serde_json::to_string(&value.payload)?,
+            last_status: value.last_status.map(|status| status as u32),
+            last_error: value.last_error,
+            attempts: attempts
+                .into_iter()
+                .map(|attempt| boltzr::web_hook_dead_letter::Attempt {
+                    timestamp: attempt.timestamp,
+                    status: attempt.status.map(u32::from),
+                    error: attempt.error,
+                })
+                .collect(),
+            created_at: value.created_at.and_utc().timestamp() as u64,
+        })
+    }
+}
+
+impl TryFrom<EvmLockup> for boltzr::EvmLockup {
+    type Error = anyhow::Error;
+
+    fn try_from(value: EvmLockup) -> Result<Self, Self::Error> {
+        Ok(boltzr::EvmLockup {
+            contract_address: Address::try_from(value.contract_address.as_slice())?.to_string(),
+            transaction_hash: value.transaction_hash,
+            log_index: u64::try_from(value.log_index)?,
+            block_height: u64::try_from(value.block_height)?,
+            preimage_hash: value.preimage_hash,
+            amount: value.amount,
+            token_address: value
+                .token_address
+                .map(|address| Address::try_from(address.as_slice()).map(|a| a.to_string()))
+                .transpose()?,
+            claim_address: Address::try_from(value.claim_address.as_slice())?.to_string(),
+            refund_address: Address::try_from(value.refund_address.as_slice())?.to_string(),
+            timelock: u64::try_from(value.timelock)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::api::ws;
@@ -827,20 +1186,28 @@ mod test {
     use crate::db::helpers::chain_swap::{
         ChainSwapCondition, ChainSwapDataNullableCondition, ChainSwapHelper,
     };
+    use crate::db::helpers::evm_lockup::test::MockEvmLockupHelper;
     use crate::db::helpers::reverse_swap::{
         ReverseSwapCondition, ReverseSwapHelper, ReverseSwapNullableCondition,
     };
     use crate::db::helpers::swap::{SwapCondition, SwapHelper, SwapNullableCondition};
     use crate::db::helpers::web_hook::WebHookHelper;
     use crate::db::models::ReverseRoutingHint;
-    use crate::db::models::{ChainSwapInfo, ReverseSwap, Swap, WebHook, WebHookState};
+    use crate::db::models::{
+        ChainSwapInfo, EvmLockup, NewWebHookDeadLetter, ReverseSwap, Swap, WebHook,
+        WebHookDeadLetter, WebHookState,
+    };
     use crate::grpc::service::BoltzService;
     use crate::grpc::service::boltzr::boltz_r_server::BoltzR;
     use crate::grpc::service::boltzr::sign_evm_refund_request::Contract;
     use crate::grpc::service::boltzr::{
-        CreateWebHookRequest, CreateWebHookResponse, DeleteWebHookRequest, DeleteWebHookResponse,
-        GetInfoRequest, GetInfoResponse, SendWebHookRequest, SendWebHookResponse,
-        SignEvmRefundRequest, StartWebHookRetriesRequest, StartWebHookRetriesResponse,
+        self, CreateWebHookRequest, CreateWebHookResponse, DeleteWebHookRequest,
+        DeleteWebHookResponse, EvmLockupState, GetEvmLockupsRequest, GetInfoRequest,
+        GetInfoResponse, GetWebHookDeadLetterRequest, ListWebHookDeadLettersRequest,
+        PurgeWebHookDeadLettersRequest, PurgeWebHookDeadLettersResponse,
+        ReplayWebHookDeadLetterRequest, SendEvmTransactionRequest, SendWebHookRequest,
+        SendWebHookResponse, SignEvmRefundRequest, StartWebHookRetriesRequest,
+        StartWebHookRetriesResponse,
     };
     use crate::grpc::status_fetcher::StatusFetcher;
     use crate::notifications::commands::Commands;
@@ -849,7 +1216,7 @@ mod test {
     use crate::swap::manager::test::MockManager;
     use crate::tracing_setup::ReloadHandler;
     use boltz_cache::{Cache, MemCache};
-    use boltz_evm::FixedBytes;
+    use boltz_evm::{Address, FixedBytes};
     use mockall::mock;
     use rand::Rng;
     use std::collections::HashMap;
@@ -928,6 +1295,23 @@ mod test {
             fn get_by_id(&self, id: &str) -> QueryResponse<Option<WebHook>>;
             fn get_by_state(&self, state: WebHookState) -> QueryResponse<Vec<WebHook>>;
             fn get_swap_status(&self, id: &str) -> QueryResponse<Option<String>>;
+
+            fn insert_dead_letter(&self, dead_letter: &NewWebHookDeadLetter) -> QueryResponse<usize>;
+            fn get_dead_letters(&self, hook_id: Option<String>) -> QueryResponse<Vec<WebHookDeadLetter>>;
+            fn get_dead_letter(&self, id: i64) -> QueryResponse<Option<WebHookDeadLetter>>;
+            fn set_dead_letter_attempts(
+                &self,
+                id: i64,
+                last_status: Option<i32>,
+                last_error: Option<String>,
+                attempts: serde_json::Value,
+            ) -> QueryResponse<usize>;
+            fn delete_dead_letter(&self, id: i64) -> QueryResponse<usize>;
+            fn delete_dead_letters(
+                &self,
+                hook_id: Option<String>,
+                older_than: Option<chrono::NaiveDateTime>,
+            ) -> QueryResponse<usize>;
         }
     }
 
@@ -992,6 +1376,7 @@ mod test {
                 url: "https://some.url".to_string(),
                 hash_swap_id: false,
                 status: vec![],
+                secret: None,
             }))
             .await
             .unwrap()
@@ -1009,6 +1394,7 @@ mod test {
                 url: "notAUrl".to_string(),
                 hash_swap_id: false,
                 status: vec![],
+                secret: None,
             }))
             .await
             .err()
@@ -1017,6 +1403,24 @@ mod test {
         assert_eq!(err.message(), "relative URL without a base");
     }
 
+    #[tokio::test]
+    async fn test_create_web_hook_empty_secret() {
+        let (_, svc) = make_service().await;
+        let err = svc
+            .create_web_hook(Request::new(CreateWebHookRequest {
+                id: "id".to_string(),
+                url: "https://some.url".to_string(),
+                hash_swap_id: false,
+                status: vec![],
+                secret: Some("".to_string()),
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert_eq!(err.message(), "WebHook secret cannot be empty");
+    }
+
     #[tokio::test]
     async fn test_delete_web_hook() {
         let (_, svc) = make_service().await;
@@ -1062,6 +1466,110 @@ mod test {
         assert_eq!(err.message(), format!("could not find hook for swap {id}"));
     }
 
+    #[tokio::test]
+    async fn test_list_web_hook_dead_letters() {
+        let (_, svc) = make_service().await;
+        let res = svc
+            .list_web_hook_dead_letters(Request::new(ListWebHookDeadLettersRequest {
+                hook_id: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(res.dead_letters.len(), 1);
+
+        let dead_letter = &res.dead_letters[0];
+        assert_eq!(dead_letter.id, 1);
+        assert_eq!(dead_letter.hook_id, "deadHook");
+        assert_eq!(dead_letter.last_status, Some(500));
+        assert_eq!(dead_letter.created_at, 1_700_000_000);
+        assert_eq!(
+            dead_letter.attempts,
+            vec![
+                boltzr::web_hook_dead_letter::Attempt {
+                    timestamp: 1_700_000_000,
+                    status: Some(500),
+                    error: "HTTP 500".to_string(),
+                },
+                boltzr::web_hook_dead_letter::Attempt {
+                    timestamp: 1_700_000_060,
+                    status: None,
+                    error: "connection refused".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&dead_letter.payload).unwrap(),
+            make_dead_letter(1).payload
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_web_hook_dead_letter() {
+        let (_, svc) = make_service().await;
+        let res = svc
+            .get_web_hook_dead_letter(Request::new(GetWebHookDeadLetterRequest { id: 1 }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(res.dead_letter.unwrap().id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_web_hook_dead_letter_not_found() {
+        let (_, svc) = make_service().await;
+        let err = svc
+            .get_web_hook_dead_letter(Request::new(GetWebHookDeadLetterRequest { id: 2 }))
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(err.code(), Code::NotFound);
+        assert_eq!(err.message(), "could not find dead letter 2");
+    }
+
+    #[tokio::test]
+    async fn test_replay_web_hook_dead_letter_failed() {
+        let (_, svc) = make_service().await;
+        let res = svc
+            .replay_web_hook_dead_letter(Request::new(ReplayWebHookDeadLetterRequest { id: 1 }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert!(!res.ok);
+        assert!(res.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_replay_web_hook_dead_letter_not_found() {
+        let (_, svc) = make_service().await;
+        let err = svc
+            .replay_web_hook_dead_letter(Request::new(ReplayWebHookDeadLetterRequest { id: 2 }))
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(err.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_purge_web_hook_dead_letters() {
+        let (_, svc) = make_service().await;
+        assert_eq!(
+            svc.purge_web_hook_dead_letters(Request::new(PurgeWebHookDeadLettersRequest {
+                hook_id: Some("deadHook".to_string()),
+                older_than: Some(1_700_000_000),
+            }))
+            .await
+            .unwrap()
+            .into_inner(),
+            PurgeWebHookDeadLettersResponse { deleted: 3 }
+        );
+    }
+
     #[tokio::test]
     async fn test_sign_evm_refund_no_signer() {
         let (_, svc) = make_service().await;
@@ -1160,6 +1668,188 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_send_evm_transaction_no_signer() {
+        let (_, svc) = make_service().await;
+
+        let err = svc
+            .send_evm_transaction(Request::new(SendEvmTransactionRequest {
+                chain: "unknown".to_string(),
+                to: Address::repeat_byte(0x01).to_string(),
+                value: "0".to_string(),
+                data: vec![],
+                gas_limit: None,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::Internal);
+        assert_eq!(err.message(), "unknown signer not found");
+    }
+
+    #[tokio::test]
+    async fn test_send_evm_transaction_invalid_address() {
+        let (_, svc) = make_service().await;
+
+        let err = svc
+            .send_evm_transaction(Request::new(SendEvmTransactionRequest {
+                chain: "RBTC".to_string(),
+                to: "clearly not an address".to_string(),
+                value: "0".to_string(),
+                data: vec![],
+                gas_limit: None,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert_eq!(
+            err.message(),
+            "could not parse address: invalid string length"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_evm_transaction_invalid_value() {
+        let (_, svc) = make_service().await;
+
+        let err = svc
+            .send_evm_transaction(Request::new(SendEvmTransactionRequest {
+                chain: "RBTC".to_string(),
+                to: Address::repeat_byte(0x01).to_string(),
+                value: "-1".to_string(),
+                data: vec![],
+                gas_limit: None,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert_eq!(
+            err.message(),
+            "could not parse value: could not parse negative Ether amount"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_evm_lockups() {
+        let (_, svc) = make_service().await;
+
+        let res = svc
+            .get_evm_lockups(Request::new(GetEvmLockupsRequest {
+                chain: "RBTC".to_string(),
+                state: EvmLockupState::Refundable.into(),
+                claim_address: None,
+                refund_address: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(res.height, 100);
+        assert_eq!(
+            res.lockups,
+            vec![boltzr::EvmLockup {
+                contract_address: Address::repeat_byte(0xee).to_string(),
+                transaction_hash: vec![1; 32],
+                log_index: 2,
+                block_height: 90,
+                preimage_hash: vec![3; 32],
+                amount: "21".to_string(),
+                token_address: None,
+                claim_address: Address::repeat_byte(0x01).to_string(),
+                refund_address: Address::repeat_byte(0x02).to_string(),
+                timelock: 100,
+            }]
+        );
+        assert_eq!(
+            res.locked,
+            HashMap::from([(Address::ZERO.to_string(), "21".to_string())])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_evm_lockups_active() {
+        let (_, svc) = make_service().await;
+
+        let res = svc
+            .get_evm_lockups(Request::new(GetEvmLockupsRequest {
+                chain: "RBTC".to_string(),
+                state: EvmLockupState::Active.into(),
+                claim_address: Some(Address::repeat_byte(0x01).to_string()),
+                refund_address: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(res.lockups.len(), 2);
+        assert_eq!(
+            res.locked,
+            HashMap::from([
+                (Address::ZERO.to_string(), "21".to_string()),
+                (Address::repeat_byte(0x03).to_string(), "42".to_string()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_evm_lockups_not_indexed() {
+        let (_, svc) = make_service().await;
+
+        let err = svc
+            .get_evm_lockups(Request::new(GetEvmLockupsRequest {
+                chain: "ARB".to_string(),
+                state: EvmLockupState::Active.into(),
+                claim_address: None,
+                refund_address: None,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::NotFound);
+        assert_eq!(err.message(), "no lockups of ARB have been indexed");
+    }
+
+    #[tokio::test]
+    async fn test_get_evm_lockups_invalid_address() {
+        let (_, svc) = make_service().await;
+
+        let err = svc
+            .get_evm_lockups(Request::new(GetEvmLockupsRequest {
+                chain: "RBTC".to_string(),
+                state: EvmLockupState::Active.into(),
+                claim_address: None,
+                refund_address: Some("clearly not an address".to_string()),
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert_eq!(
+            err.message(),
+            "could not parse refund address: invalid string length"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_evm_lockups_invalid_state() {
+        let (_, svc) = make_service().await;
+
+        let err = svc
+            .get_evm_lockups(Request::new(GetEvmLockupsRequest {
+                chain: "RBTC".to_string(),
+                state: 21,
+                claim_address: None,
+                refund_address: None,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert_eq!(err.message(), "invalid lockup state");
+    }
+
     async fn make_service() -> (
         CancellationToken,
         BoltzService<MockManager, crate::notifications::mattermost::Client<Commands>>,
@@ -1182,6 +1872,8 @@ mod test {
                     Arc::new(
                         crate::db::helpers::swap_metadata::test::MockSwapMetadataHelper::new(),
                     ),
+                    Arc::new(crate::db::helpers::referral::test::MockReferralHelper::new()),
+                    Arc::new(make_mock_evm_lockup_helper()),
                     Arc::new(HashMap::from([(
                         "RBTC".to_string(),
                         Currency {
@@ -1209,6 +1901,40 @@ mod test {
         )
     }
 
+    fn make_mock_evm_lockup_helper() -> MockEvmLockupHelper {
+        let mut helper = MockEvmLockupHelper::new();
+        helper
+            .expect_get_indexed_height()
+            .returning(|symbol| Ok(if symbol == "RBTC" { Some(100) } else { None }));
+        helper.expect_get_active_lockups().returning(|symbol| {
+            let lockup = EvmLockup {
+                symbol: symbol.to_string(),
+                transaction_hash: vec![1; 32],
+                log_index: 2,
+                contract_address: vec![0xee; 20],
+                block_height: 90,
+                block_hash: vec![4; 32],
+                preimage_hash: vec![3; 32],
+                amount: "21".to_string(),
+                token_address: None,
+                claim_address: vec![0x01; 20],
+                refund_address: vec![0x02; 20],
+                timelock: 100,
+            };
+
+            Ok(vec![
+                lockup.clone(),
+                EvmLockup {
+                    amount: "42".to_string(),
+                    token_address: Some(vec![0x03; 20]),
+                    timelock: 101,
+                    ..lockup
+                },
+            ])
+        });
+        helper
+    }
+
     fn make_mock_hook_helper() -> MockWebHookHelper {
         let mut hook_helper = MockWebHookHelper::new();
         hook_helper.expect_get_by_id().returning(|id| {
@@ -1221,6 +1947,7 @@ mod test {
                     url: "http://127.0.0.1:11001".to_string(),
                     hash_swap_id: false,
                     status: None,
+                    secret: None,
                 }))
             }
         });
@@ -1230,11 +1957,59 @@ mod test {
         hook_helper.expect_insert_web_hook().returning(|_| Ok(1));
         hook_helper.expect_delete_web_hook().returning(|_| Ok(1));
         hook_helper.expect_set_state().returning(|_, _| Ok(1));
+        hook_helper
+            .expect_get_dead_letters()
+            .returning(|_| Ok(vec![make_dead_letter(1)]));
+        hook_helper.expect_get_dead_letter().returning(|id| {
+            if id == 1 {
+                Ok(Some(make_dead_letter(id)))
+            } else {
+                Ok(None)
+            }
+        });
+        hook_helper
+            .expect_set_dead_letter_attempts()
+            .returning(|_, _, _, _| Ok(1));
+        hook_helper.expect_delete_dead_letter().returning(|_| Ok(1));
+        hook_helper
+            .expect_delete_dead_letters()
+            .returning(|_, _| Ok(3));
         hook_helper.expect_clone().returning(make_mock_hook_helper);
 
         hook_helper
     }
 
+    fn make_dead_letter(id: i64) -> WebHookDeadLetter {
+        WebHookDeadLetter {
+            id,
+            hook_id: "deadHook".to_string(),
+            url: "http://127.0.0.1:11001".to_string(),
+            payload: serde_json::json!({
+                "event": "swap.update",
+                "data": {
+                    "id": "deadHook",
+                    "status": "transaction.claimed",
+                },
+            }),
+            last_status: Some(500),
+            last_error: Some("HTTP 500".to_string()),
+            attempts: serde_json::json!([
+                {
+                    "timestamp": 1_700_000_000,
+                    "status": 500,
+                    "error": "HTTP 500",
+                },
+                {
+                    "timestamp": 1_700_000_060,
+                    "error": "connection refused",
+                },
+            ]),
+            created_at: chrono::DateTime::from_timestamp(1_700_000_000, 0)
+                .unwrap()
+                .naive_utc(),
+        }
+    }
+
     fn make_mock_manager(evm_manager: Option<Arc<boltz_evm::Manager>>) -> MockManager {
         let mut manager = MockManager::new();
         let evm_manager_clone = evm_manager.clone();
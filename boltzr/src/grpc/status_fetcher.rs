@@ -72,7 +72,11 @@ impl SwapInfos for StatusFetcher {
             return Ok(Some(
                 ids.into_iter()
                     .zip(cache_entries)
-                    .map(|(id, entry)| SwapStatus { id, base: entry })
+                    .map(|(id, entry)| SwapStatus {
+                        id,
+                        base: entry,
+                        event_id: None,
+                    })
                     .collect(),
             ));
         }
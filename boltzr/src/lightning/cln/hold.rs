@@ -143,6 +143,7 @@ impl Hold {
                 max_retries: Some(2),
                 retry_interval: Some(10),
                 block_list: webhook_block_list,
+                ..Default::default()
             },
             network == wallet::Network::Regtest,
         ));
@@ -170,7 +171,12 @@ impl Hold {
         })
     }
 
-    pub fn add_offer(&self, offer: String, url: Option<String>) -> Result<()> {
+    pub fn add_offer(
+        &self,
+        offer: String,
+        url: Option<String>,
+        secret: Option<String>,
+    ) -> Result<()> {
         let signer = self.prepare_offer(self.network, &offer, url.as_deref())?;
         if self.offer_helper.get_by_signer(&signer)?.is_some() {
             return Err(anyhow!(
@@ -184,6 +190,7 @@ impl Hold {
             // Needed when we lookup if we know an offer when fetching
             offer: offer.to_lowercase(),
             url,
+            secret,
         })?;
         info!("Registered offer of {}", signer_hex);
         Ok(())
@@ -572,12 +579,13 @@ mod test {
                 signer,
                 offer: OFFER.to_lowercase(),
                 url: Some(HOOK.to_string()),
+                secret: None,
             }))
             .returning(|_| Ok(1));
 
         hold.offer_helper = Arc::new(offer_helper);
 
-        hold.add_offer(OFFER.to_uppercase(), Some(HOOK.to_string()))
+        hold.add_offer(OFFER.to_uppercase(), Some(HOOK.to_string()), None)
             .unwrap();
     }
 
@@ -597,13 +605,14 @@ mod test {
                     signer: signer.clone(),
                     offer: OFFER.to_lowercase(),
                     url: Some(HOOK.to_string()),
+                    secret: None,
                 }))
             });
 
         hold.offer_helper = Arc::new(offer_helper);
 
         assert_eq!(
-            hold.add_offer(OFFER.to_uppercase(), Some(HOOK.to_string()))
+            hold.add_offer(OFFER.to_uppercase(), Some(HOOK.to_string()), None)
                 .unwrap_err()
                 .to_string(),
             "an offer for this signing public key was registered already"
@@ -628,6 +637,7 @@ mod test {
                     signer: signer_cp.clone(),
                     offer: OFFER.to_lowercase(),
                     url: Some(HOOK.to_string()),
+                    secret: None,
                 }))
             });
 
@@ -674,6 +684,7 @@ mod test {
                     signer: signer_cp.clone(),
                     offer: OFFER.to_lowercase(),
                     url: Some(HOOK.to_string()),
+                    secret: None,
                 }))
             });
 
@@ -745,6 +756,7 @@ mod test {
                     signer: signer_cp.clone(),
                     offer: OFFER.to_lowercase(),
                     url: Some(HOOK.to_string()),
+                    secret: None,
                 }))
             });
 
@@ -791,6 +803,7 @@ mod test {
                     signer: signer_cp.clone(),
                     offer: OFFER.to_lowercase(),
                     url: Some(HOOK.to_string()),
+                    secret: None,
                 }))
             });
 
@@ -857,6 +870,7 @@ mod test {
                     signer: signer_cp.clone(),
                     offer: OFFER.to_lowercase(),
                     url: Some(HOOK.to_string()),
+                    secret: None,
                 }))
             });
 
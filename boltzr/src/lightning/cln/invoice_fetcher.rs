@@ -104,7 +104,9 @@ impl InvoiceFetcher {
             let receiver = self.invoice_caller.subscribe_successful_calls();
 
             let hook_id = hook.id();
-            self.invoice_caller.call(hook.with_url(url)).await?;
+            self.invoice_caller
+                .call(hook.with_secret(offer.secret).with_url(url))
+                .await?;
 
             let (invoice, decoded) = Self::wait_for_response(
                 hook_id,
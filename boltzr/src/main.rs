@@ -1,7 +1,9 @@
 use crate::config::parse_config;
 use crate::currencies::connect_nodes;
 use crate::db::helpers::chain_swap::ChainSwapHelperDatabase;
+use crate::db::helpers::evm_lockup::EvmLockupHelperDatabase;
 use crate::db::helpers::keys::KeysHelperDatabase;
+use crate::db::helpers::referral::ReferralHelperDatabase;
 use crate::db::helpers::reverse_swap::ReverseSwapHelperDatabase;
 use crate::db::helpers::swap::SwapHelperDatabase;
 use crate::db::helpers::swap_metadata::SwapMetadataHelperDatabase;
@@ -173,6 +175,19 @@ async fn main() {
         }
     });
 
+    let fee_escalation = config
+        .currencies
+        .iter()
+        .flatten()
+        .filter_map(|currency| {
+            currency
+                .chain
+                .as_ref()
+                .and_then(|chain| chain.fee_escalation.clone())
+                .map(|escalation| (currency.symbol.clone(), escalation))
+        })
+        .collect::<HashMap<_, _>>();
+
     let (network, currencies, offer_subscriptions) = match connect_nodes(
         cancellation_token.clone(),
         KeysHelperDatabase::new(db_pool.clone()),
@@ -206,6 +221,8 @@ async fn main() {
         Arc::new(ChainSwapHelperDatabase::new(db_pool.clone())),
         Arc::new(ReverseSwapHelperDatabase::new(db_pool.clone())),
         Arc::new(SwapMetadataHelperDatabase::new(db_pool.clone())),
+        Arc::new(ReferralHelperDatabase::new(db_pool.clone())),
+        Arc::new(EvmLockupHelperDatabase::new(db_pool.clone())),
         currencies.clone(),
         config.marking,
         config.historical,
@@ -222,6 +239,38 @@ async fn main() {
         });
     }
 
+    let mut evm_handles = Vec::new();
+    for (symbol, currency) in currencies.iter() {
+        let Some(evm_manager) = &currency.evm_manager else {
+            continue;
+        };
+
+        {
+            let nonce_manager = evm_manager.nonce_manager.clone();
+            let cancellation_token = cancellation_token.clone();
+            evm_handles.push(tokio::spawn(async move {
+                nonce_manager.start(cancellation_token).await;
+            }));
+        }
+
+        match evm_manager.indexer(EvmLockupHelperDatabase::new(db_pool.clone())) {
+            Some(Ok(indexer)) => {
+                let cancellation_token = cancellation_token.clone();
+                evm_handles.push(tokio::spawn(async move {
+                    indexer.start(cancellation_token).await;
+                }));
+            }
+            Some(Err(err)) => {
+                error!("Could not create {} lockup indexer: {}", symbol, err);
+                std::process::exit(1);
+            }
+            None => debug!(
+                "Not indexing {} lockups because it was not configured",
+                symbol
+            ),
+        }
+    }
+
     let backup_client = if let Some(backup_config) = config.backup {
         let db_backup_config: DatabaseConfig = config.postgres.clone().into();
         let channel_backup_sources = backup_adapter::from_currencies(&currencies);
@@ -284,6 +333,10 @@ async fn main() {
         db_pool.clone(),
         network,
         &config.pairs.unwrap_or_default(),
+        fee_escalation,
+        notification_client.clone().map(|client| {
+            Arc::new(client) as Arc<dyn notifications::NotificationClient + Send + Sync>
+        }),
     ) {
         Ok(swap_manager) => Arc::new(swap_manager),
         Err(err) => {
@@ -292,10 +345,12 @@ async fn main() {
         }
     };
 
+    let status_replay = service.status_replay.clone();
+
     let mut grpc_server = grpc::server::Server::new(
         cancellation_token.clone(),
         config.sidecar.grpc,
-        cache,
+        cache.clone(),
         log_reload_handler,
         service.clone(),
         swap_manager.clone(),
@@ -310,6 +365,7 @@ async fn main() {
         cancellation_token.clone(),
         swap_manager.clone(),
         service,
+        cache,
         grpc_server.status_fetcher(),
         swap_status_update_tx.clone(),
     );
@@ -330,6 +386,7 @@ async fn main() {
         config.sidecar.ws,
         grpc_server.status_fetcher(),
         swap_status_update_tx,
+        status_replay,
         offer_subscriptions,
     );
 
@@ -378,6 +435,10 @@ async fn main() {
     swap_manager_handler.await.unwrap();
     notification_listener_handle.await.unwrap();
 
+    for handle in evm_handles {
+        handle.await.unwrap();
+    }
+
     #[cfg(feature = "metrics")]
     metrics_handle.await.unwrap();
 
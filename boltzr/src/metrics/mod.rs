@@ -3,10 +3,12 @@ pub mod server;
 pub const SSE_OPEN_COUNT: &str = "sse_open_count";
 pub const GRPC_REQUEST_COUNT: &str = "grpc_request_count";
 pub const WEBHOOK_CALL_COUNT: &str = "webhook_call_count";
+pub const WEBHOOK_CIRCUIT_BREAKERS: &str = "webhook_circuit_breakers";
 pub const WEBSOCKET_OPEN_COUNT: &str = "websocket_open_count";
 pub const WEBSOCKET_MESSAGE_LIMIT_CLOSES: &str = "websocket_message_limit_closes";
 
 pub const ZEROCONF_TOOL_TXS: &str = "zeroconf_tool_txs";
 pub const ZEROCONF_TOOL_TXS_CALLS: &str = "zeroconf_tool_txs_calls";
+pub const ZEROCONF_POLICY_DECISIONS: &str = "zeroconf_policy_decisions";
 
 pub const FEE_TARGET: &str = "fee_target";
@@ -163,6 +163,12 @@ impl Server {
             "number of WebSockets closed due to inbound message rate limiting"
         );
 
+        describe_gauge!(
+            crate::metrics::WEBHOOK_CIRCUIT_BREAKERS,
+            Unit::Count,
+            "number of open and half-open Webhook circuit breakers"
+        );
+
         describe_gauge!(
             crate::metrics::WEBSOCKET_OPEN_COUNT,
             Unit::Count,
@@ -187,6 +193,12 @@ impl Server {
             "number of calls made to the 0-conf tool for accepted transactions",
         );
 
+        describe_counter!(
+            crate::metrics::ZEROCONF_POLICY_DECISIONS,
+            Unit::Count,
+            "number of 0-conf policy decisions by result and rejection reason",
+        );
+
         describe_gauge!(
             crate::metrics::FEE_TARGET,
             Unit::Count,
@@ -0,0 +1,242 @@
+use crate::db::helpers::evm_lockup::EvmLockupHelper;
+use crate::db::models::EvmLockup;
+use anyhow::Result;
+use boltz_evm::{Address, U256};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LockupState {
+    #[default]
+    Active,
+    Claimable,
+    Refundable,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LockupFilter {
+    pub state: LockupState,
+    pub claim_address: Option<Address>,
+    pub refund_address: Option<Address>,
+}
+
+impl LockupFilter {
+    /// Whether a lockup should be returned at `height`
+    ///
+    /// `height` is the indexed height, which trails the tip of the chain while the
+    /// indexer catches up; lockups whose timelock expired in the blocks after it
+    /// are still considered claimable
+    fn matches(&self, lockup: &EvmLockup, height: u64) -> bool {
+        if let Some(address) = &self.claim_address
+            && address.as_slice() != lockup.claim_address
+        {
+            return false;
+        }
+
+        if let Some(address) = &self.refund_address
+            && address.as_slice() != lockup.refund_address
+        {
+            return false;
+        }
+
+        // The contracts allow refunds once the timelock height is reached
+        let expired = lockup.timelock <= height as i64;
+        match self.state {
+            LockupState::Active => true,
+            LockupState::Claimable => !expired,
+            LockupState::Refundable => expired,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedLockups {
+    /// Height up to which the chain has been indexed
+    pub height: u64,
+    pub lockups: Vec<EvmLockup>,
+}
+
+impl IndexedLockups {
+    /// Sum of the locked amounts by token; the native currency is keyed by the zero address
+    pub fn locked(&self) -> Result<HashMap<Address, U256>> {
+        let mut locked = HashMap::new();
+
+        for lockup in &self.lockups {
+            let token = match &lockup.token_address {
+                Some(address) => Address::try_from(address.as_slice())?,
+                None => Address::ZERO,
+            };
+
+            *locked.entry(token).or_insert(U256::ZERO) += U256::from_str(&lockup.amount)?;
+        }
+
+        Ok(locked)
+    }
+}
+
+/// Queries the lockups that were indexed from the EVM swap contracts
+pub struct EvmLockups {
+    helper: Arc<dyn EvmLockupHelper + Sync + Send>,
+}
+
+impl EvmLockups {
+    pub fn new(helper: Arc<dyn EvmLockupHelper + Sync + Send>) -> Self {
+        Self { helper }
+    }
+
+    /// `None` when nothing has been indexed for the chain
+    pub fn get(&self, symbol: &str, filter: &LockupFilter) -> Result<Option<IndexedLockups>> {
+        let height = match self.helper.get_indexed_height(symbol)? {
+            Some(height) => height,
+            None => return Ok(None),
+        };
+
+        Ok(Some(IndexedLockups {
+            height,
+            lockups: self
+                .helper
+                .get_active_lockups(symbol)?
+                .into_iter()
+                .filter(|lockup| filter.matches(lockup, height))
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::helpers::evm_lockup::test::MockEvmLockupHelper;
+    use rstest::rstest;
+
+    const SYMBOL: &str = "RBTC";
+
+    const CLAIM_ADDRESS: Address = Address::repeat_byte(0x01);
+    const REFUND_ADDRESS: Address = Address::repeat_byte(0x02);
+    const TOKEN_ADDRESS: Address = Address::repeat_byte(0x03);
+
+    fn lockup(timelock: i64, amount: &str, token_address: Option<Address>) -> EvmLockup {
+        EvmLockup {
+            symbol: SYMBOL.to_string(),
+            transaction_hash: vec![0; 32],
+            log_index: 0,
+            contract_address: vec![0xee; 20],
+            block_height: 1,
+            block_hash: vec![1; 32],
+            preimage_hash: vec![2; 32],
+            amount: amount.to_string(),
+            token_address: token_address.map(|address| address.to_vec()),
+            claim_address: CLAIM_ADDRESS.to_vec(),
+            refund_address: REFUND_ADDRESS.to_vec(),
+            timelock,
+        }
+    }
+
+    fn lockups(height: Option<u64>, lockups: Vec<EvmLockup>) -> EvmLockups {
+        let mut helper = MockEvmLockupHelper::new();
+        helper
+            .expect_get_indexed_height()
+            .returning(move |_| Ok(height));
+        helper
+            .expect_get_active_lockups()
+            .returning(move |_| Ok(lockups.clone()));
+
+        EvmLockups::new(Arc::new(helper))
+    }
+
+    #[rstest]
+    #[case(LockupState::Active, vec![99, 100, 101])]
+    #[case(LockupState::Claimable, vec![101])]
+    #[case(LockupState::Refundable, vec![99, 100])]
+    fn test_get_state(#[case] state: LockupState, #[case] expected: Vec<i64>) {
+        let lockups = lockups(
+            Some(100),
+            vec![
+                lockup(99, "1", None),
+                lockup(100, "1", None),
+                lockup(101, "1", None),
+            ],
+        );
+
+        let res = lockups
+            .get(
+                SYMBOL,
+                &LockupFilter {
+                    state,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(res.height, 100);
+        assert_eq!(
+            res.lockups
+                .iter()
+                .map(|lockup| lockup.timelock)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(Some(CLAIM_ADDRESS), None, 1)]
+    #[case(None, Some(REFUND_ADDRESS), 1)]
+    #[case(Some(REFUND_ADDRESS), None, 0)]
+    #[case(None, Some(CLAIM_ADDRESS), 0)]
+    fn test_get_addresses(
+        #[case] claim_address: Option<Address>,
+        #[case] refund_address: Option<Address>,
+        #[case] expected: usize,
+    ) {
+        let lockups = lockups(Some(1), vec![lockup(100, "1", None)]);
+
+        let res = lockups
+            .get(
+                SYMBOL,
+                &LockupFilter {
+                    claim_address,
+                    refund_address,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.lockups.len(), expected);
+    }
+
+    #[test]
+    fn test_get_not_indexed() {
+        let lockups = lockups(None, vec![]);
+        assert!(
+            lockups
+                .get(SYMBOL, &LockupFilter::default())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_locked() {
+        let lockups = IndexedLockups {
+            height: 1,
+            lockups: vec![
+                lockup(100, "21", None),
+                lockup(100, "1000000000000000000000000000000", None),
+                lockup(100, "5", Some(TOKEN_ADDRESS)),
+            ],
+        };
+
+        assert_eq!(
+            lockups.locked().unwrap(),
+            HashMap::from([
+                (
+                    Address::ZERO,
+                    U256::from_str("1000000000000000000000000000021").unwrap()
+                ),
+                (TOKEN_ADDRESS, U256::from(5)),
+            ])
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use crate::currencies::Currencies;
 use crate::db::helpers::chain_swap::ChainSwapHelper;
+use crate::db::helpers::evm_lockup::EvmLockupHelper;
+use crate::db::helpers::referral::ReferralHelper;
 use crate::db::helpers::reverse_swap::ReverseSwapHelper;
 use crate::db::helpers::swap::SwapHelper;
 use crate::db::helpers::swap_metadata::SwapMetadataHelper;
 use crate::service::country_codes::CountryCodes;
+use crate::service::evm_lockups::EvmLockups;
 use crate::service::lightning_info::{GraphLightningInfo, LightningInfo};
 use crate::service::pair_stats::PairStatsFetcher;
 use crate::service::prometheus::{CachedPrometheusClient, RawPrometheusClient};
@@ -14,25 +17,34 @@ use std::sync::Arc;
 use tracing::warn;
 
 mod country_codes;
+mod evm_lockups;
 mod lightning_info;
 mod pair_stats;
 mod prometheus;
 mod pubkey_iterator;
+mod referral_swaps;
 mod rescue;
+mod status_replay;
 
 pub use country_codes::MarkingsConfig;
+pub use evm_lockups::{IndexedLockups, LockupFilter, LockupState};
 pub use pair_stats::HistoricalConfig;
 pub use pubkey_iterator::{
     KeyVecIterator, MAX_GAP_LIMIT, MAX_PAGINATION_LIMIT, Pagination, PubkeyIterator,
     SingleKeyIterator, XpubIterator,
 };
+pub use referral_swaps::{AuthenticationError, ReferralAuth, ReferralSwaps};
 pub use rescue::RestoreQuery;
+pub use status_replay::{Replay, StatusReplay};
 
 pub struct Service {
     pub swap_rescue: SwapRescue,
     pub country_codes: CountryCodes,
     pub lightning_info: Box<dyn LightningInfo + Send + Sync>,
     pub pair_stats: Option<PairStatsFetcher>,
+    pub referral_swaps: ReferralSwaps,
+    pub status_replay: StatusReplay,
+    pub evm_lockups: EvmLockups,
 }
 
 impl Service {
@@ -42,12 +54,22 @@ impl Service {
         chain_swap_helper: Arc<dyn ChainSwapHelper + Sync + Send>,
         reverse_swap_helper: Arc<dyn ReverseSwapHelper + Sync + Send>,
         metadata_helper: Arc<dyn SwapMetadataHelper + Sync + Send>,
+        referral_helper: Arc<dyn ReferralHelper + Sync + Send>,
+        evm_lockup_helper: Arc<dyn EvmLockupHelper + Sync + Send>,
         currencies: Currencies,
         markings_config: Option<MarkingsConfig>,
         historical_config: Option<HistoricalConfig>,
         cache: Cache,
     ) -> Self {
         Self {
+            referral_swaps: ReferralSwaps::new(
+                referral_helper,
+                swap_helper.clone(),
+                chain_swap_helper.clone(),
+                reverse_swap_helper.clone(),
+            ),
+            status_replay: StatusReplay::new(cache.clone()),
+            evm_lockups: EvmLockups::new(evm_lockup_helper),
             swap_rescue: SwapRescue::new(
                 cache.clone(),
                 swap_helper,
@@ -175,12 +197,26 @@ pub mod test {
                 .expect_get_all_nullable()
                 .returning(|_| Ok(vec![]));
 
+            let swap_helper = Arc::new(swap_helper);
+            let chain_swap_helper = Arc::new(chain_swap_helper);
+            let reverse_swap_helper = Arc::new(reverse_swap_helper);
+
             Self {
+                referral_swaps: ReferralSwaps::new(
+                    Arc::new(crate::db::helpers::referral::test::MockReferralHelper::new()),
+                    swap_helper.clone(),
+                    chain_swap_helper.clone(),
+                    reverse_swap_helper.clone(),
+                ),
+                status_replay: StatusReplay::new(Cache::Memory(MemCache::new())),
+                evm_lockups: EvmLockups::new(Arc::new(
+                    crate::db::helpers::evm_lockup::test::MockEvmLockupHelper::new(),
+                )),
                 swap_rescue: SwapRescue::new(
                     Cache::Memory(MemCache::new()),
-                    Arc::new(swap_helper),
-                    Arc::new(chain_swap_helper),
-                    Arc::new(reverse_swap_helper),
+                    swap_helper,
+                    chain_swap_helper,
+                    reverse_swap_helper,
                     Arc::new(HashMap::new()),
                     Arc::new(
                         crate::db::helpers::swap_metadata::test::MockSwapMetadataHelper::new(),
@@ -0,0 +1,485 @@
+use crate::db::helpers::chain_swap::ChainSwapHelper;
+use crate::db::helpers::referral::ReferralHelper;
+use crate::db::helpers::reverse_swap::ReverseSwapHelper;
+use crate::db::helpers::swap::SwapHelper;
+use crate::db::models::SomeSwap;
+use crate::db::schema::{chainSwaps, reverseSwaps, swaps};
+use anyhow::Result;
+use dashmap::DashMap;
+use diesel::{BoolExpressionMethods, ExpressionMethods, PgExpressionMethods};
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// Maximal difference in seconds between the timestamp of an authenticated
+/// request and the time of the server
+const TIMESTAMP_TOLERANCE: u64 = 60;
+
+/// Maximal number of cached results of referral swap lookups; the cache is
+/// cleared when it grows past it
+const MAX_CACHED_REFERRAL_SWAPS: usize = 10_000;
+
+const FINAL_SUBMARINE_STATUS: &[&str] =
+    &["swap.expired", "invoice.failedToPay", "transaction.claimed"];
+const FINAL_REVERSE_STATUS: &[&str] = &[
+    "swap.expired",
+    "invoice.settled",
+    "transaction.failed",
+    "transaction.refunded",
+];
+const FINAL_CHAIN_STATUS: &[&str] = &[
+    "swap.expired",
+    "transaction.failed",
+    "transaction.claimed",
+    "transaction.refunded",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthenticationError {
+    InvalidTimestamp,
+    TimestampDeviation,
+    Unauthorized,
+}
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthenticationError::InvalidTimestamp => f.write_str("TS header not a number"),
+            AuthenticationError::TimestampDeviation => write!(
+                f,
+                "TS header deviates from server time by more than {TIMESTAMP_TOLERANCE} seconds"
+            ),
+            AuthenticationError::Unauthorized => f.write_str("unauthorized"),
+        }
+    }
+}
+
+impl std::error::Error for AuthenticationError {}
+
+/// Headers with which a referral authenticates a request
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferralAuth {
+    pub ts: String,
+    pub api_key: String,
+    pub hmac: String,
+}
+
+/// Authenticates referrals and looks up the swaps created with them
+pub struct ReferralSwaps {
+    referral_helper: Arc<dyn ReferralHelper + Sync + Send>,
+    swap_helper: Arc<dyn SwapHelper + Sync + Send>,
+    chain_swap_helper: Arc<dyn ChainSwapHelper + Sync + Send>,
+    reverse_swap_helper: Arc<dyn ReverseSwapHelper + Sync + Send>,
+
+    /// Results of lookups by (referral, swap id), shared by all connections
+    referral_swaps: DashMap<(String, String), bool>,
+}
+
+impl ReferralSwaps {
+    pub fn new(
+        referral_helper: Arc<dyn ReferralHelper + Sync + Send>,
+        swap_helper: Arc<dyn SwapHelper + Sync + Send>,
+        chain_swap_helper: Arc<dyn ChainSwapHelper + Sync + Send>,
+        reverse_swap_helper: Arc<dyn ReverseSwapHelper + Sync + Send>,
+    ) -> Self {
+        Self {
+            referral_helper,
+            swap_helper,
+            chain_swap_helper,
+            reverse_swap_helper,
+            referral_swaps: DashMap::new(),
+        }
+    }
+
+    /// Verifies the HMAC of a request the same way the backend authenticates
+    /// referral requests and returns the id of the referral
+    ///
+    /// `path` includes the query string; like in the backend, the body is only
+    /// part of the HMAC of POST requests; `now` is the current UNIX timestamp in seconds
+    #[instrument(name = "ReferralSwaps::authenticate", skip_all)]
+    pub fn authenticate(
+        &self,
+        auth: &ReferralAuth,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        now: u64,
+    ) -> Result<String> {
+        let ts = auth
+            .ts
+            .parse::<u64>()
+            .map_err(|_| AuthenticationError::InvalidTimestamp)?;
+        if ts.abs_diff(now) > TIMESTAMP_TOLERANCE {
+            return Err(AuthenticationError::TimestampDeviation.into());
+        }
+
+        let credentials = self
+            .referral_helper
+            .get_credentials(&auth.api_key)?
+            .ok_or(AuthenticationError::Unauthorized)?;
+
+        let provided = hex::decode(&auth.hmac).map_err(|_| AuthenticationError::Unauthorized)?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(credentials.apiSecret.as_bytes())
+            .map_err(|_| AuthenticationError::Unauthorized)?;
+        mac.update(format!("{ts}{method}{path}").as_bytes());
+        if method == "POST" {
+            mac.update(body);
+        }
+        mac.verify_slice(&provided)
+            .map_err(|_| AuthenticationError::Unauthorized)?;
+
+        Ok(credentials.id)
+    }
+
+    /// Ids of all swaps of a referral that are not in a final state yet
+    #[instrument(name = "ReferralSwaps::pending_swap_ids", skip(self))]
+    pub fn pending_swap_ids(&self, referral: &str) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+
+        ids.extend(
+            self.swap_helper
+                .get_all(Box::new(
+                    swaps::dsl::referral
+                        .is_not_distinct_from(referral)
+                        .and(swaps::dsl::status.ne_all(FINAL_SUBMARINE_STATUS.to_vec())),
+                ))?
+                .into_iter()
+                .map(|swap| swap.id),
+        );
+        ids.extend(
+            self.reverse_swap_helper
+                .get_all(Box::new(
+                    reverseSwaps::dsl::referral
+                        .is_not_distinct_from(referral)
+                        .and(reverseSwaps::dsl::status.ne_all(FINAL_REVERSE_STATUS.to_vec())),
+                ))?
+                .into_iter()
+                .map(|swap| swap.id),
+        );
+        ids.extend(
+            self.chain_swap_helper
+                .get_all(Box::new(
+                    chainSwaps::dsl::referral
+                        .is_not_distinct_from(referral)
+                        .and(chainSwaps::dsl::status.ne_all(FINAL_CHAIN_STATUS.to_vec())),
+                ))?
+                .into_iter()
+                .map(|swap| swap.id()),
+        );
+
+        Ok(ids)
+    }
+
+    /// Checks whether a swap was created with a referral
+    ///
+    /// The database is queried in a blocking task and the result is cached,
+    /// so that the lookup runs only once when many connections of the same
+    /// referral receive the same update
+    #[instrument(name = "ReferralSwaps::is_referral_swap", skip(self))]
+    pub async fn is_referral_swap(&self, referral: &str, id: &str) -> Result<bool> {
+        let key = (referral.to_string(), id.to_string());
+        if let Some(cached) = self.referral_swaps.get(&key) {
+            return Ok(*cached);
+        }
+
+        let swap_helper = self.swap_helper.clone();
+        let chain_swap_helper = self.chain_swap_helper.clone();
+        let reverse_swap_helper = self.reverse_swap_helper.clone();
+        let (referral, id) = key.clone();
+
+        let res = tokio::task::spawn_blocking(move || {
+            Self::query_referral_swap(
+                swap_helper.as_ref(),
+                chain_swap_helper.as_ref(),
+                reverse_swap_helper.as_ref(),
+                &referral,
+                &id,
+            )
+        })
+        .await??;
+
+        if self.referral_swaps.len() >= MAX_CACHED_REFERRAL_SWAPS {
+            self.referral_swaps.clear();
+        }
+        self.referral_swaps.insert(key, res);
+
+        Ok(res)
+    }
+
+    fn query_referral_swap(
+        swap_helper: &(dyn SwapHelper + Sync + Send),
+        chain_swap_helper: &(dyn ChainSwapHelper + Sync + Send),
+        reverse_swap_helper: &(dyn ReverseSwapHelper + Sync + Send),
+        referral: &str,
+        id: &str,
+    ) -> Result<bool> {
+        if !swap_helper
+            .get_all(Box::new(
+                swaps::dsl::id
+                    .eq(id.to_string())
+                    .and(swaps::dsl::referral.is_not_distinct_from(referral)),
+            ))?
+            .is_empty()
+        {
+            return Ok(true);
+        }
+
+        if !reverse_swap_helper
+            .get_all(Box::new(
+                reverseSwaps::dsl::id
+                    .eq(id.to_string())
+                    .and(reverseSwaps::dsl::referral.is_not_distinct_from(referral)),
+            ))?
+            .is_empty()
+        {
+            return Ok(true);
+        }
+
+        Ok(!chain_swap_helper
+            .get_all(Box::new(
+                chainSwaps::dsl::id
+                    .eq(id.to_string())
+                    .and(chainSwaps::dsl::referral.is_not_distinct_from(referral)),
+            ))?
+            .is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::helpers::chain_swap::test::MockChainSwapHelper;
+    use crate::db::helpers::referral::test::MockReferralHelper;
+    use crate::db::helpers::reverse_swap::test::MockReverseSwapHelper;
+    use crate::db::helpers::swap::test::MockSwapHelper;
+    use crate::db::models::{ChainSwapInfo, ReferralCredentials, ReverseSwap, Swap};
+    use diesel::QueryDsl;
+    use diesel::debug_query;
+    use diesel::pg::Pg;
+    use rstest::rstest;
+
+    const API_KEY: &str = "key";
+    const API_SECRET: &str = "secret";
+    const NOW: u64 = 1_700_000_000;
+
+    fn sign(secret: &str, ts: u64, method: &str, path: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{ts}{method}{path}").as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn referral_swaps(
+        swap_helper: MockSwapHelper,
+        reverse_swap_helper: MockReverseSwapHelper,
+        chain_swap_helper: MockChainSwapHelper,
+    ) -> ReferralSwaps {
+        let mut referral_helper = MockReferralHelper::new();
+        referral_helper
+            .expect_get_credentials()
+            .returning(|api_key| {
+                Ok(if api_key == API_KEY {
+                    Some(ReferralCredentials {
+                        id: "pro".to_string(),
+                        apiKey: API_KEY.to_string(),
+                        apiSecret: API_SECRET.to_string(),
+                    })
+                } else {
+                    None
+                })
+            });
+
+        ReferralSwaps::new(
+            Arc::new(referral_helper),
+            Arc::new(swap_helper),
+            Arc::new(chain_swap_helper),
+            Arc::new(reverse_swap_helper),
+        )
+    }
+
+    fn auth(ts: &str, api_key: &str, hmac: String) -> ReferralAuth {
+        ReferralAuth {
+            ts: ts.to_string(),
+            api_key: api_key.to_string(),
+            hmac,
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let swaps = referral_swaps(
+            MockSwapHelper::new(),
+            MockReverseSwapHelper::new(),
+            MockChainSwapHelper::new(),
+        );
+
+        let path = "/streamswapstatus?id=swap";
+        assert_eq!(
+            swaps
+                .authenticate(
+                    &auth(
+                        &NOW.to_string(),
+                        API_KEY,
+                        sign(API_SECRET, NOW, "GET", path)
+                    ),
+                    "GET",
+                    path,
+                    &[],
+                    NOW + 30,
+                )
+                .unwrap(),
+            "pro"
+        );
+    }
+
+    #[test]
+    fn test_authenticate_post_body() {
+        let swaps = referral_swaps(
+            MockSwapHelper::new(),
+            MockReverseSwapHelper::new(),
+            MockChainSwapHelper::new(),
+        );
+
+        let path = "/v2/swap/rescue";
+        let body = br#"{"xpub":"xpub"}"#;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(API_SECRET.as_bytes()).unwrap();
+        mac.update(format!("{NOW}POST{path}").as_bytes());
+        mac.update(body);
+        let hmac = hex::encode(mac.finalize().into_bytes());
+
+        let auth = auth(&NOW.to_string(), API_KEY, hmac);
+        assert_eq!(
+            swaps.authenticate(&auth, "POST", path, body, NOW).unwrap(),
+            "pro"
+        );
+        assert!(swaps.authenticate(&auth, "POST", path, b"{}", NOW).is_err());
+    }
+
+    #[rstest]
+    #[case::ts_not_number(
+        auth("now", API_KEY, sign(API_SECRET, NOW, "GET", "/")),
+        AuthenticationError::InvalidTimestamp
+    )]
+    #[case::ts_too_old(
+        auth(&(NOW - 61).to_string(), API_KEY, sign(API_SECRET, NOW - 61, "GET", "/")),
+        AuthenticationError::TimestampDeviation
+    )]
+    #[case::ts_in_future(
+        auth(&(NOW + 61).to_string(), API_KEY, sign(API_SECRET, NOW + 61, "GET", "/")),
+        AuthenticationError::TimestampDeviation
+    )]
+    #[case::unknown_key(
+        auth(&NOW.to_string(), "unknown", sign(API_SECRET, NOW, "GET", "/")),
+        AuthenticationError::Unauthorized
+    )]
+    #[case::wrong_secret(
+        auth(&NOW.to_string(), API_KEY, sign("wrong", NOW, "GET", "/")),
+        AuthenticationError::Unauthorized
+    )]
+    #[case::wrong_path(
+        auth(&NOW.to_string(), API_KEY, sign(API_SECRET, NOW, "GET", "/other")),
+        AuthenticationError::Unauthorized
+    )]
+    #[case::not_hex(
+        auth(&NOW.to_string(), API_KEY, "not hex".to_string()),
+        AuthenticationError::Unauthorized
+    )]
+    fn test_authenticate_error(#[case] auth: ReferralAuth, #[case] expected: AuthenticationError) {
+        let swaps = referral_swaps(
+            MockSwapHelper::new(),
+            MockReverseSwapHelper::new(),
+            MockChainSwapHelper::new(),
+        );
+
+        let err = swaps.authenticate(&auth, "GET", "/", &[], NOW).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<AuthenticationError>().unwrap(),
+            &expected
+        );
+    }
+
+    #[test]
+    fn test_pending_swap_ids() {
+        let mut swap_helper = MockSwapHelper::new();
+        swap_helper.expect_get_all().returning(|condition| {
+            let sql = debug_query::<Pg, _>(&swaps::table.filter(condition)).to_string();
+            assert!(sql.contains(r#""swaps"."referral" IS NOT DISTINCT FROM"#));
+            for status in FINAL_SUBMARINE_STATUS {
+                assert!(sql.contains(status));
+            }
+
+            Ok(vec![Swap {
+                id: "submarine".to_string(),
+                ..Default::default()
+            }])
+        });
+
+        let mut reverse_swap_helper = MockReverseSwapHelper::new();
+        reverse_swap_helper.expect_get_all().returning(|_| {
+            Ok(vec![ReverseSwap {
+                id: "reverse".to_string(),
+                ..Default::default()
+            }])
+        });
+
+        let mut chain_swap_helper = MockChainSwapHelper::new();
+        chain_swap_helper.expect_get_all().returning(|_| Ok(vec![]));
+
+        let swaps = referral_swaps(swap_helper, reverse_swap_helper, chain_swap_helper);
+        assert_eq!(
+            swaps.pending_swap_ids("pro").unwrap(),
+            vec!["submarine".to_string(), "reverse".to_string()]
+        );
+    }
+
+    #[rstest]
+    #[case::submarine(1, 0, 0, true)]
+    #[case::reverse(0, 1, 0, true)]
+    #[case::chain(0, 0, 1, true)]
+    #[case::none(0, 0, 0, false)]
+    #[tokio::test]
+    async fn test_is_referral_swap(
+        #[case] submarine: usize,
+        #[case] reverse: usize,
+        #[case] chain: usize,
+        #[case] expected: bool,
+    ) {
+        let mut swap_helper = MockSwapHelper::new();
+        swap_helper
+            .expect_get_all()
+            .returning(move |_| Ok(vec![Swap::default(); submarine]));
+
+        let mut reverse_swap_helper = MockReverseSwapHelper::new();
+        reverse_swap_helper
+            .expect_get_all()
+            .returning(move |_| Ok(vec![ReverseSwap::default(); reverse]));
+
+        let mut chain_swap_helper = MockChainSwapHelper::new();
+        chain_swap_helper
+            .expect_get_all()
+            .returning(move |_| Ok(vec![ChainSwapInfo::default(); chain]));
+
+        let swaps = referral_swaps(swap_helper, reverse_swap_helper, chain_swap_helper);
+        assert_eq!(swaps.is_referral_swap("pro", "id").await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_is_referral_swap_cached() {
+        let mut swap_helper = MockSwapHelper::new();
+        swap_helper
+            .expect_get_all()
+            .times(1)
+            .returning(|_| Ok(vec![Swap::default()]));
+
+        let swaps = referral_swaps(
+            swap_helper,
+            MockReverseSwapHelper::new(),
+            MockChainSwapHelper::new(),
+        );
+        for _ in 0..3 {
+            assert!(swaps.is_referral_swap("pro", "id").await.unwrap());
+        }
+    }
+}
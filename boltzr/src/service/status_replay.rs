@@ -0,0 +1,323 @@
+use crate::api::ws::types::SwapStatus;
+use anyhow::Result;
+use boltz_cache::Cache;
+use tracing::instrument;
+
+const CACHE_KEY_STATUS_REPLAY: &str = "swap:replay";
+const FIELD_HEAD: &str = "head";
+
+/// Number of updates that are kept for clients that reconnect
+const REPLAY_CAPACITY: u64 = 10_000;
+
+/// Maximal number of updates read for a single reconnecting client; clients
+/// that missed more get the latest status of their swaps instead
+const MAX_REPLAY_UPDATES: u64 = 500;
+
+/// Updates recorded after a cursor and the event id of the last of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub head: u64,
+    pub updates: Vec<SwapStatus>,
+}
+
+/// Assigns monotonically increasing event ids to swap status updates and keeps
+/// the latest of them in the cache, so that status streams can be resumed
+///
+/// Event ids are allocated with an atomic increment in the cache, so that all
+/// instances sharing it hand out unique ones
+#[derive(Debug, Clone)]
+pub struct StatusReplay {
+    cache: Cache,
+    capacity: u64,
+    max_replay: u64,
+}
+
+impl StatusReplay {
+    pub fn new(cache: Cache) -> Self {
+        Self::with_capacity(cache, REPLAY_CAPACITY)
+    }
+
+    fn with_capacity(cache: Cache, capacity: u64) -> Self {
+        Self {
+            cache,
+            capacity,
+            max_replay: capacity.min(MAX_REPLAY_UPDATES),
+        }
+    }
+
+    #[instrument(name = "StatusReplay::record", skip_all)]
+    pub async fn record(&self, updates: Vec<SwapStatus>) -> Result<Vec<SwapStatus>> {
+        if updates.is_empty() {
+            return Ok(updates);
+        }
+
+        let last = self
+            .cache
+            .increment(
+                CACHE_KEY_STATUS_REPLAY,
+                FIELD_HEAD,
+                updates.len() as u64,
+                None,
+            )
+            .await?;
+
+        let mut recorded = Vec::with_capacity(updates.len());
+        for (event_id, mut update) in (last + 1 - updates.len() as u64..).zip(updates) {
+            update.event_id = Some(event_id);
+
+            self.cache
+                .set(
+                    CACHE_KEY_STATUS_REPLAY,
+                    &event_id.to_string(),
+                    &update,
+                    None,
+                )
+                .await?;
+            if event_id > self.capacity {
+                self.cache
+                    .delete(
+                        CACHE_KEY_STATUS_REPLAY,
+                        &(event_id - self.capacity).to_string(),
+                    )
+                    .await?;
+            }
+
+            recorded.push(update);
+        }
+
+        Ok(recorded)
+    }
+
+    /// Returns the updates with an event id after the cursor or `None` when
+    /// they cannot be replayed because the cursor is too old
+    ///
+    /// The head is allocated before the updates are written, so updates that
+    /// are still being recorded end the replay early; they are broadcast to the
+    /// client once they were written
+    #[instrument(name = "StatusReplay::since", skip(self))]
+    pub async fn since(&self, cursor: u64) -> Result<Option<Replay>> {
+        let head = self
+            .cache
+            .get::<u64>(CACHE_KEY_STATUS_REPLAY, FIELD_HEAD)
+            .await?
+            .unwrap_or_default();
+
+        // A cursor ahead of the head was handed out before the buffer was lost
+        if cursor > head || head - cursor > self.max_replay {
+            return Ok(None);
+        }
+
+        let fields = (cursor + 1..=head)
+            .map(|event_id| event_id.to_string())
+            .collect::<Vec<_>>();
+        let entries = self
+            .cache
+            .get_multiple::<SwapStatus>(
+                CACHE_KEY_STATUS_REPLAY,
+                &fields
+                    .iter()
+                    .map(|field| field.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .await?;
+
+        // Without the first update, there is no way to tell whether it was evicted
+        if entries.first().is_some_and(|entry| entry.is_none()) {
+            return Ok(None);
+        }
+
+        let updates = entries
+            .into_iter()
+            .map_while(|entry| entry)
+            .collect::<Vec<_>>();
+        Ok(Some(Replay {
+            head: cursor + updates.len() as u64,
+            updates,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use boltz_cache::MemCache;
+
+    #[tokio::test]
+    async fn test_record_assigns_event_ids() {
+        let replay = StatusReplay::new(Cache::Memory(MemCache::new()));
+
+        let first = replay
+            .record(vec![
+                SwapStatus::new("swap1".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap2".to_string(), "swap.created".to_string()),
+            ])
+            .await
+            .unwrap();
+        let second = replay
+            .record(vec![SwapStatus::new(
+                "swap1".to_string(),
+                "invoice.set".to_string(),
+            )])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            first
+                .iter()
+                .chain(second.iter())
+                .map(|update| update.event_id)
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(2), Some(3)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_continues_from_cached_head() {
+        let cache = Cache::Memory(MemCache::new());
+        StatusReplay::new(cache.clone())
+            .record(vec![SwapStatus::new(
+                "swap1".to_string(),
+                "swap.created".to_string(),
+            )])
+            .await
+            .unwrap();
+
+        let recorded = StatusReplay::new(cache)
+            .record(vec![SwapStatus::new(
+                "swap1".to_string(),
+                "invoice.set".to_string(),
+            )])
+            .await
+            .unwrap();
+        assert_eq!(recorded[0].event_id, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_since() {
+        let replay = StatusReplay::new(Cache::Memory(MemCache::new()));
+        replay
+            .record(vec![
+                SwapStatus::new("swap1".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap2".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap1".to_string(), "invoice.set".to_string()),
+                SwapStatus::new("swap3".to_string(), "swap.created".to_string()),
+            ])
+            .await
+            .unwrap();
+
+        let res = replay.since(1).await.unwrap().unwrap();
+        assert_eq!(res.head, 4);
+        assert_eq!(
+            res.updates
+                .iter()
+                .map(|update| (update.id.as_str(), update.event_id))
+                .collect::<Vec<_>>(),
+            vec![("swap2", Some(2)), ("swap1", Some(3)), ("swap3", Some(4))]
+        );
+
+        let res = replay.since(4).await.unwrap().unwrap();
+        assert_eq!(res.head, 4);
+        assert!(res.updates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_record_shared_cache() {
+        let cache = Cache::Memory(MemCache::new());
+        let first = StatusReplay::new(cache.clone());
+        let second = StatusReplay::new(cache);
+
+        let (first, second) = tokio::join!(
+            first.record(vec![
+                SwapStatus::new("swap1".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap1".to_string(), "invoice.set".to_string()),
+            ]),
+            second.record(vec![
+                SwapStatus::new("swap2".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap2".to_string(), "invoice.set".to_string()),
+            ]),
+        );
+
+        let mut event_ids = first
+            .unwrap()
+            .iter()
+            .chain(second.unwrap().iter())
+            .map(|update| update.event_id.unwrap())
+            .collect::<Vec<_>>();
+        event_ids.sort();
+        assert_eq!(event_ids, vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_since_pending_record() {
+        let cache = Cache::Memory(MemCache::new());
+        let replay = StatusReplay::new(cache.clone());
+        replay
+            .record(vec![SwapStatus::new(
+                "swap1".to_string(),
+                "swap.created".to_string(),
+            )])
+            .await
+            .unwrap();
+
+        // Another instance allocated event ids but did not write the updates yet
+        cache
+            .increment(CACHE_KEY_STATUS_REPLAY, FIELD_HEAD, 2, None)
+            .await
+            .unwrap();
+
+        let res = replay.since(0).await.unwrap().unwrap();
+        assert_eq!(res.head, 1);
+        assert_eq!(res.updates.len(), 1);
+
+        assert_eq!(replay.since(1).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_since_too_many_updates() {
+        let replay = StatusReplay::new(Cache::Memory(MemCache::new()));
+        replay
+            .record(
+                (0..=MAX_REPLAY_UPDATES)
+                    .map(|_| SwapStatus::new("swap1".to_string(), "swap.created".to_string()))
+                    .collect(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(replay.since(0).await.unwrap(), None);
+        assert_eq!(
+            replay.since(1).await.unwrap().unwrap().updates.len() as u64,
+            MAX_REPLAY_UPDATES
+        );
+    }
+
+    #[tokio::test]
+    async fn test_since_cursor_ahead_of_head() {
+        let replay = StatusReplay::new(Cache::Memory(MemCache::new()));
+        assert_eq!(replay.since(21).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_since_evicted() {
+        let replay = StatusReplay::with_capacity(Cache::Memory(MemCache::new()), 2);
+        replay
+            .record(vec![
+                SwapStatus::new("swap1".to_string(), "swap.created".to_string()),
+                SwapStatus::new("swap1".to_string(), "invoice.set".to_string()),
+                SwapStatus::new("swap1".to_string(), "transaction.mempool".to_string()),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(replay.since(0).await.unwrap(), None);
+
+        let res = replay.since(1).await.unwrap().unwrap();
+        assert_eq!(
+            res.updates
+                .iter()
+                .map(|update| update.event_id)
+                .collect::<Vec<_>>(),
+            vec![Some(2), Some(3)]
+        );
+    }
+}
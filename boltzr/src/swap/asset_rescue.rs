@@ -5,7 +5,7 @@ use crate::{
         helpers::{chain_swap::ChainSwapHelper, swap::SwapHelper},
         models::{LightningSwap, SomeSwap, SwapVersion},
     },
-    swap::SwapUpdate,
+    swap::{SwapUpdate, nonce_guard::CacheNonceGuard},
     wallet::Wallet,
 };
 use anyhow::{Context, Result};
@@ -14,6 +14,7 @@ use boltz_cache::Cache;
 use boltz_core::{
     FeeTarget, Musig, Network,
     elements::{AssetPair, Tree as ElementsTree, construct_asset_rescue},
+    musig::{MusigSnapshot, RestoredSession},
 };
 use elements::{
     Address, OutPoint, Transaction,
@@ -34,10 +35,9 @@ type RescueNode<'a> = (
     Arc<dyn Wallet + Send + Sync>,
 );
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct PendingRescue {
-    sec_nonce: String,
-    pub_nonce: String,
+    session: MusigSnapshot,
     sighash: String,
     funding_txid: String,
     funding_vout: u32,
@@ -163,6 +163,7 @@ impl AssetRescue {
                     )?,
                 )?),
                 destination,
+                signer: None,
             },
             &AssetPair {
                 tx_out: funding_out,
@@ -176,9 +177,11 @@ impl AssetRescue {
                     )?,
                 )?),
                 destination: &change_destination,
+                signer: None::<Keypair>,
             },
             FeeTarget::Relative(chain.estimate_fee().await?),
-        )?;
+        )
+        .await?;
 
         let sighash = SighashCache::new(&tx).taproot_key_spend_signature_hash(
             0,
@@ -200,12 +203,28 @@ impl AssetRescue {
                 musig_keys.public_key(),
                 Musig::convert_pub_key(&swap_rescue_details.their_public_key)?,
             ],
-        )?
-        .message(sighash)
-        .generate_nonce(&mut Musig::rng());
+        )?;
+
+        // The tweak is persisted with the session, so apply it before generating the nonce
+        let internal_key = musig.agg_pk();
+        let musig = musig
+            .xonly_tweak_add(&Musig::convert_scalar_be(
+                &swap_rescue_details
+                    .swap_tree
+                    .build()?
+                    .finalize(
+                        &Secp256k1::new(),
+                        bitcoin::XOnlyPublicKey::from_slice(&internal_key.serialize())?,
+                    )?
+                    .tap_tweak()
+                    .to_scalar()
+                    .to_be_bytes(),
+            )?)?
+            .message(sighash)
+            .generate_nonce(&mut Musig::rng());
 
         let pub_nonce = musig.pub_nonce().serialize();
-        let sec_nonce = musig.dangerous_secnonce().dangerous_into_bytes();
+        let session = musig.snapshot(&self.nonce_guard()).await?;
 
         let tx = hex::encode(tx.serialize());
         self.cache
@@ -213,8 +232,7 @@ impl AssetRescue {
                 CACHE_KEY,
                 &Self::cache_field_swap(swap_id),
                 &PendingRescue {
-                    sec_nonce: hex::encode(sec_nonce),
-                    pub_nonce: hex::encode(pub_nonce),
+                    session,
                     sighash: hex::encode(sighash),
                     funding_txid: funding_utxo.txid.to_string(),
                     funding_vout: funding_utxo.vout,
@@ -258,30 +276,24 @@ impl AssetRescue {
         };
 
         let (swap, symbol, swap_rescue_details) = self.get_swap(swap_id)?;
+        let reserved_utxo = Self::cache_field_reserved_utxo(
+            &symbol,
+            &pending_rescue.funding_txid,
+            pending_rescue.funding_vout,
+        );
 
         let res = self
             .broadcast_inner(
                 &symbol,
                 swap,
                 swap_rescue_details,
-                &pending_rescue,
+                pending_rescue,
                 pub_nonce,
                 partial_signature,
             )
             .await;
 
-        if let Err(e) = self
-            .cache
-            .delete(
-                CACHE_KEY,
-                &Self::cache_field_reserved_utxo(
-                    &symbol,
-                    &pending_rescue.funding_txid,
-                    pending_rescue.funding_vout,
-                ),
-            )
-            .await
-        {
+        if let Err(e) = self.cache.delete(CACHE_KEY, &reserved_utxo).await {
             tracing::warn!("Failed to delete reserved UTXO cache: {e}");
         }
 
@@ -297,7 +309,7 @@ impl AssetRescue {
         symbol: &str,
         swap: Box<dyn SomeSwap + Send + Sync>,
         swap_rescue_details: AssetRescueDetails,
-        pending_rescue: &PendingRescue,
+        pending_rescue: PendingRescue,
         pub_nonce: &[u8],
         partial_signature: &[u8],
     ) -> Result<String> {
@@ -313,30 +325,17 @@ impl AssetRescue {
                 .private_key
                 .secret_bytes(),
         )?;
-        let musig = Musig::setup(musig_keys, vec![musig_keys.public_key(), their_public_key])?;
-
-        let internal_key = musig.agg_pk();
-        let musig = musig
-            .xonly_tweak_add(&Musig::convert_scalar_be(
-                &swap_rescue_details
-                    .swap_tree
-                    .build()?
-                    .finalize(
-                        &Secp256k1::new(),
-                        bitcoin::XOnlyPublicKey::from_slice(&internal_key.serialize())?,
-                    )?
-                    .tap_tweak()
-                    .to_scalar()
-                    .to_be_bytes(),
-            )?)?
-            .message(message.as_slice().try_into()?);
+        let musig = match pending_rescue
+            .session
+            .restore(musig_keys, &self.nonce_guard())
+            .await?
+        {
+            RestoredSession::WithNonce(musig) => musig,
+            _ => return Err(anyhow::anyhow!("pending rescue has no fresh nonce")),
+        };
         let aggregate_key = musig.agg_pk();
 
         let musig = musig
-            .dangerous_set_nonce(
-                hex::decode(&pending_rescue.sec_nonce)?.as_slice(),
-                hex::decode(&pending_rescue.pub_nonce)?.as_slice(),
-            )?
             .aggregate_nonces(vec![(
                 their_public_key,
                 Musig::convert_pub_nonce(pub_nonce)?,
@@ -525,6 +524,10 @@ impl AssetRescue {
         }
     }
 
+    fn nonce_guard(&self) -> CacheNonceGuard {
+        CacheNonceGuard::new(self.cache.clone(), CACHE_TTL)
+    }
+
     fn cache_field_swap(id: &str) -> String {
         format!("swap:{}", id)
     }
@@ -608,6 +611,10 @@ mod tests {
             self.id.clone()
         }
 
+        fn pair(&self) -> String {
+            "L-BTC/BTC".to_string()
+        }
+
         fn status(&self) -> SwapUpdate {
             self.status
         }
@@ -620,6 +627,10 @@ mod tests {
             Ok("L-BTC".to_string())
         }
 
+        fn claim_timeout_block_height(&self) -> Result<u64> {
+            Ok(0)
+        }
+
         async fn claim_details(
             &self,
             _wallet: &Arc<dyn Wallet + Send + Sync>,
@@ -632,6 +643,10 @@ mod tests {
             Ok("L-BTC".to_string())
         }
 
+        fn refund_timeout_block_height(&self) -> Result<u64> {
+            Ok(0)
+        }
+
         async fn refund_details(
             &self,
             _wallet: &Arc<dyn Wallet + Send + Sync>,
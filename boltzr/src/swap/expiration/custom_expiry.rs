@@ -126,6 +126,7 @@ impl ExpirationChecker for CustomExpirationChecker {
                     failure_reason: Some(failure_reason.to_string()),
                     ..Default::default()
                 },
+                event_id: None,
             })?;
         }
 
@@ -139,7 +140,7 @@ mod test {
     use crate::db::helpers::QueryResponse;
     use crate::db::helpers::referral::{ReferralCondition, ReferralHelper};
     use crate::db::helpers::swap::{SwapCondition, SwapHelper, SwapNullableCondition};
-    use crate::db::models::Swap;
+    use crate::db::models::{ReferralCredentials, Swap};
     use crate::db::schema::swaps::dsl::swaps;
     use diesel::QueryDsl;
     use diesel::debug_query;
@@ -176,6 +177,7 @@ mod test {
 
         impl ReferralHelper for ReferralHelper {
             fn get_all(&self, condition: ReferralCondition) -> QueryResponse<Vec<Referral>>;
+            fn get_credentials(&self, api_key: &str) -> QueryResponse<Option<ReferralCredentials>>;
         }
     }
 
@@ -290,6 +292,7 @@ mod test {
                     failure_reason: Some(FAILURE_REASON_CUSTOM_EXPIRATION.to_string()),
                     ..Default::default()
                 },
+                event_id: None,
             }
         );
     }
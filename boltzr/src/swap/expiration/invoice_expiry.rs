@@ -119,6 +119,7 @@ impl ExpirationChecker for InvoiceExpirationChecker {
                     failure_reason: Some(failure_reason.to_string()),
                     ..Default::default()
                 },
+                event_id: None,
             })?;
         }
 
@@ -277,6 +278,7 @@ mod test {
                     failure_reason: Some(FAILURE_REASON_EXPIRED_INVOICE.to_string()),
                     ..Default::default()
                 },
+                event_id: None,
             }
         );
     }
@@ -1,17 +1,25 @@
 use super::PairConfig;
 use super::timeout_delta::TimeoutDeltaProvider;
 use crate::api::ws::types::SwapStatus;
+use crate::chain::bumper::{
+    Bumper, ClaimTransactionHandler, Escalation, EscalationConfig, RefundTransactionHandler,
+};
 use crate::chain::mrh_watcher::MrhWatcher;
 use crate::chain::types::Type;
 use crate::currencies::{Currencies, Currency};
 use crate::db::Pool;
 use crate::db::helpers::chain_swap::{ChainSwapHelper, ChainSwapHelperDatabase};
 use crate::db::helpers::chain_tip::{ChainTipHelper, ChainTipHelperDatabase};
+use crate::db::helpers::claim_transaction::{
+    ClaimStatus, ClaimTransactionHelper, ClaimTransactionHelperDatabase,
+};
 use crate::db::helpers::referral::ReferralHelperDatabase;
+use crate::db::helpers::refund_transaction::RefundTransactionHelperDatabase;
 use crate::db::helpers::reverse_swap::{ReverseSwapHelper, ReverseSwapHelperDatabase};
 use crate::db::helpers::script_pubkey::ScriptPubKeyHelperDatabase;
 use crate::db::helpers::swap::{SwapHelper, SwapHelperDatabase};
-use crate::db::models::{SomeSwap, SwapType};
+use crate::db::models::{PendingClaim, SomeSwap, SwapType};
+use crate::notifications::NotificationClient;
 use crate::swap::AssetRescueConfig;
 use crate::swap::asset_rescue::AssetRescue;
 use crate::swap::expiration::{CustomExpirationChecker, InvoiceExpirationChecker, Scheduler};
@@ -27,7 +35,7 @@ use boltz_core::wrapper::Transaction;
 use diesel::ExpressionMethods;
 use futures_util::future::try_join_all;
 use futures_util::{StreamExt, TryStreamExt};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tokio_util::sync::CancellationToken;
@@ -61,6 +69,14 @@ pub trait SwapManager {
 
     async fn claim_batch(&self, swap_ids: Vec<String>) -> Result<(Transaction, u64)>;
 
+    /// Tracks a broadcast claim transaction so that the bumper can replace it when fees rise
+    fn claim_batch_broadcasted(
+        &self,
+        symbol: &str,
+        swap_ids: &[String],
+        transaction_id: &str,
+    ) -> Result<()>;
+
     fn listen_to_updates(&self) -> broadcast::Receiver<SwapStatus>;
 
     async fn rescan_chains(
@@ -86,13 +102,18 @@ pub struct Manager {
     swap_repo: Arc<dyn SwapHelper + Sync + Send>,
     chain_swap_repo: Arc<dyn ChainSwapHelper + Sync + Send>,
     reverse_swap_repo: Arc<dyn ReverseSwapHelper + Sync + Send>,
+    claim_transaction_repo: Arc<dyn ClaimTransactionHelper + Sync + Send>,
     timeout_delta_provider: Arc<TimeoutDeltaProvider>,
 
     utxo_nursery: UtxoNursery,
     asset_rescue: Arc<AssetRescue>,
+
+    fee_escalation: HashMap<String, EscalationConfig>,
+    notification_client: Option<Arc<dyn NotificationClient + Send + Sync>>,
 }
 
 impl Manager {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cancellation_token: CancellationToken,
         asset_rescue_config: Option<AssetRescueConfig>,
@@ -101,6 +122,8 @@ impl Manager {
         pool: Pool,
         network: crate::wallet::Network,
         pairs: &[PairConfig],
+        fee_escalation: HashMap<String, EscalationConfig>,
+        notification_client: Option<Arc<dyn NotificationClient + Send + Sync>>,
     ) -> Result<Self> {
         let (update_tx, _) = broadcast::channel::<SwapStatus>(128);
 
@@ -116,6 +139,7 @@ impl Manager {
             swap_repo: swap_repo.clone(),
             chain_swap_repo: chain_swap_repo.clone(),
             reverse_swap_repo: Arc::new(ReverseSwapHelperDatabase::new(pool.clone())),
+            claim_transaction_repo: Arc::new(ClaimTransactionHelperDatabase::new(pool.clone())),
             timeout_delta_provider: Arc::new(TimeoutDeltaProvider::new(&currencies, pairs)?),
             utxo_nursery: UtxoNursery::new(
                 cancellation_token,
@@ -134,6 +158,8 @@ impl Manager {
                 swap_repo,
                 chain_swap_repo,
             )),
+            fee_escalation,
+            notification_client,
         })
     }
 
@@ -161,6 +187,12 @@ impl Manager {
         );
         let nursery = self.utxo_nursery.clone();
 
+        for bumper in self.create_bumpers() {
+            tokio::spawn(async move {
+                bumper.start().await;
+            });
+        }
+
         let currencies = self.currencies.clone();
 
         try_join_all([
@@ -180,6 +212,48 @@ impl Manager {
         .await
         .unwrap();
     }
+
+    fn create_bumpers(&self) -> Vec<Bumper> {
+        self.currencies
+            .values()
+            .filter_map(|currency| {
+                let chain = currency.chain.clone()?;
+                let wallet = currency.wallet.clone()?;
+
+                let escalation = self.fee_escalation.get(&chain.symbol());
+                let bumper = Bumper::new(
+                    self.cancellation_token.clone(),
+                    chain.clone(),
+                    vec![
+                        Arc::new(RefundTransactionHandler::new(
+                            RefundTransactionHelperDatabase::new(self.pool.clone()),
+                            ReverseSwapHelperDatabase::new(self.pool.clone()),
+                            ChainSwapHelperDatabase::new(self.pool.clone()),
+                            wallet.clone(),
+                            chain.clone(),
+                        )),
+                        Arc::new(ClaimTransactionHandler::new(
+                            ClaimTransactionHelperDatabase::new(self.pool.clone()),
+                            SwapHelperDatabase::new(self.pool.clone()),
+                            ChainSwapHelperDatabase::new(self.pool.clone()),
+                            wallet,
+                            chain,
+                            self.update_tx.clone(),
+                        )),
+                    ],
+                );
+
+                Some(match escalation {
+                    Some(config) => bumper.with_escalation(
+                        Escalation::new(config),
+                        self.timeout_delta_provider.clone(),
+                        self.notification_client.clone(),
+                    ),
+                    None => bumper,
+                })
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -319,7 +393,7 @@ impl SwapManager for Manager {
                         fee: fee.into(),
                     });
 
-                Ok(boltz_core::wrapper::construct_tx(params)?)
+                Ok(boltz_core::wrapper::construct_tx(params).await?)
             }
             Type::Elements => {
                 let inputs = inputs
@@ -338,11 +412,43 @@ impl SwapManager for Manager {
                         fee: fee.into(),
                     });
 
-                Ok(boltz_core::wrapper::construct_tx(params)?)
+                Ok(boltz_core::wrapper::construct_tx(params).await?)
             }
         }
     }
 
+    #[instrument(name = "SwapManager::claim_batch_broadcasted", skip_all)]
+    fn claim_batch_broadcasted(
+        &self,
+        symbol: &str,
+        swap_ids: &[String],
+        transaction_id: &str,
+    ) -> Result<()> {
+        let client = self
+            .get_currency(symbol)
+            .and_then(|currency| currency.chain)
+            .ok_or_else(|| anyhow!("chain client for {} not found", symbol))?;
+
+        // Only Bitcoin claims are bumped
+        if client.chain_type() != Type::Bitcoin {
+            return Ok(());
+        }
+
+        self.claim_transaction_repo.insert_pending(
+            &swap_ids
+                .iter()
+                .map(|id| PendingClaim {
+                    swap_id: id.clone(),
+                    symbol: symbol.to_string(),
+                    transaction_id: transaction_id.to_string(),
+                    status: ClaimStatus::Pending.into(),
+                })
+                .collect::<Vec<_>>(),
+        )?;
+
+        Ok(())
+    }
+
     async fn rescan_chains(
         &self,
         options: Option<Vec<RescanChainOptions>>,
@@ -528,6 +634,12 @@ pub mod test {
                 swap_type: SwapType,
             ) -> Result<(u64, u64)>;
             async fn claim_batch(&self, swap_ids: Vec<String>) -> anyhow::Result<(boltz_core::wrapper::Transaction, u64)>;
+            fn claim_batch_broadcasted(
+                &self,
+                symbol: &str,
+                swap_ids: &[String],
+                transaction_id: &str,
+            ) -> anyhow::Result<()>;
             fn get_asset_rescue(&self) -> Arc<AssetRescue>;
             fn listen_to_updates(&self) -> tokio::sync::broadcast::Receiver<SwapStatus>;
             async fn rescan_chains(
@@ -567,6 +679,7 @@ pub mod test {
             swap_repo: Arc::new(SwapHelperDatabase::new(pool.clone())),
             chain_swap_repo: Arc::new(ChainSwapHelperDatabase::new(pool.clone())),
             reverse_swap_repo: Arc::new(ReverseSwapHelperDatabase::new(pool.clone())),
+            claim_transaction_repo: Arc::new(ClaimTransactionHelperDatabase::new(pool.clone())),
             timeout_delta_provider: Arc::new(timeout_provider),
             utxo_nursery: UtxoNursery::new(
                 cancellation_token.clone(),
@@ -585,6 +698,8 @@ pub mod test {
                 Arc::new(SwapHelperDatabase::new(pool.clone())),
                 Arc::new(ChainSwapHelperDatabase::new(pool.clone())),
             )),
+            fee_escalation: HashMap::new(),
+            notification_client: None,
         };
 
         let result = manager.get_timeouts("L-BTC", "BTC", SwapType::Reverse);
@@ -2,15 +2,18 @@ mod asset_rescue;
 mod expiration;
 mod filters;
 pub mod manager;
+mod nonce_guard;
 mod status;
 mod timeout_delta;
 mod tx_check;
 mod utxo_nursery;
 
 pub use status::*;
-pub use timeout_delta::PairConfig;
+pub use timeout_delta::{PairConfig, TimeoutDeltaProvider};
 pub use utxo_nursery::*;
 
 #[cfg(test)]
 pub use asset_rescue::AssetRescue;
 pub use asset_rescue::AssetRescueConfig;
+#[cfg(test)]
+pub use timeout_delta::PairTimeoutBlockDelta;
@@ -0,0 +1,69 @@
+use boltz_cache::Cache;
+use boltz_core::musig::{NonceGuard, NonceGuardError, NonceMarker};
+
+const CACHE_KEY: &str = "musig_nonce";
+
+/// Guards the secret nonces of MuSig2 sessions that are persisted between
+/// requests; the markers live in the cache so that every instance sees them
+#[derive(Debug, Clone)]
+pub struct CacheNonceGuard {
+    cache: Cache,
+    ttl: u64,
+}
+
+impl CacheNonceGuard {
+    /// The `ttl` should match the one of the persisted session
+    pub fn new(cache: Cache, ttl: u64) -> Self {
+        Self { cache, ttl }
+    }
+
+    fn cache_field(marker: &NonceMarker) -> String {
+        format!("marker:{marker}")
+    }
+}
+
+impl NonceGuard for CacheNonceGuard {
+    async fn arm(&self, marker: &NonceMarker) -> Result<(), NonceGuardError> {
+        self.cache
+            .set(CACHE_KEY, &Self::cache_field(marker), &true, Some(self.ttl))
+            .await
+            .map_err(|err| NonceGuardError(err.to_string()))
+    }
+
+    async fn consume(&self, marker: &NonceMarker) -> Result<bool, NonceGuardError> {
+        self.cache
+            .take::<bool>(CACHE_KEY, &Self::cache_field(marker))
+            .await
+            .map(|armed| armed.is_some())
+            .map_err(|err| NonceGuardError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use boltz_cache::MemCache;
+    use boltz_core::Musig;
+
+    fn marker() -> NonceMarker {
+        let key = Musig::convert_keypair([1; 32]).unwrap();
+        let musig = Musig::setup(key, vec![key.public_key()])
+            .unwrap()
+            .message([2; 32])
+            .generate_nonce(&mut Musig::rng());
+
+        NonceMarker::from(musig.pub_nonce())
+    }
+
+    #[tokio::test]
+    async fn test_consume_once() {
+        let guard = CacheNonceGuard::new(Cache::Memory(MemCache::new()), 60);
+        let marker = marker();
+
+        assert!(!guard.consume(&marker).await.unwrap());
+
+        guard.arm(&marker).await.unwrap();
+        assert!(guard.consume(&marker).await.unwrap());
+        assert!(!guard.consume(&marker).await.unwrap());
+    }
+}
@@ -11,6 +11,8 @@ use tracing::{debug, instrument};
 
 const LIGHTNING_BUFFER: u64 = 15;
 const CROSS_CHAIN_BUFFER_FACTOR: f64 = 0.25;
+/// Timeout delta of the lockup of the user of a Chain Swap relative to the one of the server
+const CHAIN_RECEIVING_FACTOR: f64 = 1.5;
 
 /// Map of symbol to block time in minutes
 static BLOCK_TIMES: LazyLock<RwLock<HashMap<String, f64>>> = LazyLock::new(|| {
@@ -141,6 +143,50 @@ impl TimeoutDeltaProvider {
         })
     }
 
+    /// Timeout delta in blocks of `symbol` for swaps of `swap_type` on `pair`
+    pub fn get_block_delta(&self, pair: &str, symbol: &str, swap_type: SwapType) -> Result<u64> {
+        let timeouts = match self.timeout_deltas.get(pair) {
+            Some(timeouts) => timeouts,
+            None => return Err(anyhow!("no timeouts for pair: {}", pair)),
+        };
+        let split = split_pair(pair)?;
+
+        let deltas = if split.base == symbol {
+            &timeouts.base
+        } else if split.quote == symbol {
+            &timeouts.quote
+        } else {
+            return Err(anyhow!("{} is not part of pair {}", symbol, pair));
+        };
+
+        Ok(match swap_type {
+            SwapType::Submarine => deltas.swap_taproot,
+            SwapType::Reverse => deltas.reverse,
+            SwapType::Chain => deltas.chain,
+        })
+    }
+
+    /// Blocks of `sending_symbol` by which the lockup of the user on `receiving_symbol` of a
+    /// Chain Swap on `pair` times out after the lockup of the server on `sending_symbol`
+    pub fn get_chain_timeout_offset(
+        &self,
+        pair: &str,
+        sending_symbol: &str,
+        receiving_symbol: &str,
+    ) -> Result<u64> {
+        let sending_delta = self.get_block_delta(pair, sending_symbol, SwapType::Chain)?;
+
+        // Same as the backend does when creating the lockup the user sends us
+        let minutes =
+            sending_delta as f64 * CHAIN_RECEIVING_FACTOR * Self::get_block_time(sending_symbol)?;
+        let receiving_delta = (minutes / Self::get_block_time(receiving_symbol)?).ceil() as u64;
+
+        Ok(
+            Self::convert_blocks(receiving_symbol, sending_symbol, receiving_delta)?
+                .saturating_sub(sending_delta),
+        )
+    }
+
     fn convert_blocks(from_symbol: &str, to_symbol: &str, blocks: u64) -> Result<u64> {
         let minutes = blocks as f64 * Self::get_block_time(from_symbol)?;
         Ok((minutes / Self::get_block_time(to_symbol)?).ceil() as u64)
@@ -315,6 +361,101 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[rstest]
+    #[case("L-BTC", SwapType::Chain, 10)]
+    #[case("L-BTC", SwapType::Reverse, 20)]
+    #[case("L-BTC", SwapType::Submarine, 50)]
+    #[case("BTC", SwapType::Chain, 1)]
+    #[case("BTC", SwapType::Reverse, 2)]
+    #[case("BTC", SwapType::Submarine, 5)]
+    fn test_get_block_delta(
+        #[case] symbol: &str,
+        #[case] swap_type: SwapType,
+        #[case] expected: u64,
+    ) {
+        let pair_config = PairConfig {
+            base: "L-BTC".to_string(),
+            quote: "BTC".to_string(),
+            timeout_delta: PairTimeoutBlockDelta {
+                chain: 10,
+                reverse: 20,
+                swap_minimal: 30,
+                swap_maximal: 40,
+                swap_taproot: 50,
+            },
+        };
+
+        let provider = TimeoutDeltaProvider::new(&empty_currencies(), &[pair_config]).unwrap();
+        assert_eq!(
+            provider
+                .get_block_delta("L-BTC/BTC", symbol, swap_type)
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[rstest]
+    // BTC has a Chain Swap timeout delta of 2 blocks and L-BTC one of 20 blocks
+    #[case("BTC", "L-BTC", 1)]
+    #[case("L-BTC", "BTC", 10)]
+    fn test_get_chain_timeout_offset(
+        #[case] sending_symbol: &str,
+        #[case] receiving_symbol: &str,
+        #[case] expected: u64,
+    ) {
+        let pair_config = PairConfig {
+            base: "L-BTC".to_string(),
+            quote: "BTC".to_string(),
+            timeout_delta: PairTimeoutBlockDelta {
+                chain: 20,
+                reverse: 20,
+                swap_minimal: 30,
+                swap_maximal: 40,
+                swap_taproot: 50,
+            },
+        };
+
+        let provider = TimeoutDeltaProvider::new(&empty_currencies(), &[pair_config]).unwrap();
+        assert_eq!(
+            provider
+                .get_chain_timeout_offset("L-BTC/BTC", sending_symbol, receiving_symbol)
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_get_chain_timeout_offset_invalid_symbol() {
+        let pair_config = PairConfig {
+            base: "L-BTC".to_string(),
+            quote: "BTC".to_string(),
+            timeout_delta: PairTimeoutBlockDelta {
+                chain: 20,
+                reverse: 20,
+                swap_minimal: 30,
+                swap_maximal: 40,
+                swap_taproot: 50,
+            },
+        };
+
+        let provider = TimeoutDeltaProvider::new(&empty_currencies(), &[pair_config]).unwrap();
+        assert!(
+            provider
+                .get_chain_timeout_offset("L-BTC/BTC", "BTC", "INVALID")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_get_block_delta_invalid_pair() {
+        let provider = TimeoutDeltaProvider::new(&empty_currencies(), &[]).unwrap();
+        assert!(
+            provider
+                .get_block_delta("L-BTC/BTC", "BTC", SwapType::Chain)
+                .is_err()
+        );
+    }
+
     #[rstest]
     #[case("BTC", "L-BTC", 10, 100)]
     #[case("L-BTC", "BTC", 100, 10)]
@@ -482,6 +623,9 @@ mod tests {
                     ),
                 }]),
                 quoters: None,
+                indexer: None,
+                nonce_manager: None,
+                remote_signer: None,
             },
         )
         .await
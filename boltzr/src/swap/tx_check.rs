@@ -18,7 +18,14 @@ use tracing::info;
 
 const TX_CHUNK_SIZE: usize = 512;
 
-type RelevantSwaps = HashMap<Transaction, Vec<String>>;
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TxRelevance {
+    pub swap_ids: Vec<String>,
+    /// Script pubkeys of the outputs that pay swaps
+    pub swap_outputs: Vec<Vec<u8>>,
+}
+
+type RelevantSwaps = HashMap<Transaction, TxRelevance>;
 
 #[derive(Clone)]
 pub struct TxChecker {
@@ -91,9 +98,11 @@ impl TxChecker {
                         tx.txid_hex()
                     );
 
-                    map.entry((**tx).clone())
-                        .or_default()
-                        .push(pubkey.swap_id.clone());
+                    let relevance = map.entry((**tx).clone()).or_default();
+                    relevance.swap_ids.push(pubkey.swap_id.clone());
+                    if !relevance.swap_outputs.contains(&pubkey.script_pubkey) {
+                        relevance.swap_outputs.push(pubkey.script_pubkey.clone());
+                    }
                 }
             }
         }
@@ -165,7 +174,10 @@ impl TxChecker {
                     tx.txid_hex()
                 );
 
-                map.entry((**tx).clone()).or_default().push(swap.id());
+                map.entry((**tx).clone())
+                    .or_default()
+                    .swap_ids
+                    .push(swap.id());
             }
         }
 
@@ -319,7 +331,13 @@ mod test {
             .check("BTC", Transactions::Single(tx.clone()), true)
             .unwrap();
         assert_eq!(result.len(), 1);
-        assert_eq!(result.get(&tx), Some(&vec![swap_id.to_string()]));
+        assert_eq!(
+            result.get(&tx),
+            Some(&TxRelevance {
+                swap_ids: vec![swap_id.to_string()],
+                swap_outputs: vec![tx.output_script_pubkeys()[0].clone()],
+            })
+        );
     }
 
     #[test]
@@ -371,7 +389,10 @@ mod test {
             .check("BTC", Transactions::Single(tx.clone()), false)
             .unwrap();
         assert_eq!(result.len(), 1);
-        assert_eq!(result.get(&tx), Some(&vec![swap_id.to_string()]));
+        assert_eq!(
+            result.get(&tx).map(|r| &r.swap_ids),
+            Some(&vec![swap_id.to_string()])
+        );
     }
 
     #[test]
@@ -442,7 +463,10 @@ mod test {
             .check("BTC", Transactions::Single(tx.clone()), true)
             .unwrap();
         assert_eq!(result.len(), 1);
-        assert_eq!(result.get(&tx), Some(&vec![swap_id.to_string()]));
+        assert_eq!(
+            result.get(&tx).map(|r| &r.swap_ids),
+            Some(&vec![swap_id.to_string()])
+        );
     }
 
     #[test]
@@ -497,7 +521,7 @@ mod test {
             .check("BTC", Transactions::Single(tx.clone()), false)
             .unwrap();
         assert_eq!(result.len(), 1);
-        let swap_ids = result.get(&tx).unwrap();
+        let swap_ids = &result.get(&tx).unwrap().swap_ids;
         assert_eq!(swap_ids.len(), 2);
         assert!(swap_ids.contains(&swap_id_1.to_string()));
         assert!(swap_ids.contains(&swap_id_2.to_string()));
@@ -590,7 +614,7 @@ mod test {
             .check("BTC", Transactions::Single(tx.clone()), true)
             .unwrap();
         assert_eq!(result.len(), 1);
-        let mut swap_ids = result.get(&tx).unwrap().clone();
+        let mut swap_ids = result.get(&tx).unwrap().swap_ids.clone();
         swap_ids.sort();
 
         let mut expected = vec![
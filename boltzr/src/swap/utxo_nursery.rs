@@ -170,25 +170,32 @@ impl UtxoNursery {
         let tx_vec: Vec<_> = relevant_swaps.keys().cloned().collect();
 
         let zero_conf_safe = stream::iter(tx_vec.iter().cloned())
-            .map(|tx| async move {
-                if confirmed {
-                    TxStatus::Confirmed
-                } else {
-                    match chain_client.zero_conf_safe(&tx).await {
-                        Ok(safe) => {
-                            if safe {
-                                TxStatus::ZeroConfSafe
-                            } else {
+            .map(|tx| {
+                let swap_outputs = relevant_swaps
+                    .get(&tx)
+                    .map(|relevance| relevance.swap_outputs.clone())
+                    .unwrap_or_default();
+
+                async move {
+                    if confirmed {
+                        TxStatus::Confirmed
+                    } else {
+                        match chain_client.zero_conf_safe(&tx, &swap_outputs).await {
+                            Ok(safe) => {
+                                if safe {
+                                    TxStatus::ZeroConfSafe
+                                } else {
+                                    TxStatus::NotSafe
+                                }
+                            }
+                            Err(e) => {
+                                error!(
+                                    "0-conf safety check for {} transaction failed: {}",
+                                    symbol, e
+                                );
                                 TxStatus::NotSafe
                             }
                         }
-                        Err(e) => {
-                            error!(
-                                "0-conf safety check for {} transaction failed: {}",
-                                symbol, e
-                            );
-                            TxStatus::NotSafe
-                        }
                     }
                 }
             })
@@ -197,13 +204,13 @@ impl UtxoNursery {
             .await;
 
         for (tx, status) in tx_vec.into_iter().zip(zero_conf_safe) {
-            let swap_ids = relevant_swaps.remove(&tx).context("swap ids not found")?;
+            let relevance = relevant_swaps.remove(&tx).context("swap ids not found")?;
 
             if let Err(e) = self.relevant_txs.send(RelevantTx {
                 symbol: symbol.to_string(),
                 status,
                 tx,
-                swaps: swap_ids,
+                swaps: relevance.swap_ids,
             }) {
                 error!(
                     "UTXO nursery failed to send relevant {} transaction: {}",
@@ -8,13 +8,15 @@ use std::fmt::Debug;
 use std::net::IpAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{cmp, fmt};
 use tokio::sync::broadcast::{Receiver, Sender};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::db::models::WebHookState;
+use crate::webhook::circuit_breaker::CircuitBreaker;
+use crate::webhook::retry::RetryPolicy;
 use crate::webhook::types::WebHookCallParams;
 use crate::webhook::{WebHookCallData, WebHookEvent};
 
@@ -23,12 +25,36 @@ const DEFAULT_MAX_RETRIES: u64 = 5;
 const DEFAULT_RETRY_INTERVAL: u64 = 60;
 
 const MAX_URL_LENGTH: usize = 250;
+const MAX_ATTEMPT_HISTORY: usize = 32;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CallResult {
     Success,
     Failed,
     NotIncluded,
+    /// The circuit breaker of the host is open and the call was not attempted
+    Paused,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CallAttempt {
+    pub timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    pub error: String,
+}
+
+impl CallAttempt {
+    fn new(status: Option<u16>, error: String) -> Self {
+        Self {
+            status,
+            error,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -62,8 +88,16 @@ pub struct Config {
     pub max_retries: Option<u64>,
     #[serde(rename = "retryInterval")]
     pub retry_interval: Option<u64>,
+    #[serde(rename = "maxRetryInterval")]
+    pub max_retry_interval: Option<u64>,
+    #[serde(rename = "retryBackoffMultiplier")]
+    pub retry_backoff_multiplier: Option<f64>,
+    #[serde(rename = "retryJitter")]
+    pub retry_jitter: Option<f64>,
     #[serde(rename = "blockList")]
     pub block_list: Option<Vec<String>>,
+    #[serde(rename = "circuitBreaker")]
+    pub circuit_breaker: Option<crate::webhook::circuit_breaker::Config>,
 }
 
 pub trait Hook {
@@ -71,6 +105,7 @@ pub trait Hook {
 
     fn id(&self) -> Self::Id;
     fn url(&self) -> String;
+    fn secret(&self) -> Option<String>;
 }
 
 pub trait HookState<H: Hook> {
@@ -79,6 +114,13 @@ pub trait HookState<H: Hook> {
     fn get_by_state(&self, state: WebHookState) -> Result<Vec<H>>;
     fn get_retry_data(&self, hook: &H) -> Result<Option<WebHookCallData>>;
     fn set_state(&self, hook: &H, state: WebHookState) -> Result<()>;
+
+    fn add_dead_letter(
+        &self,
+        hook: &H,
+        params: &WebHookCallData,
+        attempts: &[CallAttempt],
+    ) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -91,12 +133,15 @@ where
     hook_state: Arc<S>,
     cancellation_token: CancellationToken,
     retry_count: Arc<DashMap<H::Id, u64>>,
+    retry_skips: Arc<DashMap<H::Id, u64>>,
+    attempts: Arc<DashMap<H::Id, Vec<CallAttempt>>>,
+    circuit_breaker: Arc<CircuitBreaker>,
     successful_calls: Sender<(H, Vec<u8>)>,
 
     allow_insecure: bool,
     request_timeout: Duration,
     max_retries: u64,
-    retry_interval: Duration,
+    retry_policy: RetryPolicy,
     block_list: Vec<String>,
 }
 
@@ -119,20 +164,29 @@ where
         let timeout = config.request_timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT);
         trace!("{} WebHook call timeout: {}s", name, timeout);
 
+        let retry_policy = RetryPolicy::new(
+            Duration::from_secs(config.retry_interval.unwrap_or(DEFAULT_RETRY_INTERVAL)),
+            config.max_retry_interval.map(Duration::from_secs),
+            config.retry_backoff_multiplier,
+            config.retry_jitter,
+        );
+        trace!("{} WebHook retry policy: {:?}", name, retry_policy);
+
         let (tx, _) = tokio::sync::broadcast::channel(256);
 
         Self {
+            circuit_breaker: Arc::new(CircuitBreaker::new(name.clone(), config.circuit_breaker)),
             name,
+            retry_policy,
             max_retries,
             allow_insecure,
             cancellation_token,
             successful_calls: tx,
             hook_state: Arc::new(hook_state),
             retry_count: Arc::new(DashMap::new()),
+            retry_skips: Arc::new(DashMap::new()),
+            attempts: Arc::new(DashMap::new()),
             request_timeout: Duration::from_secs(timeout),
-            retry_interval: Duration::from_secs(
-                config.retry_interval.unwrap_or(DEFAULT_RETRY_INTERVAL),
-            ),
             block_list: config.block_list.unwrap_or_default(),
         }
     }
@@ -154,12 +208,13 @@ where
 
         debug!(
             "Retrying failed {} WebHook calls every: {:#?}",
-            self.name, self.retry_interval
+            self.name,
+            self.retry_policy.base()
         );
 
         loop {
             tokio::select! {
-                _ = tokio::time::sleep(self.retry_interval) => {
+                _ = tokio::time::sleep(self.retry_policy.base()) => {
                     retry().await;
                 }
                 _ = self.cancellation_token.cancelled() => {
@@ -211,9 +266,35 @@ where
                 hook.id(),
                 err
             );
+            self.record_attempt(&hook, CallAttempt::new(None, err.to_string()));
             return Ok(CallResult::Failed);
         }
 
+        if let Some(host) = host_of(&hook.url())
+            && !self.circuit_breaker.allow(&host)
+        {
+            debug!(
+                "Not calling {} WebHook for {} because circuit breaker of {} is {:?}",
+                self.name,
+                hook.id(),
+                host,
+                self.circuit_breaker.state(&host)
+            );
+
+            #[cfg(feature = "metrics")]
+            metrics::counter!(crate::metrics::WEBHOOK_CALL_COUNT, "status" => "paused", "type" => self.name.clone())
+                .increment(1);
+
+            // The call fails fast without a request; it is recorded like a failed one
+            // so that the retry loop picks it up and eventually gives up on the host
+            self.record_attempt(
+                &hook,
+                CallAttempt::new(None, format!("circuit breaker of {} is open", host)),
+            );
+            self.hook_state.set_state(&hook, WebHookState::Failed)?;
+            return Ok(CallResult::Paused);
+        }
+
         debug!(
             "Calling {} WebHook for {}: {}",
             self.name,
@@ -221,43 +302,7 @@ where
             hook.url(),
         );
 
-        let client = reqwest::Client::builder()
-            .connect_timeout(self.request_timeout)
-            .timeout(self.request_timeout)
-            .dns_resolver(Arc::new(super::resolver::SsrfGuardResolver::new(
-                self.allow_insecure,
-            )))
-            .redirect(super::resolver::build_redirect_policy(
-                self.allow_insecure,
-                self.block_list.clone(),
-            ))
-            .build()
-            .unwrap();
-
-        let res = match client
-            .post(hook.url())
-            .json(&WebHookCallParams {
-                event: match data {
-                    WebHookCallData::SwapUpdate(_) => WebHookEvent::SwapUpdate,
-                    WebHookCallData::InvoiceRequest(_) => WebHookEvent::InvoiceRequest,
-                },
-                data,
-            })
-            .send()
-            .await
-        {
-            Ok(res) => {
-                let status = res.status();
-                if status.is_success() {
-                    res.bytes().await.map_err(|err| anyhow::anyhow!(err))
-                } else {
-                    Err(anyhow::anyhow!("HTTP {}", status.as_u16()))
-                }
-            }
-            Err(err) => Err(anyhow::anyhow!(err)),
-        };
-
-        match res {
+        match self.send(&hook, data).await {
             Ok(res) => {
                 info!("Called {} WebHook for {}", self.name, hook.id());
 
@@ -266,10 +311,12 @@ where
                     .increment(1);
 
                 self.retry_count.remove(&hook.id());
+                self.retry_skips.remove(&hook.id());
+                self.attempts.remove(&hook.id());
                 self.hook_state.set_state(&hook, WebHookState::Ok)?;
 
                 if self.successful_calls.receiver_count() > 0
-                    && let Err(send_err) = self.successful_calls.send((hook, res.to_vec()))
+                    && let Err(send_err) = self.successful_calls.send((hook, res))
                 {
                     warn!(
                         "Failed to send successful WebHook call to channel: {}",
@@ -279,18 +326,19 @@ where
 
                 Ok(CallResult::Success)
             }
-            Err(err) => {
+            Err(attempt) => {
                 warn!(
                     "{} WebHook request for {} failed: {}",
                     self.name,
                     hook.id(),
-                    err
+                    attempt.error
                 );
 
                 #[cfg(feature = "metrics")]
                 metrics::counter!(crate::metrics::WEBHOOK_CALL_COUNT, "status" => "failed", "type" => self.name.clone())
                     .increment(1);
 
+                self.record_attempt(&hook, attempt);
                 self.hook_state.set_state(&hook, WebHookState::Failed)?;
 
                 Ok(CallResult::Failed)
@@ -298,6 +346,144 @@ where
         }
     }
 
+    /// Sends a WebHook call outside of the retry loop; a successful call resets the hook state
+    #[instrument(name = "Caller::replay", skip(self, hook, data))]
+    pub async fn replay(&self, hook: H, data: WebHookCallData) -> Result<(), CallAttempt> {
+        if let Err(err) = check_ip(&hook.url(), self.allow_insecure).await {
+            return Err(CallAttempt::new(None, err.to_string()));
+        }
+
+        debug!(
+            "Replaying {} WebHook call for {}: {}",
+            self.name,
+            hook.id(),
+            hook.url(),
+        );
+
+        let res = self.send(&hook, data).await.map(|_| ());
+
+        if res.is_ok() {
+            self.retry_count.remove(&hook.id());
+            self.retry_skips.remove(&hook.id());
+            self.attempts.remove(&hook.id());
+
+            if let Err(err) = self.hook_state.set_state(&hook, WebHookState::Ok) {
+                error!(
+                    "Could not update state of {} WebHook for {} after replay: {}",
+                    self.name,
+                    hook.id(),
+                    err
+                );
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::counter!(
+            crate::metrics::WEBHOOK_CALL_COUNT,
+            "status" => if res.is_ok() { "replayed" } else { "replay_failed" },
+            "type" => self.name.clone()
+        )
+        .increment(1);
+
+        res
+    }
+
+    async fn send(&self, hook: &H, data: WebHookCallData) -> Result<Vec<u8>, CallAttempt> {
+        let res = self.send_request(hook, data).await;
+
+        if let Some(host) = host_of(&hook.url()) {
+            match res {
+                Ok(_) => self.circuit_breaker.record_success(&host),
+                Err(_) => self.circuit_breaker.record_failure(&host),
+            }
+        }
+
+        res
+    }
+
+    async fn send_request(&self, hook: &H, data: WebHookCallData) -> Result<Vec<u8>, CallAttempt> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(self.request_timeout)
+            .timeout(self.request_timeout)
+            .dns_resolver(Arc::new(super::resolver::SsrfGuardResolver::new(
+                self.allow_insecure,
+            )))
+            .redirect(super::resolver::build_redirect_policy(
+                self.allow_insecure,
+                self.block_list.clone(),
+            ))
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_vec(&WebHookCallParams {
+            event: match data {
+                WebHookCallData::SwapUpdate(_) => WebHookEvent::SwapUpdate,
+                WebHookCallData::InvoiceRequest(_) => WebHookEvent::InvoiceRequest,
+            },
+            data,
+        })
+        .map_err(|err| CallAttempt::new(None, err.to_string()))?;
+
+        let mut request = client
+            .post(hook.url())
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+
+        if let Some(secret) = hook.secret() {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|err| CallAttempt::new(None, err.to_string()))?
+                .as_secs();
+            request = request.header(
+                boltz_utils::webhook::SIGNATURE_HEADER,
+                boltz_utils::webhook::sign(&secret, timestamp, &body)
+                    .map_err(|err| CallAttempt::new(None, err.to_string()))?,
+            );
+        }
+
+        match request.body(body).send().await {
+            Ok(res) => {
+                let status = res.status();
+                if status.is_success() {
+                    res.bytes()
+                        .await
+                        .map(|res| res.to_vec())
+                        .map_err(|err| CallAttempt::new(Some(status.as_u16()), err.to_string()))
+                } else {
+                    Err(CallAttempt::new(
+                        Some(status.as_u16()),
+                        format!("HTTP {}", status.as_u16()),
+                    ))
+                }
+            }
+            Err(err) => Err(CallAttempt::new(None, err.to_string())),
+        }
+    }
+
+    fn record_attempt(&self, hook: &H, attempt: CallAttempt) {
+        let mut attempts = self.attempts.entry(hook.id()).or_default();
+        if attempts.len() >= MAX_ATTEMPT_HISTORY {
+            attempts.remove(0);
+        }
+        attempts.push(attempt);
+    }
+
+    fn add_dead_letter(&self, hook: &H, params: &WebHookCallData) {
+        let attempts = self
+            .attempts
+            .remove(&hook.id())
+            .map(|(_, attempts)| attempts)
+            .unwrap_or_default();
+
+        if let Err(err) = self.hook_state.add_dead_letter(hook, params, &attempts) {
+            error!(
+                "Could not save dead letter of {} WebHook call for {}: {}",
+                self.name,
+                hook.id(),
+                err
+            );
+        }
+    }
+
     #[instrument(name = "Caller::retry_calls", skip(self))]
     async fn retry_calls(&self) -> Result<(), Box<dyn Error>> {
         let to_retry = self.hook_state.get_by_state(WebHookState::Failed)?;
@@ -342,6 +528,19 @@ where
 
     #[instrument(name = "Caller::retry_call", skip(self, hook))]
     async fn retry_call(&self, hook: H) -> Result<(), Box<dyn Error>> {
+        if let Some(mut remaining) = self.retry_skips.get_mut(&hook.id())
+            && *remaining > 0
+        {
+            *remaining -= 1;
+            trace!(
+                "Backing off {} WebHook call for {} for {} more iterations",
+                self.name,
+                hook.id(),
+                *remaining
+            );
+            return Ok(());
+        }
+
         let params = self.hook_state.get_retry_data(&hook)?;
 
         let abandon_hook = |hook: H| -> Result<(), Box<dyn Error>> {
@@ -356,6 +555,8 @@ where
             .increment(1);
 
             self.retry_count.remove(&hook.id());
+            self.retry_skips.remove(&hook.id());
+            self.attempts.remove(&hook.id());
             self.hook_state.set_state(&hook, WebHookState::Abandoned)?;
 
             Ok(())
@@ -368,7 +569,7 @@ where
                 hook.id(),
                 hook.url()
             );
-            let res = self.call_webhook(hook.clone(), params).await?;
+            let res = self.call_webhook(hook.clone(), params.clone()).await?;
 
             if res == CallResult::Success {
                 self.retry_count.remove(&hook.id());
@@ -390,9 +591,16 @@ where
             );
 
             if res == CallResult::NotIncluded || failed_count >= self.max_retries {
+                if res != CallResult::NotIncluded {
+                    self.add_dead_letter(&hook, &params);
+                }
                 abandon_hook(hook)?;
             } else {
                 self.retry_count.insert(hook.id(), failed_count);
+                self.retry_skips.insert(
+                    hook.id(),
+                    self.retry_policy.iterations_to_skip(failed_count),
+                );
             }
         } else {
             warn!(
@@ -408,6 +616,12 @@ where
     }
 }
 
+fn host_of(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_string()))
+}
+
 pub async fn check_ip(url: &str, allow_insecure: bool) -> Result<()> {
     if allow_insecure {
         return Ok(());
@@ -477,6 +691,13 @@ mod caller_test {
             fn get_by_state(&self, state: WebHookState) -> Result<Vec<WebHook>>;
             fn get_retry_data(&self, id: &WebHook) -> Result<Option<WebHookCallData>>;
             fn set_state(&self, id: &WebHook, state: WebHookState) -> Result<()>;
+
+            fn add_dead_letter(
+                &self,
+                hook: &WebHook,
+                params: &WebHookCallData,
+                attempts: &[CallAttempt],
+            ) -> Result<()>;
         }
     }
 
@@ -509,6 +730,7 @@ mod caller_test {
                 retry_interval: Some(60),
                 request_timeout: Some(10),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -567,6 +789,7 @@ mod caller_test {
                 retry_interval: Some(60),
                 request_timeout: Some(10),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -592,6 +815,109 @@ mod caller_test {
         cancel_token.cancel();
     }
 
+    #[tokio::test]
+    async fn test_call_webhook_signature() {
+        use axum::body::Bytes;
+        use axum::http::HeaderMap;
+
+        let mut web_hook_helper = make_mock_hook_state();
+        web_hook_helper
+            .expect_should_be_skipped()
+            .returning(|_, _| false);
+        web_hook_helper.expect_set_state().returning(|_, _| Ok(()));
+
+        let port = 10009;
+        let secret = "super secret";
+
+        let caller = Caller::new(
+            CancellationToken::new(),
+            "test".to_string(),
+            Config {
+                max_retries: Some(5),
+                retry_interval: Some(60),
+                request_timeout: Some(10),
+                block_list: None,
+                ..Default::default()
+            },
+            true,
+            web_hook_helper,
+        );
+
+        let received = Arc::new(Mutex::new(Vec::<(Option<String>, Bytes)>::new()));
+        let received_cp = received.clone();
+        let router = Router::new().route(
+            "/",
+            post(move |headers: HeaderMap, body: Bytes| {
+                let received = received_cp.clone();
+                async move {
+                    received.lock().unwrap().push((
+                        headers
+                            .get(boltz_utils::webhook::SIGNATURE_HEADER)
+                            .map(|header| header.to_str().unwrap().to_string()),
+                        body,
+                    ));
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{port}"))
+            .await
+            .unwrap();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        let data = WebHookCallData::SwapUpdate(SwapUpdateCallData {
+            id: "signed".to_string(),
+            status: "some.update".to_string(),
+        });
+
+        let signed_hook = WebHook {
+            id: "signed".to_string(),
+            url: format!("http://127.0.0.1:{port}"),
+            secret: Some(secret.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            caller
+                .call_webhook(signed_hook, data.clone())
+                .await
+                .unwrap(),
+            CallResult::Success
+        );
+
+        let unsigned_hook = WebHook {
+            id: "unsigned".to_string(),
+            url: format!("http://127.0.0.1:{port}"),
+            ..Default::default()
+        };
+        assert_eq!(
+            caller.call_webhook(unsigned_hook, data).await.unwrap(),
+            CallResult::Success
+        );
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+
+        let (signature, body) = &received[0];
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        boltz_utils::webhook::verify(
+            secret,
+            signature.as_ref().unwrap(),
+            body,
+            now,
+            boltz_utils::webhook::DEFAULT_REPLAY_WINDOW,
+        )
+        .unwrap();
+
+        assert!(received[1].0.is_none());
+
+        server.abort();
+    }
+
     #[tokio::test]
     async fn test_call_webhook_failed_connect() {
         let mut web_hook_helper = make_mock_hook_state();
@@ -622,6 +948,7 @@ mod caller_test {
                 retry_interval: Some(60),
                 request_timeout: Some(10),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -671,6 +998,7 @@ mod caller_test {
                 retry_interval: Some(60),
                 request_timeout: Some(10),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -729,6 +1057,7 @@ mod caller_test {
                 retry_interval: Some(60),
                 request_timeout: Some(10),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -773,6 +1102,7 @@ mod caller_test {
             state: WebHookState::Failed.as_ref().to_string(),
             hash_swap_id: false,
             status: None,
+            secret: None,
         };
 
         let hook_cp = hook.clone();
@@ -789,6 +1119,7 @@ mod caller_test {
                 state: WebHookState::Failed.as_ref().to_string(),
                 hash_swap_id: false,
                 status: None,
+                secret: None,
             }))
             .returning(|_| {
                 Ok(Some(WebHookCallData::SwapUpdate(SwapUpdateCallData {
@@ -811,6 +1142,7 @@ mod caller_test {
                 retry_interval: Some(1),
                 request_timeout: Some(1),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -868,6 +1200,7 @@ mod caller_test {
                         state: WebHookState::Failed.as_ref().to_string(),
                         hash_swap_id: false,
                         status: None,
+                        secret: None,
                     },
                     WebHook {
                         url: url.clone(),
@@ -875,6 +1208,7 @@ mod caller_test {
                         state: WebHookState::Failed.as_ref().to_string(),
                         hash_swap_id: true,
                         status: None,
+                        secret: None,
                     },
                 ])
             });
@@ -908,6 +1242,7 @@ mod caller_test {
                 retry_interval: Some(5),
                 request_timeout: Some(5),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -964,6 +1299,7 @@ mod caller_test {
                     state: WebHookState::Failed.as_ref().to_string(),
                     hash_swap_id: false,
                     status: None,
+                    secret: None,
                 }])
             });
 
@@ -978,6 +1314,21 @@ mod caller_test {
             .expect_set_state()
             .returning(move |_, _| Ok(()));
 
+        web_hook_helper
+            .expect_add_dead_letter()
+            .withf(move |hook, params, attempts| {
+                hook.id == id
+                    && *params
+                        == WebHookCallData::SwapUpdate(SwapUpdateCallData {
+                            id: id.to_string(),
+                            status: status.to_string(),
+                        })
+                    && attempts.len() == 2
+                    && attempts.iter().all(|attempt| attempt.status.is_none())
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
         let caller_cancel = CancellationToken::new();
         let max_retries = 2;
         let caller = Caller::new(
@@ -988,6 +1339,7 @@ mod caller_test {
                 retry_interval: Some(5),
                 request_timeout: Some(5),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -996,8 +1348,261 @@ mod caller_test {
         assert!(caller.retry_count.get(&id.to_string()).is_none());
         caller.retry_calls().await.unwrap();
         assert_eq!(*caller.retry_count.get(&id.to_string()).unwrap().value(), 1);
+        assert_eq!(caller.attempts.get(&id.to_string()).unwrap().len(), 1);
+        caller.retry_calls().await.unwrap();
+        assert!(caller.retry_count.get(&id.to_string()).is_none());
+        assert!(caller.attempts.get(&id.to_string()).is_none());
+    }
+
+    fn make_failing_retry_state(id: &'static str, url: String) -> MockHookState {
+        let mut web_hook_helper = make_mock_hook_state();
+        web_hook_helper
+            .expect_should_be_skipped()
+            .returning(|_, _| false);
+        web_hook_helper
+            .expect_get_by_state()
+            .with(predicate::eq(WebHookState::Failed))
+            .returning(move |_| {
+                Ok(vec![WebHook {
+                    url: url.clone(),
+                    id: id.to_string(),
+                    state: WebHookState::Failed.as_ref().to_string(),
+                    ..Default::default()
+                }])
+            });
+        web_hook_helper.expect_get_retry_data().returning(move |_| {
+            Ok(Some(WebHookCallData::SwapUpdate(SwapUpdateCallData {
+                id: id.to_string(),
+                status: "some.update".to_string(),
+            })))
+        });
+        web_hook_helper
+            .expect_set_state()
+            .returning(move |_, _| Ok(()));
+        web_hook_helper
+    }
+
+    #[tokio::test]
+    async fn test_retry_calls_backoff() {
+        let id = "backoff";
+        let caller = Caller::new(
+            CancellationToken::new(),
+            "test".to_string(),
+            Config {
+                max_retries: Some(5),
+                retry_interval: Some(5),
+                request_timeout: Some(5),
+                max_retry_interval: Some(60),
+                retry_backoff_multiplier: Some(2.0),
+                circuit_breaker: Some(crate::webhook::circuit_breaker::Config {
+                    failure_threshold: Some(0),
+                    cooldown: None,
+                }),
+                ..Default::default()
+            },
+            true,
+            make_failing_retry_state(id, "http://127.0.0.1:10011".to_string()),
+        );
+
+        caller.retry_calls().await.unwrap();
+        assert_eq!(*caller.retry_count.get(&id.to_string()).unwrap().value(), 1);
+        assert_eq!(*caller.retry_skips.get(&id.to_string()).unwrap().value(), 1);
+
+        // Backing off; the call is not attempted
+        caller.retry_calls().await.unwrap();
+        assert_eq!(*caller.retry_count.get(&id.to_string()).unwrap().value(), 1);
+        assert_eq!(*caller.retry_skips.get(&id.to_string()).unwrap().value(), 0);
+        assert_eq!(caller.attempts.get(&id.to_string()).unwrap().len(), 1);
+
+        caller.retry_calls().await.unwrap();
+        assert_eq!(*caller.retry_count.get(&id.to_string()).unwrap().value(), 2);
+        assert_eq!(*caller.retry_skips.get(&id.to_string()).unwrap().value(), 3);
+        assert_eq!(caller.attempts.get(&id.to_string()).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_pauses_calls() {
+        let id = "breaker";
+        let caller = Caller::new(
+            CancellationToken::new(),
+            "test".to_string(),
+            Config {
+                max_retries: Some(5),
+                retry_interval: Some(5),
+                request_timeout: Some(5),
+                circuit_breaker: Some(crate::webhook::circuit_breaker::Config {
+                    failure_threshold: Some(2),
+                    cooldown: Some(60),
+                }),
+                ..Default::default()
+            },
+            true,
+            make_failing_retry_state(id, "http://127.0.0.1:10012".to_string()),
+        );
+
+        let hook = WebHook {
+            id: id.to_string(),
+            url: "http://127.0.0.1:10012".to_string(),
+            ..Default::default()
+        };
+        let data = WebHookCallData::SwapUpdate(SwapUpdateCallData {
+            id: id.to_string(),
+            status: "some.update".to_string(),
+        });
+
+        for _ in 0..2 {
+            assert_eq!(
+                caller
+                    .call_webhook(hook.clone(), data.clone())
+                    .await
+                    .unwrap(),
+                CallResult::Failed
+            );
+        }
+        assert_eq!(
+            caller.circuit_breaker.state("127.0.0.1"),
+            crate::webhook::circuit_breaker::State::Open
+        );
+
+        assert_eq!(
+            caller.call_webhook(hook, data).await.unwrap(),
+            CallResult::Paused
+        );
+        assert_eq!(caller.attempts.get(&id.to_string()).unwrap().len(), 3);
+        assert_eq!(
+            caller.attempts.get(&id.to_string()).unwrap()[2].error,
+            "circuit breaker of 127.0.0.1 is open"
+        );
+
+        // Paused calls count towards the retry limit
+        caller.retry_calls().await.unwrap();
+        assert_eq!(*caller.retry_count.get(&id.to_string()).unwrap().value(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_paused_calls_dead_lettered() {
+        let id = "breaker_dead_letter";
+        let url = "http://127.0.0.1:10013".to_string();
+
+        let mut hook_state = make_failing_retry_state(id, url.clone());
+        hook_state
+            .expect_add_dead_letter()
+            .withf(move |hook, _, attempts| {
+                hook.id == id
+                    && attempts.len() == 2
+                    && attempts[1].error == "circuit breaker of 127.0.0.1 is open"
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let caller = Caller::new(
+            CancellationToken::new(),
+            "test".to_string(),
+            Config {
+                max_retries: Some(1),
+                retry_interval: Some(5),
+                request_timeout: Some(5),
+                circuit_breaker: Some(crate::webhook::circuit_breaker::Config {
+                    failure_threshold: Some(1),
+                    cooldown: Some(60),
+                }),
+                ..Default::default()
+            },
+            true,
+            hook_state,
+        );
+
+        assert_eq!(
+            caller
+                .call_webhook(
+                    WebHook {
+                        id: id.to_string(),
+                        url,
+                        ..Default::default()
+                    },
+                    WebHookCallData::SwapUpdate(SwapUpdateCallData {
+                        id: id.to_string(),
+                        status: "some.update".to_string(),
+                    }),
+                )
+                .await
+                .unwrap(),
+            CallResult::Failed
+        );
+
+        // The host stays down, so the retry is paused and the call is given up on
         caller.retry_calls().await.unwrap();
         assert!(caller.retry_count.get(&id.to_string()).is_none());
+        assert!(caller.attempts.get(&id.to_string()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let mut web_hook_helper = make_mock_hook_state();
+        web_hook_helper
+            .expect_set_state()
+            .withf(|hook, state| hook.id == "replay" && *state == WebHookState::Ok)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let port = 10010;
+        let caller = Caller::new(
+            CancellationToken::new(),
+            "test".to_string(),
+            Config {
+                max_retries: Some(5),
+                retry_interval: Some(60),
+                request_timeout: Some(10),
+                block_list: None,
+                ..Default::default()
+            },
+            true,
+            web_hook_helper,
+        );
+
+        let (cancel_token, received_calls, _received_headers) = start_server(port).await;
+        let data = WebHookCallData::SwapUpdate(SwapUpdateCallData {
+            id: "replay".to_string(),
+            status: "some.update".to_string(),
+        });
+
+        caller
+            .replay(
+                WebHook {
+                    id: "replay".to_string(),
+                    url: format!("http://127.0.0.1:{port}"),
+                    ..Default::default()
+                },
+                data.clone(),
+            )
+            .await
+            .unwrap();
+
+        let err = caller
+            .replay(
+                WebHook {
+                    id: "replay".to_string(),
+                    url: format!("http://127.0.0.1:{port}/fail"),
+                    ..Default::default()
+                },
+                data.clone(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, Some(500));
+        assert_eq!(err.error, "HTTP 500");
+
+        assert_eq!(received_calls.lock().unwrap().len(), 2);
+        assert_eq!(
+            received_calls.lock().unwrap()[0],
+            WebHookCallParams {
+                event: WebHookEvent::SwapUpdate,
+                data,
+            }
+        );
+        assert!(caller.attempts.is_empty());
+
+        cancel_token.cancel();
     }
 
     #[tokio::test]
@@ -1017,6 +1622,7 @@ mod caller_test {
             hash_swap_id: false,
             status: Some(vec!["invoice.set".to_string()]),
             state: WebHookState::Failed.as_ref().to_string(),
+            secret: None,
         };
         let hook_cp = hook.clone();
         web_hook_helper
@@ -1049,6 +1655,7 @@ mod caller_test {
                 retry_interval: Some(5),
                 request_timeout: Some(5),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -1216,6 +1823,7 @@ mod caller_test {
                 retry_interval: Some(60),
                 request_timeout: Some(10),
                 block_list: None,
+                ..Default::default()
             },
             true,
             web_hook_helper,
@@ -0,0 +1,360 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+// Disabled unless configured
+const DEFAULT_FAILURE_THRESHOLD: u64 = 0;
+const DEFAULT_COOLDOWN: u64 = 300;
+
+/// Maximal number of hosts whose failures are tracked
+const MAX_TRACKED_HOSTS: usize = 10_000;
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Clone, Debug)]
+pub struct Config {
+    /// Consecutive failures after which calls to a host are paused; unset or 0 disables the breaker
+    #[serde(rename = "failureThreshold")]
+    pub failure_threshold: Option<u64>,
+    /// Seconds to pause calls to a host before probing it again
+    pub cooldown: Option<u64>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum State {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    failures: u64,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+impl HostState {
+    fn state(&self) -> State {
+        match self.opened_at {
+            None => State::Closed,
+            Some(_) if self.probing => State::HalfOpen,
+            Some(_) => State::Open,
+        }
+    }
+}
+
+/// Pauses calls to hosts that failed repeatedly
+///
+/// Once the cooldown of an open breaker has passed, a single call is let
+/// through as probe; its result decides whether the breaker closes again
+///
+/// Hosts come from user supplied URLs, so only a limited number of them is
+/// tracked; when full, hosts that did not trip their breaker are forgotten
+/// first, then the ones whose breakers opened the longest time ago
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    failure_threshold: u64,
+    cooldown: Duration,
+    max_hosts: usize,
+    hosts: DashMap<String, HostState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: String, config: Option<Config>) -> Self {
+        let config = config.unwrap_or_default();
+        let failure_threshold = config
+            .failure_threshold
+            .unwrap_or(DEFAULT_FAILURE_THRESHOLD);
+        let cooldown = config.cooldown.unwrap_or(DEFAULT_COOLDOWN);
+        debug!(
+            "{} WebHook circuit breaker threshold {} with cooldown of {}s",
+            name, failure_threshold, cooldown
+        );
+
+        Self {
+            name,
+            failure_threshold,
+            cooldown: Duration::from_secs(cooldown),
+            max_hosts: MAX_TRACKED_HOSTS,
+            hosts: DashMap::new(),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.failure_threshold > 0
+    }
+
+    pub fn state(&self, host: &str) -> State {
+        self.hosts
+            .get(host)
+            .map(|state| state.state())
+            .unwrap_or(State::Closed)
+    }
+
+    /// Whether a call to the host may be made right now
+    pub fn allow(&self, host: &str) -> bool {
+        if !self.enabled() {
+            return true;
+        }
+
+        {
+            let mut state = match self.hosts.get_mut(host) {
+                Some(state) => state,
+                None => return true,
+            };
+
+            match state.opened_at {
+                None => return true,
+                Some(_) if state.probing => return false,
+                Some(opened_at) => {
+                    if opened_at.elapsed() < self.cooldown {
+                        return false;
+                    }
+
+                    state.probing = true;
+                }
+            }
+        }
+
+        debug!("Probing {} WebHook host {}", self.name, host);
+        self.export();
+        true
+    }
+
+    pub fn record_success(&self, host: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        if let Some((_, state)) = self.hosts.remove(host)
+            && state.opened_at.is_some()
+        {
+            info!("Resuming {} WebHook calls to {}", self.name, host);
+            self.export();
+        }
+    }
+
+    pub fn record_failure(&self, host: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        if !self.hosts.contains_key(host) && self.hosts.len() >= self.max_hosts {
+            self.evict();
+        }
+
+        let opened = {
+            let mut state = self.hosts.entry(host.to_string()).or_default();
+            state.failures += 1;
+
+            if state.probing
+                || (state.opened_at.is_none() && state.failures >= self.failure_threshold)
+            {
+                warn!(
+                    "Pausing {} WebHook calls to {} for {:?} after {} consecutive failures",
+                    self.name, host, self.cooldown, state.failures
+                );
+                state.opened_at = Some(Instant::now());
+                state.probing = false;
+                true
+            } else {
+                false
+            }
+        };
+
+        if opened {
+            self.export();
+        }
+    }
+
+    fn evict(&self) {
+        self.hosts.retain(|_, state| state.opened_at.is_some());
+        if self.hosts.len() < self.max_hosts {
+            return;
+        }
+
+        let oldest = self
+            .hosts
+            .iter()
+            .min_by_key(|entry| entry.opened_at)
+            .map(|entry| entry.key().clone());
+        if let Some(host) = oldest {
+            debug!("Forgetting {} WebHook host {}", self.name, host);
+            self.hosts.remove(&host);
+            self.export();
+        }
+    }
+
+    /// Exports the number of open and half-open breakers; no guard of the
+    /// hosts map may be held when calling this
+    fn export(&self) {
+        #[cfg(feature = "metrics")]
+        {
+            let (mut open, mut half_open) = (0, 0);
+            for entry in self.hosts.iter() {
+                match entry.state() {
+                    State::Open => open += 1,
+                    State::HalfOpen => half_open += 1,
+                    State::Closed => {}
+                }
+            }
+
+            for (state, count) in [("open", open), ("half_open", half_open)] {
+                metrics::gauge!(
+                    crate::metrics::WEBHOOK_CIRCUIT_BREAKERS,
+                    "state" => state,
+                    "type" => self.name.clone()
+                )
+                .set(count as f64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HOST: &str = "partner.example";
+
+    fn new_breaker(failure_threshold: u64, cooldown: u64) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test".to_string(),
+            Some(Config {
+                failure_threshold: Some(failure_threshold),
+                cooldown: Some(cooldown),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_opens_after_threshold() {
+        let breaker = new_breaker(3, 60);
+
+        for _ in 0..2 {
+            breaker.record_failure(HOST);
+            assert_eq!(breaker.state(HOST), State::Closed);
+            assert!(breaker.allow(HOST));
+        }
+
+        breaker.record_failure(HOST);
+        assert_eq!(breaker.state(HOST), State::Open);
+        assert!(!breaker.allow(HOST));
+
+        assert!(breaker.allow("other.example"));
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let breaker = new_breaker(2, 60);
+
+        breaker.record_failure(HOST);
+        breaker.record_success(HOST);
+        breaker.record_failure(HOST);
+
+        assert_eq!(breaker.state(HOST), State::Closed);
+    }
+
+    #[test]
+    fn test_probe_success_closes() {
+        let breaker = new_breaker(1, 0);
+
+        breaker.record_failure(HOST);
+        assert_eq!(breaker.state(HOST), State::Open);
+
+        assert!(breaker.allow(HOST));
+        assert_eq!(breaker.state(HOST), State::HalfOpen);
+        // Only a single probe at a time
+        assert!(!breaker.allow(HOST));
+
+        breaker.record_success(HOST);
+        assert_eq!(breaker.state(HOST), State::Closed);
+        assert!(breaker.allow(HOST));
+    }
+
+    #[test]
+    fn test_probe_failure_reopens() {
+        let breaker = new_breaker(2, 0);
+
+        breaker.record_failure(HOST);
+        breaker.record_failure(HOST);
+
+        assert!(breaker.allow(HOST));
+        breaker.record_failure(HOST);
+        assert_eq!(breaker.state(HOST), State::Open);
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let breaker = new_breaker(1, 60);
+
+        breaker.record_failure(HOST);
+        assert!(!breaker.allow(HOST));
+
+        breaker.hosts.get_mut(HOST).unwrap().opened_at =
+            Some(Instant::now() - Duration::from_secs(61));
+        assert!(breaker.allow(HOST));
+        assert_eq!(breaker.state(HOST), State::HalfOpen);
+    }
+
+    #[test]
+    fn test_evicts_closed_hosts_first() {
+        let mut breaker = new_breaker(2, 60);
+        breaker.max_hosts = 3;
+
+        breaker.record_failure(HOST);
+        breaker.record_failure(HOST);
+        for i in 0..2 {
+            breaker.record_failure(&format!("{i}.example"));
+        }
+        assert_eq!(breaker.hosts.len(), 3);
+
+        breaker.record_failure("new.example");
+        assert_eq!(breaker.hosts.len(), 2);
+        assert_eq!(breaker.state(HOST), State::Open);
+        assert!(breaker.hosts.contains_key("new.example"));
+    }
+
+    #[test]
+    fn test_evicts_oldest_open_host() {
+        let mut breaker = new_breaker(1, 60);
+        breaker.max_hosts = 3;
+
+        for i in 0..3 {
+            breaker.record_failure(&format!("{i}.example"));
+        }
+        breaker.hosts.get_mut("1.example").unwrap().opened_at =
+            Some(Instant::now() - Duration::from_secs(61));
+
+        breaker.record_failure(HOST);
+        assert_eq!(breaker.hosts.len(), 3);
+        assert!(!breaker.hosts.contains_key("1.example"));
+        assert_eq!(breaker.state(HOST), State::Open);
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let breaker = CircuitBreaker::new("test".to_string(), None);
+        assert!(!breaker.enabled());
+
+        for _ in 0..10 {
+            breaker.record_failure(HOST);
+        }
+
+        assert_eq!(breaker.state(HOST), State::Closed);
+        assert!(breaker.allow(HOST));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let breaker = new_breaker(0, 60);
+
+        for _ in 0..10 {
+            breaker.record_failure(HOST);
+        }
+
+        assert_eq!(breaker.state(HOST), State::Closed);
+        assert!(breaker.allow(HOST));
+    }
+}
@@ -2,7 +2,7 @@ use crate::db::models::WebHookState;
 use crate::lightning::cln::ReplyBlindedPath;
 use crate::types;
 use crate::webhook::WebHookCallData;
-use crate::webhook::caller::{CallResult, Caller, Config, Hook, HookState};
+use crate::webhook::caller::{CallAttempt, CallResult, Caller, Config, Hook, HookState};
 use crate::webhook::types::InvoiceRequestCallData;
 use anyhow::{Result, anyhow};
 use dashmap::DashMap;
@@ -10,6 +10,7 @@ use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 use tokio::sync::broadcast::Receiver;
 use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
 const NAME: &str = "BOLT12 invoice";
 
@@ -20,6 +21,7 @@ pub struct InvoiceHook<T: types::Bool> {
     pub reply_blinded_path: Option<ReplyBlindedPath>,
 
     url: Option<String>,
+    secret: Option<String>,
 
     phantom: std::marker::PhantomData<T>,
 }
@@ -46,6 +48,7 @@ impl InvoiceHook<types::False> {
             invoice_request: hex::encode(invoice_request),
             reply_blinded_path,
             url: None,
+            secret: None,
             phantom: std::marker::PhantomData,
         }
     }
@@ -58,10 +61,16 @@ impl<T: types::Bool> InvoiceHook<T> {
             invoice_request: self.invoice_request,
             reply_blinded_path: self.reply_blinded_path,
             url: Some(url),
+            secret: self.secret,
             phantom: std::marker::PhantomData,
         }
     }
 
+    pub fn with_secret(mut self, secret: Option<String>) -> Self {
+        self.secret = secret;
+        self
+    }
+
     pub fn id(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
         self.invoice_request.hash(&mut hasher);
@@ -79,6 +88,10 @@ impl Hook for InvoiceHook<types::True> {
     fn url(&self) -> String {
         self.url.clone().unwrap()
     }
+
+    fn secret(&self) -> Option<String> {
+        self.secret.clone()
+    }
 }
 
 impl<T: types::Bool> From<&InvoiceHook<T>> for WebHookCallData {
@@ -128,6 +141,29 @@ impl HookState<InvoiceHook<types::True>> for InvoiceHookState {
 
         Ok(())
     }
+
+    fn add_dead_letter(
+        &self,
+        hook: &InvoiceHook<types::True>,
+        _params: &WebHookCallData,
+        attempts: &[CallAttempt],
+    ) -> Result<()> {
+        // The dead letter queue is only for swap status calls: abandoned invoice requests
+        // have timed out already and could not be answered when replayed, so only a
+        // record of them is logged
+        warn!(
+            "Dropping {} WebHook call for offer {} to {} after {} failed attempts: {}",
+            NAME,
+            hook.offer,
+            hook.url(),
+            attempts.len(),
+            attempts
+                .last()
+                .map(|attempt| attempt.error.as_str())
+                .unwrap_or("unknown error")
+        );
+        Ok(())
+    }
 }
 
 impl InvoiceCaller {
@@ -182,6 +218,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("https://example.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
@@ -190,6 +227,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("https://different.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
@@ -200,6 +238,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("https://example.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
@@ -215,12 +254,23 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some(url.to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
         assert_eq!(hook.url(), url);
     }
 
+    #[test]
+    fn test_invoice_hook_secret() {
+        let secret = "secret";
+        let hook = InvoiceHook::new("test_offer".to_string(), &[1, 2, 3], None)
+            .with_secret(Some(secret.to_string()))
+            .with_url("https://example.com/webhook".to_string());
+
+        assert_eq!(Hook::secret(&hook), Some(secret.to_string()));
+    }
+
     #[test]
     fn test_should_be_skipped() {
         let state = InvoiceHookState::new();
@@ -229,6 +279,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("http://example.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
         let params = (&hook).into();
@@ -244,6 +295,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("http://example.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
@@ -266,6 +318,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("http://example.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
@@ -286,6 +339,7 @@ mod tests {
             offer: "test_offer".to_string(),
             reply_blinded_path: None,
             url: Some("http://example.com".to_string()),
+            secret: None,
             phantom: std::marker::PhantomData,
         };
 
@@ -1,6 +1,8 @@
 pub mod caller;
+pub mod circuit_breaker;
 pub mod invoice_caller;
 mod resolver;
+mod retry;
 pub mod status_caller;
 mod types;
 
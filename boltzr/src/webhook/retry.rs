@@ -0,0 +1,147 @@
+use rand::Rng;
+use std::time::Duration;
+
+const DEFAULT_BACKOFF_MULTIPLIER: f64 = 1.0;
+const DEFAULT_JITTER: f64 = 0.0;
+
+/// Decides how long a failed WebHook call waits before it is retried again
+///
+/// Retries are attempted by a loop that runs every `base` interval; the delay
+/// of a hook is therefore expressed as the number of loop iterations it skips
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    base: Duration,
+    max: Duration,
+    multiplier: f64,
+    jitter: f64,
+}
+
+impl RetryPolicy {
+    pub fn new(
+        base: Duration,
+        max: Option<Duration>,
+        multiplier: Option<f64>,
+        jitter: Option<f64>,
+    ) -> Self {
+        Self {
+            base,
+            max: max.unwrap_or(base).max(base),
+            multiplier: multiplier.unwrap_or(DEFAULT_BACKOFF_MULTIPLIER).max(1.0),
+            jitter: jitter.unwrap_or(DEFAULT_JITTER).clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    /// Delay before the next retry after `failed_count` retries have failed already
+    pub fn delay(&self, failed_count: u64) -> Duration {
+        let exponent = i32::try_from(failed_count).unwrap_or(i32::MAX);
+        let delay =
+            (self.base.as_secs_f64() * self.multiplier.powi(exponent)).min(self.max.as_secs_f64());
+
+        let jitter = if self.jitter > 0.0 {
+            rand::thread_rng().gen_range(-self.jitter..=self.jitter)
+        } else {
+            0.0
+        };
+
+        Duration::from_secs_f64((delay * (1.0 + jitter)).max(self.base.as_secs_f64()))
+    }
+
+    /// Number of retry loop iterations to skip before the next retry
+    pub fn iterations_to_skip(&self, failed_count: u64) -> u64 {
+        if self.base.is_zero() {
+            return 0;
+        }
+
+        let iterations = (self.delay(failed_count).as_secs_f64() / self.base.as_secs_f64()).round();
+        (iterations as u64).saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 60)]
+    #[case(1, 120)]
+    #[case(2, 240)]
+    #[case(3, 480)]
+    #[case(4, 600)]
+    #[case(64, 600)]
+    fn test_delay_exponential(#[case] failed_count: u64, #[case] expected: u64) {
+        let policy = RetryPolicy::new(
+            Duration::from_secs(60),
+            Some(Duration::from_secs(600)),
+            Some(2.0),
+            None,
+        );
+        assert_eq!(policy.delay(failed_count), Duration::from_secs(expected));
+    }
+
+    #[test]
+    fn test_delay_default_is_fixed() {
+        let policy = RetryPolicy::new(Duration::from_secs(60), None, None, None);
+        for failed_count in 0..10 {
+            assert_eq!(policy.delay(failed_count), Duration::from_secs(60));
+            assert_eq!(policy.iterations_to_skip(failed_count), 0);
+        }
+    }
+
+    #[test]
+    fn test_delay_jitter() {
+        let policy = RetryPolicy::new(
+            Duration::from_secs(60),
+            Some(Duration::from_secs(3_600)),
+            Some(2.0),
+            Some(0.5),
+        );
+
+        for _ in 0..100 {
+            let delay = policy.delay(3);
+            assert!(delay >= Duration::from_secs(240));
+            assert!(delay <= Duration::from_secs(720));
+        }
+    }
+
+    #[test]
+    fn test_delay_never_below_base() {
+        let policy = RetryPolicy::new(Duration::from_secs(60), None, None, Some(1.0));
+        for _ in 0..100 {
+            assert!(policy.delay(0) >= Duration::from_secs(60));
+        }
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    #[case(2, 3)]
+    #[case(3, 7)]
+    #[case(4, 9)]
+    fn test_iterations_to_skip(#[case] failed_count: u64, #[case] expected: u64) {
+        let policy = RetryPolicy::new(
+            Duration::from_secs(60),
+            Some(Duration::from_secs(600)),
+            Some(2.0),
+            None,
+        );
+        assert_eq!(policy.iterations_to_skip(failed_count), expected);
+    }
+
+    #[test]
+    fn test_invalid_values_are_clamped() {
+        let policy = RetryPolicy::new(
+            Duration::from_secs(60),
+            Some(Duration::from_secs(1)),
+            Some(0.5),
+            Some(2.0),
+        );
+        assert_eq!(policy.max, Duration::from_secs(60));
+        assert_eq!(policy.multiplier, 1.0);
+        assert_eq!(policy.jitter, 1.0);
+    }
+}
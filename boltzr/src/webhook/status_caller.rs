@@ -1,4 +1,4 @@
-use super::caller::{CallResult, Caller, Config};
+use super::caller::{CallAttempt, CallResult, Caller, Config};
 use crate::db::helpers::web_hook::WebHookHelperDatabase;
 use crate::db::models::WebHook;
 use crate::webhook::types::{SwapUpdateCallData, WebHookCallData};
@@ -46,6 +46,10 @@ impl StatusCaller {
         self.caller.call_webhook(hook, data).await
     }
 
+    pub async fn replay(&self, hook: WebHook, data: WebHookCallData) -> Result<(), CallAttempt> {
+        self.caller.replay(hook, data).await
+    }
+
     pub fn validate_url(&self, url: &str, allow_http: bool) -> anyhow::Result<()> {
         self.caller.validate_url(url, allow_http)
     }
@@ -39,6 +39,7 @@ import OverpaymentProtector from './swap/OverpaymentProtector';
 import type { Currency } from './wallet/WalletManager';
 import WalletManager from './wallet/WalletManager';
 import EthereumManager from './wallet/ethereum/EthereumManager';
+import SequentialSigner from './wallet/ethereum/SequentialSigner';
 import type { NetworkDetails } from './wallet/ethereum/EvmNetworks';
 import { networks } from './wallet/ethereum/EvmNetworks';
 
@@ -306,6 +307,9 @@ class Boltz {
       await this.sidecar.start();
       this.logger.info('Connected to sidecar');
 
+      // EVM transactions are sent through the nonce managers of the sidecar from now on
+      SequentialSigner.useSidecar(this.sidecar, this.logger);
+
       await this.prometheus.start();
 
       // Query the chain tips now to avoid them being updated after the chain clients are initialized
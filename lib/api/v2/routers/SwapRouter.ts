@@ -242,6 +242,9 @@ class SwapRouter extends RouterBase {
      *             type: string
      *           default: []
      *           description: Swap status events for which the Webhook should be called. If undefined or empty, the Webhook will be called for all status events
+     *         secret:
+     *           type: string
+     *           description: Secret with which the Webhook calls are signed with HMAC-SHA256 in the "Boltz-Signature" header
      */
 
     /**
@@ -3562,6 +3565,7 @@ class SwapRouter extends RouterBase {
       { name: 'url', type: 'string' },
       { name: 'status', type: 'object', optional: true },
       { name: 'hashSwapId', type: 'boolean', optional: true },
+      { name: 'secret', type: 'string', optional: true },
     ]);
 
     if (res.status) {
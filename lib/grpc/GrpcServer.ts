@@ -71,6 +71,10 @@ class GrpcServer {
       revokeJwt: grpcService.revokeJwt,
       listJwts: grpcService.listJwts,
       listMethods: grpcService.listMethods,
+      listWebHookDeadLetters: grpcService.listWebHookDeadLetters,
+      getWebHookDeadLetter: grpcService.getWebHookDeadLetter,
+      replayWebHookDeadLetter: grpcService.replayWebHookDeadLetter,
+      purgeWebHookDeadLetters: grpcService.purgeWebHookDeadLetters,
     });
   }
 
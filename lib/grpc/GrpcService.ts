@@ -30,6 +30,7 @@ import JwtTokenRepository from '../db/repositories/JwtTokenRepository';
 import PendingEthereumTransactionRepository from '../db/repositories/PendingEthereumTransactionRepository';
 import ReferralRepository from '../db/repositories/ReferralRepository';
 import TransactionLabelRepository from '../db/repositories/TransactionLabelRepository';
+import type * as sidecarrpc from '../proto/boltzr';
 import type * as boltzrpc from '../proto/boltzrpc';
 import { LogLevel } from '../proto/boltzrpc';
 import type Service from '../service/Service';
@@ -834,6 +835,42 @@ class GrpcService {
     });
   };
 
+  public listWebHookDeadLetters: handleUnaryCall<
+    sidecarrpc.ListWebHookDeadLettersRequest,
+    sidecarrpc.ListWebHookDeadLettersResponse
+  > = async (call, callback) => {
+    await GrpcService.handleCallback(call, callback, () =>
+      this.service.sidecar.listWebHookDeadLetters(call.request),
+    );
+  };
+
+  public getWebHookDeadLetter: handleUnaryCall<
+    sidecarrpc.GetWebHookDeadLetterRequest,
+    sidecarrpc.GetWebHookDeadLetterResponse
+  > = async (call, callback) => {
+    await GrpcService.handleCallback(call, callback, () =>
+      this.service.sidecar.getWebHookDeadLetter(call.request),
+    );
+  };
+
+  public replayWebHookDeadLetter: handleUnaryCall<
+    sidecarrpc.ReplayWebHookDeadLetterRequest,
+    sidecarrpc.ReplayWebHookDeadLetterResponse
+  > = async (call, callback) => {
+    await GrpcService.handleCallback(call, callback, () =>
+      this.service.sidecar.replayWebHookDeadLetter(call.request),
+    );
+  };
+
+  public purgeWebHookDeadLetters: handleUnaryCall<
+    sidecarrpc.PurgeWebHookDeadLettersRequest,
+    sidecarrpc.PurgeWebHookDeadLettersResponse
+  > = async (call, callback) => {
+    await GrpcService.handleCallback(call, callback, () =>
+      this.service.sidecar.purgeWebHookDeadLetters(call.request),
+    );
+  };
+
   private static invalidArgument = (error: unknown) => {
     const message = formatError(error);
     return {
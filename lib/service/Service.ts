@@ -152,6 +152,7 @@ type WebHookData = {
   url: string;
   hashSwapId?: boolean;
   status?: string[];
+  secret?: string;
 };
 
 type ExtraFees = {
@@ -2638,6 +2639,7 @@ class Service {
         data.url,
         data.hashSwapId,
         data.status,
+        data.secret,
       );
     } catch (e) {
       this.logger.warn(
@@ -503,6 +503,18 @@ class DeferredClaimer extends CoopSignerBase<{
         await currency!.chainClient!.sendRawTransaction(
           getHexString(res.transaction),
         );
+
+        try {
+          await this.sidecar.claimBatchBroadcasted(
+            symbol,
+            swaps.map((s) => s.swap.id),
+            claimTransactionId,
+          );
+        } catch (e) {
+          this.logger.warn(
+            `Could not track ${symbol} claim transaction ${claimTransactionId}: ${formatError(e)}`,
+          );
+        }
         break;
       }
 
@@ -6,22 +6,26 @@ import path from 'path';
 import type { BaseClientEvents } from '../BaseClient';
 import BaseClient from '../BaseClient';
 import type { ConfigType } from '../Config';
-import { parseTransaction } from '../Core';
+import { calculateTransactionFee, parseTransaction } from '../Core';
 import type Logger from '../Logger';
 import { LogLevel } from '../Logger';
 import { sleep } from '../PromiseUtils';
+import { TxView } from '../TxView';
 import {
   formatError,
   fromProtoInt,
+  getHexBuffer,
   getHexString,
   getVersion,
   stringify,
+  toOptionalProtoInt,
   toProtoInt,
 } from '../Utils';
 import type SwapInfos from '../api/SwapInfos';
 import type { SomeTransaction } from '../chain/ChainClient';
 import ElementsClient from '../chain/ElementsClient';
 import { ClientStatus, CurrencyType, SwapUpdateEvent } from '../consts/Enums';
+import ChainSwapRepository from '../db/repositories/ChainSwapRepository';
 import SwapRepository from '../db/repositories/SwapRepository';
 import { grpcOptions, unaryCall } from '../lightning/GrpcUtils';
 import { createSsl } from '../lightning/cln/Types';
@@ -278,10 +282,12 @@ class Sidecar extends BaseClient<
     url: string,
     hashSwapId?: boolean,
     statusInclude?: string[],
+    secret?: string,
   ) => {
     const req: sidecarrpc.CreateWebHookRequest = {
       id: swapId,
       url,
+      secret,
       hashSwapId: hashSwapId || false,
       status: statusInclude || [],
     };
@@ -303,6 +309,36 @@ class Sidecar extends BaseClient<
     >('deleteWebHook', req);
   };
 
+  public listWebHookDeadLetters = (
+    req: sidecarrpc.ListWebHookDeadLettersRequest,
+  ) =>
+    this.unaryNodeCall<
+      sidecarrpc.ListWebHookDeadLettersRequest,
+      sidecarrpc.ListWebHookDeadLettersResponse
+    >('listWebHookDeadLetters', req);
+
+  public getWebHookDeadLetter = (req: sidecarrpc.GetWebHookDeadLetterRequest) =>
+    this.unaryNodeCall<
+      sidecarrpc.GetWebHookDeadLetterRequest,
+      sidecarrpc.GetWebHookDeadLetterResponse
+    >('getWebHookDeadLetter', req);
+
+  public replayWebHookDeadLetter = (
+    req: sidecarrpc.ReplayWebHookDeadLetterRequest,
+  ) =>
+    this.unaryNodeCall<
+      sidecarrpc.ReplayWebHookDeadLetterRequest,
+      sidecarrpc.ReplayWebHookDeadLetterResponse
+    >('replayWebHookDeadLetter', req);
+
+  public purgeWebHookDeadLetters = (
+    req: sidecarrpc.PurgeWebHookDeadLettersRequest,
+  ) =>
+    this.unaryNodeCall<
+      sidecarrpc.PurgeWebHookDeadLettersRequest,
+      sidecarrpc.PurgeWebHookDeadLettersResponse
+    >('purgeWebHookDeadLetters', req);
+
   public claimBatch = async (swapIds: string[]) => {
     const req: sidecarrpc.ClaimBatchRequest = {
       swapIds,
@@ -320,6 +356,23 @@ class Sidecar extends BaseClient<
     };
   };
 
+  public claimBatchBroadcasted = async (
+    symbol: string,
+    swapIds: string[],
+    transactionId: string,
+  ) => {
+    const req: sidecarrpc.ClaimBatchBroadcastedRequest = {
+      symbol,
+      swapIds,
+      transactionId,
+    };
+
+    await this.unaryNodeCall<
+      sidecarrpc.ClaimBatchBroadcastedRequest,
+      sidecarrpc.ClaimBatchBroadcastedResponse
+    >('claimBatchBroadcasted', req);
+  };
+
   public signEvmRefund = async (
     chain: string,
     contractAddress: string,
@@ -344,6 +397,32 @@ class Sidecar extends BaseClient<
     return res.signature;
   };
 
+  public sendEvmTransaction = async (
+    chain: string,
+    to: string,
+    value: bigint,
+    data: Buffer,
+    gasLimit: bigint | undefined,
+  ) => {
+    const req: sidecarrpc.SendEvmTransactionRequest = {
+      chain,
+      to,
+      value: value.toString(),
+      data,
+      gasLimit: toOptionalProtoInt(gasLimit),
+    };
+
+    const res = await this.unaryNodeCall<
+      sidecarrpc.SendEvmTransactionRequest,
+      sidecarrpc.SendEvmTransactionResponse
+    >('sendEvmTransaction', req);
+    return {
+      hash: `0x${getHexString(res.transactionHash)}`,
+      nonce: fromProtoInt(res.nonce),
+      raw: `0x${getHexString(res.rawTransaction)}`,
+    };
+  };
+
   public decodeInvoiceOrOffer = async (invoiceOrOffer: string) => {
     const req: sidecarrpc.DecodeInvoiceOrOfferRequest = {
       invoiceOrOffer,
@@ -515,6 +594,33 @@ class Sidecar extends BaseClient<
         });
         return;
       }
+      // Sent when a claim transaction was replaced with a higher fee one
+      case SwapUpdateEvent.TransactionClaimed: {
+        const transactionInfo = update.transactionInfo!;
+        this.logger.info(
+          `Claim transaction of swap ${update.id} was replaced with: ${transactionInfo.id}`,
+        );
+
+        try {
+          await this.setReplacedClaimMinerFee(update.id, transactionInfo.hex!);
+        } catch (e) {
+          this.logger.warn(
+            `Could not update miner fee of replaced claim of swap ${update.id}: ${formatError(e)}`,
+          );
+        }
+
+        this.eventHandler.emit('swap.update', {
+          id: update.id,
+          status: {
+            status: SwapUpdateEvent.TransactionClaimed,
+            transaction: {
+              id: transactionInfo.id,
+              hex: transactionInfo.hex,
+            },
+          },
+        });
+        return;
+      }
       case SwapUpdateEvent.InvoiceFailedToPay: {
         const swap = await SwapRepository.getSwap({
           id: update.id,
@@ -541,6 +647,44 @@ class Sidecar extends BaseClient<
     }
   };
 
+  // The id of the replacement is tracked in the pending claims of the sidecar
+  private setReplacedClaimMinerFee = async (
+    id: string,
+    transactionHex: string,
+  ) => {
+    const swap = await SwapRepository.getSwap({ id });
+    const chainSwap =
+      swap === null ? await ChainSwapRepository.getChainSwap({ id }) : null;
+    if (swap === null && chainSwap === null) {
+      throw `could not find swap with id: ${id}`;
+    }
+
+    const symbol =
+      swap !== null ? swap.chainCurrency : chainSwap!.receivingData.symbol;
+    const currency = this.eventHandler.nursery.currencies.get(symbol);
+    if (currency?.chainClient === undefined) {
+      throw `no chain client for ${symbol}`;
+    }
+
+    const transaction = parseTransaction(currency.type, transactionHex);
+
+    // Every input of a (batch) claim spends one swap
+    const minerFee = Math.ceil(
+      (await calculateTransactionFee(currency.chainClient, transaction)) /
+        TxView.of(transaction).inputs.length,
+    );
+
+    if (swap !== null) {
+      await SwapRepository.setMinerFee(swap, minerFee);
+    } else {
+      await ChainSwapRepository.setClaimMinerFee(
+        chainSwap!,
+        getHexBuffer(chainSwap!.preimage!),
+        minerFee,
+      );
+    }
+  };
+
   /**
    * Rescans one or more chains. If none are specified, all chains will be rescanned
    * @param requests - The chains to rescan
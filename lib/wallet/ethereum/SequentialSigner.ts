@@ -6,11 +6,19 @@ import type {
   TypedDataDomain,
   TypedDataField,
 } from 'ethers';
-import { AbstractSigner, Transaction, getBigInt } from 'ethers';
+import {
+  AbstractSigner,
+  Transaction,
+  TransactionResponse,
+  getBigInt,
+  getBytes,
+} from 'ethers';
 import InstrumentedLock from '../../InstrumentedLock';
+import Logger from '../../Logger';
 import Tracing from '../../Tracing';
 import { formatError } from '../../Utils';
 import PendingEthereumTransactionRepository from '../../db/repositories/PendingEthereumTransactionRepository';
+import type Sidecar from '../../sidecar/Sidecar';
 import { bumpGasLimit } from './EthereumUtils';
 
 class SequentialSigner extends AbstractSigner {
@@ -24,6 +32,11 @@ class SequentialSigner extends AbstractSigner {
   // distinct nonces (see signTransactionInternal)
   private static readonly reservedNonces = new Map<string, number>();
 
+  // Once set, transactions are sent through the nonce manager of the sidecar,
+  // which also replaces them with higher fees when they get stuck
+  private static sidecar: Sidecar | undefined;
+  private static logger = Logger.disabledLogger;
+
   private readonly lock: InstrumentedLock;
 
   constructor(
@@ -41,6 +54,14 @@ class SequentialSigner extends AbstractSigner {
     this.lock = lock;
   }
 
+  public static useSidecar = (
+    sidecar: Sidecar | undefined,
+    logger: Logger = Logger.disabledLogger,
+  ) => {
+    SequentialSigner.sidecar = sidecar;
+    SequentialSigner.logger = logger;
+  };
+
   public getAddress = (): Promise<string> => this.signer.getAddress();
 
   public connect = (provider: null | Provider): Signer => {
@@ -73,6 +94,58 @@ class SequentialSigner extends AbstractSigner {
     }
   };
 
+  public sendTransaction = async (
+    tx: TransactionRequest,
+  ): Promise<TransactionResponse> => {
+    const sidecar = SequentialSigner.sidecar;
+    if (sidecar === undefined) {
+      return super.sendTransaction(tx);
+    }
+
+    return await this.lock.acquire(
+      SequentialSigner.txLock,
+      'sendTransaction',
+      async () => {
+        const populated = await this.populateCall(tx);
+        await this.addGasLimitBuffer(populated);
+        if (populated.to === null || populated.to === undefined) {
+          throw new Error('contract deployments cannot be sent by the sidecar');
+        }
+
+        const value = getBigInt(populated.value ?? 0);
+        await this.checkBalance(value);
+
+        const sent = await sidecar.sendEvmTransaction(
+          this.symbol,
+          populated.to,
+          value,
+          Buffer.from(getBytes(populated.data ?? '0x')),
+          getBigInt(populated.gasLimit!),
+        );
+
+        // The transaction is out; failing now would make callers send it again
+        const parsed = Transaction.from(sent.raw);
+        try {
+          await PendingEthereumTransactionRepository.addTransaction(
+            sent.hash,
+            this.symbol,
+            sent.nonce,
+            parsed.value,
+            sent.raw,
+          );
+        } catch (error) {
+          SequentialSigner.logger.error(
+            `Could not record ${this.symbol} transaction ${sent.hash} sent by the sidecar: ${formatError(error)}`,
+          );
+        }
+
+        // The sidecar might broadcast through a different node than ours,
+        // so the response is built from the signed transaction
+        return this.toResponse(sent.hash, parsed);
+      },
+    );
+  };
+
   public signMessage = (message: string | Uint8Array): Promise<string> =>
     this.signer.signMessage(message);
 
@@ -110,14 +183,7 @@ class SequentialSigner extends AbstractSigner {
           }
 
           if (tx.value !== undefined && tx.value !== null) {
-            const [ourBalance, pendingTxsValue] = await Promise.all([
-              this.signer.provider!.getBalance(await this.getAddress()),
-              PendingEthereumTransactionRepository.getTotalSent(this.symbol),
-            ]);
-
-            if (ourBalance - pendingTxsValue < BigInt(tx.value)) {
-              throw new Error('insufficient balance');
-            }
+            await this.checkBalance(BigInt(tx.value));
           }
 
           const signed = await this.signer.signTransaction(
@@ -157,6 +223,44 @@ class SequentialSigner extends AbstractSigner {
     );
   };
 
+  private checkBalance = async (value: bigint) => {
+    const [ourBalance, pendingTxsValue] = await Promise.all([
+      this.signer.provider!.getBalance(await this.getAddress()),
+      PendingEthereumTransactionRepository.getTotalSent(this.symbol),
+    ]);
+
+    if (ourBalance - pendingTxsValue < value) {
+      throw new Error('insufficient balance');
+    }
+  };
+
+  private toResponse = (hash: string, tx: Transaction): TransactionResponse =>
+    new TransactionResponse(
+      {
+        blockNumber: null,
+        blockHash: null,
+        hash,
+        index: 0,
+        type: tx.type!,
+        to: tx.to,
+        from: tx.from!,
+        nonce: tx.nonce,
+        gasLimit: tx.gasLimit,
+        gasPrice: tx.gasPrice ?? tx.maxFeePerGas ?? 0n,
+        maxPriorityFeePerGas: tx.maxPriorityFeePerGas,
+        maxFeePerGas: tx.maxFeePerGas,
+        maxFeePerBlobGas: tx.maxFeePerBlobGas,
+        data: tx.data,
+        value: tx.value,
+        chainId: tx.chainId,
+        signature: tx.signature!,
+        accessList: tx.accessList,
+        blobVersionedHashes: tx.blobVersionedHashes,
+        authorizationList: tx.authorizationList,
+      },
+      this.provider!,
+    );
+
   private addGasLimitBuffer = async (
     tx: TransactionRequest,
   ): Promise<TransactionRequest> => {
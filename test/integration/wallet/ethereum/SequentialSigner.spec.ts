@@ -1,4 +1,7 @@
+import { Transaction } from 'ethers';
+import { getHexString } from '../../../../lib/Utils';
 import PendingEthereumTransactionRepository from '../../../../lib/db/repositories/PendingEthereumTransactionRepository';
+import type Sidecar from '../../../../lib/sidecar/Sidecar';
 import { bumpGasLimit } from '../../../../lib/wallet/ethereum/EthereumUtils';
 import SequentialSigner from '../../../../lib/wallet/ethereum/SequentialSigner';
 import type { EthereumSetup } from '../EthereumTools';
@@ -78,6 +81,148 @@ describe('SequentialSigner', () => {
     });
   });
 
+  describe('sendTransaction through the sidecar', () => {
+    const sendEvmTransaction = jest.fn();
+
+    // Stands in for the nonce manager of the sidecar
+    const signForSidecar = async (
+      to: string,
+      value: bigint,
+      data: Buffer,
+      gasLimit: bigint,
+    ) => {
+      const raw = await setup.signer.signTransaction(
+        await setup.signer.populateTransaction({
+          to,
+          value,
+          data: `0x${getHexString(data)}`,
+          gasLimit,
+        }),
+      );
+      const parsed = Transaction.from(raw);
+
+      return { hash: parsed.hash!, nonce: parsed.nonce, raw };
+    };
+
+    beforeAll(() => {
+      SequentialSigner.useSidecar({
+        sendEvmTransaction,
+      } as unknown as Sidecar);
+    });
+
+    beforeEach(() => {
+      jest.clearAllMocks();
+
+      PendingEthereumTransactionRepository.getTotalSent = jest
+        .fn()
+        .mockResolvedValue(0n);
+
+      sendEvmTransaction.mockImplementation(
+        async (_chain, to, value, data, gasLimit) => {
+          const sent = await signForSidecar(to, value, data, gasLimit);
+          await setup.signer.provider!.broadcastTransaction(sent.raw);
+          return sent;
+        },
+      );
+    });
+
+    afterAll(() => {
+      SequentialSigner.useSidecar(undefined);
+    });
+
+    test('should send transactions through the sidecar', async () => {
+      const to = await setup.etherBase.getAddress();
+      const tx = await signer.sendTransaction({
+        to,
+        value: 1_000,
+        data: '0x0102',
+      });
+
+      expect(sendEvmTransaction).toHaveBeenCalledTimes(1);
+      expect(sendEvmTransaction).toHaveBeenCalledWith(
+        'ETH',
+        to,
+        1_000n,
+        Buffer.from([0x01, 0x02]),
+        tx.gasLimit,
+      );
+
+      expect(
+        PendingEthereumTransactionRepository.addTransaction,
+      ).toHaveBeenCalledWith(
+        tx.hash,
+        'ETH',
+        tx.nonce,
+        1_000n,
+        expect.any(String),
+      );
+      await tx.wait(1);
+    });
+
+    test('should not need our node to know the transaction', async () => {
+      let raw: string | undefined;
+      sendEvmTransaction.mockImplementationOnce(
+        async (_chain, to, value, data, gasLimit) => {
+          const sent = await signForSidecar(to, value, data, gasLimit);
+          raw = sent.raw;
+          return sent;
+        },
+      );
+
+      const to = await setup.etherBase.getAddress();
+      const tx = await signer.sendTransaction({
+        to,
+        value: 1_000,
+      });
+
+      expect(tx.hash).toEqual(Transaction.from(raw!).hash);
+      expect(tx.to).toEqual(to);
+      expect(tx.value).toEqual(1_000n);
+      expect(await signer.provider!.getTransaction(tx.hash)).toBeNull();
+      expect(
+        PendingEthereumTransactionRepository.addTransaction,
+      ).toHaveBeenCalledWith(tx.hash, 'ETH', tx.nonce, 1_000n, raw);
+
+      // Propagates to our node eventually
+      await signer.provider!.broadcastTransaction(raw!);
+      await tx.wait(1);
+    });
+
+    test('should not throw when the transaction cannot be recorded', async () => {
+      PendingEthereumTransactionRepository.addTransaction = jest
+        .fn()
+        .mockRejectedValueOnce(
+          new Error('nonce 1 on ETH already used by another transaction'),
+        )
+        .mockResolvedValue(null as never);
+
+      const tx = await signer.sendTransaction({
+        to: await setup.etherBase.getAddress(),
+        value: 1_000,
+      });
+
+      expect(sendEvmTransaction).toHaveBeenCalledTimes(1);
+      expect(
+        PendingEthereumTransactionRepository.addTransaction,
+      ).toHaveBeenCalledTimes(1);
+      await tx.wait(1);
+    });
+
+    test('should throw when we do not have enough balance', async () => {
+      const balance = await signer.provider!.getBalance(
+        await signer.getAddress(),
+      );
+
+      await expect(
+        signer.sendTransaction({
+          to: await setup.etherBase.getAddress(),
+          value: balance + 1n,
+        }),
+      ).rejects.toThrow('insufficient balance');
+      expect(sendEvmTransaction).not.toHaveBeenCalled();
+    });
+  });
+
   test('should sign messages', async () => {
     const message = 'Hello, world!';
     const signature = await signer.signMessage(message);
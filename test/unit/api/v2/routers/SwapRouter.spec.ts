@@ -2832,6 +2832,7 @@ describe('SwapRouter', () => {
       ${'invalid parameter: url'}        | ${{ url: 1 }}
       ${'invalid parameter: status'}     | ${{ url: 'http', status: 'correct' }}
       ${'invalid parameter: hashSwapId'} | ${{ url: 'http', hashSwapId: 'correct' }}
+      ${'invalid parameter: secret'}     | ${{ url: 'http', secret: 1 }}
     `(
       'should not parse webhook with invalid parameters ($error)',
       ({ error, data }) => {
@@ -129,6 +129,15 @@ const transformOutputs = (outputs: any[]) => {
   };
 };
 
+const mockListWebHookDeadLetters = jest
+  .fn()
+  .mockResolvedValue({ deadLetters: [] });
+const mockGetWebHookDeadLetter = jest.fn().mockResolvedValue({});
+const mockReplayWebHookDeadLetter = jest.fn().mockResolvedValue({ ok: true });
+const mockPurgeWebHookDeadLetters = jest
+  .fn()
+  .mockResolvedValue({ deleted: '2' });
+
 jest.mock('../../../lib/service/Service', () => {
   return jest.fn().mockImplementation(() => {
     return {
@@ -154,6 +163,12 @@ jest.mock('../../../lib/service/Service', () => {
       failureHook: {
         connectToStream: jest.fn(),
       },
+      sidecar: {
+        listWebHookDeadLetters: mockListWebHookDeadLetters,
+        getWebHookDeadLetter: mockGetWebHookDeadLetter,
+        replayWebHookDeadLetter: mockReplayWebHookDeadLetter,
+        purgeWebHookDeadLetters: mockPurgeWebHookDeadLetters,
+      },
       getInfo: mockGetInfo,
       getBalance: mockGetBalance,
       deriveKeys: mockDeriveKeys,
@@ -1465,6 +1480,38 @@ describe('GrpcService', () => {
     });
   });
 
+  describe('WebHook dead letters', () => {
+    test.each`
+      method                       | mock                           | request                        | response
+      ${'listWebHookDeadLetters'}  | ${mockListWebHookDeadLetters}  | ${{ hookId: 'swap' }}          | ${{ deadLetters: [] }}
+      ${'getWebHookDeadLetter'}    | ${mockGetWebHookDeadLetter}    | ${{ id: '1' }}                 | ${{}}
+      ${'replayWebHookDeadLetter'} | ${mockReplayWebHookDeadLetter} | ${{ id: '1' }}                 | ${{ ok: true }}
+      ${'purgeWebHookDeadLetters'} | ${mockPurgeWebHookDeadLetters} | ${{ olderThan: '1700000000' }} | ${{ deleted: '2' }}
+    `(
+      'should forward $method to the sidecar',
+      async ({ method, mock, request, response }) => {
+        await expect(
+          callUnaryAsPromise(grpcService[method], createCall(request)),
+        ).resolves.toEqual(response);
+
+        expect(mock).toHaveBeenCalledTimes(1);
+        expect(mock).toHaveBeenCalledWith(request);
+      },
+    );
+
+    test('should forward sidecar errors', async () => {
+      const error = { code: status.NOT_FOUND, message: 'not found' };
+      mockGetWebHookDeadLetter.mockRejectedValueOnce(error);
+
+      await expect(
+        callUnaryAsPromise(
+          grpcService.getWebHookDeadLetter,
+          createCall({ id: '2' }),
+        ),
+      ).rejects.toEqual(error);
+    });
+  });
+
   test('should handle resolved callbacks', async () => {
     const call = randomBytes(32);
     const cb = jest.fn();
@@ -2062,6 +2062,7 @@ describe('Service', () => {
       webHook.url,
       webHook.hashSwapId,
       undefined,
+      undefined,
     );
 
     // Throw if swap with preimage exists already
@@ -2710,6 +2711,7 @@ describe('Service', () => {
       webHook.url,
       webHook.hashSwapId,
       undefined,
+      undefined,
     );
 
     // Should add a 10% buffer to the lightning timeout block delta for cross chain swaps
@@ -0,0 +1,55 @@
+#!/usr/bin/env bash
+# Deploys Uniswap V2 to the regtest EVM chain for the tests of its quoter.
+# The artifacts of the npm packages are the ones of the canonical deployment, so the init code
+# hash of the pairs the router has baked in matches the pairs the factory creates.
+# Anvil account 9 deploys WETH9, the factory and the router with its nonces 0, 1 and 2; the tests
+# in boltz-evm derive their addresses from it.
+
+set -euo pipefail
+
+RPC_URL="${RPC_URL:-http://127.0.0.1:8545}"
+DEPLOYER_KEY="0x2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6"
+
+ARTIFACTS_DIR="node_modules/.uniswap-v2"
+
+fetch() {
+  local package="$1"
+  local version="$2"
+  local target="$ARTIFACTS_DIR/$package"
+
+  if [ -f "$target/package.json" ] && [ "$(jq -r .version "$target/package.json")" = "$version" ]; then
+    return
+  fi
+
+  rm -rf "$target"
+  mkdir -p "$target"
+  curl -sSfL "https://registry.npmjs.org/@uniswap/$package/-/$package-$version.tgz" |
+    tar -xz -C "$target" --strip-components 1
+}
+
+deploy() {
+  local artifact="$1"
+  local args="${2:-0x}"
+  local bytecode
+  bytecode="$(jq -r .bytecode "$artifact")"
+
+  cast send --rpc-url "$RPC_URL" --private-key "$DEPLOYER_KEY" --json \
+    --create "0x${bytecode#0x}${args#0x}" | jq -r .contractAddress
+}
+
+DEPLOYER="$(cast wallet address --private-key "$DEPLOYER_KEY")"
+if [ "$(cast nonce --rpc-url "$RPC_URL" "$DEPLOYER")" != "0" ]; then
+  echo "Uniswap V2 is deployed already"
+  exit 0
+fi
+
+fetch v2-core 1.0.1
+fetch v2-periphery 1.1.0-beta.0
+
+WETH="$(deploy "$ARTIFACTS_DIR/v2-periphery/build/WETH9.json")"
+FACTORY="$(deploy "$ARTIFACTS_DIR/v2-core/build/UniswapV2Factory.json" \
+  "$(cast abi-encode "constructor(address)" "$DEPLOYER")")"
+ROUTER="$(deploy "$ARTIFACTS_DIR/v2-periphery/build/UniswapV2Router02.json" \
+  "$(cast abi-encode "constructor(address,address)" "$FACTORY" "$WETH")")"
+
+echo "Deployed Uniswap V2: WETH9 $WETH, factory $FACTORY, router $ROUTER"